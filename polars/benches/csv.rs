@@ -1,6 +1,7 @@
 use criterion::{criterion_group, criterion_main, Criterion};
 use polars::prelude::*;
 use std::fs::File;
+use std::io::Cursor;
 
 fn prepare_reader() -> Result<CsvReader<'static, File>> {
     let path =
@@ -18,5 +19,41 @@ fn csv_parsing_benchmark(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, csv_parsing_benchmark);
+/// A csv file with a quoted string column, in which every `escape_every`-th field has escaped
+/// quotes.
+fn quoted_csv(escape_every: usize) -> String {
+    let mut csv = String::from("a,b\n");
+    for i in 0..1_000_000 {
+        if i % escape_every == 0 {
+            csv.push_str(&format!("\"name \"\"{}\"\", city\",{}\n", i, i));
+        } else {
+            csv.push_str(&format!("\"name {}, city\",{}\n", i, i));
+        }
+    }
+    csv
+}
+
+fn csv_quoted_benchmark(c: &mut Criterion) {
+    // unescaping is only done for the fields that have escaped quotes, all fields escaped is
+    // the baseline of unescaping every quoted field
+    let few_escaped = quoted_csv(20);
+    let all_escaped = quoted_csv(1);
+
+    c.bench_function("parse quoted csv 5% escaped", |b| {
+        b.iter(|| {
+            CsvReader::new(Cursor::new(few_escaped.as_bytes()))
+                .finish()
+                .unwrap();
+        })
+    });
+    c.bench_function("parse quoted csv 100% escaped", |b| {
+        b.iter(|| {
+            CsvReader::new(Cursor::new(all_escaped.as_bytes()))
+                .finish()
+                .unwrap();
+        })
+    });
+}
+
+criterion_group!(benches, csv_parsing_benchmark, csv_quoted_benchmark);
 criterion_main!(benches);
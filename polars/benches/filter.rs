@@ -0,0 +1,75 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use lazy_static::lazy_static;
+use polars::prelude::*;
+
+lazy_static! {
+    // 100 chunks of 10_000 rows with a clustered (sorted) column
+    static ref DATA: DataFrame = {
+        let chunks = (0..100i64).map(|i| {
+            let a = (i * 10_000..(i + 1) * 10_000).collect::<Vec<_>>();
+            let b = a.iter().map(|v| *v as f64 / 3.0).collect::<Vec<_>>();
+            df!["a" => a, "b" => b].unwrap()
+        });
+        polars_core::utils::accumulate_dataframes_vertical(chunks).unwrap()
+    };
+    // the masks of 5 conditions over 100M rows
+    static ref MASKS: Vec<BooleanChunked> = (2..7)
+        .map(|k| (0..100_000_000u32).map(|i| i % k != 0).collect())
+        .collect();
+}
+
+fn bench_filter(predicate: Expr, chunk_pruning: bool) {
+    let df = DATA
+        .clone()
+        .lazy()
+        .with_chunk_pruning(chunk_pruning)
+        .filter(predicate)
+        .collect()
+        .unwrap();
+    criterion::black_box(df);
+}
+
+fn filter_clustered(c: &mut Criterion) {
+    // the statistics are cached, compute them once before the measurements
+    bench_filter(col("a").eq(lit(0i64)), true);
+
+    for &chunk_pruning in &[false, true] {
+        let suffix = if chunk_pruning { "; chunk pruning" } else { "" };
+        c.bench_function(&format!("filter clustered eq{}", suffix), |b| {
+            b.iter(|| bench_filter(col("a").eq(lit(500_123i64)), chunk_pruning))
+        });
+        c.bench_function(&format!("filter clustered gt{}", suffix), |b| {
+            b.iter(|| bench_filter(col("a").gt(lit(900_000i64)), chunk_pruning))
+        });
+        // no chunk can be skipped
+        c.bench_function(&format!("filter clustered neq{}", suffix), |b| {
+            b.iter(|| bench_filter(col("a").neq(lit(500_123i64)), chunk_pruning))
+        });
+    }
+}
+
+fn mask_algebra(c: &mut Criterion) {
+    let masks = &*MASKS;
+    // every & allocates a new mask
+    c.bench_function("and 5 masks; operator", |b| {
+        b.iter(|| {
+            let mask = masks[1..]
+                .iter()
+                .fold(masks[0].clone(), |acc, mask| &acc & mask);
+            criterion::black_box(mask);
+        })
+    });
+    // the masks are combined in one buffer
+    c.bench_function("and 5 masks; mask buffer", |b| {
+        b.iter(|| {
+            let mut mask = MaskBuffer::new(&masks[0]);
+            for other in &masks[1..] {
+                mask.and_with(other).unwrap();
+            }
+            criterion::black_box(mask.finish());
+        })
+    });
+}
+
+criterion_group!(benches, filter_clustered, mask_algebra);
+criterion_main!(benches);
@@ -2,6 +2,7 @@ use criterion::{criterion_group, criterion_main, Criterion};
 use lazy_static::lazy_static;
 use polars::prelude::*;
 use polars_lazy::functions::pearson_corr;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
 lazy_static! {
     static ref DATA: DataFrame = {
@@ -24,6 +25,69 @@ lazy_static! {
     };
 }
 
+lazy_static! {
+    // 1M rows, of which 20% have a null key
+    static ref NULL_KEYS: DataFrame = {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut keys: UInt32Chunked = (0..1_000_000)
+            .map(|_| {
+                if rng.gen::<f32>() < 0.2 {
+                    None
+                } else {
+                    Some(rng.gen_range(0..1000u32))
+                }
+            })
+            .collect();
+        keys.rename("key");
+        let mut values: Float64Chunked = (0..1_000_000).map(|_| Some(rng.gen::<f64>())).collect();
+        values.rename("value");
+        DataFrame::new(vec![keys.into_series(), values.into_series()]).unwrap()
+    };
+}
+
+lazy_static! {
+    // 1M rows in 200k small groups, of which 5% of the values are null
+    static ref SMALL_GROUPS: DataFrame = {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut keys: UInt32Chunked = (0..1_000_000)
+            .map(|_| Some(rng.gen_range(0..200_000u32)))
+            .collect();
+        keys.rename("key");
+        let mut values: Float64Chunked = (0..1_000_000)
+            .map(|_| {
+                if rng.gen::<f32>() < 0.05 {
+                    None
+                } else {
+                    Some(rng.gen::<f64>())
+                }
+            })
+            .collect();
+        values.rename("value");
+        DataFrame::new(vec![keys.into_series(), values.into_series()]).unwrap()
+    };
+}
+
+lazy_static! {
+    // 10M rows with ~10k distinct days, as Int32 and as Date32 key with the same values
+    static ref DATE_KEYS: DataFrame = {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut days: Int32Chunked = (0..10_000_000)
+            .map(|_| Some(rng.gen_range(0..10_000i32)))
+            .collect();
+        days.rename("int32");
+        let mut date32 = days.cast::<Date32Type>().unwrap();
+        date32.rename("date32");
+        let mut values: Float64Chunked = (0..10_000_000).map(|_| Some(rng.gen::<f64>())).collect();
+        values.rename("value");
+        DataFrame::new(vec![
+            days.into_series(),
+            date32.into_series(),
+            values.into_series(),
+        ])
+        .unwrap()
+    };
+}
+
 fn q1(c: &mut Criterion) {
     c.bench_function("groupby q1", |b| {
         b.iter(|| {
@@ -181,7 +245,100 @@ fn q10(c: &mut Criterion) {
     });
 }
 
+fn null_keys_filter(c: &mut Criterion) {
+    c.bench_function("groupby null keys filter", |b| {
+        b.iter(|| {
+            NULL_KEYS
+                .clone()
+                .lazy()
+                .filter(col("key").is_not_null())
+                .groupby(vec![col("key")])
+                .agg(vec![col("value").std()])
+                .collect()
+                .unwrap();
+        })
+    });
+}
+
+fn null_keys_drop(c: &mut Criterion) {
+    c.bench_function("groupby null keys drop", |b| {
+        b.iter(|| {
+            NULL_KEYS
+                .clone()
+                .lazy()
+                .groupby(vec![col("key")])
+                .with_drop_null_keys(true)
+                .agg(vec![col("value").std()])
+                .collect()
+                .unwrap();
+        })
+    });
+}
+
+fn median_small_groups(c: &mut Criterion) {
+    c.bench_function("groupby median small groups", |b| {
+        b.iter(|| {
+            SMALL_GROUPS
+                .groupby("key")
+                .unwrap()
+                .select("value")
+                .median()
+                .unwrap();
+        })
+    });
+}
+
+// the baseline that sorts the values of every group
+fn median_small_groups_sorted(c: &mut Criterion) {
+    c.bench_function("groupby median small groups; sort per group", |b| {
+        b.iter(|| {
+            let lists = SMALL_GROUPS
+                .groupby("key")
+                .unwrap()
+                .select("value")
+                .agg_list()
+                .unwrap();
+            let _: Float64Chunked = lists
+                .column("value_agg_list")
+                .unwrap()
+                .list()
+                .unwrap()
+                .into_iter()
+                .map(|opt_s| opt_s.and_then(|s| s.median()))
+                .collect();
+        })
+    });
+}
+
+fn int32_key(c: &mut Criterion) {
+    c.bench_function("groupby 10M rows; Int32 key", |b| {
+        b.iter(|| {
+            DATE_KEYS
+                .groupby("int32")
+                .unwrap()
+                .select("value")
+                .sum()
+                .unwrap();
+        })
+    });
+}
+
+// the same keys as `int32_key`, grouped by their physical representation
+fn date32_key(c: &mut Criterion) {
+    c.bench_function("groupby 10M rows; Date32 key", |b| {
+        b.iter(|| {
+            DATE_KEYS
+                .groupby("date32")
+                .unwrap()
+                .select("value")
+                .sum()
+                .unwrap();
+        })
+    });
+}
+
 criterion_group!(name = benches;
 config = Criterion::default().sample_size(100);
-targets = q1, q2, q3, q4, q5, q6, q7, q8, q9, q10);
+targets = q1, q2, q3, q4, q5, q6, q7, q8, q9, q10, null_keys_filter, null_keys_drop,
+median_small_groups, median_small_groups_sorted, int32_key, date32_key);
 criterion_main!(benches);
@@ -0,0 +1,68 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use lazy_static::lazy_static;
+use polars::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// A relation with `n_keys` distinct values in the key column "a". The key column "b" is derived
+/// from "a", so that joining on ["a", "b"] matches the same rows as joining on "a".
+fn create_relation(n_rows: usize, n_keys: u32) -> DataFrame {
+    let mut rng = StdRng::seed_from_u64(n_keys as u64);
+    let a = (0..n_rows)
+        .map(|_| rng.gen_range(0..n_keys))
+        .collect::<Vec<_>>();
+    let b = a.iter().map(|v| v % 100).collect::<Vec<_>>();
+    df!["a" => a, "b" => b].unwrap()
+}
+
+lazy_static! {
+    // 1M rows
+    static ref PROBE: DataFrame = create_relation(1_000_000, 1_000_000);
+    // 100_000 rows that (almost) all have a unique key
+    static ref BUILD_UNIQUE: DataFrame = {
+        let a = (0..100_000u32).collect::<Vec<_>>();
+        let b = a.iter().map(|v| v % 100).collect::<Vec<_>>();
+        df!["a" => a, "b" => b].unwrap()
+    };
+    // 100_000 rows with only 10 distinct keys
+    static ref BUILD_DUPLICATED: DataFrame = create_relation(100_000, 10);
+}
+
+fn bench_join(build: &DataFrame, on: &[&str], how: JoinType) {
+    let out = PROBE.join(build, on.to_vec(), on.to_vec(), how).unwrap();
+    criterion::black_box(out);
+}
+
+fn join_unique_keys(c: &mut Criterion) {
+    let build = &*BUILD_UNIQUE;
+    c.bench_function("inner join unique keys", |b| {
+        b.iter(|| bench_join(build, &["a"], JoinType::Inner))
+    });
+    c.bench_function("left join unique keys", |b| {
+        b.iter(|| bench_join(build, &["a"], JoinType::Left))
+    });
+    c.bench_function("inner join unique keys; multiple keys", |b| {
+        b.iter(|| bench_join(build, &["a", "b"], JoinType::Inner))
+    });
+    c.bench_function("left join unique keys; multiple keys", |b| {
+        b.iter(|| bench_join(build, &["a", "b"], JoinType::Left))
+    });
+}
+
+fn join_duplicated_keys(c: &mut Criterion) {
+    let build = &*BUILD_DUPLICATED;
+    c.bench_function("inner join duplicated keys", |b| {
+        b.iter(|| bench_join(build, &["a"], JoinType::Inner))
+    });
+    c.bench_function("left join duplicated keys", |b| {
+        b.iter(|| bench_join(build, &["a"], JoinType::Left))
+    });
+    c.bench_function("inner join duplicated keys; multiple keys", |b| {
+        b.iter(|| bench_join(build, &["a", "b"], JoinType::Inner))
+    });
+    c.bench_function("left join duplicated keys; multiple keys", |b| {
+        b.iter(|| bench_join(build, &["a", "b"], JoinType::Left))
+    });
+}
+
+criterion_group!(benches, join_unique_keys, join_duplicated_keys);
+criterion_main!(benches);
@@ -0,0 +1,51 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use lazy_static::lazy_static;
+use polars::prelude::*;
+use std::fs::File;
+use std::path::PathBuf;
+
+lazy_static! {
+    // 16 row groups of 250_000 rows
+    static ref PATH: PathBuf = {
+        let chunks = (0..16i64).map(|i| {
+            let a = (i * 250_000..(i + 1) * 250_000).collect::<Vec<_>>();
+            let b = a.iter().map(|v| *v as f64 / 3.0).collect::<Vec<_>>();
+            let c = a.iter().map(|v| format!("value_{}", v % 1000)).collect::<Vec<_>>();
+            df!["a" => a, "b" => b, "c" => c].unwrap()
+        });
+        let df = polars_core::utils::accumulate_dataframes_vertical(chunks).unwrap();
+
+        let path = std::env::temp_dir().join("polars_bench_row_groups.parquet");
+        let f = File::create(&path).unwrap();
+        ParquetWriter::new(f).finish(&df).unwrap();
+        path
+    };
+}
+
+fn bench_read(parallel: bool, projection: Option<&[usize]>) {
+    let f = File::open(&*PATH).unwrap();
+    let df = ParquetReader::new(f)
+        .read_parallel(parallel)
+        .set_rechunk(false)
+        .finish_with_scan_ops(None, None, projection)
+        .unwrap();
+    criterion::black_box(df);
+}
+
+fn read_row_groups(c: &mut Criterion) {
+    c.bench_function("parquet read row groups sequential", |b| {
+        b.iter(|| bench_read(false, None))
+    });
+    c.bench_function("parquet read row groups parallel", |b| {
+        b.iter(|| bench_read(true, None))
+    });
+    c.bench_function("parquet read row groups sequential; projection", |b| {
+        b.iter(|| bench_read(false, Some(&[0, 2])))
+    });
+    c.bench_function("parquet read row groups parallel; projection", |b| {
+        b.iter(|| bench_read(true, Some(&[0, 2])))
+    });
+}
+
+criterion_group!(benches, read_row_groups);
+criterion_main!(benches);
@@ -0,0 +1,60 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use polars::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+fn create_primitive_ca(size: u32, null_percentage: f32) -> Float64Chunked {
+    let mut rng = StdRng::seed_from_u64(0);
+    (0..size)
+        .map(|i| {
+            if rng.gen::<f32>() < null_percentage {
+                None
+            } else {
+                Some(i as f64)
+            }
+        })
+        .collect()
+}
+
+fn window_mean(window: &[Option<f64>]) -> Option<f64> {
+    let (sum, count) = window
+        .iter()
+        .flatten()
+        .fold((0.0, 0), |(sum, count), v| (sum + v, count + 1));
+    if count == 0 {
+        None
+    } else {
+        Some(sum / count as f64)
+    }
+}
+
+fn window_mean_no_null(window: &[f64]) -> f64 {
+    window.iter().sum::<f64>() / window.len() as f64
+}
+
+fn add_benchmark(c: &mut Criterion) {
+    // compares rolling_apply with a trivial closure to the builtin rolling_mean
+    for &null_percentage in &[0.0, 0.05] {
+        let ca = create_primitive_ca(100_000, null_percentage);
+        let name = |method: &str| {
+            format!(
+                "{} 100_000 {}% nulls; window 10",
+                method,
+                null_percentage * 100.0
+            )
+        };
+        c.bench_function(&name("rolling_mean"), |b| {
+            b.iter(|| criterion::black_box(ca.rolling_mean(10, None, true, 1)))
+        });
+        c.bench_function(&name("rolling_apply"), |b| {
+            b.iter(|| criterion::black_box(ca.rolling_apply(10, window_mean, 1)))
+        });
+    }
+
+    let ca = create_primitive_ca(100_000, 0.0);
+    c.bench_function("rolling_apply_no_null 100_000; window 10", |b| {
+        b.iter(|| criterion::black_box(ca.rolling_apply_no_null(10, window_mean_no_null, 1)))
+    });
+}
+
+criterion_group!(benches, add_benchmark);
+criterion_main!(benches);
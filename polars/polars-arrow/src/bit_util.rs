@@ -1,7 +1,8 @@
-/// Forked from Arrow until their API stabilizes.
-///
-/// Note that the bound checks are optimized away.
-///
+//! Forked from Arrow until their API stabilizes.
+//!
+//! Note that the bound checks are optimized away.
+
+use arrow::buffer::{Buffer, MutableBuffer};
 
 const BIT_MASK: [u8; 8] = [1, 2, 4, 8, 16, 32, 64, 128];
 
@@ -79,3 +80,128 @@ pub fn ceil(value: usize, divisor: usize) -> usize {
         quot
     }
 }
+
+/// Combine two validity bitmaps with a bitwise AND. The bitmaps start at bit `lhs_offset` and
+/// `rhs_offset`, e.g. the offsets of sliced arrays. The output starts at bit 0 and has `len`
+/// bits, so it can be used for a new array without offset. If only one side has a bitmap, it is
+/// sliced to `len` bits.
+pub fn combine_validities(
+    lhs: Option<&Buffer>,
+    lhs_offset: usize,
+    rhs: Option<&Buffer>,
+    rhs_offset: usize,
+    len: usize,
+) -> Option<Buffer> {
+    match (lhs, rhs) {
+        (Some(lhs), Some(rhs)) => Some(bitand(lhs, lhs_offset, rhs, rhs_offset, len)),
+        (Some(lhs), None) => Some(lhs.bit_slice(lhs_offset, len)),
+        (None, Some(rhs)) => Some(rhs.bit_slice(rhs_offset, len)),
+        (None, None) => None,
+    }
+}
+
+fn bitand(lhs: &Buffer, lhs_offset: usize, rhs: &Buffer, rhs_offset: usize, len: usize) -> Buffer {
+    let bit_offset = lhs_offset % 8;
+    if bit_offset == rhs_offset % 8 {
+        // the bits line up, so the bytes can be combined as is
+        let n_bytes = ceil(bit_offset + len, 8);
+        let lhs = &lhs.as_slice()[lhs_offset / 8..lhs_offset / 8 + n_bytes];
+        let rhs = &rhs.as_slice()[rhs_offset / 8..rhs_offset / 8 + n_bytes];
+        let mut out = MutableBuffer::from_len_zeroed(n_bytes);
+        out.as_slice_mut()
+            .iter_mut()
+            .zip(lhs.iter().zip(rhs))
+            .for_each(|(out, (l, r))| *out = l & r);
+        let out: Buffer = out.into();
+        if bit_offset == 0 {
+            out
+        } else {
+            out.bit_slice(bit_offset, len)
+        }
+    } else {
+        // the chunks are shifted to start at bit 0
+        let lhs = lhs.bit_chunks(lhs_offset, len);
+        let rhs = rhs.bit_chunks(rhs_offset, len);
+        let mut out = MutableBuffer::new(ceil(len, 64) * 8);
+        lhs.iter()
+            .zip(rhs.iter())
+            .for_each(|(l, r)| out.extend_from_slice(&(l & r).to_le_bytes()));
+        let remainder = lhs.remainder_bits() & rhs.remainder_bits();
+        out.extend_from_slice(&remainder.to_le_bytes()[..ceil(lhs.remainder_len(), 8)]);
+        out.into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Pseudo random validity with about one in `null_every` bits unset.
+    fn random_validity(len: usize, seed: u64, null_every: u64) -> Vec<bool> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                // xorshift
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state % null_every != 0
+            })
+            .collect()
+    }
+
+    fn to_buffer(bits: &[bool]) -> Buffer {
+        let mut buf = MutableBuffer::from_len_zeroed(ceil(bits.len(), 8));
+        bits.iter()
+            .enumerate()
+            .filter(|(_, valid)| **valid)
+            .for_each(|(i, _)| set_bit(buf.as_slice_mut(), i));
+        buf.into()
+    }
+
+    #[test]
+    fn test_combine_validities() {
+        for &len in &[0, 1, 7, 8, 9, 63, 64, 65, 130, 300] {
+            for &null_every in &[1, 2, 3, 17] {
+                let lhs = random_validity(len + 8, len as u64 + 1, null_every);
+                let rhs = random_validity(len + 8, len as u64 + 100, null_every);
+                let (lhs_buf, rhs_buf) = (to_buffer(&lhs), to_buffer(&rhs));
+
+                for lhs_offset in 0..8 {
+                    for rhs_offset in 0..8 {
+                        let check = |out: Option<Buffer>, expected: &dyn Fn(usize) -> bool| {
+                            let out = out.unwrap();
+                            for i in 0..len {
+                                assert_eq!(
+                                    get_bit(out.as_slice(), i),
+                                    expected(i),
+                                    "len {} offsets {} {} bit {}",
+                                    len,
+                                    lhs_offset,
+                                    rhs_offset,
+                                    i
+                                );
+                            }
+                        };
+                        let out = combine_validities(
+                            Some(&lhs_buf),
+                            lhs_offset,
+                            Some(&rhs_buf),
+                            rhs_offset,
+                            len,
+                        );
+                        check(out, &|i| lhs[lhs_offset + i] && rhs[rhs_offset + i]);
+
+                        let out =
+                            combine_validities(Some(&lhs_buf), lhs_offset, None, rhs_offset, len);
+                        check(out, &|i| lhs[lhs_offset + i]);
+                        let out =
+                            combine_validities(None, lhs_offset, Some(&rhs_buf), rhs_offset, len);
+                        check(out, &|i| rhs[rhs_offset + i]);
+                    }
+                }
+                assert!(combine_validities(None, 3, None, 5, len).is_none());
+            }
+        }
+    }
+}
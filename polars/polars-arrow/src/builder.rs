@@ -67,28 +67,68 @@ impl BooleanBufferBuilder {
 
     #[inline]
     pub fn append_n(&mut self, additional: usize, v: bool) {
+        let offset = self.len();
         self.advance(additional);
         if additional > 0 && v {
-            let offset = self.len() - additional;
-            (0..additional).for_each(|i| unsafe {
-                bit_util::set_bit_raw(self.buffer.as_mut_ptr(), offset + i)
-            })
+            let (head, bytes, tail) = self.split_at_byte_boundaries(offset, additional);
+            let ptr = self.buffer.as_mut_ptr();
+            (offset..offset + head).for_each(|i| unsafe { bit_util::set_bit_raw(ptr, i) });
+            let byte_offset = (offset + head) / 8;
+            self.buffer.as_slice_mut()[byte_offset..byte_offset + bytes]
+                .iter_mut()
+                .for_each(|byte| *byte = u8::MAX);
+            let tail_offset = offset + head + bytes * 8;
+            (tail_offset..tail_offset + tail)
+                .for_each(|i| unsafe { bit_util::set_bit_raw(ptr, i) });
         }
     }
 
+    /// Append the bits of `slice`. Except for the bits before the first and after the last byte
+    /// boundary, the bits are packed a byte at a time.
     #[inline]
     pub fn append_slice(&mut self, slice: &[bool]) {
         let additional = slice.len();
+        let offset = self.len();
         self.advance(additional);
 
-        let offset = self.len() - additional;
-        for (i, v) in slice.iter().enumerate() {
+        let (head, bytes, _) = self.split_at_byte_boundaries(offset, additional);
+        let (head_values, values) = slice.split_at(head);
+        let (packed_values, tail_values) = values.split_at(bytes * 8);
+
+        let ptr = self.buffer.as_mut_ptr();
+        for (i, v) in head_values.iter().enumerate() {
+            if *v {
+                unsafe { bit_util::set_bit_raw(ptr, offset + i) }
+            }
+        }
+        let byte_offset = (offset + head) / 8;
+        self.buffer.as_slice_mut()[byte_offset..byte_offset + bytes]
+            .iter_mut()
+            .zip(packed_values.chunks_exact(8))
+            .for_each(|(byte, chunk)| {
+                *byte = chunk
+                    .iter()
+                    .enumerate()
+                    .fold(0, |acc, (i, v)| acc | ((*v as u8) << i))
+            });
+        let tail_offset = offset + head + bytes * 8;
+        for (i, v) in tail_values.iter().enumerate() {
             if *v {
-                unsafe { bit_util::set_bit_raw(self.buffer.as_mut_ptr(), offset + i) }
+                unsafe { bit_util::set_bit_raw(ptr, tail_offset + i) }
             }
         }
     }
 
+    /// Split `additional` bits starting at bit `offset` in the number of bits before the first
+    /// byte boundary, the number of whole bytes and the number of remaining bits.
+    #[inline]
+    fn split_at_byte_boundaries(&self, offset: usize, additional: usize) -> (usize, usize, usize) {
+        let head = ((8 - offset % 8) % 8).min(additional);
+        let bytes = (additional - head) / 8;
+        let tail = additional - head - bytes * 8;
+        (head, bytes, tail)
+    }
+
     pub fn shrink_to_fit(&mut self) {
         let byte_len = bit_util::ceil(self.len(), 8);
         self.buffer.resize(byte_len, 0)
@@ -130,6 +170,12 @@ impl BooleanArrayBuilder {
         self.values_builder.capacity()
     }
 
+    /// Reserve space for at least `additional` new values.
+    pub fn reserve(&mut self, additional: usize) {
+        self.values_builder.reserve(additional);
+        self.bitmap_builder.reserve(additional);
+    }
+
     /// Appends a value of type `T` into the builder
     pub fn append_value(&mut self, v: bool) {
         self.bitmap_builder.append(true);
@@ -295,4 +341,31 @@ mod test {
         let vals = out.iter().collect::<Vec<_>>();
         assert_eq!(vals, &[Some("foo"), None, Some("bar")]);
     }
+
+    #[test]
+    fn test_boolean_buffer_builder_bulk() {
+        let values = (0..50).map(|i| i % 3 == 0).collect::<Vec<_>>();
+        // start on and off byte boundaries
+        for &start in &[0, 3, 8, 13] {
+            let mut builder = BooleanBufferBuilder::new(0);
+            builder.append_n(start, true);
+            builder.append_slice(&values);
+            builder.append_n(20, true);
+            builder.append_n(5, false);
+            let len = builder.len();
+            let buffer = builder.finish();
+
+            let expected = std::iter::repeat(true)
+                .take(start)
+                .chain(values.iter().copied())
+                .chain(std::iter::repeat(true).take(20))
+                .chain(std::iter::repeat(false).take(5))
+                .collect::<Vec<_>>();
+            assert_eq!(len, expected.len());
+            let bits = (0..len)
+                .map(|i| bit_util::get_bit(buffer.as_slice(), i))
+                .collect::<Vec<_>>();
+            assert_eq!(bits, expected);
+        }
+    }
 }
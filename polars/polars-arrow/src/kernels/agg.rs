@@ -0,0 +1,258 @@
+use crate::utils::CustomIterTools;
+use arrow::array::{Array, PrimitiveArray};
+use arrow::datatypes::ArrowPrimitiveType;
+use num::{ToPrimitive, Zero};
+use std::ops::Add;
+
+/// Fold the valid values of `array`. The validity bitmap is processed 64 bits at a time:
+/// `f_slice` folds the values of words where all bits are set, words where no bits are set are
+/// skipped and only the values of the remaining words are folded one by one by `f_value`.
+///
+/// This is a lot faster than branching on every value when most values are valid, as the
+/// slices can be vectorized.
+#[inline]
+fn fold_valid<T, Acc, S, V>(array: &PrimitiveArray<T>, init: Acc, f_slice: S, f_value: V) -> Acc
+where
+    T: ArrowPrimitiveType,
+    S: Fn(Acc, &[T::Native]) -> Acc,
+    V: Fn(Acc, T::Native) -> Acc,
+{
+    let values = array.values();
+    let validity = match array.data_ref().null_buffer() {
+        Some(validity) if array.null_count() > 0 => validity,
+        _ => return f_slice(init, values),
+    };
+
+    // the bit chunks are shifted by the offset of the array, so the words are aligned with the
+    // values, even if the offset is not a multiple of 64
+    let chunks = validity.bit_chunks(array.offset(), array.len());
+    let mut acc = init;
+    for (i, mask) in chunks.iter().enumerate() {
+        let chunk = &values[i * 64..(i + 1) * 64];
+        match mask {
+            0 => {}
+            u64::MAX => acc = f_slice(acc, chunk),
+            mut mask => {
+                while mask != 0 {
+                    let bit = mask.trailing_zeros() as usize;
+                    acc = f_value(acc, chunk[bit]);
+                    // unset the lowest set bit
+                    mask &= mask - 1;
+                }
+            }
+        }
+    }
+
+    let offset = chunks.chunk_len() * 64;
+    let mask = chunks.remainder_bits();
+    for bit in 0..chunks.remainder_len() {
+        if mask & (1 << bit) != 0 {
+            acc = f_value(acc, values[offset + bit]);
+        }
+    }
+    acc
+}
+
+/// Sum the valid values of a primitive array.
+///
+/// Returns `None` if the array is empty or only contains null values.
+pub fn sum_primitive<T>(array: &PrimitiveArray<T>) -> Option<T::Native>
+where
+    T: ArrowPrimitiveType,
+    T::Native: Add<Output = T::Native> + Zero,
+{
+    if array.null_count() == array.len() {
+        return None;
+    }
+    let sum = fold_valid(
+        array,
+        T::Native::zero(),
+        |acc, slice| acc + slice.iter().copied().fold(T::Native::zero(), |a, b| a + b),
+        |acc, v| acc + v,
+    );
+    Some(sum)
+}
+
+/// A floating point sum that keeps track of the rounding error of the additions (Neumaier).
+#[derive(Copy, Clone, Default)]
+struct CompensatedSum {
+    sum: f64,
+    compensation: f64,
+}
+
+impl CompensatedSum {
+    #[inline]
+    fn add(mut self, v: f64) -> Self {
+        let sum = self.sum + v;
+        if self.sum.abs() >= v.abs() {
+            self.compensation += (self.sum - sum) + v;
+        } else {
+            self.compensation += (v - sum) + self.sum;
+        }
+        self.sum = sum;
+        self
+    }
+
+    fn value(self) -> f64 {
+        // the compensation of an infinite or NaN sum is NaN
+        if self.sum.is_finite() {
+            self.sum + self.compensation
+        } else {
+            self.sum
+        }
+    }
+}
+
+/// Number of values that are summed naively before the block sum is added to the compensated
+/// sum. The naive sum of a block can be vectorized.
+const SUM_BLOCK_SIZE: usize = 128;
+
+fn compensated_sum<T, F>(array: &PrimitiveArray<T>, f: F) -> Option<f64>
+where
+    T: ArrowPrimitiveType,
+    T::Native: ToPrimitive,
+    F: Fn(f64) -> f64 + Copy,
+{
+    if array.null_count() == array.len() {
+        return None;
+    }
+    let to_f64 = |v: T::Native| f(v.to_f64().unwrap());
+    let sum = fold_valid(
+        array,
+        CompensatedSum::default(),
+        |acc, slice| {
+            slice.chunks(SUM_BLOCK_SIZE).fold(acc, |acc, block| {
+                acc.add(block.iter().map(|v| to_f64(*v)).sum::<f64>())
+            })
+        },
+        |acc, v| acc.add(to_f64(v)),
+    );
+    Some(sum.value())
+}
+
+/// Sum the valid values of a primitive array as `f64`. Integers can't overflow and the rounding
+/// errors of floats are compensated, so this is used for the mean.
+///
+/// Returns `None` if the array is empty or only contains null values.
+pub fn sum_primitive_f64<T>(array: &PrimitiveArray<T>) -> Option<f64>
+where
+    T: ArrowPrimitiveType,
+    T::Native: ToPrimitive,
+{
+    compensated_sum(array, |v| v)
+}
+
+/// Sum the squared deviations of the valid values of a primitive array from `mean`, see
+/// [sum_primitive_f64]. This is used for the variance.
+///
+/// Returns `None` if the array is empty or only contains null values.
+pub fn sum_squared_deviations_f64<T>(array: &PrimitiveArray<T>, mean: f64) -> Option<f64>
+where
+    T: ArrowPrimitiveType,
+    T::Native: ToPrimitive,
+{
+    compensated_sum(array, |v| (v - mean) * (v - mean))
+}
+
+/// Reduce the valid values of a primitive array with `f`. This is used for the min and max
+/// aggregations.
+///
+/// Returns `None` if the array is empty or only contains null values.
+pub fn reduce_primitive<T, F>(array: &PrimitiveArray<T>, f: F) -> Option<T::Native>
+where
+    T: ArrowPrimitiveType,
+    F: Fn(T::Native, T::Native) -> T::Native + Copy,
+{
+    fold_valid(
+        array,
+        None,
+        |acc, slice| match (acc, slice.iter().copied().fold_first_(f)) {
+            (Some(acc), Some(v)) => Some(f(acc, v)),
+            (acc, None) => acc,
+            (None, v) => v,
+        },
+        |acc, v| match acc {
+            Some(acc) => Some(f(acc, v)),
+            None => Some(v),
+        },
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use arrow::array::{Float32Array, Float64Array, Int32Array, Int64Array};
+
+    /// Pseudo random validity patterns with varying densities of nulls.
+    fn random_array(len: usize, seed: u64, null_every: u64) -> Int64Array {
+        let mut state = seed;
+        (0..len)
+            .map(|i| {
+                // xorshift
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                if state % null_every == 0 {
+                    None
+                } else {
+                    Some((state % 1000) as i64 - 500 + i as i64)
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_agg_kernels_random_validity() {
+        for &len in &[0, 1, 63, 64, 65, 130, 257, 1000] {
+            for &null_every in &[1, 2, 3, 17, 1000] {
+                let array = random_array(len, len as u64 + null_every + 1, null_every);
+                for offset in [0, 1, 7, 8, 63, 64, 65, 100].iter().filter(|o| **o <= len) {
+                    let sliced = array.slice(*offset, len - offset);
+                    let sliced = sliced.as_any().downcast_ref::<Int64Array>().unwrap();
+                    let valid = sliced.iter().flatten().collect::<Vec<_>>();
+
+                    let expected_sum = if valid.is_empty() {
+                        None
+                    } else {
+                        Some(valid.iter().sum::<i64>())
+                    };
+                    assert_eq!(sum_primitive(sliced), expected_sum);
+                    assert_eq!(
+                        sum_primitive_f64(sliced),
+                        expected_sum.map(|sum| sum as f64)
+                    );
+                    assert_eq!(
+                        reduce_primitive(sliced, |a, b| if a < b { a } else { b }),
+                        valid.iter().copied().min()
+                    );
+                    assert_eq!(
+                        reduce_primitive(sliced, |a, b| if a > b { a } else { b }),
+                        valid.iter().copied().max()
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_sum_primitive_f64_precision() {
+        // 0.1 can't be represented exactly, a naive f32 sum of 1e7 values drifts far from 1e6
+        let n = 10_000_000;
+        let array: Float32Array = std::iter::repeat(0.1f32).take(n).map(Some).collect();
+        let naive = array.values().iter().fold(0.0f32, |acc, v| acc + v);
+        let expected = n as f64 * 0.1f32 as f64;
+        assert!((naive as f64 - expected).abs() > 1e4);
+        assert!((sum_primitive_f64(&array).unwrap() - expected).abs() < 1e-6 * expected);
+
+        // large values would overflow the integer sum
+        let array: Int32Array = std::iter::repeat(Some(i32::MAX)).take(10).collect();
+        assert_eq!(sum_primitive_f64(&array), Some(i32::MAX as f64 * 10.0));
+
+        let array: Float64Array = vec![Some(1.0), Some(2.0), None, Some(3.0)]
+            .into_iter()
+            .collect();
+        assert_eq!(sum_squared_deviations_f64(&array, 2.0), Some(2.0));
+        let array: Float64Array = vec![Some(1.0), Some(f64::INFINITY)].into_iter().collect();
+        assert_eq!(sum_primitive_f64(&array), Some(f64::INFINITY));
+    }
+}
@@ -0,0 +1,346 @@
+use crate::vec::AlignedVec;
+use arrow::array::{Array, ArrayData, BooleanArray};
+use arrow::buffer::MutableBuffer;
+use arrow::datatypes::ArrowNativeType;
+use arrow::datatypes::DataType;
+use arrow::util::bit_chunk_iterator::BitChunks;
+use arrow::util::bit_util;
+use num::FromPrimitive;
+use std::convert::TryInto;
+use std::ops::Add;
+
+/// Call `f` with the bit offset and the bits of every 64 bit word of `array` that are set in
+/// both the values and the validity buffer. Null values are thus never considered `true`.
+///
+/// The bits of the remainder word beyond the length of the array are unset.
+#[inline]
+fn for_each_true_word<F>(array: &BooleanArray, mut f: F)
+where
+    F: FnMut(usize, u64),
+{
+    let values = array.data_ref().buffers()[0].bit_chunks(array.offset(), array.len());
+    let remainder_len = values.remainder_len();
+    let remainder_offset = values.chunk_len() * 64;
+    // remainder_len < 64, so this cannot overflow
+    let remainder_mask = (1u64 << remainder_len) - 1;
+
+    match array.data_ref().null_buffer() {
+        Some(validity) if array.null_count() > 0 => {
+            let validity = validity.bit_chunks(array.offset(), array.len());
+            for (i, (word, valid)) in values.iter().zip(validity.iter()).enumerate() {
+                f(i * 64, word & valid)
+            }
+            let remainder = values.remainder_bits() & validity.remainder_bits();
+            f(remainder_offset, remainder & remainder_mask)
+        }
+        _ => {
+            for (i, word) in values.iter().enumerate() {
+                f(i * 64, word)
+            }
+            f(remainder_offset, values.remainder_bits() & remainder_mask)
+        }
+    }
+}
+
+/// Count the `true` values of a boolean array. Null values are not counted.
+pub fn count_true(array: &BooleanArray) -> usize {
+    let mut count = 0;
+    for_each_true_word(array, |_, word| count += word.count_ones() as usize);
+    count
+}
+
+/// Push the indices of the `true` values of `array` to `out`. The indices are shifted by
+/// `offset`, which is useful to collect the indices of multiple chunks. Null values are not
+/// considered `true`.
+///
+/// The index type `I` is generic, so that the indices can be collected as the row index type of
+/// the caller. It must be able to hold the indices of all values of `array`.
+pub fn arg_true<I>(array: &BooleanArray, offset: I, out: &mut AlignedVec<I>)
+where
+    I: ArrowNativeType + Add<Output = I> + FromPrimitive,
+{
+    for_each_true_word(array, |word_offset, mut word| {
+        let base = offset + I::from_usize(word_offset).unwrap();
+        while word != 0 {
+            out.push(base + I::from_u32(word.trailing_zeros()).unwrap());
+            // unset the lowest set bit
+            word &= word - 1;
+        }
+    })
+}
+
+/// A Kleene logic operation that combines a boolean mask with another.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MaskOp {
+    /// `lhs & rhs`
+    And,
+    /// `lhs | rhs`
+    Or,
+    /// `lhs & !rhs`
+    AndNot,
+}
+
+impl MaskOp {
+    /// Combine 64 values of both sides, given as the bits that are `true` and the validity bits.
+    /// Returns the bits that are `true` and the validity bits of the result.
+    ///
+    /// `false & null` is `false` and `true | null` is `true`, any other operation with a null
+    /// is null.
+    #[inline]
+    fn combine(self, lhs_true: u64, lhs_valid: u64, rhs_values: u64, rhs_valid: u64) -> (u64, u64) {
+        let lhs_false = lhs_valid & !lhs_true;
+        let (rhs_true, rhs_false) = match self {
+            MaskOp::AndNot => (rhs_valid & !rhs_values, rhs_valid & rhs_values),
+            _ => (rhs_valid & rhs_values, rhs_valid & !rhs_values),
+        };
+        let (out_true, out_false) = match self {
+            MaskOp::And | MaskOp::AndNot => (lhs_true & rhs_true, lhs_false | rhs_false),
+            MaskOp::Or => (lhs_true | rhs_true, lhs_false & rhs_false),
+        };
+        (out_true, out_true | out_false)
+    }
+}
+
+/// The words of a bitmap, the remainder is the last word.
+fn words<'a>(chunks: &BitChunks<'a>) -> impl Iterator<Item = u64> + 'a {
+    chunks
+        .iter()
+        .chain(std::iter::once(chunks.remainder_bits()))
+}
+
+#[inline]
+fn read_word(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes.try_into().unwrap())
+}
+
+/// The value and validity bitmaps of a boolean mask. The bitmaps are owned, so that other masks
+/// are combined with it in place, word by word.
+///
+/// A value bit is only set for a valid `true` value. The validity bitmap is only allocated once
+/// a mask with null values is combined with it.
+pub struct MutableMask {
+    values: MutableBuffer,
+    validity: Option<MutableBuffer>,
+    len: usize,
+}
+
+impl MutableMask {
+    /// A mask of `len` values that are all `false`.
+    pub fn new_false(len: usize) -> Self {
+        // whole 64 bit words, so that the bitmaps are combined a word at a time
+        let n_bytes = bit_util::ceil(len, 64) * 8;
+        Self {
+            values: MutableBuffer::from_len_zeroed(n_bytes),
+            validity: None,
+            len,
+        }
+    }
+
+    /// Copy the bitmaps of `array`.
+    pub fn from_array(array: &BooleanArray) -> Self {
+        let mut mask = Self::new_false(array.len());
+        // false | rhs == rhs
+        mask.combine(array, MaskOp::Or);
+        mask
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Set the value at index `i` to `true`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of bounds.
+    pub fn set_true(&mut self, i: usize) {
+        assert!(
+            i < self.len,
+            "index {} is out of bounds of length {}",
+            i,
+            self.len
+        );
+        bit_util::set_bit(self.values.as_slice_mut(), i);
+        if let Some(validity) = &mut self.validity {
+            bit_util::set_bit(validity.as_slice_mut(), i);
+        }
+    }
+
+    /// Combine `rhs` with this mask in place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rhs` doesn't have the same length.
+    pub fn combine(&mut self, rhs: &BooleanArray, op: MaskOp) {
+        assert_eq!(rhs.len(), self.len, "masks must have the same length");
+        let data = rhs.data_ref();
+        let rhs_values = data.buffers()[0].bit_chunks(rhs.offset(), rhs.len());
+        let rhs_values = words(&rhs_values);
+
+        match data.null_buffer() {
+            Some(rhs_validity) if rhs.null_count() > 0 => {
+                let n_bytes = self.values.len();
+                let validity = self
+                    .validity
+                    .get_or_insert_with(|| MutableBuffer::new(n_bytes).with_bitset(n_bytes, true));
+                let rhs_validity = rhs_validity.bit_chunks(rhs.offset(), rhs.len());
+                let rhs = rhs_values.zip(words(&rhs_validity));
+                combine_valid(self.values.as_slice_mut(), validity.as_slice_mut(), rhs, op)
+            }
+            _ => match &mut self.validity {
+                Some(validity) => {
+                    let rhs = rhs_values.zip(std::iter::repeat(u64::MAX));
+                    combine_valid(self.values.as_slice_mut(), validity.as_slice_mut(), rhs, op)
+                }
+                None => {
+                    for (lhs, rhs) in self
+                        .values
+                        .as_slice_mut()
+                        .chunks_exact_mut(8)
+                        .zip(rhs_values)
+                    {
+                        let (out, _) = op.combine(read_word(lhs), u64::MAX, rhs, u64::MAX);
+                        lhs.copy_from_slice(&out.to_le_bytes());
+                    }
+                }
+            },
+        }
+    }
+
+    pub fn into_array(self) -> BooleanArray {
+        let data = ArrayData::new(
+            DataType::Boolean,
+            self.len,
+            None,
+            self.validity.map(|validity| validity.into()),
+            0,
+            vec![self.values.into()],
+            vec![],
+        );
+        BooleanArray::from(data)
+    }
+}
+
+fn combine_valid<I>(values: &mut [u8], validity: &mut [u8], rhs: I, op: MaskOp)
+where
+    I: Iterator<Item = (u64, u64)>,
+{
+    let lhs = values.chunks_exact_mut(8).zip(validity.chunks_exact_mut(8));
+    for ((lhs_values, lhs_validity), (rhs_values, rhs_validity)) in lhs.zip(rhs) {
+        let (out, out_validity) = op.combine(
+            read_word(lhs_values),
+            read_word(lhs_validity),
+            rhs_values,
+            rhs_validity,
+        );
+        lhs_values.copy_from_slice(&out.to_le_bytes());
+        lhs_validity.copy_from_slice(&out_validity.to_le_bytes());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Pseudo random masks with varying densities of true and null values.
+    fn random_mask(len: usize, seed: u64, true_every: u64, null_every: u64) -> BooleanArray {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                // xorshift
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                if state % null_every == 0 {
+                    None
+                } else {
+                    Some((state >> 8) % true_every == 0)
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_arg_true_random_masks() {
+        for &len in &[0, 1, 63, 64, 65, 130, 257, 1000] {
+            for &(true_every, null_every) in &[(1, 1000), (2, 3), (3, 1), (50, 17), (1, 2)] {
+                let seed = len as u64 + true_every + null_every + 1;
+                let mask = random_mask(len, seed, true_every, null_every);
+                for offset in [0, 1, 7, 8, 63, 64, 65, 100].iter().filter(|o| **o <= len) {
+                    let sliced = mask.slice(*offset, len - offset);
+                    let sliced = sliced.as_any().downcast_ref::<BooleanArray>().unwrap();
+                    let expected = sliced
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(i, v)| {
+                            if v == Some(true) {
+                                Some(i as u32)
+                            } else {
+                                None
+                            }
+                        })
+                        .collect::<Vec<_>>();
+
+                    let mut out = AlignedVec::<u32>::default();
+                    arg_true(sliced, 10, &mut out);
+                    let expected_shifted = expected.iter().map(|i| i + 10).collect::<Vec<_>>();
+                    assert_eq!(out.as_slice(), expected_shifted.as_slice());
+                    assert_eq!(count_true(sliced), expected.len());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_mutable_mask_kleene() {
+        let kleene = |op: MaskOp, lhs: Option<bool>, rhs: Option<bool>| match (op, lhs, rhs) {
+            (MaskOp::And, Some(false), _) | (MaskOp::And, _, Some(false)) => Some(false),
+            (MaskOp::And, Some(true), Some(true)) => Some(true),
+            (MaskOp::Or, Some(true), _) | (MaskOp::Or, _, Some(true)) => Some(true),
+            (MaskOp::Or, Some(false), Some(false)) => Some(false),
+            (MaskOp::AndNot, Some(false), _) | (MaskOp::AndNot, _, Some(true)) => Some(false),
+            (MaskOp::AndNot, Some(true), Some(false)) => Some(true),
+            _ => None,
+        };
+
+        for &len in &[0, 1, 63, 64, 65, 200] {
+            for &(null_every, offset) in &[(1000, 0), (3, 0), (2, 5), (1000, 64)] {
+                let seed = len as u64 + null_every + offset as u64 + 1;
+                let lhs = random_mask(len, seed, 2, null_every);
+                let rhs = random_mask(len + offset, seed * 3, 2, null_every + 1);
+                let rhs = rhs.slice(offset, len);
+                let rhs = rhs.as_any().downcast_ref::<BooleanArray>().unwrap();
+                let no_nulls = random_mask(len, seed * 7, 3, u64::MAX);
+
+                for &op in &[MaskOp::And, MaskOp::Or, MaskOp::AndNot] {
+                    let mut mask = MutableMask::from_array(&lhs);
+                    mask.combine(rhs, op);
+                    mask.combine(&no_nulls, op);
+                    let out = mask.into_array();
+
+                    let expected = lhs
+                        .iter()
+                        .zip(rhs.iter())
+                        .zip(no_nulls.iter())
+                        .map(|((l, r), n)| kleene(op, kleene(op, l, r), n))
+                        .collect::<Vec<_>>();
+                    assert_eq!(out.iter().collect::<Vec<_>>(), expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_mutable_mask_set_true() {
+        let mut mask = MutableMask::new_false(70);
+        mask.set_true(0);
+        mask.set_true(69);
+        let mut out = AlignedVec::default();
+        arg_true(&mask.into_array(), 0, &mut out);
+        assert_eq!(out.as_slice(), &[0, 69]);
+    }
+}
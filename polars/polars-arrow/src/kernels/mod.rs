@@ -1,6 +1,8 @@
 use arrow::array::{Array, BooleanArray};
 use arrow::util::bit_chunk_iterator::BitChunkIterator;
 use std::iter::Enumerate;
+pub mod agg;
+pub mod boolean;
 pub mod set;
 pub mod shrink_to_fit;
 
@@ -1,6 +1,7 @@
+use crate::bit_util::combine_validities;
 use crate::error::{PolarsError, Result};
 use crate::kernels::BinaryMaskedSliceIterator;
-use crate::utils::{buffer_or, combine_null_buffers};
+use crate::utils::buffer_or;
 use crate::vec::AlignedVec;
 use arrow::array::*;
 use arrow::datatypes::{ArrowNativeType, ArrowNumericType, ArrowPrimitiveType};
@@ -35,7 +36,7 @@ where
     });
 
     // now we also combine it with the null buffer of the mask
-    let validity = combine_null_buffers(
+    let validity = combine_validities(
         validity.as_ref(),
         0,
         mask.data().null_buffer(),
@@ -104,6 +105,50 @@ mod test {
         assert_eq!(out, &[Some(1), Some(1), None])
     }
 
+    #[test]
+    fn test_set_mask_sliced() {
+        // xorshift
+        let mut state = 7u64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        let len = 100;
+        let val: UInt32Array = (0..len + 8)
+            .map(|i| if next() % 3 == 0 { None } else { Some(i) })
+            .collect();
+        let mask: BooleanArray = (0..len + 8)
+            .map(|_| match next() % 4 {
+                0 => None,
+                1 => Some(true),
+                _ => Some(false),
+            })
+            .collect();
+
+        for val_offset in 0..8 {
+            for mask_offset in 0..8 {
+                let val = val.slice(val_offset, len as usize);
+                let val = val.as_any().downcast_ref::<UInt32Array>().unwrap();
+                let mask = mask.slice(mask_offset, len as usize);
+                let mask = mask.as_any().downcast_ref::<BooleanArray>().unwrap();
+
+                let expected: Vec<_> = val
+                    .iter()
+                    .zip(mask.iter())
+                    .map(|(v, m)| match m {
+                        None => None,
+                        Some(true) => Some(100),
+                        Some(false) => v,
+                    })
+                    .collect();
+                let out: Vec<_> = set_with_mask(val, mask, 100).iter().collect();
+                assert_eq!(out, expected, "offsets {} {}", val_offset, mask_offset);
+            }
+        }
+    }
+
     #[test]
     fn test_set_at_idx() {
         let val = UInt32Array::from(vec![1, 2, 3]);
@@ -94,22 +94,6 @@ pub fn buffer_or(
     (arrow::compute::or(&l, &r).unwrap()).data().buffers()[0].clone()
 }
 
-/// Combine the validity by doing a bitand operation.
-pub fn combine_null_buffers(
-    opt_l: Option<&Buffer>,
-    offset_l: usize,
-    opt_r: Option<&Buffer>,
-    offset_r: usize,
-    len_in_bits: usize,
-) -> Option<Buffer> {
-    match (opt_l, opt_r) {
-        (Some(l), Some(r)) => Some(buffer_and(l, offset_l, r, offset_r, len_in_bits)),
-        (Some(l), None) => Some(l.clone()),
-        (None, Some(r)) => Some(r.clone()),
-        (None, None) => None,
-    }
-}
-
 pub trait CustomIterTools: Iterator {
     fn fold_first_<F>(mut self, f: F) -> Option<Self::Item>
     where
@@ -100,6 +100,52 @@ where
     ca
 }
 
+/// Apply `operation` on the values of two arrays, where an array of length 1 is broadcasted.
+/// The output is null if either value is null or if `operation` returns `None`, which is used
+/// for overflowing or invalid operations, e.g. a division by zero.
+pub(crate) fn arithmetic_helper_opt<T, F>(
+    lhs: &ChunkedArray<T>,
+    rhs: &ChunkedArray<T>,
+    operation: F,
+) -> ChunkedArray<T>
+where
+    T: PolarsNumericType,
+    F: Fn(T::Native, T::Native) -> Option<T::Native>,
+{
+    let operation =
+        |opt_lhs: Option<T::Native>, opt_rhs: Option<T::Native>| match (opt_lhs, opt_rhs) {
+            (Some(lhs), Some(rhs)) => operation(lhs, rhs),
+            _ => None,
+        };
+    let mut ca: ChunkedArray<T> = match (lhs.len(), rhs.len()) {
+        (a, b) if a == b => lhs
+            .into_iter()
+            .zip(rhs.into_iter())
+            .map(|(opt_lhs, opt_rhs)| operation(opt_lhs, opt_rhs))
+            .collect(),
+        // broadcast right path
+        (_, 1) => {
+            let opt_rhs = rhs.get(0);
+            lhs.into_iter()
+                .map(|opt_lhs| operation(opt_lhs, opt_rhs))
+                .collect()
+        }
+        (1, _) => {
+            let opt_lhs = lhs.get(0);
+            rhs.into_iter()
+                .map(|opt_rhs| operation(opt_lhs, opt_rhs))
+                .collect()
+        }
+        _ => panic!("Cannot apply operation on arrays of different lengths"),
+    };
+    ca.rename(lhs.name());
+    ca
+}
+
+fn is_float<T: PolarsNumericType>() -> bool {
+    matches!(T::get_dtype(), DataType::Float32 | DataType::Float64)
+}
+
 // Operands on ChunkedArray & ChunkedArray
 
 impl<T> Add for &ChunkedArray<T>
@@ -131,8 +177,27 @@ where
 {
     type Output = ChunkedArray<T>;
 
+    /// Integer division by zero results in a null value. Float division by zero follows
+    /// IEEE 754 and results in `inf`, `-inf` or `NaN`.
     fn div(self, rhs: Self) -> Self::Output {
-        arithmetic_helper(self, rhs, compute::divide, |lhs, rhs| lhs / rhs)
+        // the arrow kernel errors on a zero divisor, so we only use it if there are no zeros.
+        // Null slots are also scanned, those may lead to the slower path, but not to a wrong result.
+        let has_zero = rhs
+            .downcast_iter()
+            .any(|arr| arr.values().iter().any(|v| v.is_zero()));
+        if has_zero && is_float::<T>() {
+            arithmetic_helper_opt(self, rhs, |lhs, rhs| Some(lhs / rhs))
+        } else if has_zero {
+            arithmetic_helper_opt(self, rhs, |lhs, rhs| {
+                if rhs.is_zero() {
+                    None
+                } else {
+                    Some(lhs / rhs)
+                }
+            })
+        } else {
+            arithmetic_helper(self, rhs, compute::divide, |lhs, rhs| lhs / rhs)
+        }
     }
 }
 
@@ -320,8 +385,15 @@ where
 {
     type Output = ChunkedArray<T>;
 
+    /// Integer division by zero results in null values. Float division by zero follows
+    /// IEEE 754 and results in `inf`, `-inf` or `NaN`.
     fn div(self, rhs: N) -> Self::Output {
         let rhs: T::Native = NumCast::from(rhs).expect("could not cast");
+        if rhs.is_zero() && is_float::<T>() {
+            return self.apply(|v| v / rhs);
+        } else if rhs.is_zero() {
+            return ChunkedArray::full_null(self.name(), self.len());
+        }
         self.apply_kernel(|arr| Arc::new(divide_scalar(arr, rhs).unwrap()))
     }
 }
@@ -564,4 +636,92 @@ pub(crate) mod test {
         let b = a.pow_f64(2.);
         println!("{:?}", b);
     }
+
+    #[test]
+    fn test_div_by_zero() {
+        let a = Int32Chunked::new_from_slice("a", &[7, -7, 7, -7, 1]);
+        let b = Int32Chunked::new_from_slice("b", &[2, 2, -2, -2, 0]);
+        // integer division truncates towards zero and a zero divisor results in null
+        assert_eq!(
+            Vec::from(&(&a / &b)),
+            &[Some(3), Some(-3), Some(-3), Some(3), None]
+        );
+        assert_eq!((&a / &b).name(), "a");
+        let zero = Int32Chunked::new_from_slice("b", &[0]);
+        assert_eq!(Vec::from(&(&a / &zero)), &[None; 5]);
+        assert_eq!(
+            Vec::from(&(&zero / &b)),
+            &[Some(0), Some(0), Some(0), Some(0), None]
+        );
+        assert_eq!(Vec::from(&(&a / 0)), &[None; 5]);
+
+        // floats keep the IEEE 754 semantics
+        let a = Float64Chunked::new_from_slice("a", &[1.0, -1.0, 0.0, 3.0]);
+        let b = Float64Chunked::new_from_slice("b", &[0.0, 0.0, 0.0, 2.0]);
+        let out = &a / &b;
+        assert_eq!(out.null_count(), 0);
+        assert_eq!(out.get(0), Some(f64::INFINITY));
+        assert_eq!(out.get(1), Some(f64::NEG_INFINITY));
+        assert!(out.get(2).unwrap().is_nan());
+        assert_eq!(out.get(3), Some(1.5));
+        let out = &a / 0;
+        assert_eq!(out.null_count(), 0);
+        assert_eq!(out.get(0), Some(f64::INFINITY));
+    }
+
+    #[test]
+    fn test_sliced_validity() {
+        // xorshift
+        let mut state = 11u64;
+        let mut random_ca = |len: i32| {
+            (0..len)
+                .map(|i| {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    if state % 3 == 0 {
+                        None
+                    } else {
+                        Some(i)
+                    }
+                })
+                .collect::<Int32Chunked>()
+        };
+        let (a, b) = (random_ca(80), random_ca(80));
+        let len = 70;
+
+        let binary = |l: Option<i32>, r: Option<i32>, f: fn(i32, i32) -> i32| match (l, r) {
+            (Some(l), Some(r)) => Some(f(l, r)),
+            _ => None,
+        };
+        for lhs_offset in 0..8 {
+            for rhs_offset in 0..8 {
+                let lhs = a.slice(lhs_offset, len);
+                let rhs = b.slice(rhs_offset, len);
+                let expected = |f: fn(i32, i32) -> i32| {
+                    lhs.into_iter()
+                        .zip(rhs.into_iter())
+                        .map(|(l, r)| binary(l, r, f))
+                        .collect::<Vec<_>>()
+                };
+                assert_eq!(Vec::from(&(&lhs + &rhs)), expected(|l, r| l + r));
+                assert_eq!(Vec::from(&(&lhs - &rhs)), expected(|l, r| l - r));
+                assert_eq!(Vec::from(&(&lhs * &rhs)), expected(|l, r| l * r));
+                assert_eq!(
+                    Vec::from(&lhs.gt(&rhs)),
+                    lhs.into_iter()
+                        .zip(rhs.into_iter())
+                        .map(|(l, r)| Some(l? > r?))
+                        .collect::<Vec<_>>()
+                );
+            }
+            // broadcast
+            let lhs = a.slice(lhs_offset, len);
+            let rhs = Int32Chunked::new_from_slice("b", &[2]);
+            assert_eq!(
+                Vec::from(&(&lhs + &rhs)),
+                lhs.into_iter().map(|l| Some(l? + 2)).collect::<Vec<_>>()
+            );
+        }
+    }
 }
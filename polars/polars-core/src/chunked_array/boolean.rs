@@ -1,10 +1,259 @@
 use crate::prelude::*;
-use crate::utils::NoNull;
+use arrow::array::ArrayRef;
+use polars_arrow::kernels::boolean::{arg_true, count_true, MaskOp, MutableMask};
 
 impl BooleanChunked {
-    pub fn arg_true(&self) -> UInt32Chunked {
-        // the allocation is probably cheaper as the filter is super fast
-        let ca: NoNull<UInt32Chunked> = (0u32..self.len() as u32).collect();
-        ca.into_inner().filter(self).unwrap()
+    /// Get the indices of the `true` values. Null values are not considered `true`.
+    pub fn arg_true(&self) -> IdxCa {
+        let mut idx = AlignedVec::with_capacity_aligned(self.count_true());
+        let mut offset = 0;
+        for arr in self.downcast_iter() {
+            arg_true(arr, offset, &mut idx);
+            offset += arr.len() as IdxSize;
+        }
+        IdxCa::new_from_aligned_vec(self.name(), idx)
+    }
+
+    /// Count the `true` values. Null values are not counted.
+    pub(crate) fn count_true(&self) -> usize {
+        self.downcast_iter().map(count_true).sum()
+    }
+
+    /// Create a mask of length `len` that is `true` at the `indices`, the inverse of
+    /// [arg_true](Self::arg_true). Null indices are ignored.
+    ///
+    /// A sparse set of rows is smaller as indices, a dense set as a mask.
+    pub fn from_indices(indices: &IdxCa, len: usize) -> Result<Self> {
+        let mut mask = MutableMask::new_false(len);
+        for idx in indices.into_iter().flatten() {
+            let idx = idx as usize;
+            if idx >= len {
+                return Err(PolarsError::IndexOutOfBounds { index: idx, len });
+            }
+            mask.set_true(idx);
+        }
+        Ok(MaskBuffer::from_mask(indices.name(), mask).finish())
+    }
+
+    /// Kleene AND with `rhs`, see [MaskBuffer] to combine more masks in one buffer.
+    pub fn and_with(&self, rhs: &BooleanChunked) -> Result<Self> {
+        let mut mask = MaskBuffer::new(self);
+        mask.and_with(rhs)?;
+        Ok(mask.finish())
+    }
+
+    /// Kleene OR with `rhs`, see [MaskBuffer] to combine more masks in one buffer.
+    pub fn or_with(&self, rhs: &BooleanChunked) -> Result<Self> {
+        let mut mask = MaskBuffer::new(self);
+        mask.or_with(rhs)?;
+        Ok(mask.finish())
+    }
+
+    /// Kleene `self & !rhs`, the rows of `self` that are not in `rhs`. See [MaskBuffer] to
+    /// combine more masks in one buffer.
+    pub fn and_not(&self, rhs: &BooleanChunked) -> Result<Self> {
+        let mut mask = MaskBuffer::new(self);
+        mask.and_not(rhs)?;
+        Ok(mask.finish())
+    }
+}
+
+/// A boolean mask of which the bitmaps are owned, so that other masks are combined with it in
+/// place, 64 values at a time. A chain of masks, like the conditions of a filter, is combined
+/// in a single output buffer instead of allocating a mask per operation.
+///
+/// The operations follow Kleene logic: `false & null` is `false` and `true | null` is `true`,
+/// any other operation with a null is null. This is the same as the `&` and `|` operators on a
+/// [BooleanChunked].
+///
+/// # Example
+///
+/// ```rust
+/// # use polars_core::prelude::*;
+/// fn example(masks: &[BooleanChunked]) -> Result<BooleanChunked> {
+///     let mut mask = MaskBuffer::new(&masks[0]);
+///     for other in &masks[1..] {
+///         mask.and_with(other)?;
+///     }
+///     Ok(mask.finish())
+/// }
+/// ```
+pub struct MaskBuffer {
+    name: String,
+    mask: MutableMask,
+}
+
+impl MaskBuffer {
+    /// Copy `mask` to an owned buffer.
+    pub fn new(mask: &BooleanChunked) -> Self {
+        let mask_buf = match mask.rechunk().downcast_iter().next() {
+            Some(arr) => MutableMask::from_array(arr),
+            None => MutableMask::new_false(0),
+        };
+        Self::from_mask(mask.name(), mask_buf)
+    }
+
+    fn from_mask(name: &str, mask: MutableMask) -> Self {
+        Self {
+            name: name.to_string(),
+            mask,
+        }
+    }
+
+    fn combine(&mut self, rhs: &BooleanChunked, op: MaskOp) -> Result<()> {
+        if rhs.len() != self.mask.len() {
+            return Err(PolarsError::ShapeMisMatch(
+                format!(
+                    "cannot combine a mask of length {} with a mask of length {}",
+                    self.mask.len(),
+                    rhs.len()
+                )
+                .into(),
+            ));
+        }
+        if let Some(arr) = rhs.rechunk().downcast_iter().next() {
+            self.mask.combine(arr, op)
+        }
+        Ok(())
+    }
+
+    /// Kleene AND with `rhs` in place.
+    pub fn and_with(&mut self, rhs: &BooleanChunked) -> Result<()> {
+        self.combine(rhs, MaskOp::And)
+    }
+
+    /// Kleene OR with `rhs` in place.
+    pub fn or_with(&mut self, rhs: &BooleanChunked) -> Result<()> {
+        self.combine(rhs, MaskOp::Or)
+    }
+
+    /// Kleene `self & !rhs` in place.
+    pub fn and_not(&mut self, rhs: &BooleanChunked) -> Result<()> {
+        self.combine(rhs, MaskOp::AndNot)
+    }
+
+    pub fn finish(self) -> BooleanChunked {
+        let arr = Arc::new(self.mask.into_array()) as ArrayRef;
+        BooleanChunked::new_from_chunks(&self.name, vec![arr])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_arg_true() {
+        let values = (0..200)
+            .map(|i| match i % 7 {
+                0 => None,
+                1 | 4 => Some(true),
+                _ => Some(false),
+            })
+            .collect::<Vec<_>>();
+        let expected = values
+            .iter()
+            .enumerate()
+            .filter_map(|(i, v)| {
+                if *v == Some(true) {
+                    Some(i as IdxSize)
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // multiple chunks, of which some have an offset
+        let ca = BooleanChunked::new_from_opt_slice("mask", &values);
+        let mut chunked = ca.slice(0, 3);
+        chunked.append(&ca.slice(3, 70));
+        chunked.append(&ca.slice(73, 127));
+        assert_eq!(chunked.chunks().len(), 3);
+
+        for ca in &[ca, chunked] {
+            let idx = ca.arg_true();
+            assert_eq!(idx.name(), "mask");
+            assert_eq!(idx.cont_slice().unwrap(), expected.as_slice());
+            assert_eq!(ca.count_true(), expected.len());
+        }
+    }
+
+    #[test]
+    fn test_mask_algebra() -> Result<()> {
+        let a = BooleanChunked::new_from_opt_slice(
+            "a",
+            &[
+                Some(true),
+                Some(true),
+                Some(true),
+                Some(false),
+                Some(false),
+                None,
+                None,
+            ],
+        );
+        let b = BooleanChunked::new_from_opt_slice(
+            "b",
+            &[
+                Some(true),
+                Some(false),
+                None,
+                Some(false),
+                None,
+                Some(true),
+                None,
+            ],
+        );
+        // multiple chunks
+        let mut b_chunked = b.slice(0, 2);
+        b_chunked.append(&b.slice(2, 5));
+
+        let values = |ca: &BooleanChunked| -> Vec<Option<bool>> { ca.into_iter().collect() };
+        for b in &[&b, &b_chunked] {
+            let b = *b;
+            let and = a.and_with(b)?;
+            assert_eq!(and.name(), "a");
+            assert_eq!(values(&and), values(&(&a & b)));
+            assert_eq!(values(&a.or_with(b)?), values(&(&a | b)));
+            assert_eq!(values(&a.and_not(b)?), values(&(&a & &!b)));
+        }
+
+        // a chain of masks in one buffer
+        let mut mask = MaskBuffer::new(&a);
+        mask.or_with(&b)?;
+        mask.and_not(&b)?;
+        mask.and_with(&a)?;
+        let expected = &(&(&a | &b) & &!&b) & &a;
+        assert_eq!(values(&mask.finish()), values(&expected));
+
+        assert!(matches!(
+            a.and_with(&b.slice(0, 3)),
+            Err(PolarsError::ShapeMisMatch(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_indices() -> Result<()> {
+        let idx = IdxCa::new_from_opt_slice("idx", &[Some(1), None, Some(4), Some(1)]);
+        let mask = BooleanChunked::from_indices(&idx, 6)?;
+        assert_eq!(mask.name(), "idx");
+        assert_eq!(
+            Vec::<Option<bool>>::from(&mask),
+            &[
+                Some(false),
+                Some(true),
+                Some(false),
+                Some(false),
+                Some(true),
+                Some(false)
+            ]
+        );
+        assert_eq!(mask.arg_true().cont_slice()?, &[1, 4]);
+        assert!(matches!(
+            BooleanChunked::from_indices(&idx, 4),
+            Err(PolarsError::IndexOutOfBounds { index: 4, len: 4 })
+        ));
+        Ok(())
     }
 }
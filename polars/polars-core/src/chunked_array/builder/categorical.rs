@@ -1,14 +1,25 @@
+use crate::chunked_array::estimated_array_data_size;
 use crate::prelude::*;
 use crate::{
-    datatypes::PlHashMap,
+    datatypes::{PlHashMap, PlHashSet},
     use_string_cache,
     utils::arrow::array::{Array, ArrayBuilder},
+    StringCacheGeneration,
 };
-use arrow::array::{LargeStringArray, LargeStringBuilder, PrimitiveBuilder};
+use arrow::array::{
+    make_array, ArrayData, ArrayRef, LargeStringArray, LargeStringBuilder, PrimitiveBuilder,
+};
+use arrow::compute::cast;
 use std::marker::PhantomData;
+use std::ops::Deref;
 
 pub enum RevMappingBuilder {
-    Global(PlHashMap<u32, u32>, LargeStringBuilder, u128),
+    Global(
+        PlHashMap<u32, u32>,
+        LargeStringBuilder,
+        u128,
+        StringCacheGeneration,
+    ),
     Local(LargeStringBuilder),
 }
 
@@ -17,7 +28,7 @@ impl RevMappingBuilder {
         use RevMappingBuilder::*;
         match self {
             Local(builder) => builder.append_value(value).unwrap(),
-            Global(map, builder, _) => {
+            Global(map, builder, _, _) => {
                 if !map.contains_key(&idx) {
                     builder.append_value(value).unwrap();
                     let new_idx = builder.len() as u32 - 1;
@@ -31,16 +42,24 @@ impl RevMappingBuilder {
         use RevMappingBuilder::*;
         match self {
             Local(mut b) => RevMapping::Local(b.finish()),
-            Global(mut map, mut b, uuid) => {
+            Global(mut map, mut b, uuid, generation) => {
                 map.shrink_to_fit();
-                RevMapping::Global(map, b.finish(), uuid)
+                RevMapping::Global(map, b.finish(), uuid, generation)
             }
         }
     }
 }
 
 pub enum RevMapping {
-    Global(PlHashMap<u32, u32>, LargeStringArray, u128),
+    /// The indices in the global string cache mapped to the categories, the categories, the
+    /// uuid of the cache and the cache generation in which the mapping was created. The
+    /// categories are owned by the mapping, so pruning the string cache doesn't affect them.
+    Global(
+        PlHashMap<u32, u32>,
+        LargeStringArray,
+        u128,
+        StringCacheGeneration,
+    ),
     Local(LargeStringArray),
 }
 
@@ -48,15 +67,28 @@ pub enum RevMapping {
 impl RevMapping {
     pub fn len(&self) -> usize {
         match self {
-            Self::Global(_, a, _) => a.len(),
+            Self::Global(_, a, _, _) => a.len(),
             Self::Local(a) => a.len(),
         }
     }
 
+    /// Estimate the number of bytes of the mapping, see
+    /// [ChunkedArray::estimated_size](crate::chunked_array::ChunkedArray::estimated_size).
+    pub fn estimated_size(&self) -> usize {
+        let mut seen = PlHashSet::default();
+        match self {
+            Self::Global(map, a, _, _) => {
+                map.capacity() * 2 * std::mem::size_of::<u32>()
+                    + estimated_array_data_size(a.data(), &mut seen)
+            }
+            Self::Local(a) => estimated_array_data_size(a.data(), &mut seen),
+        }
+    }
+
     /// Categorical to str
     pub fn get(&self, idx: u32) -> &str {
         match self {
-            Self::Global(map, a, _) => {
+            Self::Global(map, a, _, _) => {
                 let idx = *map.get(&idx).unwrap();
                 a.value(idx as usize)
             }
@@ -66,7 +98,18 @@ impl RevMapping {
     /// Check if the categoricals are created under the same global string cache.
     pub fn same_src(&self, other: &Self) -> bool {
         match (self, other) {
-            (RevMapping::Global(_, _, l), RevMapping::Global(_, _, r)) => *l == *r,
+            (RevMapping::Global(_, _, l, _), RevMapping::Global(_, _, r, _)) => *l == *r,
+            _ => false,
+        }
+    }
+
+    /// Check if `self` is a Local mapping that starts with all categories of the Local mapping
+    /// `other`, so that the indexes of `other` are also valid in `self`.
+    pub(crate) fn extends(&self, other: &Self) -> bool {
+        match (self, other) {
+            (RevMapping::Local(l), RevMapping::Local(r)) => {
+                l.len() >= r.len() && (0..r.len()).all(|idx| l.value(idx) == r.value(idx))
+            }
             _ => false,
         }
     }
@@ -74,7 +117,7 @@ impl RevMapping {
     /// str to Categorical
     pub fn find(&self, value: &str) -> Option<u32> {
         match self {
-            Self::Global(map, a, _) => {
+            Self::Global(map, a, _, _) => {
                 map.iter()
                     // Safety:
                     // value is always within bounds
@@ -90,6 +133,215 @@ impl RevMapping {
     }
 }
 
+fn dictionary_dtype() -> ArrowDataType {
+    ArrowDataType::Dictionary(
+        Box::new(ArrowDataType::UInt32),
+        Box::new(ArrowDataType::LargeUtf8),
+    )
+}
+
+impl CategoricalChunked {
+    /// Convert to arrow `DictionaryArray`s with `u32` keys and large-utf8 values.
+    ///
+    /// All chunks share a single dictionary: the categories of the [RevMapping] in index order.
+    /// The indexes of a Local mapping are kept as is. The indexes in the global string cache
+    /// are replaced by the position of the category in the mapping, so the dictionary is dense.
+    pub fn to_arrow_dictionary(&self) -> Vec<ArrayRef> {
+        let (keys, values) = match self.categorical_map.as_deref() {
+            Some(RevMapping::Global(map, values, _, _)) => (
+                self.deref().apply(|idx| *map.get(&idx).unwrap()),
+                values.data().clone(),
+            ),
+            Some(RevMapping::Local(values)) => (self.deref().clone(), values.data().clone()),
+            None => (
+                self.deref().clone(),
+                LargeStringArray::from(Vec::<&str>::new()).data().clone(),
+            ),
+        };
+
+        keys.downcast_iter()
+            .map(|arr| {
+                let data = arr.data();
+                let mut builder = ArrayData::builder(dictionary_dtype())
+                    .buffers(data.buffers().to_vec())
+                    .len(arr.len())
+                    .offset(arr.offset())
+                    .add_child_data(values.clone());
+                if let Some(null_buf) = data.null_buffer().cloned() {
+                    builder = builder.null_bit_buffer(null_buf);
+                }
+                make_array(builder.build())
+            })
+            .collect()
+    }
+
+    /// Create a CategoricalChunked from arrow `DictionaryArray`s with integer keys and utf8
+    /// values.
+    ///
+    /// The keys are used as the indexes of a Local [RevMapping] of which index `i` maps to
+    /// dictionary value `i`, so the values are not interned again. The chunks must share their
+    /// dictionary, or every dictionary must extend the previous ones, like the delta
+    /// dictionaries of arrow IPC.
+    pub fn from_arrow_dictionary(name: &str, chunks: &[ArrayRef]) -> Result<Self> {
+        let mut keys = Vec::with_capacity(chunks.len());
+        let mut mapping: Option<RevMapping> = None;
+
+        for arr in chunks {
+            let key_dtype = match arr.data_type() {
+                ArrowDataType::Dictionary(key_dtype, value_dtype)
+                    if matches!(
+                        **value_dtype,
+                        ArrowDataType::Utf8 | ArrowDataType::LargeUtf8
+                    ) =>
+                {
+                    (**key_dtype).clone()
+                }
+                dt => {
+                    return Err(PolarsError::DataTypeMisMatch(
+                        format!("cannot create a categorical from arrow type {:?}", dt).into(),
+                    ))
+                }
+            };
+            let data = arr.data();
+
+            let mut builder = ArrayData::builder(key_dtype)
+                .buffers(data.buffers().to_vec())
+                .len(arr.len())
+                .offset(arr.offset());
+            if let Some(null_buf) = data.null_buffer().cloned() {
+                builder = builder.null_bit_buffer(null_buf);
+            }
+            keys.push(cast(&make_array(builder.build()), &ArrowDataType::UInt32)?);
+
+            let values = cast(
+                &make_array(data.child_data()[0].clone()),
+                &ArrowDataType::LargeUtf8,
+            )?;
+            let chunk_mapping = RevMapping::Local(LargeStringArray::from(values.data().clone()));
+
+            mapping = match mapping {
+                None => Some(chunk_mapping),
+                Some(prev) if chunk_mapping.extends(&prev) => Some(chunk_mapping),
+                Some(prev) if prev.extends(&chunk_mapping) => Some(prev),
+                Some(_) => {
+                    return Err(PolarsError::InvalidOperation(
+                        format!(
+                            "the chunks of categorical column '{}' have different dictionaries",
+                            name
+                        )
+                        .into(),
+                    ))
+                }
+            };
+        }
+
+        let mut ca = CategoricalChunked::new_from_chunks(name, keys);
+        ca.categorical_map =
+            Some(Arc::new(mapping.unwrap_or_else(|| {
+                RevMapping::Local(LargeStringArray::from(Vec::<&str>::new()))
+            })));
+        Ok(ca)
+    }
+}
+
+/// How [Utf8Chunked::cast_to_categorical_with] handles a string that is not a category of the
+/// mapping.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum UnknownCategory {
+    /// Return an error.
+    Error,
+    /// Set the value to null.
+    Null,
+    /// Add the string as a new category. Only valid for a Local mapping.
+    Extend,
+}
+
+impl Utf8Chunked {
+    /// Cast to a categorical that uses an existing [RevMapping], e.g. the mapping of a
+    /// categorical created earlier, so that the indexes of both categoricals are the same.
+    /// Unlike the global string cache this only affects the categoricals that share the mapping.
+    ///
+    /// Strings that are not a category of `mapping` are handled according to `on_unknown`.
+    /// With [UnknownCategory::Extend] the output gets a new Local mapping that starts with the
+    /// categories of `mapping`, otherwise `mapping` is shared by the output.
+    pub fn cast_to_categorical_with(
+        &self,
+        mapping: &Arc<RevMapping>,
+        on_unknown: UnknownCategory,
+    ) -> Result<CategoricalChunked> {
+        if on_unknown == UnknownCategory::Extend
+            && matches!(mapping.as_ref(), RevMapping::Global(..))
+        {
+            return Err(PolarsError::InvalidOperation(
+                "a mapping of the global string cache cannot be extended".into(),
+            ));
+        }
+        let mut lookup = PlHashMap::with_capacity(mapping.len());
+        match mapping.as_ref() {
+            RevMapping::Global(map, values, _, _) => {
+                for (global_idx, idx) in map {
+                    lookup.insert(values.value(*idx as usize), *global_idx);
+                }
+            }
+            RevMapping::Local(values) => {
+                for idx in 0..values.len() {
+                    lookup.entry(values.value(idx)).or_insert(idx as u32);
+                }
+            }
+        }
+        // the categories that are added to a Local mapping
+        let mut new_categories = Vec::new();
+
+        let mut keys = PrimitiveBuilder::<UInt32Type>::new(self.len());
+        for opt_s in self.into_iter() {
+            let s = match opt_s {
+                Some(s) => s,
+                None => {
+                    keys.append_null()?;
+                    continue;
+                }
+            };
+            match (lookup.get(s), on_unknown) {
+                (Some(idx), _) => keys.append_value(*idx)?,
+                (None, UnknownCategory::Error) => {
+                    return Err(PolarsError::ValueError(
+                        format!("'{}' is not a category of the mapping", s).into(),
+                    ))
+                }
+                (None, UnknownCategory::Null) => keys.append_null()?,
+                (None, UnknownCategory::Extend) => {
+                    let idx = (mapping.len() + new_categories.len()) as u32;
+                    lookup.insert(s, idx);
+                    new_categories.push(s);
+                    keys.append_value(idx)?;
+                }
+            }
+        }
+
+        let mapping = match mapping.as_ref() {
+            RevMapping::Local(values) if !new_categories.is_empty() => {
+                let mut builder = LargeStringBuilder::new(values.len() + new_categories.len());
+                for idx in 0..values.len() {
+                    builder.append_value(values.value(idx))?;
+                }
+                for s in new_categories {
+                    builder.append_value(s)?;
+                }
+                Arc::new(RevMapping::Local(builder.finish()))
+            }
+            _ => mapping.clone(),
+        };
+
+        let mut ca =
+            CategoricalChunked::new_from_chunks(self.name(), vec![Arc::new(keys.finish())]);
+        ca.categorical_map = Some(mapping);
+        if self.chunks.len() > 1 && !self.is_empty() {
+            ca = ca.split_chunks(self.chunk_id().filter(|len| *len > 0));
+        }
+        Ok(ca)
+    }
+}
+
 pub struct CategoricalChunkedBuilder {
     array_builder: PrimitiveBuilder<UInt32Type>,
     field: Field,
@@ -100,8 +352,9 @@ impl CategoricalChunkedBuilder {
     pub fn new(name: &str, capacity: usize) -> Self {
         let builder = LargeStringBuilder::new(capacity / 10);
         let reverse_mapping = if use_string_cache() {
-            let uuid = crate::STRING_CACHE.lock_map().uuid;
-            RevMappingBuilder::Global(PlHashMap::default(), builder, uuid)
+            let mut cache = crate::STRING_CACHE.lock_map();
+            let generation = StringCacheGeneration::new(&mut cache);
+            RevMappingBuilder::Global(PlHashMap::default(), builder, cache.uuid, generation)
         } else {
             RevMappingBuilder::Local(builder)
         };
@@ -112,6 +365,16 @@ impl CategoricalChunkedBuilder {
             reverse_mapping,
         }
     }
+
+    /// Create a builder that maps the strings to local indexes, also if the global string
+    /// cache is used. The global string cache is not touched.
+    pub fn new_local(name: &str, capacity: usize) -> Self {
+        CategoricalChunkedBuilder {
+            array_builder: PrimitiveBuilder::<UInt32Type>::new(capacity),
+            field: Field::new(name, DataType::Categorical),
+            reverse_mapping: RevMappingBuilder::Local(LargeStringBuilder::new(capacity / 10)),
+        }
+    }
 }
 impl CategoricalChunkedBuilder {
     /// Appends all the values in a single lock of the global string cache.
@@ -119,20 +382,13 @@ impl CategoricalChunkedBuilder {
     where
         I: IntoIterator<Item = Option<&'a str>>,
     {
-        if use_string_cache() {
+        if matches!(self.reverse_mapping, RevMappingBuilder::Global(..)) {
             let mut cache = crate::STRING_CACHE.lock_map();
 
             for opt_s in i {
                 match opt_s {
                     Some(s) => {
-                        let idx = match cache.map.get(s) {
-                            Some(idx) => *idx,
-                            None => {
-                                let idx = cache.map.len() as u32;
-                                cache.map.insert(s.to_string(), idx);
-                                idx
-                            }
-                        };
+                        let idx = cache.get_or_insert(s);
                         // we still need to check if the idx is already stored in our map
                         self.reverse_mapping.insert(idx, s);
                         self.array_builder.append_value(idx).unwrap();
@@ -185,7 +441,10 @@ impl CategoricalChunkedBuilder {
 #[cfg(test)]
 mod test {
     use crate::prelude::*;
-    use crate::{reset_string_cache, toggle_string_cache, SINGLE_LOCK};
+    use crate::{
+        prune_string_cache, reset_string_cache, string_cache_len, string_cache_memory_usage,
+        toggle_string_cache, StringCacheHolder, SINGLE_LOCK,
+    };
 
     #[test]
     fn test_categorical_rev() -> Result<()> {
@@ -215,4 +474,222 @@ mod test {
         assert_eq!(out.categorical_map.unwrap().len(), 2);
         Ok(())
     }
+
+    #[test]
+    fn test_prune_string_cache() -> Result<()> {
+        let _lock = SINGLE_LOCK.lock();
+        toggle_string_cache(true);
+        reset_string_cache();
+
+        let cast = |v: &[&str]| Utf8Chunked::new_from_slice("a", v).cast::<CategoricalType>();
+        let a = cast(&["foo", "bar"])?;
+        let b = cast(&["bar", "baz"])?;
+        assert_eq!(string_cache_len(), 3);
+        assert!(string_cache_memory_usage() >= "foobarbaz".len());
+
+        // `a` and `b` are created in the same generation, so "foo" is kept while `b` lives
+        drop(a);
+        assert_eq!(prune_string_cache(), 0);
+
+        let c = cast(&["baz"])?;
+        drop(b);
+        // "foo" and "bar" are only used in the first generation, which has no live categoricals
+        assert_eq!(prune_string_cache(), 2);
+        assert_eq!(string_cache_len(), 1);
+
+        // a pruned string gets a new index, the index of "baz" is unchanged
+        let d = cast(&["baz", "bar"])?;
+        let c_idx = c.cast::<UInt32Type>()?;
+        let d_idx = d.cast::<UInt32Type>()?;
+        assert_eq!(c_idx.get(0), d_idx.get(0));
+        assert_eq!(d_idx.get(1), Some(3));
+        assert!(c
+            .categorical_map
+            .as_ref()
+            .unwrap()
+            .same_src(d.categorical_map.as_ref().unwrap()));
+
+        // the categories are owned by the categoricals, which stay valid when the cache is
+        // pruned or reset
+        drop(d);
+        assert_eq!(prune_string_cache(), 0);
+        reset_string_cache();
+        assert_eq!(string_cache_len(), 0);
+        let utf8 = c.cast::<Utf8Type>()?;
+        assert_eq!(Vec::from(&utf8), &[Some("baz")]);
+        assert_eq!(
+            c.categorical_map
+                .as_ref()
+                .unwrap()
+                .get(c_idx.get(0).unwrap()),
+            "baz"
+        );
+
+        toggle_string_cache(false);
+        Ok(())
+    }
+
+    #[test]
+    fn test_local_builder() -> Result<()> {
+        use super::{CategoricalChunkedBuilder, RevMapping};
+
+        let _lock = SINGLE_LOCK.lock();
+        toggle_string_cache(true);
+        reset_string_cache();
+
+        let mut builder = CategoricalChunkedBuilder::new_local("a", 4);
+        builder.from_iter(vec![Some("foo"), None, Some("bar"), Some("foo")]);
+        let ca = builder.finish();
+        // the global string cache is not used
+        assert_eq!(string_cache_len(), 0);
+        assert!(matches!(
+            ca.categorical_map.as_deref(),
+            Some(RevMapping::Local(_))
+        ));
+        let idx = ca.cast::<UInt32Type>()?;
+        assert_eq!(Vec::from(&idx), &[Some(0), None, Some(1), Some(0)]);
+
+        toggle_string_cache(false);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cast_chunked_to_categorical() -> Result<()> {
+        let _lock = SINGLE_LOCK.lock();
+        let mut ca = Utf8Chunked::new_from_slice("a", &["a", "b"]);
+        ca.append(&Utf8Chunked::new_from_slice("a", &["b", "c"]));
+
+        let out = ca.cast::<CategoricalType>()?;
+        assert_eq!(out.chunk_id().collect::<Vec<_>>(), &[2, 2]);
+        assert_eq!(out.categorical_map.as_ref().unwrap().len(), 3);
+        let idx = out.cast::<UInt32Type>()?;
+        assert_eq!(Vec::from(&idx), &[Some(0), Some(1), Some(1), Some(2)]);
+        assert_eq!(Vec::from(&out.cast::<Utf8Type>()?), Vec::from(&ca));
+        Ok(())
+    }
+
+    #[test]
+    fn test_cast_to_categorical_with() -> Result<()> {
+        use super::UnknownCategory;
+
+        let _lock = SINGLE_LOCK.lock();
+        let hist = Utf8Chunked::new_from_slice("a", &["x", "y"]).cast::<CategoricalType>()?;
+        let mapping = hist.get_categorical_map().unwrap();
+        let new = Utf8Chunked::new_from_opt_slice("a", &[Some("y"), Some("z"), None]);
+
+        assert!(matches!(
+            new.cast_to_categorical_with(mapping, UnknownCategory::Error),
+            Err(PolarsError::ValueError(_))
+        ));
+
+        let out = new.cast_to_categorical_with(mapping, UnknownCategory::Null)?;
+        assert!(Arc::ptr_eq(out.get_categorical_map().unwrap(), mapping));
+        let idx = out.cast::<UInt32Type>()?;
+        assert_eq!(Vec::from(&idx), &[Some(1), None, None]);
+
+        let out = new.cast_to_categorical_with(mapping, UnknownCategory::Extend)?;
+        let idx = out.cast::<UInt32Type>()?;
+        assert_eq!(Vec::from(&idx), &[Some(1), Some(2), None]);
+        assert!(out.get_categorical_map().unwrap().extends(mapping));
+        // the batches can be combined, as the codes of `hist` are valid in the extended mapping
+        let mut all = hist.clone();
+        all.append(&out);
+        assert_eq!(
+            Vec::from(&all.cast::<Utf8Type>()?),
+            &[Some("x"), Some("y"), Some("y"), Some("z"), None]
+        );
+
+        toggle_string_cache(true);
+        let global = Utf8Chunked::new_from_slice("a", &["x"]).cast::<CategoricalType>()?;
+        let mapping = global.get_categorical_map().unwrap();
+        assert!(new
+            .cast_to_categorical_with(mapping, UnknownCategory::Extend)
+            .is_err());
+        let out = new.cast_to_categorical_with(mapping, UnknownCategory::Null)?;
+        assert_eq!(out.null_count(), 3);
+        toggle_string_cache(false);
+        Ok(())
+    }
+
+    #[test]
+    fn test_string_cache_holder() -> Result<()> {
+        let _lock = SINGLE_LOCK.lock();
+        toggle_string_cache(false);
+        let ca = Utf8Chunked::new_from_slice("a", &["x", "y"]);
+        {
+            let _outer = StringCacheHolder::hold();
+            let a = ca.cast::<CategoricalType>()?;
+            {
+                let _inner = StringCacheHolder::hold();
+            }
+            // the cache outlives the nested holder
+            let b = ca.cast::<CategoricalType>()?;
+            let (a, b) = (
+                a.get_categorical_map().unwrap(),
+                b.get_categorical_map().unwrap(),
+            );
+            assert!(a.same_src(b));
+            assert_eq!(string_cache_len(), 2);
+        }
+        assert_eq!(string_cache_len(), 0);
+        let local = ca.cast::<CategoricalType>()?;
+        assert!(!local
+            .get_categorical_map()
+            .unwrap()
+            .same_src(local.get_categorical_map().unwrap()));
+
+        // a cache that is turned on by the user is kept after the holder is dropped
+        toggle_string_cache(true);
+        {
+            let _holder = StringCacheHolder::hold();
+            ca.cast::<CategoricalType>()?;
+        }
+        assert_eq!(string_cache_len(), 2);
+        toggle_string_cache(false);
+        assert_eq!(string_cache_len(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_arrow_dictionary() -> Result<()> {
+        use super::RevMapping;
+
+        let _lock = SINGLE_LOCK.lock();
+        toggle_string_cache(true);
+        reset_string_cache();
+
+        // the global indexes of the categories of `ca` don't start at zero
+        let _other = Utf8Chunked::new_from_slice("a", &["x", "y"]).cast::<CategoricalType>()?;
+        let values = &[Some("b"), None, Some("a"), Some("b")];
+        let ca = Utf8Chunked::new_from_opt_slice("a", values).cast::<CategoricalType>()?;
+        let out = CategoricalChunked::from_arrow_dictionary("a", &ca.to_arrow_dictionary())?;
+        assert!(matches!(
+            out.categorical_map.as_deref(),
+            Some(RevMapping::Local(_))
+        ));
+        // the dictionary is dense and in the order of the mapping
+        let idx = out.cast::<UInt32Type>()?;
+        assert_eq!(Vec::from(&idx), &[Some(0), None, Some(1), Some(0)]);
+        assert_eq!(Vec::from(&out.cast::<Utf8Type>()?), values);
+        toggle_string_cache(false);
+
+        // local indexes are kept, and a dictionary may extend the dictionary of earlier chunks
+        let a = Utf8Chunked::new_from_slice("a", &["x", "y"]).cast::<CategoricalType>()?;
+        let b = Utf8Chunked::new_from_slice("a", &["z", "x"]).cast::<CategoricalType>()?;
+        let delta = Utf8Chunked::new_from_slice("a", &["x", "y", "z"]).cast::<CategoricalType>()?;
+        let mut chunks = a.to_arrow_dictionary();
+        chunks.extend(delta.to_arrow_dictionary());
+        let out = CategoricalChunked::from_arrow_dictionary("a", &chunks)?;
+        let idx = out.cast::<UInt32Type>()?;
+        assert_eq!(
+            Vec::from(&idx),
+            &[Some(0), Some(1), Some(0), Some(1), Some(2)]
+        );
+        assert_eq!(out.categorical_map.as_ref().unwrap().len(), 3);
+
+        // dictionaries that don't extend each other can't share a mapping
+        chunks.extend(b.to_arrow_dictionary());
+        assert!(CategoricalChunked::from_arrow_dictionary("a", &chunks).is_err());
+        Ok(())
+    }
 }
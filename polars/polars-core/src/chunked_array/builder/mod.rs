@@ -1,13 +1,19 @@
 pub mod categorical;
-pub use self::categorical::CategoricalChunkedBuilder;
+pub use self::categorical::{CategoricalChunkedBuilder, UnknownCategory};
 use crate::{
     prelude::*,
-    utils::{get_iter_capacity, NoNull},
+    utils::{get_iter_capacity, index_to_chunked_index, NoNull},
 };
 pub use arrow::alloc;
-use arrow::array::{ArrayData, PrimitiveArray, PrimitiveBuilder};
-use arrow::array::{ArrayRef, LargeListBuilder};
-use arrow::{array::Array, buffer::Buffer};
+use arrow::array::{ArrayBuilder, ArrayData, PrimitiveArray, PrimitiveBuilder};
+use arrow::array::{
+    ArrayRef, LargeListArray, LargeListBuilder, LargeStringArray, MutableArrayData,
+};
+use arrow::datatypes::ArrowNativeType;
+use arrow::{
+    array::Array,
+    buffer::{Buffer, MutableBuffer},
+};
 use num::Num;
 use polars_arrow::prelude::*;
 use std::borrow::Cow;
@@ -65,14 +71,41 @@ impl BooleanChunkedBuilder {
             field: Field::new(name, DataType::Boolean),
         }
     }
+
+    /// Reserve space for at least `additional` new values.
+    pub fn reserve(&mut self, additional: usize) {
+        self.array_builder.reserve(additional)
+    }
+
+    /// Appends a slice of values. The bits are packed a byte at a time.
+    pub fn append_slice(&mut self, values: &[bool]) {
+        self.array_builder.append_slice(values)
+    }
+
+    /// Appends a slice of values and a slice that is `false` where the value is null.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slices have different lengths.
+    pub fn append_values_with_validity(&mut self, values: &[bool], validity: &[bool]) {
+        self.array_builder.append_values(values, validity)
+    }
 }
 
+/// Builds a [ChunkedArray] by appending values. The values are written to a contiguous buffer, so
+/// that the bulk methods, e.g. [append_slice](PrimitiveChunkedBuilder::append_slice), are a
+/// single copy.
+///
+/// The buffers only grow when more values are appended than the capacity, which is never the case
+/// if the total number of values is [reserved](PrimitiveChunkedBuilder::reserve) upfront.
 pub struct PrimitiveChunkedBuilder<T>
 where
     T: PolarsPrimitiveType,
     T::Native: Default,
 {
-    array_builder: PrimitiveBuilder<T>,
+    values: AlignedVec<T::Native>,
+    validity: BooleanBufferBuilder,
+    null_count: usize,
     field: Field,
 }
 
@@ -84,17 +117,25 @@ where
     /// Appends a value of type `T` into the builder
     #[inline]
     fn append_value(&mut self, v: T::Native) {
-        self.array_builder.append_value(v).unwrap()
+        self.values.push(v);
+        self.validity.append(true);
     }
 
     /// Appends a null slot into the builder
     #[inline]
     fn append_null(&mut self) {
-        self.array_builder.append_null().unwrap()
+        self.values.push(T::Native::default());
+        self.validity.append(false);
+        self.null_count += 1;
     }
 
     fn finish(mut self) -> ChunkedArray<T> {
-        let arr = Arc::new(self.array_builder.finish());
+        let null_bit_buffer = if self.null_count > 0 {
+            Some(self.validity.finish())
+        } else {
+            None
+        };
+        let arr = Arc::new(self.values.into_primitive_array::<T>(null_bit_buffer));
 
         ChunkedArray {
             field: Arc::new(self.field),
@@ -109,18 +150,75 @@ where
 impl<T> PrimitiveChunkedBuilder<T>
 where
     T: PolarsPrimitiveType,
+    T::Native: Default,
 {
     pub fn new(name: &str, capacity: usize) -> Self {
         PrimitiveChunkedBuilder {
-            array_builder: PrimitiveBuilder::<T>::new(capacity),
+            values: AlignedVec::with_capacity_aligned(capacity),
+            validity: BooleanBufferBuilder::new(capacity),
+            null_count: 0,
             field: Field::new(name, T::get_dtype()),
         }
     }
+
+    /// The number of values that can be appended before the buffers grow.
+    pub fn capacity(&self) -> usize {
+        self.values.capacity()
+    }
+
+    /// Reserve space for at least `additional` new values.
+    pub fn reserve(&mut self, additional: usize) {
+        reserve_aligned(&mut self.values, additional);
+        self.validity.reserve(additional);
+    }
+
+    /// Appends a slice of values with a single copy.
+    pub fn append_slice(&mut self, values: &[T::Native]) {
+        self.values.extend_from_slice(values);
+        self.validity.append_n(values.len(), true);
+    }
+
+    /// Appends a slice of optional values.
+    pub fn append_option_slice(&mut self, values: &[Option<T::Native>]) {
+        self.reserve(values.len());
+        for opt_v in values {
+            self.append_option(*opt_v)
+        }
+    }
+
+    /// Appends a slice of values with a single copy and a slice that is `false` where the value
+    /// is null. The validity bits are packed a byte at a time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slices have different lengths.
+    pub fn append_values_with_validity(&mut self, values: &[T::Native], validity: &[bool]) {
+        assert_eq!(values.len(), validity.len());
+        self.values.extend_from_slice(values);
+        self.validity.append_slice(validity);
+        self.null_count += validity.iter().filter(|valid| !**valid).count();
+    }
+}
+
+/// Reserve space for at least `additional` values after the current length of `v`.
+fn reserve_aligned<T: ArrowNativeType>(v: &mut AlignedVec<T>, additional: usize) {
+    // `AlignedVec::reserve` grows relative to the capacity, not the length
+    let remaining = v.capacity() - v.len();
+    if additional > remaining {
+        v.reserve(additional - remaining);
+    }
 }
 
+/// Builds a [Utf8Chunked] by appending values. The values are written to contiguous buffers
+/// that grow in place, so the finished [ChunkedArray] has a single chunk.
+///
+/// The buffers only grow when more values or bytes are appended than the capacity, which is
+/// never the case if these are [reserved](Utf8ChunkedBuilder::reserve) upfront.
 pub struct Utf8ChunkedBuilder {
-    pub builder: LargeStringBuilder,
-    pub capacity: usize,
+    values: AlignedVec<u8>,
+    offsets: AlignedVec<i64>,
+    validity: BooleanBufferBuilder,
+    null_count: usize,
     field: Field,
 }
 
@@ -132,23 +230,60 @@ impl Utf8ChunkedBuilder {
     /// * `capacity` - Number of string elements in the final array.
     /// * `bytes_capacity` - Number of bytes needed to store the string values.
     pub fn new(name: &str, capacity: usize, bytes_capacity: usize) -> Self {
+        let mut offsets = AlignedVec::with_capacity_aligned(capacity + 1);
+        offsets.push(0);
         Utf8ChunkedBuilder {
-            builder: LargeStringBuilder::with_capacity(bytes_capacity, capacity),
-            capacity,
+            values: AlignedVec::with_capacity_aligned(bytes_capacity),
+            offsets,
+            validity: BooleanBufferBuilder::new(capacity),
+            null_count: 0,
             field: Field::new(name, DataType::Utf8),
         }
     }
 
+    /// The number of values and the number of bytes that can be appended before the buffers
+    /// grow.
+    pub fn capacity(&self) -> (usize, usize) {
+        (self.offsets.capacity() - 1, self.values.capacity())
+    }
+
+    /// Reserve space for at least `additional` new values with a total length of
+    /// `bytes_additional` bytes.
+    pub fn reserve(&mut self, additional: usize, bytes_additional: usize) {
+        reserve_aligned(&mut self.values, bytes_additional);
+        reserve_aligned(&mut self.offsets, additional);
+        self.validity.reserve(additional);
+    }
+
+    /// Appends the values of an iterator. `bytes_len` is the total length of the values, which
+    /// is used to reserve the memory exactly once.
+    pub fn append_values_iter<I, S>(&mut self, iter: I, bytes_len: usize)
+    where
+        I: IntoIterator<Item = S>,
+        I::IntoIter: ExactSizeIterator,
+        S: AsRef<str>,
+    {
+        let iter = iter.into_iter();
+        self.reserve(iter.len(), bytes_len);
+        for v in iter {
+            self.append_value(v)
+        }
+    }
+
     /// Appends a value of type `T` into the builder
     #[inline]
     pub fn append_value<S: AsRef<str>>(&mut self, v: S) {
-        self.builder.append_value(v.as_ref()).unwrap();
+        self.values.extend_from_slice(v.as_ref().as_bytes());
+        self.offsets.push(self.values.len() as i64);
+        self.validity.append(true);
     }
 
     /// Appends a null slot into the builder
     #[inline]
     pub fn append_null(&mut self) {
-        self.builder.append_null().unwrap();
+        self.offsets.push(self.values.len() as i64);
+        self.validity.append(false);
+        self.null_count += 1;
     }
 
     #[inline]
@@ -160,7 +295,15 @@ impl Utf8ChunkedBuilder {
     }
 
     pub fn finish(mut self) -> Utf8Chunked {
-        let arr = Arc::new(self.builder.finish());
+        let len = self.offsets.len() - 1;
+        let mut data = ArrayData::builder(ArrowDataType::LargeUtf8)
+            .len(len)
+            .add_buffer(self.offsets.into_arrow_buffer())
+            .add_buffer(self.values.into_arrow_buffer());
+        if self.null_count > 0 {
+            data = data.null_bit_buffer(self.validity.finish());
+        }
+        let arr = Arc::new(LargeStringArray::from(data.build()));
         ChunkedArray {
             field: Arc::new(self.field),
             chunks: vec![arr],
@@ -558,6 +701,69 @@ impl ListBuilderTrait for ListBooleanChunkedBuilder {
     }
 }
 
+/// Builds a ListChunked of which every list is a concatenation of slices of the same `values`.
+///
+/// The slices are copied in bulk from the arrow buffers of `values`, instead of value by value.
+/// This works for `values` of any data type, including lists.
+pub struct ListSlicesChunkedBuilder<'a> {
+    values: MutableArrayData<'a>,
+    chunk_lengths: Vec<usize>,
+    offsets: MutableBuffer,
+    length_so_far: i64,
+    len: usize,
+    name: String,
+}
+
+impl<'a> ListSlicesChunkedBuilder<'a> {
+    pub fn new(name: &str, values: &'a Series, capacity: usize) -> Self {
+        let arrays = values.chunks().iter().map(|arr| arr.data()).collect();
+        let mut offsets = MutableBuffer::new((capacity + 1) * std::mem::size_of::<i64>());
+        offsets.push(0i64);
+        ListSlicesChunkedBuilder {
+            values: MutableArrayData::new(arrays, false, values.len()),
+            chunk_lengths: values.chunk_lengths().collect(),
+            offsets,
+            length_so_far: 0,
+            len: 0,
+            name: name.to_string(),
+        }
+    }
+
+    /// Append a single list that consists of the `(offset, length)` slices of the values.
+    ///
+    /// # Panics
+    /// Panics if a slice is out of bounds.
+    pub fn append_series_slices<I: IntoIterator<Item = (usize, usize)>>(&mut self, slices: I) {
+        for (mut offset, mut len) in slices {
+            // a slice may span multiple chunks
+            while len > 0 {
+                let (chunk_idx, idx) =
+                    index_to_chunked_index(self.chunk_lengths.iter().copied(), offset);
+                let chunk_len = self.chunk_lengths[chunk_idx];
+                let n = std::cmp::min(len, chunk_len - idx);
+                self.values.extend(chunk_idx, idx, idx + n);
+                self.length_so_far += n as i64;
+                offset += n;
+                len -= n;
+            }
+        }
+        self.offsets.push(self.length_so_far);
+        self.len += 1;
+    }
+
+    pub fn finish(self) -> ListChunked {
+        let values = self.values.freeze();
+        let field = arrow::datatypes::Field::new("item", values.data_type().clone(), true);
+        let data = ArrayData::builder(ArrowDataType::LargeList(Box::new(field)))
+            .len(self.len)
+            .add_buffer(self.offsets.into())
+            .add_child_data(values)
+            .build();
+        let arr = Arc::new(LargeListArray::from(data)) as ArrayRef;
+        ListChunked::new_from_chunks(&self.name, vec![arr])
+    }
+}
+
 pub fn get_list_builder(
     dt: &DataType,
     value_capacity: usize,
@@ -610,6 +816,74 @@ mod test {
         assert_eq!(Vec::from(&ca), values);
     }
 
+    #[test]
+    fn test_primitive_builder_bulk() {
+        let mut builder = PrimitiveChunkedBuilder::<Float64Type>::new("foo", 0);
+        // reserving the total length upfront means the buffers don't grow
+        builder.reserve(1000);
+        let capacity = builder.capacity();
+        assert!(capacity >= 1000);
+
+        let values = (0..500).map(|v| v as f64).collect::<Vec<_>>();
+        let validity = (0..490).map(|i| i % 10 != 0).collect::<Vec<_>>();
+        builder.append_slice(&values);
+        builder.append_values_with_validity(&values[..490], &validity);
+        builder.append_option_slice(&[Some(1.0), None]);
+        builder.append_value(2.0);
+        builder.append_null();
+        builder.append_slice(&values[..6]);
+        assert_eq!(builder.capacity(), capacity);
+
+        let ca = builder.finish();
+        assert_eq!(ca.len(), 1000);
+        assert_eq!(ca.null_count(), 49 + 1 + 1);
+        assert_eq!(ca.get(10), Some(10.0));
+        assert_eq!(ca.get(500), None);
+        assert_eq!(ca.get(501), Some(1.0));
+        assert_eq!(
+            &Vec::from(&ca)[990..],
+            &[
+                Some(1.0),
+                None,
+                Some(2.0),
+                None,
+                Some(0.0),
+                Some(1.0),
+                Some(2.0),
+                Some(3.0),
+                Some(4.0),
+                Some(5.0)
+            ]
+        );
+
+        let mut builder = BooleanChunkedBuilder::new("foo", 0);
+        builder.reserve(20);
+        builder.append_slice(&[true, false, true]);
+        builder.append_values_with_validity(&[true, true], &[false, true]);
+        let ca = builder.finish();
+        assert_eq!(
+            Vec::from(&ca),
+            &[Some(true), Some(false), Some(true), None, Some(true)]
+        );
+
+        let mut builder = Utf8ChunkedBuilder::new("foo", 2, 2);
+        builder.append_value("a");
+        // the buffers grow in place, which doesn't change the number of chunks
+        builder.append_values_iter(vec!["bb", "ccc"], 5);
+        builder.append_null();
+        builder.reserve(1, 3);
+        let capacity = builder.capacity();
+        assert!(capacity.0 >= 5 && capacity.1 >= 9);
+        builder.append_value("ddd");
+        assert_eq!(builder.capacity(), capacity);
+        let ca = builder.finish();
+        assert_eq!(ca.chunks().len(), 1);
+        assert_eq!(
+            Vec::from(&ca),
+            &[Some("a"), Some("bb"), Some("ccc"), None, Some("ddd")]
+        );
+    }
+
     #[test]
     fn test_list_builder() {
         let values_builder = PrimitiveBuilder::<Int32Type>::new(10);
@@ -150,6 +150,23 @@ where
             (Duration(_), Int64) => unsafe {
                 cast_from_dtype!(self, transmute_array_from_dtype, Int64.to_arrow())
             },
+            // the values of the Int64 are in the time unit of the duration
+            (Int64, Duration(_)) => unsafe {
+                cast_from_dtype!(self, transmute_array_from_dtype, N::get_dtype().to_arrow())
+            },
+            // dates and their physical type share the same memory layout, so casting between them
+            // doesn't need to touch the values. This makes `to_physical_repr` free.
+            (Date32, Int32) | (Int32, Date32) | (Date64, Int64) | (Int64, Date64) => unsafe {
+                cast_from_dtype!(self, transmute_array_from_dtype, N::get_dtype().to_arrow())
+            },
+            (Duration(TimeUnit::Millisecond), Duration(TimeUnit::Nanosecond)) => {
+                let ca: Int64Chunked = self.cast()?;
+                (ca * 1_000_000i64).cast::<N>()
+            }
+            (Duration(TimeUnit::Nanosecond), Duration(TimeUnit::Millisecond)) => {
+                let ca: Int64Chunked = self.cast()?;
+                (ca / 1_000_000i64).cast::<N>()
+            }
             // paths not supported by arrow kernel
             // to float32
             (Duration(_), Float32) | (Date32, Float32) | (Date64, Float32)
@@ -192,8 +209,12 @@ impl ChunkCast for Utf8Chunked {
             DataType::Categorical => {
                 let iter = self.into_iter();
                 let mut builder = CategoricalChunkedBuilder::new(self.name(), self.len());
+                // all chunks share a single mapping, the output keeps the chunks of the input
                 builder.from_iter(iter);
-                let ca = builder.finish();
+                let mut ca = builder.finish();
+                if self.chunks.len() > 1 && !self.is_empty() {
+                    ca = ca.split_chunks(self.chunk_id().filter(|len| *len > 0));
+                }
                 let ca = unsafe { std::mem::transmute(ca) };
                 Ok(ca)
             }
@@ -1,4 +1,5 @@
 use crate::utils::align_chunks_binary;
+use crate::vector_hasher::list_values_eq;
 use crate::{prelude::*, utils::NoNull};
 use arrow::compute::*;
 use arrow::{
@@ -21,7 +22,8 @@ where
         rhs: &ChunkedArray<T>,
         operator: impl Fn(&PrimitiveArray<T>, &PrimitiveArray<T>) -> arrow::error::Result<BooleanArray>,
     ) -> Result<BooleanChunked> {
-        let chunks = self
+        let (lhs, rhs) = align_chunks_binary(self, rhs);
+        let chunks = lhs
             .downcast_iter()
             .zip(rhs.downcast_iter())
             .map(|(left, right)| {
@@ -74,7 +76,7 @@ where
             }
         }
         // same length
-        else if self.chunk_id().zip(rhs.chunk_id()).all(|(l, r)| l == r) {
+        else if self.len() == rhs.len() {
             // should not fail if arrays are equal
             self.comparison(rhs, comparison::eq)
                 .expect("should not fail.")
@@ -93,7 +95,7 @@ where
             }
         }
         // same length
-        else if self.chunk_id().zip(rhs.chunk_id()).all(|(l, r)| l == r) {
+        else if self.len() == rhs.len() {
             self.comparison(rhs, comparison::neq)
                 .expect("should not fail.")
         } else {
@@ -111,7 +113,7 @@ where
             }
         }
         // same length
-        else if self.chunk_id().zip(rhs.chunk_id()).all(|(l, r)| l == r) {
+        else if self.len() == rhs.len() {
             self.comparison(rhs, comparison::gt)
                 .expect("should not fail.")
         } else {
@@ -129,7 +131,7 @@ where
             }
         }
         // same length
-        else if self.chunk_id().zip(rhs.chunk_id()).all(|(l, r)| l == r) {
+        else if self.len() == rhs.len() {
             self.comparison(rhs, comparison::gt_eq)
                 .expect("should not fail.")
         } else {
@@ -147,7 +149,7 @@ where
             }
         }
         // same length
-        else if self.chunk_id().zip(rhs.chunk_id()).all(|(l, r)| l == r) {
+        else if self.len() == rhs.len() {
             self.comparison(rhs, comparison::lt)
                 .expect("should not fail.")
         } else {
@@ -165,7 +167,7 @@ where
             }
         }
         // same length
-        else if self.chunk_id().zip(rhs.chunk_id()).all(|(l, r)| l == r) {
+        else if self.len() == rhs.len() {
             self.comparison(rhs, comparison::lt_eq)
                 .expect("should not fail.")
         } else {
@@ -282,7 +284,8 @@ impl Utf8Chunked {
         rhs: &Utf8Chunked,
         operator: impl Fn(&LargeStringArray, &LargeStringArray) -> arrow::error::Result<BooleanArray>,
     ) -> Result<BooleanChunked> {
-        let chunks = self
+        let (lhs, rhs) = align_chunks_binary(self, rhs);
+        let chunks = lhs
             .chunks
             .iter()
             .zip(&rhs.chunks)
@@ -323,7 +326,7 @@ impl ChunkCompare<&Utf8Chunked> for Utf8Chunked {
             }
         }
         // same length
-        else if self.chunk_id().zip(rhs.chunk_id()).all(|(l, r)| l == r) {
+        else if self.len() == rhs.len() {
             self.comparison(rhs, eq_utf8).expect("should not fail")
         } else {
             apply_operand_on_chunkedarray_by_iter!(self, rhs, ==)
@@ -340,7 +343,7 @@ impl ChunkCompare<&Utf8Chunked> for Utf8Chunked {
             }
         }
         // same length
-        else if self.chunk_id().zip(rhs.chunk_id()).all(|(l, r)| l == r) {
+        else if self.len() == rhs.len() {
             self.comparison(rhs, neq_utf8).expect("should not fail")
         } else {
             apply_operand_on_chunkedarray_by_iter!(self, rhs, !=)
@@ -357,7 +360,7 @@ impl ChunkCompare<&Utf8Chunked> for Utf8Chunked {
             }
         }
         // same length
-        else if self.chunk_id().zip(rhs.chunk_id()).all(|(l, r)| l == r) {
+        else if self.len() == rhs.len() {
             self.comparison(rhs, gt_utf8).expect("should not fail")
         } else {
             apply_operand_on_chunkedarray_by_iter!(self, rhs, >)
@@ -374,7 +377,7 @@ impl ChunkCompare<&Utf8Chunked> for Utf8Chunked {
             }
         }
         // same length
-        else if self.chunk_id().zip(rhs.chunk_id()).all(|(l, r)| l == r) {
+        else if self.len() == rhs.len() {
             self.comparison(rhs, gt_eq_utf8).expect("should not fail")
         } else {
             apply_operand_on_chunkedarray_by_iter!(self, rhs, >=)
@@ -391,7 +394,7 @@ impl ChunkCompare<&Utf8Chunked> for Utf8Chunked {
             }
         }
         // same length
-        else if self.chunk_id().zip(rhs.chunk_id()).all(|(l, r)| l == r) {
+        else if self.len() == rhs.len() {
             self.comparison(rhs, lt_utf8).expect("should not fail")
         } else {
             apply_operand_on_chunkedarray_by_iter!(self, rhs, <)
@@ -408,7 +411,7 @@ impl ChunkCompare<&Utf8Chunked> for Utf8Chunked {
             }
         }
         // same length
-        else if self.chunk_id().zip(rhs.chunk_id()).all(|(l, r)| l == r) {
+        else if self.len() == rhs.len() {
             self.comparison(rhs, lt_eq_utf8).expect("should not fail")
         } else {
             apply_operand_on_chunkedarray_by_iter!(self, rhs, <=)
@@ -533,7 +536,15 @@ macro_rules! impl_cmp_list {
 
 impl ChunkCompare<&ListChunked> for ListChunked {
     fn eq_missing(&self, rhs: &ListChunked) -> BooleanChunked {
-        impl_cmp_list!(self, rhs, series_equal_missing)
+        // a null list is only equal to another null list
+        self.into_iter()
+            .zip(rhs.into_iter())
+            .map(|(opt_left, opt_right)| match (opt_left, opt_right) {
+                (None, None) => true,
+                (Some(left), Some(right)) => list_values_eq(&left, &right),
+                _ => false,
+            })
+            .collect()
     }
 
     fn eq(&self, rhs: &ListChunked) -> BooleanChunked {
@@ -729,7 +740,18 @@ impl ChunkEqualElement for Utf8Chunked {
     }
 }
 
-impl ChunkEqualElement for ListChunked {}
+impl ChunkEqualElement for ListChunked {
+    unsafe fn equal_element(&self, idx_self: usize, idx_other: usize, other: &Series) -> bool {
+        let ca_other = other.as_ref().as_ref();
+        debug_assert!(self.dtype() == other.dtype());
+        let ca_other = &*(ca_other as *const ListChunked);
+        match (self.get(idx_self), ca_other.get(idx_other)) {
+            (None, None) => true,
+            (Some(l), Some(r)) => list_values_eq(&l, &r),
+            _ => false,
+        }
+    }
+}
 impl ChunkEqualElement for CategoricalChunked {
     unsafe fn equal_element(&self, idx_self: usize, idx_other: usize, other: &Series) -> bool {
         let ca_other = other.as_ref().as_ref();
@@ -17,7 +17,8 @@ use crate::prelude::AlignedVec;
 use crate::utils::CustomIterTools;
 use arrow::array::{Array, ArrayData, ArrayRef, PrimitiveArray};
 use arrow::datatypes::{
-    Float32Type, Int16Type, Int32Type, Int64Type, Int8Type, UInt16Type, UInt32Type, UInt64Type,
+    Date32Type, Date64Type, DurationMillisecondType, DurationNanosecondType, Float32Type,
+    Int16Type, Int32Type, Int64Type, Int8Type, TimeUnit, UInt16Type, UInt32Type, UInt64Type,
     UInt8Type,
 };
 use num::{Float, NumCast};
@@ -61,6 +62,10 @@ where
         Int32 => transmute_array::<_, Int32Type>(arr),
         Int16 => transmute_array::<_, Int16Type>(arr),
         Int8 => transmute_array::<_, Int8Type>(arr),
+        Duration(TimeUnit::Nanosecond) => transmute_array::<_, DurationNanosecondType>(arr),
+        Duration(TimeUnit::Millisecond) => transmute_array::<_, DurationMillisecondType>(arr),
+        Date32 => transmute_array::<_, Date32Type>(arr),
+        Date64 => transmute_array::<_, Date64Type>(arr),
         _ => todo!(),
     }
 }
@@ -1,7 +1,76 @@
 use crate::prelude::Arc;
-use arrow::array::{ArrayRef, LargeStringArray, UInt32Array};
+use arrow::array::{Array, ArrayRef, LargeStringArray, LargeStringBuilder, UInt32Array};
 
+/// The number of bytes of the string values. Computed from the offsets, the values are not read.
 pub(crate) fn string_lengths(array: &LargeStringArray) -> ArrayRef {
-    let array: UInt32Array = array.iter().map(|v| v.map(|v| v.len() as u32)).collect();
+    let offsets = array.value_offsets();
+    let lengths = offsets.windows(2).map(|w| (w[1] - w[0]) as u32);
+    let array: UInt32Array = if array.null_count() == 0 {
+        lengths.map(Some).collect()
+    } else {
+        lengths
+            .enumerate()
+            .map(|(i, len)| if array.is_valid(i) { Some(len) } else { None })
+            .collect()
+    };
     Arc::new(array)
 }
+
+/// The number of unicode scalar values of the string values.
+pub(crate) fn string_n_chars(array: &LargeStringArray) -> ArrayRef {
+    let array: UInt32Array = array
+        .iter()
+        .map(|v| v.map(|v| v.chars().count() as u32))
+        .collect();
+    Arc::new(array)
+}
+
+/// The largest char boundary of `s` that is not larger than `idx`.
+fn floor_char_boundary(s: &str, idx: usize) -> usize {
+    let mut idx = std::cmp::min(idx, s.len());
+    while !s.is_char_boundary(idx) {
+        idx -= 1;
+    }
+    idx
+}
+
+/// The byte range of the substring of `s` that starts at `offset` and has at most `length` bytes.
+/// A negative `offset` counts from the end. Both ends are rounded down to a char boundary.
+fn substring_range(s: &str, offset: i64, length: Option<u64>) -> (usize, usize) {
+    let len = s.len();
+    let start = if offset >= 0 {
+        std::cmp::min(offset as usize, len)
+    } else {
+        len.saturating_sub(offset.unsigned_abs() as usize)
+    };
+    let end = match length {
+        Some(length) => std::cmp::min(start.saturating_add(length as usize), len),
+        None => len,
+    };
+    (floor_char_boundary(s, start), floor_char_boundary(s, end))
+}
+
+/// Take a substring of the string values, see [substring_range]. The values buffer is allocated
+/// once, with the size determined in a first pass.
+pub(crate) fn string_slice(array: &LargeStringArray, offset: i64, length: Option<u64>) -> ArrayRef {
+    let values_size = array
+        .iter()
+        .flatten()
+        .map(|s| {
+            let (start, end) = substring_range(s, offset, length);
+            end - start
+        })
+        .sum();
+
+    let mut builder = LargeStringBuilder::with_capacity(values_size, array.len());
+    for opt_s in array.iter() {
+        match opt_s {
+            Some(s) => {
+                let (start, end) = substring_range(s, offset, length);
+                builder.append_value(&s[start..end]).unwrap()
+            }
+            None => builder.append_null().unwrap(),
+        }
+    }
+    Arc::new(builder.finish())
+}
@@ -3,17 +3,19 @@ use crate::utils::arrow::util::bit_util::unset_bit;
 use crate::utils::CustomIterTools;
 use arrow::array::{
     Array, ArrayData, BooleanArray, LargeStringArray, LargeStringBuilder, PrimitiveArray,
-    UInt32Array,
 };
 use arrow::buffer::{Buffer, MutableBuffer};
 use polars_arrow::buffer::IsValid;
 use std::mem;
 use std::sync::Arc;
 
-/// Take kernel for single chunk without nulls and arrow array as index.
+/// Take kernel for single chunk with nulls and arrow array as index.
+///
+/// The values and the output validity, determined by both the null indices and the null values,
+/// are computed in a single pass over the indices.
 pub(crate) unsafe fn take_primitive_unchecked<T: PolarsNumericType>(
     arr: &PrimitiveArray<T>,
-    indices: &UInt32Array,
+    indices: &IdxArr,
 ) -> Arc<PrimitiveArray<T>> {
     let array_values = arr.values();
     let index_values = indices.values();
@@ -23,50 +25,45 @@ pub(crate) unsafe fn take_primitive_unchecked<T: PolarsNumericType>(
         .expect("null buffer should be there");
     let values_offset = arr.offset();
     let indices_offset = indices.offset();
+    let validity_indices = indices.data_ref().null_buffer();
 
-    // first take the values, these are always needed
-    let values: AlignedVec<T::Native> = index_values
-        .iter()
-        .map(|idx| *array_values.get_unchecked(*idx as usize))
-        .collect_trusted();
-
-    // the validity buffer we will fill with all valid. And we unset the ones that are null
-    // in later checks
-    // this is in the assumption that most values will be valid.
-    // Maybe we could add another branch based on the null count
-    let num_bytes = indices.len() * std::mem::size_of::<i32>() / 8;
+    // the validity buffer is filled with all valid and the nulls are unset,
+    // in the assumption that most values will be valid.
+    let num_bytes = (indices.len() + 7) / 8;
     let mut validity = MutableBuffer::new(num_bytes).with_bitset(num_bytes, true);
     let validity_slice = validity.as_slice_mut();
 
-    let arr = if let Some(validity_indices) = indices.data_ref().null_buffer() {
-        index_values.iter().enumerate().for_each(|(i, idx)| {
+    let values: AlignedVec<T::Native> = index_values
+        .iter()
+        .enumerate()
+        .map(|(i, idx)| {
             // i is iteration count
             // idx is the index that we take from the values array.
             let idx = *idx as usize;
-            if validity_indices.is_null_unchecked(i + indices_offset)
-                || validity_values.is_null_unchecked(idx + values_offset)
-            {
-                unset_bit(validity_slice, i)
-            }
-        });
-        values.into_primitive_array(Some(validity.into()))
-    } else {
-        index_values.iter().enumerate().for_each(|(i, idx)| {
-            let idx = *idx as usize;
-            if validity_values.is_null_unchecked(idx + values_offset) {
-                unset_bit(validity_slice, i)
+            let idx_is_null = match validity_indices {
+                Some(buf) => buf.is_null_unchecked(i + indices_offset),
+                None => false,
+            };
+            if idx_is_null {
+                unset_bit(validity_slice, i);
+                // the index of a null may be anything, so the value isn't read
+                T::Native::default()
+            } else {
+                if validity_values.is_null_unchecked(idx + values_offset) {
+                    unset_bit(validity_slice, i)
+                }
+                *array_values.get_unchecked(idx)
             }
-        });
-        values.into_primitive_array(Some(validity.into()))
-    };
+        })
+        .collect_trusted();
 
-    Arc::new(arr)
+    Arc::new(values.into_primitive_array(Some(validity.into())))
 }
 
 /// Take kernel for single chunk without nulls and arrow array as index.
 pub(crate) unsafe fn take_no_null_primitive_unchecked<T: PolarsNumericType>(
     arr: &PrimitiveArray<T>,
-    indices: &UInt32Array,
+    indices: &IdxArr,
 ) -> Arc<PrimitiveArray<T>> {
     assert_eq!(arr.null_count(), 0);
 
@@ -494,10 +491,7 @@ pub(crate) fn take_utf8_iter<I: IntoIterator<Item = usize>>(
     Arc::new(iter.collect())
 }
 
-pub(crate) unsafe fn take_utf8(
-    arr: &LargeStringArray,
-    indices: &UInt32Array,
-) -> Arc<LargeStringArray> {
+pub(crate) unsafe fn take_utf8(arr: &LargeStringArray, indices: &IdxArr) -> Arc<LargeStringArray> {
     let data_len = indices.len();
 
     let offset_len_in_bytes = (data_len + 1) * mem::size_of::<i64>();
@@ -638,13 +632,13 @@ mod test {
     fn test_utf8_kernel() {
         let s = LargeStringArray::from(vec![Some("foo"), None, Some("bar")]);
         unsafe {
-            let out = take_utf8(&s, &UInt32Array::from(vec![1, 2]));
+            let out = take_utf8(&s, &IdxArr::from(vec![1, 2]));
             assert!(out.is_null(0));
             assert!(out.is_valid(1));
-            let out = take_utf8(&s, &UInt32Array::from(vec![None, Some(2)]));
+            let out = take_utf8(&s, &IdxArr::from(vec![None, Some(2)]));
             assert!(out.is_null(0));
             assert!(out.is_valid(1));
-            let out = take_utf8(&s, &UInt32Array::from(vec![None, None]));
+            let out = take_utf8(&s, &IdxArr::from(vec![None, None]));
             assert!(out.is_null(0));
             assert!(out.is_null(1));
         }
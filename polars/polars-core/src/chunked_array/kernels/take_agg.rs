@@ -3,17 +3,19 @@ use crate::prelude::*;
 use arrow::array::{Array, PrimitiveArray};
 use polars_arrow::buffer::IsValid;
 
-/// Take kernel for single chunk without nulls and an iterator as index.
+/// Take kernel for single chunk without nulls and an iterator as index. The accumulator may
+/// have a different type than the values.
 pub(crate) unsafe fn take_agg_no_null_primitive_iter_unchecked<
     T: PolarsNumericType,
+    TOut,
     I: IntoIterator<Item = usize>,
-    F: Fn(T::Native, T::Native) -> T::Native,
+    F: Fn(TOut, T::Native) -> TOut,
 >(
     arr: &PrimitiveArray<T>,
     indices: I,
     f: F,
-    init: T::Native,
-) -> T::Native {
+    init: TOut,
+) -> TOut {
     debug_assert_eq!(arr.null_count(), 0);
 
     let array_values = arr.values();
@@ -62,20 +64,24 @@ pub(crate) unsafe fn take_agg_primitive_iter_unchecked<
     }
 }
 
-/// Take kernel for single chunk and an iterator as index.
+/// Take kernel for single chunk and an iterator as index. The accumulator may have a different
+/// type than the values.
+///
+/// Returns `None` if all the taken values are null.
 ///
 /// # Panics
 /// panics if the array does not have nulls
 pub(crate) unsafe fn take_agg_primitive_iter_unchecked_count_nulls<
     T: PolarsNumericType,
+    TOut,
     I: IntoIterator<Item = usize>,
-    F: Fn(T::Native, T::Native) -> T::Native,
+    F: Fn(TOut, T::Native) -> TOut,
 >(
     arr: &PrimitiveArray<T>,
     indices: I,
     f: F,
-    init: T::Native,
-) -> Option<(T::Native, u32)> {
+    init: TOut,
+) -> Option<(TOut, u32)> {
     if arr.null_count() == arr.len() {
         return None;
     }
@@ -88,7 +94,9 @@ pub(crate) unsafe fn take_agg_primitive_iter_unchecked_count_nulls<
         .expect("null buffer should be there");
 
     let mut null_count = 0;
+    let mut len = 0;
     let out = indices.into_iter().fold(init, |acc, idx| {
+        len += 1;
         if buf.is_valid_unchecked(idx + offset) {
             f(acc, *array_values.get_unchecked(idx))
         } else {
@@ -96,7 +104,7 @@ pub(crate) unsafe fn take_agg_primitive_iter_unchecked_count_nulls<
             acc
         }
     });
-    if out == init {
+    if null_count == len {
         None
     } else {
         Some((out, null_count))
@@ -8,7 +8,7 @@ use arrow::{
         UInt64Array, UInt8Array,
     },
     buffer::Buffer,
-    datatypes::TimeUnit,
+    datatypes::{TimeUnit, ToByteSlice},
 };
 use itertools::Itertools;
 use std::convert::TryFrom;
@@ -28,6 +28,9 @@ pub mod iterator;
 pub mod kernels;
 #[cfg(feature = "ndarray")]
 mod ndarray;
+#[cfg(feature = "ndarray")]
+pub use self::ndarray::IndexOrder;
+mod statistics;
 
 #[cfg(feature = "object")]
 #[cfg_attr(docsrs, doc(cfg(feature = "object")))]
@@ -38,6 +41,9 @@ mod random;
 #[cfg(feature = "strings")]
 #[cfg_attr(docsrs, doc(cfg(feature = "strings")))]
 pub mod strings;
+#[cfg(feature = "dtype-struct")]
+#[cfg_attr(docsrs, doc(cfg(feature = "dtype-struct")))]
+pub mod struct_;
 #[cfg(feature = "temporal")]
 #[cfg_attr(docsrs, doc(cfg(feature = "temporal")))]
 pub mod temporal;
@@ -50,7 +56,8 @@ use arrow::array::{
 };
 
 use crate::chunked_array::builder::categorical::RevMapping;
-use crate::utils::{slice_offsets, CustomIterTools};
+pub use crate::chunked_array::statistics::{ChunkMinMax, StatValue, Statistics};
+use crate::utils::{slice_offsets, split_ca_at, CustomIterTools};
 use arrow::util::bit_util::{get_bit, round_upto_power_of_2};
 use polars_arrow::array::ValueSize;
 use std::mem;
@@ -58,6 +65,24 @@ use std::ops::{Deref, DerefMut};
 
 pub type ChunkIdIter<'a> = std::iter::Map<std::slice::Iter<'a, ArrayRef>, fn(&ArrayRef) -> usize>;
 
+/// Sum the lengths of the buffers of `data` and its children. Buffers of which the pointer is
+/// already in `seen` are skipped.
+pub(crate) fn estimated_array_data_size(
+    data: &ArrayData,
+    seen: &mut PlHashSet<*const u8>,
+) -> usize {
+    let mut size = 0;
+    for buffer in data.buffers().iter().chain(data.null_buffer()) {
+        if seen.insert(buffer.as_ptr()) {
+            size += buffer.len();
+        }
+    }
+    for child in data.child_data() {
+        size += estimated_array_data_size(child, seen);
+    }
+    size
+}
+
 /// # ChunkedArray
 ///
 /// Every Series contains a `ChunkedArray<T>`. Unlike Series, ChunkedArray's are typed. This allows
@@ -156,15 +181,15 @@ pub struct ChunkedArray<T> {
     // first bit: sorted
     // second_bit: sorted reverse
     pub(crate) bit_settings: u8,
+    /// lazily computed statistics, reset on mutation
+    pub(crate) stats: Arc<Statistics>,
 }
 
 impl<T> ChunkedArray<T> {
-    #[cfg(feature = "asof_join")]
     pub(crate) fn is_sorted(&self) -> bool {
         self.bit_settings & 1 != 0
     }
 
-    #[cfg(feature = "asof_join")]
     pub(crate) fn is_sorted_reverse(&self) -> bool {
         self.bit_settings & 1 << 1 != 0
     }
@@ -177,6 +202,24 @@ impl<T> ChunkedArray<T> {
         }
     }
 
+    /// Get the lazily computed statistics of this ChunkedArray.
+    pub fn statistics(&self) -> &Statistics {
+        &self.stats
+    }
+
+    /// Invalidate the cached statistics and sorted flags. Must be called by every operation
+    /// that mutates the data in place.
+    pub(crate) fn reset_statistics(&mut self) {
+        self.stats = Default::default();
+        self.bit_settings = 0;
+    }
+
+    /// Invalidate the statistics that depend on the chunk layout or the order of the data.
+    /// Must be called by every operation that reorders the data or changes the chunks in place.
+    pub(crate) fn reset_chunk_statistics(&mut self) {
+        self.stats = Arc::new(self.stats.without_chunks());
+    }
+
     /// Get Arrow ArrayData
     pub fn array_data(&self) -> Vec<&ArrayData> {
         self.chunks.iter().map(|arr| arr.data()).collect()
@@ -187,6 +230,32 @@ impl<T> ChunkedArray<T> {
         self.categorical_map.as_ref()
     }
 
+    /// Estimate the number of bytes of this ChunkedArray: the value buffers, validity bitmaps
+    /// and offsets of all chunks, and the mapping to the string values of a categorical array.
+    ///
+    /// A buffer that is shared by multiple chunks, e.g. after slicing, is counted once and in
+    /// full. Buffers that are shared with other arrays are not deduplicated.
+    pub fn estimated_size(&self) -> usize {
+        #[cfg(feature = "object")]
+        if let DataType::Object(_) = self.dtype() {
+            return self
+                .chunks
+                .iter()
+                .map(|arr| arr.get_buffer_memory_size())
+                .sum();
+        }
+        let mut seen = PlHashSet::default();
+        let mut size = self
+            .chunks
+            .iter()
+            .map(|arr| estimated_array_data_size(arr.data(), &mut seen))
+            .sum::<usize>();
+        if let Some(map) = &self.categorical_map {
+            size += map.estimated_size();
+        }
+        size
+    }
+
     /// Get the index of the first non null value in this ChunkedArray.
     pub fn first_non_null(&self) -> Option<usize> {
         if self.null_count() == self.len() {
@@ -235,6 +304,7 @@ impl<T> ChunkedArray<T> {
                 arrow::compute::concat(self.chunks.iter().map(|a| &**a).collect_vec().as_slice())
                     .unwrap(),
             ];
+        self.reset_chunk_statistics();
     }
 
     /// Unpack a Series to the same physical type.
@@ -341,6 +411,7 @@ impl<T> ChunkedArray<T> {
         }
         if self.field.data_type() == other.data_type() {
             self.chunks.push(other);
+            self.reset_statistics();
             Ok(())
         } else {
             Err(PolarsError::DataTypeMisMatch(
@@ -365,6 +436,20 @@ impl<T> ChunkedArray<T> {
         }
     }
 
+    /// Split the chunks at the boundaries given by `chunk_lengths`. Every chunk boundary of `self`
+    /// should also be a boundary of `chunk_lengths`. This is zero-copy; the chunks are sliced.
+    pub(crate) fn split_chunks<I>(&self, chunk_lengths: I) -> Self
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        // every part is a slice of a single chunk
+        let chunks = split_ca_at(self, chunk_lengths)
+            .into_iter()
+            .flat_map(|part| part.chunks)
+            .collect();
+        self.copy_with_chunks(chunks)
+    }
+
     /// Slice the array. The chunks are reallocated the underlying data slices are zero copy.
     ///
     /// When offset is negative it will be counted from the end of the array.
@@ -454,10 +539,17 @@ impl<T> ChunkedArray<T> {
         Self: std::marker::Sized,
     {
         if matches!(self.dtype(), DataType::Categorical) && !self.is_empty() {
-            assert!(Arc::ptr_eq(
-                self.categorical_map.as_ref().unwrap(),
-                other.categorical_map.as_ref().unwrap()
-            ));
+            let map = self.categorical_map.as_ref().unwrap();
+            let other_map = other.categorical_map.as_ref().unwrap();
+            // Local mappings can be combined if one extends the other, e.g. the dictionaries
+            // of the record batches of an IPC file
+            if !Arc::ptr_eq(map, other_map) {
+                if other_map.extends(map) {
+                    self.categorical_map = Some(other_map.clone());
+                } else {
+                    assert!(map.extends(other_map));
+                }
+            }
         }
 
         // replace an empty array
@@ -466,6 +558,7 @@ impl<T> ChunkedArray<T> {
         } else {
             self.chunks.extend_from_slice(&other.chunks);
         }
+        self.reset_statistics();
     }
 
     /// Name of the ChunkedArray.
@@ -484,44 +577,6 @@ impl<T> ChunkedArray<T> {
     }
 }
 
-impl<T> ChunkedArray<T>
-where
-    T: PolarsDataType,
-    ChunkedArray<T>: ChunkOps,
-{
-    /// Should be used to match the chunk_id of another ChunkedArray.
-    /// # Panics
-    /// It is the callers responsibility to ensure that this ChunkedArray has a single chunk.
-    pub(crate) fn match_chunks<I>(&self, chunk_id: I) -> Self
-    where
-        I: Iterator<Item = usize>,
-    {
-        debug_assert!(self.chunks.len() == 1);
-        // Takes a ChunkedArray containing a single chunk
-        let slice = |ca: &Self| {
-            let array = &ca.chunks[0];
-
-            let mut offset = 0;
-            let chunks = chunk_id
-                .map(|len| {
-                    let out = array.slice(offset, len);
-                    offset += len;
-                    out
-                })
-                .collect();
-
-            Self::new_from_chunks(self.name(), chunks)
-        };
-
-        if self.chunks.len() != 1 {
-            let out = self.rechunk();
-            slice(&out)
-        } else {
-            slice(self)
-        }
-    }
-}
-
 impl<T> ChunkedArray<T>
 where
     T: PolarsDataType,
@@ -545,6 +600,7 @@ where
             phantom: PhantomData,
             categorical_map: None,
             bit_settings: 0,
+            stats: Default::default(),
         }
     }
 
@@ -807,6 +863,8 @@ impl<T> Clone for ChunkedArray<T> {
             chunks: self.chunks.clone(),
             phantom: PhantomData,
             categorical_map: self.categorical_map.clone(),
+            // the data is immutable, so the statistics can be shared
+            stats: self.stats.clone(),
             ..Default::default()
         }
     }
@@ -873,10 +931,26 @@ impl ListChunked {
             _ => unreachable!(),
         }
     }
+
+    /// Create a ListChunked of `length` null values, of which the lists have the arrow data type
+    /// `inner_dtype`. The offsets are all zero, so no values are allocated.
+    pub fn full_null_with_dtype(name: &str, length: usize, inner_dtype: &ArrowDataType) -> Self {
+        let field = ArrowField::new("item", inner_dtype.clone(), true);
+        let values = arrow::array::new_null_array(inner_dtype, 0);
+        let data = ArrayData::builder(ArrowDataType::LargeList(Box::new(field)))
+            .len(length)
+            .add_buffer(Buffer::from(vec![0i64; length + 1].to_byte_slice()))
+            .null_bit_buffer(Buffer::from(vec![0u8; (length + 7) / 8]))
+            .add_child_data(values.data().clone())
+            .build();
+        let arr = Arc::new(LargeListArray::from(data)) as ArrayRef;
+        ListChunked::new_from_chunks(name, vec![arr])
+    }
 }
 
 #[cfg(test)]
 pub(crate) mod test {
+    use crate::chunked_array::StatValue;
     use crate::prelude::*;
     use crate::reset_string_cache;
 
@@ -1085,4 +1159,92 @@ pub(crate) mod test {
         dbg!(before, after);
         assert!(before > after);
     }
+
+    #[test]
+    fn test_statistics_invalidation() {
+        let mut a = Int32Chunked::new_from_slice("a", &[1, 2, 3]);
+        let s = a.clone().into_series();
+        assert_eq!(s.cached_min::<i32>(), Some(1));
+        assert_eq!(s.cached_max::<i32>(), Some(3));
+        // clones share the statistics
+        assert_eq!(
+            a.statistics().min_max(),
+            Some((Some(StatValue::Int(1)), Some(StatValue::Int(3))))
+        );
+
+        // rename doesn't touch the data
+        a.rename("b");
+        assert_eq!(
+            a.statistics().min_max(),
+            Some((Some(StatValue::Int(1)), Some(StatValue::Int(3))))
+        );
+
+        a.append(&Int32Chunked::new_from_slice("b", &[10]));
+        assert!(a.statistics().min_max().is_none());
+        // the statistics of the series taken before the append are still valid
+        assert_eq!(s.cached_max::<i32>(), Some(3));
+
+        let s = a.into_series();
+        assert_eq!(s.cached_max::<i32>(), Some(10));
+        let s = s.slice(0, 2);
+        assert_eq!(s.cached_min::<i32>(), Some(1));
+        assert_eq!(s.cached_max::<i32>(), Some(2));
+    }
+
+    #[test]
+    fn test_sort_skip_with_statistics() {
+        let a = Int32Chunked::new_from_slice("a", &[2, 2, 2]);
+        let s = a.into_series();
+        assert_eq!(s.cached_min::<i32>(), Some(2));
+        assert_eq!(s.cached_max::<i32>(), Some(2));
+        let out = s.sort(true);
+        assert!(out.series_equal(&s));
+
+        let mut a = Int32Chunked::new_from_slice("a", &[3, 1, 2]);
+        a.sort_in_place(false);
+        assert!(a.is_sorted());
+        assert_eq!(Vec::from(&a.sort(false)), &[Some(1), Some(2), Some(3)]);
+        assert_eq!(Vec::from(&a.sort(true)), &[Some(3), Some(2), Some(1)]);
+
+        // these values are equal as f64, but not as i64
+        let big = 1i64 << 60;
+        let a = Int64Chunked::new_from_slice("a", &[big + 1, big, big + 2]);
+        let s = a.into_series();
+        assert_eq!(s.cached_min::<i64>(), Some(big));
+        assert_eq!(s.cached_max::<i64>(), Some(big + 2));
+        assert_eq!(
+            Vec::from(s.sort(false).i64().unwrap()),
+            &[Some(big), Some(big + 1), Some(big + 2)]
+        );
+    }
+
+    #[test]
+    fn test_chunk_statistics() {
+        let mut a = Int32Chunked::new_from_slice("a", &[3, 1]);
+        a.append(&Int32Chunked::new_from_opt_slice("a", &[None, None]));
+        a.append(&Int32Chunked::new_from_slice("a", &[5, 4]));
+        let s = a.clone().into_series();
+        let min_max = s.cached_chunk_min_max().unwrap();
+        assert_eq!(
+            min_max.as_slice(),
+            &[Some((1.0, 3.0)), None, Some((4.0, 5.0))]
+        );
+        // clones share the statistics
+        assert!(Arc::ptr_eq(
+            &min_max,
+            &a.clone().into_series().cached_chunk_min_max().unwrap()
+        ));
+
+        // the global statistics survive a sort, the chunk statistics don't
+        assert_eq!(s.cached_max::<i32>(), Some(5));
+        a.sort_in_place(false);
+        assert_eq!(
+            a.statistics().min_max(),
+            Some((Some(StatValue::Int(1)), Some(StatValue::Int(5))))
+        );
+        let min_max = a.into_series().cached_chunk_min_max().unwrap();
+        assert_eq!(min_max.as_slice(), &[Some((1.0, 5.0))]);
+
+        assert!(Series::new("a", &["a"]).cached_chunk_min_max().is_none());
+    }
 }
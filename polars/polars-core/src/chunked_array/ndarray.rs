@@ -65,6 +65,49 @@ impl ListChunked {
     }
 }
 
+/// Copy the values of the chunks into `dst`, with a single `memcpy` per chunk. Returns the number
+/// of copied values.
+fn copy_chunks<'a, T: Copy + 'a>(chunks: impl Iterator<Item = &'a [T]>, dst: &mut [T]) -> usize {
+    let mut offset = 0;
+    for chunk in chunks {
+        dst[offset..offset + chunk.len()].copy_from_slice(chunk);
+        offset += chunk.len();
+    }
+    offset
+}
+
+impl Series {
+    /// Create a 1D `ndarray::Array` from this `Series`. The values are casted to `N` and must
+    /// not be null.
+    #[cfg_attr(docsrs, doc(cfg(feature = "ndarray")))]
+    pub fn to_ndarray<N>(&self) -> Result<Array1<N::Native>>
+    where
+        N: PolarsNumericType,
+        N::Native: num::Zero + Copy,
+    {
+        if self.null_count() != 0 {
+            return Err(PolarsError::HasNullValues(
+                "Creation of ndarray with null values is not supported.".into(),
+            ));
+        }
+        let series = self.cast::<N>()?;
+        let ca = series.unpack::<N>()?;
+        let mut ndarr = Array1::zeros(ca.len());
+        // a new array is contiguous
+        copy_chunks(ca.data_views(), ndarr.as_slice_mut().unwrap());
+        Ok(ndarr)
+    }
+}
+
+/// The memory layout of a 2D `ndarray::Array` created from a `DataFrame`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum IndexOrder {
+    /// Row major, the values of a row are contiguous.
+    C,
+    /// Column major, the values of a column are contiguous.
+    Fortran,
+}
+
 impl DataFrame {
     /// Create a 2D `ndarray::Array` from this `DataFrame`. This requires all columns in the
     /// `DataFrame` to be non-null and numeric. They will be casted to the same data type
@@ -89,25 +132,171 @@ impl DataFrame {
     pub fn to_ndarray<N>(&self) -> Result<Array2<N::Native>>
     where
         N: PolarsNumericType,
-        N::Native: num::Zero + Copy,
+        N::Native: num::Zero + num::NumCast + Copy,
+    {
+        self.to_ndarray_with_options::<N>(IndexOrder::C, false)
+    }
+
+    /// Create a 2D `ndarray::Array` from this `DataFrame` with the given memory layout, see
+    /// [DataFrame::to_ndarray].
+    ///
+    /// If `nulls_as_nan`, null values are written as `NaN`, which requires `N` to be a float
+    /// type. Otherwise null values raise an error.
+    #[cfg_attr(docsrs, doc(cfg(feature = "ndarray")))]
+    pub fn to_ndarray_with_options<N>(
+        &self,
+        order: IndexOrder,
+        nulls_as_nan: bool,
+    ) -> Result<Array2<N::Native>>
+    where
+        N: PolarsNumericType,
+        N::Native: num::Zero + num::NumCast + Copy,
     {
-        let mut ndarr = Array2::zeros(self.shape());
+        let mut ndarr = match order {
+            IndexOrder::C => Array2::zeros(self.shape()),
+            IndexOrder::Fortran => Array2::zeros(self.shape().f()),
+        };
         for (col_idx, series) in self.get_columns().iter().enumerate() {
-            if series.null_count() != 0 {
-                return Err(PolarsError::HasNullValues(
-                    "Creation of ndarray with null values is not supported.".into(),
+            if !series.is_numeric() {
+                return Err(PolarsError::DataTypeMisMatch(
+                    format!(
+                        "cannot create an ndarray from column {} of type {:?}",
+                        series.name(),
+                        series.dtype()
+                    )
+                    .into(),
                 ));
             }
             // this is an Arc clone if already of type N
             let series = series.cast::<N>()?;
             let ca = series.unpack::<N>()?;
+            let mut column = ndarr.column_mut(col_idx);
 
-            ca.into_no_null_iter()
-                .enumerate()
-                .for_each(|(row_idx, val)| {
-                    ndarr[[row_idx, col_idx]] = val;
-                })
+            if ca.null_count() == 0 {
+                match column.as_slice_mut() {
+                    // the columns of a Fortran ordered array are contiguous
+                    Some(dst) => {
+                        copy_chunks(ca.data_views(), dst);
+                    }
+                    None => column
+                        .iter_mut()
+                        .zip(ca.data_views().flatten())
+                        .for_each(|(dst, val)| *dst = *val),
+                }
+            } else {
+                let nan = if nulls_as_nan {
+                    <N::Native as num::NumCast>::from(f64::NAN)
+                } else {
+                    None
+                };
+                let nan = nan.ok_or_else(|| {
+                    PolarsError::HasNullValues(
+                        "Creation of ndarray with null values is only supported for float types with nulls as NaN."
+                            .into(),
+                    )
+                })?;
+                column
+                    .iter_mut()
+                    .zip(ca.into_iter())
+                    .for_each(|(dst, val)| *dst = val.unwrap_or(nan))
+            }
         }
         Ok(ndarr)
     }
+
+    /// Create a `DataFrame` from a 2D `ndarray::Array`, with a column per column of the array.
+    /// The values of every column are copied once into the buffer of its `Series`.
+    #[cfg_attr(docsrs, doc(cfg(feature = "ndarray")))]
+    pub fn from_ndarray<N>(arr: ArrayView2<N::Native>, names: &[&str]) -> Result<DataFrame>
+    where
+        N: PolarsNumericType,
+        ChunkedArray<N>: IntoSeries,
+    {
+        if names.len() != arr.ncols() {
+            return Err(PolarsError::ShapeMisMatch(
+                format!(
+                    "got {} names for an ndarray with {} columns",
+                    names.len(),
+                    arr.ncols()
+                )
+                .into(),
+            ));
+        }
+        let columns = names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let column = arr.column(i);
+                let values = match column.as_slice() {
+                    Some(slice) => AlignedVec::new_from_slice(slice),
+                    None => {
+                        let mut values = AlignedVec::with_capacity_aligned(column.len());
+                        values.extend(column.iter().copied());
+                        values
+                    }
+                };
+                ChunkedArray::<N>::new_from_aligned_vec(name, values).into_series()
+            })
+            .collect();
+        DataFrame::new(columns)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::df;
+
+    #[test]
+    fn test_copy_chunks() {
+        let ca = Float64Chunked::new_from_slice("a", &[1.0, 2.0]);
+        let mut ca2 = ca;
+        ca2.append(&Float64Chunked::new_from_slice("a", &[3.0]));
+        let mut dst = [0.0; 3];
+        assert_eq!(copy_chunks(ca2.data_views(), &mut dst), 3);
+        assert_eq!(dst, [1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_ndarray_round_trip() -> Result<()> {
+        let df = df![
+            "a" => [1u32, 2, 3],
+            "b" => [10.0, 8.0, 6.0]
+        ]?;
+        for order in &[IndexOrder::C, IndexOrder::Fortran] {
+            let arr = df.to_ndarray_with_options::<Float64Type>(*order, false)?;
+            assert_eq!(arr, arr2(&[[1.0, 10.0], [2.0, 8.0], [3.0, 6.0]]));
+            let out = DataFrame::from_ndarray::<Float64Type>(arr.view(), &["a", "b"])?;
+            assert!(out.frame_equal(&DataFrame::new(vec![
+                df.column("a")?.cast::<Float64Type>()?,
+                df.column("b")?.clone()
+            ])?));
+        }
+
+        let s = Series::new("a", &[1i32, 2]);
+        assert_eq!(s.to_ndarray::<Int64Type>()?, arr1(&[1i64, 2]));
+
+        let arr = arr2(&[[1.0, 2.0], [3.0, 4.0]]);
+        assert!(DataFrame::from_ndarray::<Float64Type>(arr.view(), &["a"]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_ndarray_nulls() -> Result<()> {
+        let df = df![
+            "a" => [Some(1.0), None],
+            "b" => ["x", "y"]
+        ]?;
+        assert!(df.to_ndarray::<Float64Type>().is_err());
+
+        let df = df.drop("b")?;
+        assert!(df.to_ndarray::<Float64Type>().is_err());
+        assert!(df
+            .to_ndarray_with_options::<Int32Type>(IndexOrder::C, true)
+            .is_err());
+        let arr = df.to_ndarray_with_options::<Float64Type>(IndexOrder::Fortran, true)?;
+        assert_eq!(arr[[0, 0]], 1.0);
+        assert!(arr[[1, 0]].is_nan());
+        Ok(())
+    }
 }
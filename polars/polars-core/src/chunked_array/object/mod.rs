@@ -127,11 +127,16 @@ where
     }
 
     fn get_buffer_memory_size(&self) -> usize {
-        unimplemented!()
+        // the objects themselves may own heap memory that isn't counted
+        self.values.len() * std::mem::size_of::<T>()
+            + self
+                .null_bitmap
+                .as_ref()
+                .map_or(0, |b| b.buffer_ref().len())
     }
 
     fn get_array_memory_size(&self) -> usize {
-        unimplemented!()
+        self.get_buffer_memory_size() + std::mem::size_of::<Self>()
     }
 }
 
@@ -3,8 +3,10 @@ use crate::chunked_array::builder::get_list_builder;
 use crate::chunked_array::ChunkedArray;
 use crate::datatypes::BooleanChunked;
 use crate::{datatypes::PolarsNumericType, prelude::*, utils::CustomIterTools};
-use arrow::compute;
 use num::{Num, NumCast, ToPrimitive, Zero};
+use polars_arrow::kernels::agg::{
+    reduce_primitive, sum_primitive, sum_primitive_f64, sum_squared_deviations_f64,
+};
 use std::cmp::PartialOrd;
 
 /// Aggregations that return Series of unit length. Those can be used in broadcasting operations.
@@ -44,21 +46,15 @@ pub trait VarAggSeries {
 
 macro_rules! agg_float_with_nans {
     ($self:ident, $agg_method:ident, $precision:ty) => {{
-        if $self.null_count() == 0 {
-            $self
-                .into_no_null_iter()
-                .map(|a| -> $precision { NumCast::from(a).unwrap() })
-                .fold_first_(|a, b| a.$agg_method(b))
-                .map(|a| NumCast::from(a).unwrap())
-        } else {
-            $self
-                .into_iter()
-                .filter(|opt| opt.is_some())
-                .map(|opt| opt.unwrap())
-                .map(|a| -> $precision { NumCast::from(a).unwrap() })
-                .fold_first_(|a, b| a.$agg_method(b))
-                .map(|a| NumCast::from(a).unwrap())
-        }
+        let agg = |a: T::Native, b: T::Native| -> T::Native {
+            let a: $precision = NumCast::from(a).unwrap();
+            let b: $precision = NumCast::from(b).unwrap();
+            NumCast::from(a.$agg_method(b)).unwrap()
+        };
+        $self
+            .downcast_iter()
+            .filter_map(|a| reduce_primitive(a, agg))
+            .fold_first_(agg)
     }};
 }
 
@@ -84,7 +80,7 @@ where
 {
     fn sum(&self) -> Option<T::Native> {
         self.downcast_iter()
-            .map(|a| compute::sum(a))
+            .map(|a| sum_primitive(a))
             .fold(None, |acc, v| match v {
                 Some(v) => match acc {
                     None => Some(v),
@@ -100,7 +96,7 @@ where
             DataType::Float64 => agg_float_with_nans!(self, min, f64),
             _ => self
                 .downcast_iter()
-                .filter_map(|a| compute::min(a))
+                .filter_map(|a| reduce_primitive(a, |acc, v| if acc < v { acc } else { v }))
                 .fold_first_(|acc, v| if acc < v { acc } else { v }),
         }
     }
@@ -111,14 +107,18 @@ where
             DataType::Float64 => agg_float_with_nans!(self, max, f64),
             _ => self
                 .downcast_iter()
-                .filter_map(|a| compute::max(a))
+                .filter_map(|a| reduce_primitive(a, |acc, v| if acc > v { acc } else { v }))
                 .fold_first_(|acc, v| if acc > v { acc } else { v }),
         }
     }
 
     fn mean(&self) -> Option<f64> {
+        // summed as f64, so that integers don't overflow and floats don't lose precision
         let len = (self.len() - self.null_count()) as f64;
-        self.sum().map(|v| v.to_f64().unwrap() / len)
+        self.downcast_iter()
+            .filter_map(|a| sum_primitive_f64(a))
+            .fold_first_(|acc, v| acc + v)
+            .map(|sum| sum / len)
     }
 
     fn median(&self) -> Option<f64> {
@@ -132,8 +132,10 @@ where
                 // After sorting the nulls are at the start of the array.
                 let mid = value_len / 2 + null_count;
                 if value_len % 2 == 0 {
-                    NumCast::from(sorted.get(mid - 1).unwrap() + sorted.get(mid).unwrap())
-                        .map(|v: f64| v / 2.0)
+                    // add as f64, the sum of two small integers may overflow
+                    let low = sorted.get(mid - 1).unwrap().to_f64().unwrap();
+                    let high = sorted.get(mid).unwrap().to_f64().unwrap();
+                    Some((low + high) / 2.0)
                 } else {
                     sorted.get(mid).map(|v| NumCast::from(v).unwrap())
                 }
@@ -153,22 +155,29 @@ where
     }
 }
 
+/// The sample variance (ddof = 1), computed in f64 regardless of the type of the values.
+fn var_f64<T>(ca: &ChunkedArray<T>) -> Option<f64>
+where
+    T: PolarsNumericType,
+    T::Native: PartialOrd + Num + NumCast + Zero,
+{
+    let mean = ca.mean()?;
+    // Note, this is similar behavior to numpy if DDOF=1.
+    // in statistics DDOF often = 1.
+    // this last step is similar to mean, only now instead of 1/n it is 1/(n-1)
+    ca.downcast_iter()
+        .filter_map(|a| sum_squared_deviations_f64(a, mean))
+        .fold_first_(|acc, v| acc + v)
+        .map(|sum| sum / (ca.len() - ca.null_count() - 1) as f64)
+}
+
 impl<T> ChunkVar<f64> for ChunkedArray<T>
 where
     T: PolarsIntegerType,
     T::Native: PartialOrd + Num + NumCast,
 {
     fn var(&self) -> Option<f64> {
-        let mean = self.mean()?;
-        let squared = self.apply_cast_numeric::<_, Float64Type>(|value| {
-            (value.to_f64().unwrap() - mean).powf(2.0)
-        });
-        // Note, this is similar behavior to numpy if DDOF=1.
-        // in statistics DDOF often = 1.
-        // this last step is similar to mean, only now instead of 1/n it is 1/(n-1)
-        squared
-            .sum()
-            .map(|sum| sum / (self.len() - self.null_count() - 1) as f64)
+        var_f64(self)
     }
     fn std(&self) -> Option<f64> {
         self.var().map(|var| var.sqrt())
@@ -177,24 +186,16 @@ where
 
 impl ChunkVar<f32> for Float32Chunked {
     fn var(&self) -> Option<f32> {
-        let mean = self.mean()? as f32;
-        let squared = self.apply(|value| (value - mean).powf(2.0));
-        squared
-            .sum()
-            .map(|sum| sum / (self.len() - self.null_count() - 1) as f32)
+        var_f64(self).map(|var| var as f32)
     }
     fn std(&self) -> Option<f32> {
-        self.var().map(|var| var.sqrt())
+        var_f64(self).map(|var| var.sqrt() as f32)
     }
 }
 
 impl ChunkVar<f64> for Float64Chunked {
     fn var(&self) -> Option<f64> {
-        let mean = self.mean()?;
-        let squared = self.apply(|value| (value - mean).powf(2.0));
-        squared
-            .sum()
-            .map(|sum| sum / (self.len() - self.null_count() - 1) as f64)
+        var_f64(self)
     }
     fn std(&self) -> Option<f64> {
         self.var().map(|var| var.sqrt())
@@ -268,6 +269,19 @@ where
     ChunkedArray<T>: IntoSeries,
 {
     fn sum_as_series(&self) -> Series {
+        // small integers are summed as 64 bit integers to prevent overflow, and f32 is summed
+        // as f64 to prevent the loss of precision
+        match T::get_dtype() {
+            DataType::Int8 | DataType::Int16 => {
+                return self.cast::<Int64Type>().unwrap().sum_as_series()
+            }
+            #[cfg(feature = "dtype-u64")]
+            DataType::UInt8 | DataType::UInt16 => {
+                return self.cast::<UInt64Type>().unwrap().sum_as_series()
+            }
+            DataType::Float32 => return self.cast::<Float64Type>().unwrap().sum_as_series(),
+            _ => {}
+        }
         let v = self.sum();
         let mut ca: ChunkedArray<T> = [v].iter().copied().collect();
         ca.rename(self.name());
@@ -517,6 +531,7 @@ impl<T> ArgAgg for ObjectChunked<T> {}
 #[cfg(test)]
 mod test {
     use crate::prelude::*;
+    use crate::utils::NoNull;
 
     #[test]
     fn test_var() {
@@ -556,6 +571,32 @@ mod test {
         println!("{:?}", (ca1.min(), ca2.min()))
     }
 
+    #[test]
+    fn test_agg_with_nulls() {
+        // values crossing multiple words of the validity bitmap and a non word-aligned slice
+        let values = (0..200)
+            .map(|v| if v % 3 == 0 { None } else { Some(v as f64) })
+            .collect::<Vec<_>>();
+        let ca = Float64Chunked::new_from_opt_slice("a", &values);
+        let ca = ca.slice(5, 150);
+        let valid = ca.into_iter().flatten().collect::<Vec<_>>();
+        assert_eq!(ca.sum(), Some(valid.iter().sum()));
+        assert_eq!(ca.min(), Some(5.0));
+        assert_eq!(ca.max(), Some(154.0));
+        assert_eq!(
+            ca.mean(),
+            Some(valid.iter().sum::<f64>() / valid.len() as f64)
+        );
+
+        let ca = Int32Chunked::new_from_opt_slice("a", &[None, Some(3), None, Some(-1), Some(2)]);
+        assert_eq!(ca.sum(), Some(4));
+        assert_eq!(ca.min(), Some(-1));
+        assert_eq!(ca.max(), Some(3));
+        let ca = Int32Chunked::new_from_opt_slice("a", &[None, None]);
+        assert_eq!(ca.sum(), None);
+        assert_eq!(ca.min(), None);
+    }
+
     #[test]
     fn test_median() {
         let ca = UInt32Chunked::new_from_opt_slice(
@@ -627,4 +668,48 @@ mod test {
         assert_eq!(ca.mean(), None);
         assert_eq!(ca.mean_as_series().f32().unwrap().get(0), None);
     }
+
+    #[test]
+    fn test_mean_var_precision() {
+        // a naive f32 sum of 1e7 times 0.1 is off by more than 8%
+        let n = 10_000_000;
+        let ca: NoNull<Float32Chunked> = std::iter::repeat(0.1f32).take(n).collect();
+        let ca = ca.into_inner();
+        let expected = 0.1f32 as f64;
+        assert!((ca.mean().unwrap() - expected).abs() < 1e-12);
+
+        // the sum of the values overflows i32 and i64
+        let ca = Int32Chunked::new_from_slice("", &[i32::MAX; 10]);
+        assert_eq!(ca.mean(), Some(i32::MAX as f64));
+        let ca = Int64Chunked::new_from_slice("", &[i64::MAX; 4]);
+        assert_eq!(ca.mean(), Some(i64::MAX as f64));
+
+        // small deviations of large values are accumulated in f64
+        let (a, b) = (1000.1f32, 1000.2f32);
+        let n = 1_000_000;
+        let ca: NoNull<Float32Chunked> = (0..n).map(|i| if i % 2 == 0 { a } else { b }).collect();
+        let ca = ca.into_inner();
+        let half_diff = (b as f64 - a as f64) / 2.0;
+        let expected = half_diff * half_diff * n as f64 / (n - 1) as f64;
+        assert!((ca.var().unwrap() as f64 - expected).abs() < 1e-6 * expected);
+    }
+
+    #[test]
+    #[cfg(feature = "dtype-u8")]
+    fn test_small_integer_aggregations() {
+        // the sum of the values doesn't fit in a u8
+        let ca = UInt8Chunked::new_from_slice("", &[200, 250, 100, 150]);
+        assert_eq!(ca.mean(), Some(175.0));
+        assert_eq!(ca.median(), Some(175.0));
+        assert_eq!(ca.sum_as_series().u64().unwrap().get(0), Some(700));
+    }
+
+    #[test]
+    fn test_float32_sum() {
+        // in f32, 2^24 + 1 rounds to 2^24
+        let ca = Float32Chunked::new_from_slice("", &[16_777_216.0, 1.0, 1.0]);
+        let s = ca.sum_as_series();
+        assert_eq!(s.dtype(), &DataType::Float64);
+        assert_eq!(s.f64().unwrap().get(0), Some(16_777_218.0));
+    }
 }
@@ -2,6 +2,7 @@
 use crate::prelude::*;
 use crate::utils::{CustomIterTools, NoNull};
 use arrow::array::{Array, ArrayRef, BooleanArray, LargeStringArray, PrimitiveArray};
+use arrow::buffer::Buffer;
 use std::borrow::Cow;
 use std::convert::TryFrom;
 
@@ -36,6 +37,14 @@ macro_rules! apply_enumerate {
     }};
 }
 
+/// The validity of `arr` for an output array without offset. The values of a sliced array start
+/// at its offset, so the validity should start there as well.
+fn sliced_validity(arr: &dyn Array) -> Option<Buffer> {
+    arr.data_ref()
+        .null_buffer()
+        .map(|buf| buf.bit_slice(arr.offset(), arr.len()))
+}
+
 impl<'a, T> ChunkApply<'a, T::Native, T::Native> for ChunkedArray<T>
 where
     T: PolarsNumericType,
@@ -46,11 +55,10 @@ where
         S: PolarsNumericType,
     {
         let mut ca: ChunkedArray<S> = self
-            .data_views()
-            .zip(self.null_bits())
-            .map(|(slice, (_null_count, opt_buffer))| {
-                let vec: AlignedVec<_> = slice.iter().copied().map(f).collect_trusted();
-                (vec, opt_buffer.cloned())
+            .downcast_iter()
+            .map(|arr| {
+                let vec: AlignedVec<_> = arr.values().iter().copied().map(f).collect_trusted();
+                (vec, sliced_validity(arr))
             })
             .collect();
         ca.rename(self.name());
@@ -90,12 +98,10 @@ where
         F: Fn(T::Native) -> T::Native + Copy,
     {
         let mut ca: ChunkedArray<T> = self
-            .data_views()
-            .into_iter()
-            .zip(self.null_bits())
-            .map(|(slice, (_null_count, opt_buffer))| {
-                let vec: AlignedVec<_> = slice.iter().copied().map(f).collect_trusted();
-                (vec, opt_buffer.cloned())
+            .downcast_iter()
+            .map(|arr| {
+                let vec: AlignedVec<_> = arr.values().iter().copied().map(f).collect_trusted();
+                (vec, sliced_validity(arr))
             })
             .collect();
         ca.rename(self.name());
@@ -180,7 +186,7 @@ impl<'a> ChunkApply<'a, bool, bool> for BooleanChunked {
             let av: AlignedVec<_> = (0..array.len())
                 .map(|idx| unsafe { f(array.value_unchecked(idx)) })
                 .collect_trusted();
-            let null_bit_buffer = array.data_ref().null_buffer().cloned();
+            let null_bit_buffer = sliced_validity(array);
             Arc::new(av.into_primitive_array::<S>(null_bit_buffer)) as ArrayRef
         })
     }
@@ -260,7 +266,7 @@ impl<'a> ChunkApply<'a, &'a str, Cow<'a, str>> for Utf8Chunked {
                 let av: AlignedVec<_> = (0..array.len())
                     .map(|idx| unsafe { f(array.value_unchecked(idx)) })
                     .collect_trusted();
-                let null_bit_buffer = array.data_ref().null_buffer().cloned();
+                let null_bit_buffer = sliced_validity(array);
                 Arc::new(av.into_primitive_array::<S>(null_bit_buffer)) as ArrayRef
             })
             .collect();
@@ -281,7 +287,7 @@ impl<'a> ChunkApply<'a, &'a str, Cow<'a, str>> for Utf8Chunked {
                     .map(f)
                     .trust_my_length(array.len())
                     .collect_trusted();
-                let null_bit_buffer = array.data_ref().null_buffer().cloned();
+                let null_bit_buffer = sliced_validity(array);
                 Arc::new(av.into_primitive_array::<S>(null_bit_buffer)) as ArrayRef
             })
             .collect();
@@ -417,7 +423,7 @@ impl<'a> ChunkApply<'a, Series, Series> for ListChunked {
                         f(series)
                     })
                     .collect_trusted();
-                let null_bit_buffer = array.data_ref().null_buffer().cloned();
+                let null_bit_buffer = sliced_validity(array);
                 Arc::new(av.into_primitive_array::<S>(null_bit_buffer)) as ArrayRef
             })
             .collect();
@@ -446,7 +452,7 @@ impl<'a> ChunkApply<'a, Series, Series> for ListChunked {
                         f(v)
                     })
                     .collect_trusted();
-                let null_bit_buffer = array.data_ref().null_buffer().cloned();
+                let null_bit_buffer = sliced_validity(array);
                 Arc::new(av.into_primitive_array::<S>(null_bit_buffer)) as ArrayRef
             })
             .collect();
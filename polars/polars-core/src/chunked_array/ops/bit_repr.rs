@@ -1,5 +1,6 @@
 use crate::prelude::*;
 use arrow::array::{make_array, Array, ArrayData};
+use num::Float;
 
 impl<T> ToBitRepr for ChunkedArray<T>
 where
@@ -60,6 +61,63 @@ where
     }
 }
 
+/// The key of a float value: a single NaN for all NaN's and `0.0` for `-0.0`.
+#[inline]
+fn canonical_float<T: Float>(v: T) -> T {
+    if v.is_nan() {
+        T::nan()
+    } else if v.is_zero() {
+        T::zero()
+    } else {
+        v
+    }
+}
+
+impl Float32Chunked {
+    /// The bits of the values as groupby and join keys, see
+    /// [toggle_bitwise_float_keys](crate::toggle_bitwise_float_keys).
+    pub(crate) fn key_bit_repr(&self) -> UInt32Chunked {
+        if crate::use_bitwise_float_keys() {
+            self.bit_repr_small()
+        } else {
+            self.apply_cast_numeric(|v| canonical_float(v).to_bits())
+        }
+    }
+}
+
+impl Float64Chunked {
+    /// The bits of the values as groupby and join keys, see
+    /// [toggle_bitwise_float_keys](crate::toggle_bitwise_float_keys).
+    pub(crate) fn key_bit_repr(&self) -> UInt64Chunked {
+        if crate::use_bitwise_float_keys() {
+            self.bit_repr_large()
+        } else {
+            self.apply_cast_numeric(|v| canonical_float(v).to_bits())
+        }
+    }
+}
+
+/// Replace float keys by the integer Series of their key bits, so that the hash and the equality
+/// of the keys agree. Other Series are returned as is.
+pub(crate) fn float_keys_to_bits(s: &Series) -> Series {
+    match s.dtype() {
+        DataType::Float32 => s.f32().unwrap().key_bit_repr().into_series(),
+        DataType::Float64 => {
+            let ca = s.f64().unwrap().key_bit_repr();
+            #[cfg(feature = "dtype-u64")]
+            {
+                ca.into_series()
+            }
+            #[cfg(not(feature = "dtype-u64"))]
+            {
+                ca.apply_cast_numeric::<_, Int64Type>(|v| v as i64)
+                    .into_series()
+            }
+        }
+        _ => s.clone(),
+    }
+}
+
 impl ToBitRepr for CategoricalChunked {
     fn bit_repr_is_large() -> bool {
         // u32
@@ -0,0 +1,203 @@
+use crate::prelude::*;
+use num::{Bounded, Signed};
+
+/// Saturate `value` to the bounds, a `None` bound doesn't bound that side.
+#[inline]
+fn clip_value<T: PartialOrd>(value: T, min: Option<T>, max: Option<T>) -> T {
+    match (min, max) {
+        (Some(min), _) if value < min => min,
+        (_, Some(max)) if value > max => max,
+        _ => value,
+    }
+}
+
+fn check_bound_length(len: usize, bound_len: usize) -> Result<()> {
+    if bound_len == 1 || bound_len == len {
+        Ok(())
+    } else {
+        Err(PolarsError::ShapeMisMatch(
+            format!(
+                "the bounds of clip should have length 1 or {}, got {}",
+                len, bound_len
+            )
+            .into(),
+        ))
+    }
+}
+
+/// Iterate over a bound, broadcasting a bound of length 1 to `len`.
+fn bound_iter<'a, T>(
+    bound: &'a ChunkedArray<T>,
+    len: usize,
+) -> Box<dyn Iterator<Item = Option<T::Native>> + 'a>
+where
+    T: PolarsNumericType,
+{
+    if bound.len() == 1 {
+        Box::new(std::iter::repeat(bound.get(0)).take(len))
+    } else {
+        Box::new(bound.into_iter())
+    }
+}
+
+impl<T> ChunkedArray<T>
+where
+    T: PolarsNumericType,
+{
+    /// Saturate the values to the bounds `min` and `max`. Bounds of length 1 are broadcasted.
+    ///
+    /// A null bound doesn't bound the values on that side and null values stay null.
+    pub fn clip(&self, min: &ChunkedArray<T>, max: &ChunkedArray<T>) -> Result<Self> {
+        check_bound_length(self.len(), min.len())?;
+        check_bound_length(self.len(), max.len())?;
+
+        if min.len() == 1 && max.len() == 1 {
+            let (min, max) = (min.get(0), max.get(0));
+            return Ok(self.apply(|v| clip_value(v, min, max)));
+        }
+        let mut ca: Self = self
+            .into_iter()
+            .zip(bound_iter(min, self.len()))
+            .zip(bound_iter(max, self.len()))
+            .map(|((opt_v, min), max)| opt_v.map(|v| clip_value(v, min, max)))
+            .collect();
+        ca.rename(self.name());
+        Ok(ca)
+    }
+}
+
+impl<T> ChunkedArray<T>
+where
+    T: PolarsNumericType,
+    T::Native: Signed + Bounded,
+{
+    /// Compute the absolute values.
+    ///
+    /// Integers saturate, the absolute value of the minimal value of a signed integer type is the
+    /// maximum value of that type, e.g. `i64::MIN` becomes `i64::MAX`.
+    pub fn abs(&self) -> Self {
+        self.apply(|v| {
+            if v == T::Native::min_value() && T::Native::min_value() < -T::Native::max_value() {
+                T::Native::max_value()
+            } else {
+                v.abs()
+            }
+        })
+    }
+}
+
+impl Series {
+    /// Saturate the values to the bounds `min` and `max`. Bounds of length 1 are broadcasted and
+    /// the bounds are cast to the dtype of the Series.
+    ///
+    /// A null bound doesn't bound the values on that side and null values stay null.
+    pub fn clip(&self, min: &Series, max: &Series) -> Result<Series> {
+        let min = min.cast_with_dtype(self.dtype())?;
+        let max = max.cast_with_dtype(self.dtype())?;
+
+        macro_rules! clip {
+            ($ca:expr) => {{
+                let ca = $ca;
+                ca.clip(
+                    ca.unpack_series_matching_type(&min)?,
+                    ca.unpack_series_matching_type(&max)?,
+                )
+                .map(|ca| ca.into_series())
+            }};
+        }
+        if self.is_numeric() {
+            match_arrow_data_type_apply_macro_ca_logical_num!(self, clip)
+        } else {
+            Err(PolarsError::InvalidOperation(
+                format!("clip not supported for dtype {:?}", self.dtype()).into(),
+            ))
+        }
+    }
+
+    /// Compute the absolute values. Unsigned integers are returned as is.
+    ///
+    /// Signed integers saturate, e.g. the absolute value of `i64::MIN` is `i64::MAX`.
+    pub fn abs(&self) -> Result<Series> {
+        use DataType::*;
+        let out = match self.dtype() {
+            #[cfg(feature = "dtype-i8")]
+            Int8 => self.i8().unwrap().abs().into_series(),
+            #[cfg(feature = "dtype-i16")]
+            Int16 => self.i16().unwrap().abs().into_series(),
+            Int32 => self.i32().unwrap().abs().into_series(),
+            Int64 => self.i64().unwrap().abs().into_series(),
+            Float32 => self.f32().unwrap().abs().into_series(),
+            Float64 => self.f64().unwrap().abs().into_series(),
+            // the remaining numeric types are unsigned
+            _ if self.is_numeric() => self.clone(),
+            dt => {
+                return Err(PolarsError::InvalidOperation(
+                    format!("abs not supported for dtype {:?}", dt).into(),
+                ))
+            }
+        };
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_clip() -> Result<()> {
+        let s = Series::new("a", &[Some(-5), Some(0), None, Some(5), Some(10)]);
+        let out = s.clip(&Series::new("min", &[-1]), &Series::new("max", &[6.5]))?;
+        assert_eq!(out.name(), "a");
+        assert_eq!(
+            Vec::from(out.i32()?),
+            &[Some(-1), Some(0), None, Some(5), Some(6)]
+        );
+
+        // a null bound doesn't bound
+        let out = s.clip(
+            &Series::new("min", &[None::<i32>]),
+            &Series::new("max", &[1]),
+        )?;
+        assert_eq!(
+            Vec::from(out.i32()?),
+            &[Some(-5), Some(0), None, Some(1), Some(1)]
+        );
+
+        // bounds per element
+        let s = Series::new("a", &[1.0, 2.0, 3.0, f64::NAN]);
+        let min = Series::new("min", &[Some(2.0), None, Some(0.0), Some(0.0)]);
+        let out = s.clip(&min, &Series::new("max", &[2.5]))?;
+        let out = Vec::from(out.f64()?);
+        assert_eq!(&out[..3], &[Some(2.0), Some(2.0), Some(2.5)]);
+        assert!(out[3].unwrap().is_nan());
+
+        assert!(s.clip(&Series::new("min", &[1.0, 2.0]), &min).is_err());
+        assert!(Series::new("a", &["a"])
+            .clip(&Series::new("min", &["a"]), &Series::new("max", &["b"]))
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_abs() -> Result<()> {
+        let s = Series::new(
+            "a",
+            &[Some(i64::MIN), Some(-1), None, Some(2), Some(i64::MAX)],
+        );
+        let out = s.abs()?;
+        assert_eq!(
+            Vec::from(out.i64()?),
+            &[Some(i64::MAX), Some(1), None, Some(2), Some(i64::MAX)]
+        );
+        let s = Series::new("a", &[-1.5f32, 0.0, 2.5]);
+        assert_eq!(
+            Vec::from(s.abs()?.f32()?),
+            &[Some(1.5), Some(0.0), Some(2.5)]
+        );
+        let s = Series::new("a", &[1u32, 2]);
+        assert!(s.abs()?.series_equal(&s));
+        assert!(Series::new("a", &["a"]).abs().is_err());
+        Ok(())
+    }
+}
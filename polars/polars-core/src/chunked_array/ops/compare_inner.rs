@@ -3,10 +3,12 @@
 //!
 
 use super::take_random::{
-    BoolTakeRandom, BoolTakeRandomSingleChunk, NumTakeRandomChunked, NumTakeRandomCont,
-    NumTakeRandomSingleChunk, Utf8TakeRandom, Utf8TakeRandomSingleChunk,
+    BoolTakeRandom, BoolTakeRandomSingleChunk, ListTakeRandom, ListTakeRandomSingleChunk,
+    NumTakeRandomChunked, NumTakeRandomCont, NumTakeRandomSingleChunk, TakeRandBranch2,
+    Utf8TakeRandom, Utf8TakeRandomSingleChunk,
 };
 use crate::prelude::*;
+use crate::vector_hasher::list_values_eq;
 use std::cmp::{Ordering, PartialEq};
 
 pub trait PartialEqInner: Send + Sync {
@@ -83,6 +85,30 @@ where
     }
 }
 
+/// Two lists are equal if they have the same length and equal elements, a null list is only
+/// equal to another null list. This agrees with the hash of the lists.
+#[inline]
+fn list_eq(a: Option<Series>, b: Option<Series>) -> bool {
+    match (a, b) {
+        (None, None) => true,
+        (Some(a), Some(b)) => list_values_eq(&a, &b),
+        _ => false,
+    }
+}
+
+impl PartialEqInner for ListTakeRandom<'_> {
+    unsafe fn eq_element_unchecked(&self, idx_a: usize, idx_b: usize) -> bool {
+        // nulls so we can not do unchecked
+        list_eq(self.get(idx_a), self.get(idx_b))
+    }
+}
+
+impl PartialEqInner for ListTakeRandomSingleChunk<'_> {
+    unsafe fn eq_element_unchecked(&self, idx_a: usize, idx_b: usize) -> bool {
+        list_eq(self.get(idx_a), self.get(idx_b))
+    }
+}
+
 /// Create a type that implements PartialEqInner
 pub(crate) trait IntoPartialEqInner<'a> {
     /// Create a type that implements `TakeRandom`.
@@ -113,7 +139,7 @@ where
         } else {
             let t = NumTakeRandomChunked {
                 chunks: chunks.collect(),
-                chunk_lens: self.chunks.iter().map(|a| a.len() as u32).collect(),
+                chunk_lens: self.chunks.iter().map(|a| a.len() as IdxSize).collect(),
             };
             Box::new(t)
         }
@@ -132,7 +158,7 @@ impl<'a> IntoPartialEqInner<'a> for &'a Utf8Chunked {
                 let chunks = self.downcast_chunks();
                 let t = Utf8TakeRandom {
                     chunks,
-                    chunk_lens: self.chunks.iter().map(|a| a.len() as u32).collect(),
+                    chunk_lens: self.chunks.iter().map(|a| a.len() as IdxSize).collect(),
                 };
                 Box::new(t)
             }
@@ -152,7 +178,7 @@ impl<'a> IntoPartialEqInner<'a> for &'a BooleanChunked {
                 let chunks = self.downcast_chunks();
                 let t = BoolTakeRandom {
                     chunks,
-                    chunk_lens: self.chunks.iter().map(|a| a.len() as u32).collect(),
+                    chunk_lens: self.chunks.iter().map(|a| a.len() as IdxSize).collect(),
                 };
                 Box::new(t)
             }
@@ -161,8 +187,11 @@ impl<'a> IntoPartialEqInner<'a> for &'a BooleanChunked {
 }
 
 impl<'a> IntoPartialEqInner<'a> for &'a ListChunked {
-    fn into_partial_eq_inner(self) -> Box<dyn PartialEqInner> {
-        unimplemented!()
+    fn into_partial_eq_inner(self) -> Box<dyn PartialEqInner + 'a> {
+        match self.take_rand() {
+            TakeRandBranch2::Single(t) => Box::new(t),
+            TakeRandBranch2::Multi(t) => Box::new(t),
+        }
     }
 }
 
@@ -226,7 +255,7 @@ where
         } else {
             let t = NumTakeRandomChunked {
                 chunks: chunks.collect(),
-                chunk_lens: self.chunks.iter().map(|a| a.len() as u32).collect(),
+                chunk_lens: self.chunks.iter().map(|a| a.len() as IdxSize).collect(),
             };
             Box::new(t)
         }
@@ -245,7 +274,7 @@ impl<'a> IntoPartialOrdInner<'a> for &'a Utf8Chunked {
                 let chunks = self.downcast_chunks();
                 let t = Utf8TakeRandom {
                     chunks,
-                    chunk_lens: self.chunks.iter().map(|a| a.len() as u32).collect(),
+                    chunk_lens: self.chunks.iter().map(|a| a.len() as IdxSize).collect(),
                 };
                 Box::new(t)
             }
@@ -265,7 +294,7 @@ impl<'a> IntoPartialOrdInner<'a> for &'a BooleanChunked {
                 let chunks = self.downcast_chunks();
                 let t = BoolTakeRandom {
                     chunks,
-                    chunk_lens: self.chunks.iter().map(|a| a.len() as u32).collect(),
+                    chunk_lens: self.chunks.iter().map(|a| a.len() as IdxSize).collect(),
                 };
                 Box::new(t)
             }
@@ -0,0 +1,421 @@
+//! One hot encoding of a column in dummy (indicator) variables.
+use crate::chunked_array::builder::categorical::RevMapping;
+#[cfg(feature = "object")]
+use crate::chunked_array::object::ObjectType;
+use crate::datatypes::PlHashSet;
+use crate::frame::groupby::{GroupTuples, IntoGroupTuples};
+use crate::prelude::*;
+use crate::utils::CustomIterTools;
+use arrow::array::{ArrayData, BooleanArray};
+use arrow::buffer::MutableBuffer;
+use arrow::util::bit_util;
+use num::NumCast;
+use std::fmt::Display;
+use std::hash::Hash;
+
+#[cfg(feature = "dtype-u8")]
+type DummyType = UInt8Type;
+#[cfg(not(feature = "dtype-u8"))]
+type DummyType = Int32Type;
+
+/// Options of [to_dummies_with_options](crate::frame::DataFrame::to_dummies_with_options).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DummiesOptions {
+    /// Create Boolean indicator columns instead of UInt8 columns.
+    pub boolean: bool,
+    /// Add a `{column}_null` indicator column if the column has null values. Otherwise a null
+    /// value is 0 in all indicator columns.
+    pub null_column: bool,
+    /// The maximum number of distinct values of a column. A column with more distinct values
+    /// returns an error instead of creating an indicator column per value.
+    pub max_categories: Option<usize>,
+}
+
+impl Default for DummiesOptions {
+    fn default() -> Self {
+        Self {
+            boolean: false,
+            null_column: false,
+            max_categories: Some(10_000),
+        }
+    }
+}
+
+impl DummiesOptions {
+    pub fn and_boolean(mut self, boolean: bool) -> Self {
+        self.boolean = boolean;
+        self
+    }
+
+    pub fn and_null_column(mut self, null_column: bool) -> Self {
+        self.null_column = null_column;
+        self
+    }
+
+    pub fn and_max_categories(mut self, max_categories: Option<usize>) -> Self {
+        self.max_categories = max_categories;
+        self
+    }
+}
+
+/// An indicator column of which all values are allocated upfront as 0.
+trait Indicator {
+    fn zeroed(len: usize) -> Self;
+
+    fn set(&mut self, idx: usize);
+
+    fn finish(self, name: &str, len: usize) -> Series;
+}
+
+impl Indicator for AlignedVec<<DummyType as ArrowPrimitiveType>::Native> {
+    fn zeroed(len: usize) -> Self {
+        (0..len).map(|_| 0).collect_trusted()
+    }
+
+    #[inline]
+    fn set(&mut self, idx: usize) {
+        self.inner[idx] = 1;
+    }
+
+    fn finish(self, name: &str, _len: usize) -> Series {
+        ChunkedArray::<DummyType>::new_from_aligned_vec(name, self).into_series()
+    }
+}
+
+/// A bit-packed Boolean indicator column.
+impl Indicator for MutableBuffer {
+    fn zeroed(len: usize) -> Self {
+        MutableBuffer::from_len_zeroed(bit_util::ceil(len, 8))
+    }
+
+    #[inline]
+    fn set(&mut self, idx: usize) {
+        bit_util::set_bit(self.as_slice_mut(), idx);
+    }
+
+    fn finish(self, name: &str, len: usize) -> Series {
+        let data = ArrayData::new(
+            ArrowDataType::Boolean,
+            len,
+            Some(0),
+            None,
+            0,
+            vec![self.into()],
+            vec![],
+        );
+        BooleanChunked::new_from_chunks(name, vec![Arc::new(BooleanArray::from(data))])
+            .into_series()
+    }
+}
+
+/// Make `name` unique among the `used` names by appending a `_{n}` suffix and mark it as used.
+pub(crate) fn unique_name(name: &str, used: &mut PlHashSet<String>) -> String {
+    let mut out = name.to_string();
+    let mut n = 1;
+    while used.contains(&out) {
+        out = format!("{}_{}", name, n);
+        n += 1;
+    }
+    used.insert(out.clone());
+    out
+}
+
+/// The name of the indicator column of `value`. Control characters, such as newlines, are
+/// replaced by `_`.
+fn indicator_name(column: &str, value: &str) -> String {
+    let mut name = String::with_capacity(column.len() + value.len() + 1);
+    name.push_str(column);
+    name.push('_');
+    name.extend(value.chars().map(|c| if c.is_control() { '_' } else { c }));
+    name
+}
+
+fn check_n_categories(name: &str, n_categories: usize, options: &DummiesOptions) -> Result<()> {
+    match options.max_categories {
+        Some(max) if n_categories > max => Err(PolarsError::ValueError(
+            format!(
+                "column '{}' has {} distinct values, which exceeds the maximum of {} dummy columns",
+                name, n_categories, max
+            )
+            .into(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Create the indicator columns of the column `name` with `len` rows in a single pass over
+/// `codes`. The `categories` are the stringified distinct values, indexed by their code, and
+/// `codes` yields the row index and the code of the rows, `None` for a null value.
+fn fill_indicators<B, I>(
+    name: &str,
+    len: usize,
+    categories: &[String],
+    codes: I,
+    null_column: bool,
+) -> DataFrame
+where
+    B: Indicator,
+    I: Iterator<Item = (usize, Option<u32>)>,
+{
+    let mut indicators = (0..categories.len())
+        .map(|_| Some(B::zeroed(len)))
+        .collect::<Vec<_>>();
+    let mut null_indicator = if null_column {
+        Some(B::zeroed(len))
+    } else {
+        None
+    };
+    for (idx, code) in codes {
+        match (code, &mut null_indicator) {
+            (Some(code), _) => indicators[code as usize].as_mut().unwrap().set(idx),
+            (None, Some(null_indicator)) => null_indicator.set(idx),
+            (None, None) => {}
+        }
+    }
+
+    // the null indicator keeps its name, values that clash with it get a suffix
+    let null_name = indicator_name(name, "null");
+    let mut used = PlHashSet::default();
+    if null_indicator.is_some() {
+        used.insert(null_name.clone());
+    }
+    let mut names = categories
+        .iter()
+        .enumerate()
+        .map(|(code, value)| (indicator_name(name, value), code))
+        .collect::<Vec<_>>();
+    names.sort_unstable();
+
+    let mut columns = names
+        .into_iter()
+        .map(|(indicator, code)| {
+            let indicator_name = unique_name(&indicator, &mut used);
+            indicators[code]
+                .take()
+                .unwrap()
+                .finish(&indicator_name, len)
+        })
+        .collect::<Vec<_>>();
+    if let Some(null_indicator) = null_indicator {
+        columns.push(null_indicator.finish(&null_name, len));
+    }
+    DataFrame::new_no_checks(columns)
+}
+
+fn finish_dummies<I>(
+    name: &str,
+    len: usize,
+    categories: &[String],
+    codes: I,
+    has_nulls: bool,
+    options: &DummiesOptions,
+) -> DataFrame
+where
+    I: Iterator<Item = (usize, Option<u32>)>,
+{
+    let null_column = options.null_column && has_nulls;
+    if options.boolean {
+        fill_indicators::<MutableBuffer, _>(name, len, categories, codes, null_column)
+    } else {
+        fill_indicators::<AlignedVec<<DummyType as ArrowPrimitiveType>::Native>, _>(
+            name,
+            len,
+            categories,
+            codes,
+            null_column,
+        )
+    }
+}
+
+/// Create the dummies of a column from its groups. `value` stringifies the value at a row index,
+/// and returns `None` for a null value.
+fn dummies_from_groups<F>(
+    name: &str,
+    len: usize,
+    groups: &GroupTuples,
+    value: F,
+    null_count: usize,
+    options: &DummiesOptions,
+) -> Result<DataFrame>
+where
+    F: Fn(usize) -> Option<String>,
+{
+    let has_nulls = null_count > 0;
+    check_n_categories(name, groups.len() - has_nulls as usize, options)?;
+
+    let mut categories = Vec::with_capacity(groups.len());
+    let group_codes = groups
+        .iter()
+        .map(|(first, _)| {
+            value(*first as usize).map(|v| {
+                categories.push(v);
+                (categories.len() - 1) as u32
+            })
+        })
+        .collect::<Vec<_>>();
+    let codes = groups
+        .iter()
+        .zip(group_codes)
+        .flat_map(|((_, idx), code)| idx.iter().map(move |&i| (i as usize, code)));
+
+    Ok(finish_dummies(
+        name,
+        len,
+        &categories,
+        codes,
+        has_nulls,
+        options,
+    ))
+}
+
+impl ToDummies<Utf8Type> for Utf8Chunked {
+    fn to_dummies(&self, options: &DummiesOptions) -> Result<DataFrame> {
+        let groups = self.group_tuples(true);
+        dummies_from_groups(
+            self.name(),
+            self.len(),
+            &groups,
+            |idx| self.get(idx).map(|v| v.to_string()),
+            self.null_count(),
+            options,
+        )
+    }
+}
+
+impl<T> ToDummies<T> for ChunkedArray<T>
+where
+    T: PolarsIntegerType + Sync,
+    T::Native: Hash + Eq + Display + NumCast,
+    ChunkedArray<T>: ChunkOps + ChunkCompare<T::Native> + ChunkUnique<T>,
+{
+    fn to_dummies(&self, options: &DummiesOptions) -> Result<DataFrame> {
+        let groups = self.group_tuples(true);
+        dummies_from_groups(
+            self.name(),
+            self.len(),
+            &groups,
+            |idx| self.get(idx).map(|v| v.to_string()),
+            self.null_count(),
+            options,
+        )
+    }
+}
+
+/// The categories are taken from the `RevMapping`, so every category of the mapping gets an
+/// indicator column, also if it doesn't occur in this array.
+impl ToDummies<CategoricalType> for CategoricalChunked {
+    fn to_dummies(&self, options: &DummiesOptions) -> Result<DataFrame> {
+        let rev_map = self.categorical_map.as_ref().unwrap();
+        check_n_categories(self.name(), rev_map.len(), options)?;
+
+        let categories = match &**rev_map {
+            RevMapping::Local(a) | RevMapping::Global(_, a, _, _) => (0..a.len())
+                .map(|idx| a.value(idx).to_string())
+                .collect::<Vec<_>>(),
+        };
+        let physical = self.cast::<UInt32Type>()?;
+        let codes = physical.into_iter().enumerate();
+        let df = match &**rev_map {
+            RevMapping::Local(_) => finish_dummies(
+                self.name(),
+                self.len(),
+                &categories,
+                codes,
+                self.null_count() > 0,
+                options,
+            ),
+            RevMapping::Global(map, _, _, _) => finish_dummies(
+                self.name(),
+                self.len(),
+                &categories,
+                codes.map(|(idx, opt_v)| (idx, opt_v.map(|v| map[&v]))),
+                self.null_count() > 0,
+                options,
+            ),
+        };
+        Ok(df)
+    }
+}
+
+impl ToDummies<ListType> for ListChunked {}
+#[cfg(feature = "object")]
+impl<T> ToDummies<ObjectType<T>> for ObjectChunked<T> {}
+impl ToDummies<Float32Type> for Float32Chunked {}
+impl ToDummies<Float64Type> for Float64Chunked {}
+impl ToDummies<BooleanType> for BooleanChunked {}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+
+    #[test]
+    #[cfg(feature = "dtype-u8")]
+    fn test_to_dummies_nulls_and_names() -> Result<()> {
+        let s = Series::new(
+            "a",
+            &[Some("x"), None, Some("null"), Some("x\ny"), Some("x")],
+        );
+        let dummies = s.to_dummies()?;
+        assert_eq!(dummies.get_column_names(), &["a_null", "a_x", "a_x_y"]);
+        assert_eq!(
+            Vec::from(dummies.column("a_x")?.u8()?),
+            &[Some(1), Some(0), Some(0), Some(0), Some(1)]
+        );
+
+        let options = DummiesOptions::default()
+            .and_boolean(true)
+            .and_null_column(true);
+        let dummies = s.to_dummies_with_options(&options)?;
+        // the null indicator keeps its name
+        assert_eq!(
+            dummies.get_column_names(),
+            &["a_null_1", "a_x", "a_x_y", "a_null"]
+        );
+        assert_eq!(
+            Vec::from(dummies.column("a_null")?.bool()?),
+            &[
+                Some(false),
+                Some(true),
+                Some(false),
+                Some(false),
+                Some(false)
+            ]
+        );
+        assert_eq!(
+            Vec::from(dummies.column("a_x")?.bool()?),
+            &[
+                Some(true),
+                Some(false),
+                Some(false),
+                Some(false),
+                Some(true)
+            ]
+        );
+
+        let options = DummiesOptions::default().and_max_categories(Some(2));
+        assert!(matches!(
+            s.to_dummies_with_options(&options),
+            Err(PolarsError::ValueError(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "dtype-u8")]
+    fn test_to_dummies_categorical() -> Result<()> {
+        let _lock = crate::SINGLE_LOCK.lock();
+        for use_cache in &[false, true] {
+            toggle_string_cache(*use_cache);
+            let s = Series::new("a", &[Some("b"), Some("a"), None, Some("b")])
+                .cast::<CategoricalType>()?;
+            let dummies = s.to_dummies()?;
+            assert_eq!(dummies.get_column_names(), &["a_a", "a_b"]);
+            assert_eq!(
+                Vec::from(dummies.column("a_b")?.u8()?),
+                &[Some(1), Some(0), Some(0), Some(1)]
+            );
+        }
+        toggle_string_cache(false);
+        Ok(())
+    }
+}
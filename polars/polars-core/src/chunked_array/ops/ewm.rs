@@ -0,0 +1,352 @@
+use crate::prelude::*;
+use arrow::array::{ArrayRef, PrimitiveArray};
+use num::{Float, NumCast};
+
+/// Options for the exponentially weighted functions.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct EWMOptions {
+    /// Smoothing factor `0 < alpha <= 1`.
+    pub alpha: f64,
+    /// Divide by the decaying adjustment factor in the beginning periods, the weight of the
+    /// observation `i` steps back is `(1 - alpha)^i`. If `false` the recursive definition
+    /// `y_t = (1 - alpha) * y_{t-1} + alpha * x_t` is used.
+    pub adjust: bool,
+    /// If `false` the variance and standard deviation are corrected for the statistical bias.
+    pub bias: bool,
+    /// The minimal number of observations before a value is returned, before that the output is
+    /// null.
+    pub min_periods: usize,
+    /// Null values don't update the state. If `false` a null value returns null, if `true` the
+    /// last value is repeated.
+    pub fill_null_forward: bool,
+}
+
+impl Default for EWMOptions {
+    fn default() -> Self {
+        Self {
+            alpha: 0.5,
+            adjust: true,
+            bias: false,
+            min_periods: 1,
+            fill_null_forward: false,
+        }
+    }
+}
+
+impl EWMOptions {
+    pub fn new(alpha: f64) -> Self {
+        Self {
+            alpha,
+            ..Default::default()
+        }
+    }
+
+    pub fn and_adjust(mut self, adjust: bool) -> Self {
+        self.adjust = adjust;
+        self
+    }
+
+    pub fn and_bias(mut self, bias: bool) -> Self {
+        self.bias = bias;
+        self
+    }
+
+    pub fn and_min_periods(mut self, min_periods: usize) -> Self {
+        self.min_periods = min_periods;
+        self
+    }
+
+    pub fn and_fill_null_forward(mut self, fill_null_forward: bool) -> Self {
+        self.fill_null_forward = fill_null_forward;
+        self
+    }
+
+    fn check(&self) -> Result<()> {
+        if self.alpha > 0.0 && self.alpha <= 1.0 {
+            Ok(())
+        } else {
+            Err(PolarsError::ValueError(
+                format!("alpha should be in the range (0, 1], got {}", self.alpha).into(),
+            ))
+        }
+    }
+}
+
+/// The decayed state of the exponentially weighted mean and variance. This is the online
+/// algorithm of the weighted mean and covariance, where the weights of the previous observations
+/// decay by `1 - alpha` for every new observation.
+struct EWMState<T> {
+    decay: T,
+    new_wt: T,
+    adjust: bool,
+    mean: T,
+    cov: T,
+    old_wt: T,
+    sum_wt: T,
+    sum_wt2: T,
+    n_obs: usize,
+}
+
+impl<T: Float> EWMState<T> {
+    fn new(options: &EWMOptions) -> Self {
+        let alpha: T = NumCast::from(options.alpha).unwrap();
+        Self {
+            decay: T::one() - alpha,
+            new_wt: if options.adjust { T::one() } else { alpha },
+            adjust: options.adjust,
+            mean: T::zero(),
+            cov: T::zero(),
+            old_wt: T::one(),
+            sum_wt: T::one(),
+            sum_wt2: T::one(),
+            n_obs: 0,
+        }
+    }
+
+    fn update(&mut self, x: T) {
+        self.n_obs += 1;
+        if self.n_obs == 1 {
+            self.mean = x;
+            return;
+        }
+        self.sum_wt = self.sum_wt * self.decay;
+        self.sum_wt2 = self.sum_wt2 * self.decay * self.decay;
+        self.old_wt = self.old_wt * self.decay;
+
+        let old_mean = self.mean;
+        let total_wt = self.old_wt + self.new_wt;
+        if self.mean != x {
+            self.mean = (self.old_wt * old_mean + self.new_wt * x) / total_wt;
+        }
+        let d_old = old_mean - self.mean;
+        let d_new = x - self.mean;
+        self.cov =
+            (self.old_wt * (self.cov + d_old * d_old) + self.new_wt * d_new * d_new) / total_wt;
+
+        self.sum_wt = self.sum_wt + self.new_wt;
+        self.sum_wt2 = self.sum_wt2 + self.new_wt * self.new_wt;
+        self.old_wt = total_wt;
+        if !self.adjust {
+            self.sum_wt = self.sum_wt / self.old_wt;
+            self.sum_wt2 = self.sum_wt2 / (self.old_wt * self.old_wt);
+            self.old_wt = T::one();
+        }
+    }
+
+    fn mean(&self) -> Option<T> {
+        Some(self.mean)
+    }
+
+    fn var(&self, bias: bool) -> Option<T> {
+        if bias {
+            return Some(self.cov);
+        }
+        let numerator = self.sum_wt * self.sum_wt;
+        let denominator = numerator - self.sum_wt2;
+        if denominator > T::zero() {
+            Some(numerator / denominator * self.cov)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> ChunkedArray<T>
+where
+    T: PolarsFloatType,
+    T::Native: Float,
+{
+    /// Run the state over all values and compute the output with `f`. The state is carried
+    /// across the chunks, so the chunks of the output are aligned with the input.
+    fn ewm_apply<F>(&self, options: &EWMOptions, f: F) -> Result<Self>
+    where
+        F: Fn(&EWMState<T::Native>) -> Option<T::Native>,
+    {
+        options.check()?;
+        let min_periods = std::cmp::max(options.min_periods, 1);
+        let mut state = EWMState::new(options);
+
+        let chunks = self
+            .downcast_iter()
+            .map(|array| {
+                let out: PrimitiveArray<T> = array
+                    .into_iter()
+                    .map(|opt_v| {
+                        match opt_v {
+                            Some(v) => state.update(v),
+                            None if options.fill_null_forward => {}
+                            None => return None,
+                        }
+                        if state.n_obs >= min_periods {
+                            f(&state)
+                        } else {
+                            None
+                        }
+                    })
+                    .collect();
+                Arc::new(out) as ArrayRef
+            })
+            .collect();
+        Ok(ChunkedArray::new_from_chunks(self.name(), chunks))
+    }
+
+    /// Compute the exponentially weighted mean.
+    pub fn ewm_mean(&self, options: &EWMOptions) -> Result<Self> {
+        self.ewm_apply(options, |state| state.mean())
+    }
+
+    /// Compute the exponentially weighted variance. The first value is null if the variance is
+    /// bias corrected, as the correction is undefined for a single observation.
+    pub fn ewm_var(&self, options: &EWMOptions) -> Result<Self> {
+        let bias = options.bias;
+        self.ewm_apply(options, |state| state.var(bias))
+    }
+
+    /// Compute the exponentially weighted standard deviation.
+    pub fn ewm_std(&self, options: &EWMOptions) -> Result<Self> {
+        let bias = options.bias;
+        self.ewm_apply(options, |state| state.var(bias).map(|v| v.sqrt()))
+    }
+}
+
+impl Series {
+    /// Apply an exponentially weighted function. Float32 stays Float32, the other numeric types
+    /// are cast to Float64.
+    fn ewm_dispatch(
+        &self,
+        options: &EWMOptions,
+        f32_fn: fn(&Float32Chunked, &EWMOptions) -> Result<Float32Chunked>,
+        f64_fn: fn(&Float64Chunked, &EWMOptions) -> Result<Float64Chunked>,
+    ) -> Result<Series> {
+        match self.dtype() {
+            DataType::Float32 => f32_fn(self.f32().unwrap(), options).map(|ca| ca.into_series()),
+            _ if self.is_numeric() => {
+                let s = self.cast::<Float64Type>()?;
+                f64_fn(s.f64().unwrap(), options).map(|ca| ca.into_series())
+            }
+            dt => Err(PolarsError::InvalidOperation(
+                format!(
+                    "exponentially weighted functions not supported for dtype {:?}",
+                    dt
+                )
+                .into(),
+            )),
+        }
+    }
+
+    /// Compute the exponentially weighted mean. See [EWMOptions] for the options.
+    pub fn ewm_mean(&self, options: &EWMOptions) -> Result<Series> {
+        self.ewm_dispatch(options, Float32Chunked::ewm_mean, Float64Chunked::ewm_mean)
+    }
+
+    /// Compute the exponentially weighted variance. See [EWMOptions] for the options.
+    pub fn ewm_var(&self, options: &EWMOptions) -> Result<Series> {
+        self.ewm_dispatch(options, Float32Chunked::ewm_var, Float64Chunked::ewm_var)
+    }
+
+    /// Compute the exponentially weighted standard deviation. See [EWMOptions] for the options.
+    pub fn ewm_std(&self, options: &EWMOptions) -> Result<Series> {
+        self.ewm_dispatch(options, Float32Chunked::ewm_std, Float64Chunked::ewm_std)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn assert_close(out: &Series, expected: &[Option<f64>]) {
+        let out = Vec::from(out.f64().unwrap());
+        assert_eq!(out.len(), expected.len());
+        for (a, b) in out.iter().zip(expected) {
+            match (a, b) {
+                (Some(a), Some(b)) => assert!((a - b).abs() < 1e-12, "{} != {}", a, b),
+                (a, b) => assert_eq!(a, b),
+            }
+        }
+    }
+
+    #[test]
+    fn test_ewm_reference_values() -> Result<()> {
+        let s = Series::new("a", &[Some(1), Some(2), None, Some(3), Some(4)]);
+
+        let options = EWMOptions::new(0.5);
+        assert_close(
+            &s.ewm_mean(&options)?,
+            &[
+                Some(1.0),
+                Some(5.0 / 3.0),
+                None,
+                Some(17.0 / 7.0),
+                Some(49.0 / 15.0),
+            ],
+        );
+        assert_close(
+            &s.ewm_var(&options)?,
+            &[None, Some(0.5), None, Some(13.0 / 14.0), Some(97.0 / 70.0)],
+        );
+        assert_close(
+            &s.ewm_var(&options.and_bias(true))?,
+            &[
+                Some(0.0),
+                Some(2.0 / 9.0),
+                None,
+                Some(26.0 / 49.0),
+                Some(194.0 / 225.0),
+            ],
+        );
+
+        let options = EWMOptions::new(0.5).and_adjust(false);
+        assert_close(
+            &s.ewm_mean(&options)?,
+            &[Some(1.0), Some(1.5), None, Some(2.25), Some(3.125)],
+        );
+        assert_close(
+            &s.ewm_var(&options)?,
+            &[None, Some(0.5), None, Some(1.1), Some(71.0 / 42.0)],
+        );
+        assert_close(
+            &s.ewm_std(&options.and_bias(true))?,
+            &[
+                Some(0.0),
+                Some(0.5),
+                None,
+                Some(0.6875f64.sqrt()),
+                Some(1.109375f64.sqrt()),
+            ],
+        );
+
+        // min periods and carrying the last value forward over nulls
+        let options = EWMOptions::new(0.5)
+            .and_adjust(false)
+            .and_min_periods(2)
+            .and_fill_null_forward(true);
+        assert_close(
+            &s.ewm_mean(&options)?,
+            &[None, Some(1.5), Some(1.5), Some(2.25), Some(3.125)],
+        );
+
+        assert!(s.ewm_mean(&EWMOptions::new(0.0)).is_err());
+        assert!(Series::new("a", &["a"]).ewm_mean(&options).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_ewm_chunks() -> Result<()> {
+        let options = EWMOptions::new(0.3);
+        let values = [1.0, 4.0, 2.0, 8.0, 5.0, 7.0];
+        let single = Float64Chunked::new_from_slice("a", &values);
+        let mut chunked = Float64Chunked::new_from_slice("a", &values[..2]);
+        chunked.append(&Float64Chunked::new_from_slice("a", &values[2..5]));
+        chunked.append(&Float64Chunked::new_from_slice("a", &values[5..]));
+
+        let out = chunked.ewm_var(&options)?;
+        assert_eq!(out.chunks().len(), 3);
+        assert!(out
+            .into_series()
+            .series_equal(&single.ewm_var(&options)?.into_series()));
+
+        let s = Float32Chunked::new_from_slice("a", &[1.0, 2.0]).into_series();
+        assert_eq!(s.ewm_mean(&options)?.dtype(), &DataType::Float32);
+        Ok(())
+    }
+}
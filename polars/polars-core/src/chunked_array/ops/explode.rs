@@ -9,7 +9,7 @@ use itertools::Itertools;
 use std::convert::TryFrom;
 
 /// Convert Arrow array offsets to indexes of the original list
-pub(crate) fn offsets_to_indexes(offsets: &[i64], capacity: usize) -> AlignedVec<u32> {
+pub(crate) fn offsets_to_indexes(offsets: &[i64], capacity: usize) -> AlignedVec<IdxSize> {
     let mut idx = AlignedVec::with_capacity_aligned(capacity);
 
     let mut count = 0;
@@ -0,0 +1,128 @@
+use crate::prelude::*;
+
+/// Determines which bounds of an interval are included.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ClosedInterval {
+    Both,
+    Left,
+    Right,
+    None,
+}
+
+impl ClosedInterval {
+    fn include_low(self) -> bool {
+        matches!(self, ClosedInterval::Both | ClosedInterval::Left)
+    }
+
+    fn include_high(self) -> bool {
+        matches!(self, ClosedInterval::Both | ClosedInterval::Right)
+    }
+}
+
+/// Combine the comparisons with the lower and the upper bound. The output is null if the value is
+/// null. A comparison with a null bound is null, so a null bound gives null, or `false` if the
+/// comparison with the other bound is `false`.
+fn is_between_impl<T, L, H>(
+    values: &T,
+    low: L,
+    high: H,
+    closed: ClosedInterval,
+    name: &str,
+) -> BooleanChunked
+where
+    T: ChunkCompare<L> + ChunkCompare<H>,
+{
+    let lower = if closed.include_low() {
+        values.gt_eq(low)
+    } else {
+        values.gt(low)
+    };
+    let upper = if closed.include_high() {
+        values.lt_eq(high)
+    } else {
+        values.lt(high)
+    };
+    let mut out = &lower & &upper;
+    out.rename(name);
+    out
+}
+
+impl<T> ChunkedArray<T>
+where
+    T: PolarsNumericType,
+    T::Native: NumComp,
+{
+    /// Get a mask of the values that are between `low` and `high`. Bounds of length 1 are
+    /// broadcasted. `closed` determines if the bounds are included.
+    pub fn is_between(
+        &self,
+        low: &ChunkedArray<T>,
+        high: &ChunkedArray<T>,
+        closed: ClosedInterval,
+    ) -> BooleanChunked {
+        is_between_impl(self, low, high, closed, self.name())
+    }
+}
+
+impl Series {
+    /// Get a mask of the values that are between `low` and `high`. Bounds of length 1 are
+    /// broadcasted. `closed` determines if the bounds are included.
+    ///
+    /// The output is null where the value is null. Where a bound is null the output is null, or
+    /// `false` if the value is outside the other bound.
+    pub fn is_between(
+        &self,
+        low: &Series,
+        high: &Series,
+        closed: ClosedInterval,
+    ) -> BooleanChunked {
+        is_between_impl(self, low, high, closed, self.name())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_is_between() {
+        let s = Series::new("a", &[Some(1), Some(2), None, Some(3), Some(4)]);
+        let low = Series::new("low", &[2]);
+        let high = Series::new("high", &[3.0]);
+
+        let out = s.is_between(&low, &high, ClosedInterval::Both);
+        assert_eq!(out.name(), "a");
+        assert_eq!(
+            Vec::from(&out),
+            &[Some(false), Some(true), None, Some(true), Some(false)]
+        );
+        let out = s.is_between(&low, &high, ClosedInterval::Left);
+        assert_eq!(
+            Vec::from(&out),
+            &[Some(false), Some(true), None, Some(false), Some(false)]
+        );
+        let out = s.is_between(&low, &high, ClosedInterval::Right);
+        assert_eq!(
+            Vec::from(&out),
+            &[Some(false), Some(false), None, Some(true), Some(false)]
+        );
+        let out = s.is_between(&low, &high, ClosedInterval::None);
+        assert_eq!(
+            Vec::from(&out),
+            &[Some(false), Some(false), None, Some(false), Some(false)]
+        );
+
+        // bounds per element
+        let ca = Int32Chunked::new_from_slice("a", &[1, 5, 3]);
+        let low = Int32Chunked::new_from_slice("low", &[0, 6, 3]);
+        let high = Int32Chunked::new_from_slice("high", &[1, 7, 3]);
+        let out = ca.is_between(&low, &high, ClosedInterval::Both);
+        assert_eq!(Vec::from(&out), &[Some(true), Some(false), Some(true)]);
+
+        // a null bound is null, unless the value is outside the other bound
+        let low = Int32Chunked::new_from_opt_slice("low", &[None, None, Some(0)]);
+        let high = Int32Chunked::new_from_opt_slice("high", &[Some(1), Some(4), None]);
+        let out = ca.is_between(&low, &high, ClosedInterval::Both);
+        assert_eq!(Vec::from(&out), &[None, Some(false), None]);
+    }
+}
@@ -5,7 +5,7 @@ use crate::chunked_array::object::ObjectType;
 use crate::prelude::*;
 use crate::series::implementations::SeriesWrap;
 use crate::utils::NoNull;
-use arrow::array::{ArrayRef, UInt32Array};
+use arrow::array::ArrayRef;
 use std::marker::Sized;
 
 pub(crate) mod aggregate;
@@ -13,20 +13,29 @@ pub(crate) mod any_value;
 pub(crate) mod apply;
 pub(crate) mod bit_repr;
 pub(crate) mod chunkops;
+pub(crate) mod clip;
 pub(crate) mod compare_inner;
 pub(crate) mod cum_agg;
 pub(crate) mod downcast;
+pub(crate) mod dummies;
+#[cfg(feature = "ewma")]
+pub(crate) mod ewm;
 pub(crate) mod explode;
 pub(crate) mod fill_none;
 pub(crate) mod filter;
+pub(crate) mod is_between;
 #[cfg(feature = "is_in")]
 pub(crate) mod is_in;
 pub(crate) mod peaks;
 #[cfg(feature = "repeat_by")]
 pub(crate) mod repeat_by;
+#[cfg(feature = "search_sorted")]
+pub(crate) mod search_sorted;
 pub(crate) mod set;
 pub(crate) mod shift;
 pub(crate) mod sort;
+#[cfg(feature = "concat_str")]
+pub(crate) mod str_concat;
 pub(crate) mod take;
 pub(crate) mod take_random;
 pub(crate) mod take_single;
@@ -252,6 +261,38 @@ pub trait ChunkWindowCustom<T> {
             "rolling mean not supported for this datatype".into(),
         ))
     }
+
+    /// Apply a custom function over a rolling window.
+    ///
+    /// For every value, the closure receives the window of at most `window_size` values that
+    /// ends at that value, oldest value first. The windows at the start of the array are shorter.
+    /// If the window has less than `min_periods` non-null values, the output is null.
+    ///
+    /// The windows are slices into a single buffer, so no `Series` is created per window.
+    fn rolling_apply<F>(&self, _window_size: u32, _f: F, _min_periods: u32) -> Result<Self>
+    where
+        F: Fn(&[Option<T>]) -> Option<T>,
+        Self: std::marker::Sized,
+    {
+        Err(PolarsError::InvalidOperation(
+            "rolling apply not supported for this datatype".into(),
+        ))
+    }
+
+    /// Apply a custom function over a rolling window of an array without null values.
+    /// This is faster than [rolling_apply](ChunkWindowCustom::rolling_apply) as the windows are
+    /// slices of the values of the array.
+    ///
+    /// Returns an error if the array has null values.
+    fn rolling_apply_no_null<F>(&self, _window_size: u32, _f: F, _min_periods: u32) -> Result<Self>
+    where
+        F: Fn(&[T]) -> T,
+        Self: std::marker::Sized,
+    {
+        Err(PolarsError::InvalidOperation(
+            "rolling apply not supported for this datatype".into(),
+        ))
+    }
 }
 
 /// Random access
@@ -295,7 +336,7 @@ where
     I: Iterator<Item = usize>,
     INulls: Iterator<Item = Option<usize>>,
 {
-    Array(&'a UInt32Array),
+    Array(&'a IdxArr),
     Iter(I),
     // will return a null where None
     IterNulls(INulls),
@@ -305,8 +346,8 @@ pub type Dummy<T> = std::iter::Once<T>;
 pub type TakeIdxIter<'a, I> = TakeIdx<'a, I, Dummy<Option<usize>>>;
 pub type TakeIdxIterNull<'a, INull> = TakeIdx<'a, Dummy<usize>, INull>;
 
-impl<'a> From<&'a UInt32Chunked> for TakeIdx<'a, Dummy<usize>, Dummy<Option<usize>>> {
-    fn from(ca: &'a UInt32Chunked) -> Self {
+impl<'a> From<&'a IdxCa> for TakeIdx<'a, Dummy<usize>, Dummy<Option<usize>>> {
+    fn from(ca: &'a IdxCa) -> Self {
         if ca.chunks.len() == 1 {
             TakeIdx::Array(ca.downcast_iter().next().unwrap())
         } else {
@@ -590,7 +631,7 @@ pub trait ChunkUnique<T> {
 
     /// Get first index of the unique values in a `ChunkedArray`.
     /// This Vec is sorted.
-    fn arg_unique(&self) -> Result<UInt32Chunked>;
+    fn arg_unique(&self) -> Result<IdxCa>;
 
     /// Number of unique values in the `ChunkedArray`
     fn n_unique(&self) -> Result<usize> {
@@ -620,9 +661,10 @@ pub trait ChunkUnique<T> {
 }
 
 pub trait ToDummies<T>: ChunkUnique<T> {
-    fn to_dummies(&self) -> Result<DataFrame> {
+    /// Create an indicator column for every distinct value.
+    fn to_dummies(&self, _options: &DummiesOptions) -> Result<DataFrame> {
         Err(PolarsError::InvalidOperation(
-            "is_duplicated is not implemented for this dtype".into(),
+            "to_dummies is not implemented for this dtype".into(),
         ))
     }
 }
@@ -636,10 +678,10 @@ pub trait ChunkSort<T> {
     fn sort_in_place(&mut self, reverse: bool);
 
     /// Retrieve the indexes needed to sort this array.
-    fn argsort(&self, reverse: bool) -> UInt32Chunked;
+    fn argsort(&self, reverse: bool) -> IdxCa;
 
     /// Retrieve the indexes need to sort this and the other arrays.
-    fn argsort_multiple(&self, _other: &[Series], _reverse: &[bool]) -> Result<UInt32Chunked> {
+    fn argsort_multiple(&self, _other: &[Series], _reverse: &[bool]) -> Result<IdxCa> {
         Err(PolarsError::InvalidOperation(
             "argsort_multiple not implemented for this dtype".into(),
         ))
@@ -777,12 +819,10 @@ impl ChunkFull<&Series> for ListChunked {
 }
 
 impl ChunkFullNull for ListChunked {
+    /// The inner type of the lists is unknown, use
+    /// [full_null_with_dtype](ListChunked::full_null_with_dtype) to set it.
     fn full_null(name: &str, length: usize) -> ListChunked {
-        let mut ca = (0..length)
-            .map::<Option<Series>, _>(|_| None)
-            .collect::<Self>();
-        ca.rename(name);
-        ca
+        ListChunked::full_null_with_dtype(name, length, &ArrowDataType::Null)
     }
 }
 
@@ -912,15 +952,34 @@ impl ChunkExpandAtIndex<CategoricalType> for CategoricalChunked {
 }
 
 impl ChunkExpandAtIndex<ListType> for ListChunked {
-    fn expand_at_index(&self, _index: usize, _length: usize) -> ListChunked {
-        unimplemented!()
+    fn expand_at_index(&self, index: usize, length: usize) -> ListChunked {
+        match self.get(index) {
+            // every list is a slice of the same values, so they are copied without a builder
+            // per value type
+            Some(s) => {
+                let mut builder = ListSlicesChunkedBuilder::new(self.name(), &s, length);
+                for _ in 0..length {
+                    builder.append_series_slices(std::iter::once((0, s.len())));
+                }
+                builder.finish()
+            }
+            None => match self.dtype() {
+                DataType::List(inner) => {
+                    ListChunked::full_null_with_dtype(self.name(), length, inner)
+                }
+                _ => unreachable!(),
+            },
+        }
     }
 }
 
 #[cfg(feature = "object")]
-impl<T> ChunkExpandAtIndex<ObjectType<T>> for ObjectChunked<T> {
-    fn expand_at_index(&self, _index: usize, _length: usize) -> ObjectChunked<T> {
-        todo!()
+impl<T: PolarsObject> ChunkExpandAtIndex<ObjectType<T>> for ObjectChunked<T> {
+    fn expand_at_index(&self, index: usize, length: usize) -> ObjectChunked<T> {
+        let opt_val = TakeRandom::get(&self, index);
+        let mut ca: Self = (0..length).map(|_| opt_val.cloned()).collect();
+        ca.rename(self.name());
+        ca
     }
 }
 
@@ -1001,6 +1060,19 @@ pub trait RepeatBy {
     }
 }
 
+/// Find the indices at which values are inserted to keep an array sorted.
+#[cfg(feature = "search_sorted")]
+#[cfg_attr(docsrs, doc(cfg(feature = "search_sorted")))]
+pub trait ChunkSearchSorted<T: PolarsDataType> {
+    /// Binary search the index at which every value of `values` would be inserted in the sorted
+    /// array. Values are cast to the data type of the array first.
+    fn search_sorted(&self, _values: &Series, _options: &SearchSortedOptions) -> Result<IdxCa> {
+        Err(PolarsError::InvalidOperation(
+            format!("search_sorted not supported by {:?}", T::get_dtype()).into(),
+        ))
+    }
+}
+
 #[cfg(feature = "is_first")]
 #[cfg_attr(docsrs, doc(cfg(feature = "is_first")))]
 pub trait IsFirst<T: PolarsDataType> {
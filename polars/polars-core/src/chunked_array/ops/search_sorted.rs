@@ -0,0 +1,367 @@
+//! Binary search for the indices at which values are inserted in a sorted ChunkedArray.
+use super::ChunkSearchSorted;
+use crate::prelude::*;
+use crate::utils::{get_supertype, NoNull};
+use arrow::array::{Array, ArrayRef};
+use std::cmp::Ordering;
+
+/// The side of a run of equal values at which a value is inserted.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SearchSortedSide {
+    /// Insert before the equal values, at the index of the first value that is not smaller.
+    Left,
+    /// Insert after the equal values, at the index of the first value that is larger.
+    Right,
+}
+
+/// Options of [search_sorted_with_options](crate::series::SeriesTrait::search_sorted_with_options).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SearchSortedOptions {
+    pub side: SearchSortedSide,
+    /// Trust that the array is sorted in the direction of `descending` if its sorted flag isn't
+    /// set, instead of checking the values. The result of searching an unsorted array is
+    /// unspecified.
+    pub assume_sorted: bool,
+    /// The array is sorted in descending order. Only used with `assume_sorted`, otherwise the
+    /// direction is taken from the sorted flags or the values.
+    pub descending: bool,
+}
+
+impl Default for SearchSortedOptions {
+    fn default() -> Self {
+        Self {
+            side: SearchSortedSide::Left,
+            assume_sorted: false,
+            descending: false,
+        }
+    }
+}
+
+impl SearchSortedOptions {
+    pub fn and_side(mut self, side: SearchSortedSide) -> Self {
+        self.side = side;
+        self
+    }
+
+    pub fn and_assume_sorted(mut self, assume_sorted: bool) -> Self {
+        self.assume_sorted = assume_sorted;
+        self
+    }
+
+    pub fn and_descending(mut self, descending: bool) -> Self {
+        self.descending = descending;
+        self
+    }
+}
+
+/// Order the values like an ascending sort: null values before all other values and NaN before
+/// all numbers.
+#[inline]
+fn compare_nulls_first<V: PartialOrd>(a: &Option<V>, b: &Option<V>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.partial_cmp(b).unwrap_or_else(|| {
+            // at least one of the values is NaN
+            #[allow(clippy::eq_op)]
+            match (a != a, b != b) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Less,
+                _ => Ordering::Greater,
+            }
+        }),
+        (None, Some(_)) => Ordering::Less,
+        (Some(_), None) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// The first index in `0..len` for which `pred` is false. `pred` must be true for a prefix of
+/// the range.
+fn partition_point<F: Fn(usize) -> bool>(len: usize, pred: F) -> usize {
+    let (mut lo, mut hi) = (0, len);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if pred(mid) {
+            lo = mid + 1
+        } else {
+            hi = mid
+        }
+    }
+    lo
+}
+
+/// Maps an index of a ChunkedArray to its chunk and the index in that chunk, by a binary search
+/// over the cumulative lengths of the chunks. The chunks are searched without rechunking.
+struct ChunkOffsets(Vec<usize>);
+
+impl ChunkOffsets {
+    fn new(chunks: &[ArrayRef]) -> Self {
+        let mut offset = 0;
+        ChunkOffsets(
+            chunks
+                .iter()
+                .map(|arr| {
+                    let start = offset;
+                    offset += arr.len();
+                    start
+                })
+                .collect(),
+        )
+    }
+
+    /// `idx` must be in bounds. Empty chunks are skipped, as the last chunk that starts at or
+    /// before `idx` is taken.
+    #[inline]
+    fn locate(&self, idx: usize) -> (usize, usize) {
+        let chunk = partition_point(self.0.len(), |i| self.0[i] <= idx) - 1;
+        (chunk, idx - self.0[chunk])
+    }
+}
+
+/// Whether the values are sorted in descending order, `None` if they aren't sorted. Equal values
+/// are sorted in both directions, those are reported as ascending.
+fn sorted_descending<V, I>(values: I) -> Option<bool>
+where
+    V: PartialOrd,
+    I: Iterator<Item = Option<V>>,
+{
+    let mut ascending = true;
+    let mut descending = true;
+    let mut prev = None;
+    for v in values {
+        if let Some(prev) = &prev {
+            match compare_nulls_first(prev, &v) {
+                Ordering::Less => descending = false,
+                Ordering::Greater => ascending = false,
+                Ordering::Equal => {}
+            }
+            if !ascending && !descending {
+                return None;
+            }
+        }
+        prev = Some(v);
+    }
+    Some(!ascending)
+}
+
+/// Determine the direction of the search from the sorted flags, the options or by checking the
+/// values. Returns `true` for a descending array.
+fn search_direction<T, F>(
+    ca: &ChunkedArray<T>,
+    options: &SearchSortedOptions,
+    check: F,
+) -> Result<bool>
+where
+    F: FnOnce() -> Option<bool>,
+{
+    if ca.is_sorted() {
+        Ok(false)
+    } else if ca.is_sorted_reverse() {
+        Ok(true)
+    } else if options.assume_sorted {
+        Ok(options.descending)
+    } else {
+        check().ok_or_else(|| {
+            PolarsError::InvalidOperation(
+                format!(
+                    "search_sorted requires column '{}' to be sorted, set `assume_sorted` to skip this check",
+                    ca.name()
+                )
+                .into(),
+            )
+        })
+    }
+}
+
+/// Binary search the insertion index of every value. `get` returns the value of the sorted array
+/// at an index, `None` for a null value.
+fn search_sorted_by<V, G, I>(
+    name: &str,
+    len: usize,
+    get: G,
+    values: I,
+    descending: bool,
+    side: SearchSortedSide,
+) -> IdxCa
+where
+    V: PartialOrd,
+    G: Fn(usize) -> Option<V>,
+    I: Iterator<Item = Option<V>>,
+{
+    let mut ca = values
+        .map(|value| {
+            let idx = partition_point(len, |idx| {
+                let mut ord = compare_nulls_first(&get(idx), &value);
+                if descending {
+                    ord = ord.reverse();
+                }
+                match side {
+                    SearchSortedSide::Left => ord == Ordering::Less,
+                    SearchSortedSide::Right => ord != Ordering::Greater,
+                }
+            });
+            idx as IdxSize
+        })
+        .collect::<NoNull<IdxCa>>()
+        .into_inner();
+    ca.rename(name);
+    ca
+}
+
+impl<T> ChunkSearchSorted<T> for ChunkedArray<T>
+where
+    T: PolarsNumericType,
+{
+    fn search_sorted(&self, values: &Series, options: &SearchSortedOptions) -> Result<IdxCa> {
+        // numeric values are compared in the supertype, e.g. a float value is not truncated to
+        // the integer dtype of this array
+        if values.is_numeric() {
+            let dtype = get_supertype(self.dtype(), values.dtype())?;
+            if &dtype != self.dtype() {
+                return self
+                    .cast_with_dtype(&dtype)?
+                    .search_sorted_with_options(values, options);
+            }
+        }
+        let values = values.cast_with_dtype(self.dtype())?;
+        let values = self.unpack_series_matching_type(&values)?;
+        let descending = search_direction(self, options, || sorted_descending(self.into_iter()))?;
+
+        let offsets = ChunkOffsets::new(&self.chunks);
+        let arrs = self.downcast_iter().collect::<Vec<_>>();
+        let get = |idx| {
+            let (chunk, idx) = offsets.locate(idx);
+            let arr = arrs[chunk];
+            if arr.is_null(idx) {
+                None
+            } else {
+                Some(arr.value(idx))
+            }
+        };
+        Ok(search_sorted_by(
+            self.name(),
+            self.len(),
+            get,
+            values.into_iter(),
+            descending,
+            options.side,
+        ))
+    }
+}
+
+impl ChunkSearchSorted<Utf8Type> for Utf8Chunked {
+    fn search_sorted(&self, values: &Series, options: &SearchSortedOptions) -> Result<IdxCa> {
+        let values = values.cast_with_dtype(&DataType::Utf8)?;
+        let values = values.utf8()?;
+        let descending = search_direction(self, options, || sorted_descending(self.into_iter()))?;
+
+        let offsets = ChunkOffsets::new(&self.chunks);
+        let arrs = self.downcast_iter().collect::<Vec<_>>();
+        let get = |idx| {
+            let (chunk, idx) = offsets.locate(idx);
+            let arr = arrs[chunk];
+            if arr.is_null(idx) {
+                None
+            } else {
+                Some(arr.value(idx))
+            }
+        };
+        Ok(search_sorted_by(
+            self.name(),
+            self.len(),
+            get,
+            values.into_iter(),
+            descending,
+            options.side,
+        ))
+    }
+}
+
+impl ChunkSearchSorted<BooleanType> for BooleanChunked {}
+impl ChunkSearchSorted<ListType> for ListChunked {}
+impl ChunkSearchSorted<CategoricalType> for CategoricalChunked {}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+
+    fn search(s: &Series, values: &Series, side: SearchSortedSide) -> Vec<Option<IdxSize>> {
+        Vec::from(&s.search_sorted(values, side).unwrap())
+    }
+
+    #[test]
+    fn test_search_sorted_sides() {
+        let values = Series::new("v", &[0, 1, 2, 3, 4]);
+        // two chunks, with a run of equal values over the chunk boundary
+        let mut s = Series::new("a", &[1, 2, 2]);
+        s.append(&Series::new("a", &[2, 3])).unwrap();
+        assert_eq!(s.n_chunks(), 2);
+
+        assert_eq!(
+            search(&s, &values, SearchSortedSide::Left),
+            &[Some(0), Some(0), Some(1), Some(4), Some(5)]
+        );
+        assert_eq!(
+            search(&s, &values, SearchSortedSide::Right),
+            &[Some(0), Some(1), Some(4), Some(5), Some(5)]
+        );
+
+        let s = Series::new("a", &["b", "d", "d"]);
+        let values = Series::new("v", &["a", "d", "e"]);
+        assert_eq!(
+            search(&s, &values, SearchSortedSide::Left),
+            &[Some(0), Some(1), Some(3)]
+        );
+        assert_eq!(
+            search(&s, &values, SearchSortedSide::Right),
+            &[Some(0), Some(3), Some(3)]
+        );
+    }
+
+    #[test]
+    fn test_search_sorted_descending_and_nulls() {
+        let s = Series::new("a", &[Some(3), Some(2), Some(2), Some(1), None]);
+        let values = Series::new("v", &[Some(4), Some(2), Some(0), None]);
+        assert_eq!(
+            search(&s, &values, SearchSortedSide::Left),
+            &[Some(0), Some(1), Some(4), Some(4)]
+        );
+        assert_eq!(
+            search(&s, &values, SearchSortedSide::Right),
+            &[Some(0), Some(3), Some(4), Some(5)]
+        );
+
+        // nulls are sorted first in ascending order
+        let s = s.sort(false);
+        assert_eq!(
+            search(&s, &values, SearchSortedSide::Left),
+            &[Some(5), Some(2), Some(1), Some(0)]
+        );
+    }
+
+    #[test]
+    fn test_search_sorted_unsorted() {
+        let s = Series::new("a", &[1, 3, 2]);
+        let values = Series::new("v", &[3]);
+        assert!(matches!(
+            s.search_sorted(&values, SearchSortedSide::Left),
+            Err(PolarsError::InvalidOperation(_))
+        ));
+        let options = SearchSortedOptions::default().and_assume_sorted(true);
+        assert!(s.search_sorted_with_options(&values, &options).is_ok());
+    }
+
+    #[test]
+    fn test_search_sorted_supertype() {
+        let s = Series::new("a", &[1i64, 2, 3]);
+        // the float values are not truncated to integers
+        let values = Series::new("v", &[1.5f64, 2.0, -0.5]);
+        assert_eq!(
+            search(&s, &values, SearchSortedSide::Left),
+            &[Some(1), Some(1), Some(0)]
+        );
+        assert_eq!(
+            search(&s, &values, SearchSortedSide::Right),
+            &[Some(1), Some(2), Some(0)]
+        );
+    }
+}
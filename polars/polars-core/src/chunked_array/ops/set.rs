@@ -1,41 +1,158 @@
 use crate::prelude::*;
-use crate::utils::{align_chunks_binary, CustomIterTools};
-use arrow::array::ArrayRef;
-use polars_arrow::kernels::set::{set_at_idx_no_null, set_with_mask};
+use crate::utils::align_chunks_binary;
+use arrow::array::{Array, ArrayData, ArrayRef, BooleanArray};
+use arrow::buffer::{Buffer, MutableBuffer};
+use arrow::util::bit_util::{self, set_bit, unset_bit};
+use polars_arrow::kernels::set::set_with_mask;
 use std::sync::Arc;
 
-macro_rules! impl_set_at_idx_with {
-    ($self:ident, $builder:ident, $idx:ident, $f:ident) => {{
-        let mut idx_iter = $idx.into_iter();
-        let mut ca_iter = $self.into_iter().enumerate();
-
-        while let Some(current_idx) = idx_iter.next() {
-            if current_idx > $self.len() {
-                return Err(PolarsError::OutOfBounds(
-                    format!(
-                        "index: {} outside of ChunkedArray with length: {}",
-                        current_idx,
-                        $self.len()
-                    )
-                    .into(),
-                ));
-            }
-            while let Some((cnt_idx, opt_val)) = ca_iter.next() {
-                if cnt_idx == current_idx {
-                    $builder.append_option($f(opt_val));
-                    break;
-                } else {
-                    $builder.append_option(opt_val);
+fn out_of_bounds(index: usize, len: usize) -> PolarsError {
+    PolarsError::IndexOutOfBounds { index, len }
+}
+
+/// Collect the indices in a boolean mask. This way the indices don't have to be sorted and the
+/// array only has to be traversed once.
+fn idx_to_mask<I: IntoIterator<Item = usize>>(idx: I, len: usize) -> Result<Vec<bool>> {
+    let mut mask = vec![false; len];
+    for idx in idx {
+        *mask.get_mut(idx).ok_or_else(|| out_of_bounds(idx, len))? = true;
+    }
+    Ok(mask)
+}
+
+/// Same as [idx_to_mask], but returns the mask as a `BooleanChunked`.
+fn idx_to_mask_ca<I: IntoIterator<Item = usize>>(idx: I, len: usize) -> Result<BooleanChunked> {
+    Ok(BooleanChunked::new_from_slice("", &idx_to_mask(idx, len)?))
+}
+
+/// Patch only the chunks in which `mask` selects a value. The other chunks are shared with `ca`,
+/// so setting a few values in a large array only copies the chunks that contain them.
+///
+/// The arrow buffers of a chunk may be shared with other arrays, e.g. slices, which we cannot
+/// observe. Therefore a chunk is never patched in place, but sharing the untouched chunks gives
+/// the same savings when the values that are set are clustered.
+fn patch_chunks<T, F>(ca: &ChunkedArray<T>, mask: &BooleanChunked, patch: F) -> ChunkedArray<T>
+where
+    T: PolarsDataType,
+    F: Fn(&ChunkedArray<T>, &BooleanChunked) -> ChunkedArray<T>,
+{
+    let (left, mask) = align_chunks_binary(ca, mask);
+    let mut chunks = Vec::with_capacity(left.chunks.len());
+    for (arr, mask_arr) in left.chunks.iter().zip(&mask.chunks) {
+        let mask_bool = mask_arr.as_any().downcast_ref::<BooleanArray>().unwrap();
+        if (0..mask_bool.len()).any(|i| mask_bool.is_valid(i) && mask_bool.value(i)) {
+            let chunk = ChunkedArray::<T>::new_from_chunks(ca.name(), vec![arr.clone()]);
+            let mask = BooleanChunked::new_from_chunks("", vec![mask_arr.clone()]);
+            chunks.extend(patch(&chunk, &mask).chunks);
+        } else {
+            chunks.push(arr.clone());
+        }
+    }
+    ChunkedArray::new_from_chunks(ca.name(), chunks)
+}
+
+fn all_set_bitmap(len: usize) -> MutableBuffer {
+    let num_bytes = bit_util::ceil(len, 8);
+    MutableBuffer::new(num_bytes).with_bitset(num_bytes, true)
+}
+
+/// Validity bitmap of a `ChunkedArray` that is being patched.
+/// The bitmap is only allocated if there are null values.
+struct Validity {
+    bitmap: Option<MutableBuffer>,
+    len: usize,
+}
+
+impl Validity {
+    /// Copy the validity of all chunks into a single bitmap.
+    fn from_chunks(chunks: &[ArrayRef], len: usize) -> Self {
+        let bitmap = if chunks.iter().any(|arr| arr.null_count() > 0) {
+            let mut bitmap = all_set_bitmap(len);
+            let slice = bitmap.as_slice_mut();
+            let mut offset = 0;
+            for arr in chunks {
+                if arr.null_count() > 0 {
+                    (0..arr.len())
+                        .filter(|i| arr.is_null(*i))
+                        .for_each(|i| unset_bit(slice, offset + i));
                 }
+                offset += arr.len();
             }
+            Some(bitmap)
+        } else {
+            None
+        };
+        Validity { bitmap, len }
+    }
+
+    #[inline]
+    fn set(&mut self, idx: usize, valid: bool) {
+        let len = self.len;
+        match (&mut self.bitmap, valid) {
+            (Some(bitmap), true) => set_bit(bitmap.as_slice_mut(), idx),
+            (None, true) => {}
+            (bitmap, false) => unset_bit(
+                bitmap
+                    .get_or_insert_with(|| all_set_bitmap(len))
+                    .as_slice_mut(),
+                idx,
+            ),
         }
-        // the last idx is probably not the last value so we finish the iterator
-        while let Some((_, opt_val)) = ca_iter.next() {
-            $builder.append_option(opt_val);
-        }
+    }
 
-        let ca = $builder.finish();
-        Ok(ca)
+    fn finish(self) -> Option<Buffer> {
+        self.bitmap.map(|bitmap| bitmap.into())
+    }
+}
+
+/// Copy the values of all chunks in a single buffer that can be patched.
+fn copy_values<T: PolarsNumericType>(ca: &ChunkedArray<T>) -> AlignedVec<T::Native> {
+    let mut av = AlignedVec::with_capacity_aligned(ca.len());
+    ca.downcast_iter()
+        .for_each(|arr| av.extend_from_slice(arr.values()));
+    av
+}
+
+/// Copy the values of all chunks in a single bitmap that can be patched.
+fn copy_bool_values(ca: &BooleanChunked) -> MutableBuffer {
+    let mut bitmap = MutableBuffer::from_len_zeroed(bit_util::ceil(ca.len(), 8));
+    let slice = bitmap.as_slice_mut();
+    let mut offset = 0;
+    ca.downcast_iter().for_each(|arr| {
+        (0..arr.len())
+            .filter(|i| arr.value(*i))
+            .for_each(|i| set_bit(slice, offset + i));
+        offset += arr.len();
+    });
+    bitmap
+}
+
+fn finish_bool(name: &str, values: MutableBuffer, validity: Validity) -> BooleanChunked {
+    let len = validity.len;
+    let data = ArrayData::new(
+        ArrowDataType::Boolean,
+        len,
+        None,
+        validity.finish(),
+        0,
+        vec![values.into()],
+        vec![],
+    );
+    let arr = Arc::new(BooleanArray::from(data)) as ArrayRef;
+    BooleanChunked::new_from_chunks(name, vec![arr])
+}
+
+macro_rules! impl_set_at_idx_with {
+    ($self:ident, $builder:ident, $idx:ident, $f:ident) => {{
+        let mask = idx_to_mask($idx, $self.len())?;
+        $self
+            .into_iter()
+            .zip(mask)
+            .for_each(|(opt_val, set)| match set {
+                true => $builder.append_option($f(opt_val)),
+                false => $builder.append_option(opt_val),
+            });
+        Ok($builder.finish())
     }};
 }
 
@@ -43,12 +160,22 @@ macro_rules! check_bounds {
     ($self:ident, $mask:ident) => {{
         if $self.len() != $mask.len() {
             return Err(PolarsError::ShapeMisMatch(
-                "Shape of parameter `mask` could not be used in `set` operation.".into(),
+                format!(
+                    "Shape of parameter `mask` could not be used in `set` operation: mask has length {}, ChunkedArray has length {}",
+                    $mask.len(),
+                    $self.len()
+                )
+                .into(),
             ));
         }
     }};
 }
 
+// Only the chunks that contain values to set are patched, see `patch_chunks`. The numeric and
+// boolean chunks are patched by copying the values and validity bitmap once and updating the
+// copies. Strings cannot be patched as their values have a variable length, so those chunks are
+// rebuilt in a single pass.
+
 impl<'a, T> ChunkSet<'a, T::Native, T::Native> for ChunkedArray<T>
 where
     T: PolarsNumericType,
@@ -58,37 +185,8 @@ where
         idx: I,
         value: Option<T::Native>,
     ) -> Result<Self> {
-        if self.null_count() == 0 {
-            if let Some(value) = value {
-                // fast path uses kernel
-                if self.chunks.len() == 1 {
-                    let arr = set_at_idx_no_null(
-                        self.downcast_iter().next().unwrap(),
-                        idx.into_iter(),
-                        value,
-                    )?;
-                    return Ok(Self::new_from_chunks(self.name(), vec![Arc::new(arr)]));
-                }
-                // Other fast path. Slightly slower as it does not do a memcpy
-                else {
-                    let mut av = self.into_no_null_iter().collect_trusted::<AlignedVec<_>>();
-                    let data = av.as_mut_slice();
-
-                    idx.into_iter().try_for_each::<_, Result<_>>(|idx| {
-                        let val = data.get_mut(idx).ok_or_else(|| {
-                            PolarsError::OutOfBounds(
-                                format!("{} out of bounds on array of length: {}", idx, self.len())
-                                    .into(),
-                            )
-                        })?;
-                        *val = value;
-                        Ok(())
-                    })?;
-                    return Ok(Self::new_from_aligned_vec(self.name(), av));
-                }
-            }
-        }
-        self.set_at_idx_with(idx, |_| value)
+        let mask = idx_to_mask_ca(idx, self.len())?;
+        self.set(&mask, value)
     }
 
     fn set_at_idx_with<I: IntoIterator<Item = usize>, F>(&'a self, idx: I, f: F) -> Result<Self>
@@ -101,36 +199,33 @@ where
 
     fn set(&'a self, mask: &BooleanChunked, value: Option<T::Native>) -> Result<Self> {
         check_bounds!(self, mask);
-
-        // Fast path uses the kernel in polars-arrow
-        if let Some(value) = value {
-            // kernel expects no null values in mask
-            if mask.null_count() == 0 {
-                let (left, mask) = align_chunks_binary(self, mask);
-
-                // apply binary kernel.
-                let chunks = left
-                    .downcast_iter()
-                    .into_iter()
-                    .zip(mask.downcast_iter())
-                    .map(|(arr, mask)| {
-                        let a = set_with_mask(arr, mask, value);
-                        Arc::new(a) as ArrayRef
-                    })
-                    .collect();
-                return Ok(ChunkedArray::new_from_chunks(self.name(), chunks));
+        Ok(patch_chunks(self, mask, |ca, mask| {
+            // Fast path uses the kernel in polars-arrow
+            if let Some(value) = value {
+                // kernel expects no null values in mask
+                if mask.null_count() == 0 {
+                    let arr = set_with_mask(
+                        ca.downcast_iter().next().unwrap(),
+                        mask.downcast_iter().next().unwrap(),
+                        value,
+                    );
+                    return ChunkedArray::new_from_chunks(ca.name(), vec![Arc::new(arr)]);
+                }
             }
-        }
-        // slow path, could be optimized.
-        let ca = mask
-            .into_iter()
-            .zip(self.into_iter())
-            .map(|(mask_val, opt_val)| match mask_val {
-                Some(true) => value,
-                _ => opt_val,
-            })
-            .collect();
-        Ok(ca)
+            let mut values = copy_values(ca);
+            let mut validity = Validity::from_chunks(&ca.chunks, ca.len());
+            let data = values.as_mut_slice();
+
+            mask.into_iter()
+                .enumerate()
+                .filter(|(_, mask_val)| *mask_val == Some(true))
+                .for_each(|(idx, _)| {
+                    data[idx] = value.unwrap_or_default();
+                    validity.set(idx, value.is_some());
+                });
+            let arr = values.into_primitive_array::<T>(validity.finish());
+            ChunkedArray::new_from_chunks(ca.name(), vec![Arc::new(arr)])
+        }))
     }
 
     fn set_with<F>(&'a self, mask: &BooleanChunked, f: F) -> Result<Self>
@@ -156,29 +251,37 @@ impl<'a> ChunkSet<'a, bool, bool> for BooleanChunked {
         idx: I,
         value: Option<bool>,
     ) -> Result<Self> {
-        self.set_at_idx_with(idx, |_| value)
+        let mask = idx_to_mask_ca(idx, self.len())?;
+        self.set(&mask, value)
     }
 
     fn set_at_idx_with<I: IntoIterator<Item = usize>, F>(&'a self, idx: I, f: F) -> Result<Self>
     where
         F: Fn(Option<bool>) -> Option<bool>,
     {
-        // TODO: implement fast path
         let mut builder = BooleanChunkedBuilder::new(self.name(), self.len());
         impl_set_at_idx_with!(self, builder, idx, f)
     }
 
     fn set(&'a self, mask: &BooleanChunked, value: Option<bool>) -> Result<Self> {
         check_bounds!(self, mask);
-        let ca = mask
-            .into_iter()
-            .zip(self.into_iter())
-            .map(|(mask_val, opt_val)| match mask_val {
-                Some(true) => value,
-                _ => opt_val,
-            })
-            .collect();
-        Ok(ca)
+        Ok(patch_chunks(self, mask, |ca, mask| {
+            let mut values = copy_bool_values(ca);
+            let mut validity = Validity::from_chunks(&ca.chunks, ca.len());
+            let data = values.as_slice_mut();
+
+            mask.into_iter()
+                .enumerate()
+                .filter(|(_, mask_val)| *mask_val == Some(true))
+                .for_each(|(idx, _)| {
+                    match value {
+                        Some(true) => set_bit(data, idx),
+                        _ => unset_bit(data, idx),
+                    }
+                    validity.set(idx, value.is_some());
+                });
+            finish_bool(ca.name(), values, validity)
+        }))
     }
 
     fn set_with<F>(&'a self, mask: &BooleanChunked, f: F) -> Result<Self>
@@ -207,37 +310,8 @@ impl<'a> ChunkSet<'a, &'a str, String> for Utf8Chunked {
     where
         Self: Sized,
     {
-        let idx_iter = idx.into_iter();
-        let mut ca_iter = self.into_iter().enumerate();
-        let mut builder = Utf8ChunkedBuilder::new(self.name(), self.len(), self.get_values_size());
-
-        for current_idx in idx_iter {
-            if current_idx > self.len() {
-                return Err(PolarsError::OutOfBounds(
-                    format!(
-                        "index: {} outside of ChunkedArray with length: {}",
-                        current_idx,
-                        self.len()
-                    )
-                    .into(),
-                ));
-            }
-            for (cnt_idx, opt_val_self) in &mut ca_iter {
-                if cnt_idx == current_idx {
-                    builder.append_option(opt_value);
-                    break;
-                } else {
-                    builder.append_option(opt_val_self);
-                }
-            }
-        }
-        // the last idx is probably not the last value so we finish the iterator
-        for (_, opt_val_self) in ca_iter {
-            builder.append_option(opt_val_self);
-        }
-
-        let ca = builder.finish();
-        Ok(ca)
+        let mask = idx_to_mask_ca(idx, self.len())?;
+        self.set(&mask, opt_value)
     }
 
     fn set_at_idx_with<I: IntoIterator<Item = usize>, F>(&'a self, idx: I, f: F) -> Result<Self>
@@ -254,15 +328,16 @@ impl<'a> ChunkSet<'a, &'a str, String> for Utf8Chunked {
         Self: Sized,
     {
         check_bounds!(self, mask);
-        let ca = mask
-            .into_iter()
-            .zip(self.into_iter())
-            .map(|(mask_val, opt_val)| match mask_val {
-                Some(true) => value,
-                _ => opt_val,
-            })
-            .collect();
-        Ok(ca)
+        Ok(patch_chunks(self, mask, |ca, mask| {
+            let mut builder = Utf8ChunkedBuilder::new(ca.name(), ca.len(), ca.get_values_size());
+            ca.into_iter()
+                .zip(mask)
+                .for_each(|(opt_val, opt_mask)| match opt_mask {
+                    Some(true) => builder.append_option(value),
+                    _ => builder.append_option(opt_val),
+                });
+            builder.finish()
+        }))
     }
 
     fn set_with<F>(&'a self, mask: &BooleanChunked, f: F) -> Result<Self>
@@ -285,6 +360,7 @@ impl<'a> ChunkSet<'a, &'a str, String> for Utf8Chunked {
 #[cfg(test)]
 mod test {
     use crate::prelude::*;
+    use arrow::array::{Array, ArrayRef};
 
     #[test]
     fn test_set() {
@@ -341,4 +417,83 @@ mod test {
         let ca = ca.set(&mask, Some(true)).unwrap();
         assert_eq!(Vec::from(&ca), &[Some(false), Some(true), Some(true)]);
     }
+
+    #[test]
+    fn test_set_validity() -> Result<()> {
+        // multiple chunks and unsorted indices
+        let mut ca = Int32Chunked::new_from_opt_slice("a", &[Some(1), None, Some(3)]);
+        ca.append(&Int32Chunked::new_from_slice("a", &[4, 5]));
+        let out = ca.set_at_idx(vec![4, 1, 0], Some(10))?;
+        assert_eq!(
+            Vec::from(&out),
+            &[Some(10), Some(10), Some(3), Some(4), Some(10)]
+        );
+        assert_eq!(out.name(), "a");
+        assert_eq!(out.null_count(), 0);
+
+        // set to null on an array without a validity bitmap
+        let ca = Int32Chunked::new_from_slice("a", &[1, 2, 3]);
+        let out = ca.set_at_idx(vec![2], None)?;
+        assert_eq!(Vec::from(&out), &[Some(1), Some(2), None]);
+        assert_eq!(out.null_count(), 1);
+        let mask = BooleanChunked::new_from_opt_slice("mask", &[Some(true), None, Some(false)]);
+        let out = out.set(&mask, None)?;
+        assert_eq!(Vec::from(&out), &[None, Some(2), None]);
+
+        // booleans
+        let ca = BooleanChunked::new_from_opt_slice("a", &[Some(true), None, Some(false)]);
+        let out = ca.set_at_idx(vec![1, 2], Some(true))?;
+        assert_eq!(Vec::from(&out), &[Some(true), Some(true), Some(true)]);
+        let out = ca.set_at_idx(vec![0], None)?;
+        assert_eq!(Vec::from(&out), &[None, None, Some(false)]);
+        let mask = BooleanChunked::new_from_slice("mask", &[false, true, true]);
+        let out = ca.set(&mask, Some(false))?;
+        assert_eq!(Vec::from(&out), &[Some(true), Some(false), Some(false)]);
+
+        // utf8
+        let ca = Utf8Chunked::new_from_opt_slice("a", &[Some("a"), None, Some("c")]);
+        let out = ca.set_at_idx(vec![2, 1], Some("b"))?;
+        assert_eq!(Vec::from(&out), &[Some("a"), Some("b"), Some("b")]);
+        let out = ca.set_at_idx(vec![0], None)?;
+        assert_eq!(Vec::from(&out), &[None, None, Some("c")]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_errors() {
+        let ca = Int32Chunked::new_from_slice("a", &[1, 2, 3]);
+        let err = ca.set_at_idx(vec![3], Some(1)).unwrap_err();
+        assert!(matches!(
+            err,
+            PolarsError::IndexOutOfBounds { index: 3, len: 3 }
+        ));
+        let ca = Utf8Chunked::new_from_slice("a", &["a", "b"]);
+        assert!(ca.set_at_idx(vec![2], Some("c")).is_err());
+        let ca = BooleanChunked::new_from_slice("a", &[true]);
+        assert!(ca.set_at_idx(vec![1], Some(false)).is_err());
+
+        let mask = BooleanChunked::new_from_slice("mask", &[true, false]);
+        let ca = Int32Chunked::new_from_slice("a", &[1, 2, 3]);
+        let err = ca.set(&mask, Some(1)).unwrap_err();
+        assert!(format!("{:?}", err).contains("mask has length 2, ChunkedArray has length 3"));
+    }
+
+    #[test]
+    fn test_set_shares_untouched_chunks() {
+        let values_ptr = |arr: &ArrayRef| arr.data().buffers()[0].as_ptr();
+
+        let mut ca = Int32Chunked::new_from_slice("a", &[1, 2]);
+        ca.append(&Int32Chunked::new_from_slice("a", &[3, 4]));
+        let out = ca.set_at_idx(vec![3], None).unwrap();
+        assert_eq!(Vec::from(&out), &[Some(1), Some(2), Some(3), None]);
+        assert_eq!(values_ptr(&out.chunks()[0]), values_ptr(&ca.chunks()[0]));
+        assert_ne!(values_ptr(&out.chunks()[1]), values_ptr(&ca.chunks()[1]));
+
+        let mut ca = Utf8Chunked::new_from_slice("a", &["a", "b"]);
+        ca.append(&Utf8Chunked::new_from_slice("a", &["c"]));
+        let mask = BooleanChunked::new_from_slice("mask", &[true, false, false]);
+        let out = ca.set(&mask, Some("d")).unwrap();
+        assert_eq!(Vec::from(&out), &[Some("d"), Some("b"), Some("c")]);
+        assert_eq!(values_ptr(&out.chunks()[1]), values_ptr(&ca.chunks()[1]));
+    }
 }
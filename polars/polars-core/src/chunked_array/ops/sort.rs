@@ -1,3 +1,4 @@
+use crate::chunked_array::StatValue;
 use crate::prelude::compare_inner::PartialOrdInner;
 use crate::prelude::*;
 use crate::utils::{CustomIterTools, NoNull};
@@ -102,12 +103,32 @@ fn sort_parallel<T>(ca: &ChunkedArray<T>) -> bool {
             .unwrap_or(1000000)
 }
 
+/// Check with the sorted flags and the cached statistics if sorting can be skipped.
+fn is_already_sorted<T>(ca: &ChunkedArray<T>, reverse: bool) -> bool
+where
+    T: PolarsNumericType,
+{
+    if (reverse && ca.is_sorted_reverse()) || (!reverse && ca.is_sorted()) {
+        return true;
+    }
+    // floats are excluded because NaN values are ignored by min/max
+    if ca.null_count() > 0 || matches!(T::get_dtype(), DataType::Float32 | DataType::Float64) {
+        return false;
+    }
+    // if all values are equal, the array is sorted in both directions. The statistics of
+    // integers are exact, so this compares the native values and not their f64 approximation.
+    matches!(
+        ca.statistics().min_max(),
+        Some((Some(StatValue::Int(min)), Some(StatValue::Int(max)))) if min == max
+    )
+}
+
 macro_rules! argsort {
     ($self:expr, $reverse:expr) => {{
         let sort_parallel = sort_parallel($self);
 
         let mut vals = Vec::with_capacity($self.len());
-        let mut count: u32 = 0;
+        let mut count: IdxSize = 0;
         $self.downcast_iter().for_each(|arr| {
             let iter = arr.iter().map(|v| {
                 let i = count;
@@ -124,7 +145,7 @@ macro_rules! argsort {
             |(_, a), (_, b)| a.partial_cmp(b).unwrap(),
             |(_, a), (_, b)| b.partial_cmp(a).unwrap(),
         );
-        let ca: NoNull<UInt32Chunked> = vals.into_iter().map(|(idx, _v)| idx).collect_trusted();
+        let ca: NoNull<IdxCa> = vals.into_iter().map(|(idx, _v)| idx).collect_trusted();
         let mut ca = ca.into_inner();
         ca.rename($self.name());
         ca
@@ -137,6 +158,11 @@ where
     T::Native: std::cmp::PartialOrd,
 {
     fn sort(&self, reverse: bool) -> ChunkedArray<T> {
+        if is_already_sorted(self, reverse) {
+            let mut ca = self.clone();
+            ca.set_sorted(reverse);
+            return ca;
+        }
         let sort_parallel = sort_parallel(self);
 
         if let Ok(vals) = self.cont_slice() {
@@ -160,7 +186,9 @@ where
                 order_reverse,
             );
 
-            return ChunkedArray::new_from_aligned_vec(self.name(), new);
+            let mut ca = ChunkedArray::new_from_aligned_vec(self.name(), new);
+            ca.set_sorted(reverse);
+            return ca;
         }
 
         if self.null_count() == 0 {
@@ -186,14 +214,18 @@ where
     fn sort_in_place(&mut self, reverse: bool) {
         let sorted = self.sort(reverse);
         self.chunks = sorted.chunks;
+        // the statistics don't depend on the order, only the sorted flags and chunks change
+        self.reset_chunk_statistics();
+        self.bit_settings = 0;
+        self.set_sorted(reverse);
     }
 
-    fn argsort(&self, reverse: bool) -> UInt32Chunked {
+    fn argsort(&self, reverse: bool) -> IdxCa {
         let sort_parallel = sort_parallel(self);
 
-        let ca: NoNull<UInt32Chunked> = if self.null_count() == 0 {
+        let ca: NoNull<IdxCa> = if self.null_count() == 0 {
             let mut vals = Vec::with_capacity(self.len());
-            let mut count: u32 = 0;
+            let mut count: IdxSize = 0;
             self.downcast_iter().for_each(|arr| {
                 let values = arr.values();
                 let iter = values.iter().map(|&v| {
@@ -215,7 +247,7 @@ where
             vals.into_iter().map(|(idx, _v)| idx).collect_trusted()
         } else {
             let mut vals = Vec::with_capacity(self.len());
-            let mut count: u32 = 0;
+            let mut count: IdxSize = 0;
             self.downcast_iter().for_each(|arr| {
                 let iter = arr.iter().map(|v| {
                     let i = count;
@@ -244,7 +276,7 @@ where
     ///
     /// This function is very opinionated.
     /// We assume that all numeric `Series` are of the same type, if not it will panic
-    fn argsort_multiple(&self, other: &[Series], reverse: &[bool]) -> Result<UInt32Chunked> {
+    fn argsort_multiple(&self, other: &[Series], reverse: &[bool]) -> Result<IdxCa> {
         for ca in other {
             assert_eq!(self.len(), ca.len());
         }
@@ -263,7 +295,7 @@ where
 
         let compare_inner: Vec<_> = other.iter().map(|s| s.into_partial_ord_inner()).collect();
 
-        let mut count: u32 = 0;
+        let mut count: IdxSize = 0;
         let mut vals: Vec<_> = self
             .into_iter()
             .map(|v| {
@@ -287,7 +319,7 @@ where
                 (_, ord) => ord,
             },
         );
-        let ca: NoNull<UInt32Chunked> = vals.into_iter().map(|(idx, _v)| idx).collect();
+        let ca: NoNull<IdxCa> = vals.into_iter().map(|(idx, _v)| idx).collect();
         let mut ca = ca.into_inner();
         ca.set_sorted(reverse[0]);
         Ok(ca)
@@ -348,9 +380,12 @@ impl ChunkSort<Utf8Type> for Utf8Chunked {
     fn sort_in_place(&mut self, reverse: bool) {
         let sorted = self.sort(reverse);
         self.chunks = sorted.chunks;
+        self.reset_chunk_statistics();
+        self.bit_settings = 0;
+        self.set_sorted(reverse);
     }
 
-    fn argsort(&self, reverse: bool) -> UInt32Chunked {
+    fn argsort(&self, reverse: bool) -> IdxCa {
         argsort!(self, reverse)
     }
 
@@ -363,7 +398,7 @@ impl ChunkSort<Utf8Type> for Utf8Chunked {
     /// In this case we assume that all numeric `Series` are `f64` types. The caller needs to
     /// uphold this contract. If not, it will panic.
     ///
-    fn argsort_multiple(&self, other: &[Series], reverse: &[bool]) -> Result<UInt32Chunked> {
+    fn argsort_multiple(&self, other: &[Series], reverse: &[bool]) -> Result<IdxCa> {
         for ca in other {
             if self.len() != ca.len() {
                 return Err(PolarsError::ShapeMisMatch(
@@ -372,7 +407,7 @@ impl ChunkSort<Utf8Type> for Utf8Chunked {
             }
         }
         assert_eq!(other.len(), reverse.len() - 1);
-        let mut count: u32 = 0;
+        let mut count: IdxSize = 0;
         let mut vals: Vec<_> = self
             .into_iter()
             .map(|v| {
@@ -397,7 +432,7 @@ impl ChunkSort<Utf8Type> for Utf8Chunked {
                 (_, ord) => ord,
             },
         );
-        let ca: NoNull<UInt32Chunked> = vals.into_iter().map(|(idx, _v)| idx).collect();
+        let ca: NoNull<IdxCa> = vals.into_iter().map(|(idx, _v)| idx).collect();
         let mut ca = ca.into_inner();
         ca.set_sorted(reverse[0]);
         Ok(ca)
@@ -413,7 +448,7 @@ impl ChunkSort<CategoricalType> for CategoricalChunked {
         self.deref_mut().sort_in_place(reverse)
     }
 
-    fn argsort(&self, reverse: bool) -> UInt32Chunked {
+    fn argsort(&self, reverse: bool) -> IdxCa {
         self.deref().argsort(reverse)
     }
 }
@@ -427,7 +462,7 @@ impl ChunkSort<ListType> for ListChunked {
         unimplemented!()
     }
 
-    fn argsort(&self, _reverse: bool) -> UInt32Chunked {
+    fn argsort(&self, _reverse: bool) -> IdxCa {
         unimplemented!()
     }
 }
@@ -442,7 +477,7 @@ impl<T> ChunkSort<ObjectType<T>> for ObjectChunked<T> {
         unimplemented!()
     }
 
-    fn argsort(&self, _reverse: bool) -> UInt32Chunked {
+    fn argsort(&self, _reverse: bool) -> IdxCa {
         unimplemented!()
     }
 }
@@ -455,9 +490,12 @@ impl ChunkSort<BooleanType> for BooleanChunked {
     fn sort_in_place(&mut self, reverse: bool) {
         let sorted = self.sort(reverse);
         self.chunks = sorted.chunks;
+        self.reset_chunk_statistics();
+        self.bit_settings = 0;
+        self.set_sorted(reverse);
     }
 
-    fn argsort(&self, reverse: bool) -> UInt32Chunked {
+    fn argsort(&self, reverse: bool) -> IdxCa {
         argsort!(self, reverse)
     }
 }
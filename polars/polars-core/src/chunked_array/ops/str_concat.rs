@@ -0,0 +1,127 @@
+use crate::prelude::*;
+
+/// Number of bytes needed to concatenate the values of `iter`, separated by `delimiter`.
+fn concat_bytes_len<'a, I>(iter: I, delimiter: &str, null_value: Option<&str>) -> usize
+where
+    I: Iterator<Item = Option<&'a str>>,
+{
+    let (n_values, n_bytes) = iter
+        .filter_map(|opt_v| opt_v.or(null_value))
+        .fold((0, 0), |(n_values, n_bytes), v| {
+            (n_values + 1, n_bytes + v.len())
+        });
+    n_bytes + n_values.saturating_sub(1) * delimiter.len()
+}
+
+fn concat_into<'a, I>(buf: &mut String, iter: I, delimiter: &str, null_value: Option<&str>)
+where
+    I: Iterator<Item = Option<&'a str>>,
+{
+    for (i, v) in iter.filter_map(|opt_v| opt_v.or(null_value)).enumerate() {
+        if i > 0 {
+            buf.push_str(delimiter);
+        }
+        buf.push_str(v);
+    }
+}
+
+impl Utf8Chunked {
+    /// Concatenate all values into a single string, separated by `delimiter`.
+    ///
+    /// Null values are skipped, unless a `null_value` placeholder is given. The result
+    /// always has length 1; an empty or all null array results in an empty string.
+    pub fn str_concat(&self, delimiter: &str, null_value: Option<&str>) -> Utf8Chunked {
+        let bytes_cap = concat_bytes_len(self.into_iter(), delimiter, null_value);
+        let mut buf = String::with_capacity(bytes_cap);
+        concat_into(&mut buf, self.into_iter(), delimiter, null_value);
+
+        let mut builder = Utf8ChunkedBuilder::new(self.name(), 1, bytes_cap);
+        builder.append_value(&buf);
+        builder.finish()
+    }
+}
+
+impl ListChunked {
+    /// Join the string values of every list into a single string, separated by `delimiter`.
+    ///
+    /// Null values within a list are skipped, unless a `null_value` placeholder is given.
+    /// Null lists remain null.
+    pub fn lst_join(&self, delimiter: &str, null_value: Option<&str>) -> Result<Utf8Chunked> {
+        if self.inner_dtype() != DataType::Utf8 {
+            return Err(PolarsError::InvalidOperation(
+                format!(
+                    "cannot join list with inner dtype {:?}, expected Utf8",
+                    self.inner_dtype()
+                )
+                .into(),
+            ));
+        }
+        // first determine the total size, so that the values buffer is allocated only once
+        let bytes_cap = self
+            .into_iter()
+            .map(|opt_s| {
+                opt_s
+                    .map(|s| concat_bytes_len(s.utf8().unwrap().into_iter(), delimiter, null_value))
+                    .unwrap_or(0)
+            })
+            .sum();
+        let mut builder = Utf8ChunkedBuilder::new(self.name(), self.len(), bytes_cap);
+
+        // use a string buffer, to amortize alloc
+        let mut buf = String::with_capacity(128);
+        for opt_s in self.into_iter() {
+            match opt_s {
+                Some(s) => {
+                    buf.clear();
+                    concat_into(
+                        &mut buf,
+                        s.utf8().unwrap().into_iter(),
+                        delimiter,
+                        null_value,
+                    );
+                    builder.append_value(&buf);
+                }
+                None => builder.append_null(),
+            }
+        }
+        Ok(builder.finish())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_str_concat() {
+        let ca = Utf8Chunked::new_from_opt_slice("a", &[Some("foo"), None, Some("bar")]);
+        let out = ca.str_concat("-", None);
+        assert_eq!(out.name(), "a");
+        assert_eq!(Vec::from(&out), &[Some("foo-bar")]);
+        let out = ca.str_concat("-", Some("null"));
+        assert_eq!(Vec::from(&out), &[Some("foo-null-bar")]);
+
+        let ca = Utf8Chunked::new_from_opt_slice("a", &[None::<&str>]);
+        assert_eq!(Vec::from(&ca.str_concat("-", None)), &[Some("")]);
+    }
+
+    #[test]
+    fn test_lst_join() -> Result<()> {
+        let mut builder = get_list_builder(&DataType::Utf8, 6, 3, "a");
+        builder.append_series(&Series::new("", &[Some("a"), None, Some("b")]));
+        builder.append_null();
+        builder.append_series(&Series::new("", &["c"]));
+        let ca = builder.finish();
+
+        let out = ca.lst_join(", ", None)?;
+        assert_eq!(Vec::from(&out), &[Some("a, b"), None, Some("c")]);
+        let out = ca.lst_join(", ", Some("?"))?;
+        assert_eq!(Vec::from(&out), &[Some("a, ?, b"), None, Some("c")]);
+
+        let s = Series::new("a", &[1, 2]);
+        let mut builder = get_list_builder(&DataType::Int32, 2, 1, "a");
+        builder.append_series(&s);
+        assert!(builder.finish().lst_join(", ", None).is_err());
+        Ok(())
+    }
+}
@@ -56,6 +56,37 @@ macro_rules! take_opt_iter_n_chunks_unchecked {
     }};
 }
 
+/// Collect indices that may be None into an index array, asserting that they are in bounds of
+/// an array of length `len`. The index array is taken with the one pass kernels of `take_unchecked`.
+fn checked_opt_idx<I>(iter: I, len: usize) -> IdxCa
+where
+    I: Iterator<Item = Option<usize>>,
+{
+    iter.map(|opt_idx| {
+        opt_idx.map(|idx| {
+            assert!(
+                idx < len,
+                "take index {} is out of bounds for length {}",
+                idx,
+                len
+            );
+            idx as IdxSize
+        })
+    })
+    .collect()
+}
+
+/// An empty array has no valid index, so only None indices can be taken from it. Panics on the
+/// first index that is not None.
+fn assert_no_index_into_empty<I>(mut iter: I)
+where
+    I: Iterator<Item = Option<usize>>,
+{
+    if let Some(idx) = iter.find_map(|opt_idx| opt_idx) {
+        panic!("take index {} is out of bounds for length 0", idx)
+    }
+}
+
 impl<T> ChunkTake for ChunkedArray<T>
 where
     T: PolarsNumericType,
@@ -146,6 +177,13 @@ where
         match indices {
             TakeIdx::Array(array) => {
                 if self.is_empty() || array.null_count() == array.len() {
+                    if self.is_empty() {
+                        assert_no_index_into_empty(
+                            array
+                                .into_iter()
+                                .map(|opt_idx| opt_idx.map(|idx| idx as usize)),
+                        );
+                    }
                     return Self::full_null(self.name(), array.len());
                 }
                 let array = match self.chunks.len() {
@@ -164,7 +202,8 @@ where
             }
             TakeIdx::Iter(iter) => {
                 if self.is_empty() {
-                    return Self::full_null(self.name(), iter.size_hint().0);
+                    assert_no_index_into_empty(iter.map(Some));
+                    return Self::full_null(self.name(), 0);
                 }
                 let array = match (self.null_count(), self.chunks.len()) {
                     (0, 1) => take_no_null_primitive_iter(chunks.next().unwrap(), iter) as ArrayRef,
@@ -177,8 +216,11 @@ where
                 };
                 self.copy_with_chunks(vec![array])
             }
-            TakeIdx::IterNulls(_) => {
-                panic!("not supported in take, only supported in take_unchecked for the join operation")
+            TakeIdx::IterNulls(iter) => {
+                let idx = checked_opt_idx(iter, self.len());
+                // Safety:
+                // the indices are bound checked.
+                unsafe { self.take_unchecked((&idx).into()) }
             }
         }
     }
@@ -265,6 +307,13 @@ impl ChunkTake for BooleanChunked {
         match indices {
             TakeIdx::Array(array) => {
                 if self.is_empty() || array.null_count() == array.len() {
+                    if self.is_empty() {
+                        assert_no_index_into_empty(
+                            array
+                                .into_iter()
+                                .map(|opt_idx| opt_idx.map(|idx| idx as usize)),
+                        );
+                    }
                     return Self::full_null(self.name(), array.len());
                 }
                 let array = match self.chunks.len() {
@@ -289,7 +338,8 @@ impl ChunkTake for BooleanChunked {
             }
             TakeIdx::Iter(iter) => {
                 if self.is_empty() {
-                    return Self::full_null(self.name(), iter.size_hint().0);
+                    assert_no_index_into_empty(iter.map(Some));
+                    return Self::full_null(self.name(), 0);
                 }
                 let array = match (self.null_count(), self.chunks.len()) {
                     (0, 1) => take_no_null_bool_iter(chunks.next().unwrap(), iter) as ArrayRef,
@@ -302,8 +352,11 @@ impl ChunkTake for BooleanChunked {
                 };
                 self.copy_with_chunks(vec![array])
             }
-            TakeIdx::IterNulls(_) => {
-                panic!("not supported in take, only supported in take_unchecked for the join operation")
+            TakeIdx::IterNulls(iter) => {
+                let idx = checked_opt_idx(iter, self.len());
+                // Safety:
+                // the indices are bound checked.
+                unsafe { self.take_unchecked((&idx).into()) }
             }
         }
     }
@@ -390,6 +443,11 @@ impl ChunkTake for Utf8Chunked {
         match indices {
             TakeIdx::Array(array) => {
                 if self.is_empty() {
+                    assert_no_index_into_empty(
+                        array
+                            .into_iter()
+                            .map(|opt_idx| opt_idx.map(|idx| idx as usize)),
+                    );
                     return Self::full_null(self.name(), array.len());
                 }
                 let array = match self.chunks.len() {
@@ -414,7 +472,8 @@ impl ChunkTake for Utf8Chunked {
             }
             TakeIdx::Iter(iter) => {
                 if self.is_empty() {
-                    return Self::full_null(self.name(), iter.size_hint().0);
+                    assert_no_index_into_empty(iter.map(Some));
+                    return Self::full_null(self.name(), 0);
                 }
                 let array = match (self.null_count(), self.chunks.len()) {
                     (0, 1) => take_no_null_utf8_iter(chunks.next().unwrap(), iter) as ArrayRef,
@@ -427,8 +486,11 @@ impl ChunkTake for Utf8Chunked {
                 };
                 self.copy_with_chunks(vec![array])
             }
-            TakeIdx::IterNulls(_) => {
-                panic!("not supported in take, only supported in take_unchecked for the join operation")
+            TakeIdx::IterNulls(iter) => {
+                let idx = checked_opt_idx(iter, self.len());
+                // Safety:
+                // the indices are bound checked.
+                unsafe { self.take_unchecked((&idx).into()) }
             }
         }
     }
@@ -454,6 +516,11 @@ impl ChunkTake for ListChunked {
         match indices {
             TakeIdx::Array(array) => {
                 if self.is_empty() {
+                    assert_no_index_into_empty(
+                        array
+                            .into_iter()
+                            .map(|opt_idx| opt_idx.map(|idx| idx as usize)),
+                    );
                     return Self::full_null(self.name(), array.len());
                 }
                 let array = match self.chunks.len() {
@@ -478,7 +545,8 @@ impl ChunkTake for ListChunked {
             }
             TakeIdx::Iter(iter) => {
                 if self.is_empty() {
-                    return Self::full_null(self.name(), iter.size_hint().0);
+                    assert_no_index_into_empty(iter.map(Some));
+                    return Self::full_null(self.name(), 0);
                 }
                 let mut ca: ListChunked = take_iter_n_chunks!(self, iter);
                 ca.rename(self.name());
@@ -486,7 +554,9 @@ impl ChunkTake for ListChunked {
             }
             TakeIdx::IterNulls(iter) => {
                 if self.is_empty() {
-                    return Self::full_null(self.name(), iter.size_hint().0);
+                    let mut len = 0;
+                    assert_no_index_into_empty(iter.inspect(|_| len += 1));
+                    return Self::full_null(self.name(), len);
                 }
 
                 let mut ca: ListChunked = take_opt_iter_n_chunks!(self, iter);
@@ -537,10 +607,15 @@ impl<T: PolarsObject> ChunkTake for ObjectChunked<T> {
         I: Iterator<Item = usize>,
         INulls: Iterator<Item = Option<usize>>,
     {
-        // current implementation is suboptimal, every iterator is allocated to UInt32Array
+        // current implementation is suboptimal, every iterator is allocated to an IdxArr
         match indices {
             TakeIdx::Array(array) => {
                 if self.is_empty() {
+                    assert_no_index_into_empty(
+                        array
+                            .into_iter()
+                            .map(|opt_idx| opt_idx.map(|idx| idx as usize)),
+                    );
                     return Self::full_null(self.name(), array.len());
                 }
                 match self.chunks.len() {
@@ -581,7 +656,8 @@ impl<T: PolarsObject> ChunkTake for ObjectChunked<T> {
             }
             TakeIdx::Iter(iter) => {
                 if self.is_empty() {
-                    return Self::full_null(self.name(), iter.size_hint().0);
+                    assert_no_index_into_empty(iter.map(Some));
+                    return Self::full_null(self.name(), 0);
                 }
 
                 let taker = self.take_rand();
@@ -591,7 +667,9 @@ impl<T: PolarsObject> ChunkTake for ObjectChunked<T> {
             }
             TakeIdx::IterNulls(iter) => {
                 if self.is_empty() {
-                    return Self::full_null(self.name(), iter.size_hint().0);
+                    let mut len = 0;
+                    assert_no_index_into_empty(iter.inspect(|_| len += 1));
+                    return Self::full_null(self.name(), len);
                 }
                 let taker = self.take_rand();
 
@@ -695,4 +773,102 @@ mod test {
         assert_eq!(ca.get(1), Some("b"));
         assert_eq!(ca.get(2), Some("c"));
     }
+
+    #[test]
+    fn test_take_opt_iter_and_checked() -> Result<()> {
+        let idx = [Some(2), None, Some(0), Some(1)];
+        let take_opt = |s: &Series| s.take_opt_iter(&mut idx.iter().copied());
+
+        let s = Series::new("a", &[Some(1), None, Some(3)]);
+        let out = take_opt(&s);
+        assert_eq!(Vec::from(out.i32()?), &[Some(3), None, Some(1), None]);
+        // multiple chunks
+        let mut chunked = s.clone();
+        chunked.append(&s)?;
+        assert!(take_opt(&chunked).series_equal_missing(&out));
+
+        let s = Series::new("a", &["x", "y", "z"]);
+        let out = take_opt(&s);
+        assert_eq!(
+            Vec::from(out.utf8()?),
+            &[Some("z"), None, Some("x"), Some("y")]
+        );
+
+        let s = Series::new("a", &[true, false, true]);
+        let out = take_opt(&s);
+        assert_eq!(
+            Vec::from(out.bool()?),
+            &[Some(true), None, Some(true), Some(false)]
+        );
+
+        let s = Series::new("a", &[Series::new("", &[1, 2]), Series::new("", &[3])]);
+        let out = s.take_opt_iter(&mut vec![Some(1), None, Some(0)].into_iter());
+        let lengths: Vec<_> = out
+            .list()?
+            .into_iter()
+            .map(|s| s.map(|s| s.len()))
+            .collect();
+        assert_eq!(lengths, &[Some(1), None, Some(2)]);
+
+        // the codes are taken and the mapping is shared
+        let s = Series::new("a", &["x", "y", "z"]).cast::<CategoricalType>()?;
+        let out = take_opt(&s);
+        let (ca, out_ca) = (s.categorical()?, out.categorical()?);
+        assert!(Arc::ptr_eq(
+            ca.get_categorical_map().unwrap(),
+            out_ca.get_categorical_map().unwrap()
+        ));
+        assert_eq!(
+            Vec::from(out.cast::<Utf8Type>()?.utf8()?),
+            &[Some("z"), None, Some("x"), Some("y")]
+        );
+
+        // a single null index into an array with nulls
+        let s = Series::new("a", &[Some(1), None]);
+        let idx = IdxCa::new_from_opt_slice("idx", &[None]);
+        assert_eq!(s.take_checked(&idx)?.null_count(), 1);
+
+        let idx = IdxCa::new_from_opt_slice("idx", &[Some(1), None, Some(0)]);
+        let out = s.take_checked(&idx)?;
+        assert_eq!(Vec::from(out.i32()?), &[None, None, Some(1)]);
+        let idx = IdxCa::new_from_slice("idx", &[0, 5, 1]);
+        match s.take_checked(&idx) {
+            Err(PolarsError::OutOfBounds(msg)) => assert!(msg.contains('5')),
+            _ => panic!("expected an out of bounds error"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_take_opt_iter_out_of_bounds() {
+        let s = Series::new("a", &[1, 2, 3]);
+        s.take_opt_iter(&mut vec![Some(0), Some(3)].into_iter());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_take_list_from_empty() {
+        let s = Series::new("a", &[Series::new("", &[1])]).slice(0, 0);
+        s.take_opt_iter(&mut vec![None, Some(0)].into_iter());
+    }
+
+    #[test]
+    #[cfg(feature = "object")]
+    fn test_take_opt_iter_object() {
+        let s = ObjectChunked::new_from_vec("a", vec![1i32, 2, 3]).into_series();
+        let out = s.take_opt_iter(&mut vec![Some(2), None].into_iter());
+        let ca = out.as_any().downcast_ref::<ObjectChunked<i32>>().unwrap();
+        assert_eq!(ca.get(0), Some(&3));
+        assert_eq!(out.null_count(), 1);
+
+        // only null indices can be taken from an empty array
+        let empty = s.slice(0, 0);
+        let out = empty.take_opt_iter(&mut vec![None, None].into_iter());
+        assert_eq!(out.null_count(), 2);
+        let out = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            empty.take_opt_iter(&mut vec![Some(0)].into_iter())
+        }));
+        assert!(out.is_err());
+    }
 }
@@ -9,8 +9,10 @@ use unsafe_unwrap::UnsafeUnwrap;
 
 macro_rules! take_random_get {
     ($self:ident, $index:ident) => {{
-        let (chunk_idx, arr_idx) =
-            crate::utils::index_to_chunked_index($self.chunk_lens.iter().copied(), $index as u32);
+        let (chunk_idx, arr_idx) = crate::utils::index_to_chunked_index(
+            $self.chunk_lens.iter().copied(),
+            $index as IdxSize,
+        );
         let arr = $self.chunks.get(chunk_idx as usize);
         match arr {
             Some(arr) => {
@@ -29,8 +31,10 @@ macro_rules! take_random_get {
 
 macro_rules! take_random_get_unchecked {
     ($self:ident, $index:ident) => {{
-        let (chunk_idx, arr_idx) =
-            crate::utils::index_to_chunked_index($self.chunk_lens.iter().copied(), $index as u32);
+        let (chunk_idx, arr_idx) = crate::utils::index_to_chunked_index(
+            $self.chunk_lens.iter().copied(),
+            $index as IdxSize,
+        );
         $self
             .chunks
             .get_unchecked(chunk_idx as usize)
@@ -146,7 +150,7 @@ where
         } else {
             let t = NumTakeRandomChunked {
                 chunks: chunks.collect(),
-                chunk_lens: self.chunks.iter().map(|a| a.len() as u32).collect(),
+                chunk_lens: self.chunks.iter().map(|a| a.len() as IdxSize).collect(),
             };
             TakeRandBranch3::Multi(t)
         }
@@ -155,7 +159,7 @@ where
 
 pub struct Utf8TakeRandom<'a> {
     pub(crate) chunks: Chunks<'a, LargeStringArray>,
-    pub(crate) chunk_lens: Vec<u32>,
+    pub(crate) chunk_lens: Vec<IdxSize>,
 }
 
 impl<'a> TakeRandom for Utf8TakeRandom<'a> {
@@ -205,7 +209,7 @@ impl<'a> IntoTakeRandom<'a> for &'a Utf8Chunked {
                 let chunks = self.downcast_chunks();
                 let t = Utf8TakeRandom {
                     chunks,
-                    chunk_lens: self.chunks.iter().map(|a| a.len() as u32).collect(),
+                    chunk_lens: self.chunks.iter().map(|a| a.len() as IdxSize).collect(),
                 };
                 TakeRandBranch2::Multi(t)
             }
@@ -228,7 +232,7 @@ impl<'a> IntoTakeRandom<'a> for &'a BooleanChunked {
                 let chunks = self.downcast_chunks();
                 let t = BoolTakeRandom {
                     chunks,
-                    chunk_lens: self.chunks.iter().map(|a| a.len() as u32).collect(),
+                    chunk_lens: self.chunks.iter().map(|a| a.len() as IdxSize).collect(),
                 };
                 TakeRandBranch2::Multi(t)
             }
@@ -252,7 +256,7 @@ impl<'a> IntoTakeRandom<'a> for &'a ListChunked {
             let t = ListTakeRandom {
                 ca: self,
                 chunks: chunks.collect(),
-                chunk_lens: self.chunks.iter().map(|a| a.len() as u32).collect(),
+                chunk_lens: self.chunks.iter().map(|a| a.len() as IdxSize).collect(),
             };
             TakeRandBranch2::Multi(t)
         }
@@ -264,7 +268,7 @@ where
     T: PolarsNumericType,
 {
     pub(crate) chunks: Vec<&'a PrimitiveArray<T>>,
-    pub(crate) chunk_lens: Vec<u32>,
+    pub(crate) chunk_lens: Vec<IdxSize>,
 }
 
 impl<'a, T> TakeRandom for NumTakeRandomChunked<'a, T>
@@ -331,7 +335,7 @@ where
 
 pub struct BoolTakeRandom<'a> {
     pub(crate) chunks: Chunks<'a, BooleanArray>,
-    pub(crate) chunk_lens: Vec<u32>,
+    pub(crate) chunk_lens: Vec<IdxSize>,
 }
 
 impl<'a> TakeRandom for BoolTakeRandom<'a> {
@@ -369,7 +373,7 @@ impl<'a> TakeRandom for BoolTakeRandomSingleChunk<'a> {
 pub struct ListTakeRandom<'a> {
     ca: &'a ListChunked,
     chunks: Vec<&'a LargeListArray>,
-    chunk_lens: Vec<u32>,
+    chunk_lens: Vec<IdxSize>,
 }
 
 impl<'a> TakeRandom for ListTakeRandom<'a> {
@@ -419,7 +423,7 @@ impl<'a> TakeRandom for ListTakeRandomSingleChunk<'a> {
 #[cfg(feature = "object")]
 pub struct ObjectTakeRandom<'a, T: PolarsObject> {
     chunks: Vec<&'a ObjectArray<T>>,
-    chunk_lens: Vec<u32>,
+    chunk_lens: Vec<IdxSize>,
 }
 
 #[cfg(feature = "object")]
@@ -472,7 +476,7 @@ impl<'a, T: PolarsObject> IntoTakeRandom<'a> for &'a ObjectChunked<T> {
         } else {
             let t = ObjectTakeRandom {
                 chunks: chunks.collect(),
-                chunk_lens: self.chunks.iter().map(|a| a.len() as u32).collect(),
+                chunk_lens: self.chunks.iter().map(|a| a.len() as IdxSize).collect(),
             };
             TakeRandBranch2::Multi(t)
         }
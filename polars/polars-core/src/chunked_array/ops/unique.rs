@@ -4,17 +4,14 @@ use crate::chunked_array::object::ObjectType;
 use crate::datatypes::PlHashSet;
 use crate::frame::groupby::{GroupTuples, IntoGroupTuples};
 use crate::prelude::*;
-use crate::utils::{CustomIterTools, NoNull};
+use crate::utils::{ensure_unique_names, CustomIterTools, NoNull};
 use arrow::array::Array;
-use itertools::Itertools;
 use num::NumCast;
-use rayon::prelude::*;
-use std::fmt::Display;
 use std::hash::Hash;
 
 fn finish_is_unique_helper(
-    mut unique_idx: Vec<u32>,
-    len: u32,
+    mut unique_idx: Vec<IdxSize>,
+    len: IdxSize,
     unique_val: bool,
     duplicated_val: bool,
 ) -> BooleanChunked {
@@ -38,8 +35,8 @@ fn finish_is_unique_helper(
 }
 
 pub(crate) fn is_unique_helper2(
-    unique_idx: Vec<u32>,
-    len: u32,
+    unique_idx: Vec<IdxSize>,
+    len: IdxSize,
     unique_val: bool,
     duplicated_val: bool,
 ) -> BooleanChunked {
@@ -49,7 +46,7 @@ pub(crate) fn is_unique_helper2(
 
 pub(crate) fn is_unique_helper(
     groups: GroupTuples,
-    len: u32,
+    len: IdxSize,
     unique_val: bool,
     duplicated_val: bool,
 ) -> BooleanChunked {
@@ -72,31 +69,71 @@ macro_rules! is_unique_duplicated {
         $ca.into_iter().enumerate().for_each(|(idx, key)| {
             idx_key
                 .entry(key)
-                .and_modify(|v: &mut (u32, bool)| v.1 = false)
-                .or_insert((idx as u32, true));
+                .and_modify(|v: &mut (IdxSize, bool)| v.1 = false)
+                .or_insert((idx as IdxSize, true));
         });
 
         let idx: Vec<_> = idx_key
             .into_iter()
             .filter_map(|(_k, v)| if v.1 { Some(v.0) } else { None })
             .collect();
-        let mut out = is_unique_helper2(idx, $ca.len() as u32, !$inverse, $inverse);
+        let mut out = is_unique_helper2(idx, $ca.len() as IdxSize, !$inverse, $inverse);
         out.rename($ca.name());
         Ok(out)
     }};
 }
 
+macro_rules! impl_value_counts {
+    ($self:expr) => {{
+        let group_tuples = $self.group_tuples(true);
+        let values =
+            unsafe { $self.take_unchecked(group_tuples.iter().map(|t| t.0 as usize).into()) };
+        let mut counts: NoNull<UInt32Chunked> = group_tuples
+            .into_iter()
+            .map(|(_, groups)| groups.len() as u32)
+            .collect();
+        counts.rename("counts");
+        let cols = vec![values.into_series(), counts.into_inner().into_series()];
+        ensure_unique_names(&cols, "value_counts")?;
+        let df = DataFrame::new_no_checks(cols);
+        df.sort("counts", true)
+    }};
+}
+
 impl ChunkUnique<ListType> for ListChunked {
     fn unique(&self) -> Result<ChunkedArray<ListType>> {
-        Err(PolarsError::InvalidOperation(
-            "unique not supported for list".into(),
-        ))
+        let idx = self.arg_unique()?;
+        // Safety:
+        // the first indexes of the groups are in bounds
+        Ok(unsafe { self.take_unchecked((&idx).into()) })
     }
 
-    fn arg_unique(&self) -> Result<UInt32Chunked> {
-        Err(PolarsError::InvalidOperation(
-            "unique not supported for list".into(),
-        ))
+    fn arg_unique(&self) -> Result<IdxCa> {
+        let mut idx = self
+            .group_tuples(true)
+            .into_iter()
+            .map(|(first, _)| first)
+            .collect::<Vec<_>>();
+        idx.sort_unstable();
+        let mut ca: NoNull<IdxCa> = idx.into_iter().collect();
+        ca.rename(self.name());
+        Ok(ca.into_inner())
+    }
+
+    fn is_unique(&self) -> Result<BooleanChunked> {
+        let mut out = is_unique_helper(self.group_tuples(true), self.len() as IdxSize, true, false);
+        out.rename(self.name());
+        Ok(out)
+    }
+
+    fn is_duplicated(&self) -> Result<BooleanChunked> {
+        let mut out = is_unique_helper(self.group_tuples(true), self.len() as IdxSize, false, true);
+        out.rename(self.name());
+        Ok(out)
+    }
+
+    fn value_counts(&self) -> Result<DataFrame> {
+        impl_value_counts!(self)
     }
 }
 #[cfg(feature = "object")]
@@ -107,7 +144,7 @@ impl<T> ChunkUnique<ObjectType<T>> for ObjectChunked<T> {
         ))
     }
 
-    fn arg_unique(&self) -> Result<UInt32Chunked> {
+    fn arg_unique(&self) -> Result<IdxCa> {
         Err(PolarsError::InvalidOperation(
             "unique not supported for object".into(),
         ))
@@ -121,7 +158,7 @@ where
     a.collect()
 }
 
-fn arg_unique<T>(a: impl Iterator<Item = T>, capacity: usize) -> AlignedVec<u32>
+fn arg_unique<T>(a: impl Iterator<Item = T>, capacity: usize) -> AlignedVec<IdxSize>
 where
     T: Hash + Eq,
 {
@@ -129,7 +166,7 @@ where
     let mut unique = AlignedVec::with_capacity_aligned(capacity);
     a.enumerate().for_each(|(idx, val)| {
         if set.insert(val) {
-            unique.push(idx as u32)
+            unique.push(idx as IdxSize)
         }
     });
     unique
@@ -144,22 +181,6 @@ macro_rules! arg_unique_ca {
     }};
 }
 
-macro_rules! impl_value_counts {
-    ($self:expr) => {{
-        let group_tuples = $self.group_tuples(true);
-        let values =
-            unsafe { $self.take_unchecked(group_tuples.iter().map(|t| t.0 as usize).into()) };
-        let mut counts: NoNull<UInt32Chunked> = group_tuples
-            .into_iter()
-            .map(|(_, groups)| groups.len() as u32)
-            .collect();
-        counts.rename("counts");
-        let cols = vec![values.into_series(), counts.into_inner().into_series()];
-        let df = DataFrame::new_no_checks(cols);
-        df.sort("counts", true)
-    }};
-}
-
 impl<T> ChunkUnique<T> for ChunkedArray<T>
 where
     T: PolarsIntegerType,
@@ -171,8 +192,8 @@ where
         Ok(Self::new_from_opt_iter(self.name(), set.iter().copied()))
     }
 
-    fn arg_unique(&self) -> Result<UInt32Chunked> {
-        Ok(UInt32Chunked::new_from_aligned_vec(
+    fn arg_unique(&self) -> Result<IdxCa> {
+        Ok(IdxCa::new_from_aligned_vec(
             self.name(),
             arg_unique_ca!(self),
         ))
@@ -204,8 +225,8 @@ impl ChunkUnique<Utf8Type> for Utf8Chunked {
         ))
     }
 
-    fn arg_unique(&self) -> Result<UInt32Chunked> {
-        Ok(UInt32Chunked::new_from_aligned_vec(
+    fn arg_unique(&self) -> Result<IdxCa> {
+        Ok(IdxCa::new_from_aligned_vec(
             self.name(),
             arg_unique_ca!(self),
         ))
@@ -232,7 +253,7 @@ impl ChunkUnique<CategoricalType> for CategoricalChunked {
         let cat_map = self.categorical_map.as_ref().unwrap();
         let mut ca = match &**cat_map {
             RevMapping::Local(a) => UInt32Chunked::new_from_iter(self.name(), 0..(a.len() as u32)),
-            RevMapping::Global(map, _, _) => {
+            RevMapping::Global(map, _, _, _) => {
                 UInt32Chunked::new_from_iter(self.name(), map.keys().copied())
             }
         };
@@ -240,7 +261,7 @@ impl ChunkUnique<CategoricalType> for CategoricalChunked {
         ca.cast()
     }
 
-    fn arg_unique(&self) -> Result<UInt32Chunked> {
+    fn arg_unique(&self) -> Result<IdxCa> {
         self.cast::<UInt32Type>()?.arg_unique()
     }
 
@@ -259,92 +280,6 @@ impl ChunkUnique<CategoricalType> for CategoricalChunked {
     }
 }
 
-#[cfg(feature = "dtype-u8")]
-fn dummies_helper(mut groups: Vec<u32>, len: usize, name: &str) -> UInt8Chunked {
-    groups.sort_unstable();
-
-    let mut av: AlignedVec<_> = (0..len).map(|_| 0u8).collect_trusted();
-
-    for idx in groups {
-        let elem = unsafe { av.inner.get_unchecked_mut(idx as usize) };
-        *elem = 1;
-    }
-
-    ChunkedArray::new_from_aligned_vec(name, av)
-}
-
-#[cfg(not(feature = "dtype-u8"))]
-fn dummies_helper(mut groups: Vec<u32>, len: usize, name: &str) -> Int32Chunked {
-    groups.sort_unstable();
-
-    // let mut group_member_iter = groups.into_iter();
-    let mut av: AlignedVec<_> = (0..len).map(|_| 0i32).collect_trusted();
-
-    for idx in groups {
-        let elem = unsafe { av.inner.get_unchecked_mut(idx as usize) };
-        *elem = 1;
-    }
-
-    ChunkedArray::new_from_aligned_vec(name, av)
-}
-
-fn sort_columns(columns: Vec<Series>) -> Vec<Series> {
-    columns
-        .into_iter()
-        .sorted_by_key(|s| s.name().to_string())
-        .collect()
-}
-
-impl ToDummies<Utf8Type> for Utf8Chunked {
-    fn to_dummies(&self) -> Result<DataFrame> {
-        let groups = self.group_tuples(true);
-        let col_name = self.name();
-
-        let columns = groups
-            .into_par_iter()
-            .map(|(first, groups)| {
-                let val = unsafe { self.get_unchecked(first as usize) };
-                let name = format!("{}_{}", col_name, val);
-                let ca = dummies_helper(groups, self.len(), &name);
-                ca.into_series()
-            })
-            .collect();
-
-        Ok(DataFrame::new_no_checks(sort_columns(columns)))
-    }
-}
-impl<T> ToDummies<T> for ChunkedArray<T>
-where
-    T: PolarsIntegerType + Sync,
-    T::Native: Hash + Eq + Display + NumCast,
-    ChunkedArray<T>: ChunkOps + ChunkCompare<T::Native> + ChunkUnique<T>,
-{
-    fn to_dummies(&self) -> Result<DataFrame> {
-        let groups = self.group_tuples(true);
-        let col_name = self.name();
-
-        let columns = groups
-            .into_par_iter()
-            .map(|(first, groups)| {
-                let val = unsafe { self.get_unchecked(first as usize) };
-                let name = format!("{}_{}", col_name, val);
-                let ca = dummies_helper(groups, self.len(), &name);
-                ca.into_series()
-            })
-            .collect();
-
-        Ok(DataFrame::new_no_checks(sort_columns(columns)))
-    }
-}
-
-impl ToDummies<ListType> for ListChunked {}
-#[cfg(feature = "object")]
-impl<T> ToDummies<ObjectType<T>> for ObjectChunked<T> {}
-impl ToDummies<Float32Type> for Float32Chunked {}
-impl ToDummies<Float64Type> for Float64Chunked {}
-impl ToDummies<BooleanType> for BooleanChunked {}
-impl ToDummies<CategoricalType> for CategoricalChunked {}
-
 impl ChunkUnique<BooleanType> for BooleanChunked {
     fn unique(&self) -> Result<Self> {
         // can be None, Some(true), Some(false)
@@ -360,8 +295,8 @@ impl ChunkUnique<BooleanType> for BooleanChunked {
         Ok(ChunkedArray::new_from_opt_slice(self.name(), &unique))
     }
 
-    fn arg_unique(&self) -> Result<UInt32Chunked> {
-        Ok(UInt32Chunked::new_from_aligned_vec(
+    fn arg_unique(&self) -> Result<IdxCa> {
+        Ok(IdxCa::new_from_aligned_vec(
             self.name(),
             arg_unique_ca!(self),
         ))
@@ -385,7 +320,7 @@ impl ChunkUnique<Float32Type> for Float32Chunked {
             .collect())
     }
 
-    fn arg_unique(&self) -> Result<UInt32Chunked> {
+    fn arg_unique(&self) -> Result<IdxCa> {
         self.bit_repr_small().arg_unique()
     }
 
@@ -410,7 +345,7 @@ impl ChunkUnique<Float64Type> for Float64Chunked {
             .collect())
     }
 
-    fn arg_unique(&self) -> Result<UInt32Chunked> {
+    fn arg_unique(&self) -> Result<IdxCa> {
         #[cfg(feature = "dtype-u64")]
         {
             self.bit_repr_large().arg_unique()
@@ -361,11 +361,72 @@ where
             min_periods,
         ))
     }
+
+    fn rolling_apply<F>(&self, window_size: u32, f: F, min_periods: u32) -> Result<Self>
+    where
+        F: Fn(&[Option<T::Native>]) -> Option<T::Native>,
+    {
+        check_input(window_size, min_periods)?;
+        // collect once, so that every window is a slice of the same buffer
+        let values = self.into_iter().collect::<Vec<_>>();
+        let window_size = window_size as usize;
+
+        // the number of non-null values in the window
+        let mut n_valid = 0;
+        let mut ca: Self = (0..values.len())
+            .map(|i| {
+                if values[i].is_some() {
+                    n_valid += 1;
+                }
+                if i >= window_size && values[i - window_size].is_some() {
+                    n_valid -= 1;
+                }
+                if n_valid < min_periods {
+                    None
+                } else {
+                    f(&values[(i + 1).saturating_sub(window_size)..=i])
+                }
+            })
+            .collect();
+        ca.rename(self.name());
+        Ok(ca)
+    }
+
+    fn rolling_apply_no_null<F>(&self, window_size: u32, f: F, min_periods: u32) -> Result<Self>
+    where
+        F: Fn(&[T::Native]) -> T::Native,
+    {
+        check_input(window_size, min_periods)?;
+        if self.null_count() > 0 {
+            return Err(PolarsError::HasNullValues(
+                "rolling_apply_no_null cannot be applied to an array with null values".into(),
+            ));
+        }
+        let ca = self.rechunk();
+        let values = ca.cont_slice()?;
+        let window_size = window_size as usize;
+        let mut out: Self = (0..values.len())
+            .map(|i| {
+                let window = &values[(i + 1).saturating_sub(window_size)..=i];
+                if (window.len() as u32) < min_periods {
+                    None
+                } else {
+                    Some(f(window))
+                }
+            })
+            .collect();
+        out.rename(self.name());
+        Ok(out)
+    }
 }
 
 /// utility
 fn check_input(window_size: u32, min_periods: u32) -> Result<()> {
-    if min_periods > window_size {
+    if window_size == 0 {
+        Err(PolarsError::ValueError(
+            "`window_size` should be > 0".into(),
+        ))
+    } else if min_periods > window_size {
         Err(PolarsError::ValueError(
             "`windows_size` should be >= `min_periods`".into(),
         ))
@@ -465,6 +526,7 @@ mod test {
 
         // check err on wrong input
         assert!(ca.rolling_mean(1, None, true, 2).is_err());
+        assert!(ca.rolling_mean(0, None, true, 0).is_err());
 
         // validate that we divide by the proper window length. (same as pandas)
         let a = ca.rolling_mean(3, None, true, 1).unwrap();
@@ -481,4 +543,43 @@ mod test {
             ]
         );
     }
+
+    #[test]
+    fn test_rolling_apply() -> Result<()> {
+        let ca =
+            Int32Chunked::new_from_opt_slice("foo", &[Some(1), Some(2), None, Some(4), Some(5)]);
+        // the windows are passed oldest value first
+        let a = ca.rolling_apply(
+            3,
+            |window| {
+                let last = window.iter().rev().flatten().next()?;
+                let first = window.iter().flatten().next()?;
+                Some(last - first)
+            },
+            1,
+        )?;
+        assert_eq!(a.name(), "foo");
+        assert_eq!(
+            Vec::from(&a),
+            &[Some(0), Some(1), Some(1), Some(2), Some(1)]
+        );
+
+        let a = ca.rolling_apply(2, |window| window.iter().flatten().copied().max(), 2)?;
+        assert_eq!(Vec::from(&a), &[None, Some(2), None, None, Some(5)]);
+
+        // the same result as the built-in functions
+        let ca = Int32Chunked::new_from_slice("foo", &[1, 2, 3, 2, 1]);
+        let a = ca.rolling_apply_no_null(2, |window| window.iter().sum(), 0)?;
+        assert_eq!(Vec::from(&a), Vec::from(&ca.rolling_sum(2, None, true, 0)?));
+        let a = ca.rolling_apply_no_null(3, |window| window.len() as i32, 3)?;
+        assert_eq!(Vec::from(&a), &[None, None, Some(3), Some(3), Some(3)]);
+
+        let ca = Int32Chunked::new_from_opt_slice("foo", &[Some(1), None]);
+        assert!(matches!(
+            ca.rolling_apply_no_null(2, |window| window[0], 0),
+            Err(PolarsError::HasNullValues(_))
+        ));
+        assert!(ca.rolling_apply(3, |window| window[0], 4).is_err());
+        Ok(())
+    }
 }
@@ -62,28 +62,33 @@ where
     }
 }
 
+/// Create the indices to sample `n` rows of `len` rows. All columns should use the same indices.
+fn create_rand_idx(n: usize, len: usize, with_replacement: bool) -> Result<IdxCa> {
+    if !with_replacement && n > len {
+        return Err(PolarsError::ShapeMisMatch(
+            "n is larger than the number of elements in this array".into(),
+        ));
+    }
+    let idx: NoNull<IdxCa> = match with_replacement {
+        true => create_rand_index_with_replacement(n, len)
+            .1
+            .map(|i| i as IdxSize)
+            .collect(),
+        false => create_rand_index_no_replacement(n, len)
+            .1
+            .map(|i| i as IdxSize)
+            .collect(),
+    };
+    Ok(idx.into_inner())
+}
+
 impl DataFrame {
     /// Sample n datapoints from this DataFrame.
     pub fn sample_n(&self, n: usize, with_replacement: bool) -> Result<Self> {
-        if !with_replacement && n > self.height() {
-            return Err(PolarsError::ShapeMisMatch(
-                "n is larger than the number of elements in this array".into(),
-            ));
-        }
-        // all columns should used the same indices. So we first create the indices.
-        let idx: NoNull<UInt32Chunked> = match with_replacement {
-            true => create_rand_index_with_replacement(n, self.height())
-                .1
-                .map(|i| i as u32)
-                .collect(),
-            false => create_rand_index_no_replacement(n, self.height())
-                .1
-                .map(|i| i as u32)
-                .collect(),
-        };
+        let idx = create_rand_idx(n, self.height(), with_replacement)?;
         // Safety:
         // indices are withing bounds
-        Ok(unsafe { self.take_unchecked(&idx.into_inner()) })
+        Ok(unsafe { self.take_unchecked(&idx) })
     }
 
     /// Sample a fraction between 0.0-1.0 of this DataFrame.
@@ -93,6 +98,23 @@ impl DataFrame {
     }
 }
 
+#[cfg(feature = "dtype-struct")]
+impl StructChunked {
+    /// Sample n rows from this StructChunked.
+    pub fn sample_n(&self, n: usize, with_replacement: bool) -> Result<Self> {
+        let idx = create_rand_idx(n, self.len(), with_replacement)?;
+        // Safety:
+        // indices are withing bounds
+        self.try_apply_fields(false, |s| unsafe { s.take_unchecked(&idx) })
+    }
+
+    /// Sample a fraction between 0.0-1.0 of this StructChunked.
+    pub fn sample_frac(&self, frac: f64, with_replacement: bool) -> Result<Self> {
+        let n = (self.len() as f64 * frac) as usize;
+        self.sample_n(n, with_replacement)
+    }
+}
+
 impl<T> ChunkedArray<T>
 where
     T: PolarsNumericType,
@@ -0,0 +1,118 @@
+use num::NumCast;
+use std::sync::{Arc, Mutex};
+
+/// A cached minimum or maximum. Integers are stored exactly, because not every 64 bit integer
+/// can be represented by a `f64`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum StatValue {
+    Int(i128),
+    Float(f64),
+}
+
+impl StatValue {
+    /// Convert the value to `T`, `None` if it cannot be represented by `T`.
+    pub fn cast<T: NumCast>(self) -> Option<T> {
+        match self {
+            StatValue::Int(v) => T::from(v),
+            StatValue::Float(v) => T::from(v),
+        }
+    }
+}
+
+#[derive(Default, Debug, Copy, Clone)]
+struct StatisticsInner {
+    // the outer option indicates if the value is computed
+    min: Option<Option<StatValue>>,
+    max: Option<Option<StatValue>>,
+    n_unique: Option<usize>,
+}
+
+/// The minimum and maximum of the non-null values of a single chunk, `None` if the chunk
+/// only contains nulls.
+pub type ChunkMinMax = Option<(f64, f64)>;
+
+/// Lazily computed statistics of a `ChunkedArray`.
+///
+/// The statistics are computed on first request and stored for reuse. Cloning a `ChunkedArray`
+/// shares the statistics, as the data is immutable. Every operation that mutates the data
+/// must reset them by assigning new (empty) `Statistics`.
+#[derive(Default, Debug)]
+pub struct Statistics {
+    inner: Mutex<StatisticsInner>,
+    chunks: Mutex<Option<Arc<Vec<ChunkMinMax>>>>,
+}
+
+impl Statistics {
+    fn get_or_init<T: Copy, F, G, S>(&self, get: G, set: S, compute: F) -> T
+    where
+        F: FnOnce() -> T,
+        G: Fn(&StatisticsInner) -> Option<T>,
+        S: Fn(&mut StatisticsInner, T),
+    {
+        if let Some(v) = get(&self.inner.lock().unwrap()) {
+            return v;
+        }
+        // compute outside of the lock, so that we don't hold it during a potentially
+        // expensive aggregation
+        let v = compute();
+        set(&mut self.inner.lock().unwrap(), v);
+        v
+    }
+
+    /// Get the minimum value. If not yet known, it is computed with `compute` and stored.
+    pub(crate) fn min_or_init<F: FnOnce() -> Option<StatValue>>(
+        &self,
+        compute: F,
+    ) -> Option<StatValue> {
+        self.get_or_init(|s| s.min, |s, v| s.min = Some(v), compute)
+    }
+
+    /// Get the maximum value. If not yet known, it is computed with `compute` and stored.
+    pub(crate) fn max_or_init<F: FnOnce() -> Option<StatValue>>(
+        &self,
+        compute: F,
+    ) -> Option<StatValue> {
+        self.get_or_init(|s| s.max, |s, v| s.max = Some(v), compute)
+    }
+
+    /// Get the number of unique values. If not yet known, it is computed with `compute` and stored.
+    pub(crate) fn n_unique_or_init<F: FnOnce() -> usize>(&self, compute: F) -> usize {
+        self.get_or_init(|s| s.n_unique, |s, v| s.n_unique = Some(v), compute)
+    }
+
+    /// Get the number of unique values if these are already computed.
+    pub(crate) fn n_unique(&self) -> Option<usize> {
+        self.inner.lock().unwrap().n_unique
+    }
+
+    /// Get the minimum and maximum if these are already computed.
+    pub(crate) fn min_max(&self) -> Option<(Option<StatValue>, Option<StatValue>)> {
+        let inner = self.inner.lock().unwrap();
+        match (inner.min, inner.max) {
+            (Some(min), Some(max)) => Some((min, max)),
+            _ => None,
+        }
+    }
+
+    /// Get the minimum and maximum of every chunk. If not yet known, they are computed with
+    /// `compute` and stored. Nothing is stored if `compute` returns `None`.
+    pub(crate) fn chunk_min_max_or_init<F: FnOnce() -> Option<Vec<ChunkMinMax>>>(
+        &self,
+        compute: F,
+    ) -> Option<Arc<Vec<ChunkMinMax>>> {
+        if let Some(v) = self.chunks.lock().unwrap().as_ref() {
+            return Some(v.clone());
+        }
+        let v = Arc::new(compute()?);
+        *self.chunks.lock().unwrap() = Some(v.clone());
+        Some(v)
+    }
+
+    /// Copy the statistics that don't depend on the chunk layout or the order of the data.
+    pub(crate) fn without_chunks(&self) -> Statistics {
+        Statistics {
+            inner: Mutex::new(*self.inner.lock().unwrap()),
+            chunks: Default::default(),
+        }
+    }
+}
@@ -1,14 +1,18 @@
-use crate::chunked_array::kernels::strings::string_lengths;
+use crate::chunked_array::kernels::strings::{string_lengths, string_n_chars, string_slice};
 use crate::prelude::*;
-use arrow::compute::kernels::substring::substring;
 use regex::Regex;
 
 impl Utf8Chunked {
-    /// Get the length of the string values.
+    /// Get the length of the string values in bytes.
     pub fn str_lengths(&self) -> UInt32Chunked {
         self.apply_kernel_cast(string_lengths)
     }
 
+    /// Get the length of the string values in unicode scalar values (chars).
+    pub fn str_n_chars(&self) -> UInt32Chunked {
+        self.apply_kernel_cast(string_n_chars)
+    }
+
     /// Check if strings contain a regex pattern
     pub fn contains(&self, pat: &str) -> Result<BooleanChunked> {
         let reg = Regex::new(pat)?;
@@ -54,12 +58,58 @@ impl Utf8Chunked {
     /// Slice the string values
     /// Determines a substring starting from `start` and with optional length `length` of each of the elements in `array`.
     /// `start` can be negative, in which case the start counts from the end of the string.
+    ///
+    /// `start` and `length` are in bytes. A slice never splits a char, both ends are rounded
+    /// down to the previous char boundary.
     pub fn str_slice(&self, start: i64, length: Option<u64>) -> Result<Self> {
         let chunks = self
             .downcast_iter()
-            .map(|c| substring(c, start, &length))
-            .collect::<arrow::error::Result<_>>()?;
+            .map(|c| string_slice(c, start, length))
+            .collect();
 
         Ok(Self::new_from_chunks(self.name(), chunks))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_str_lengths() {
+        let ca = Utf8Chunked::new_from_opt_slice("a", &[Some("foo"), None, Some(""), Some("é😀")]);
+        assert_eq!(
+            Vec::from(&ca.str_lengths()),
+            &[Some(3), None, Some(0), Some(6)]
+        );
+        assert_eq!(
+            Vec::from(&ca.str_n_chars()),
+            &[Some(3), None, Some(0), Some(2)]
+        );
+
+        // a slice of the array starts at an offset
+        let ca = ca.slice(1, 3);
+        assert_eq!(Vec::from(&ca.str_lengths()), &[None, Some(0), Some(6)]);
+    }
+
+    #[test]
+    fn test_str_slice() -> Result<()> {
+        let ca = Utf8Chunked::new_from_opt_slice("a", &[Some("foobar"), None, Some("")]);
+        let out = ca.str_slice(1, Some(3))?;
+        assert_eq!(Vec::from(&out), &[Some("oob"), None, Some("")]);
+        let out = ca.str_slice(-2, None)?;
+        assert_eq!(Vec::from(&out), &[Some("ar"), None, Some("")]);
+        let out = ca.str_slice(-10, Some(2))?;
+        assert_eq!(Vec::from(&out), &[Some("fo"), None, Some("")]);
+        let out = ca.str_slice(10, None)?;
+        assert_eq!(Vec::from(&out), &[Some(""), None, Some("")]);
+
+        // the emoji is 4 bytes, the ends are rounded down to its first byte
+        let ca = Utf8Chunked::new_from_slice("a", &["a😀b"]);
+        assert_eq!(Vec::from(&ca.str_slice(0, Some(3))?), &[Some("a")]);
+        assert_eq!(Vec::from(&ca.str_slice(2, None)?), &[Some("😀b")]);
+        assert_eq!(Vec::from(&ca.str_slice(1, Some(4))?), &[Some("😀")]);
+        assert_eq!(Vec::from(&ca.str_slice(-2, None)?), &[Some("😀b")]);
+        Ok(())
+    }
+}
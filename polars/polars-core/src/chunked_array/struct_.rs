@@ -0,0 +1,565 @@
+//! A nested type that groups multiple [Series](crate::series::Series) of equal length into a
+//! single column.
+use crate::prelude::*;
+use arrow::array::{make_array, Array, ArrayData, ArrayRef};
+use arrow::buffer::MutableBuffer;
+use arrow::util::bit_util;
+use std::collections::HashSet;
+use std::convert::TryFrom;
+
+/// A column of the `Struct` data type. Every row is a struct of the values of the field
+/// [Series](crate::series::Series) at that row. A row is null if the optional validity is `false`
+/// at that row.
+///
+/// The operations that change the rows, (e.g. `filter`, `take`, `slice`) are applied to the
+/// fields and the validity.
+#[derive(Clone)]
+pub struct StructChunked {
+    field: Field,
+    fields: Vec<Series>,
+    validity: Option<BooleanChunked>,
+    // the arrow `StructArray`s of the fields, with aligned chunks
+    chunks: Vec<ArrayRef>,
+}
+
+impl StructChunked {
+    /// Create a new `StructChunked` from the fields. The fields should have the same length and
+    /// unique names.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use polars_core::prelude::*;
+    /// let a = Series::new("a", &[1, 2]);
+    /// let b = Series::new("b", &["x", "y"]);
+    /// let ca = StructChunked::new("payload", &[a, b]).unwrap();
+    /// assert_eq!(ca.len(), 2);
+    /// ```
+    pub fn new(name: &str, fields: &[Series]) -> Result<Self> {
+        Self::new_with_validity(name, fields.to_vec(), None)
+    }
+
+    /// Create a new `StructChunked` where the rows for which `validity` is `false` or null are
+    /// null.
+    pub fn new_with_validity(
+        name: &str,
+        fields: Vec<Series>,
+        validity: Option<BooleanChunked>,
+    ) -> Result<Self> {
+        let len = match fields.first() {
+            Some(s) => s.len(),
+            None => {
+                return Err(PolarsError::NoData(
+                    "a struct should have at least one field".into(),
+                ))
+            }
+        };
+        if fields.iter().any(|s| s.len() != len)
+            || matches!(&validity, Some(validity) if validity.len() != len)
+        {
+            return Err(PolarsError::ShapeMisMatch(
+                "the fields of a struct should have the same length".into(),
+            ));
+        }
+        let mut names = HashSet::with_capacity(fields.len());
+        for s in &fields {
+            #[cfg(feature = "object")]
+            if matches!(s.dtype(), DataType::Object(_)) {
+                return Err(PolarsError::InvalidOperation(
+                    "the fields of a struct cannot be of dtype Object".into(),
+                ));
+            }
+            if !names.insert(s.name()) {
+                return Err(PolarsError::Duplicate(
+                    format!("multiple fields of a struct with the name {}", s.name()).into(),
+                ));
+            }
+        }
+        Ok(Self::new_unchecked(name, fields, validity))
+    }
+
+    /// The fields are not checked on equal lengths and unique names.
+    fn new_unchecked(
+        name: &str,
+        mut fields: Vec<Series>,
+        validity: Option<BooleanChunked>,
+    ) -> Self {
+        // nulls in the validity are null structs, if all rows are valid we don't need a validity
+        let mut validity = validity.and_then(|validity| {
+            let validity = validity.fill_none_with_value(false).unwrap();
+            if validity.all_true() {
+                None
+            } else {
+                Some(validity)
+            }
+        });
+
+        // the arrays of a struct should have equal length, so the chunks of the fields need to
+        // be aligned
+        let chunk_lengths = fields[0].chunk_lengths().collect::<Vec<_>>();
+        let aligned = fields
+            .iter()
+            .map(|s| s.chunk_lengths())
+            .chain(validity.iter().map(|ca| ca.chunk_id()))
+            .all(|lengths| lengths.eq(chunk_lengths.iter().copied()));
+        if !aligned {
+            fields = fields.iter().map(|s| s.rechunk()).collect();
+            validity = validity.map(|ca| ca.rechunk());
+        }
+
+        let dtype = DataType::Struct(fields.iter().map(|s| s.field().clone()).collect());
+        let arrow_dtype = dtype.to_arrow();
+        let chunks = (0..fields[0].n_chunks())
+            .map(|i| {
+                let len = fields[0].chunks()[i].len();
+                let mut builder = ArrayData::builder(arrow_dtype.clone()).len(len);
+                for s in &fields {
+                    builder = builder.add_child_data(s.chunks()[i].data().clone());
+                }
+                if let Some(validity) = &validity {
+                    let arr = validity.downcast_iter().nth(i).unwrap();
+                    let mut buf = MutableBuffer::from_len_zeroed(bit_util::ceil(len, 8));
+                    let bits = buf.as_slice_mut();
+                    (0..len)
+                        .filter(|&idx| arr.value(idx))
+                        .for_each(|idx| bit_util::set_bit(bits, idx));
+                    builder = builder.null_bit_buffer(buf.into());
+                }
+                make_array(builder.build())
+            })
+            .collect();
+
+        StructChunked {
+            field: Field::new(name, dtype),
+            fields,
+            validity,
+            chunks,
+        }
+    }
+
+    /// Name of the column.
+    pub fn name(&self) -> &str {
+        self.field.name()
+    }
+
+    /// Rename the column.
+    pub fn rename(&mut self, name: &str) {
+        self.field = Field::new(name, self.field.data_type().clone())
+    }
+
+    /// Get the `Struct` data type.
+    pub fn dtype(&self) -> &DataType {
+        self.field.data_type()
+    }
+
+    /// Get the field of this column.
+    pub fn ref_field(&self) -> &Field {
+        &self.field
+    }
+
+    /// The number of rows.
+    pub fn len(&self) -> usize {
+        self.fields[0].len()
+    }
+
+    /// Check if the column has no rows.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The arrow `StructArray`s of the fields.
+    pub fn chunks(&self) -> &Vec<ArrayRef> {
+        &self.chunks
+    }
+
+    /// The fields of the struct.
+    pub fn fields(&self) -> &[Series] {
+        &self.fields
+    }
+
+    /// Get the field with the given name.
+    pub fn field_by_name(&self, name: &str) -> Result<Series> {
+        self.fields
+            .iter()
+            .find(|s| s.name() == name)
+            .cloned()
+            .ok_or_else(|| {
+                PolarsError::NotFound(format!(
+                    "struct field {} not found in {}",
+                    name,
+                    self.name()
+                ))
+            })
+    }
+
+    /// The rows that are `false` are null. `None` if there are no null rows.
+    pub fn validity(&self) -> Option<&BooleanChunked> {
+        self.validity.as_ref()
+    }
+
+    /// Count the null rows.
+    pub fn null_count(&self) -> usize {
+        match &self.validity {
+            Some(validity) => validity.len() - validity.count_true(),
+            None => 0,
+        }
+    }
+
+    /// Get a mask of the null rows.
+    pub fn is_null(&self) -> BooleanChunked {
+        match &self.validity {
+            Some(validity) => {
+                let mut mask = !validity;
+                mask.rename("is_null");
+                mask
+            }
+            None => BooleanChunked::full("is_null", false, self.len()),
+        }
+    }
+
+    /// Get a mask of the valid rows.
+    pub fn is_not_null(&self) -> BooleanChunked {
+        match &self.validity {
+            Some(validity) => {
+                let mut mask = validity.clone();
+                mask.rename("is_not_null");
+                mask
+            }
+            None => BooleanChunked::full("is_not_null", true, self.len()),
+        }
+    }
+
+    /// Get the row at `index` as an `AnyValue::Struct` of the field values.
+    pub fn get_any_value(&self, index: usize) -> AnyValue {
+        match &self.validity {
+            Some(validity) if !validity.get(index).unwrap_or(false) => AnyValue::Null,
+            _ => AnyValue::Struct(self.fields.iter().map(|s| s.get(index)).collect()),
+        }
+    }
+
+    /// Append the rows of `other`. The data types should be equal.
+    pub fn append(&mut self, other: &StructChunked) -> Result<()> {
+        if self.dtype() != other.dtype() {
+            return Err(PolarsError::DataTypeMisMatch(
+                "cannot append Series; data types don't match".into(),
+            ));
+        }
+        let len = self.len();
+        let mut fields = self.fields.clone();
+        for (s, other) in fields.iter_mut().zip(other.fields()) {
+            s.append(other)?;
+        }
+        let validity = match (&self.validity, &other.validity) {
+            (None, None) => None,
+            (left, right) => {
+                let mut validity = left
+                    .clone()
+                    .unwrap_or_else(|| BooleanChunked::full("", true, len));
+                validity.append(
+                    &right
+                        .clone()
+                        .unwrap_or_else(|| BooleanChunked::full("", true, other.len())),
+                );
+                Some(validity)
+            }
+        };
+        *self = Self::new_unchecked(self.name(), fields, validity);
+        Ok(())
+    }
+
+    /// Apply `f` to the fields and the validity, e.g. to `filter` or `take` the rows. `f` should
+    /// return `Series` of equal lengths.
+    ///
+    /// If `materialize_validity` is `true`, `f` is also applied to an all `true` validity, such that
+    /// the nulls that `f` introduces (e.g. by `shift`) are null rows of the output.
+    pub fn try_apply_fields<F>(&self, materialize_validity: bool, f: F) -> Result<Self>
+    where
+        F: Fn(&Series) -> Result<Series>,
+    {
+        let fields = self.fields.iter().map(&f).collect::<Result<Vec<_>>>()?;
+        let validity = match (&self.validity, materialize_validity) {
+            (Some(validity), _) => Some(validity.clone()),
+            (None, true) => Some(BooleanChunked::full("", true, self.len())),
+            (None, false) => None,
+        };
+        let validity = match validity {
+            Some(validity) => Some(f(&validity.into_series())?.bool()?.clone()),
+            None => None,
+        };
+        Ok(Self::new_unchecked(self.name(), fields, validity))
+    }
+
+    /// See [try_apply_fields](StructChunked::try_apply_fields).
+    pub fn apply_fields<F>(&self, materialize_validity: bool, f: F) -> Self
+    where
+        F: Fn(&Series) -> Series,
+    {
+        self.try_apply_fields(materialize_validity, |s| Ok(f(s)))
+            .unwrap()
+    }
+}
+
+impl DataFrame {
+    /// Replace the `Struct` columns with their fields. The fields are inserted at the position of
+    /// the struct column. Null rows of the struct are null in all of its fields.
+    ///
+    /// An error is returned if a field has the name of another column, see
+    /// [unnest_with_suffix](DataFrame::unnest_with_suffix) to resolve the collisions.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use polars_core::prelude::*;
+    /// fn example(df: &DataFrame) -> Result<DataFrame> {
+    ///     df.unnest(&["payload"])
+    /// }
+    /// ```
+    pub fn unnest<I, S>(&self, columns: I) -> Result<DataFrame>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.unnest_with_suffix(columns, None)
+    }
+
+    /// Replace the `Struct` columns with their fields. If a field has the name of another
+    /// column, `suffix` is appended to the name of the field. An error is returned if the name
+    /// still collides, or if no `suffix` is given.
+    pub fn unnest_with_suffix<I, S>(&self, columns: I, suffix: Option<&str>) -> Result<DataFrame>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut unnest = HashSet::new();
+        for name in columns {
+            let s = self.column(name.as_ref())?;
+            s.struct_()?;
+            unnest.insert(s.name().to_string());
+        }
+
+        let mut names: HashSet<String> = self
+            .get_column_names()
+            .into_iter()
+            .filter(|name| !unnest.contains(*name))
+            .map(|name| name.to_string())
+            .collect();
+
+        let mut columns = Vec::with_capacity(self.width());
+        for s in self.get_columns() {
+            if !unnest.contains(s.name()) {
+                columns.push(s.clone());
+                continue;
+            }
+            let ca = s.struct_()?;
+            // propagate the null rows of the struct to the fields
+            let null_idx = ca.validity().map(|validity| {
+                validity
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, valid)| match valid {
+                        Some(true) => Some(i as IdxSize),
+                        _ => None,
+                    })
+                    .collect::<IdxCa>()
+            });
+            for field in ca.fields() {
+                let mut field = match &null_idx {
+                    // Safety:
+                    // the indices are in bounds
+                    Some(idx) => unsafe { field.take_unchecked(idx)? },
+                    None => field.clone(),
+                };
+                if names.contains(field.name()) {
+                    match suffix {
+                        Some(suffix) => {
+                            let name = format!("{}{}", field.name(), suffix);
+                            field.rename(&name);
+                        }
+                        None => {
+                            return Err(PolarsError::Duplicate(
+                                format!(
+                                    "cannot unnest {}, the field {} already exists as a column; \
+                                    use a suffix to rename the field",
+                                    ca.name(),
+                                    field.name()
+                                )
+                                .into(),
+                            ))
+                        }
+                    }
+                }
+                if !names.insert(field.name().to_string()) {
+                    return Err(PolarsError::Duplicate(
+                        format!(
+                            "cannot unnest {}, the column {} already exists",
+                            ca.name(),
+                            field.name()
+                        )
+                        .into(),
+                    ));
+                }
+                columns.push(field);
+            }
+        }
+        DataFrame::new(columns)
+    }
+}
+
+/// Build a `StructChunked` from arrow `StructArray`s.
+pub(crate) fn struct_from_arrow(name: &str, chunks: &[ArrayRef]) -> Result<StructChunked> {
+    let mut out: Option<StructChunked> = None;
+    for arr in chunks {
+        let fields = match arr.data_type() {
+            ArrowDataType::Struct(fields) => fields,
+            dt => {
+                return Err(PolarsError::DataTypeMisMatch(
+                    format!("expected a struct array, got {:?}", dt).into(),
+                ))
+            }
+        };
+        // the children are not sliced with the struct array, so we apply its offset
+        let columns = fields
+            .iter()
+            .zip(arr.data().child_data())
+            .map(|(field, child)| {
+                let child = make_array(child.clone()).slice(arr.offset(), arr.len());
+                Series::try_from((field.name().as_str(), vec![child]))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let validity = if arr.null_count() > 0 {
+            Some((0..arr.len()).map(|i| arr.is_valid(i)).collect())
+        } else {
+            None
+        };
+        let ca = StructChunked::new_with_validity(name, columns, validity)?;
+        match &mut out {
+            Some(out) => out.append(&ca)?,
+            None => out = Some(ca),
+        }
+    }
+    out.ok_or_else(|| PolarsError::NoData("cannot create a struct from no arrays".into()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::df;
+
+    #[test]
+    fn test_struct_ops() -> Result<()> {
+        let a = Series::new("a", &[Some(1), None, Some(3), Some(4)]);
+        let b = Series::new("b", &["x", "y", "z", "w"]);
+        let validity =
+            BooleanChunked::new_from_opt_slice("", &[Some(true), Some(true), Some(false), None]);
+        let ca = StructChunked::new_with_validity(
+            "payload",
+            vec![a.clone(), b.clone()],
+            Some(validity),
+        )?;
+        assert_eq!(
+            ca.dtype(),
+            &DataType::Struct(vec![a.field().clone(), b.field().clone()])
+        );
+        assert_eq!(ca.null_count(), 2);
+        assert!(ca.field_by_name("b")?.series_equal(&b));
+        assert!(ca.field_by_name("c").is_err());
+        assert_eq!(
+            ca.get_any_value(1),
+            AnyValue::Struct(vec![AnyValue::Null, AnyValue::Utf8("y")])
+        );
+        assert_eq!(ca.get_any_value(2), AnyValue::Null);
+        assert_eq!(format!("{}", ca.get_any_value(0)), "{1,\"x\"}");
+
+        let s = ca.into_series();
+        let out = s.filter(&BooleanChunked::new_from_slice(
+            "",
+            &[false, true, true, false],
+        ))?;
+        assert_eq!(
+            Vec::from(out.struct_()?.fields()[1].utf8()?),
+            &[Some("y"), Some("z")]
+        );
+        assert_eq!(out.null_count(), 1);
+
+        let idx = IdxCa::new_from_opt_slice("", &[Some(3), None, Some(0)]);
+        let out = s.take(&idx);
+        assert_eq!(
+            Vec::from(&out.is_null()),
+            &[Some(true), Some(true), Some(false)]
+        );
+        assert_eq!(out.struct_()?.fields()[0].get(2), AnyValue::Int32(1));
+
+        let out = s.slice(1, 2);
+        assert_eq!(out.len(), 2);
+        assert_eq!(out.get(0), s.get(1));
+
+        let out = s.shift(1);
+        assert_eq!(out.get(0), AnyValue::Null);
+        assert_eq!(out.get(1), s.get(0));
+
+        // structs don't cache statistics and have no minimum or maximum
+        assert_eq!(s.cached_min::<i32>(), None);
+        assert_eq!(s.cached_max::<i32>(), None);
+
+        // non-struct and invalid inputs
+        assert!(a.struct_().is_err());
+        assert!(StructChunked::new("payload", &[a.clone(), a.clone()]).is_err());
+        assert!(StructChunked::new("payload", &[a, b.slice(0, 2)]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_struct_vstack_arrow() -> Result<()> {
+        let a = Series::new("a", &[1, 2]);
+        let b = Series::new("b", &[0.5, 1.5]);
+        let ca = StructChunked::new("payload", &[a, b])?;
+        let validity = BooleanChunked::new_from_slice("", &[false, true]);
+        let ca_nulls =
+            StructChunked::new_with_validity("payload", ca.fields().to_vec(), Some(validity))?;
+
+        let df = DataFrame::new(vec![ca.into_series()])?;
+        let other = DataFrame::new(vec![ca_nulls.into_series()])?;
+        let out = df.vstack(&other)?;
+        let s = out.column("payload")?;
+        assert_eq!(s.len(), 4);
+        assert_eq!(s.null_count(), 1);
+        assert_eq!(s.n_chunks(), 2);
+
+        let from_arrow = Series::try_from(("payload", s.chunks().clone()))?;
+        assert_eq!(from_arrow.dtype(), s.dtype());
+        for i in 0..s.len() {
+            assert_eq!(from_arrow.get(i), s.get(i));
+        }
+        let from_arrow = Series::try_from(("payload", s.chunks()[1].slice(1, 1)))?;
+        assert_eq!(from_arrow.get(0), s.get(3));
+        Ok(())
+    }
+
+    #[test]
+    fn test_unnest() -> Result<()> {
+        let df = df![
+            "a" => [1, 2],
+            "b" => ["x", "y"],
+            "c" => [true, false]
+        ]?;
+        let payload = StructChunked::new("payload", &df.select_series(&["a", "b"])?)?;
+        let validity = BooleanChunked::new_from_slice("", &[true, false]);
+        let payload =
+            StructChunked::new_with_validity("payload", payload.fields().to_vec(), Some(validity))?;
+        let df = DataFrame::new(vec![df.column("c")?.clone(), payload.into_series()])?;
+
+        let out = df.unnest(&["payload"])?;
+        assert_eq!(out.get_column_names(), &["c", "a", "b"]);
+        assert_eq!(Vec::from(out.column("a")?.i32()?), &[Some(1), None]);
+        assert_eq!(Vec::from(out.column("b")?.utf8()?), &[Some("x"), None]);
+
+        // collisions
+        let payload = StructChunked::new("payload", &[Series::new("c", &[1, 2])])?;
+        let df = DataFrame::new(vec![df.column("c")?.clone(), payload.into_series()])?;
+        assert!(df.unnest(&["payload"]).is_err());
+        let out = df.unnest_with_suffix(&["payload"], Some("_payload"))?;
+        assert_eq!(out.get_column_names(), &["c", "c_payload"]);
+        assert!(df.unnest(&["c"]).is_err());
+        Ok(())
+    }
+}
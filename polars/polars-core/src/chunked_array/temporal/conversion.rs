@@ -7,9 +7,9 @@ use crate::chunked_array::kernels::temporal::{
 };
 use crate::prelude::*;
 use crate::utils::CustomIterTools;
+use chrono::format::{self, Item, Parsed, StrftimeItems};
 use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime};
 pub use conversions_utils::*;
-use regex::Regex;
 
 pub trait FromNaiveTime<T, N> {
     fn new_from_naive_time(name: &str, v: &[N]) -> Self;
@@ -165,174 +165,225 @@ impl AsNaiveTime for Time64NanosecondChunked {
     }
 }
 
-const N_PATTERNS: usize = 7;
-fn date_pattern(val: &str, id: usize) -> Option<&'static str> {
-    match id {
-        0 => {
-            // 2021-12-1
-            // 2021-12-31
-            let pat = r"^\d{4}-\d{1,2}-\d{1,2}\s*$";
-            let reg = Regex::new(pat).expect("wrong regex");
-            if reg.is_match(val) {
-                return Some("%Y-%m-%d");
-            }
-        }
-        1 => {
-            // 1-12-2021
-            // 31-12-2021
-            let pat = r"^\d{1,2}-\d{1,2}-\d{4}\s*$";
-            let reg = Regex::new(pat).expect("wrong regex");
-            if reg.is_match(val) {
-                return Some("%d-%m-%Y");
-            }
-        }
-        2 => {
-            // 2021/12/31 12:54:98
-            let pat = r"^\d{4}/\d{1,2}/\d{1,2}\s*$";
-            let reg = Regex::new(pat).expect("wrong regex");
-            if reg.is_match(val) {
-                return Some("%Y/%m/%d %H:%M:%S");
-            }
-        }
-        3 => {
-            // 2021-12-31 24:58:01
-            let pat = r"^\d{4}-\d{1,2}-\d{1,2} \d{2}:\d{2}:\d{2}\s*$";
-            let reg = Regex::new(pat).expect("wrong regex");
-            if reg.is_match(val) {
-                return Some("%Y-%m-%d %H:%M:%S");
-            }
-        }
-        4 => {
-            // 2021/12/31 24:58:01
-            let pat = r"^\d{4}/\d{1,2}/\d{1,2} \d{2}:\d{2}:\d{2}\s*$";
-            let reg = Regex::new(pat).expect("wrong regex");
-            if reg.is_match(val) {
-                return Some("%Y/%m/%d %H:%M:%S");
-            }
-        }
-        5 => {
-            // no separator
-            // 20210319 23:58:50
-            // 2021319 23:58:50
-            // 202131 23:58:50
-            let pat = r"^\d{6,8} \d{2}:\d{2}:\d{2}\s*$";
-            let reg = Regex::new(pat).expect("wrong regex");
-            if reg.is_match(val) {
-                return Some("%Y%m%d %H:%M:%S");
-            }
-        }
-        6 => {
-            // no separator
-            // 20210319
-            // 2021319 (2021-03-19)
-            // 202131 (2021-03-01)
-            let pat = r"^\d{6,8}\s*$";
-            let reg = Regex::new(pat).expect("wrong regex");
-            if reg.is_match(val) {
-                return Some("%Y%m%d");
-            }
+/// The formats that are tried, in this order, to infer the format of date(time) strings.
+/// The fractional seconds of the `%.f` formats are optional. A timezone offset is converted to
+/// UTC.
+pub const DATETIME_PATTERNS: &[&str] = &[
+    // 2021-12-31
+    "%Y-%m-%d",
+    // 31-12-2021
+    "%d-%m-%Y",
+    // 2021/12/31
+    "%Y/%m/%d",
+    // 20211231
+    "%Y%m%d",
+    // 2021-12-31T23:58:01.123+01:00
+    "%Y-%m-%dT%H:%M:%S%.f%:z",
+    // 2021-12-31T23:58:01.123Z
+    "%Y-%m-%dT%H:%M:%S%.fZ",
+    // 2021-12-31T23:58:01.123
+    "%Y-%m-%dT%H:%M:%S%.f",
+    // 2021-12-31 23:58:01.123+01:00
+    "%Y-%m-%d %H:%M:%S%.f%:z",
+    // 2021-12-31 23:58:01.123
+    "%Y-%m-%d %H:%M:%S%.f",
+    // 2021/12/31 23:58:01
+    "%Y/%m/%d %H:%M:%S%.f",
+    // 31-12-2021 23:58:01
+    "%d-%m-%Y %H:%M:%S%.f",
+    // 20211231 23:58:01
+    "%Y%m%d %H:%M:%S%.f",
+    // 2021-12-31T23:58
+    "%Y-%m-%dT%H:%M",
+    // 2021-12-31 23:58
+    "%Y-%m-%d %H:%M",
+];
+
+/// The number of non-null values that is used to infer the format of date(time) strings.
+const N_INFER_VALUES: usize = 16;
+
+/// A [strftime](chrono::format::strftime) format that is parsed once, so that the values
+/// can be parsed without parsing the format again.
+pub struct DatetimeFormat<'a> {
+    items: Vec<Item<'a>>,
+}
+
+impl<'a> DatetimeFormat<'a> {
+    pub fn new(fmt: &'a str) -> Result<Self> {
+        let items: Vec<_> = StrftimeItems::new(fmt).collect();
+        if items.iter().any(|item| matches!(item, Item::Error)) {
+            return Err(PolarsError::ComputeError(
+                format!("invalid date(time) format: {:?}", fmt).into(),
+            ));
         }
-        _ => (),
+        Ok(DatetimeFormat { items })
     }
-    None
-}
 
-impl Utf8Chunked {
-    fn get_first_val(&self) -> Result<&str> {
-        let idx = match self.first_non_null() {
-            Some(idx) => idx,
-            None => {
-                return Err(PolarsError::HasNullValues(
-                    "Cannot determine date parsing format, all values are null".into(),
-                ))
-            }
-        };
-        let val = self.get(idx).expect("should not be null");
-        Ok(val)
+    /// Parse a value. A value without a time is at midnight and a value with a timezone offset
+    /// is converted to UTC. Leading and trailing whitespace is ignored.
+    pub fn parse(&self, s: &str) -> Option<NaiveDateTime> {
+        let mut parsed = Parsed::new();
+        format::parse(&mut parsed, s.trim(), self.items.iter()).ok()?;
+        if parsed.offset.is_some() {
+            parsed.to_datetime().ok().map(|dt| dt.naive_utc())
+        } else if parsed.hour_mod_12.is_some() {
+            parsed.to_naive_datetime_with_offset(0).ok()
+        } else {
+            parsed
+                .to_naive_date()
+                .ok()
+                .map(|date| date.and_hms(0, 0, 0))
+        }
     }
+}
 
-    fn sniff_fmt_date64(&self) -> Result<&'static str> {
-        let val = self.get_first_val()?;
-        for id in 0..N_PATTERNS {
-            if let Some(pattern) = date_pattern(val, id) {
-                return Ok(pattern);
-            }
+/// Infer the format of date(time) strings. This is the one of the [DATETIME_PATTERNS] that
+/// parses most of the `values`, the first pattern wins a tie.
+pub fn infer_datetime_format(values: &[&str]) -> Option<&'static str> {
+    let mut best = None;
+    let mut best_count = 0;
+    for pattern in DATETIME_PATTERNS {
+        let fmt = DatetimeFormat::new(pattern).expect("valid pattern");
+        let count = values.iter().filter(|s| fmt.parse(s).is_some()).count();
+        if count > best_count {
+            best = Some(*pattern);
+            best_count = count;
         }
-        Err(PolarsError::Other(
-            "Could not find an appropriate format to parse dates, please define a fmt".into(),
-        ))
     }
+    best
+}
 
-    fn sniff_fmt_date32(&self) -> Result<&'static str> {
-        let val = self.get_first_val()?;
-        for id in 0..N_PATTERNS {
-            if let Some(pattern) = date_pattern(val, id) {
-                return Ok(pattern);
-            }
+impl Utf8Chunked {
+    fn sniff_fmt(&self) -> Result<&'static str> {
+        let values: Vec<_> = self.into_iter().flatten().take(N_INFER_VALUES).collect();
+        if values.is_empty() {
+            return Err(PolarsError::HasNullValues(
+                "Cannot determine date parsing format, all values are null".into(),
+            ));
         }
-        Err(PolarsError::Other(
-            "Could not find an appropriate format to parse dates, please define a fmt".into(),
-        ))
+        infer_datetime_format(&values).ok_or_else(|| {
+            PolarsError::Other(
+                format!(
+                    "Could not find an appropriate format to parse dates like {:?}, please define a fmt",
+                    values[0]
+                )
+                .into(),
+            )
+        })
     }
 
-    pub fn as_date32(&self, fmt: Option<&str>) -> Result<Date32Chunked> {
+    fn parse_datetime<T, F>(
+        &self,
+        fmt: Option<&str>,
+        strict: bool,
+        to_physical: F,
+    ) -> Result<ChunkedArray<T>>
+    where
+        T: PolarsPrimitiveType,
+        T::Native: Default,
+        F: Fn(NaiveDateTime) -> T::Native,
+    {
         let fmt = match fmt {
             Some(fmt) => fmt,
-            None => self.sniff_fmt_date32()?,
+            None => self.sniff_fmt()?,
         };
-
-        let mut ca: Date32Chunked = match self.null_count() {
-            0 => self
-                .into_no_null_iter()
-                .map(|s| parse_naive_date_from_str(s, fmt).map(naive_date_to_date32))
-                .collect(),
-            _ => self
-                .into_iter()
-                .map(|opt_s| {
-                    let opt_nd =
-                        opt_s.map(|s| parse_naive_date_from_str(s, fmt).map(naive_date_to_date32));
-                    match opt_nd {
-                        None => None,
-                        Some(None) => None,
-                        Some(Some(nd)) => Some(nd),
+        let fmt = DatetimeFormat::new(fmt)?;
+
+        let mut builder = PrimitiveChunkedBuilder::<T>::new(self.name(), self.len());
+        for (row, opt_s) in self.into_iter().enumerate() {
+            match opt_s {
+                Some(s) => match fmt.parse(s) {
+                    Some(dt) => builder.append_value(to_physical(dt)),
+                    None if strict && !s.trim().is_empty() => {
+                        return Err(PolarsError::Parse {
+                            dtype: T::get_dtype(),
+                            value: s.to_string(),
+                            row,
+                        })
                     }
-                })
-                .collect(),
-        };
-        ca.rename(self.name());
-        Ok(ca)
+                    None => builder.append_null(),
+                },
+                None => builder.append_null(),
+            }
+        }
+        Ok(builder.finish())
     }
 
+    /// Parse the values to `Date32` with a strptime `fmt`. If `fmt` is `None`, the format is
+    /// inferred from the first non-null values. Values that cannot be parsed become null.
+    pub fn as_date32(&self, fmt: Option<&str>) -> Result<Date32Chunked> {
+        self.parse_datetime(fmt, false, |dt| naive_date_to_date32(dt.date()))
+    }
+
+    /// Parse the values to `Date64` with a strptime `fmt`. If `fmt` is `None`, the format is
+    /// inferred from the first non-null values. Values that cannot be parsed become null.
     pub fn as_date64(&self, fmt: Option<&str>) -> Result<Date64Chunked> {
-        let fmt = match fmt {
-            Some(fmt) => fmt,
-            None => self.sniff_fmt_date64()?,
-        };
+        self.parse_datetime(fmt, false, |dt| naive_datetime_to_date64(&dt))
+    }
 
-        let mut ca: Date64Chunked = match self.null_count() {
-            0 => self
-                .into_no_null_iter()
-                .map(|s| {
-                    parse_naive_datetime_from_str(s, fmt).map(|dt| naive_datetime_to_date64(&dt))
-                })
-                .collect(),
-            _ => self
-                .into_iter()
-                .map(|opt_s| {
-                    let opt_nd = opt_s.map(|s| {
-                        parse_naive_datetime_from_str(s, fmt)
-                            .map(|dt| naive_datetime_to_date64(&dt))
-                    });
-                    match opt_nd {
-                        None => None,
-                        Some(None) => None,
-                        Some(Some(nd)) => Some(nd),
-                    }
-                })
-                .collect(),
-        };
-        ca.rename(self.name());
-        Ok(ca)
+    /// Parse the values to a `Date32` or `Date64` `dtype`, see [as_date32](Utf8Chunked::as_date32).
+    /// If `strict`, a value that cannot be parsed raises an error instead of becoming null.
+    pub fn strptime(&self, dtype: &DataType, fmt: Option<&str>, strict: bool) -> Result<Series> {
+        match dtype {
+            DataType::Date32 => self
+                .parse_datetime::<Date32Type, _>(fmt, strict, |dt| naive_date_to_date32(dt.date()))
+                .map(|ca| ca.into_series()),
+            DataType::Date64 => self
+                .parse_datetime::<Date64Type, _>(fmt, strict, |dt| naive_datetime_to_date64(&dt))
+                .map(|ca| ca.into_series()),
+            dt => Err(PolarsError::InvalidOperation(
+                format!(
+                    "cannot parse strings to {:?}, expected Date32 or Date64",
+                    dt
+                )
+                .into(),
+            )),
+        }
+    }
+}
+
+macro_rules! impl_duration_constructors {
+    ($ca_type:ident, $ms:expr) => {
+        impl $ca_type {
+            fn from_unit(name: &str, v: &[i64], factor: i64) -> Self {
+                Self::new_from_iter(name, v.iter().map(|v| *v * factor))
+            }
+
+            /// Create a duration array from a number of milliseconds.
+            pub fn from_millis(name: &str, v: &[i64]) -> Self {
+                Self::from_unit(name, v, $ms)
+            }
+
+            /// Create a duration array from a number of seconds.
+            pub fn from_seconds(name: &str, v: &[i64]) -> Self {
+                Self::from_unit(name, v, $ms * 1000)
+            }
+
+            /// Create a duration array from a number of minutes.
+            pub fn from_minutes(name: &str, v: &[i64]) -> Self {
+                Self::from_unit(name, v, $ms * 60_000)
+            }
+
+            /// Create a duration array from a number of hours.
+            pub fn from_hours(name: &str, v: &[i64]) -> Self {
+                Self::from_unit(name, v, $ms * 3_600_000)
+            }
+
+            /// Create a duration array from a number of days.
+            pub fn from_days(name: &str, v: &[i64]) -> Self {
+                Self::from_unit(name, v, $ms * 86_400_000)
+            }
+        }
+    };
+}
+
+impl_duration_constructors!(DurationMillisecondChunked, 1);
+impl_duration_constructors!(DurationNanosecondChunked, 1_000_000);
+
+impl DurationNanosecondChunked {
+    /// Create a duration array from a number of nanoseconds.
+    pub fn from_nanos(name: &str, v: &[i64]) -> Self {
+        Self::new_from_slice(name, v)
     }
 }
 
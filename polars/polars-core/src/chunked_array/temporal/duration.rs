@@ -0,0 +1,135 @@
+use super::conversions_utils::{date64_as_datetime, naive_datetime_to_date64};
+use crate::prelude::*;
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
+
+/// Determines which boundaries of a range or window are included.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ClosedWindow {
+    Left,
+    Right,
+    Both,
+    None,
+}
+
+impl ClosedWindow {
+    pub(crate) fn include_start(self) -> bool {
+        matches!(self, ClosedWindow::Left | ClosedWindow::Both)
+    }
+
+    pub(crate) fn include_stop(self) -> bool {
+        matches!(self, ClosedWindow::Right | ClosedWindow::Both)
+    }
+}
+
+/// A calendar aware interval.
+///
+/// Months (and years) don't have a fixed length, so they are stored separately
+/// from the fixed size part of the interval, which is stored in milliseconds.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Duration {
+    months: i64,
+    milliseconds: i64,
+}
+
+impl Duration {
+    pub fn new(months: i64, milliseconds: i64) -> Self {
+        Duration {
+            months,
+            milliseconds,
+        }
+    }
+
+    pub fn from_years(n: i64) -> Self {
+        Self::new(n * 12, 0)
+    }
+
+    pub fn from_months(n: i64) -> Self {
+        Self::new(n, 0)
+    }
+
+    pub fn from_weeks(n: i64) -> Self {
+        Self::from_days(n * 7)
+    }
+
+    pub fn from_days(n: i64) -> Self {
+        Self::from_hours(n * 24)
+    }
+
+    pub fn from_hours(n: i64) -> Self {
+        Self::from_minutes(n * 60)
+    }
+
+    pub fn from_minutes(n: i64) -> Self {
+        Self::from_seconds(n * 60)
+    }
+
+    pub fn from_seconds(n: i64) -> Self {
+        Self::from_milliseconds(n * 1000)
+    }
+
+    pub fn from_milliseconds(n: i64) -> Self {
+        Self::new(0, n)
+    }
+
+    pub fn months(&self) -> i64 {
+        self.months
+    }
+
+    pub fn milliseconds(&self) -> i64 {
+        self.milliseconds
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.months == 0 && self.milliseconds == 0
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.months < 0 || self.milliseconds < 0
+    }
+
+    /// A `Duration` that moves in two directions at once cannot be used to step.
+    pub(crate) fn has_mixed_sign(&self) -> bool {
+        (self.months < 0 && self.milliseconds > 0) || (self.months > 0 && self.milliseconds < 0)
+    }
+
+    /// Add `n` times this duration to a date64 (milliseconds since epoch) value.
+    ///
+    /// Month arithmetic clips to the last day of the month, e.g. January 31 + 1 month is
+    /// February 28 (or 29).
+    pub fn add_date64(&self, t: i64, n: i64) -> Result<i64> {
+        let mut t = t;
+        if self.months != 0 {
+            let dt = date64_as_datetime(t);
+            let dt = add_months(&dt, self.months * n).ok_or_else(|| {
+                PolarsError::OutOfBounds(
+                    format!(
+                        "adding {} months to {} is out of range",
+                        self.months * n,
+                        dt
+                    )
+                    .into(),
+                )
+            })?;
+            t = naive_datetime_to_date64(&dt);
+        }
+        Ok(t + self.milliseconds * n)
+    }
+}
+
+fn add_months(dt: &NaiveDateTime, months: i64) -> Option<NaiveDateTime> {
+    let total = dt.year() as i64 * 12 + dt.month0() as i64 + months;
+    let year = total.div_euclid(12) as i32;
+    let month = total.rem_euclid(12) as u32 + 1;
+
+    // clip the day to the last day of the target month
+    let mut day = dt.day();
+    loop {
+        if let Some(date) = NaiveDate::from_ymd_opt(year, month, day) {
+            return Some(date.and_time(dt.time()));
+        }
+        if day <= 28 {
+            return None;
+        }
+        day -= 1;
+    }
+}
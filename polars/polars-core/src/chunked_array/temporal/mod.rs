@@ -1,11 +1,13 @@
 //! Traits and utilities for temporal data.
 pub mod conversion;
 pub(crate) mod conversions_utils;
+mod duration;
 pub use self::conversion::{
     AsDuration, AsNaiveDate, AsNaiveDateTime, AsNaiveTime, FromNaiveDate, FromNaiveDateTime,
     FromNaiveTime,
 };
 pub(crate) use self::conversions_utils::*;
+pub use self::duration::{ClosedWindow, Duration};
 use chrono::NaiveDateTime;
 
 pub fn unix_time() -> NaiveDateTime {
@@ -63,4 +65,50 @@ mod test {
             ca.cont_slice().unwrap()
         );
     }
+
+    #[test]
+    fn test_strptime_inference() -> Result<()> {
+        let ca = Utf8Chunked::new_from_opt_slice(
+            "dates",
+            &[
+                None,
+                Some("2021-03-19T10:15:30.250"),
+                Some("2021-03-19T10:15:30"),
+                Some("foo"),
+            ],
+        );
+        // the format that parses most of the first values is used, the invalid value becomes null
+        let out = ca.as_date64(None)?;
+        assert_eq!(out.name(), "dates");
+        assert_eq!(
+            Vec::from(&out),
+            &[None, Some(1616148930250), Some(1616148930000), None]
+        );
+        let out = ca.as_date32(None)?;
+        assert_eq!(Vec::from(&out), &[None, Some(18705), Some(18705), None]);
+
+        // timezone offsets are converted to UTC
+        let ca = Utf8Chunked::new_from_slice(
+            "dates",
+            &["2021-03-19 10:15:30+02:00", "2021-03-19 10:15:30.5-01:00"],
+        );
+        let out = ca.as_date64(None)?;
+        assert_eq!(Vec::from(&out), &[Some(1616141730000), Some(1616152530500)]);
+
+        let ca = Utf8Chunked::new_from_slice("dates", &["19-03-2021", "20-03-2021", "x"]);
+        let out = ca.strptime(&DataType::Date32, None, false)?;
+        assert_eq!(out.dtype(), &DataType::Date32);
+        assert_eq!(out.null_count(), 1);
+        let err = ca.strptime(&DataType::Date32, None, true).unwrap_err();
+        assert!(matches!(
+            err,
+            PolarsError::Parse { dtype: DataType::Date32, ref value, row: 2 } if value == "x"
+        ));
+
+        let ca = Utf8Chunked::new_from_slice("dates", &["19 March 2021"]);
+        assert!(ca.as_date64(None).is_err());
+        let out = ca.as_date64(Some("%d %B %Y"))?;
+        assert_eq!(Vec::from(&out), &[Some(1616112000000)]);
+        Ok(())
+    }
 }
@@ -27,6 +27,7 @@ impl<T> Default for ChunkedArray<T> {
             phantom: PhantomData,
             categorical_map: None,
             bit_settings: 0,
+            stats: Default::default(),
         }
     }
 }
@@ -267,6 +268,7 @@ impl<T: PolarsObject> FromIterator<Option<T>> for ObjectChunked<T> {
             phantom: PhantomData,
             categorical_map: None,
             bit_settings: 0,
+            stats: Default::default(),
         }
     }
 }
@@ -7,6 +7,7 @@
 //! are currently supported.
 //!
 use crate::prelude::*;
+use crate::utils::get_supertype;
 use ahash::RandomState;
 pub use arrow::datatypes::DataType as ArrowDataType;
 pub use arrow::datatypes::{
@@ -136,6 +137,26 @@ pub type DurationMillisecondChunked = ChunkedArray<DurationMillisecondType>;
 pub type Time64NanosecondChunked = ChunkedArray<Time64NanosecondType>;
 pub type CategoricalChunked = ChunkedArray<CategoricalType>;
 
+/// The type of the row indices of joins, group tuples and takes. The `bigidx` feature switches
+/// it from `u32` to `u64`, for DataFrames with more than `u32::MAX` rows.
+#[cfg(not(feature = "bigidx"))]
+pub type IdxSize = u32;
+#[cfg(feature = "bigidx")]
+pub type IdxSize = u64;
+#[cfg(not(feature = "bigidx"))]
+pub type IdxType = UInt32Type;
+#[cfg(feature = "bigidx")]
+pub type IdxType = UInt64Type;
+/// A ChunkedArray of row indices.
+pub type IdxCa = ChunkedArray<IdxType>;
+/// An arrow array of row indices.
+pub type IdxArr = arrow::array::PrimitiveArray<IdxType>;
+/// The data type of a Series of row indices.
+#[cfg(not(feature = "bigidx"))]
+pub const IDX_DTYPE: DataType = DataType::UInt32;
+#[cfg(feature = "bigidx")]
+pub const IDX_DTYPE: DataType = DataType::UInt64;
+
 pub trait PolarsPrimitiveType: ArrowPrimitiveType + Send + Sync + PolarsDataType {}
 // impl PolarsPrimitiveType for BooleanType {}
 impl PolarsPrimitiveType for UInt8Type {}
@@ -233,6 +254,9 @@ pub enum AnyValue<'a> {
     #[cfg(feature = "object")]
     /// Use as_any to get a dyn Any
     Object(&'a str),
+    #[cfg(feature = "dtype-struct")]
+    /// The values of the fields of a struct.
+    Struct(Vec<AnyValue<'a>>),
 }
 
 impl From<f64> for AnyValue<'_> {
@@ -354,6 +378,8 @@ impl Display for DataType {
             #[cfg(feature = "object")]
             DataType::Object(s) => s,
             DataType::Categorical => "cat",
+            #[cfg(feature = "dtype-struct")]
+            DataType::Struct(fields) => return write!(f, "struct[{}]", fields.len()),
             _ => panic!("{:?} not implemented", self),
         };
         f.write_str(s)
@@ -381,9 +407,12 @@ impl PartialEq for AnyValue<'_> {
             (Time64(l, _), Time64(r, _)) => l == r,
             (Duration(l, _), Duration(r, _)) => l == r,
             (Boolean(l), Boolean(r)) => l == r,
-            (List(_), List(_)) => panic!("eq between list series not supported"),
+            // same length and equal elements, consistent with the hash of a list
+            (List(l), List(r)) => l.dtype() == r.dtype() && l.series_equal_missing(r),
             #[cfg(feature = "object")]
             (Object(_), Object(_)) => panic!("eq between object not supported"),
+            #[cfg(feature = "dtype-struct")]
+            (Struct(l), Struct(r)) => l == r,
             // should it?
             (Null, Null) => true,
             _ => false,
@@ -440,9 +469,21 @@ pub enum DataType {
     Object(&'static str),
     Null,
     Categorical,
+    #[cfg(feature = "dtype-struct")]
+    /// A nested type of named fields, see [StructChunked](crate::chunked_array::struct_::StructChunked).
+    Struct(Vec<Field>),
 }
 
 impl DataType {
+    /// Check if this is a signed or unsigned integer type.
+    pub fn is_integer(&self) -> bool {
+        use DataType::*;
+        matches!(
+            self,
+            Int8 | Int16 | Int32 | Int64 | UInt8 | UInt16 | UInt32 | UInt64
+        )
+    }
+
     pub fn to_arrow(&self) -> ArrowDataType {
         use DataType::*;
         match self {
@@ -461,14 +502,20 @@ impl DataType {
             Date32 => ArrowDataType::Date32,
             Date64 => ArrowDataType::Date64,
             Time64(tu) => ArrowDataType::Time64(tu.clone()),
-            List(dt) => {
-                ArrowDataType::List(Box::new(arrow::datatypes::Field::new("", dt.clone(), true)))
-            }
+            // the field name matches the arrays created by the list builders, so nested lists
+            // have the same arrow data type as their chunks
+            List(dt) => ArrowDataType::LargeList(Box::new(arrow::datatypes::Field::new(
+                "item",
+                dt.clone(),
+                true,
+            ))),
             Duration(tu) => ArrowDataType::Duration(tu.clone()),
             Null => ArrowDataType::Null,
             #[cfg(feature = "object")]
             Object(_) => unimplemented!(),
             Categorical => ArrowDataType::UInt32,
+            #[cfg(feature = "dtype-struct")]
+            Struct(fields) => ArrowDataType::Struct(fields.iter().map(|f| f.to_arrow()).collect()),
         }
     }
 }
@@ -480,7 +527,7 @@ impl PartialEq<ArrowDataType> for DataType {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Field {
     name: String,
     data_type: DataType,
@@ -583,7 +630,7 @@ impl Schema {
             }
         }
         let valid_fields: Vec<String> = self.fields.iter().map(|f| f.name().clone()).collect();
-        Err(PolarsError::NotFound(format!(
+        Err(PolarsError::ColumnNotFound(format!(
             "Unable to get field named \"{}\". Valid fields: {:?}",
             name, valid_fields
         )))
@@ -594,22 +641,37 @@ impl Schema {
         ArrowSchema::new(fields)
     }
 
-    pub fn try_merge(schemas: &[Self]) -> Result<Self> {
+    /// Merge schemas into a single schema that contains all fields, in order of first occurrence.
+    ///
+    /// Fields that occur in multiple schemas get the supertype of their data types.
+    /// An error is returned if no such supertype exists.
+    pub fn try_merge<I>(schemas: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = Schema>,
+    {
         let mut merged = Self::default();
 
         for schema in schemas {
             // merge fields
-            for field in &schema.fields {
-                let mut new_field = true;
-                for merged_field in &mut merged.fields {
-                    if field.name != merged_field.name {
-                        continue;
+            for field in schema.fields {
+                match merged.fields.iter_mut().find(|f| f.name == field.name) {
+                    Some(merged_field) => {
+                        if merged_field.data_type != field.data_type {
+                            let dtype = get_supertype(&merged_field.data_type, &field.data_type)
+                                .map_err(|_| {
+                                    PolarsError::DataTypeMisMatch(
+                                        format!(
+                                            "cannot merge field {} with data types {:?} and {:?}",
+                                            field.name, merged_field.data_type, field.data_type
+                                        )
+                                        .into(),
+                                    )
+                                })?;
+                            merged_field.coerce(dtype);
+                        }
                     }
-                    new_field = false;
-                }
-                // found a new field, add to field list
-                if new_field {
-                    merged.fields.push(field.clone());
+                    // found a new field, add to field list
+                    None => merged.fields.push(field),
                 }
             }
         }
@@ -627,6 +689,20 @@ impl Schema {
 
 pub type SchemaRef = Arc<Schema>;
 
+/// The arrow type of the values of the large list that polars creates from a list with values of
+/// type `dt`. Polars uses the large variants of the offset based types.
+pub(crate) fn large_list_values_dtype(dt: &ArrowDataType) -> ArrowDataType {
+    match dt {
+        ArrowDataType::Utf8 => ArrowDataType::LargeUtf8,
+        ArrowDataType::List(f) => ArrowDataType::LargeList(Box::new(ArrowField::new(
+            f.name(),
+            large_list_values_dtype(f.data_type()),
+            f.is_nullable(),
+        ))),
+        dt => dt.clone(),
+    }
+}
+
 impl From<&ArrowDataType> for DataType {
     fn from(dt: &ArrowDataType) -> Self {
         match dt {
@@ -644,6 +720,8 @@ impl From<&ArrowDataType> for DataType {
             ArrowDataType::Float32 => DataType::Float32,
             ArrowDataType::Float64 => DataType::Float64,
             ArrowDataType::LargeList(f) => DataType::List(f.data_type().clone()),
+            // list arrays are converted to large lists when a Series is created
+            ArrowDataType::List(f) => DataType::List(large_list_values_dtype(f.data_type())),
             ArrowDataType::Date32 => DataType::Date32,
             ArrowDataType::Date64 => DataType::Date64,
             ArrowDataType::Time64(TimeUnit::Nanosecond) => DataType::Time64(TimeUnit::Nanosecond),
@@ -654,6 +732,11 @@ impl From<&ArrowDataType> for DataType {
                 DataType::Duration(TimeUnit::Millisecond)
             }
             ArrowDataType::Utf8 => DataType::Utf8,
+            ArrowDataType::Dictionary(_, _) => DataType::Categorical,
+            #[cfg(feature = "dtype-struct")]
+            ArrowDataType::Struct(fields) => {
+                DataType::Struct(fields.iter().map(|f| f.into()).collect())
+            }
             dt => panic!("Arrow datatype {:?} not supported by Polars", dt),
         }
     }
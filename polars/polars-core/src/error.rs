@@ -1,3 +1,4 @@
+use crate::datatypes::DataType;
 use std::borrow::Cow;
 use thiserror::Error as ThisError;
 
@@ -15,12 +16,29 @@ pub enum PolarsError {
     DataTypeMisMatch(ErrString),
     #[error("Not found: {0}")]
     NotFound(String),
+    #[error("Column not found: {0}")]
+    ColumnNotFound(String),
+    #[error("Schema mismatch: expected {expected}, found {found}")]
+    SchemaMisMatch {
+        expected: ErrString,
+        found: ErrString,
+    },
     #[error("Lengths don't match: {0}")]
     ShapeMisMatch(ErrString),
     #[error("{0}")]
     Other(ErrString),
     #[error("Out of bounds: {0}")]
     OutOfBounds(ErrString),
+    #[error("Out of bounds: index {index} outside of length {len}")]
+    IndexOutOfBounds { index: usize, len: usize },
+    #[error("Could not parse {value:?} as {dtype:?} at row {row}")]
+    Parse {
+        dtype: DataType,
+        value: String,
+        row: usize,
+    },
+    #[error("{0}")]
+    ComputeError(ErrString),
     #[error("Not contiguous or null values")]
     NoSlice,
     #[error("Such empty...: {0}")]
@@ -44,10 +62,12 @@ pub enum PolarsError {
     #[error(transparent)]
     Io(#[from] std::io::Error),
     #[error(transparent)]
-    #[cfg(any(feature = "strings", feature = "temporal"))]
+    #[cfg(feature = "strings")]
     Regex(#[from] regex::Error),
     #[error("DuplicateError: {0}")]
     Duplicate(ErrString),
+    #[error("Resource exhausted: {0}")]
+    ResourceExhausted(ErrString),
     #[error("implementation error; this should not have happened.")]
     ImplementationError,
 }
@@ -1,3 +1,8 @@
+//! Formatting of [DataFrame] and [Series].
+//!
+//! The output is configured with [FmtConfig]. By default the configuration is read from the
+//! `POLARS_FMT_MAX_ROWS`, `POLARS_FMT_MAX_COLS`, `POLARS_FMT_STR_LEN` and
+//! `POLARS_FMT_HIDE_DTYPES` environment variables.
 use crate::prelude::*;
 
 #[cfg(feature = "temporal")]
@@ -5,11 +10,90 @@ use crate::chunked_array::temporal::{
     date32_as_datetime, date64_as_datetime, time64_nanosecond_as_time,
 };
 use num::{Num, NumCast};
+use std::borrow::Cow;
+use std::cell::RefCell;
 use std::{
     fmt,
     fmt::{Debug, Display, Formatter},
 };
+use unicode_width::UnicodeWidthChar;
 const LIMIT: usize = 25;
+const STR_LEN: usize = 15;
+#[cfg(any(feature = "plain_fmt", feature = "pretty_fmt"))]
+const FRAME_LIMIT: usize = 8;
+#[cfg(any(feature = "plain_fmt", feature = "pretty_fmt"))]
+const FRAME_STR_LEN: usize = 32;
+
+thread_local! {
+    static LOCAL_FMT_CONFIG: RefCell<Option<FmtConfig>> = RefCell::new(None);
+}
+
+/// Configuration of the formatting of a [DataFrame] and a [Series].
+#[derive(Clone, Debug)]
+pub struct FmtConfig {
+    /// Maximum number of rows shown. Defaults to 8 for a `DataFrame` and 25 for a `Series`.
+    pub max_rows: Option<usize>,
+    /// Maximum number of columns of a `DataFrame` shown.
+    pub max_cols: usize,
+    /// Maximum display width of a string or list value. Defaults to 32 for a `DataFrame` and 15
+    /// for a `Series`.
+    pub str_len: Option<usize>,
+    /// Show the data types below the column names of a `DataFrame`.
+    pub show_dtypes: bool,
+}
+
+impl Default for FmtConfig {
+    fn default() -> Self {
+        FmtConfig {
+            max_rows: None,
+            max_cols: 8,
+            str_len: None,
+            show_dtypes: true,
+        }
+    }
+}
+
+impl FmtConfig {
+    /// Read the configuration from the environment variables. Unset variables get the default.
+    pub fn from_env() -> Self {
+        fn parse_var(key: &str) -> Option<usize> {
+            std::env::var(key).ok().and_then(|v| v.parse().ok())
+        }
+        let default = FmtConfig::default();
+        FmtConfig {
+            max_rows: parse_var("POLARS_FMT_MAX_ROWS"),
+            max_cols: parse_var("POLARS_FMT_MAX_COLS").unwrap_or(default.max_cols),
+            str_len: parse_var("POLARS_FMT_STR_LEN"),
+            show_dtypes: std::env::var("POLARS_FMT_HIDE_DTYPES").is_err(),
+        }
+    }
+
+    /// Set the configuration of the current thread. This takes precedence over the environment
+    /// variables. Pass `None` to use the environment variables again.
+    pub fn set_local(config: Option<FmtConfig>) {
+        LOCAL_FMT_CONFIG.with(|local| *local.borrow_mut() = config)
+    }
+
+    /// Get the configuration used by the current thread.
+    pub fn current() -> Self {
+        LOCAL_FMT_CONFIG
+            .with(|local| local.borrow().clone())
+            .unwrap_or_else(FmtConfig::from_env)
+    }
+}
+
+/// Truncate a value to a display width of at most `max_width` and mark truncation with `...`.
+/// Wide characters, e.g. East Asian characters, count for two columns.
+fn truncate_str(v: &str, max_width: usize) -> Cow<'_, str> {
+    let mut width = 0;
+    for (i, c) in v.char_indices() {
+        width += c.width().unwrap_or(0);
+        if width > max_width {
+            return Cow::Owned(format!("{}...", &v[..i]));
+        }
+    }
+    Cow::Borrowed(v)
+}
 
 #[cfg(feature = "pretty_fmt")]
 use comfy_table::modifiers::UTF8_ROUND_CORNERS;
@@ -68,8 +152,6 @@ mod temporal {
         v
     }
 }
-#[cfg(any(feature = "plain_fmt", feature = "pretty_fmt"))]
-use std::borrow::Cow;
 #[cfg(not(feature = "temporal"))]
 use temporal::*;
 
@@ -83,23 +165,14 @@ macro_rules! format_array {
             $name,
             $dtype
         )?;
-        let truncate = matches!($a.dtype(), DataType::Utf8);
+        let truncate = matches!($a.dtype(), DataType::Utf8 | DataType::List(_));
+        let str_len = FmtConfig::current().str_len.unwrap_or(STR_LEN);
         let limit = std::cmp::min($limit, $a.len());
 
         let write = |v, f: &mut Formatter| {
             if truncate {
                 let v = format!("{}", v);
-                let v_trunc = &v[..v
-                    .char_indices()
-                    .take(15)
-                    .last()
-                    .map(|(i, c)| i + c.len_utf8())
-                    .unwrap_or(0)];
-                if v == v_trunc {
-                    write!(f, "\t{}\n", v)?;
-                } else {
-                    write!(f, "\t{}...\n", v_trunc)?;
-                }
+                write!(f, "\t{}\n", truncate_str(&v, str_len))?;
             } else {
                 write!(f, "\t{}\n", v)?;
             };
@@ -159,7 +232,7 @@ fn format_object_array(
 
 macro_rules! set_limit {
     ($self:ident) => {
-        std::cmp::min($self.len(), LIMIT)
+        std::cmp::min($self.len(), FmtConfig::current().max_rows.unwrap_or(LIMIT))
     };
 }
 
@@ -264,7 +337,7 @@ impl Debug for Series {
                 format_array!(limit, f, self.u8().unwrap(), "u8", self.name(), "Series")
             }
             DataType::UInt16 => {
-                format_array!(limit, f, self.u16().unwrap(), "u6", self.name(), "Series")
+                format_array!(limit, f, self.u16().unwrap(), "u16", self.name(), "Series")
             }
             DataType::UInt32 => {
                 format_array!(limit, f, self.u32().unwrap(), "u32", self.name(), "Series")
@@ -350,6 +423,15 @@ impl Debug for Series {
                 self.name(),
                 "Series"
             ),
+            #[cfg(feature = "dtype-struct")]
+            DataType::Struct(_) => format_array!(
+                limit,
+                f,
+                self.struct_().unwrap(),
+                "struct",
+                self.name(),
+                "Series"
+            ),
             _ => unimplemented!(),
         }
     }
@@ -368,52 +450,64 @@ impl Debug for DataFrame {
 }
 
 #[cfg(any(feature = "plain_fmt", feature = "pretty_fmt"))]
-fn prepare_row(row: Vec<Cow<'_, str>>, n_first: usize, n_last: usize) -> Vec<String> {
-    fn make_str_val(v: &str) -> String {
-        let string_limit = 32;
-        let v_trunc = &v[..v
-            .char_indices()
-            .take(string_limit)
-            .last()
-            .map(|(i, c)| i + c.len_utf8())
-            .unwrap_or(0)];
-        if v == v_trunc {
-            v.to_string()
-        } else {
-            format!("{}...", v_trunc)
-        }
-    }
-
+fn prepare_row(
+    row: Vec<Cow<'_, str>>,
+    n_first: usize,
+    n_last: usize,
+    str_len: usize,
+) -> Vec<String> {
     let reduce_columns = n_first + n_last < row.len();
     let mut row_str = Vec::with_capacity(n_first + n_last + reduce_columns as usize);
     for v in row[0..n_first].iter() {
-        row_str.push(make_str_val(v));
+        row_str.push(truncate_str(v, str_len).into_owned());
     }
     if reduce_columns {
         row_str.push("...".to_string());
     }
     for v in row[row.len() - n_last..].iter() {
-        row_str.push(make_str_val(v));
+        row_str.push(truncate_str(v, str_len).into_owned());
     }
     row_str
 }
 
+/// Get the rows that are shown. If rows are omitted, the middle row is a row of `...`.
+#[cfg(any(feature = "plain_fmt", feature = "pretty_fmt"))]
+fn prepare_rows(
+    df: &DataFrame,
+    max_n_rows: usize,
+    n_first: usize,
+    n_last: usize,
+    str_len: usize,
+) -> Vec<Vec<String>> {
+    let height = df.height();
+    if df.width() == 0 {
+        return vec![];
+    }
+    let get_row = |i| {
+        let row = df.columns.iter().map(|s| s.str_value(i)).collect();
+        prepare_row(row, n_first, n_last, str_len)
+    };
+    if height > max_n_rows {
+        let (n_top, n_bottom) = ((max_n_rows + 1) / 2, max_n_rows / 2);
+        let n_cols = n_first + n_last + (n_first + n_last < df.width()) as usize;
+        let mut rows = Vec::with_capacity(max_n_rows + 1);
+        rows.extend((0..n_top).map(get_row));
+        rows.push(vec!["...".to_string(); n_cols]);
+        rows.extend((height - n_bottom..height).map(get_row));
+        rows
+    } else {
+        (0..height).map(get_row).collect()
+    }
+}
+
 impl Display for DataFrame {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         let height = self.height();
         if !self.columns.iter().all(|s| s.len() == height) {
             panic!("The columns lengths in the DataFrame are not equal.");
         }
-
-        let max_n_cols = std::env::var("POLARS_FMT_MAX_COLS")
-            .unwrap_or_else(|_| "8".to_string())
-            .parse()
-            .unwrap_or(8);
-        #[cfg(any(feature = "plain_fmt", feature = "pretty_fmt"))]
-        let max_n_rows = std::env::var("POLARS_FMT_MAX_ROWS")
-            .unwrap_or_else(|_| "8".to_string())
-            .parse()
-            .unwrap_or(8);
+        let config = FmtConfig::current();
+        let max_n_cols = config.max_cols;
 
         let (n_first, n_last) = if self.width() > max_n_cols {
             ((max_n_cols + 1) / 2, max_n_cols / 2)
@@ -422,7 +516,13 @@ impl Display for DataFrame {
         };
         let reduce_columns = n_first + n_last < self.width();
 
-        let field_to_str = |f: &Field| format!("{}\n---\n{}", f.name(), f.data_type());
+        let field_to_str = |f: &Field| {
+            if config.show_dtypes {
+                format!("{}\n---\n{}", f.name(), f.data_type())
+            } else {
+                f.name().to_string()
+            }
+        };
 
         let mut names = Vec::with_capacity(n_first + n_last + reduce_columns as usize);
         let schema = self.schema();
@@ -436,6 +536,15 @@ impl Display for DataFrame {
         for field in fields[self.width() - n_last..].iter() {
             names.push(field_to_str(field))
         }
+        #[cfg(any(feature = "plain_fmt", feature = "pretty_fmt"))]
+        let rows = prepare_rows(
+            self,
+            config.max_rows.unwrap_or(FRAME_LIMIT),
+            n_first,
+            n_last,
+            config.str_len.unwrap_or(FRAME_STR_LEN),
+        );
+
         #[cfg(feature = "pretty_fmt")]
         {
             let mut table = Table::new();
@@ -452,30 +561,8 @@ impl Display for DataFrame {
                         .unwrap_or(100),
                 )
                 .set_header(names);
-            let mut rows = Vec::with_capacity(max_n_rows);
-            if self.height() > max_n_rows {
-                for i in 0..(max_n_rows / 2) {
-                    let row = self.columns.iter().map(|s| s.str_value(i)).collect();
-                    rows.push(prepare_row(row, n_first, n_last));
-                }
-                let dots = rows[0].iter().map(|_| "...".to_string()).collect();
-                rows.push(dots);
-                for i in (self.height() - max_n_rows / 2 - 1)..self.height() {
-                    let row = self.columns.iter().map(|s| s.str_value(i)).collect();
-                    rows.push(prepare_row(row, n_first, n_last));
-                }
-                for row in rows {
-                    table.add_row(row);
-                }
-            } else {
-                for i in 0..max_n_rows {
-                    if i < self.height() && self.width() > 0 {
-                        let row = self.columns.iter().map(|s| s.str_value(i)).collect();
-                        table.add_row(prepare_row(row, n_first, n_last));
-                    } else {
-                        break;
-                    }
-                }
+            for row in rows {
+                table.add_row(row);
             }
 
             write!(f, "shape: {:?}\n{}", self.shape(), table)?;
@@ -493,40 +580,22 @@ impl Display for DataFrame {
         {
             let mut table = Table::new();
             table.set_titles(Row::new(names.into_iter().map(|s| Cell::new(&s)).collect()));
-            let mut rows = Vec::with_capacity(max_n_rows);
-            if self.height() > max_n_rows {
-                for i in 0..(max_n_rows / 2) {
-                    let row = self.columns.iter().map(|s| s.str_value(i)).collect();
-                    rows.push(prepare_row(row, n_first, n_last));
-                }
-                let dots = rows[0].iter().map(|_| "...".to_string()).collect();
-                rows.push(dots);
-                for i in (self.height() - max_n_rows / 2 - 1)..self.height() {
-                    let row = self.columns.iter().map(|s| s.str_value(i)).collect();
-                    rows.push(prepare_row(row, n_first, n_last));
-                }
-                for row in rows {
-                    table.add_row(Row::new(row.into_iter().map(|s| Cell::new(&s)).collect()));
-                }
-            } else {
-                for i in 0..max_n_rows {
-                    if i < self.height() && self.width() > 0 {
-                        let row = self.columns.iter().map(|s| s.str_value(i)).collect();
-                        table.add_row(Row::new(
-                            prepare_row(row, n_first, n_last)
-                                .into_iter()
-                                .map(|s| Cell::new(&s))
-                                .collect(),
-                        ));
-                    } else {
-                        break;
-                    }
-                }
+            for row in rows {
+                table.add_row(Row::new(row.into_iter().map(|s| Cell::new(&s)).collect()));
             }
 
             write!(f, "shape: {:?}\n{}", self.shape(), table)?;
         }
 
+        #[cfg(any(feature = "plain_fmt", feature = "pretty_fmt"))]
+        {
+            if reduce_columns {
+                let n_hidden = self.width() - n_first - n_last;
+                let plural = if n_hidden == 1 { "" } else { "s" };
+                write!(f, "\n{} column{} hidden", n_hidden, plural)?;
+            }
+        }
+
         Ok(())
     }
 }
@@ -550,6 +619,40 @@ fn fmt_float<T: Num + NumCast>(f: &mut Formatter<'_>, width: usize, v: T) -> fmt
     }
 }
 
+const DURATION_PARTS: [(&str, u128); 6] = [
+    ("d", 86_400_000_000_000),
+    ("h", 3_600_000_000_000),
+    ("m", 60_000_000_000),
+    ("s", 1_000_000_000),
+    ("ms", 1_000_000),
+    ("ns", 1),
+];
+
+/// Format a duration in nanoseconds as its nonzero components, e.g. `2d 3h 15m`. This takes an
+/// `i128`, as a duration in milliseconds may not fit in an `i64` in nanoseconds.
+fn fmt_duration_ns(f: &mut Formatter<'_>, v: i128) -> fmt::Result {
+    if v == 0 {
+        return write!(f, "0ms");
+    }
+    if v < 0 {
+        write!(f, "-")?;
+    }
+    let mut rem = v.unsigned_abs();
+    let mut first = true;
+    for (unit, size) in DURATION_PARTS.iter() {
+        let n = rem / *size;
+        rem %= *size;
+        if n > 0 {
+            if !first {
+                write!(f, " ")?;
+            }
+            write!(f, "{}{}", n, unit)?;
+            first = false;
+        }
+    }
+    Ok(())
+}
+
 impl Display for AnyValue<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         let width = 0;
@@ -574,11 +677,24 @@ impl Display for AnyValue<'_> {
             AnyValue::Time64(v, TimeUnit::Nanosecond) => {
                 write!(f, "{}", time64_nanosecond_as_time(*v))
             }
-            AnyValue::Duration(v, TimeUnit::Nanosecond) => write!(f, "{}", v),
-            AnyValue::Duration(v, TimeUnit::Millisecond) => write!(f, "{}", v),
+            AnyValue::Duration(v, TimeUnit::Nanosecond) => fmt_duration_ns(f, *v as i128),
+            AnyValue::Duration(v, TimeUnit::Millisecond) => {
+                fmt_duration_ns(f, *v as i128 * 1_000_000)
+            }
             AnyValue::List(s) => write!(f, "{}", s.fmt_list()),
             #[cfg(feature = "object")]
             AnyValue::Object(_) => write!(f, "object"),
+            #[cfg(feature = "dtype-struct")]
+            AnyValue::Struct(vals) => {
+                write!(f, "{{")?;
+                for (i, v) in vals.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", v)?;
+                }
+                write!(f, "}}")
+            }
             _ => unimplemented!(),
         }
     }
@@ -664,6 +780,18 @@ impl<T> FmtList for ObjectChunked<T> {
     }
 }
 
+#[cfg(feature = "dtype-struct")]
+impl FmtList for StructChunked {
+    fn fmt_list(&self) -> String {
+        let v = |i| format!("{}", self.get_any_value(i));
+        match self.len() {
+            0 => "[]".to_string(),
+            len if len <= 3 => format!("[{}]", (0..len).map(v).collect::<Vec<_>>().join(", ")),
+            len => format!("[{}, {}, ... {}]", v(0), v(1), v(len - 1)),
+        }
+    }
+}
+
 #[cfg(all(
     test,
     feature = "temporal",
@@ -781,7 +909,7 @@ Series: 'foo' [str]
             r#"shape: (1,)
 Series: 'foo' [str]
 [
-	"😀😁😂😃😄😅😆😇😈😉😊😋😌😎...
+	"😀😁😂😃😄😅😆...
 ]"#,
             format!("{:?}", s)
         );
@@ -833,4 +961,64 @@ Series: 'foo' [i32]
             format!("{:?}", s)
         );
     }
+
+    #[test]
+    fn test_truncate_str() {
+        use super::truncate_str;
+        assert_eq!(truncate_str("abc", 3), "abc");
+        assert_eq!(truncate_str("abcd", 3), "abc...");
+        // wide characters take two columns
+        assert_eq!(truncate_str("日本語", 6), "日本語");
+        assert_eq!(truncate_str("日本語", 5), "日本...");
+    }
+
+    #[test]
+    fn test_fmt_config() {
+        let config = FmtConfig {
+            max_rows: Some(2),
+            max_cols: 2,
+            str_len: Some(5),
+            show_dtypes: false,
+        };
+        FmtConfig::set_local(Some(config));
+
+        let s = Series::new("foo", (0..10).collect::<Vec<_>>());
+        assert_eq!(
+            r#"shape: (10,)
+Series: 'foo' [i32]
+[
+	0
+	...
+	9
+]"#,
+            format!("{}", s)
+        );
+
+        #[cfg(any(feature = "plain_fmt", feature = "pretty_fmt"))]
+        {
+            let df = df![
+                "a" => ["abcdefgh", "b", "c"],
+                "middle" => [1, 2, 3],
+                "c" => [4, 5, 6]
+            ]
+            .unwrap();
+            let out = format!("{}", df);
+            assert!(out.contains("\"abcd..."));
+            assert!(out.contains("..."));
+            assert!(!out.contains("---"));
+            assert!(!out.contains("middle"));
+            assert!(out.ends_with("1 column hidden"));
+
+            let df = df![
+                "a" => [1],
+                "b" => [2],
+                "c" => [3],
+                "d" => [4]
+            ]
+            .unwrap();
+            assert!(format!("{}", df).ends_with("2 columns hidden"));
+        }
+
+        FmtConfig::set_local(None);
+    }
 }
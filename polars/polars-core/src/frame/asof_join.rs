@@ -4,7 +4,7 @@ use num::Bounded;
 use polars_arrow::trusted_len::PushUnchecked;
 
 pub(crate) trait JoinAsof<T: PolarsDataType> {
-    fn join_asof(&self, _other: &ChunkedArray<T>) -> Result<Vec<Option<u32>>> {
+    fn join_asof(&self, _other: &ChunkedArray<T>) -> Result<Vec<Option<IdxSize>>> {
         Err(PolarsError::InvalidOperation(
             format!(
                 "asof join not implemented for key with dtype: {:?}",
@@ -20,11 +20,11 @@ where
     T: PolarsNumericType,
     T::Native: Bounded,
 {
-    fn join_asof(&self, other: &ChunkedArray<T>) -> Result<Vec<Option<u32>>> {
+    fn join_asof(&self, other: &ChunkedArray<T>) -> Result<Vec<Option<IdxSize>>> {
         let mut rhs_iter = other.into_iter();
         let mut tuples = Vec::with_capacity(self.len());
         if self.null_count() > 0 {
-            return Err(PolarsError::Other(
+            return Err(PolarsError::HasNullValues(
                 "keys of asof join should not have null values".into(),
             ));
         }
@@ -41,7 +41,7 @@ where
         for arr in self.downcast_iter() {
             for &lhs_val in arr.values() {
                 if lhs_val < previous_lhs_val {
-                    return Err(PolarsError::Other(
+                    return Err(PolarsError::ComputeError(
                         "left key of asof join must be sorted".into(),
                     ));
                 }
@@ -101,21 +101,31 @@ impl DataFrame {
     /// This is similar to a left-join except that we match on nearest key rather than equal keys.
     /// The keys must be sorted to perform an asof join
     pub fn join_asof(&self, other: &DataFrame, left_on: &str, right_on: &str) -> Result<DataFrame> {
+        self.join_asof_with_suffix(other, left_on, right_on, None)
+    }
+
+    pub(crate) fn join_asof_with_suffix(
+        &self,
+        other: &DataFrame,
+        left_on: &str,
+        right_on: &str,
+        suffix: Option<&str>,
+    ) -> Result<DataFrame> {
         let left_key = self.column(left_on)?;
         let right_key = other.column(right_on)?;
 
         let take_idx = left_key.join_asof(right_key)?;
         // Safety:
-        // join tuples are in bounds
+        // the asof indices are in bounds of the right key.
         let right_df = unsafe {
-            other.take_opt_iter_unchecked(
+            other.take_join_opt_idx(
                 take_idx
                     .into_iter()
                     .map(|opt_idx| opt_idx.map(|idx| idx as usize)),
             )
         };
 
-        self.finish_join(self.clone(), right_df)
+        self.finish_join(self.clone(), right_df, suffix)
     }
 }
 
@@ -94,13 +94,14 @@ impl DataFrame {
                 // expand all the other columns based the exploded first column
                 if i == 0 {
                     let row_idx = offsets_to_indexes(offsets, exploded.len());
-                    let row_idx = UInt32Chunked::new_from_aligned_vec("", row_idx);
+                    let row_idx = IdxCa::new_from_aligned_vec("", row_idx);
                     // Safety
                     // We just created indices that are in bounds.
                     df = unsafe { df.take_unchecked(&row_idx) };
                 }
                 if exploded.len() == df.height() {
                     df.columns.insert(col_idx, exploded);
+                    df.name_index.invalidate();
                 } else {
                     return Err(PolarsError::ShapeMisMatch(
                         format!("The exploded columns don't have the same length. Length DataFrame: {}. Length exploded column {}: {}", df.height(), exploded.name(), exploded.len()).into(),
@@ -2,9 +2,11 @@ use crate::POOL;
 use ahash::RandomState;
 use num::{Bounded, Num, NumCast, ToPrimitive, Zero};
 use rayon::prelude::*;
+use std::cmp::Ordering;
 use std::collections::HashSet;
 use std::hash::Hash;
 
+use crate::chunked_array::builder::ListSlicesChunkedBuilder;
 use crate::chunked_array::kernels::take_agg::{
     take_agg_no_null_primitive_iter_unchecked, take_agg_primitive_iter_unchecked,
     take_agg_primitive_iter_unchecked_count_nulls,
@@ -17,35 +19,35 @@ use polars_arrow::builder::{BooleanArrayBuilder, LargeStringBuilder};
 use std::convert::TryFrom;
 
 pub(crate) trait NumericAggSync {
-    fn agg_mean(&self, _groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+    fn agg_mean(&self, _groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
         None
     }
-    fn agg_min(&self, _groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+    fn agg_min(&self, _groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
         None
     }
-    fn agg_max(&self, _groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+    fn agg_max(&self, _groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
         None
     }
-    fn agg_sum(&self, _groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+    fn agg_sum(&self, _groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
         None
     }
-    fn agg_std(&self, _groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+    fn agg_std(&self, _groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
         None
     }
-    fn agg_var(&self, _groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+    fn agg_var(&self, _groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
         None
     }
 
     /// Count the valid values. That is length - null_count
     /// Used in partitioned aggregation to compute the mean values.
-    fn agg_valid_count(&self, _groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+    fn agg_valid_count(&self, _groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
         None
     }
 }
 
-fn agg_helper<T, F>(groups: &[(u32, Vec<u32>)], f: F) -> Option<Series>
+fn agg_helper<T, F>(groups: &[(IdxSize, Vec<IdxSize>)], f: F) -> Option<Series>
 where
-    F: Fn(&(u32, Vec<u32>)) -> Option<T::Native> + Send + Sync,
+    F: Fn(&(IdxSize, Vec<IdxSize>)) -> Option<T::Native> + Send + Sync,
     T: PolarsNumericType,
     ChunkedArray<T>: IntoSeries,
 {
@@ -53,14 +55,65 @@ where
     Some(ca.into_series())
 }
 
+impl<T> ChunkedArray<T>
+where
+    T: PolarsNumericType,
+{
+    /// Fold the values of every group with `f`, starting at `init`. The null values are skipped
+    /// and a group without non-null values results in a null value.
+    ///
+    /// The groups are folded by iterating over the group indices into the values of the array,
+    /// so no `ChunkedArray` is created per group.
+    pub fn agg_fold<F>(&self, groups: &[(IdxSize, Vec<IdxSize>)], init: T::Native, f: F) -> Self
+    where
+        F: Fn(T::Native, T::Native) -> T::Native + Send + Sync,
+    {
+        let ca = self.rechunk();
+        let arr = match ca.downcast_iter().next() {
+            Some(arr) => arr,
+            None => return Self::full_null(self.name(), groups.len()),
+        };
+        let values = arr.values();
+        let mut out: Self = if ca.null_count() == 0 {
+            POOL.install(|| {
+                groups
+                    .par_iter()
+                    .map(|(_, idx)| {
+                        if idx.is_empty() {
+                            None
+                        } else {
+                            Some(idx.iter().fold(init, |acc, &i| f(acc, values[i as usize])))
+                        }
+                    })
+                    .collect()
+            })
+        } else {
+            POOL.install(|| {
+                groups
+                    .par_iter()
+                    .map(|(_, idx)| {
+                        idx.iter()
+                            .filter(|&&i| arr.is_valid(i as usize))
+                            .fold(None, |acc, &i| {
+                                Some(f(acc.unwrap_or(init), values[i as usize]))
+                            })
+                    })
+                    .collect()
+            })
+        };
+        out.rename(self.name());
+        out
+    }
+}
+
 impl NumericAggSync for BooleanChunked {
-    fn agg_min(&self, groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+    fn agg_min(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
         self.cast::<UInt32Type>().unwrap().agg_min(groups)
     }
-    fn agg_max(&self, groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+    fn agg_max(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
         self.cast::<UInt32Type>().unwrap().agg_max(groups)
     }
-    fn agg_sum(&self, groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+    fn agg_sum(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
         self.cast::<UInt32Type>().unwrap().agg_sum(groups)
     }
 }
@@ -76,49 +129,47 @@ where
     T::Native: std::ops::Add<Output = T::Native> + Num + NumCast + Bounded,
     ChunkedArray<T>: IntoSeries,
 {
-    fn agg_mean(&self, groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+    fn agg_mean(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
+        // the values are summed as f64, so that integers don't overflow and floats don't
+        // lose precision
         agg_helper::<Float64Type, _>(groups, |(first, idx)| {
             if idx.is_empty() {
                 None
             } else if idx.len() == 1 {
-                self.get(*first as usize).map(|sum| sum.to_f64().unwrap())
+                self.get(*first as usize).map(|v| v.to_f64().unwrap())
             } else {
                 match (self.null_count(), self.chunks.len()) {
-                    (0, 1) => unsafe {
-                        take_agg_no_null_primitive_iter_unchecked(
-                            self.downcast_iter().next().unwrap(),
-                            idx.iter().map(|i| *i as usize),
-                            |a, b| a + b,
-                            T::Native::zero(),
-                        )
+                    (0, 1) => {
+                        let sum = unsafe {
+                            take_agg_no_null_primitive_iter_unchecked(
+                                self.downcast_iter().next().unwrap(),
+                                idx.iter().map(|i| *i as usize),
+                                |a: f64, b| a + b.to_f64().unwrap(),
+                                0.0,
+                            )
+                        };
+                        Some(sum / idx.len() as f64)
                     }
-                    .to_f64()
-                    .map(|sum| sum / idx.len() as f64),
                     (_, 1) => unsafe {
                         take_agg_primitive_iter_unchecked_count_nulls(
                             self.downcast_iter().next().unwrap(),
                             idx.iter().map(|i| *i as usize),
-                            |a, b| a + b,
-                            T::Native::zero(),
+                            |a: f64, b| a + b.to_f64().unwrap(),
+                            0.0,
                         )
                     }
-                    .map(|(sum, null_count)| {
-                        sum.to_f64()
-                            .map(|sum| sum / (idx.len() as f64 - null_count as f64))
-                            .unwrap()
-                    }),
+                    .map(|(sum, null_count)| sum / (idx.len() as f64 - null_count as f64)),
                     _ => {
                         let take =
                             unsafe { self.take_unchecked(idx.iter().map(|i| *i as usize).into()) };
-                        let opt_sum: Option<T::Native> = take.sum();
-                        opt_sum.map(|sum| sum.to_f64().unwrap() / idx.len() as f64)
+                        take.mean()
                     }
                 }
             }
         })
     }
 
-    fn agg_min(&self, groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+    fn agg_min(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
         agg_helper::<T, _>(groups, |(first, idx)| {
             if idx.is_empty() {
                 None
@@ -152,7 +203,7 @@ where
         })
     }
 
-    fn agg_max(&self, groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+    fn agg_max(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
         agg_helper::<T, _>(groups, |(first, idx)| {
             if idx.is_empty() {
                 None
@@ -186,7 +237,20 @@ where
         })
     }
 
-    fn agg_sum(&self, groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+    fn agg_sum(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
+        // small integers are summed as 64 bit integers to prevent overflow, and f32 is summed
+        // as f64 to prevent the loss of precision
+        match T::get_dtype() {
+            DataType::Int8 | DataType::Int16 => {
+                return self.cast::<Int64Type>().unwrap().agg_sum(groups)
+            }
+            #[cfg(feature = "dtype-u64")]
+            DataType::UInt8 | DataType::UInt16 => {
+                return self.cast::<UInt64Type>().unwrap().agg_sum(groups)
+            }
+            DataType::Float32 => return self.cast::<Float64Type>().unwrap().agg_sum(groups),
+            _ => {}
+        }
         agg_helper::<T, _>(groups, |(first, idx)| {
             if idx.is_empty() {
                 None
@@ -219,34 +283,40 @@ where
             }
         })
     }
-    fn agg_var(&self, groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+    fn agg_var(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
         agg_helper::<Float64Type, _>(groups, |(_first, idx)| {
             if idx.is_empty() {
                 return None;
             }
             let take = unsafe { self.take_unchecked(idx.iter().map(|i| *i as usize).into()) };
+            // a Float32 column has a Float32 variance
             take.into_series()
                 .var_as_series()
-                .unpack::<Float64Type>()
+                .cast::<Float64Type>()
+                .unwrap()
+                .f64()
                 .unwrap()
                 .get(0)
         })
     }
-    fn agg_std(&self, groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+    fn agg_std(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
         agg_helper::<Float64Type, _>(groups, |(_first, idx)| {
             if idx.is_empty() {
                 return None;
             }
             let take = unsafe { self.take_unchecked(idx.iter().map(|i| *i as usize).into()) };
+            // a Float32 column has a Float32 standard deviation
             take.into_series()
                 .std_as_series()
-                .unpack::<Float64Type>()
+                .cast::<Float64Type>()
+                .unwrap()
+                .f64()
                 .unwrap()
                 .get(0)
         })
     }
     #[cfg(feature = "lazy")]
-    fn agg_valid_count(&self, groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+    fn agg_valid_count(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
         agg_helper::<UInt32Type, _>(groups, |(_first, idx)| {
             if idx.is_empty() {
                 None
@@ -261,7 +331,7 @@ where
 }
 
 pub(crate) trait AggFirst {
-    fn agg_first(&self, _groups: &[(u32, Vec<u32>)]) -> Series;
+    fn agg_first(&self, _groups: &[(IdxSize, Vec<IdxSize>)]) -> Series;
 }
 
 macro_rules! impl_agg_first {
@@ -286,31 +356,31 @@ where
     T: PolarsNumericType + Send,
     ChunkedArray<T>: IntoSeries,
 {
-    fn agg_first(&self, groups: &[(u32, Vec<u32>)]) -> Series {
+    fn agg_first(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Series {
         impl_agg_first!(self, groups, ChunkedArray<T>)
     }
 }
 
 impl AggFirst for BooleanChunked {
-    fn agg_first(&self, groups: &[(u32, Vec<u32>)]) -> Series {
+    fn agg_first(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Series {
         impl_agg_first!(self, groups, BooleanChunked)
     }
 }
 
 impl AggFirst for Utf8Chunked {
-    fn agg_first(&self, groups: &[(u32, Vec<u32>)]) -> Series {
+    fn agg_first(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Series {
         impl_agg_first!(self, groups, Utf8Chunked)
     }
 }
 
 impl AggFirst for ListChunked {
-    fn agg_first(&self, groups: &[(u32, Vec<u32>)]) -> Series {
+    fn agg_first(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Series {
         impl_agg_first!(self, groups, ListChunked)
     }
 }
 
 impl AggFirst for CategoricalChunked {
-    fn agg_first(&self, groups: &[(u32, Vec<u32>)]) -> Series {
+    fn agg_first(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Series {
         let out = self
             .cast::<UInt32Type>()
             .unwrap()
@@ -325,7 +395,7 @@ impl AggFirst for CategoricalChunked {
 
 #[cfg(feature = "object")]
 impl<T: PolarsObject> AggFirst for ObjectChunked<T> {
-    fn agg_first(&self, groups: &[(u32, Vec<u32>)]) -> Series {
+    fn agg_first(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Series {
         let ca: Self = groups
             .iter()
             .map(|(first, idx)| {
@@ -341,7 +411,7 @@ impl<T: PolarsObject> AggFirst for ObjectChunked<T> {
 }
 
 pub(crate) trait AggLast {
-    fn agg_last(&self, _groups: &[(u32, Vec<u32>)]) -> Series;
+    fn agg_last(&self, _groups: &[(IdxSize, Vec<IdxSize>)]) -> Series;
 }
 
 macro_rules! impl_agg_last {
@@ -367,25 +437,25 @@ where
     T: PolarsNumericType + Send,
     ChunkedArray<T>: IntoSeries,
 {
-    fn agg_last(&self, groups: &[(u32, Vec<u32>)]) -> Series {
+    fn agg_last(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Series {
         impl_agg_last!(self, groups, ChunkedArray<T>)
     }
 }
 
 impl AggLast for BooleanChunked {
-    fn agg_last(&self, groups: &[(u32, Vec<u32>)]) -> Series {
+    fn agg_last(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Series {
         impl_agg_last!(self, groups, BooleanChunked)
     }
 }
 
 impl AggLast for Utf8Chunked {
-    fn agg_last(&self, groups: &[(u32, Vec<u32>)]) -> Series {
+    fn agg_last(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Series {
         impl_agg_last!(self, groups, Utf8Chunked)
     }
 }
 
 impl AggLast for CategoricalChunked {
-    fn agg_last(&self, groups: &[(u32, Vec<u32>)]) -> Series {
+    fn agg_last(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Series {
         self.cast::<UInt32Type>()
             .unwrap()
             .agg_last(groups)
@@ -395,21 +465,21 @@ impl AggLast for CategoricalChunked {
 }
 
 impl AggLast for ListChunked {
-    fn agg_last(&self, groups: &[(u32, Vec<u32>)]) -> Series {
+    fn agg_last(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Series {
         impl_agg_last!(self, groups, ListChunked)
     }
 }
 
 #[cfg(feature = "object")]
 impl<T: PolarsObject> AggLast for ObjectChunked<T> {
-    fn agg_last(&self, groups: &[(u32, Vec<u32>)]) -> Series {
+    fn agg_last(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Series {
         let ca: Self = groups
             .iter()
             .map(|(_first, idx)| {
                 if idx.is_empty() {
                     return None;
                 }
-                self.get((idx.len() - 1) as usize).cloned()
+                self.get(idx[idx.len() - 1] as usize).cloned()
             })
             .collect();
 
@@ -418,7 +488,7 @@ impl<T: PolarsObject> AggLast for ObjectChunked<T> {
 }
 
 pub(crate) trait AggNUnique {
-    fn agg_n_unique(&self, _groups: &[(u32, Vec<u32>)]) -> Option<UInt32Chunked> {
+    fn agg_n_unique(&self, _groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<UInt32Chunked> {
         None
     }
 }
@@ -458,7 +528,7 @@ where
     T: PolarsIntegerType + Sync,
     T::Native: Hash + Eq,
 {
-    fn agg_n_unique(&self, groups: &[(u32, Vec<u32>)]) -> Option<UInt32Chunked> {
+    fn agg_n_unique(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<UInt32Chunked> {
         Some(impl_agg_n_unique!(self, groups, NoNull<UInt32Chunked>))
     }
 }
@@ -468,7 +538,7 @@ impl AggNUnique for Float32Chunked {}
 impl AggNUnique for Float64Chunked {}
 impl AggNUnique for ListChunked {}
 impl AggNUnique for CategoricalChunked {
-    fn agg_n_unique(&self, groups: &[(u32, Vec<u32>)]) -> Option<UInt32Chunked> {
+    fn agg_n_unique(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<UInt32Chunked> {
         self.cast::<UInt32Type>()
             .unwrap()
             .agg_n_unique(groups)
@@ -483,19 +553,19 @@ impl<T> AggNUnique for ObjectChunked<T> {}
 
 // TODO: could be faster as it can only be null, true, or false
 impl AggNUnique for BooleanChunked {
-    fn agg_n_unique(&self, groups: &[(u32, Vec<u32>)]) -> Option<UInt32Chunked> {
+    fn agg_n_unique(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<UInt32Chunked> {
         Some(impl_agg_n_unique!(self, groups, NoNull<UInt32Chunked>))
     }
 }
 
 impl AggNUnique for Utf8Chunked {
-    fn agg_n_unique(&self, groups: &[(u32, Vec<u32>)]) -> Option<UInt32Chunked> {
+    fn agg_n_unique(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<UInt32Chunked> {
         Some(impl_agg_n_unique!(self, groups, NoNull<UInt32Chunked>))
     }
 }
 
 pub trait AggList {
-    fn agg_list(&self, _groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+    fn agg_list(&self, _groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
         None
     }
 }
@@ -506,7 +576,7 @@ where
     T::Native: Num,
     ChunkedArray<T>: IntoSeries,
 {
-    fn agg_list(&self, groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+    fn agg_list(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
         let arr = match self.cont_slice() {
             Ok(values) => {
                 let mut offsets =
@@ -555,7 +625,7 @@ where
 }
 
 impl AggList for BooleanChunked {
-    fn agg_list(&self, groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+    fn agg_list(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
         let values_cap = self.len();
         let values_builder = BooleanArrayBuilder::new(values_cap);
         let mut builder = ListBooleanChunkedBuilder::new(self.name(), values_builder, groups.len());
@@ -571,7 +641,7 @@ impl AggList for BooleanChunked {
 }
 
 impl AggList for Utf8Chunked {
-    fn agg_list(&self, groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+    fn agg_list(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
         let values_cap = self.len();
         let values_builder = LargeStringBuilder::with_capacity(values_cap * 5, values_cap);
         let mut builder = ListUtf8ChunkedBuilder::new(self.name(), values_builder, groups.len());
@@ -586,49 +656,157 @@ impl AggList for Utf8Chunked {
     }
 }
 
-impl AggList for ListChunked {}
+impl AggList for ListChunked {
+    fn agg_list(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
+        // every row is a list of the values, so the result is a list of lists
+        let values = self.clone().into_series();
+        let mut builder = ListSlicesChunkedBuilder::new(self.name(), &values, groups.len());
+        for (_first, idx) in groups {
+            builder.append_series_slices(idx.iter().map(|i| (*i as usize, 1)));
+        }
+        Some(builder.finish().into_series())
+    }
+}
+
+impl ListChunked {
+    /// Aggregate the lists of every group to a single list, by concatenating them. Null lists
+    /// don't add any values.
+    ///
+    /// The values of the lists are copied in bulk, the inner data type is preserved.
+    pub fn agg_flatten(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
+        let arrays = self.downcast_iter().collect::<Vec<_>>();
+        // the values of all chunks, the lists are slices of these
+        let values = arrays.iter().map(|arr| arr.values()).collect::<Vec<_>>();
+        let mut values_start = Vec::with_capacity(values.len());
+        let mut start = 0;
+        for arr in &values {
+            values_start.push(start);
+            start += arr.len();
+        }
+        let values = Series::try_from((self.name(), values)).ok()?;
+
+        let mut builder = ListSlicesChunkedBuilder::new(self.name(), &values, groups.len());
+        for (_first, idx) in groups {
+            builder.append_series_slices(idx.iter().filter_map(|i| {
+                let (chunk_idx, i) = self.index_to_chunked_index(*i as usize);
+                let arr = arrays[chunk_idx];
+                if arr.is_null(i) {
+                    return None;
+                }
+                let offsets = arr.value_offsets();
+                let offset = values_start[chunk_idx] + offsets[i] as usize;
+                Some((offset, (offsets[i + 1] - offsets[i]) as usize))
+            }));
+        }
+        Some(builder.finish().into_series())
+    }
+}
 #[cfg(feature = "object")]
 impl<T> AggList for ObjectChunked<T> {}
 
 pub(crate) trait AggQuantile {
-    fn agg_quantile(&self, _groups: &[(u32, Vec<u32>)], _quantile: f64) -> Option<Series> {
+    fn agg_quantile(&self, _groups: &[(IdxSize, Vec<IdxSize>)], _quantile: f64) -> Option<Series> {
         None
     }
 
-    fn agg_median(&self, _groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+    fn agg_median(&self, _groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
         None
     }
 }
 
+/// Order NaN values before all other values, like [ChunkSort::sort] does.
+fn order_nan_first<T: PartialOrd>(a: &T, b: &T) -> Ordering {
+    a.partial_cmp(b).unwrap_or_else(|| {
+        // nan != nan
+        #[allow(clippy::eq_op)]
+        match (a != a, b != b) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Less,
+            _ => Ordering::Greater,
+        }
+    })
+}
+
+/// Reorder `vals` so that the `k`-th value in sorted order is at index `k`, and return it.
+///
+/// The values are only partially sorted, which takes fewer comparisons than sorting them.
+pub(crate) fn select_kth<T: PartialOrd + Copy>(vals: &mut [T], k: usize) -> T {
+    *vals.select_nth_unstable_by(k, order_nan_first).1
+}
+
+/// Call `f` with the non-null values of every group, a group without non-null values is null.
+///
+/// The values of a group are gathered in a buffer that is reused for the groups of a thread, so
+/// `f` may reorder them, e.g. to select the k-th value without sorting the group.
+fn agg_select<T, R, F>(
+    ca: &ChunkedArray<T>,
+    groups: &[(IdxSize, Vec<IdxSize>)],
+    f: F,
+) -> Option<Series>
+where
+    T: PolarsNumericType,
+    R: PolarsNumericType,
+    ChunkedArray<R>: IntoSeries,
+    F: Fn(&mut [T::Native]) -> R::Native + Send + Sync,
+{
+    let ca = ca.rechunk();
+    let arr = match ca.downcast_iter().next() {
+        Some(arr) => arr,
+        None => return Some(ChunkedArray::<R>::full_null("", groups.len()).into_series()),
+    };
+    let values = arr.values();
+    let has_nulls = ca.null_count() > 0;
+
+    let out: ChunkedArray<R> = POOL.install(|| {
+        groups
+            .par_iter()
+            .map_init(Vec::new, |buf, (_, idx)| {
+                buf.clear();
+                if has_nulls {
+                    buf.extend(
+                        idx.iter()
+                            .filter(|&&i| arr.is_valid(i as usize))
+                            .map(|&i| values[i as usize]),
+                    );
+                } else {
+                    buf.extend(idx.iter().map(|&i| values[i as usize]));
+                }
+                if buf.is_empty() {
+                    None
+                } else {
+                    Some(f(buf))
+                }
+            })
+            .collect()
+    });
+    Some(out.into_series())
+}
+
 impl<T> AggQuantile for ChunkedArray<T>
 where
     T: PolarsNumericType + Sync,
     T::Native: PartialOrd + Num + NumCast + Zero,
     ChunkedArray<T>: IntoSeries,
 {
-    fn agg_quantile(&self, groups: &[(u32, Vec<u32>)], quantile: f64) -> Option<Series> {
-        agg_helper::<T, _>(groups, |(_first, idx)| {
-            if idx.is_empty() {
-                return None;
-            }
-
-            let group_vals = unsafe { self.take_unchecked(idx.iter().map(|i| *i as usize).into()) };
-            let sorted_idx_ca = group_vals.argsort(false);
-            let sorted_idx = sorted_idx_ca.downcast_iter().next().unwrap().values();
-            let quant_idx = (quantile * (sorted_idx.len() - 1) as f64) as usize;
-            let value_idx = sorted_idx[quant_idx];
-            group_vals.get(value_idx as usize)
+    fn agg_quantile(&self, groups: &[(IdxSize, Vec<IdxSize>)], quantile: f64) -> Option<Series> {
+        agg_select::<T, T, _>(self, groups, |vals| {
+            let k = (quantile * (vals.len() - 1) as f64) as usize;
+            select_kth(vals, k)
         })
     }
 
-    fn agg_median(&self, groups: &[(u32, Vec<u32>)]) -> Option<Series> {
-        agg_helper::<Float64Type, _>(groups, |(_first, idx)| {
-            if idx.is_empty() {
-                return None;
+    fn agg_median(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
+        agg_select::<T, Float64Type, _>(self, groups, |vals| {
+            let len = vals.len();
+            let (lower, mid, _) = vals.select_nth_unstable_by(len / 2, order_nan_first);
+            let high = mid.to_f64().unwrap();
+            if len % 2 == 0 {
+                // the largest value of the lower half; add as f64, as the sum may overflow
+                let low = lower.iter().copied().max_by(order_nan_first).unwrap();
+                (low.to_f64().unwrap() + high) / 2.0
+            } else {
+                high
             }
-
-            let group_vals = unsafe { self.take_unchecked(idx.iter().map(|i| *i as usize).into()) };
-            group_vals.median()
         })
     }
 }
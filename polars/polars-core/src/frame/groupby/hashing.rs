@@ -18,7 +18,8 @@ pub(crate) fn groupby<T>(a: impl Iterator<Item = T>) -> GroupTuples
 where
     T: Hash + Eq,
 {
-    let mut hash_tbl: PlHashMap<T, (u32, Vec<u32>)> = PlHashMap::with_capacity(HASHMAP_INIT_SIZE);
+    let mut hash_tbl: PlHashMap<T, (IdxSize, Vec<IdxSize>)> =
+        PlHashMap::with_capacity(HASHMAP_INIT_SIZE);
     let mut cnt = 0;
     a.for_each(|k| {
         let idx = cnt;
@@ -44,10 +45,13 @@ where
 ///
 /// Besides numeric values, we can also use this for pre hashed strings. The keys are simply a ptr to the str + precomputed hash.
 /// The hash will be used to rehash, and the str will be used for equality.
+///
+/// If `valid` is given, the rows where it is `false` are not part of any group.
 pub(crate) fn groupby_threaded_num<T, IntoSlice>(
     keys: Vec<IntoSlice>,
     group_size_hint: usize,
     n_partitions: u64,
+    valid: Option<&[bool]>,
 ) -> GroupTuples
 where
     T: Send + Hash + Eq + Sync + Copy + AsU64,
@@ -62,20 +66,22 @@ where
         (0..n_partitions).into_par_iter().map(|thread_no| {
             let thread_no = thread_no as u64;
 
-            let mut hash_tbl: PlHashMap<T, (u32, Vec<u32>)> =
+            let mut hash_tbl: PlHashMap<T, (IdxSize, Vec<IdxSize>)> =
                 PlHashMap::with_capacity(HASHMAP_INIT_SIZE);
 
             let mut offset = 0;
             for keys in &keys {
                 let keys = keys.as_ref();
-                let len = keys.len() as u32;
+                let len = keys.len() as IdxSize;
 
                 let mut cnt = 0;
                 keys.iter().for_each(|k| {
                     let idx = cnt + offset;
                     cnt += 1;
 
-                    if this_partition(k.as_u64(), thread_no, n_partitions) {
+                    if this_partition(k.as_u64(), thread_no, n_partitions)
+                        && valid.map_or(true, |valid| valid[idx as usize])
+                    {
                         let entry = hash_tbl.entry(*k);
 
                         match entry {
@@ -126,7 +132,7 @@ pub(crate) unsafe fn compare_df_rows(keys: &DataFrame, idx_a: usize, idx_b: usiz
 pub(crate) fn populate_multiple_key_hashmap<V, H, F, G>(
     hash_tbl: &mut HashMap<IdxHash, V, H>,
     // row index
-    idx: u32,
+    idx: IdxSize,
     // hash
     original_h: u64,
     // keys of the hash table (will not be inserted, the indexes will be used)
@@ -184,7 +190,7 @@ pub(crate) unsafe fn compare_keys<'a>(
 pub(crate) fn populate_multiple_key_hashmap2<'a, V, H, F, G>(
     hash_tbl: &mut HashMap<IdxHash, V, H>,
     // row index
-    idx: u32,
+    idx: IdxSize,
     // hash
     original_h: u64,
     // keys of the hash table (will not be inserted, the indexes will be used)
@@ -223,9 +229,24 @@ pub(crate) fn populate_multiple_key_hashmap2<'a, V, H, F, G>(
     }
 }
 
+/// Combined validity of the keys. `None` if none of the keys has null values.
+pub(crate) fn keys_valid_mask(keys: &DataFrame) -> Option<Vec<bool>> {
+    let mut mask: Option<BooleanChunked> = None;
+    for s in keys.get_columns().iter().filter(|s| s.null_count() > 0) {
+        let valid = s.is_not_null();
+        mask = Some(match mask {
+            Some(mask) => mask & valid,
+            None => valid,
+        });
+    }
+    mask.map(|mask| mask.into_no_null_iter().collect())
+}
+
+/// If `valid` is given, the rows where it is `false` are not part of any group.
 pub(crate) fn groupby_threaded_multiple_keys_flat(
     keys: DataFrame,
     n_partitions: usize,
+    valid: Option<&[bool]>,
 ) -> GroupTuples {
     let dfs = split_df(&keys, n_partitions).unwrap();
     let (hashes, _random_state) = df_rows_to_hashes_threaded(&dfs, None);
@@ -245,19 +266,21 @@ pub(crate) fn groupby_threaded_multiple_keys_flat(
             let hashes = &hashes;
             let thread_no = thread_no as u64;
 
-            let mut hash_tbl: HashMap<IdxHash, (u32, Vec<u32>), IdBuildHasher> =
+            let mut hash_tbl: HashMap<IdxHash, (IdxSize, Vec<IdxSize>), IdBuildHasher> =
                 HashMap::with_capacity_and_hasher(HASHMAP_INIT_SIZE, Default::default());
 
             let mut offset = 0;
             for hashes in hashes {
-                let len = hashes.len() as u32;
+                let len = hashes.len() as IdxSize;
 
                 let mut idx = 0;
                 for hashes_chunk in hashes.data_views() {
                     for &h in hashes_chunk {
                         // partition hashes by thread no.
                         // So only a part of the hashes go to this hashmap
-                        if this_partition(h, thread_no, n_partitions) {
+                        if this_partition(h, thread_no, n_partitions)
+                            && valid.map_or(true, |valid| valid[(idx + offset) as usize])
+                        {
                             let idx = idx + offset;
                             populate_multiple_key_hashmap2(
                                 &mut hash_tbl,
@@ -1,11 +1,15 @@
 use self::hashing::*;
 use crate::chunked_array::builder::PrimitiveChunkedBuilder;
+use crate::chunked_array::ops::bit_repr::float_keys_to_bits;
 use crate::frame::select::Selection;
 use crate::prelude::*;
-use crate::utils::{accumulate_dataframes_vertical, set_partition_size, split_ca, NoNull};
+use crate::utils::{
+    accumulate_dataframes_vertical, ensure_unique_names, set_partition_size, split_ca, NoNull,
+};
 use crate::vector_hasher::{AsU64, StrHash};
 use crate::POOL;
 use ahash::RandomState;
+use arrow::array::PrimitiveBuilder;
 use hashbrown::HashMap;
 use num::NumCast;
 use rayon::prelude::*;
@@ -18,9 +22,12 @@ pub(crate) mod hashing;
 pub(crate) mod pivot;
 #[cfg(feature = "downsample")]
 pub mod resample;
+pub mod typed;
 
-pub type GroupTuples = Vec<(u32, Vec<u32>)>;
-pub type GroupedMap<T> = HashMap<T, Vec<u32>, RandomState>;
+pub use typed::GroupValues;
+
+pub type GroupTuples = Vec<(IdxSize, Vec<IdxSize>)>;
+pub type GroupedMap<T> = HashMap<T, Vec<IdxSize>, RandomState>;
 
 /// Used to create the tuples for a groupby operation.
 pub trait IntoGroupTuples {
@@ -55,21 +62,21 @@ where
         if ca.chunks.len() == 1 {
             if ca.null_count() == 0 {
                 let keys = vec![ca.cont_slice().unwrap()];
-                groupby_threaded_num(keys, group_size_hint, n_partitions)
+                groupby_threaded_num(keys, group_size_hint, n_partitions, None)
             } else {
                 let keys = ca
                     .downcast_iter()
                     .map(|arr| arr.into_iter().collect::<Vec<_>>())
                     .collect::<Vec<_>>();
-                groupby_threaded_num(keys, group_size_hint, n_partitions)
+                groupby_threaded_num(keys, group_size_hint, n_partitions, None)
             }
             // use the polars-iterators
         } else if ca.null_count() == 0 {
             let keys = vec![ca.into_no_null_iter().collect::<Vec<_>>()];
-            groupby_threaded_num(keys, group_size_hint, n_partitions)
+            groupby_threaded_num(keys, group_size_hint, n_partitions, None)
         } else {
             let keys = vec![ca.into_iter().collect::<Vec<_>>()];
-            groupby_threaded_num(keys, group_size_hint, n_partitions)
+            groupby_threaded_num(keys, group_size_hint, n_partitions, None)
         }
     } else if ca.null_count() == 0 {
         groupby(ca.into_no_null_iter())
@@ -99,11 +106,25 @@ where
                 };
                 num_group_tuples(ca, multithreaded)
             }
-            DataType::Int64 | DataType::Float64 => {
+            DataType::Float64 => {
+                // convince the compiler that we are this type.
+                let ca: &Float64Chunked = unsafe {
+                    &*(self as *const ChunkedArray<T> as *const ChunkedArray<Float64Type>)
+                };
+                num_group_tuples(&ca.key_bit_repr(), multithreaded)
+            }
+            DataType::Float32 => {
+                // convince the compiler that we are this type.
+                let ca: &Float32Chunked = unsafe {
+                    &*(self as *const ChunkedArray<T> as *const ChunkedArray<Float32Type>)
+                };
+                num_group_tuples(&ca.key_bit_repr(), multithreaded)
+            }
+            DataType::Int64 => {
                 let ca = self.bit_repr_large();
                 num_group_tuples(&ca, multithreaded)
             }
-            DataType::Int32 | DataType::Float32 => {
+            DataType::Int32 => {
                 let ca = self.bit_repr_small();
                 num_group_tuples(&ca, multithreaded)
             }
@@ -145,7 +166,7 @@ impl IntoGroupTuples for Utf8Chunked {
                     })
                     .collect::<Vec<_>>()
             });
-            groupby_threaded_num(str_hashes, 0, n_partitions as u64)
+            groupby_threaded_num(str_hashes, 0, n_partitions as u64, None)
         } else {
             let str_hashes = self
                 .into_iter()
@@ -169,7 +190,18 @@ impl IntoGroupTuples for CategoricalChunked {
     }
 }
 
-impl IntoGroupTuples for ListChunked {}
+impl IntoGroupTuples for ListChunked {
+    fn group_tuples(&self, multithreaded: bool) -> GroupTuples {
+        // the lists are hashed and compared like the rows of a single key column
+        let n_partitions = if multithreaded {
+            set_partition_size()
+        } else {
+            1
+        };
+        let keys = DataFrame::new_no_checks(vec![self.clone().into_series()]);
+        groupby_threaded_multiple_keys_flat(keys, n_partitions, None)
+    }
+}
 #[cfg(feature = "object")]
 impl<T> IntoGroupTuples for ObjectChunked<T> {}
 
@@ -275,8 +307,47 @@ fn pack_u32_u64_tuples(opt_l: Option<u32>, opt_r: Option<u64>) -> [u8; 13] {
     val
 }
 
+/// Options of a groupby operation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GroupbyOptions {
+    /// Exclude the rows of which any of the keys is null, instead of grouping them in null groups.
+    pub drop_null_keys: bool,
+    /// Determine the groups on multiple threads.
+    pub multithreaded: bool,
+}
+
+impl Default for GroupbyOptions {
+    fn default() -> Self {
+        GroupbyOptions {
+            drop_null_keys: false,
+            multithreaded: true,
+        }
+    }
+}
+
 impl DataFrame {
     pub fn groupby_with_series(&self, by: Vec<Series>, multithreaded: bool) -> Result<GroupBy> {
+        self.groupby_with_series_and_options(
+            by,
+            GroupbyOptions {
+                multithreaded,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Group DataFrame using the Series as keys, see [GroupbyOptions] for the options.
+    pub fn groupby_with_series_and_options(
+        &self,
+        by: Vec<Series>,
+        options: GroupbyOptions,
+    ) -> Result<GroupBy> {
+        let groups = self.group_tuples_of_keys(&by, options)?;
+        Ok(GroupBy::new(self, by, groups, None))
+    }
+
+    fn group_tuples_of_keys(&self, by: &[Series], options: GroupbyOptions) -> Result<GroupTuples> {
+        let multithreaded = options.multithreaded;
         macro_rules! finish_packed_bit_path {
             ($ca0:expr, $ca1:expr, $pack_fn:expr) => {{
                 let n_partitions = set_partition_size();
@@ -300,11 +371,17 @@ impl DataFrame {
                         .collect::<Vec<_>>()
                 });
 
-                return Ok(GroupBy::new(
-                    self,
-                    by,
-                    groupby_threaded_num(keys, 0, n_partitions as u64),
-                    None,
+                // the rows with a null key are skipped while the hash table is populated
+                let valid = if options.drop_null_keys {
+                    keys_valid_mask(&keys_df)
+                } else {
+                    None
+                };
+                return Ok(groupby_threaded_num(
+                    keys,
+                    0,
+                    n_partitions as u64,
+                    valid.as_deref(),
                 ));
             }};
         }
@@ -315,15 +392,13 @@ impl DataFrame {
             ));
         };
 
-        // make sure that categorical is used as uint32 in value type
+        // make sure that categorical is used as uint32 in value type and that float keys are
+        // compared by their key bits
         let keys_df = DataFrame::new(
             by.iter()
                 .map(|s| match s.dtype() {
                     DataType::Categorical => s.cast::<UInt32Type>().unwrap(),
-                    DataType::Float32 => s.bit_repr_small().into_series(),
-                    // otherwise we use the vec hash for float
-                    #[cfg(feature = "dtype-u64")]
-                    DataType::Float64 => s.bit_repr_large().into_series(),
+                    DataType::Float32 | DataType::Float64 => float_keys_to_bits(s),
                     _ => {
                         // is date like
                         if !s.is_numeric() && s.is_numeric_physical() {
@@ -339,7 +414,19 @@ impl DataFrame {
         let groups = match by.len() {
             1 => {
                 let series = &by[0];
-                series.group_tuples(multithreaded)
+                if options.drop_null_keys && series.null_count() > 0 {
+                    // the typed paths group the null rows, so the rows are hashed as in the
+                    // multiple key path, which skips the rows with a null key
+                    let valid = keys_valid_mask(&keys_df);
+                    let n_partitions = if multithreaded {
+                        set_partition_size()
+                    } else {
+                        1
+                    };
+                    groupby_threaded_multiple_keys_flat(keys_df, n_partitions, valid.as_deref())
+                } else {
+                    series.group_tuples(multithreaded)
+                }
             }
             _ => {
                 // multiple keys is always multi-threaded
@@ -376,11 +463,17 @@ impl DataFrame {
                     }
                 }
 
+                // the rows with a null key are skipped while the hash table is populated
+                let valid = if options.drop_null_keys {
+                    keys_valid_mask(&keys_df)
+                } else {
+                    None
+                };
                 let n_partitions = set_partition_size();
-                groupby_threaded_multiple_keys_flat(keys_df, n_partitions)
+                groupby_threaded_multiple_keys_flat(keys_df, n_partitions, valid.as_deref())
             }
         };
-        Ok(GroupBy::new(self, by, groups, None))
+        Ok(groups)
     }
 
     /// Group DataFrame using a Series column.
@@ -400,6 +493,32 @@ impl DataFrame {
         self.groupby_with_series(selected_keys, true)
     }
 
+    /// Group DataFrame using a Series column, see [GroupbyOptions] for the options.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use polars_core::prelude::*;
+    /// use polars_core::frame::groupby::GroupbyOptions;
+    /// fn groupby_sum_non_null(df: &DataFrame) -> Result<DataFrame> {
+    ///     let options = GroupbyOptions {
+    ///         drop_null_keys: true,
+    ///         ..Default::default()
+    ///     };
+    ///     df.groupby_with_options("column_name", options)?
+    ///     .select("agg_column_name")
+    ///     .sum()
+    /// }
+    /// ```
+    pub fn groupby_with_options<'g, J, S: Selection<'g, J>>(
+        &self,
+        by: S,
+        options: GroupbyOptions,
+    ) -> Result<GroupBy> {
+        let selected_keys = self.select_series(by)?;
+        self.groupby_with_series_and_options(selected_keys, options)
+    }
+
     /// Group DataFrame using a Series column.
     /// The groups are ordered by their smallest row index.
     pub fn groupby_stable<'g, J, S: Selection<'g, J>>(&self, by: S) -> Result<GroupBy> {
@@ -584,6 +703,119 @@ impl<'df, 'selection_str> GroupBy<'df, 'selection_str> {
         DataFrame::new(cols)
     }
 
+    /// Aggregate grouped series with a custom fold. Per group, `f` is called with the
+    /// accumulator, that starts at `init`, and every non-null value. The aggregated columns are
+    /// cast to `T` and named `{name}_fold`. A group without non-null values results in a null.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use polars_core::prelude::*;
+    /// fn example(df: DataFrame) -> Result<DataFrame> {
+    ///     // the product of the temperatures per date
+    ///     df.groupby("date")?
+    ///         .select("temp")
+    ///         .agg_fold::<Int32Type, _>(1, |acc, v| acc * v)
+    /// }
+    /// ```
+    pub fn agg_fold<T, F>(&self, init: T::Native, f: F) -> Result<DataFrame>
+    where
+        T: PolarsNumericType,
+        ChunkedArray<T>: IntoSeries,
+        F: Fn(T::Native, T::Native) -> T::Native + Send + Sync,
+    {
+        let (mut cols, agg_cols) = self.prepare_agg()?;
+
+        for agg_col in agg_cols {
+            let new_name = format!("{}_fold", agg_col.name());
+            let s = agg_col.cast::<T>()?;
+            let mut agg = s.unpack::<T>()?.agg_fold(&self.groups, init, &f);
+            agg.rename(&new_name);
+            cols.push(agg.into_series());
+        }
+        DataFrame::new(cols)
+    }
+
+    /// Get typed access to the values of `column` per group. The column is cast to `T`.
+    ///
+    /// The groups are in the same order as [keys](Self::keys).
+    pub fn group_values<T>(&self, column: &str) -> Result<GroupValues<'_, T>>
+    where
+        T: PolarsNumericType,
+    {
+        let s = self.df.column(column)?.cast::<T>()?;
+        Ok(GroupValues::new(s.unpack::<T>()?, &self.groups))
+    }
+
+    /// Aggregate `column` with a custom accumulator. Per group, the accumulator starts at
+    /// `init` and `f` is called with every non-null value of the group. The column is cast to
+    /// `T` and the groups are aggregated in parallel.
+    ///
+    /// Returns the accumulator of every group, in the same order as [keys](Self::keys). Use
+    /// [agg_to_series](Self::agg_to_series) to turn the results into a `Series`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use polars_core::prelude::*;
+    /// fn example(df: DataFrame) -> Result<DataFrame> {
+    ///     // the product of the temperatures per date
+    ///     let gb = df.groupby("date")?;
+    ///     let products = gb.agg_primitive::<Int32Type, _, _>("temp", 1i64, |acc, v| {
+    ///         *acc *= v as i64
+    ///     })?;
+    ///     let products = products.into_iter().map(Some);
+    ///     let agg = gb.agg_to_series::<Int64Type, _>("temp_product", products)?;
+    ///     let mut cols = gb.keys();
+    ///     cols.push(agg);
+    ///     DataFrame::new(cols)
+    /// }
+    /// ```
+    pub fn agg_primitive<T, Acc, F>(&self, column: &str, init: Acc, f: F) -> Result<Vec<Acc>>
+    where
+        T: PolarsNumericType,
+        Acc: Clone + Send + Sync,
+        F: Fn(&mut Acc, T::Native) + Send + Sync,
+    {
+        let values = self.group_values::<T>(column)?;
+        Ok(POOL.install(|| {
+            (0..values.len())
+                .into_par_iter()
+                .map(|group| {
+                    let mut acc = init.clone();
+                    match values.no_null_values(group) {
+                        Some(iter) => iter.for_each(|v| f(&mut acc, v)),
+                        None => values.values(group).flatten().for_each(|v| f(&mut acc, v)),
+                    }
+                    acc
+                })
+                .collect()
+        }))
+    }
+
+    /// Collect one result per group into a `Series` named `name`, aligned with
+    /// [keys](Self::keys). Returns an error if the number of results isn't the number of groups.
+    pub fn agg_to_series<T, I>(&self, name: &str, results: I) -> Result<Series>
+    where
+        T: PolarsNumericType,
+        ChunkedArray<T>: IntoSeries,
+        I: IntoIterator<Item = Option<T::Native>>,
+    {
+        let mut ca: ChunkedArray<T> = results.into_iter().collect();
+        if ca.len() != self.groups.len() {
+            return Err(PolarsError::ShapeMisMatch(
+                format!(
+                    "expected one aggregated value per group ({}), got {}",
+                    self.groups.len(),
+                    ca.len()
+                )
+                .into(),
+            ));
+        }
+        ca.rename(name);
+        Ok(ca.into_series())
+    }
+
     /// Aggregate grouped series and compute the sum per group.
     ///
     /// # Example
@@ -928,6 +1160,27 @@ impl<'df, 'selection_str> GroupBy<'df, 'selection_str> {
         DataFrame::new(cols)
     }
 
+    /// Aggregate grouped series and compute the number of null values per group.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use polars_core::prelude::*;
+    /// fn example(df: DataFrame) -> Result<DataFrame> {
+    ///     df.groupby("date")?.select("temp").null_count()
+    /// }
+    /// ```
+    pub fn null_count(&self) -> Result<DataFrame> {
+        let (mut cols, agg_cols) = self.prepare_agg()?;
+        for agg_col in agg_cols {
+            let new_name = fmt_groupby_column(agg_col.name(), GroupByMethod::NullCount);
+            let mut ca = agg_null_count(&agg_col, &self.groups);
+            ca.rename(&new_name);
+            cols.push(ca.into_series())
+        }
+        DataFrame::new(cols)
+    }
+
     /// Get the groupby group indexes.
     ///
     /// # Example
@@ -955,17 +1208,8 @@ impl<'df, 'selection_str> GroupBy<'df, 'selection_str> {
     /// ```
     pub fn groups(&self) -> Result<DataFrame> {
         let mut cols = self.keys();
-
-        let mut column: ListChunked = self
-            .groups
-            .iter()
-            .map(|(_first, idx)| {
-                let ca: NoNull<UInt32Chunked> = idx.iter().map(|&v| v as u32).collect();
-                ca.into_inner().into_series()
-            })
-            .collect();
         let new_name = fmt_groupby_column("", GroupByMethod::Groups);
-        column.rename(&new_name);
+        let column = groups_to_list(&new_name, &self.groups);
         cols.push(column.into_series());
         DataFrame::new(cols)
     }
@@ -1068,6 +1312,11 @@ impl<'df, 'selection_str> GroupBy<'df, 'selection_str> {
                             let ca = builder.finish();
                             cols.push(ca.into_series());
                         }
+                        "null_count" => {
+                            let mut ca = agg_null_count(agg_col, &self.groups);
+                            ca.rename(&format!("{}_null_count", agg_col.name()));
+                            cols.push(ca.into_series());
+                        }
                         a => panic!("aggregation: {:?} is not supported", a),
                     }
                 }
@@ -1114,6 +1363,30 @@ impl<'df, 'selection_str> GroupBy<'df, 'selection_str> {
         DataFrame::new(cols)
     }
 
+    /// Aggregate the List columns of the groups into a single list per group, by concatenating
+    /// the lists of the group. Null lists are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use polars_core::prelude::*;
+    /// fn example(df: DataFrame) -> Result<DataFrame> {
+    ///     // all the tags of a user in a single list
+    ///     df.groupby("user")?.select("tags").flatten()
+    /// }
+    /// ```
+    pub fn flatten(&self) -> Result<DataFrame> {
+        let (mut cols, agg_cols) = self.prepare_agg()?;
+        for agg_col in agg_cols {
+            let new_name = fmt_groupby_column(agg_col.name(), GroupByMethod::Flatten);
+            if let Some(mut agg) = agg_col.list()?.agg_flatten(&self.groups) {
+                agg.rename(&new_name);
+                cols.push(agg);
+            }
+        }
+        DataFrame::new(cols)
+    }
+
     /// Apply a closure over the groups as a new DataFrame.
     pub fn apply<F>(&self, f: F) -> Result<DataFrame>
     where
@@ -1127,6 +1400,7 @@ impl<'df, 'selection_str> GroupBy<'df, 'selection_str> {
                 new_cols.extend_from_slice(&self.selected_keys);
                 let cols = self.df.select_series(agg)?;
                 new_cols.extend(cols.into_iter());
+                ensure_unique_names(&new_cols, "groupby apply")?;
                 DataFrame::new_no_checks(new_cols)
             }
         } else {
@@ -1161,7 +1435,9 @@ pub enum GroupByMethod {
     NUnique,
     Quantile(f64),
     Count,
+    NullCount,
     List,
+    Flatten,
     Std,
     Var,
 }
@@ -1180,20 +1456,52 @@ pub fn fmt_groupby_column(name: &str, method: GroupByMethod) -> String {
         Groups => "groups".to_string(),
         NUnique => format!("{}_n_unique", name),
         Count => format!("{}_count", name),
+        NullCount => format!("{}_null_count", name),
         List => format!("{}_agg_list", name),
+        Flatten => format!("{}_flatten", name),
         Quantile(quantile) => format!("{}_quantile_{:.2}", name, quantile),
         Std => format!("{}_agg_std", name),
         Var => format!("{}_agg_var", name),
     }
 }
 
+/// Count the null values of every group of `s`. The validity bitmap is only read if `s` has null
+/// values; the null count of `s` itself is known without iterating.
+/// Used in eager and lazy code for the `null_count` aggregation.
+pub fn agg_null_count(s: &Series, groups: &[(IdxSize, Vec<IdxSize>)]) -> UInt32Chunked {
+    let mut ca: NoNull<UInt32Chunked> = if s.null_count() == 0 {
+        groups.iter().map(|_| 0u32).collect()
+    } else {
+        let s = s.rechunk();
+        let arr = &s.chunks()[0];
+        groups
+            .iter()
+            .map(|(_first, idx)| idx.iter().filter(|i| arr.is_null(**i as usize)).count() as u32)
+            .collect()
+    };
+    ca.rename(s.name());
+    ca.into_inner()
+}
+
+/// Collect the row indexes of every group in a list column of [IdxSize].
+/// Used in eager and lazy code to get the groups of a groupby operation.
+pub fn groups_to_list(name: &str, groups: &[(IdxSize, Vec<IdxSize>)]) -> ListChunked {
+    let n_values = groups.iter().map(|(_first, idx)| idx.len()).sum();
+    let values_builder = PrimitiveBuilder::<IdxType>::new(n_values);
+    let mut builder = ListPrimitiveChunkedBuilder::new(name, values_builder, groups.len());
+    for (_first, idx) in groups {
+        builder.append_slice(Some(idx));
+    }
+    builder.finish()
+}
+
 #[cfg(test)]
 mod test {
     use itertools::Itertools;
 
     use crate::frame::groupby::{groupby, groupby_threaded_num};
     use crate::prelude::*;
-    use crate::utils::split_ca;
+    use crate::utils::{split_ca, NoNull};
     use num::traits::FloatConst;
 
     #[test]
@@ -1462,7 +1770,7 @@ mod test {
             let a = groupby(ca.into_iter()).into_iter().sorted().collect_vec();
 
             let keys = splitted.iter().map(|ca| ca.cont_slice().unwrap()).collect();
-            let b = groupby_threaded_num(keys, 0, splitted.len() as u64)
+            let b = groupby_threaded_num(keys, 0, splitted.len() as u64, None)
                 .into_iter()
                 .sorted()
                 .collect_vec();
@@ -1514,6 +1822,73 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_groupby_mean_precision() -> Result<()> {
+        let n = 100_000;
+        let g: NoNull<UInt32Chunked> = (0..n as u32).map(|i| i % 2).collect();
+        let int: NoNull<Int32Chunked> = (0..n).map(|_| i32::MAX).collect();
+        let flt: NoNull<Float32Chunked> = (0..n).map(|_| 0.1f32).collect();
+        let mut flt32_var: Float32Chunked = (0..n)
+            .map(|i| {
+                if i % 4 < 2 {
+                    Some(1000.1f32)
+                } else {
+                    Some(1000.2f32)
+                }
+            })
+            .collect();
+        flt32_var.rename("var");
+        let mut g = g.into_inner();
+        g.rename("g");
+        let mut int = int.into_inner();
+        int.rename("int");
+        let mut flt = flt.into_inner();
+        flt.rename("flt");
+        // a second chunk takes the multiple chunks path
+        let df = DataFrame::new(vec![
+            g.into_series(),
+            int.into_series(),
+            flt.into_series(),
+            flt32_var.into_series(),
+        ])?;
+        let df = df.vstack(&df.slice(0, 10))?;
+
+        for df in &[df.clone(), df.agg_chunks()] {
+            let out = df
+                .groupby("g")?
+                .select(&["int", "flt"])
+                .mean()?
+                .sort("g", false)?;
+            for v in out.column("int_mean")?.f64()? {
+                assert_eq!(v, Some(i32::MAX as f64));
+            }
+            for v in out.column("flt_mean")?.f64()? {
+                assert!((v.unwrap() - 0.1f32 as f64).abs() < 1e-12);
+            }
+
+            // the variance of a Float32 column is computed in f64
+            let out = df.groupby("g")?.select("var").var()?;
+            let diff = 1000.2f32 as f64 - 1000.1f32 as f64;
+            for v in out.column("var_agg_var")?.f64()? {
+                let v = v.unwrap();
+                assert!((v - diff * diff / 4.0).abs() < 1e-4 * v);
+            }
+        }
+
+        // a group of which the values sum to zero
+        let df = df![
+            "g" => [1, 1, 1, 2],
+            "v" => [Some(-1), None, Some(1), Some(2)]
+        ]?;
+        let out = df.groupby("g")?.select("v").mean()?.sort("g", false)?;
+        assert_eq!(
+            Vec::from(out.column("v_mean")?.f64()?),
+            &[Some(0.0), Some(2.0)]
+        );
+        Ok(())
+    }
+
     #[test]
     #[cfg_attr(miri, ignore)]
     fn test_groupby_null_group() -> Result<()> {
@@ -1530,4 +1905,600 @@ mod test {
         dbg!(out);
         Ok(())
     }
+
+    #[test]
+    fn test_groupby_groups() -> Result<()> {
+        let df = df![
+            "a" => [Some("foo"), None, Some("foo"), None, Some("bar")],
+            "b" => [1, 1, 1, 1, 2],
+            "c" => [1, 2, 3, 4, 5]
+        ]?;
+
+        let out = df.groupby_stable(&["a", "b"])?.groups()?;
+        assert_eq!(out.get_column_names(), &["a", "b", "groups"]);
+        assert_eq!(
+            out.column("groups")?.dtype(),
+            &DataType::List(IDX_DTYPE.to_arrow())
+        );
+        assert_eq!(
+            Vec::from(out.column("a")?.utf8()?),
+            &[Some("foo"), None, Some("bar")]
+        );
+        let groups = out
+            .column("groups")?
+            .list()?
+            .into_iter()
+            .map(|opt_s| Vec::from(opt_s.unwrap().idx().unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            groups,
+            &[
+                vec![Some(0), Some(2)],
+                vec![Some(1), Some(3)],
+                vec![Some(4)]
+            ]
+        );
+
+        // the exploded indexes take the data in group order
+        let idx = out.explode("groups")?;
+        let idx = idx.column("groups")?.idx()?;
+        let taken = df.take(idx);
+        assert_eq!(
+            Vec::from(taken.column("c")?.i32()?),
+            &[Some(1), Some(3), Some(2), Some(4), Some(5)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_groupby_null_count() -> Result<()> {
+        let mut df = df![
+            "a" => ["foo", "bar", "foo", "bar", "ham"],
+            "b" => [Some(1), None, None, None, Some(2)],
+            "c" => [1, 2, 3, 4, 5]
+        ]?;
+        // the validity of the second chunk is offset
+        df.vstack_mut(&df.slice(1, 2))?;
+
+        let out = df.groupby_stable("a")?.select(&["b", "c"]).null_count()?;
+        assert_eq!(
+            out.get_column_names(),
+            &["a", "b_null_count", "c_null_count"]
+        );
+        assert_eq!(
+            Vec::from(out.column("b_null_count")?.u32()?),
+            &[Some(2), Some(3), Some(0)]
+        );
+        assert_eq!(
+            Vec::from(out.column("c_null_count")?.u32()?),
+            &[Some(0), Some(0), Some(0)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_groupby_agg_primitive() -> Result<()> {
+        let df = df![
+            "key" => ["a", "b", "a", "c", "b", "a", "c"],
+            "val" => [Some(2i32), Some(3), None, None, Some(-4), Some(5), None]
+        ]?;
+        let gb = df.groupby_stable("key")?;
+
+        // a custom product aggregation
+        let products =
+            gb.agg_primitive::<Int32Type, _, _>("val", None, |acc: &mut Option<i64>, v| {
+                *acc = Some(acc.unwrap_or(1) * v as i64)
+            })?;
+        let out = gb.agg_to_series::<Int64Type, _>("val_product", products)?;
+
+        // reference computation on the taken groups
+        let val = df.column("val")?.i32()?;
+        let expected = gb
+            .get_groups()
+            .iter()
+            .map(|(_, idx)| {
+                let group = val.take(idx.iter().map(|i| *i as usize).into());
+                (&group)
+                    .into_iter()
+                    .flatten()
+                    .fold(None, |acc: Option<i64>, v| {
+                        Some(acc.unwrap_or(1) * v as i64)
+                    })
+            })
+            .collect::<Int64Chunked>();
+        assert_eq!(Vec::from(out.i64()?), Vec::from(&expected));
+        assert_eq!(Vec::from(out.i64()?), &[Some(10), Some(-12), None]);
+        assert_eq!(out.name(), "val_product");
+        assert!(gb.keys()[0].series_equal(&Series::new("key", &["a", "b", "c"])));
+
+        let values = gb.group_values::<Int32Type>("val")?;
+        assert_eq!(values.len(), 3);
+        assert!(values.no_null_values(0).is_none());
+        assert_eq!(values.indices(0), &[0, 2, 5]);
+        assert_eq!(
+            values.values(0).collect::<Vec<_>>(),
+            &[Some(2), None, Some(5)]
+        );
+
+        // the column is cast to the requested type
+        let sums = gb.agg_primitive::<Float64Type, _, _>("val", 0.0, |acc, v| *acc += v)?;
+        assert_eq!(sums, &[7.0, -1.0, 0.0]);
+
+        assert!(gb
+            .agg_to_series::<Int64Type, _>("wrong", vec![Some(1)])
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_groupby_median_quantile() {
+        // xorshift
+        let mut state = 3u64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        // small groups with ties, an empty group and a group of only null values
+        let with_nulls: Float64Chunked = (0..500)
+            .map(|i| {
+                if i % 100 == 7 || next() % 5 == 0 {
+                    None
+                } else {
+                    Some((next() % 10) as f64)
+                }
+            })
+            .collect();
+        let with_nan: Float64Chunked = (0..500)
+            .map(|_| match next() % 10 {
+                0 => Some(f64::NAN),
+                v => Some(v as f64),
+            })
+            .collect();
+        let mut groups = (0..100 as IdxSize)
+            .map(|g| (g, (g..500).step_by(100).collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+        groups.push((0, vec![]));
+
+        let same = |a: Option<f64>, b: Option<f64>| match (a, b) {
+            (Some(a), Some(b)) => a == b || (a.is_nan() && b.is_nan()),
+            (a, b) => a.is_none() && b.is_none(),
+        };
+        for ca in &[with_nulls, with_nan] {
+            // the sort based implementations
+            let group_vals = groups
+                .iter()
+                .map(|(_, idx)| unsafe {
+                    ca.take_unchecked(idx.iter().map(|i| *i as usize).into())
+                })
+                .collect::<Vec<_>>();
+
+            let median = ca.agg_median(&groups).unwrap();
+            for (i, group) in group_vals.iter().enumerate() {
+                assert!(same(median.f64().unwrap().get(i), group.median()));
+            }
+
+            for &quantile in &[0.0, 0.2, 0.5, 0.9, 1.0] {
+                let out = ca.agg_quantile(&groups, quantile).unwrap();
+                for (i, group) in group_vals.iter().enumerate() {
+                    let null_count = group.null_count();
+                    let expected = match group.len() - null_count {
+                        0 => None,
+                        n => {
+                            let k = (quantile * (n - 1) as f64) as usize;
+                            ChunkSort::sort(group, false).get(null_count + k)
+                        }
+                    };
+                    assert!(same(out.f64().unwrap().get(i), expected));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_groupby_quantile_select_comparisons() {
+        use super::aggregations::select_kth;
+        use std::cell::Cell;
+        use std::cmp::Ordering;
+
+        // a value that counts how often it is compared
+        #[derive(Clone, Copy)]
+        struct Counted<'a>(f64, &'a Cell<usize>);
+        impl PartialEq for Counted<'_> {
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
+        impl PartialOrd for Counted<'_> {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                self.1.set(self.1.get() + 1);
+                self.0.partial_cmp(&other.0)
+            }
+        }
+
+        let selected = Cell::new(0);
+        let sorted = Cell::new(0);
+        let mut state = 3u64;
+        let values = (0..10_001)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                (state % 1000) as f64
+            })
+            .collect::<Vec<_>>();
+        let mut a = values
+            .iter()
+            .map(|v| Counted(*v, &selected))
+            .collect::<Vec<_>>();
+        let mut b = values
+            .iter()
+            .map(|v| Counted(*v, &sorted))
+            .collect::<Vec<_>>();
+
+        let median = select_kth(&mut a, 5_000);
+        // the sort based baseline
+        b.sort_by(|x, y| x.partial_cmp(y).unwrap());
+        assert_eq!(median.0, b[5_000].0);
+        // selection is linear, a sort is n * log(n)
+        assert!(
+            selected.get() * 2 < sorted.get(),
+            "selection took {} comparisons, sorting {}",
+            selected.get(),
+            sorted.get()
+        );
+    }
+
+    #[test]
+    fn test_groupby_agg_fold() -> Result<()> {
+        let mut df = df![
+            "a" => ["foo", "bar", "foo", "bar", "ham"],
+            "b" => [Some(1), None, Some(3), None, Some(2)],
+            "c" => [1, 2, 3, 4, 5]
+        ]?;
+        // multiple chunks are folded as one
+        df.vstack_mut(&df.slice(0, 1))?;
+
+        let out = df
+            .groupby_stable("a")?
+            .select(&["b", "c"])
+            .agg_fold::<Int64Type, _>(10, |acc, v| acc * v)?;
+        assert_eq!(out.get_column_names(), &["a", "b_fold", "c_fold"]);
+        assert_eq!(out.column("b_fold")?.dtype(), &DataType::Int64);
+        assert_eq!(
+            Vec::from(out.column("b_fold")?.i64()?),
+            &[Some(30), None, Some(20)]
+        );
+        assert_eq!(
+            Vec::from(out.column("c_fold")?.i64()?),
+            &[Some(30), Some(80), Some(50)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_groupby_list_column() -> Result<()> {
+        let values_builder = arrow::array::PrimitiveBuilder::<Int32Type>::new(8);
+        let mut builder = ListPrimitiveChunkedBuilder::new("tags", values_builder, 5);
+        builder.append_slice(Some(&[1, 2]));
+        builder.append_slice(Some(&[]));
+        builder.append_null();
+        builder.append_slice(Some(&[3]));
+        builder.append_slice(Some(&[4, 5]));
+        let tags = builder.finish().into_series();
+
+        let mut df = DataFrame::new(vec![
+            Series::new("a", &["foo", "bar", "foo", "foo", "ham"]),
+            tags,
+        ])?;
+        // the lists of a group can be located in different chunks
+        df.vstack_mut(&df.slice(0, 2))?;
+
+        let out = df.groupby_stable("a")?.select("tags").flatten()?;
+        let flat = out.column("tags_flatten")?;
+        assert_eq!(flat.dtype(), &DataType::List(ArrowDataType::Int32));
+        let flat = flat
+            .list()?
+            .into_iter()
+            .map(|opt_s| Vec::from(opt_s.unwrap().i32().unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            flat,
+            &[
+                vec![Some(1), Some(2), Some(3), Some(1), Some(2)],
+                vec![],
+                vec![Some(4), Some(5)]
+            ]
+        );
+
+        // an empty group results in an empty list
+        let groups = vec![(0, vec![]), (1, vec![1, 2])];
+        let out = df.column("tags")?.list()?.agg_flatten(&groups).unwrap();
+        assert_eq!(out.len(), 2);
+        assert!(out
+            .list()?
+            .into_iter()
+            .all(|opt_s| opt_s.unwrap().is_empty()));
+
+        let out = df.groupby_stable("a")?.select("tags").agg_list()?;
+        let list = out.column("tags_agg_list")?;
+        assert_eq!(
+            list.dtype(),
+            &DataType::List(ArrowDataType::LargeList(Box::new(
+                arrow::datatypes::Field::new("item", ArrowDataType::Int32, true)
+            )))
+        );
+        let first = list.list()?.get(0).unwrap();
+        assert_eq!(first.len(), 4);
+        let lens = first
+            .list()?
+            .into_iter()
+            .map(|opt_s| opt_s.map(|s| s.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(lens, &[Some(2), None, Some(1), Some(2)]);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_drop_duplicates_list_payload() -> Result<()> {
+        let s0 = Series::new("", &[1, 2]);
+        let s1 = Series::new("", &[3]);
+        let s2 = Series::new("", &[4, 5, 6]);
+        let df = DataFrame::new(vec![
+            Series::new("id", &[1, 1, 2, 2, 3]),
+            Series::new("name", &["a", "a", "b", "c", "c"]),
+            Series::new("payload", &[s0.clone(), s1.clone(), s2, s0, s1]),
+        ])?;
+
+        // only the subset is hashed, the list column is carried through
+        let out = df.drop_duplicates(true, Some(&["id".to_string()]))?;
+        assert_eq!(
+            Vec::from(out.column("id")?.i32()?),
+            &[Some(1), Some(2), Some(3)]
+        );
+        let lens = out
+            .column("payload")?
+            .list()?
+            .into_iter()
+            .map(|opt_s| opt_s.unwrap().len())
+            .collect::<Vec<_>>();
+        assert_eq!(lens, &[2, 3, 1]);
+
+        let subset = ["id".to_string(), "name".to_string()];
+        let out = df.drop_duplicates(true, Some(&subset))?;
+        assert_eq!(
+            Vec::from(out.column("name")?.utf8()?),
+            &[Some("a"), Some("b"), Some("c"), Some("c")]
+        );
+        assert_eq!(out.column("payload")?.len(), 4);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_distinct_list_keys() -> Result<()> {
+        use crate::chunked_array::builder::get_list_builder;
+
+        let mut builder = get_list_builder(&DataType::Int32, 16, 10, "tags");
+        let rows = [
+            Some(Series::new("", &[Some(1), Some(2)])),
+            Some(Series::new("", &[Some(1), None])),
+            None,
+            Some(Series::new("", &[Some(1), Some(2)])),
+            Some(Series::new("", &[Some(1), None])),
+            None,
+            Some(Series::new("", &[None::<i32>; 0])),
+            Some(Series::new("", &[None, Some(1)])),
+            Some(Series::new("", &[None::<i32>; 0])),
+            Some(Series::new("", &[Some(1)])),
+        ];
+        for opt_s in &rows {
+            builder.append_opt_series(opt_s.as_ref());
+        }
+        let tags = builder.finish().into_series();
+        let mut df = DataFrame::new(vec![
+            tags,
+            Series::new("v", &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]),
+        ])?;
+        // equal lists can be located in different chunks
+        df.vstack_mut(&df.slice(0, 2))?;
+
+        let tags = df.column("tags")?;
+        assert!(tags.get(0) == tags.get(3));
+        assert!(tags.get(2) == tags.get(5));
+        assert!(tags.get(1) != tags.get(7));
+        assert!(tags.get(6) != tags.get(2));
+
+        let expected = &[Some(0), Some(1), Some(2), Some(6), Some(7), Some(9)];
+        let out = df.drop_duplicates(true, Some(&["tags".to_string()]))?;
+        assert_eq!(Vec::from(out.column("v")?.i32()?), expected);
+        let expected = expected
+            .iter()
+            .map(|v| v.map(|v| v as IdxSize))
+            .collect::<Vec<_>>();
+        assert_eq!(Vec::from(&tags.arg_unique()?), expected);
+        assert_eq!(tags.unique()?.len(), 6);
+        assert_eq!(tags.n_unique()?, 6);
+        assert_eq!(
+            Vec::from(&tags.is_unique()?),
+            &[
+                Some(false),
+                Some(false),
+                Some(false),
+                Some(false),
+                Some(false),
+                Some(false),
+                Some(false),
+                Some(true),
+                Some(false),
+                Some(true),
+                Some(false),
+                Some(false)
+            ]
+        );
+
+        // a list key together with another key
+        df.replace("v", Series::new("v", &[0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0]))?;
+        let out = df.drop_duplicates(true, Some(&["v".to_string(), "tags".to_string()]))?;
+        assert_eq!(out.height(), 7);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "sort_multiple")]
+    #[cfg_attr(miri, ignore)]
+    fn test_groupby_drop_null_keys() -> Result<()> {
+        use crate::frame::groupby::GroupbyOptions;
+
+        let df = df![
+            "a" => [Some(1), None, Some(1), Some(2), None, Some(2), Some(1)],
+            "b" => [Some("x"), Some("x"), None, Some("y"), Some("y"), Some("y"), Some("x")],
+            "c" => [Some(1), Some(1), Some(1), None, Some(2), Some(2), Some(1)],
+            "v" => [1, 2, 3, 4, 5, 6, 7]
+        ]?;
+        let options = GroupbyOptions {
+            drop_null_keys: true,
+            ..Default::default()
+        };
+        // single key, two numeric keys and the generic multiple key path
+        for keys in &[vec!["a"], vec!["a", "c"], vec!["a", "b", "c"]] {
+            let out = df
+                .groupby_with_options(keys, options)?
+                .select("v")
+                .sum()?
+                .sort(keys, vec![false; keys.len()])?;
+
+            let mut mask = df.column(keys[0])?.is_not_null();
+            for key in &keys[1..] {
+                mask = mask & df.column(key)?.is_not_null();
+            }
+            let expected = df
+                .filter(&mask)?
+                .groupby(keys)?
+                .select("v")
+                .sum()?
+                .sort(keys, vec![false; keys.len()])?;
+            assert!(out.frame_equal_missing(&expected));
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "dtype-date32", feature = "dtype-date64"))]
+    #[cfg_attr(miri, ignore)]
+    fn test_groupby_date_keys() -> Result<()> {
+        let df = DataFrame::new(vec![
+            Series::new("date32", &[3, 1, 3, 2, 1]).cast::<Date32Type>()?,
+            Series::new("date64", &[30i64, 10, 30, 20, 10]).cast::<Date64Type>()?,
+            Series::new("str", &["a", "b", "a", "c", "b"]),
+            Series::new("v", &[1, 2, 3, 4, 5]),
+        ])?;
+
+        // single key, two numeric keys and the generic multiple key path
+        for keys in &[
+            vec!["date32"],
+            vec!["date64"],
+            vec!["date32", "date64"],
+            vec!["date32", "date64", "str"],
+        ] {
+            let out = df.groupby(keys)?.select("v").sum()?.sort(keys[0], false)?;
+            for key in keys {
+                assert_eq!(out.column(key)?.dtype(), df.column(key)?.dtype());
+            }
+            assert_eq!(
+                Vec::from(out.column("v_sum")?.i32()?),
+                &[Some(7), Some(4), Some(4)]
+            );
+        }
+
+        let subset = ["date32".to_string(), "date64".to_string()];
+        let out = df.drop_duplicates(true, Some(&subset))?;
+        assert_eq!(out.column("date32")?.dtype(), &DataType::Date32);
+        assert_eq!(out.column("date64")?.dtype(), &DataType::Date64);
+        assert_eq!(
+            Vec::from(out.column("v")?.i32()?),
+            &[Some(1), Some(2), Some(4)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_groupby_float_keys() -> Result<()> {
+        let _lock = crate::SINGLE_LOCK.lock();
+        let nan_payload = f64::from_bits(0x7ff8_0000_0000_0001);
+        let values = [
+            Some(f64::NAN),
+            Some(nan_payload),
+            Some(-f64::NAN),
+            Some(0.0),
+            Some(-0.0),
+            Some(1.0),
+            Some(1.0),
+            None,
+        ];
+        // repeat the values so that the keys are grouped on multiple threads
+        let f: Float64Chunked = values
+            .iter()
+            .cycle()
+            .take(values.len() * 200)
+            .copied()
+            .collect();
+        let f = f.into_series();
+        let f_32 = f.cast::<Float32Type>()?;
+        let g = Series::new("g", vec![1; f.len()]);
+        let s = Series::new("s", vec!["a"; f.len()]);
+
+        let n_groups = |df: &DataFrame, by: Vec<Series>, multithreaded: bool| -> Result<usize> {
+            Ok(df
+                .groupby_with_series(by, multithreaded)?
+                .get_groups()
+                .len())
+        };
+        let df = DataFrame::new(vec![f.clone()])?;
+        for &(bitwise, expected) in &[(false, 4), (true, 7)] {
+            crate::toggle_bitwise_float_keys(bitwise);
+            for &multithreaded in &[true, false] {
+                assert_eq!(n_groups(&df, vec![f.clone()], multithreaded)?, expected);
+                assert_eq!(n_groups(&df, vec![f_32.clone()], multithreaded)?, expected);
+                // two numeric keys and the generic multiple key path
+                assert_eq!(
+                    n_groups(&df, vec![f.clone(), g.clone()], multithreaded)?,
+                    expected
+                );
+                assert_eq!(
+                    n_groups(&df, vec![f.clone(), g.clone(), s.clone()], multithreaded)?,
+                    expected
+                );
+            }
+        }
+
+        // the equality of float lists follows the same rules as their hash
+        let lists = Series::new(
+            "l",
+            &[
+                Series::new("", &[f64::NAN]),
+                Series::new("", &[f64::NAN]),
+                Series::new("", &[-0.0]),
+                Series::new("", &[0.0]),
+                Series::new("", &[1.0]),
+            ],
+        );
+        let df = DataFrame::new(vec![lists.clone()])?;
+        for &(bitwise, expected) in &[(false, 3), (true, 4)] {
+            crate::toggle_bitwise_float_keys(bitwise);
+            assert_eq!(n_groups(&df, vec![lists.clone()], false)?, expected);
+            // [NaN] == [NaN] and [-0.0] == [0.0]
+            let left = lists.take_iter(&mut [0usize, 2].iter().copied());
+            let right = lists.take_iter(&mut [1usize, 3].iter().copied());
+            assert_eq!(
+                Vec::from(&left.list()?.eq_missing(right.list()?)),
+                &[Some(true), Some(!bitwise)]
+            );
+        }
+        crate::toggle_bitwise_float_keys(false);
+        Ok(())
+    }
 }
@@ -194,7 +194,7 @@ pub(crate) trait ChunkPivot {
         &self,
         _pivot_series: &'a Series,
         _keys: Vec<Series>,
-        _groups: &[(u32, Vec<u32>)],
+        _groups: &[(IdxSize, Vec<IdxSize>)],
         _agg_type: PivotAgg,
     ) -> Result<DataFrame> {
         Err(PolarsError::InvalidOperation(
@@ -206,7 +206,7 @@ pub(crate) trait ChunkPivot {
         &self,
         _pivot_series: &'a Series,
         _keys: Vec<Series>,
-        _groups: &[(u32, Vec<u32>)],
+        _groups: &[(IdxSize, Vec<IdxSize>)],
     ) -> Result<DataFrame> {
         Err(PolarsError::InvalidOperation(
             "Pivot count operation not implemented for this type".into(),
@@ -231,7 +231,7 @@ fn create_column_values_map<'a, T>(
 /// Create a hashmap that maps columns/keys to the result of the aggregation.
 fn create_new_column_builder_map<'a, T>(
     pivot_vec: &'a [Option<Groupable>],
-    groups: &[(u32, Vec<u32>)],
+    groups: &[(IdxSize, Vec<IdxSize>)],
 ) -> PlHashMap<&'a Groupable<'a>, PrimitiveChunkedBuilder<T>>
 where
     T: PolarsNumericType,
@@ -256,7 +256,7 @@ where
         &self,
         pivot_series: &'a Series,
         keys: Vec<Series>,
-        groups: &[(u32, Vec<u32>)],
+        groups: &[(IdxSize, Vec<IdxSize>)],
         agg_type: PivotAgg,
     ) -> Result<DataFrame> {
         // TODO: save an allocation by creating a random access struct for the Groupable utility type.
@@ -325,7 +325,7 @@ where
         &self,
         pivot_series: &'a Series,
         keys: Vec<Series>,
-        groups: &[(u32, Vec<u32>)],
+        groups: &[(IdxSize, Vec<IdxSize>)],
     ) -> Result<DataFrame> {
         pivot_count_impl(self, pivot_series, keys, groups)
     }
@@ -335,7 +335,7 @@ fn pivot_count_impl<'a, CA: TakeRandom>(
     ca: &CA,
     pivot_series: &'a Series,
     keys: Vec<Series>,
-    groups: &[(u32, Vec<u32>)],
+    groups: &[(IdxSize, Vec<IdxSize>)],
 ) -> Result<DataFrame> {
     let mut pivot_series = pivot_series.clone();
     let iter = pivot_series.as_groupable_iter()?;
@@ -385,7 +385,7 @@ impl ChunkPivot for BooleanChunked {
         &self,
         pivot_series: &'a Series,
         keys: Vec<Series>,
-        groups: &[(u32, Vec<u32>)],
+        groups: &[(IdxSize, Vec<IdxSize>)],
     ) -> Result<DataFrame> {
         pivot_count_impl(self, pivot_series, keys, groups)
     }
@@ -395,7 +395,7 @@ impl ChunkPivot for Utf8Chunked {
         &self,
         pivot_series: &'a Series,
         keys: Vec<Series>,
-        groups: &[(u32, Vec<u32>)],
+        groups: &[(IdxSize, Vec<IdxSize>)],
     ) -> Result<DataFrame> {
         pivot_count_impl(&self, pivot_series, keys, groups)
     }
@@ -406,7 +406,7 @@ impl ChunkPivot for CategoricalChunked {
         &self,
         pivot_series: &'a Series,
         keys: Vec<Series>,
-        groups: &[(u32, Vec<u32>)],
+        groups: &[(IdxSize, Vec<IdxSize>)],
     ) -> Result<DataFrame> {
         self.cast::<UInt32Type>()
             .unwrap()
@@ -0,0 +1,80 @@
+//! Typed access to the values of the groups of a [GroupBy](super::GroupBy).
+use super::GroupTuples;
+use crate::prelude::*;
+use arrow::array::{Array, PrimitiveArray};
+
+/// The values of a numeric column per group. Created by
+/// [GroupBy::group_values](super::GroupBy::group_values).
+///
+/// The column is rechunked once, after which the values of a group are read by indexing into a
+/// single buffer. No `Series` or `AnyValue` is created per group.
+///
+/// # Indices
+///
+/// The group indices are in bounds of the column by construction, as the groups are computed
+/// from the `DataFrame` the column is taken from. The indices are still bounds checked, so
+/// groups that are modified with [get_groups_mut](super::GroupBy::get_groups_mut) panic
+/// instead of reading out of bounds.
+pub struct GroupValues<'a, T: PolarsNumericType> {
+    ca: ChunkedArray<T>,
+    groups: &'a GroupTuples,
+}
+
+impl<'a, T: PolarsNumericType> GroupValues<'a, T> {
+    pub(crate) fn new(ca: &ChunkedArray<T>, groups: &'a GroupTuples) -> Self {
+        GroupValues {
+            ca: ca.rechunk(),
+            groups,
+        }
+    }
+
+    fn arr(&self) -> Option<&PrimitiveArray<T>> {
+        self.ca.downcast_iter().next()
+    }
+
+    fn slice(&self) -> &[T::Native] {
+        self.arr().map(|arr| arr.values()).unwrap_or(&[])
+    }
+
+    /// Number of groups.
+    pub fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Whether the column has null values.
+    pub fn has_nulls(&self) -> bool {
+        self.ca.null_count() > 0
+    }
+
+    /// The row indices of the group at position `group`.
+    pub fn indices(&self, group: usize) -> &'a [IdxSize] {
+        &self.groups[group].1
+    }
+
+    /// Iterate over the values of the group at position `group`. Null values are `None`.
+    pub fn values(&self, group: usize) -> impl Iterator<Item = Option<T::Native>> + '_ {
+        let validity = self.arr().filter(|_| self.has_nulls());
+        let values = self.slice();
+        self.indices(group).iter().map(move |&i| {
+            let i = i as usize;
+            match validity {
+                Some(arr) if arr.is_null(i) => None,
+                _ => Some(values[i]),
+            }
+        })
+    }
+
+    /// Iterate over the values of the group at position `group` without checking validity.
+    /// Returns `None` if the column has null values, use [values](Self::values) in that case.
+    pub fn no_null_values(&self, group: usize) -> Option<impl Iterator<Item = T::Native> + '_> {
+        if self.has_nulls() {
+            return None;
+        }
+        let values = self.slice();
+        Some(self.indices(group).iter().map(move |&i| values[i as usize]))
+    }
+}
@@ -0,0 +1,54 @@
+/// The row indices of the build relation that belong to a key of a join hash table.
+///
+/// Most keys of a join occur only once in the build relation. Those store their single row index
+/// inline, so that a build table with unique keys doesn't do a heap allocation per key. A key is
+/// moved to a `Vec` when its second row index is pushed.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum BuildIdx {
+    Single(IdxSize),
+    Multiple(Vec<IdxSize>),
+}
+
+impl BuildIdx {
+    #[inline]
+    pub(crate) fn new(idx: IdxSize) -> Self {
+        BuildIdx::Single(idx)
+    }
+
+    /// Add a row index. The indices keep the order in which they are pushed.
+    #[inline]
+    pub(crate) fn push(&mut self, idx: IdxSize) {
+        match self {
+            BuildIdx::Single(first) => *self = BuildIdx::Multiple(vec![*first, idx]),
+            BuildIdx::Multiple(v) => v.push(idx),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn as_slice(&self) -> &[IdxSize] {
+        match self {
+            BuildIdx::Single(idx) => std::slice::from_ref(idx),
+            BuildIdx::Multiple(v) => v,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn iter(&self) -> std::slice::Iter<'_, IdxSize> {
+        self.as_slice().iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_build_idx_push() {
+        let mut idx = BuildIdx::new(3);
+        assert_eq!(idx.as_slice(), &[3]);
+        idx.push(1);
+        idx.push(7);
+        assert_eq!(idx, BuildIdx::Multiple(vec![3, 1, 7]));
+        assert_eq!(idx.iter().copied().collect::<Vec<_>>(), &[3, 1, 7]);
+    }
+}
@@ -1,11 +1,15 @@
+mod build_idx;
 mod multiple_keys;
 
+use crate::chunked_array::ops::bit_repr::float_keys_to_bits;
+pub(crate) use build_idx::BuildIdx;
+
 use crate::frame::hash_join::multiple_keys::{
     inner_join_multiple_keys, left_join_multiple_keys, outer_join_multiple_keys,
 };
 use crate::frame::select::Selection;
 use crate::prelude::*;
-use crate::utils::{set_partition_size, split_ca, NoNull};
+use crate::utils::{ensure_unique_names, set_partition_size, split_ca, NoNull};
 use crate::vector_hasher::{
     create_hash_and_keys_threaded_vectorized, prepare_hashed_relation_threaded, this_partition,
     AsU64, StrHash,
@@ -39,6 +43,172 @@ pub(crate) fn check_categorical_src(l: &Series, r: &Series) -> Result<()> {
     Ok(())
 }
 
+/// The dtype of the hashed representation of a join key.
+fn join_key_physical_dtype(dtype: &DataType) -> DataType {
+    match dtype {
+        DataType::Date32 => DataType::Int32,
+        DataType::Date64 | DataType::Time64(_) | DataType::Duration(_) => DataType::Int64,
+        dt => dt.clone(),
+    }
+}
+
+/// Create the `DataFrame` of the keys of a join on multiple columns. Logical keys (e.g. Date32)
+/// are hashed and compared by their physical representation, which is a free cast, and float
+/// keys by their key bits. The output columns of the join are taken from the original frames, so
+/// they keep their logical dtype.
+fn multiple_keys_df(keys: &[Series]) -> DataFrame {
+    DataFrame::new_no_checks(
+        keys.iter()
+            .map(|s| float_keys_to_bits(&s.to_physical_repr()))
+            .collect(),
+    )
+}
+
+/// Check that the join keys can be joined. The keys should have the same dtype, only a logical
+/// left key (e.g. Date32) may be joined on a right key with the same physical type.
+pub(crate) fn check_join_keys(l: &Series, r: &Series) -> Result<()> {
+    let compatible = l.dtype() == r.dtype() || {
+        let physical = join_key_physical_dtype(l.dtype());
+        &physical != l.dtype() && physical == join_key_physical_dtype(r.dtype())
+    };
+    if !compatible {
+        return Err(PolarsError::SchemaMisMatch {
+            expected: format!("join key '{}' of dtype {:?}", r.name(), l.dtype()).into(),
+            found: format!("{:?}", r.dtype()).into(),
+        });
+    }
+    check_categorical_src(l, r)
+}
+
+/// The signedness and the number of bits of an integer dtype.
+fn integer_width(dtype: &DataType) -> Option<(bool, u8)> {
+    use DataType::*;
+    match dtype {
+        UInt8 => Some((false, 8)),
+        UInt16 => Some((false, 16)),
+        UInt32 => Some((false, 32)),
+        UInt64 => Some((false, 64)),
+        Int8 => Some((true, 8)),
+        Int16 => Some((true, 16)),
+        Int32 => Some((true, 32)),
+        Int64 => Some((true, 64)),
+        _ => None,
+    }
+}
+
+/// The dtype to which numeric join keys of different dtypes are cast. Keys are only cast to a
+/// dtype that can hold all values of both keys, such that no key value changes.
+///
+/// Returns `None` if the keys don't have such a dtype in common. A 64 bit integer key and a float
+/// key are an error, as distinct integers would be equal as `Float64`.
+fn join_key_supertype(l: &DataType, r: &DataType) -> Result<Option<DataType>> {
+    use DataType::*;
+    if l == r {
+        return Ok(None);
+    }
+    let dtype = match (integer_width(l), integer_width(r)) {
+        (Some((l_signed, l_width)), Some((r_signed, r_width))) => {
+            if l_signed == r_signed {
+                Some(if l_width >= r_width { l } else { r }.clone())
+            } else {
+                let (signed, signed_width, unsigned_width) = if l_signed {
+                    (l, l_width, r_width)
+                } else {
+                    (r, r_width, l_width)
+                };
+                if signed_width > unsigned_width {
+                    Some(signed.clone())
+                } else if unsigned_width < 64 {
+                    Some(Int64)
+                } else {
+                    None
+                }
+            }
+        }
+        (l_width, r_width) => {
+            let is_float = |dt: &DataType| matches!(dt, Float32 | Float64);
+            let is_numeric = |dt: &DataType| integer_width(dt).is_some() || is_float(dt);
+            if !(is_numeric(l) && is_numeric(r)) {
+                return Ok(None);
+            }
+            // every integer of at most 32 bits is exactly representable as f64
+            if let Some((_, 64)) = l_width.or(r_width) {
+                return Err(PolarsError::InvalidOperation(
+                    format!(
+                        "cannot join keys of dtype {:?} and {:?}, cast the keys to a common dtype",
+                        l, r
+                    )
+                    .into(),
+                ));
+            }
+            Some(Float64)
+        }
+    };
+    Ok(dtype)
+}
+
+/// Cast numeric join keys of different dtypes to a common dtype, e.g. an `UInt8` key and an
+/// `Int32` key are joined as `Int32`.
+fn coerce_join_keys(
+    left_keys: &[Series],
+    right_keys: &[Series],
+) -> Result<(Vec<Series>, Vec<Series>)> {
+    let mut left = Vec::with_capacity(left_keys.len());
+    let mut right = Vec::with_capacity(right_keys.len());
+    for (l, r) in left_keys.iter().zip(right_keys) {
+        match join_key_supertype(l.dtype(), r.dtype())? {
+            Some(dtype) => {
+                left.push(l.cast_with_dtype(&dtype)?);
+                right.push(r.cast_with_dtype(&dtype)?);
+            }
+            None => {
+                left.push(l.clone());
+                right.push(r.clone());
+            }
+        }
+    }
+    Ok((left, right))
+}
+
+/// The join tuples hold [IdxSize] row indices, check that all rows of both relations can be
+/// indexed. The `bigidx` feature lifts the limit of `u32::MAX` rows.
+pub(crate) fn check_join_height(left_height: usize, right_height: usize) -> Result<()> {
+    let max = IdxSize::MAX as usize;
+    if left_height > max || right_height > max {
+        return Err(PolarsError::InvalidOperation(
+            format!(
+                "joins support DataFrames of at most {} rows, got heights {} and {}",
+                max, left_height, right_height
+            )
+            .into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Check that the join keys have the same length as their DataFrame.
+fn check_join_key_lengths(
+    left: &DataFrame,
+    right: &DataFrame,
+    left_keys: &[Series],
+    right_keys: &[Series],
+) -> Result<()> {
+    for (df, keys) in &[(left, left_keys), (right, right_keys)] {
+        if let Some(s) = keys.iter().find(|s| s.len() != df.height()) {
+            return Err(PolarsError::ShapeMisMatch(
+                format!(
+                    "join key '{}' has length {}, but the DataFrame has height {}",
+                    s.name(),
+                    s.len(),
+                    df.height()
+                )
+                .into(),
+            ));
+        }
+    }
+    Ok(())
+}
+
 macro_rules! det_hash_prone_order {
     ($self:expr, $other:expr) => {{
         // The shortest relation will be used to create a hash table.
@@ -70,9 +240,9 @@ pub enum JoinType {
 
 unsafe fn get_hash_tbl_threaded_join_partitioned<T, H>(
     h: u64,
-    hash_tables: &[HashMap<T, Vec<u32>, H>],
+    hash_tables: &[HashMap<T, BuildIdx, H>],
     len: u64,
-) -> &HashMap<T, Vec<u32>, H> {
+) -> &HashMap<T, BuildIdx, H> {
     let mut idx = 0;
     for i in 0..len {
         // can only be done for powers of two.
@@ -86,9 +256,9 @@ unsafe fn get_hash_tbl_threaded_join_partitioned<T, H>(
 
 unsafe fn get_hash_tbl_threaded_join_mut_partitioned<T, H>(
     h: u64,
-    hash_tables: &mut [HashMap<T, Vec<u32>, H>],
+    hash_tables: &mut [HashMap<T, BuildIdx, H>],
     len: u64,
-) -> &mut HashMap<T, Vec<u32>, H> {
+) -> &mut HashMap<T, BuildIdx, H> {
     let mut idx = 0;
     for i in 0..len {
         // can only be done for powers of two.
@@ -103,18 +273,18 @@ unsafe fn get_hash_tbl_threaded_join_mut_partitioned<T, H>(
 /// Probe the build table and add tuples to the results (inner join)
 fn probe_inner<T, F>(
     probe: &[T],
-    hash_tbls: &[PlHashMap<T, Vec<u32>>],
-    results: &mut Vec<(u32, u32)>,
+    hash_tbls: &[PlHashMap<T, BuildIdx>],
+    results: &mut Vec<(IdxSize, IdxSize)>,
     local_offset: usize,
     n_tables: u64,
     swap_fn: F,
 ) where
     T: Send + Hash + Eq + Sync + Copy + AsU64,
-    F: Fn(u32, u32) -> (u32, u32),
+    F: Fn(IdxSize, IdxSize) -> (IdxSize, IdxSize),
 {
     assert!(hash_tbls.len().is_power_of_two());
     probe.iter().enumerate().for_each(|(idx_a, k)| {
-        let idx_a = (idx_a + local_offset) as u32;
+        let idx_a = (idx_a + local_offset) as IdxSize;
         // probe table that contains the hashed value
         let current_probe_table =
             unsafe { get_hash_tbl_threaded_join_partitioned(k.as_u64(), hash_tbls, n_tables) };
@@ -128,7 +298,7 @@ fn probe_inner<T, F>(
     });
 }
 
-pub(crate) fn create_probe_table<T, IntoSlice>(keys: Vec<IntoSlice>) -> Vec<PlHashMap<T, Vec<u32>>>
+pub(crate) fn create_probe_table<T, IntoSlice>(keys: Vec<IntoSlice>) -> Vec<PlHashMap<T, BuildIdx>>
 where
     T: Send + Hash + Eq + Sync + Copy + AsU64,
     IntoSlice: AsRef<[T]> + Send + Sync,
@@ -142,13 +312,13 @@ where
         (0..n_partitions).into_par_iter().map(|partition_no| {
             let partition_no = partition_no as u64;
 
-            let mut hash_tbl: PlHashMap<T, Vec<u32>> = PlHashMap::with_capacity(HASHMAP_INIT_SIZE);
+            let mut hash_tbl: PlHashMap<T, BuildIdx> = PlHashMap::with_capacity(HASHMAP_INIT_SIZE);
 
             let n_partitions = n_partitions as u64;
             let mut offset = 0;
             for keys in &keys {
                 let keys = keys.as_ref();
-                let len = keys.len() as u32;
+                let len = keys.len() as IdxSize;
 
                 let mut cnt = 0;
                 keys.iter().for_each(|k| {
@@ -160,7 +330,7 @@ where
 
                         match entry {
                             Entry::Vacant(entry) => {
-                                entry.insert(vec![idx]);
+                                entry.insert(BuildIdx::new(idx));
                             }
                             Entry::Occupied(mut entry) => {
                                 let v = entry.get_mut();
@@ -182,7 +352,7 @@ fn hash_join_tuples_inner<T, IntoSlice>(
     build: Vec<IntoSlice>,
     // Because b should be the shorter relation we could need to swap to keep left left and right right.
     swap: bool,
-) -> Vec<(u32, u32)>
+) -> Vec<(IdxSize, IdxSize)>
 where
     IntoSlice: AsRef<[T]> + Send + Sync,
     T: Send + Hash + Eq + Sync + Copy + AsU64,
@@ -247,7 +417,7 @@ where
 fn hash_join_tuples_left<T, IntoSlice>(
     probe: Vec<IntoSlice>,
     build: Vec<IntoSlice>,
-) -> Vec<(u32, Option<u32>)>
+) -> Vec<(IdxSize, Option<IdxSize>)>
 where
     IntoSlice: AsRef<[T]> + Send + Sync,
     T: Send + Hash + Eq + Sync + Copy + AsU64,
@@ -285,7 +455,7 @@ where
                 let mut results = Vec::with_capacity(probe.len());
 
                 probe.iter().enumerate().for_each(|(idx_a, k)| {
-                    let idx_a = (idx_a + offset) as u32;
+                    let idx_a = (idx_a + offset) as IdxSize;
                     // probe table that contains the hashed value
                     let current_probe_table = unsafe {
                         get_hash_tbl_threaded_join_partitioned(k.as_u64(), hash_tbls, n_tables)
@@ -313,8 +483,8 @@ where
 /// Probe the build table and add tuples to the results (inner join)
 fn probe_outer<T, F, G, H>(
     probe_hashes: &[Vec<(u64, T)>],
-    hash_tbls: &mut [PlHashMap<T, Vec<u32>>],
-    results: &mut Vec<(Option<u32>, Option<u32>)>,
+    hash_tbls: &mut [PlHashMap<T, BuildIdx>],
+    results: &mut Vec<(Option<IdxSize>, Option<IdxSize>)>,
     n_tables: u64,
     // Function that get index_a, index_b when there is a match and pushes to result
     swap_fn_match: F,
@@ -325,11 +495,11 @@ fn probe_outer<T, F, G, H>(
 ) where
     T: Send + Hash + Eq + Sync + Copy,
     // idx_a, idx_b -> ...
-    F: Fn(u32, u32) -> (Option<u32>, Option<u32>),
+    F: Fn(IdxSize, IdxSize) -> (Option<IdxSize>, Option<IdxSize>),
     // idx_a -> ...
-    G: Fn(u32) -> (Option<u32>, Option<u32>),
+    G: Fn(IdxSize) -> (Option<IdxSize>, Option<IdxSize>),
     // idx_b -> ...
-    H: Fn(u32) -> (Option<u32>, Option<u32>),
+    H: Fn(IdxSize) -> (Option<IdxSize>, Option<IdxSize>),
 {
     // needed for the partition shift instead of modulo to make sense
     assert!(n_tables.is_power_of_two());
@@ -371,7 +541,7 @@ fn hash_join_tuples_outer<T, I, J>(
     a: Vec<I>,
     b: Vec<J>,
     swap: bool,
-) -> Vec<(Option<u32>, Option<u32>)>
+) -> Vec<(Option<IdxSize>, Option<IdxSize>)>
 where
     I: Iterator<Item = T> + Send,
     J: Iterator<Item = T> + Send,
@@ -428,67 +598,73 @@ where
 }
 
 pub(crate) trait HashJoin<T> {
-    fn hash_join_inner(&self, _other: &ChunkedArray<T>) -> Vec<(u32, u32)> {
+    fn hash_join_inner(&self, _other: &ChunkedArray<T>) -> Vec<(IdxSize, IdxSize)> {
         unimplemented!()
     }
-    fn hash_join_left(&self, _other: &ChunkedArray<T>) -> Vec<(u32, Option<u32>)> {
+    fn hash_join_left(&self, _other: &ChunkedArray<T>) -> Vec<(IdxSize, Option<IdxSize>)> {
         unimplemented!()
     }
-    fn hash_join_outer(&self, _other: &ChunkedArray<T>) -> Vec<(Option<u32>, Option<u32>)> {
+    fn hash_join_outer(&self, _other: &ChunkedArray<T>) -> Vec<(Option<IdxSize>, Option<IdxSize>)> {
         unimplemented!()
     }
 }
 
 impl HashJoin<Float32Type> for Float32Chunked {
-    fn hash_join_inner(&self, other: &Float32Chunked) -> Vec<(u32, u32)> {
-        let ca = self.bit_repr_small();
-        let other = other.bit_repr_small();
+    fn hash_join_inner(&self, other: &Float32Chunked) -> Vec<(IdxSize, IdxSize)> {
+        let ca = self.key_bit_repr();
+        let other = other.key_bit_repr();
         ca.hash_join_inner(&other)
     }
-    fn hash_join_left(&self, other: &Float32Chunked) -> Vec<(u32, Option<u32>)> {
-        let ca = self.bit_repr_small();
-        let other = other.bit_repr_small();
+    fn hash_join_left(&self, other: &Float32Chunked) -> Vec<(IdxSize, Option<IdxSize>)> {
+        let ca = self.key_bit_repr();
+        let other = other.key_bit_repr();
         ca.hash_join_left(&other)
     }
-    fn hash_join_outer(&self, other: &Float32Chunked) -> Vec<(Option<u32>, Option<u32>)> {
-        let ca = self.bit_repr_small();
-        let other = other.bit_repr_small();
+    fn hash_join_outer(&self, other: &Float32Chunked) -> Vec<(Option<IdxSize>, Option<IdxSize>)> {
+        let ca = self.key_bit_repr();
+        let other = other.key_bit_repr();
         ca.hash_join_outer(&other)
     }
 }
 
 impl HashJoin<Float64Type> for Float64Chunked {
-    fn hash_join_inner(&self, other: &Float64Chunked) -> Vec<(u32, u32)> {
-        let ca = self.bit_repr_large();
-        let other = other.bit_repr_large();
+    fn hash_join_inner(&self, other: &Float64Chunked) -> Vec<(IdxSize, IdxSize)> {
+        let ca = self.key_bit_repr();
+        let other = other.key_bit_repr();
         ca.hash_join_inner(&other)
     }
-    fn hash_join_left(&self, other: &Float64Chunked) -> Vec<(u32, Option<u32>)> {
-        let ca = self.bit_repr_large();
-        let other = other.bit_repr_large();
+    fn hash_join_left(&self, other: &Float64Chunked) -> Vec<(IdxSize, Option<IdxSize>)> {
+        let ca = self.key_bit_repr();
+        let other = other.key_bit_repr();
         ca.hash_join_left(&other)
     }
-    fn hash_join_outer(&self, other: &Float64Chunked) -> Vec<(Option<u32>, Option<u32>)> {
-        let ca = self.bit_repr_large();
-        let other = other.bit_repr_large();
+    fn hash_join_outer(&self, other: &Float64Chunked) -> Vec<(Option<IdxSize>, Option<IdxSize>)> {
+        let ca = self.key_bit_repr();
+        let other = other.key_bit_repr();
         ca.hash_join_outer(&other)
     }
 }
 
 impl HashJoin<ListType> for ListChunked {}
 impl HashJoin<CategoricalType> for CategoricalChunked {
-    fn hash_join_inner(&self, other: &CategoricalChunked) -> Vec<(u32, u32)> {
+    fn hash_join_inner(&self, other: &CategoricalChunked) -> Vec<(IdxSize, IdxSize)> {
         self.deref().hash_join_inner(&other.cast().unwrap())
     }
-    fn hash_join_left(&self, other: &CategoricalChunked) -> Vec<(u32, Option<u32>)> {
+    fn hash_join_left(&self, other: &CategoricalChunked) -> Vec<(IdxSize, Option<IdxSize>)> {
         self.deref().hash_join_left(&other.cast().unwrap())
     }
-    fn hash_join_outer(&self, other: &CategoricalChunked) -> Vec<(Option<u32>, Option<u32>)> {
+    fn hash_join_outer(
+        &self,
+        other: &CategoricalChunked,
+    ) -> Vec<(Option<IdxSize>, Option<IdxSize>)> {
         self.deref().hash_join_outer(&other.cast().unwrap())
     }
 }
 
-fn num_group_join_inner<T>(left: &ChunkedArray<T>, right: &ChunkedArray<T>) -> Vec<(u32, u32)>
+fn num_group_join_inner<T>(
+    left: &ChunkedArray<T>,
+    right: &ChunkedArray<T>,
+) -> Vec<(IdxSize, IdxSize)>
 where
     T: PolarsIntegerType,
     T::Native: Hash + Eq + Send + AsU64,
@@ -565,7 +741,7 @@ where
 fn num_group_join_left<T>(
     left: &ChunkedArray<T>,
     right: &ChunkedArray<T>,
-) -> Vec<(u32, Option<u32>)>
+) -> Vec<(IdxSize, Option<IdxSize>)>
 where
     T: PolarsIntegerType,
     T::Native: Hash + Eq + Send + AsU64,
@@ -643,7 +819,7 @@ where
     T: PolarsIntegerType + Sync,
     T::Native: Eq + Hash + num::NumCast,
 {
-    fn hash_join_inner(&self, other: &ChunkedArray<T>) -> Vec<(u32, u32)> {
+    fn hash_join_inner(&self, other: &ChunkedArray<T>) -> Vec<(IdxSize, IdxSize)> {
         match self.dtype() {
             DataType::UInt64 => {
                 // convince the compiler that we are this type.
@@ -683,7 +859,7 @@ where
         }
     }
 
-    fn hash_join_left(&self, other: &ChunkedArray<T>) -> Vec<(u32, Option<u32>)> {
+    fn hash_join_left(&self, other: &ChunkedArray<T>) -> Vec<(IdxSize, Option<IdxSize>)> {
         match self.dtype() {
             DataType::UInt64 => {
                 // convince the compiler that we are this type.
@@ -723,7 +899,7 @@ where
         }
     }
 
-    fn hash_join_outer(&self, other: &ChunkedArray<T>) -> Vec<(Option<u32>, Option<u32>)> {
+    fn hash_join_outer(&self, other: &ChunkedArray<T>) -> Vec<(Option<IdxSize>, Option<IdxSize>)> {
         let (a, b, swap) = det_hash_prone_order!(self, other);
 
         let n_partitions = set_partition_size();
@@ -752,19 +928,19 @@ where
 }
 
 impl HashJoin<BooleanType> for BooleanChunked {
-    fn hash_join_inner(&self, other: &BooleanChunked) -> Vec<(u32, u32)> {
+    fn hash_join_inner(&self, other: &BooleanChunked) -> Vec<(IdxSize, IdxSize)> {
         let ca = self.cast::<UInt32Type>().unwrap();
         let other = other.cast::<UInt32Type>().unwrap();
         ca.hash_join_inner(&other)
     }
 
-    fn hash_join_left(&self, other: &BooleanChunked) -> Vec<(u32, Option<u32>)> {
+    fn hash_join_left(&self, other: &BooleanChunked) -> Vec<(IdxSize, Option<IdxSize>)> {
         let ca = self.cast::<UInt32Type>().unwrap();
         let other = other.cast::<UInt32Type>().unwrap();
         ca.hash_join_left(&other)
     }
 
-    fn hash_join_outer(&self, other: &BooleanChunked) -> Vec<(Option<u32>, Option<u32>)> {
+    fn hash_join_outer(&self, other: &BooleanChunked) -> Vec<(Option<IdxSize>, Option<IdxSize>)> {
         let (a, b, swap) = det_hash_prone_order!(self, other);
 
         let n_partitions = set_partition_size();
@@ -811,7 +987,7 @@ fn prepare_strs<'a>(been_split: &'a [Utf8Chunked], hb: &RandomState) -> Vec<Vec<
 }
 
 impl HashJoin<Utf8Type> for Utf8Chunked {
-    fn hash_join_inner(&self, other: &Utf8Chunked) -> Vec<(u32, u32)> {
+    fn hash_join_inner(&self, other: &Utf8Chunked) -> Vec<(IdxSize, IdxSize)> {
         let n_threads = POOL.current_num_threads();
 
         let (a, b, swap) = det_hash_prone_order!(self, other);
@@ -825,7 +1001,7 @@ impl HashJoin<Utf8Type> for Utf8Chunked {
         hash_join_tuples_inner(str_hashes_a, str_hashes_b, swap)
     }
 
-    fn hash_join_left(&self, other: &Utf8Chunked) -> Vec<(u32, Option<u32>)> {
+    fn hash_join_left(&self, other: &Utf8Chunked) -> Vec<(IdxSize, Option<IdxSize>)> {
         let n_threads = POOL.current_num_threads();
 
         let hb = RandomState::default();
@@ -837,7 +1013,7 @@ impl HashJoin<Utf8Type> for Utf8Chunked {
         hash_join_tuples_left(str_hashes_a, str_hashes_b)
     }
 
-    fn hash_join_outer(&self, other: &Utf8Chunked) -> Vec<(Option<u32>, Option<u32>)> {
+    fn hash_join_outer(&self, other: &Utf8Chunked) -> Vec<(Option<IdxSize>, Option<IdxSize>)> {
         let (a, b, swap) = det_hash_prone_order!(self, other);
 
         let n_partitions = set_partition_size();
@@ -869,7 +1045,7 @@ pub trait ZipOuterJoinColumn {
     fn zip_outer_join_column(
         &self,
         _right_column: &Series,
-        _opt_join_tuples: &[(Option<u32>, Option<u32>)],
+        _opt_join_tuples: &[(Option<IdxSize>, Option<IdxSize>)],
     ) -> Series {
         unimplemented!()
     }
@@ -883,7 +1059,7 @@ where
     fn zip_outer_join_column(
         &self,
         right_column: &Series,
-        opt_join_tuples: &[(Option<u32>, Option<u32>)],
+        opt_join_tuples: &[(Option<IdxSize>, Option<IdxSize>)],
     ) -> Series {
         let right_ca = self.unpack_series_matching_type(right_column).unwrap();
 
@@ -921,7 +1097,7 @@ macro_rules! impl_zip_outer_join {
             fn zip_outer_join_column(
                 &self,
                 right_column: &Series,
-                opt_join_tuples: &[(Option<u32>, Option<u32>)],
+                opt_join_tuples: &[(Option<IdxSize>, Option<IdxSize>)],
             ) -> Series {
                 let right_ca = self.unpack_series_matching_type(right_column).unwrap();
 
@@ -950,11 +1126,13 @@ impl_zip_outer_join!(BooleanChunked);
 impl_zip_outer_join!(Utf8Chunked);
 
 impl DataFrame {
-    /// Utility method to finish a join.
+    /// Utility method to finish a join. Columns of the right DataFrame that also exist in the left
+    /// DataFrame get a `suffix`, which defaults to `"_right"`.
     pub(crate) fn finish_join(
         &self,
         mut df_left: DataFrame,
         mut df_right: DataFrame,
+        suffix: Option<&str>,
     ) -> Result<DataFrame> {
         let mut left_names = HashSet::with_capacity_and_hasher(df_left.width(), RandomState::new());
 
@@ -970,25 +1148,65 @@ impl DataFrame {
             }
         });
 
+        let suffix = suffix.unwrap_or("_right");
         for name in rename_strs {
-            df_right.rename(&name, &format!("{}_right", name))?;
+            df_right.rename(&name, &format!("{}{}", name, suffix))?;
         }
 
+        // the suffixed names may still collide with existing columns
+        let columns = df_left
+            .columns
+            .iter()
+            .chain(df_right.columns.iter())
+            .cloned()
+            .collect::<Vec<_>>();
+        ensure_unique_names(&columns, &format!("join with suffix '{}'", suffix))?;
+
         df_left.hstack_mut(&df_right.columns)?;
         Ok(df_left)
     }
 
-    fn create_left_df<B: Sync>(&self, join_tuples: &[(u32, B)], left_join: bool) -> DataFrame {
+    fn create_left_df<B: Sync>(&self, join_tuples: &[(IdxSize, B)], left_join: bool) -> DataFrame {
         if left_join && join_tuples.len() == self.height() {
             self.clone()
         } else {
-            unsafe {
-                self.take_iter_unchecked(join_tuples.iter().map(|(left, _right)| *left as usize))
-            }
+            // Safety:
+            // the join tuples are in bounds of the left keys.
+            unsafe { self.take_join_idx(join_tuples.iter().map(|(left, _right)| *left as usize)) }
         }
     }
 
+    /// Take the rows of one side of a join by the join tuples of that side.
+    ///
+    /// # Safety
+    ///
+    /// The indices must be in bounds, which holds for join tuples that are computed from the
+    /// join keys of this DataFrame.
+    pub(crate) unsafe fn take_join_idx<I>(&self, idx: I) -> DataFrame
+    where
+        I: Iterator<Item = usize> + Clone + Sync,
+    {
+        self.take_iter_unchecked(idx)
+    }
+
+    /// Take the rows of one side of a join by the join tuples of that side, where `None` means
+    /// that there is no match and produces a row of nulls.
+    ///
+    /// # Safety
+    ///
+    /// The indices must be in bounds, which holds for join tuples that are computed from the
+    /// join keys of this DataFrame.
+    pub(crate) unsafe fn take_join_opt_idx<I>(&self, idx: I) -> DataFrame
+    where
+        I: Iterator<Item = Option<usize>> + Clone + Sync,
+    {
+        self.take_opt_iter_unchecked(idx)
+    }
+
     /// Generic join method. Can be used to join on multiple columns.
+    ///
+    /// Numeric keys of different dtypes are cast to a dtype that holds the values of both, e.g.
+    /// an `UInt8` key can be joined with an `Int32` key.
     pub fn join<'a, J, S1: Selection<'a, J>, S2: Selection<'a, J>>(
         &self,
         other: &DataFrame,
@@ -996,131 +1214,255 @@ impl DataFrame {
         right_on: S2,
         how: JoinType,
     ) -> Result<DataFrame> {
+        self.join_with_suffix(other, left_on, right_on, how, None)
+    }
+
+    /// Generic join method where the columns of `other` that also exist in `self` get the given
+    /// `suffix`. If no suffix is given `"_right"` is used.
+    pub fn join_with_suffix<'a, J, S1: Selection<'a, J>, S2: Selection<'a, J>>(
+        &self,
+        other: &DataFrame,
+        left_on: S1,
+        right_on: S2,
+        how: JoinType,
+        suffix: Option<&str>,
+    ) -> Result<DataFrame> {
+        check_join_height(self.height(), other.height())?;
+
         #[cfg(feature = "cross_join")]
         if let JoinType::Cross = how {
-            return self.cross_join(other);
+            return self.cross_join_with_suffix(other, suffix);
         }
 
         let selected_left = self.select_series(left_on)?;
         let selected_right = other.select_series(right_on)?;
-        assert_eq!(selected_right.len(), selected_left.len());
 
-        for (l, r) in selected_left.iter().zip(&selected_right) {
-            check_categorical_src(l, r)?
-        }
-
-        if selected_left.len() == 1 {
-            return match how {
-                JoinType::Inner => {
-                    self.inner_join(other, selected_left[0].name(), selected_right[0].name())
-                }
-                JoinType::Left => {
-                    self.left_join(other, selected_left[0].name(), selected_right[0].name())
-                }
-                JoinType::Outer => {
-                    self.outer_join(other, selected_left[0].name(), selected_right[0].name())
-                }
-                #[cfg(feature = "asof_join")]
-                JoinType::AsOf => {
-                    self.join_asof(other, selected_left[0].name(), selected_right[0].name())
-                }
-                #[cfg(feature = "cross_join")]
-                JoinType::Cross => {
-                    unreachable!()
+        #[cfg(feature = "asof_join")]
+        if let JoinType::AsOf = how {
+            return match (selected_left.as_slice(), selected_right.as_slice()) {
+                ([s_left], [s_right]) => {
+                    self.join_asof_with_suffix(other, s_left.name(), s_right.name(), suffix)
                 }
+                _ => Err(PolarsError::ValueError(
+                    "asof join not supported for join on multiple keys".into(),
+                )),
             };
         }
 
-        fn remove_selected(df: &DataFrame, selected: &[Series]) -> DataFrame {
-            let mut new = None;
+        fn remove_selected(df: &DataFrame, selected: &[Series]) -> Result<DataFrame> {
+            let mut new = df.clone();
             for s in selected {
-                new = match new {
-                    None => Some(df.drop(s.name()).unwrap()),
-                    Some(new) => Some(new.drop(s.name()).unwrap()),
-                }
+                new = new.drop(s.name())?;
             }
-            new.unwrap()
+            Ok(new)
         }
 
-        impl DataFrame {
-            fn len(&self) -> usize {
-                self.height()
+        // the right join columns are removed, in an outer join the left join columns are
+        // replaced by the combined keys of both sides.
+        let other = remove_selected(other, &selected_right)?;
+        match how {
+            JoinType::Outer => remove_selected(self, &selected_left)?.join_on_series_impl(
+                &other,
+                &selected_left,
+                &selected_right,
+                how,
+                suffix,
+                &vec![true; selected_left.len()],
+            ),
+            _ => {
+                self.join_on_series_impl(&other, &selected_left, &selected_right, how, suffix, &[])
             }
         }
+    }
+
+    /// Join on the given key Series instead of columns. The keys must have the same length
+    /// as their DataFrame and are not added to the output, which holds all columns of `self`
+    /// and `other`. Columns of `other` that also exist in `self` get the suffix `"_right"`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use polars_core::prelude::*;
+    /// // join an `Int32` id on an `Int64` id
+    /// fn join_dfs(left: &DataFrame, right: &DataFrame) -> Result<DataFrame> {
+    ///     let left_key = left.column("id")?.cast_with_dtype(&DataType::Int64)?;
+    ///     let right_key = right.column("id")?.clone();
+    ///     left.join_on_series(right, &[left_key], &[right_key], JoinType::Inner)
+    /// }
+    /// ```
+    pub fn join_on_series(
+        &self,
+        other: &DataFrame,
+        left_keys: &[Series],
+        right_keys: &[Series],
+        how: JoinType,
+    ) -> Result<DataFrame> {
+        self.join_on_series_with_suffix(other, left_keys, right_keys, how, None)
+    }
+
+    /// Join on the given key Series, where the columns of `other` that also exist in `self` get
+    /// the given `suffix`. If no suffix is given `"_right"` is used.
+    pub fn join_on_series_with_suffix(
+        &self,
+        other: &DataFrame,
+        left_keys: &[Series],
+        right_keys: &[Series],
+        how: JoinType,
+        suffix: Option<&str>,
+    ) -> Result<DataFrame> {
+        check_join_key_lengths(self, other, left_keys, right_keys)?;
+        self.join_on_series_impl(other, left_keys, right_keys, how, suffix, &[])
+    }
+
+    /// Join `self` and `other` on the given keys. In an outer join, the left keys for which
+    /// `coalesce_outer_keys` is set are combined with the right keys and stored in the column
+    /// with the name of the left key, which is added if it doesn't exist.
+    ///
+    /// Numeric keys of different dtypes are joined on a common dtype, which is also the dtype of
+    /// a combined outer key.
+    pub(crate) fn join_on_series_impl(
+        &self,
+        other: &DataFrame,
+        left_keys: &[Series],
+        right_keys: &[Series],
+        how: JoinType,
+        suffix: Option<&str>,
+        coalesce_outer_keys: &[bool],
+    ) -> Result<DataFrame> {
+        #[cfg(feature = "cross_join")]
+        if let JoinType::Cross = how {
+            return self.cross_join_with_suffix(other, suffix);
+        }
+
+        if left_keys.is_empty() {
+            return Err(PolarsError::ValueError(
+                "at least one join key is required".into(),
+            ));
+        }
+        if left_keys.len() != right_keys.len() {
+            return Err(PolarsError::ShapeMisMatch(
+                format!(
+                    "the number of left join keys: {} does not match the number of right join keys: {}",
+                    left_keys.len(),
+                    right_keys.len()
+                )
+                .into(),
+            ));
+        }
+        // the frames may have no columns left if all columns are keys
+        check_join_height(left_keys[0].len(), right_keys[0].len())?;
+        let (left_keys, right_keys) = coerce_join_keys(left_keys, right_keys)?;
+        let (left_keys, right_keys) = (left_keys.as_slice(), right_keys.as_slice());
+        for (l, r) in left_keys.iter().zip(right_keys) {
+            check_join_keys(l, r)?
+        }
+        let single_key = left_keys.len() == 1;
 
         // This is still single threaded and can create very large keys that are inserted in the
         // hashmap. TODO: implement same hashing technique as in grouping.
         match how {
             JoinType::Inner => {
-                let left = DataFrame::new_no_checks(selected_left);
-                let right = DataFrame::new_no_checks(selected_right.clone());
-                let (left, right, swap) = det_hash_prone_order!(left, right);
-                let join_tuples = inner_join_multiple_keys(&left, &right, swap);
+                let join_tuples = if single_key {
+                    left_keys[0].hash_join_inner(&right_keys[0])
+                } else {
+                    let left = multiple_keys_df(left_keys);
+                    let right = multiple_keys_df(right_keys);
+                    let (left, right, swap) = det_hash_prone_order!(left, right);
+                    inner_join_multiple_keys(&left, &right, swap)?
+                };
 
                 let (df_left, df_right) = POOL.join(
                     || self.create_left_df(&join_tuples, false),
+                    // Safety:
+                    // the join tuples are in bounds of the right keys.
                     || unsafe {
-                        // remove join columns
-                        remove_selected(other, &selected_right).take_iter_unchecked(
-                            join_tuples.iter().map(|(_left, right)| *right as usize),
-                        )
+                        other
+                            .take_join_idx(join_tuples.iter().map(|(_left, right)| *right as usize))
                     },
                 );
-                self.finish_join(df_left, df_right)
+                self.finish_join(df_left, df_right, suffix)
             }
             JoinType::Left => {
-                let left = DataFrame::new_no_checks(selected_left);
-                let right = DataFrame::new_no_checks(selected_right.clone());
-                let join_tuples = left_join_multiple_keys(&left, &right);
+                let opt_join_tuples = if single_key {
+                    left_keys[0].hash_join_left(&right_keys[0])
+                } else {
+                    let left = multiple_keys_df(left_keys);
+                    let right = multiple_keys_df(right_keys);
+                    left_join_multiple_keys(&left, &right)?
+                };
 
                 let (df_left, df_right) = POOL.join(
-                    || self.create_left_df(&join_tuples, true),
+                    || self.create_left_df(&opt_join_tuples, true),
+                    // Safety:
+                    // the join tuples are in bounds of the right keys.
                     || unsafe {
-                        // remove join columns
-                        remove_selected(other, &selected_right).take_opt_iter_unchecked(
-                            join_tuples
+                        other.take_join_opt_idx(
+                            opt_join_tuples
                                 .iter()
                                 .map(|(_left, right)| right.map(|i| i as usize)),
                         )
                     },
                 );
-                self.finish_join(df_left, df_right)
+                self.finish_join(df_left, df_right, suffix)
             }
             JoinType::Outer => {
-                let left = DataFrame::new_no_checks(selected_left.clone());
-                let right = DataFrame::new_no_checks(selected_right.clone());
-
-                let (left, right, swap) = det_hash_prone_order!(left, right);
-                let opt_join_tuples = outer_join_multiple_keys(&left, &right, swap);
+                let opt_join_tuples = if single_key {
+                    left_keys[0].hash_join_outer(&right_keys[0])
+                } else {
+                    let left = multiple_keys_df(left_keys);
+                    let right = multiple_keys_df(right_keys);
+                    let (left, right, swap) = det_hash_prone_order!(left, right);
+                    outer_join_multiple_keys(&left, &right, swap)?
+                };
 
                 // Take the left and right dataframes by join tuples
                 let (mut df_left, df_right) = POOL.join(
+                    // Safety:
+                    // the join tuples are in bounds of the left keys.
                     || unsafe {
-                        remove_selected(self, &selected_left).take_opt_iter_unchecked(
+                        self.take_join_opt_idx(
                             opt_join_tuples
                                 .iter()
                                 .map(|(left, _right)| left.map(|i| i as usize)),
                         )
                     },
+                    // Safety:
+                    // the join tuples are in bounds of the right keys.
                     || unsafe {
-                        remove_selected(other, &selected_right).take_opt_iter_unchecked(
+                        other.take_join_opt_idx(
                             opt_join_tuples
                                 .iter()
                                 .map(|(_left, right)| right.map(|i| i as usize)),
                         )
                     },
                 );
-                for (s_left, s_right) in selected_left.iter().zip(&selected_right) {
+                let keys = left_keys.iter().zip(right_keys).zip(coalesce_outer_keys);
+                for ((s_left, s_right), _) in keys.filter(|(_, coalesce)| **coalesce) {
                     let mut s = s_left.zip_outer_join_column(s_right, &opt_join_tuples);
                     s.rename(s_left.name());
-                    df_left.hstack_mut(&[s])?;
+                    df_left.with_column(s)?;
                 }
-                self.finish_join(df_left, df_right)
+                self.finish_join(df_left, df_right, suffix)
             }
             #[cfg(feature = "asof_join")]
-            JoinType::AsOf => Err(PolarsError::ValueError(
-                "asof join not supported for join on multiple keys".into(),
-            )),
+            JoinType::AsOf => {
+                if !single_key {
+                    return Err(PolarsError::ValueError(
+                        "asof join not supported for join on multiple keys".into(),
+                    ));
+                }
+                let take_idx = left_keys[0].join_asof(&right_keys[0])?;
+                // Safety:
+                // the asof indices are in bounds of the right keys.
+                let df_right = unsafe {
+                    other.take_join_opt_idx(
+                        take_idx
+                            .into_iter()
+                            .map(|opt_idx| opt_idx.map(|idx| idx as usize)),
+                    )
+                };
+                self.finish_join(self.clone(), df_right, suffix)
+            }
             #[cfg(feature = "cross_join")]
             JoinType::Cross => {
                 unreachable!()
@@ -1144,30 +1486,7 @@ impl DataFrame {
         left_on: &str,
         right_on: &str,
     ) -> Result<DataFrame> {
-        let s_left = self.column(left_on)?;
-        let s_right = other.column(right_on)?;
-        self.inner_join_from_series(other, s_left, s_right)
-    }
-
-    pub(crate) fn inner_join_from_series(
-        &self,
-        other: &DataFrame,
-        s_left: &Series,
-        s_right: &Series,
-    ) -> Result<DataFrame> {
-        check_categorical_src(s_left, s_right)?;
-        let join_tuples = s_left.hash_join_inner(s_right);
-
-        let (df_left, df_right) = POOL.join(
-            || self.create_left_df(&join_tuples, false),
-            || unsafe {
-                other
-                    .drop(s_right.name())
-                    .unwrap()
-                    .take_iter_unchecked(join_tuples.iter().map(|(_left, right)| *right as usize))
-            },
-        );
-        self.finish_join(df_left, df_right)
+        self.join(other, left_on, right_on, JoinType::Inner)
     }
 
     /// Perform a left join on two DataFrames
@@ -1180,31 +1499,7 @@ impl DataFrame {
     /// }
     /// ```
     pub fn left_join(&self, other: &DataFrame, left_on: &str, right_on: &str) -> Result<DataFrame> {
-        let s_left = self.column(left_on)?;
-        let s_right = other.column(right_on)?;
-        self.left_join_from_series(other, s_left, s_right)
-    }
-
-    pub(crate) fn left_join_from_series(
-        &self,
-        other: &DataFrame,
-        s_left: &Series,
-        s_right: &Series,
-    ) -> Result<DataFrame> {
-        check_categorical_src(s_left, s_right)?;
-        let opt_join_tuples = s_left.hash_join_left(s_right);
-
-        let (df_left, df_right) = POOL.join(
-            || self.create_left_df(&opt_join_tuples, true),
-            || unsafe {
-                other.drop(s_right.name()).unwrap().take_opt_iter_unchecked(
-                    opt_join_tuples
-                        .iter()
-                        .map(|(_left, right)| right.map(|i| i as usize)),
-                )
-            },
-        );
-        self.finish_join(df_left, df_right)
+        self.join(other, left_on, right_on, JoinType::Left)
     }
 
     /// Perform an outer join on two DataFrames
@@ -1222,44 +1517,31 @@ impl DataFrame {
         left_on: &str,
         right_on: &str,
     ) -> Result<DataFrame> {
-        let s_left = self.column(left_on)?;
-        let s_right = other.column(right_on)?;
-        self.outer_join_from_series(other, s_left, s_right)
+        self.join(other, left_on, right_on, JoinType::Outer)
     }
-    pub(crate) fn outer_join_from_series(
-        &self,
-        other: &DataFrame,
-        s_left: &Series,
-        s_right: &Series,
-    ) -> Result<DataFrame> {
-        check_categorical_src(s_left, s_right)?;
-        // Get the indexes of the joined relations
-        let opt_join_tuples = s_left.hash_join_outer(s_right);
-
-        // Take the left and right dataframes by join tuples
-        let (mut df_left, df_right) = POOL.join(
-            || unsafe {
-                self.drop(s_left.name()).unwrap().take_opt_iter_unchecked(
-                    opt_join_tuples
-                        .iter()
-                        .map(|(left, _right)| left.map(|i| i as usize)),
-                )
-            },
-            || unsafe {
-                other.drop(s_right.name()).unwrap().take_opt_iter_unchecked(
-                    opt_join_tuples
-                        .iter()
-                        .map(|(_left, right)| right.map(|i| i as usize)),
-                )
-            },
-        );
-        let mut s = s_left.zip_outer_join_column(s_right, &opt_join_tuples);
-        s.rename(s_left.name());
-        df_left.hstack_mut(&[s])?;
-        self.finish_join(df_left, df_right)
+}
+
+impl DataFrame {
+    fn len(&self) -> usize {
+        self.height()
     }
 }
 
+/// Outer join on the given key Series. The left keys for which `coalesce` is set are combined
+/// with the right keys and stored in the column of `a` with the name of the left key.
+#[cfg(feature = "private")]
+pub fn private_outer_join_on_series(
+    a: &DataFrame,
+    b: &DataFrame,
+    left_keys: &[Series],
+    right_keys: &[Series],
+    coalesce: &[bool],
+    suffix: Option<&str>,
+) -> Result<DataFrame> {
+    check_join_key_lengths(a, b, left_keys, right_keys)?;
+    a.join_on_series_impl(b, left_keys, right_keys, JoinType::Outer, suffix, coalesce)
+}
+
 #[cfg(test)]
 mod test {
     use crate::df;
@@ -1278,6 +1560,31 @@ mod test {
         (temp, rain)
     }
 
+    #[test]
+    fn test_probe_table_inline_unique_keys() {
+        use super::{create_probe_table, BuildIdx};
+
+        // the payload of a key is not larger than the Vec it replaces
+        assert!(std::mem::size_of::<BuildIdx>() <= std::mem::size_of::<Vec<IdxSize>>());
+
+        // 800 unique keys and 100 keys that occur twice
+        let keys = (0..1000u64).map(|i| i % 900).collect::<Vec<_>>();
+        let tables = create_probe_table(vec![keys.as_slice()]);
+        let mut single = 0;
+        let mut multiple = 0;
+        for idx in tables.iter().flat_map(|tbl| tbl.values()) {
+            match idx {
+                // a unique key doesn't allocate
+                BuildIdx::Single(_) => single += 1,
+                BuildIdx::Multiple(v) => {
+                    assert_eq!(v.len(), 2);
+                    multiple += 1
+                }
+            }
+        }
+        assert_eq!((single, multiple), (800, 100));
+    }
+
     #[test]
     #[cfg_attr(miri, ignore)]
     fn test_inner_join() {
@@ -1551,4 +1858,221 @@ mod test {
         right.inner_join(&left, "key", "key").unwrap();
         right.outer_join(&left, "key", "key").unwrap();
     }
+
+    #[test]
+    fn test_join_key_dtype_mismatch() {
+        let left = df![
+            "key" => [1, 2]
+        ]
+        .unwrap();
+        let right = df![
+            "key" => ["1", "2"]
+        ]
+        .unwrap();
+        assert!(matches!(
+            left.inner_join(&right, "key", "key"),
+            Err(PolarsError::SchemaMisMatch { .. })
+        ));
+        assert!(matches!(
+            left.join(&right, vec!["key", "key"], vec!["key"], JoinType::Left),
+            Err(PolarsError::ShapeMisMatch(_))
+        ));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_join_on_series() -> Result<()> {
+        let left = df![
+            "id" => [1, 2, 3],
+            "value" => [1.0, 2.0, 3.0]
+        ]?;
+        let right = df![
+            "id" => [20, 30, 40],
+            "value" => [20.0, 30.0, 40.0]
+        ]?;
+        let left_key = left.column("id")? * 10;
+        let right_key = right.column("id")?.clone();
+
+        // the keys are not added and the right key column is kept
+        let out = left
+            .join_on_series(
+                &right,
+                &[left_key.clone()],
+                &[right_key.clone()],
+                JoinType::Inner,
+            )?
+            .sort("id", false)?;
+        assert_eq!(
+            out.get_column_names(),
+            &["id", "value", "id_right", "value_right"]
+        );
+        assert_eq!(Vec::from(out.column("id")?.i32()?), &[Some(2), Some(3)]);
+        assert_eq!(
+            Vec::from(out.column("id_right")?.i32()?),
+            &[Some(20), Some(30)]
+        );
+
+        let out = left.join_on_series_with_suffix(
+            &right,
+            &[left_key.clone()],
+            &[right_key.clone()],
+            JoinType::Left,
+            Some("_other"),
+        )?;
+        assert_eq!(
+            out.get_column_names(),
+            &["id", "value", "id_other", "value_other"]
+        );
+        assert_eq!(out.column("value_other")?.null_count(), 1);
+
+        // an outer join doesn't combine the keys
+        let out = left.join_on_series(
+            &right,
+            &[left_key.clone()],
+            &[right_key.clone()],
+            JoinType::Outer,
+        )?;
+        assert_eq!(out.height(), 4);
+        assert_eq!(out.column("id")?.null_count(), 1);
+        assert_eq!(out.column("id_right")?.null_count(), 1);
+
+        // the name based join removes the right key column
+        let out = left.join(&right, "id", "id", JoinType::Inner)?;
+        assert_eq!(out.get_column_names(), &["id", "value", "value_right"]);
+
+        let short_key = Series::new("id", &[1, 2]);
+        assert!(matches!(
+            left.join_on_series(&right, &[short_key], &[right_key], JoinType::Inner),
+            Err(PolarsError::ShapeMisMatch(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "dtype-u8")]
+    fn test_join_numeric_key_promotion() -> Result<()> {
+        use super::join_key_supertype;
+        use DataType::*;
+        assert_eq!(join_key_supertype(&UInt8, &Int32)?, Some(Int32));
+        assert_eq!(join_key_supertype(&UInt32, &Int32)?, Some(Int64));
+        assert_eq!(join_key_supertype(&UInt8, &UInt16)?, Some(UInt16));
+        assert_eq!(join_key_supertype(&UInt64, &Int64)?, None);
+        assert_eq!(join_key_supertype(&UInt8, &Float32)?, Some(Float64));
+        assert_eq!(join_key_supertype(&Int32, &Float64)?, Some(Float64));
+        // distinct 64 bit integers may be equal as floats
+        assert!(join_key_supertype(&Int64, &Float64).is_err());
+        assert!(join_key_supertype(&Float32, &UInt64).is_err());
+        assert_eq!(join_key_supertype(&Int32, &Utf8)?, None);
+
+        let left = df![
+            "key" => [1u8, 2, 200],
+            "left" => ["a", "b", "c"]
+        ]?;
+        let right = df![
+            "key" => [200i32, 2, -56],
+            "right" => ["x", "y", "z"]
+        ]?;
+        let out = left.inner_join(&right, "key", "key")?.sort("key", false)?;
+        assert_eq!(out.column("key")?.dtype(), &UInt8);
+        assert_eq!(Vec::from(out.column("key")?.u8()?), &[Some(2), Some(200)]);
+        assert_eq!(
+            Vec::from(out.column("right")?.utf8()?),
+            &[Some("y"), Some("x")]
+        );
+
+        let out = left.outer_join(&right, "key", "key")?;
+        assert_eq!(out.column("key")?.dtype(), &Int32);
+        assert_eq!(out.height(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_join_height() {
+        let max = IdxSize::MAX as usize;
+        assert!(super::check_join_height(max, 10).is_ok());
+        // with `bigidx` every height that fits in a usize can be indexed
+        if let Some(too_large) = max.checked_add(1) {
+            assert!(matches!(
+                super::check_join_height(too_large, 10),
+                Err(PolarsError::InvalidOperation(_))
+            ));
+            assert!(super::check_join_height(10, too_large).is_err());
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "dtype-date32", feature = "dtype-date64"))]
+    #[cfg_attr(miri, ignore)]
+    fn test_join_date_keys() -> Result<()> {
+        let left = DataFrame::new(vec![
+            Series::new("date32", &[1, 2, 3]).cast::<Date32Type>()?,
+            Series::new("date64", &[10i64, 20, 30]).cast::<Date64Type>()?,
+            Series::new("left", &[1, 2, 3]),
+        ])?;
+        let right = DataFrame::new(vec![
+            Series::new("date32", &[3, 2, 4]).cast::<Date32Type>()?,
+            Series::new("date64", &[30i64, 0, 40]).cast::<Date64Type>()?,
+            Series::new("right", &[3, 2, 4]),
+        ])?;
+
+        for keys in &[vec!["date32"], vec!["date32", "date64"]] {
+            for how in &[JoinType::Inner, JoinType::Left, JoinType::Outer] {
+                let out = left.join(&right, keys, keys, *how)?;
+                assert_eq!(out.column("date32")?.dtype(), &DataType::Date32);
+                assert_eq!(out.column("date64")?.dtype(), &DataType::Date64);
+            }
+        }
+
+        let out = left.join(
+            &right,
+            &["date32", "date64"],
+            &["date32", "date64"],
+            JoinType::Inner,
+        )?;
+        assert_eq!(Vec::from(out.column("right")?.i32()?), &[Some(3)]);
+
+        // a logical key may be joined on a key with the same physical type
+        let right = DataFrame::new(vec![
+            right.column("date32")?.cast::<Int32Type>()?,
+            right.column("date64")?.cast::<Int64Type>()?,
+        ])?;
+        let out = left.join(
+            &right,
+            &["date32", "date64"],
+            &["date32", "date64"],
+            JoinType::Inner,
+        )?;
+        assert_eq!(out.column("date32")?.dtype(), &DataType::Date32);
+        assert_eq!(Vec::from(out.column("left")?.i32()?), &[Some(3)]);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_join_float_keys() -> Result<()> {
+        let _lock = crate::SINGLE_LOCK.lock();
+        let nan_payload = f64::from_bits(0x7ff8_0000_0000_0001);
+        let left = df![
+            "f" => [f64::NAN, nan_payload, 0.0, 1.0],
+            "g" => [1, 1, 1, 1]
+        ]?;
+        let right = df![
+            "f" => [-f64::NAN, -0.0, 1.0, 2.0],
+            "g" => [1, 1, 1, 1]
+        ]?;
+
+        for &(bitwise, expected) in &[(false, 4), (true, 1)] {
+            crate::toggle_bitwise_float_keys(bitwise);
+            for keys in &[vec!["f"], vec!["f", "g"]] {
+                let out = left.join(&right, keys, keys, JoinType::Inner)?;
+                assert_eq!(out.height(), expected);
+                // the same keys are matched when the float key is the second key
+                let keys = keys.iter().rev().copied().collect::<Vec<_>>();
+                let out = left.join(&right, keys.clone(), keys, JoinType::Inner)?;
+                assert_eq!(out.height(), expected);
+            }
+        }
+        crate::toggle_bitwise_float_keys(false);
+        Ok(())
+    }
 }
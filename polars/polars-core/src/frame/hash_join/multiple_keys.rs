@@ -1,6 +1,6 @@
 use crate::frame::groupby::hashing::{populate_multiple_key_hashmap, HASHMAP_INIT_SIZE};
 use crate::frame::hash_join::{
-    get_hash_tbl_threaded_join_mut_partitioned, get_hash_tbl_threaded_join_partitioned,
+    get_hash_tbl_threaded_join_mut_partitioned, get_hash_tbl_threaded_join_partitioned, BuildIdx,
 };
 use crate::prelude::*;
 use crate::utils::{set_partition_size, split_df};
@@ -9,6 +9,7 @@ use crate::POOL;
 use hashbrown::hash_map::RawEntryMut;
 use hashbrown::HashMap;
 use rayon::prelude::*;
+use std::convert::TryFrom;
 
 /// Compare the rows of two DataFrames
 unsafe fn compare_df_rows2(
@@ -26,10 +27,29 @@ unsafe fn compare_df_rows2(
     true
 }
 
+/// Add the length of a chunk to the row index `offset`, erroring if the indices no longer fit in
+/// an [IdxSize].
+fn checked_idx_offset(offset: IdxSize, len: usize) -> Result<IdxSize> {
+    IdxSize::try_from(len)
+        .ok()
+        .and_then(|len| offset.checked_add(len))
+        .ok_or_else(|| {
+            PolarsError::InvalidOperation(
+                "the row indices of the join keys don't fit in the index type, the DataFrame has too many rows"
+                    .into(),
+            )
+        })
+}
+
+/// Every partition traverses the hashes in row order, so the row indices that are stored for
+/// a key are in ascending order. The joins rely on this to emit the matches of a row in a
+/// deterministic order.
+///
+/// Errors if the row indices of `keys` don't fit in an [IdxSize].
 fn create_build_table(
     hashes: &[UInt64Chunked],
     keys: &DataFrame,
-) -> Vec<HashMap<IdxHash, Vec<u32>, IdBuildHasher>> {
+) -> Result<Vec<HashMap<IdxHash, BuildIdx, IdBuildHasher>>> {
     let n_partitions = set_partition_size();
 
     // We will create a hashtable in every thread.
@@ -38,7 +58,7 @@ fn create_build_table(
     POOL.install(|| {
         (0..n_partitions).into_par_iter().map(|part_no| {
             let part_no = part_no as u64;
-            let mut hash_tbl: HashMap<IdxHash, Vec<u32>, IdBuildHasher> =
+            let mut hash_tbl: HashMap<IdxHash, BuildIdx, IdBuildHasher> =
                 HashMap::with_capacity_and_hasher(HASHMAP_INIT_SIZE, Default::default());
 
             let n_partitions = n_partitions as u64;
@@ -57,37 +77,38 @@ fn create_build_table(
                                 idx,
                                 *h,
                                 keys,
-                                || vec![idx],
+                                || BuildIdx::new(idx),
                                 |v| v.push(idx),
                             )
                         }
                         idx += 1;
                     });
 
-                    offset += len as u32;
+                    offset = checked_idx_offset(offset, len)?;
                 }
             }
-            hash_tbl
+            Ok(hash_tbl)
         })
     })
     .collect()
 }
 
-/// Probe the build table and add tuples to the results (inner join)
+/// Probe the build table and add tuples to the results (inner join). The tuples are added in
+/// the order of the probe rows and the matches of a probe row in ascending order.
 #[allow(clippy::too_many_arguments)]
 fn probe_inner<F>(
     probe_hashes: &UInt64Chunked,
-    hash_tbls: &[HashMap<IdxHash, Vec<u32>, IdBuildHasher>],
-    results: &mut Vec<(u32, u32)>,
+    hash_tbls: &[HashMap<IdxHash, BuildIdx, IdBuildHasher>],
+    results: &mut Vec<(IdxSize, IdxSize)>,
     local_offset: usize,
     n_tables: u64,
     a: &DataFrame,
     b: &DataFrame,
     swap_fn: F,
 ) where
-    F: Fn(u32, u32) -> (u32, u32),
+    F: Fn(IdxSize, IdxSize) -> (IdxSize, IdxSize),
 {
-    let mut idx_a = local_offset as u32;
+    let mut idx_a = local_offset as IdxSize;
     for probe_hashes in probe_hashes.data_views() {
         for &h in probe_hashes {
             // probe table that contains the hashed value
@@ -122,22 +143,24 @@ fn get_offsets(probe_hashes: &[UInt64Chunked]) -> Vec<usize> {
         .collect()
 }
 
+/// The join tuples are ordered by the left row and the matches of a left row by the right row,
+/// regardless of which relation is used to build the hash table.
 pub(crate) fn inner_join_multiple_keys(
     a: &DataFrame,
     b: &DataFrame,
     swap: bool,
-) -> Vec<(u32, u32)> {
+) -> Result<Vec<(IdxSize, IdxSize)>> {
     // we assume that the b DataFrame is the shorter relation.
     // b will be used for the build phase.
 
     let n_threads = POOL.current_num_threads();
-    let dfs_a = split_df(a, n_threads).unwrap();
-    let dfs_b = split_df(b, n_threads).unwrap();
+    let dfs_a = split_df(a, n_threads)?;
+    let dfs_b = split_df(b, n_threads)?;
 
     let (build_hashes, random_state) = df_rows_to_hashes_threaded(&dfs_b, None);
     let (probe_hashes, _) = df_rows_to_hashes_threaded(&dfs_a, Some(random_state));
 
-    let hash_tbls = create_build_table(&build_hashes, b);
+    let hash_tbls = create_build_table(&build_hashes, b)?;
     // early drop to reduce memory pressure
     drop(build_hashes);
 
@@ -145,7 +168,7 @@ pub(crate) fn inner_join_multiple_keys(
     let offsets = get_offsets(&probe_hashes);
     // next we probe the other relation
     // code duplication is because we want to only do the swap check once
-    POOL.install(|| {
+    let mut join_tuples = POOL.install(|| {
         probe_hashes
             .into_par_iter()
             .zip(offsets)
@@ -183,27 +206,40 @@ pub(crate) fn inner_join_multiple_keys(
                 results
             })
             .flatten()
-            .collect()
-    })
+            .collect::<Vec<_>>()
+    });
+    // the probe side is the right relation, so the tuples are in the order of the right rows
+    if swap {
+        POOL.install(|| join_tuples.par_sort_unstable());
+    }
+    Ok(join_tuples)
 }
 
 #[cfg(feature = "private")]
-pub fn private_left_join_multiple_keys(a: &DataFrame, b: &DataFrame) -> Vec<(u32, Option<u32>)> {
+pub fn private_left_join_multiple_keys(
+    a: &DataFrame,
+    b: &DataFrame,
+) -> Result<Vec<(IdxSize, Option<IdxSize>)>> {
     left_join_multiple_keys(a, b)
 }
 
-pub(crate) fn left_join_multiple_keys(a: &DataFrame, b: &DataFrame) -> Vec<(u32, Option<u32>)> {
+/// The join tuples are in the order of the left rows and the matches of a left row in ascending
+/// order of the right rows.
+pub(crate) fn left_join_multiple_keys(
+    a: &DataFrame,
+    b: &DataFrame,
+) -> Result<Vec<(IdxSize, Option<IdxSize>)>> {
     // we assume that the b DataFrame is the shorter relation.
     // b will be used for the build phase.
 
     let n_threads = POOL.current_num_threads();
-    let dfs_a = split_df(a, n_threads).unwrap();
-    let dfs_b = split_df(b, n_threads).unwrap();
+    let dfs_a = split_df(a, n_threads)?;
+    let dfs_b = split_df(b, n_threads)?;
 
     let (build_hashes, random_state) = df_rows_to_hashes_threaded(&dfs_b, None);
     let (probe_hashes, _) = df_rows_to_hashes_threaded(&dfs_a, Some(random_state));
 
-    let hash_tbls = create_build_table(&build_hashes, b);
+    let hash_tbls = create_build_table(&build_hashes, b)?;
     // early drop to reduce memory pressure
     drop(build_hashes);
 
@@ -212,7 +248,7 @@ pub(crate) fn left_join_multiple_keys(a: &DataFrame, b: &DataFrame) -> Vec<(u32,
 
     // next we probe the other relation
     // code duplication is because we want to only do the swap check once
-    POOL.install(|| {
+    let join_tuples = POOL.install(|| {
         probe_hashes
             .into_par_iter()
             .zip(offsets)
@@ -223,7 +259,7 @@ pub(crate) fn left_join_multiple_keys(a: &DataFrame, b: &DataFrame) -> Vec<(u32,
                     Vec::with_capacity(probe_hashes.len() / POOL.current_num_threads());
                 let local_offset = offset;
 
-                let mut idx_a = local_offset as u32;
+                let mut idx_a = local_offset as IdxSize;
                 for probe_hashes in probe_hashes.data_views() {
                     for &h in probe_hashes {
                         // probe table that contains the hashed value
@@ -254,15 +290,16 @@ pub(crate) fn left_join_multiple_keys(a: &DataFrame, b: &DataFrame) -> Vec<(u32,
             })
             .flatten()
             .collect()
-    })
+    });
+    Ok(join_tuples)
 }
 
 /// Probe the build table and add tuples to the results (inner join)
 #[allow(clippy::too_many_arguments)]
 fn probe_outer<F, G, H>(
     probe_hashes: &[UInt64Chunked],
-    hash_tbls: &mut [HashMap<IdxHash, Vec<u32>, IdBuildHasher>],
-    results: &mut Vec<(Option<u32>, Option<u32>)>,
+    hash_tbls: &mut [HashMap<IdxHash, BuildIdx, IdBuildHasher>],
+    results: &mut Vec<(Option<IdxSize>, Option<IdxSize>)>,
     n_tables: u64,
     a: &DataFrame,
     b: &DataFrame,
@@ -274,11 +311,11 @@ fn probe_outer<F, G, H>(
     swap_fn_drain: H,
 ) where
     // idx_a, idx_b -> ...
-    F: Fn(u32, u32) -> (Option<u32>, Option<u32>),
+    F: Fn(IdxSize, IdxSize) -> (Option<IdxSize>, Option<IdxSize>),
     // idx_a -> ...
-    G: Fn(u32) -> (Option<u32>, Option<u32>),
+    G: Fn(IdxSize) -> (Option<IdxSize>, Option<IdxSize>),
     // idx_b -> ...
-    H: Fn(u32) -> (Option<u32>, Option<u32>),
+    H: Fn(IdxSize) -> (Option<IdxSize>, Option<IdxSize>),
 {
     let mut idx_a = 0;
 
@@ -327,7 +364,7 @@ pub(crate) fn outer_join_multiple_keys(
     a: &DataFrame,
     b: &DataFrame,
     swap: bool,
-) -> Vec<(Option<u32>, Option<u32>)> {
+) -> Result<Vec<(Option<IdxSize>, Option<IdxSize>)>> {
     // we assume that the b DataFrame is the shorter relation.
     // b will be used for the build phase.
 
@@ -335,13 +372,13 @@ pub(crate) fn outer_join_multiple_keys(
     let mut results = Vec::with_capacity(size);
 
     let n_threads = POOL.current_num_threads();
-    let dfs_a = split_df(a, n_threads).unwrap();
-    let dfs_b = split_df(b, n_threads).unwrap();
+    let dfs_a = split_df(a, n_threads)?;
+    let dfs_b = split_df(b, n_threads)?;
 
     let (build_hashes, random_state) = df_rows_to_hashes_threaded(&dfs_b, None);
     let (probe_hashes, _) = df_rows_to_hashes_threaded(&dfs_a, Some(random_state));
 
-    let mut hash_tbls = create_build_table(&build_hashes, b);
+    let mut hash_tbls = create_build_table(&build_hashes, b)?;
     // early drop to reduce memory pressure
     drop(build_hashes);
 
@@ -376,5 +413,78 @@ pub(crate) fn outer_join_multiple_keys(
             |idx_b| (None, Some(idx_b)),
         )
     }
-    results
+    Ok(results)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_checked_idx_offset() {
+        assert_eq!(checked_idx_offset(10, 5).unwrap(), 15);
+        assert_eq!(
+            checked_idx_offset(IdxSize::MAX - 5, 5).unwrap(),
+            IdxSize::MAX
+        );
+        assert!(checked_idx_offset(IdxSize::MAX - 5, 6).is_err());
+        if let Some(len) = (IdxSize::MAX as usize).checked_add(1) {
+            assert!(checked_idx_offset(0, len).is_err());
+        }
+    }
+
+    #[test]
+    fn test_multiple_keys_join_order() -> Result<()> {
+        // every key occurs many times on both sides
+        let left = df![
+            "a" => (0..1000).map(|i| i % 7).collect::<Vec<i32>>(),
+            "b" => (0..1000).map(|i| (i % 3).to_string()).collect::<Vec<_>>()
+        ]?;
+        let right = df![
+            "a" => (0..600).rev().map(|i| i % 7).collect::<Vec<i32>>(),
+            "b" => (0..600).rev().map(|i| (i % 3).to_string()).collect::<Vec<_>>()
+        ]?;
+
+        let is_ordered = |tuples: &[(IdxSize, IdxSize)]| {
+            tuples
+                .windows(2)
+                .all(|w| w[0].0 < w[1].0 || (w[0].0 == w[1].0 && w[0].1 < w[1].1))
+        };
+
+        let expected = left_join_multiple_keys(&left, &right)?;
+        let matches = expected
+            .iter()
+            .map(|(l, r)| (*l, r.unwrap()))
+            .collect::<Vec<_>>();
+        // the keys repeat every 21 rows
+        let count = |n: usize, r: usize| (0..n).filter(|i| i % 21 == r).count();
+        let n_matches = (0..21)
+            .map(|r| count(1000, r) * count(600, r))
+            .sum::<usize>();
+        assert_eq!(matches.len(), n_matches);
+        assert!(is_ordered(&matches));
+        for _ in 0..20 {
+            assert_eq!(left_join_multiple_keys(&left, &right)?, expected);
+        }
+
+        // the left relation is the build side and the right relation the probe side
+        let inner = inner_join_multiple_keys(&right, &left, true)?;
+        assert_eq!(inner, matches);
+        // the right relation is the build side
+        assert_eq!(inner_join_multiple_keys(&left, &right, false)?, matches);
+        for _ in 0..20 {
+            assert_eq!(inner_join_multiple_keys(&right, &left, true)?, matches);
+        }
+
+        let df_left = left.hstack(&[Series::new("idx", (0..1000).collect::<Vec<IdxSize>>())])?;
+        let df_right = right.hstack(&[Series::new("idx", (0..600).collect::<Vec<IdxSize>>())])?;
+        for how in &[JoinType::Inner, JoinType::Left] {
+            let out = df_left.join(&df_right, &["a", "b"], &["a", "b"], *how)?;
+            let idx = out.column("idx")?.idx()?.into_no_null_iter();
+            let idx_right = out.column("idx_right")?.idx()?.into_no_null_iter();
+            let tuples = idx.zip(idx_right).collect::<Vec<_>>();
+            assert_eq!(tuples, matches);
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,488 @@
+use crate::prelude::compare_inner::PartialOrdInner;
+use crate::prelude::*;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// Compute the take indices that merge two sorted sequences. The indices of `right` are offset
+/// by `left_len`, such that they can be used on the vertical concatenation of both inputs.
+///
+/// Equal values of `left` are taken before those of `right`, so the merge is stable.
+fn merge_take_idx<T, L, R>(
+    left: L,
+    right: R,
+    left_len: usize,
+    key: &str,
+) -> Result<AlignedVec<IdxSize>>
+where
+    T: PartialOrd,
+    L: Iterator<Item = T>,
+    R: Iterator<Item = T>,
+{
+    let not_sorted = |side: &str| {
+        PolarsError::ComputeError(
+            format!(
+                "merge_sorted expects the key column '{}' of the {} DataFrame to be sorted in ascending order",
+                key, side
+            )
+            .into(),
+        )
+    };
+
+    let mut left = left.peekable();
+    let mut right = right.peekable();
+    let mut prev_left: Option<T> = None;
+    let mut prev_right: Option<T> = None;
+    let mut left_idx: IdxSize = 0;
+    let mut right_idx = left_len as IdxSize;
+
+    let (lower, _) = left.size_hint();
+    let (lower_right, _) = right.size_hint();
+    let mut idx = AlignedVec::with_capacity_aligned(lower + lower_right);
+
+    loop {
+        let take_left = match (left.peek(), right.peek()) {
+            (Some(l), Some(r)) => l <= r,
+            (Some(_), None) => true,
+            (None, Some(_)) => false,
+            (None, None) => break,
+        };
+        if take_left {
+            let v = left.next().unwrap();
+            if matches!(&prev_left, Some(prev) if *prev > v) {
+                return Err(not_sorted("left"));
+            }
+            prev_left = Some(v);
+            idx.push(left_idx);
+            left_idx += 1;
+        } else {
+            let v = right.next().unwrap();
+            if matches!(&prev_right, Some(prev) if *prev > v) {
+                return Err(not_sorted("right"));
+            }
+            prev_right = Some(v);
+            idx.push(right_idx);
+            right_idx += 1;
+        }
+    }
+    Ok(idx)
+}
+
+/// Compares the rows of the key columns of a sort, with the ordering of `reverse` per column.
+struct RowCmp<'a> {
+    compare_inner: Vec<Box<dyn PartialOrdInner + 'a>>,
+    reverse: &'a [bool],
+}
+
+impl<'a> RowCmp<'a> {
+    fn new(keys: &'a [Series], reverse: &'a [bool]) -> Self {
+        RowCmp {
+            compare_inner: keys.iter().map(|s| s.into_partial_ord_inner()).collect(),
+            reverse,
+        }
+    }
+
+    /// # Safety
+    /// The indices must be in bounds of the key columns.
+    unsafe fn cmp(&self, idx_a: usize, idx_b: usize) -> Ordering {
+        for (cmp, reverse) in self.compare_inner.iter().zip(self.reverse) {
+            let ordering = cmp.cmp_element_unchecked(idx_a, idx_b);
+            match (ordering, reverse) {
+                (Ordering::Equal, _) => continue,
+                (_, true) => return ordering.reverse(),
+                _ => return ordering,
+            }
+        }
+        Ordering::Equal
+    }
+}
+
+/// A sorted run that is read in consecutive batches, see [DataFrame::merge_sorted_batched_runs].
+struct BatchedRun<I> {
+    batches: I,
+    /// The rows of the current batch that are not merged yet.
+    remaining: DataFrame,
+}
+
+impl<I: Iterator<Item = Result<DataFrame>>> BatchedRun<I> {
+    /// Start the run at its first non empty batch, `None` if the run has no rows.
+    fn new(mut batches: I) -> Result<Option<Self>> {
+        Ok(Self::next_batch(&mut batches)?.map(|remaining| BatchedRun { batches, remaining }))
+    }
+
+    fn next_batch(batches: &mut I) -> Result<Option<DataFrame>> {
+        for batch in batches {
+            let batch = batch?;
+            if batch.height() > 0 {
+                return Ok(Some(batch));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// The next row of one of the sorted runs in a k-way merge.
+struct RunHead<'a> {
+    /// Row index in the concatenated runs.
+    idx: usize,
+    /// End of the run (exclusive).
+    end: usize,
+    cmp: &'a dyn Fn(usize, usize) -> Ordering,
+}
+
+impl Ord for RunHead<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // `BinaryHeap` is a max-heap, so the ordering is reversed to pop the smallest row first.
+        // Equal rows are ordered by their index, which keeps the merge stable.
+        (self.cmp)(other.idx, self.idx).then_with(|| other.idx.cmp(&self.idx))
+    }
+}
+
+impl PartialOrd for RunHead<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for RunHead<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.idx == other.idx
+    }
+}
+
+impl Eq for RunHead<'_> {}
+
+impl DataFrame {
+    /// Merge two DataFrames that are sorted in ascending order by the column `key` into a single
+    /// DataFrame that is sorted by `key`. This is a linear merge and cheaper than sorting the
+    /// concatenated DataFrames.
+    ///
+    /// Both DataFrames should have the same schema. Rows with equal keys keep their order, with
+    /// the rows of `self` before the rows of `other`. Null values are expected at the start of the
+    /// key columns, as in an ascending sort.
+    ///
+    /// An error is returned if a key column turns out not to be sorted.
+    pub fn merge_sorted(&self, other: &DataFrame, key: &str) -> Result<DataFrame> {
+        let left_schema = self.schema();
+        let right_schema = other.schema();
+        if left_schema != right_schema {
+            return Err(PolarsError::SchemaMisMatch {
+                expected: format!("{:?}", left_schema).into(),
+                found: format!("{:?}", right_schema).into(),
+            });
+        }
+        let left_key = self.column(key)?;
+        let right_key = other.column(key)?;
+
+        match left_key.dtype() {
+            DataType::List(_) | DataType::Categorical | DataType::Null => {
+                return Err(PolarsError::InvalidOperation(
+                    format!(
+                        "merge_sorted not supported for key of dtype {:?}",
+                        left_key.dtype()
+                    )
+                    .into(),
+                ))
+            }
+            #[cfg(feature = "object")]
+            DataType::Object(_) => {
+                return Err(PolarsError::InvalidOperation(
+                    "merge_sorted not supported for key of dtype Object".into(),
+                ))
+            }
+            _ => {}
+        }
+
+        macro_rules! merge {
+            ($ca:expr, $right:expr) => {{
+                let right = $ca.unpack_series_matching_type($right)?;
+                merge_take_idx($ca.into_iter(), right.into_iter(), $ca.len(), key)?
+            }};
+        }
+        let idx = match_arrow_data_type_apply_macro_ca!(left_key, merge, merge, merge, right_key);
+        let idx = IdxCa::new_from_aligned_vec("", idx);
+
+        let df = self.vstack(other)?;
+        Ok(df.take(&idx))
+    }
+    /// Merge DataFrames that are each sorted by the columns `by` (as with [DataFrame::sort]) into
+    /// a single sorted DataFrame. This is a k-way merge of the sorted runs, used to combine the
+    /// runs of a sort that doesn't fit in memory.
+    ///
+    /// The runs are not checked to be sorted. Rows that compare equal are taken in the order of
+    /// the runs.
+    pub fn merge_sorted_runs(
+        runs: &[DataFrame],
+        by: &[&str],
+        reverse: &[bool],
+    ) -> Result<DataFrame> {
+        if by.len() != reverse.len() {
+            return Err(PolarsError::ValueError(
+                format!(
+                    "The amount of ordering booleans: {} does not match that no. of Series: {}",
+                    reverse.len(),
+                    by.len()
+                )
+                .into(),
+            ));
+        }
+        let mut df = match runs.first() {
+            Some(df) => df.clone(),
+            None => return Err(PolarsError::NoData("no sorted runs to merge".into())),
+        };
+        let mut bounds = Vec::with_capacity(runs.len());
+        bounds.push((0, df.height()));
+        for run in &runs[1..] {
+            let start = df.height();
+            df.vstack_mut(run)?;
+            bounds.push((start, df.height()));
+        }
+
+        let keys = df.select_series(by)?;
+        let row_cmp = RowCmp::new(&keys, reverse);
+        // Safety:
+        // indices are in bounds of the concatenated runs
+        let cmp = |idx_a: usize, idx_b: usize| unsafe { row_cmp.cmp(idx_a, idx_b) };
+
+        let mut heap = bounds
+            .into_iter()
+            .filter(|(start, end)| start < end)
+            .map(|(start, end)| RunHead {
+                idx: start,
+                end,
+                cmp: &cmp,
+            })
+            .collect::<BinaryHeap<_>>();
+
+        let mut idx = AlignedVec::with_capacity_aligned(df.height());
+        while let Some(mut head) = heap.pop() {
+            idx.push(head.idx as IdxSize);
+            head.idx += 1;
+            if head.idx < head.end {
+                heap.push(head);
+            }
+        }
+        let idx = IdxCa::new_from_aligned_vec("", idx);
+
+        // Safety:
+        // the indices are in bounds
+        let mut out = unsafe { df.take_unchecked(&idx) };
+        out.apply(by[0], |s| {
+            let mut s = s.clone();
+            s.get_inner_mut().set_sorted(reverse[0]);
+            s
+        })?;
+        Ok(out)
+    }
+
+    /// Merge sorted runs like [DataFrame::merge_sorted_runs], of which the rows are produced in
+    /// consecutive batches, e.g. by reading them back from disk. Only the current batch of every
+    /// run is held in memory, next to the merged output.
+    ///
+    /// The runs are merged in steps: the batch that ends with the smallest row bounds a step, and
+    /// the rows of all current batches up to that row are merged. The output has a chunk per step.
+    pub fn merge_sorted_batched_runs<I>(
+        runs: Vec<I>,
+        by: &[&str],
+        reverse: &[bool],
+    ) -> Result<DataFrame>
+    where
+        I: Iterator<Item = Result<DataFrame>>,
+    {
+        let mut active = Vec::with_capacity(runs.len());
+        for run in runs {
+            if let Some(run) = BatchedRun::new(run)? {
+                active.push(run);
+            }
+        }
+        let mut out: Option<DataFrame> = None;
+        while !active.is_empty() {
+            // the last rows of the current batches, of which the smallest bounds this step
+            let mut last_rows = active[0].remaining.select(by)?.tail(Some(1));
+            for run in &active[1..] {
+                last_rows.vstack_mut(&run.remaining.select(by)?.tail(Some(1)))?;
+            }
+            let last_keys = last_rows.get_columns().to_vec();
+            let last_cmp = RowCmp::new(&last_keys, reverse);
+            let mut bound = 0;
+            for i in 1..active.len() {
+                // Safety:
+                // there is a last row for every active run
+                if unsafe { last_cmp.cmp(i, bound) } == Ordering::Less {
+                    bound = i;
+                }
+            }
+            let bound_row = last_rows.slice(bound as i64, 1);
+
+            let mut step = Vec::with_capacity(active.len());
+            for (i, run) in active.iter_mut().enumerate() {
+                let height = run.remaining.height();
+                let n_rows = if i == bound {
+                    height
+                } else {
+                    // the rows that are not greater than the bound row form a prefix of the batch
+                    let mut keys = run.remaining.select(by)?;
+                    keys.vstack_mut(&bound_row)?;
+                    let keys = keys.get_columns().to_vec();
+                    let cmp = RowCmp::new(&keys, reverse);
+                    let (mut low, mut high) = (0, height);
+                    while low < high {
+                        let mid = (low + high) / 2;
+                        // Safety:
+                        // `mid` is a row of the batch and `height` the appended bound row
+                        if unsafe { cmp.cmp(mid, height) } == Ordering::Greater {
+                            high = mid;
+                        } else {
+                            low = mid + 1;
+                        }
+                    }
+                    low
+                };
+                if n_rows > 0 {
+                    step.push(run.remaining.slice(0, n_rows));
+                    run.remaining = run.remaining.slice(n_rows as i64, height - n_rows);
+                }
+            }
+            // equal rows are taken in the order of the runs, which keeps the merge stable
+            let merged = Self::merge_sorted_runs(&step, by, reverse)?;
+            match &mut out {
+                Some(out) => {
+                    out.vstack_mut(&merged)?;
+                }
+                None => out = Some(merged),
+            }
+
+            let mut i = 0;
+            while i < active.len() {
+                if active[i].remaining.height() == 0 {
+                    match BatchedRun::next_batch(&mut active[i].batches)? {
+                        Some(batch) => active[i].remaining = batch,
+                        None => {
+                            // keep the order of the runs
+                            active.remove(i);
+                            continue;
+                        }
+                    }
+                }
+                i += 1;
+            }
+        }
+        let mut out = out.ok_or_else(|| PolarsError::NoData("no sorted runs to merge".into()))?;
+        out.apply(by[0], |s| {
+            let mut s = s.clone();
+            s.get_inner_mut().set_sorted(reverse[0]);
+            s
+        })?;
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::df;
+
+    #[test]
+    fn test_merge_sorted() -> Result<()> {
+        let left = df![
+            "ts" => [Some(1), Some(3), Some(3), Some(7)],
+            "src" => ["l", "l", "l", "l"],
+            "v" => [1, 2, 3, 4]
+        ]?;
+        let right = df![
+            "ts" => [None, Some(2), Some(3), Some(8)],
+            "src" => ["r", "r", "r", "r"],
+            "v" => [5, 6, 7, 8]
+        ]?;
+        let out = left.merge_sorted(&right, "ts")?;
+        assert_eq!(
+            Vec::from(out.column("ts")?.i32()?),
+            &[
+                None,
+                Some(1),
+                Some(2),
+                Some(3),
+                Some(3),
+                Some(3),
+                Some(7),
+                Some(8)
+            ]
+        );
+        // equal keys: left before right
+        assert_eq!(
+            Vec::from(out.column("v")?.i32()?),
+            &[
+                Some(5),
+                Some(1),
+                Some(6),
+                Some(2),
+                Some(3),
+                Some(7),
+                Some(4),
+                Some(8)
+            ]
+        );
+
+        let left = df![
+            "name" => ["a", "c"]
+        ]?;
+        let right = df![
+            "name" => ["b", "d"]
+        ]?;
+        let out = left.merge_sorted(&right, "name")?;
+        assert_eq!(
+            Vec::from(out.column("name")?.utf8()?),
+            &[Some("a"), Some("b"), Some("c"), Some("d")]
+        );
+
+        let unsorted = df![
+            "name" => ["e", "a"]
+        ]?;
+        assert!(left.merge_sorted(&unsorted, "name").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_sorted_runs() -> Result<()> {
+        let df = df![
+            "a" => [Some(3), None, Some(1), Some(2), Some(1), Some(3), None, Some(2)],
+            "b" => ["x", "y", "z", "x", "y", "z", "x", "y"],
+            "c" => [1, 2, 3, 4, 5, 6, 7, 8]
+        ]?;
+        for reverse in &[vec![false, false], vec![true, false], vec![false, true]] {
+            let runs = (0..4)
+                .map(|i| df.slice(i * 2, 2).sort(&["a", "b"], reverse.clone()))
+                .collect::<Result<Vec<_>>>()?;
+            let out = DataFrame::merge_sorted_runs(&runs, &["a", "b"], reverse)?;
+            let expected = df.sort(&["a", "b"], reverse.clone())?;
+            assert!(out.frame_equal_missing(&expected));
+        }
+        assert!(DataFrame::merge_sorted_runs(&[], &["a"], &[false]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_sorted_batched_runs() -> Result<()> {
+        let df = df![
+            "a" => [Some(3), None, Some(1), Some(2), Some(1), Some(3), None, Some(2), Some(5), Some(0), Some(1), Some(4)],
+            "c" => [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]
+        ]?;
+        for reverse in &[false, true] {
+            // three runs of four rows, read in batches of at most three rows
+            let runs = (0..3)
+                .map(|i| df.slice(i * 4, 4).sort("a", *reverse))
+                .collect::<Result<Vec<_>>>()?;
+            let batched = runs
+                .iter()
+                .map(|run| {
+                    vec![run.slice(0, 3), run.slice(3, 3), run.slice(4, 0)]
+                        .into_iter()
+                        .map(Ok)
+                })
+                .collect::<Vec<_>>();
+            let out = DataFrame::merge_sorted_batched_runs(batched, &["a"], &[*reverse])?;
+            let expected = DataFrame::merge_sorted_runs(&runs, &["a"], &[*reverse])?;
+            assert!(out.frame_equal_missing(&expected));
+        }
+        Ok(())
+    }
+}
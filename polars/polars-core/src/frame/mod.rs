@@ -1,6 +1,7 @@
 //! DataFrame module.
 use std::borrow::Cow;
-use std::collections::HashSet;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
 use std::iter::Iterator;
 use std::mem;
 use std::sync::Arc;
@@ -10,11 +11,14 @@ use arrow::record_batch::RecordBatch;
 use itertools::Itertools;
 use rayon::prelude::*;
 
+use crate::chunked_array::ops::dummies::unique_name;
 use crate::chunked_array::ops::unique::is_unique_helper;
+use crate::frame::name_index::NameIndex;
 use crate::frame::select::Selection;
 use crate::prelude::*;
 use crate::utils::{
-    accumulate_dataframes_horizontal, accumulate_dataframes_vertical, split_ca, split_df, NoNull,
+    accumulate_dataframes_vertical, common_chunk_lengths, ensure_unique_names, split_ca, split_df,
+    NoNull,
 };
 
 mod arithmetic;
@@ -25,6 +29,9 @@ pub(crate) mod cross_join;
 pub mod explode;
 pub mod groupby;
 pub mod hash_join;
+#[cfg(feature = "merge_sorted")]
+pub(crate) mod merge_sorted;
+mod name_index;
 #[cfg(feature = "rows")]
 pub mod row;
 pub mod select;
@@ -33,7 +40,7 @@ mod upstream_traits;
 #[cfg(feature = "sort_multiple")]
 use crate::prelude::sort::prepare_argsort;
 #[cfg(feature = "row_hash")]
-use crate::vector_hasher::df_rows_to_hashes_threaded;
+use crate::vector_hasher::df_rows_to_stable_hashes_threaded;
 use crate::POOL;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -42,27 +49,25 @@ use serde::{Deserialize, Serialize};
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct DataFrame {
     pub(crate) columns: Vec<Series>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    name_index: NameIndex,
 }
 
+/// [DataFrame::filter] gathers the selected rows by index if less than
+/// `1 / FILTER_BY_IDX_SELECTIVITY` of the rows are selected.
+const FILTER_BY_IDX_SELECTIVITY: usize = 32;
+/// Below this height the filter kernel is always used.
+const FILTER_BY_IDX_MIN_HEIGHT: usize = 1024;
+
 impl DataFrame {
     /// Get the index of the column.
     fn name_to_idx(&self, name: &str) -> Result<usize> {
-        let mut idx = 0;
-        for column in &self.columns {
-            if column.name() == name {
-                break;
-            }
-            idx += 1;
-        }
-        if idx == self.columns.len() {
-            Err(PolarsError::NotFound(name.into()))
-        } else {
-            Ok(idx)
-        }
+        self.find_idx_by_name(name)
+            .ok_or_else(|| PolarsError::ColumnNotFound(name.into()))
     }
 
     fn has_column(&self, name: &str) -> Result<()> {
-        if self.columns.iter().any(|s| s.name() == name) {
+        if self.find_idx_by_name(name).is_some() {
             Err(PolarsError::Duplicate(
                 format!("column with name: '{}' already present in DataFrame", name).into(),
             ))
@@ -116,9 +121,7 @@ impl DataFrame {
             names.insert(name);
             series_cols.push(series)
         }
-        let mut df = DataFrame {
-            columns: series_cols,
-        };
+        let mut df = DataFrame::new_no_checks(series_cols);
         df.rechunk();
         Ok(df)
     }
@@ -130,8 +133,41 @@ impl DataFrame {
     /// # Panic
     /// It is the callers responsibility to uphold the contract of all `Series`
     /// having an equal length, if not this may panic down the line.
+    ///
+    /// The names of the `Series` should be unique. In debug builds this panics on duplicate
+    /// names, so that operations that produce them are caught in tests.
     pub fn new_no_checks(columns: Vec<Series>) -> DataFrame {
-        DataFrame { columns }
+        #[cfg(debug_assertions)]
+        if let Some(name) = crate::utils::find_duplicate_name(&columns) {
+            panic!(
+                "DataFrame created with more than one column with name '{}'",
+                name
+            )
+        }
+        DataFrame {
+            columns,
+            name_index: NameIndex::default(),
+        }
+    }
+
+    /// Create an empty `DataFrame` with the columns and data types of the given `schema`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use polars_core::prelude::*;
+    /// let schema = Schema::new(vec![Field::new("a", DataType::Int32)]);
+    /// let df = DataFrame::new_empty(&schema);
+    /// assert_eq!(df.height(), 0);
+    /// assert_eq!(df.schema(), schema);
+    /// ```
+    pub fn new_empty(schema: &Schema) -> DataFrame {
+        let columns = schema
+            .fields()
+            .iter()
+            .map(|fld| Series::full_null(fld.name(), 0, fld.data_type()))
+            .collect();
+        DataFrame::new_no_checks(columns)
     }
 
     /// Aggregate all chunks to contiguous memory.
@@ -159,7 +195,31 @@ impl DataFrame {
         self
     }
 
-    /// Ensure all the chunks in the DataFrame are aligned.
+    /// Aggregate all the chunks in the DataFrame to a single chunk, rechunking the columns in
+    /// parallel.
+    ///
+    /// This is faster than [as_single_chunk](DataFrame::as_single_chunk) for wide frames, at the
+    /// cost of a higher peak memory, as multiple columns are copied at the same time.
+    pub fn as_single_chunk_par(&mut self) -> &mut Self {
+        if self.columns.iter().any(|s| s.n_chunks() > 1) {
+            self.columns = POOL.install(|| {
+                self.columns
+                    .par_iter()
+                    .map(|s| {
+                        if s.n_chunks() > 1 {
+                            s.rechunk()
+                        } else {
+                            s.clone()
+                        }
+                    })
+                    .collect()
+            });
+        }
+        self
+    }
+
+    /// Ensure all the chunks in the DataFrame are aligned. If they are not, every column is
+    /// rechunked to a single chunk, in parallel.
     pub fn rechunk(&mut self) -> &mut Self {
         // TODO: remove vec allocation
         if self
@@ -170,8 +230,30 @@ impl DataFrame {
         {
             self
         } else {
-            self.as_single_chunk()
+            self.as_single_chunk_par()
+        }
+    }
+
+    /// Align the chunks of all columns, such that every column has the same chunk lengths.
+    ///
+    /// Unlike [rechunk](DataFrame::rechunk) this doesn't copy any data. Chunks are split at the
+    /// chunk boundaries of the other columns by slicing the arrays.
+    pub fn align_chunks(&mut self) -> &mut Self {
+        let chunk_lengths = common_chunk_lengths(self.columns.iter().map(|s| s.chunk_lengths()));
+        for s in &mut self.columns {
+            *s = s.split_chunks(&chunk_lengths);
         }
+        self
+    }
+
+    /// Rechunk all columns into chunks of `target_chunk_size`, see
+    /// [Series::rechunk_to](crate::series::Series::rechunk_to). All columns get the same chunk
+    /// boundaries.
+    pub fn rechunk_to(&mut self, target_chunk_size: usize) -> &mut Self {
+        for s in &mut self.columns {
+            *s = s.rechunk_to(target_chunk_size);
+        }
+        self
     }
 
     /// Get the DataFrame schema.
@@ -210,6 +292,7 @@ impl DataFrame {
                 s
             })
             .collect();
+        self.name_index.invalidate();
         Ok(())
     }
 
@@ -273,6 +356,13 @@ impl DataFrame {
         self.columns.len()
     }
 
+    /// Estimate the number of bytes of the DataFrame as the sum of the
+    /// [estimated size](crate::series::Series::estimated_size) of the columns. Buffers that are
+    /// shared between columns are counted once for every column.
+    pub fn estimated_size(&self) -> usize {
+        self.columns.iter().map(|s| s.estimated_size()).sum()
+    }
+
     /// Get height of DataFrame
     ///
     /// # Example
@@ -296,6 +386,7 @@ impl DataFrame {
         for col in columns {
             self.columns.push(col.clone());
         }
+        self.name_index.invalidate();
         self.rechunk();
         self
     }
@@ -394,6 +485,7 @@ impl DataFrame {
     pub fn drop_in_place(&mut self, name: &str) -> Result<Series> {
         let idx = self.name_to_idx(name)?;
         let result = Ok(self.columns.remove(idx));
+        self.name_index.invalidate();
         self.rechunk();
         result
     }
@@ -438,8 +530,14 @@ impl DataFrame {
     }
 
     fn insert_at_idx_no_name_check(&mut self, index: usize, series: Series) -> Result<&mut Self> {
-        if series.len() == self.height() {
+        if index > self.width() {
+            Err(PolarsError::IndexOutOfBounds {
+                index,
+                len: self.width() + 1,
+            })
+        } else if series.len() == self.height() {
             self.columns.insert(index, series);
+            self.name_index.invalidate();
             self.rechunk();
             Ok(self)
         } else {
@@ -454,13 +552,154 @@ impl DataFrame {
         }
     }
 
-    /// Insert a new column at a given index
+    /// Insert a new column at a given index. The column will be at position `index` and the
+    /// columns after it shift one position to the right.
+    ///
+    /// Returns an error if the column's length differs from the height of the `DataFrame`, if a
+    /// column with the same name already exists or if `index` is larger than the width.
     pub fn insert_at_idx<S: IntoSeries>(&mut self, index: usize, column: S) -> Result<&mut Self> {
         let series = column.into_series();
         self.has_column(series.name())?;
         self.insert_at_idx_no_name_check(index, series)
     }
 
+    /// Add a `UInt32` column with the row index as first column. The index starts at `offset`,
+    /// or at 0 if no offset is given.
+    ///
+    /// Returns an error if a column with the same name already exists or if the index doesn't
+    /// fit in a `u32`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use polars_core::prelude::*;
+    /// fn example(df: &DataFrame) -> Result<DataFrame> {
+    ///     df.with_row_count("row_nr", Some(1))
+    /// }
+    /// ```
+    pub fn with_row_count(&self, name: &str, offset: Option<u32>) -> Result<Self> {
+        let offset = offset.unwrap_or(0);
+        let height = self.height();
+        if offset as u64 + height as u64 > u32::MAX as u64 + 1 {
+            return Err(PolarsError::InvalidOperation(
+                format!(
+                    "a row count starting at {} does not fit in a u32 for {} rows",
+                    offset, height
+                )
+                .into(),
+            ));
+        }
+        let ca: NoNull<UInt32Chunked> = (0..height as u32).map(|i| offset + i).collect();
+        let mut ca = ca.into_inner();
+        ca.rename(name);
+
+        let mut df = self.clone();
+        df.insert_at_idx(0, ca)?;
+        Ok(df)
+    }
+
+    /// Move the column `name` to position `new_index`. The other columns keep their relative
+    /// order. No data is copied.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use polars_core::prelude::*;
+    /// fn move_to_front(df: &mut DataFrame, name: &str) -> Result<()> {
+    ///     df.move_column(name, 0)?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn move_column(&mut self, name: &str, new_index: usize) -> Result<&mut Self> {
+        let idx = self.name_to_idx(name)?;
+        if new_index >= self.width() {
+            return Err(PolarsError::IndexOutOfBounds {
+                index: new_index,
+                len: self.width(),
+            });
+        }
+        let s = self.columns.remove(idx);
+        self.columns.insert(new_index, s);
+        self.name_index.invalidate();
+        Ok(self)
+    }
+
+    /// Reorder the columns to the given order. `order` should contain every column name of the
+    /// `DataFrame` exactly once. No data is copied.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use polars_core::prelude::*;
+    /// fn example() -> Result<DataFrame> {
+    ///     let mut df = df!["a" => [1], "b" => [2], "c" => [3]]?;
+    ///     df.reorder_columns(&["c", "a", "b"])?;
+    ///     Ok(df)
+    /// }
+    /// ```
+    pub fn reorder_columns(&mut self, order: &[&str]) -> Result<&mut Self> {
+        let missing = self
+            .columns
+            .iter()
+            .map(|s| s.name())
+            .filter(|name| !order.contains(name))
+            .collect::<Vec<_>>();
+        let not_found = order
+            .iter()
+            .filter(|name| self.find_idx_by_name(name).is_none())
+            .collect::<Vec<_>>();
+        if !missing.is_empty() || !not_found.is_empty() {
+            return Err(PolarsError::ValueError(
+                format!(
+                    "reorder_columns expects every column name exactly once, missing: {:?}, not found: {:?}",
+                    missing, not_found
+                )
+                .into(),
+            ));
+        }
+        if order.len() != self.width() {
+            return Err(PolarsError::Duplicate(
+                format!("duplicate column names in {:?}", order).into(),
+            ));
+        }
+        let mut columns = std::mem::take(&mut self.columns)
+            .into_iter()
+            .map(Some)
+            .collect::<Vec<_>>();
+        self.columns = order
+            .iter()
+            .map(|name| {
+                let idx = columns
+                    .iter()
+                    .position(|opt_s| matches!(opt_s, Some(s) if s.name() == *name))
+                    .unwrap();
+                columns[idx].take().unwrap()
+            })
+            .collect();
+        self.name_index.invalidate();
+        Ok(self)
+    }
+
+    /// Sort the columns with a comparator function of the column names. The sort is stable. No
+    /// data is copied.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use polars_core::prelude::*;
+    /// fn sort_alphabetically(df: &mut DataFrame) {
+    ///     df.sort_columns_by(|a, b| a.cmp(b));
+    /// }
+    /// ```
+    pub fn sort_columns_by<F>(&mut self, mut compare: F) -> &mut Self
+    where
+        F: FnMut(&str, &str) -> std::cmp::Ordering,
+    {
+        self.columns.sort_by(|a, b| compare(a.name(), b.name()));
+        self.name_index.invalidate();
+        self
+    }
+
     /// Add a new column to this `DataFrame` or replace an existing one.
     pub fn with_column<S: IntoSeries>(&mut self, column: S) -> Result<&mut Self> {
         let series = column.into_series();
@@ -470,6 +709,7 @@ impl DataFrame {
                 self.apply(&name, |_| series)?;
             } else {
                 self.columns.push(series);
+                self.name_index.invalidate();
                 self.rechunk();
             }
             Ok(self)
@@ -522,19 +762,28 @@ impl DataFrame {
 
     /// Get column index of a series by name.
     pub fn find_idx_by_name(&self, name: &str) -> Option<usize> {
+        self.name_index.find(&self.columns, name)
+    }
+
+    /// Get the indices of all the columns with name `name`.
+    ///
+    /// A `DataFrame` has unique column names, but data that was created before this was enforced
+    /// may not. All the methods that take a column name operate on the first column with that
+    /// name, this method can be used to handle the other occurrences explicitly.
+    pub fn get_column_index_all(&self, name: &str) -> Vec<usize> {
         self.columns
             .iter()
             .enumerate()
-            .filter(|(_idx, series)| series.name() == name)
+            .filter(|(_, s)| s.name() == name)
             .map(|(idx, _)| idx)
-            .next()
+            .collect()
     }
 
     /// Select a single column by name.
     pub fn column(&self, name: &str) -> Result<&Series> {
         let idx = self
             .find_idx_by_name(name)
-            .ok_or_else(|| PolarsError::NotFound(name.into()))?;
+            .ok_or_else(|| PolarsError::ColumnNotFound(name.into()))?;
         Ok(self.select_at_idx(idx).unwrap())
     }
 
@@ -571,6 +820,7 @@ impl DataFrame {
         S: Selection<'a, J>,
     {
         let selected = self.select_series(selection)?;
+        ensure_unique_names(&selected, "select")?;
         let df = DataFrame::new_no_checks(selected);
         Ok(df)
     }
@@ -647,6 +897,15 @@ impl DataFrame {
             return self.filter_vertical(mask);
         }
 
+        // if only a few rows are selected, gathering them by index is cheaper than running the
+        // filter kernel on every column
+        if mask.len() == self.height()
+            && self.height() >= FILTER_BY_IDX_MIN_HEIGHT
+            && mask.count_true() * FILTER_BY_IDX_SELECTIVITY < self.height()
+        {
+            return Ok(self.take(&mask.arg_true()));
+        }
+
         let new_col = POOL.install(|| {
             self.columns
                 .par_iter()
@@ -699,7 +958,7 @@ impl DataFrame {
         I: Iterator<Item = usize> + Clone + Sync,
     {
         if std::env::var("POLARS_VERT_PAR").is_ok() {
-            let idx_ca: NoNull<UInt32Chunked> = iter.into_iter().map(|idx| idx as u32).collect();
+            let idx_ca: NoNull<IdxCa> = iter.into_iter().map(|idx| idx as IdxSize).collect();
             return self.take_unchecked_vectical(&idx_ca.into_inner());
         }
 
@@ -713,7 +972,7 @@ impl DataFrame {
             .any(|s| matches!(s.dtype(), DataType::Utf8));
 
         if n_chunks == 1 || has_utf8 {
-            let idx_ca: NoNull<UInt32Chunked> = iter.into_iter().map(|idx| idx as u32).collect();
+            let idx_ca: NoNull<IdxCa> = iter.into_iter().map(|idx| idx as IdxSize).collect();
             let idx_ca = idx_ca.into_inner();
             return self.take_unchecked(&idx_ca);
         }
@@ -740,7 +999,10 @@ impl DataFrame {
         I: Iterator<Item = Option<usize>> + Clone + Sync,
     {
         if std::env::var("POLARS_VERT_PAR").is_ok() {
-            let idx_ca: UInt32Chunked = iter.into_iter().map(|opt| opt.map(|v| v as u32)).collect();
+            let idx_ca: IdxCa = iter
+                .into_iter()
+                .map(|opt| opt.map(|v| v as IdxSize))
+                .collect();
             return self.take_unchecked_vectical(&idx_ca);
         }
 
@@ -755,7 +1017,10 @@ impl DataFrame {
             .any(|s| matches!(s.dtype(), DataType::Utf8));
 
         if n_chunks == 1 || has_utf8 {
-            let idx_ca: UInt32Chunked = iter.into_iter().map(|opt| opt.map(|v| v as u32)).collect();
+            let idx_ca: IdxCa = iter
+                .into_iter()
+                .map(|opt| opt.map(|v| v as IdxSize))
+                .collect();
             return self.take_unchecked(&idx_ca);
         }
 
@@ -777,14 +1042,14 @@ impl DataFrame {
     /// ```
     /// use polars_core::prelude::*;
     /// fn example(df: &DataFrame) -> DataFrame {
-    ///     let idx = UInt32Chunked::new_from_slice("idx", &[0, 1, 9]);
+    ///     let idx = IdxCa::new_from_slice("idx", &[0, 1, 9]);
     ///     df.take(&idx)
     /// }
     /// ```
     /// # Safety
     ///
     /// Out of bounds access doesn't Error but will return a Null value
-    pub fn take(&self, indices: &UInt32Chunked) -> Self {
+    pub fn take(&self, indices: &IdxCa) -> Self {
         let indices = if indices.chunks.len() > 1 {
             Cow::Owned(indices.rechunk())
         } else {
@@ -803,7 +1068,7 @@ impl DataFrame {
         DataFrame::new_no_checks(new_col)
     }
 
-    pub(crate) unsafe fn take_unchecked(&self, idx: &UInt32Chunked) -> Self {
+    pub(crate) unsafe fn take_unchecked(&self, idx: &IdxCa) -> Self {
         let cols = POOL.install(|| {
             self.columns
                 .par_iter()
@@ -816,7 +1081,7 @@ impl DataFrame {
         DataFrame::new_no_checks(cols)
     }
 
-    unsafe fn take_unchecked_vectical(&self, indices: &UInt32Chunked) -> Self {
+    unsafe fn take_unchecked_vectical(&self, indices: &IdxCa) -> Self {
         let n_threads = POOL.current_num_threads();
         let idxs = split_ca(indices, n_threads).unwrap();
 
@@ -855,8 +1120,9 @@ impl DataFrame {
     /// ```
     pub fn rename(&mut self, column: &str, name: &str) -> Result<&mut Self> {
         self.select_mut(column)
-            .ok_or_else(|| PolarsError::NotFound(name.into()))
+            .ok_or_else(|| PolarsError::ColumnNotFound(column.into()))
             .map(|s| s.rename(name))?;
+        self.name_index.invalidate();
         Ok(self)
     }
 
@@ -945,6 +1211,62 @@ impl DataFrame {
         self.with_column(new_col)
     }
 
+    /// Add or replace multiple columns at once. This has the same result as calling
+    /// [DataFrame::with_column] for every `Series`, but the names are resolved in a single pass
+    /// and the new columns are appended at once, which is much faster for wide DataFrames.
+    ///
+    /// If a name occurs more than once in `columns`, the last `Series` wins.
+    pub fn add_or_replace_columns(&mut self, columns: Vec<Series>) -> Result<&mut Self> {
+        let height = match (self.columns.first(), columns.first()) {
+            (Some(s), _) | (None, Some(s)) => s.len(),
+            (None, None) => return Ok(self),
+        };
+        let mut new_columns: Vec<Series> = vec![];
+        let mut new_idx = HashMap::with_hasher(RandomState::default());
+
+        for s in columns {
+            if let Some(idx) = self.find_idx_by_name(s.name()) {
+                let s = match s.len() {
+                    1 if height != 1 => s.expand_at_index(0, height),
+                    len if len == height => s,
+                    len => {
+                        return Err(PolarsError::ShapeMisMatch(
+                            format!(
+                                "Result Series has shape {} where the DataFrame has height {}",
+                                len, height
+                            )
+                            .into(),
+                        ))
+                    }
+                };
+                self.columns[idx] = s;
+            } else if s.len() != height {
+                return Err(PolarsError::ShapeMisMatch(
+                    format!(
+                        "Could add column. The Series length {} differs from the DataFrame height: {}",
+                        s.len(),
+                        height
+                    )
+                    .into(),
+                ));
+            } else {
+                match new_idx.entry(s.name().to_string()) {
+                    Entry::Occupied(entry) => new_columns[*entry.get()] = s,
+                    Entry::Vacant(entry) => {
+                        entry.insert(new_columns.len());
+                        new_columns.push(s);
+                    }
+                }
+            }
+        }
+        if !new_columns.is_empty() {
+            self.columns.extend(new_columns);
+            self.name_index.invalidate();
+        }
+        self.rechunk();
+        Ok(self)
+    }
+
     /// Replace column at index `idx` with a series.
     ///
     /// # Example
@@ -967,17 +1289,14 @@ impl DataFrame {
                 ).into()));
         };
         if idx >= self.width() {
-            return Err(PolarsError::OutOfBounds(
-                format!(
-                    "Column index: {} outside of DataFrame with {} columns",
-                    idx,
-                    self.width()
-                )
-                .into(),
-            ));
+            return Err(PolarsError::IndexOutOfBounds {
+                index: idx,
+                len: self.width(),
+            });
         }
         let old_col = &mut self.columns[idx];
         mem::swap(old_col, &mut new_column);
+        self.name_index.invalidate();
         Ok(self)
     }
 
@@ -1027,7 +1346,7 @@ impl DataFrame {
     {
         let idx = self
             .find_idx_by_name(column)
-            .ok_or_else(|| PolarsError::NotFound(column.to_string()))?;
+            .ok_or_else(|| PolarsError::ColumnNotFound(column.to_string()))?;
         self.apply_at_idx(idx, f)
     }
 
@@ -1067,15 +1386,13 @@ impl DataFrame {
     {
         let df_height = self.height();
         let width = self.width();
-        let col = self.columns.get_mut(idx).ok_or_else(|| {
-            PolarsError::OutOfBounds(
-                format!(
-                    "Column index: {} outside of DataFrame with {} columns",
-                    idx, width
-                )
-                .into(),
-            )
-        })?;
+        let col = self
+            .columns
+            .get_mut(idx)
+            .ok_or(PolarsError::IndexOutOfBounds {
+                index: idx,
+                len: width,
+            })?;
         let name = col.name().to_string();
         let new_col = f(col).into_series();
         match new_col.len() {
@@ -1152,15 +1469,13 @@ impl DataFrame {
         S: IntoSeries,
     {
         let width = self.width();
-        let col = self.columns.get_mut(idx).ok_or_else(|| {
-            PolarsError::OutOfBounds(
-                format!(
-                    "Column index: {} outside of DataFrame with {} columns",
-                    idx, width
-                )
-                .into(),
-            )
-        })?;
+        let col = self
+            .columns
+            .get_mut(idx)
+            .ok_or(PolarsError::IndexOutOfBounds {
+                index: idx,
+                len: width,
+            })?;
         let name = col.name().to_string();
 
         let _ = mem::replace(col, f(col).map(|s| s.into_series())?);
@@ -1222,7 +1537,7 @@ impl DataFrame {
     {
         let idx = self
             .find_idx_by_name(column)
-            .ok_or_else(|| PolarsError::NotFound(column.to_string()))?;
+            .ok_or_else(|| PolarsError::ColumnNotFound(column.to_string()))?;
         self.may_apply_at_idx(idx, f)
     }
 
@@ -1501,7 +1816,9 @@ impl DataFrame {
         f(self, args)
     }
 
-    /// Create dummy variables.
+    /// Create dummy variables. The `columns` are replaced by an indicator column per distinct
+    /// value, named `{column}_{value}`. If `columns` is `None` all Utf8 and Categorical columns
+    /// are encoded. The other columns are passed through.
     ///
     /// # Example
     ///
@@ -1518,42 +1835,92 @@ impl DataFrame {
     ///       "code" => &["X1", "X2", "X3", "X3", "X2", "X2", "X1", "X1"]
     ///   }.unwrap();
     ///
-    ///   let dummies = df.to_dummies().unwrap();
+    ///   let dummies = df.to_dummies(None).unwrap();
     ///   dbg!(dummies);
     /// # }
     /// ```
     /// Outputs:
     /// ```text
-    ///  +------+------+------+--------+--------+--------+---------+---------+---------+
-    ///  | id_1 | id_3 | id_2 | type_A | type_B | type_C | code_X1 | code_X2 | code_X3 |
-    ///  | ---  | ---  | ---  | ---    | ---    | ---    | ---     | ---     | ---     |
-    ///  | u8   | u8   | u8   | u8     | u8     | u8     | u8      | u8      | u8      |
-    ///  +======+======+======+========+========+========+=========+=========+=========+
-    ///  | 1    | 0    | 0    | 1      | 0      | 0      | 1       | 0       | 0       |
-    ///  +------+------+------+--------+--------+--------+---------+---------+---------+
-    ///  | 0    | 0    | 1    | 0      | 1      | 0      | 0       | 1       | 0       |
-    ///  +------+------+------+--------+--------+--------+---------+---------+---------+
-    ///  | 0    | 1    | 0    | 0      | 1      | 0      | 0       | 0       | 1       |
-    ///  +------+------+------+--------+--------+--------+---------+---------+---------+
-    ///  | 1    | 0    | 0    | 0      | 1      | 0      | 0       | 0       | 1       |
-    ///  +------+------+------+--------+--------+--------+---------+---------+---------+
-    ///  | 0    | 0    | 1    | 0      | 0      | 1      | 0       | 1       | 0       |
-    ///  +------+------+------+--------+--------+--------+---------+---------+---------+
-    ///  | 0    | 1    | 0    | 0      | 0      | 1      | 0       | 1       | 0       |
-    ///  +------+------+------+--------+--------+--------+---------+---------+---------+
-    ///  | 1    | 0    | 0    | 0      | 0      | 1      | 1       | 0       | 0       |
-    ///  +------+------+------+--------+--------+--------+---------+---------+---------+
-    ///  | 1    | 0    | 0    | 0      | 1      | 0      | 1       | 0       | 0       |
-    ///  +------+------+------+--------+--------+--------+---------+---------+---------+
+    ///  +-----+--------+--------+--------+---------+---------+---------+
+    ///  | id  | type_A | type_B | type_C | code_X1 | code_X2 | code_X3 |
+    ///  | --- | ---    | ---    | ---    | ---     | ---     | ---     |
+    ///  | i32 | u8     | u8     | u8     | u8      | u8      | u8      |
+    ///  +=====+========+========+========+=========+=========+=========+
+    ///  | 1   | 1      | 0      | 0      | 1       | 0       | 0       |
+    ///  +-----+--------+--------+--------+---------+---------+---------+
+    ///  | 2   | 0      | 1      | 0      | 0       | 1       | 0       |
+    ///  +-----+--------+--------+--------+---------+---------+---------+
+    ///  | 3   | 0      | 1      | 0      | 0       | 0       | 1       |
+    ///  +-----+--------+--------+--------+---------+---------+---------+
+    ///  | 1   | 0      | 1      | 0      | 0       | 0       | 1       |
+    ///  +-----+--------+--------+--------+---------+---------+---------+
+    ///  | 2   | 0      | 0      | 1      | 0       | 1       | 0       |
+    ///  +-----+--------+--------+--------+---------+---------+---------+
+    ///  | 3   | 0      | 0      | 1      | 0       | 1       | 0       |
+    ///  +-----+--------+--------+--------+---------+---------+---------+
+    ///  | 1   | 0      | 0      | 1      | 1       | 0       | 0       |
+    ///  +-----+--------+--------+--------+---------+---------+---------+
+    ///  | 1   | 0      | 1      | 0      | 1       | 0       | 0       |
+    ///  +-----+--------+--------+--------+---------+---------+---------+
     /// ```
-    pub fn to_dummies(&self) -> Result<Self> {
-        let cols = self
+    pub fn to_dummies(&self, columns: Option<&[&str]>) -> Result<Self> {
+        self.to_dummies_with_options(columns, &DummiesOptions::default())
+    }
+
+    /// Create dummy variables, see [to_dummies](Self::to_dummies). Indicator columns that clash
+    /// with another column name get a `_{n}` suffix.
+    pub fn to_dummies_with_options(
+        &self,
+        columns: Option<&[&str]>,
+        options: &DummiesOptions,
+    ) -> Result<Self> {
+        let selected = match columns {
+            Some(names) => {
+                for name in names {
+                    self.name_to_idx(name)?;
+                }
+                self.columns
+                    .iter()
+                    .map(|s| names.contains(&s.name()))
+                    .collect::<Vec<_>>()
+            }
+            None => self
+                .columns
+                .iter()
+                .map(|s| matches!(s.dtype(), DataType::Utf8 | DataType::Categorical))
+                .collect(),
+        };
+        let dummies = self
             .columns
             .par_iter()
-            .map(|s| s.to_dummies())
+            .zip(selected.par_iter())
+            .map(|(s, selected)| {
+                if *selected {
+                    s.to_dummies_with_options(options).map(|df| df.columns)
+                } else {
+                    Ok(vec![s.clone()])
+                }
+            })
             .collect::<Result<Vec<_>>>()?;
 
-        accumulate_dataframes_horizontal(cols)
+        let mut used = self
+            .columns
+            .iter()
+            .zip(&selected)
+            .filter(|(_, selected)| !**selected)
+            .map(|(s, _)| s.name().to_string())
+            .collect::<PlHashSet<_>>();
+        let mut out = Vec::with_capacity(dummies.iter().map(|cols| cols.len()).sum());
+        for (columns, selected) in dummies.into_iter().zip(selected) {
+            for mut s in columns {
+                if selected {
+                    let name = unique_name(s.name(), &mut used);
+                    s.rename(&name);
+                }
+                out.push(s)
+            }
+        }
+        Ok(DataFrame::new_no_checks(out))
     }
 
     /// Drop duplicate rows from a DataFrame.
@@ -1615,14 +1982,24 @@ impl DataFrame {
     pub fn is_unique(&self) -> Result<BooleanChunked> {
         let mut gb = self.groupby(self.get_column_names())?;
         let groups = std::mem::take(&mut gb.groups);
-        Ok(is_unique_helper(groups, self.height() as u32, true, false))
+        Ok(is_unique_helper(
+            groups,
+            self.height() as IdxSize,
+            true,
+            false,
+        ))
     }
 
     /// Get a mask of all the duplicated rows in the DataFrame.
     pub fn is_duplicated(&self) -> Result<BooleanChunked> {
         let mut gb = self.groupby(self.get_column_names())?;
         let groups = std::mem::take(&mut gb.groups);
-        Ok(is_unique_helper(groups, self.height() as u32, false, true))
+        Ok(is_unique_helper(
+            groups,
+            self.height() as IdxSize,
+            false,
+            true,
+        ))
     }
 
     /// Create a new DataFrame that shows the null counts per column.
@@ -1635,11 +2012,31 @@ impl DataFrame {
         Self::new_no_checks(cols)
     }
 
-    /// Hash and combine the row values
+    /// Hash and combine the row values.
+    ///
+    /// The hashes are stable: the same data and `seed` result in the same hashes on every
+    /// platform and polars version, which makes them suitable for partitioning data that is
+    /// stored or shared between processes. Every value is hashed with XXH64, nulls have a fixed
+    /// hash and the hashes of the columns are combined from left to right, so the order of the
+    /// columns matters. Integers of different widths, e.g. `1i32` and `1i64`, have the same hash.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use polars_core::prelude::*;
+    /// let df = df!("a" => &[1, 2], "b" => &["foo", "bar"]).unwrap();
+    /// let hashes = df.hash_rows(0).unwrap();
+    /// assert_eq!(hashes.len(), 2);
+    /// ```
     #[cfg(feature = "row_hash")]
-    pub fn hash_rows(&self) -> Result<UInt64Chunked> {
+    pub fn hash_rows(&self, seed: u64) -> Result<UInt64Chunked> {
+        if self.width() == 0 {
+            return Err(PolarsError::NoData(
+                "cannot hash the rows of an empty DataFrame".into(),
+            ));
+        }
         let dfs = split_df(self, POOL.current_num_threads())?;
-        let (cas, _) = df_rows_to_hashes_threaded(&dfs, None);
+        let cas = df_rows_to_stable_hashes_threaded(&dfs, seed)?;
 
         let mut iter = cas.into_iter();
         let mut acc_ca = iter.next().unwrap();
@@ -1648,6 +2045,32 @@ impl DataFrame {
         }
         Ok(acc_ca.rechunk())
     }
+
+    /// Concatenate the string representation of the values in `columns` row-wise, separated by
+    /// `delimiter`. See [concat_str](crate::functions::concat_str) for the handling of nulls.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use polars_core::prelude::*;
+    /// let df = df!("a" => &["foo", "bar"], "b" => &[1, 2]).unwrap();
+    /// let ca = df.concat_str(&["a", "b"], "-", false).unwrap();
+    /// assert_eq!(Vec::from(&ca), &[Some("foo-1"), Some("bar-2")]);
+    /// ```
+    #[cfg(feature = "concat_str")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "concat_str")))]
+    pub fn concat_str<'a, S, J>(
+        &self,
+        columns: S,
+        delimiter: &str,
+        null_as_empty: bool,
+    ) -> Result<Utf8Chunked>
+    where
+        S: Selection<'a, J>,
+    {
+        let columns = self.select_series(columns)?;
+        crate::functions::concat_str(&columns, delimiter, null_as_empty)
+    }
 }
 
 pub struct RecordBatchIter<'a> {
@@ -1865,6 +2288,30 @@ mod test {
         println!("{:?}", df);
     }
 
+    #[test]
+    fn test_filter_low_selectivity() -> Result<()> {
+        let n = 2000;
+        let df = df![
+            "a" => (0..n).collect::<Vec<i32>>(),
+            "b" => (0..n).map(|i| i.to_string()).collect::<Vec<_>>()
+        ]?;
+        let mask = (0..n)
+            .map(|i| match i % 100 {
+                0 => None,
+                7 => Some(true),
+                _ => Some(false),
+            })
+            .collect::<BooleanChunked>();
+
+        let out = df.filter(&mask)?;
+        let expected = (0..n).filter(|i| i % 100 == 7).collect::<Vec<i32>>();
+        assert_eq!(out.column("a")?.i32()?.cont_slice()?, expected.as_slice());
+        assert!(out
+            .column("b")?
+            .series_equal(&df.column("b")?.filter(&mask)?));
+        Ok(())
+    }
+
     #[test]
     fn slice() {
         let df = create_frame();
@@ -1883,8 +2330,12 @@ mod test {
             "code" => &["X1", "X2", "X3", "X3", "X2", "X2", "X1", "X1"]
         }
         .unwrap();
-        let dummies = df.to_dummies().unwrap();
+        let dummies = df.to_dummies(Some(&["id", "code"])).unwrap();
         dbg!(&dummies);
+        assert_eq!(
+            dummies.get_column_names(),
+            &["id_1", "id_2", "id_3", "type", "code_X1", "code_X2", "code_X3"]
+        );
         assert_eq!(
             Vec::from(dummies.column("id_1").unwrap().u8().unwrap()),
             &[
@@ -1899,6 +2350,17 @@ mod test {
             ]
         );
         dbg!(dummies);
+
+        // by default the Utf8 columns are encoded, clashing names get a suffix
+        let mut df = df;
+        df.with_column(Series::new("type_A", &[0; 8])).unwrap();
+        let dummies = df.to_dummies(None).unwrap();
+        assert_eq!(
+            dummies.get_column_names(),
+            &["id", "type_A_1", "type_B", "type_C", "code_X1", "code_X2", "code_X3", "type_A"]
+        );
+        assert_eq!(dummies.column("id").unwrap().dtype(), &DataType::Int32);
+        assert!(df.to_dummies(Some(&["foo"])).is_err());
     }
 
     #[test]
@@ -1913,6 +2375,47 @@ mod test {
         assert!(df.column("bar").is_ok())
     }
 
+    #[test]
+    fn test_duplicate_column_names_error() -> Result<()> {
+        let df = df! {
+            "foo" => &[1, 2, 3],
+            "bar" => &[1, 2, 3]
+        }?;
+        let foo = Series::new("foo", &[4, 5, 6]);
+        assert!(matches!(
+            DataFrame::new(vec![foo.clone(), foo.clone()]),
+            Err(PolarsError::Duplicate(_))
+        ));
+        assert!(df.hstack(&[foo.clone()]).is_err());
+        assert!(df.clone().hstack_mut(&[foo]).is_err());
+        assert!(matches!(
+            df.select(&["foo", "bar", "foo"]),
+            Err(PolarsError::Duplicate(_))
+        ));
+        assert_eq!(df.get_column_index_all("bar"), &[1]);
+        assert!(df.get_column_index_all("ham").is_empty());
+
+        // the suffixed name of "bar" collides with a column of the left frame
+        let left = df! {
+            "foo" => &[1, 2, 3],
+            "bar" => &[1, 2, 3],
+            "bar_right" => &[1, 2, 3]
+        }?;
+        match left.inner_join(&df, "foo", "foo") {
+            Err(PolarsError::Duplicate(msg)) => assert!(msg.contains("'bar_right'")),
+            _ => panic!("expected a duplicate error"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn test_new_no_checks_duplicate_names() {
+        let s = Series::new("foo", &[1, 2, 3]);
+        DataFrame::new_no_checks(vec![s.clone(), s]);
+    }
+
     #[test]
     #[cfg_attr(miri, ignore)]
     fn drop_duplicates() {
@@ -1991,4 +2494,253 @@ mod test {
         assert_eq!(df.get_column_names(), &["a", "b", "c"]);
         Ok(())
     }
+
+    #[test]
+    fn test_wide_frame_name_lookup() -> Result<()> {
+        let columns = (0..100)
+            .map(|i| Series::new(&format!("c{}", i), [i, i]))
+            .collect();
+        let mut df = DataFrame::new(columns)?;
+        assert_eq!(df.find_idx_by_name("c42"), Some(42));
+        assert!(df.column("c100").is_err());
+
+        df.rename("c42", "renamed")?;
+        assert_eq!(df.find_idx_by_name("c42"), None);
+        assert_eq!(df.find_idx_by_name("renamed"), Some(42));
+
+        df.drop_in_place("c0")?;
+        assert_eq!(df.find_idx_by_name("renamed"), Some(41));
+        df.insert_at_idx(0, Series::new("first", [0, 0]))?;
+        assert_eq!(df.find_idx_by_name("c99"), Some(99));
+        assert_eq!(df.column("first")?.name(), "first");
+
+        let names = (0..100).map(|i| format!("n{}", i)).collect::<Vec<_>>();
+        df.set_column_names(&names)?;
+        assert_eq!(df.find_idx_by_name("c99"), None);
+        assert_eq!(df.find_idx_by_name("n99"), Some(99));
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_or_replace_columns() -> Result<()> {
+        let mut df = df!(
+            "a" => [1, 2, 3],
+            "b" => [1, 2, 3]
+        )?;
+        df.add_or_replace_columns(vec![
+            Series::new("c", [1, 1, 1]),
+            Series::new("b", [2, 2, 2]),
+            Series::new("d", [1, 1, 1]),
+            Series::new("c", [3, 3, 3]),
+            Series::new("a", [5]),
+        ])?;
+        assert_eq!(df.get_column_names(), &["a", "b", "c", "d"]);
+        assert_eq!(Vec::from(df.column("a")?.i32()?), &[Some(5); 3]);
+        assert_eq!(Vec::from(df.column("b")?.i32()?), &[Some(2); 3]);
+        assert_eq!(Vec::from(df.column("c")?.i32()?), &[Some(3); 3]);
+
+        assert!(df
+            .add_or_replace_columns(vec![Series::new("e", [1, 2])])
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "row_hash")]
+    fn test_hash_rows_stable() -> Result<()> {
+        let df = df![
+            "int" => [Some(1i32), Some(-2), None],
+            "str" => [Some("a"), None, Some("polars")],
+            "float" => [0.5, -0.0, f64::NAN],
+            "bool" => [Some(true), Some(false), None]
+        ]?;
+
+        // these values must never change
+        let hashes = df.hash_rows(0)?;
+        assert_eq!(
+            Vec::from(&hashes),
+            &[
+                Some(13874904178031958524),
+                Some(3442403442662294645),
+                Some(16329103365059864720)
+            ]
+        );
+        let hashes = df.hash_rows(42)?;
+        assert_eq!(
+            Vec::from(&hashes),
+            &[
+                Some(4478388478543878575),
+                Some(12580246190613990189),
+                Some(10714537883326781442)
+            ]
+        );
+        let hashes = df.column("int")?.stable_hash(0)?;
+        assert_eq!(
+            Vec::from(&hashes),
+            &[
+                Some(11468921228449061269),
+                Some(13453299083773270399),
+                Some(0x9E37_79B9_7F4A_7C15)
+            ]
+        );
+
+        // independent of the integer width and of the number of chunks
+        let mut wide = df.column("int")?.cast::<Int64Type>()?;
+        assert_eq!(Vec::from(&wide.stable_hash(0)?), Vec::from(&hashes));
+        wide.append(&Series::new("int", &[1i64]))?;
+        let combined = wide.stable_hash(0)?;
+        assert_eq!(combined.get(3), hashes.get(0));
+
+        // -0.0 == 0.0
+        let floats = Series::new("", &[0.0f64, -0.0]).stable_hash(0)?;
+        assert_eq!(floats.get(0), floats.get(1));
+
+        // the combination is order dependent
+        let swapped = df.select(&["str", "int", "float", "bool"])?.hash_rows(0)?;
+        assert_ne!(swapped.get(0), df.hash_rows(0)?.get(0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_align_chunks() {
+        let mut a = Series::new("a", &[1, 2, 3]);
+        a.append(&Series::new("a", &[4])).unwrap();
+        let mut b = Series::new("b", &["a"]);
+        b.append(&Series::new("b", &["b", "c", "d"])).unwrap();
+        let c = Series::new("c", &[1.0, 2.0, 3.0, 4.0]);
+        let mut df = DataFrame::new(vec![a, b, c]).unwrap();
+
+        df.align_chunks();
+        for s in df.get_columns() {
+            assert_eq!(s.chunk_lengths().collect::<Vec<_>>(), &[1, 2, 1]);
+        }
+        assert_eq!(
+            Vec::from(df.column("b").unwrap().utf8().unwrap()),
+            &[Some("a"), Some("b"), Some("c"), Some("d")]
+        );
+    }
+
+    #[test]
+    fn test_rechunk_to() {
+        let mut a = Series::new("a", &[1, 2, 3]);
+        a.append(&Series::new("a", &[4, 5])).unwrap();
+        let mut b = Series::new("b", &["a"]);
+        b.append(&Series::new("b", &["b", "c", "d", "e"])).unwrap();
+        let c = Series::new("c", &[1.0, 2.0, 3.0, 4.0, 5.0]);
+        let mut df = DataFrame::new(vec![a, b, c]).unwrap();
+
+        df.rechunk_to(2);
+        for s in df.get_columns() {
+            assert_eq!(s.chunk_lengths().collect::<Vec<_>>(), &[2, 2, 1]);
+        }
+        assert_eq!(
+            Vec::from(df.column("b").unwrap().utf8().unwrap()),
+            &[Some("a"), Some("b"), Some("c"), Some("d"), Some("e")]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "private")]
+    fn test_split_df_at_chunk_boundaries() {
+        use crate::utils::split_df;
+        let heights = |dfs: &[DataFrame]| dfs.iter().map(|df| df.height()).collect::<Vec<_>>();
+
+        let mut df = df! {
+            "a" => (0..100).collect::<Vec<i32>>(),
+            "b" => (0..100).map(|v| v as f64).collect::<Vec<_>>()
+        }
+        .unwrap();
+        // no boundaries, split in equal parts
+        assert_eq!(heights(&split_df(&df, 3).unwrap()), &[33, 33, 34]);
+
+        // a boundary at 35 is close to the split point at 33
+        let a = df.column("a").unwrap();
+        let mut chunked = a.slice(0, 35);
+        chunked.append(&a.slice(35, 65)).unwrap();
+        let b = df.column("b").unwrap();
+        let mut chunked_b = b.slice(0, 35);
+        chunked_b.append(&b.slice(35, 65)).unwrap();
+        let chunked = DataFrame::new(vec![chunked, chunked_b]).unwrap();
+        let parts = split_df(&chunked, 3).unwrap();
+        assert_eq!(heights(&parts), &[35, 31, 34]);
+        // the first part is not sliced mid chunk
+        assert_eq!(parts[0].column("a").unwrap().n_chunks(), 1);
+
+        // a boundary that is far away from the split points is not used
+        df.rechunk_to(90);
+        assert_eq!(heights(&split_df(&df, 3).unwrap()), &[33, 33, 34]);
+        assert_eq!(heights(&split_df(&df, 1).unwrap()), &[100]);
+    }
+
+    #[test]
+    fn test_structured_errors() {
+        let mut df = df! {
+            "a" => [1, 2]
+        }
+        .unwrap();
+        assert!(matches!(
+            df.column("b"),
+            Err(PolarsError::ColumnNotFound(name)) if name == "b"
+        ));
+        assert!(matches!(
+            df.schema().index_of("b"),
+            Err(PolarsError::ColumnNotFound(_))
+        ));
+        assert!(matches!(
+            df.apply_at_idx(3, |s| s.clone()),
+            Err(PolarsError::IndexOutOfBounds { index: 3, len: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_reorder_columns() -> Result<()> {
+        let mut df = df! {
+            "a" => [1, 2],
+            "b" => [3, 4],
+            "c" => [5, 6]
+        }?;
+        df.insert_at_idx(1, Series::new("d", &[7, 8]))?;
+        assert_eq!(df.get_column_names(), &["a", "d", "b", "c"]);
+        assert!(df.insert_at_idx(0, Series::new("a", &[7, 8])).is_err());
+        assert!(df.insert_at_idx(0, Series::new("e", &[7])).is_err());
+        assert!(df.insert_at_idx(5, Series::new("e", &[7, 8])).is_err());
+
+        df.move_column("c", 0)?;
+        assert_eq!(df.get_column_names(), &["c", "a", "d", "b"]);
+        df.move_column("c", 3)?;
+        assert_eq!(df.get_column_names(), &["a", "d", "b", "c"]);
+        assert!(df.move_column("c", 4).is_err());
+
+        df.reorder_columns(&["b", "c", "a", "d"])?;
+        assert_eq!(df.get_column_names(), &["b", "c", "a", "d"]);
+        assert_eq!(Vec::from(df.column("d")?.i32()?), &[Some(7), Some(8)]);
+        assert!(df.reorder_columns(&["b", "c", "a"]).is_err());
+        assert!(df.reorder_columns(&["b", "c", "a", "d", "e"]).is_err());
+        assert!(df.reorder_columns(&["b", "c", "a", "d", "d"]).is_err());
+
+        df.sort_columns_by(|a, b| a.cmp(b));
+        assert_eq!(df.get_column_names(), &["a", "b", "c", "d"]);
+        assert_eq!(Vec::from(df.column("a")?.i32()?), &[Some(1), Some(2)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_with_row_count() -> Result<()> {
+        let df = df! {
+            "a" => [1, 2, 3]
+        }?;
+        let out = df.with_row_count("row_nr", None)?;
+        assert_eq!(out.get_column_names(), &["row_nr", "a"]);
+        assert_eq!(
+            Vec::from(out.column("row_nr")?.u32()?),
+            &[Some(0), Some(1), Some(2)]
+        );
+        let out = df.with_row_count("row_nr", Some(u32::MAX - 2))?;
+        assert_eq!(out.column("row_nr")?.u32()?.get(2), Some(u32::MAX));
+
+        assert!(df.with_row_count("row_nr", Some(u32::MAX - 1)).is_err());
+        assert!(df.with_row_count("a", None).is_err());
+        Ok(())
+    }
 }
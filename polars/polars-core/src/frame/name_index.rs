@@ -0,0 +1,65 @@
+use crate::prelude::*;
+use ahash::RandomState;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Below this width a linear scan over the column names is cheaper than hashing.
+const MIN_WIDTH: usize = 16;
+
+type NameMap = HashMap<String, usize, RandomState>;
+
+/// Lazily built mapping of the column names of a `DataFrame` to their index.
+///
+/// The mapping is built on the first lookup and must be invalidated by every operation that
+/// inserts, removes or renames columns. As a safeguard it is rebuilt if the width of the
+/// `DataFrame` changed, and a hit is verified against the name of the column.
+#[derive(Default)]
+pub(crate) struct NameIndex(Mutex<Option<(usize, NameMap)>>);
+
+impl Clone for NameIndex {
+    // Cloning a `DataFrame` is cheap, so we don't clone the mapping but let it be rebuilt
+    // when it is needed.
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+fn build(columns: &[Series]) -> NameMap {
+    let mut map = HashMap::with_capacity_and_hasher(columns.len(), RandomState::default());
+    for (idx, s) in columns.iter().enumerate() {
+        // keep the first occurrence, just like a linear scan would
+        map.entry(s.name().to_string()).or_insert(idx);
+    }
+    map
+}
+
+impl NameIndex {
+    /// Find the index of the column `name` in `columns`.
+    pub(crate) fn find(&self, columns: &[Series], name: &str) -> Option<usize> {
+        if columns.len() < MIN_WIDTH {
+            return columns.iter().position(|s| s.name() == name);
+        }
+        let idx = {
+            let mut guard = self.0.lock().unwrap();
+            match &*guard {
+                Some((width, _)) if *width == columns.len() => {}
+                _ => *guard = Some((columns.len(), build(columns))),
+            }
+            guard.as_ref().unwrap().1.get(name).copied()
+        };
+        match idx {
+            Some(idx) if columns[idx].name() == name => Some(idx),
+            Some(_) => {
+                // a column was renamed without invalidating the mapping
+                *self.0.lock().unwrap() = None;
+                columns.iter().position(|s| s.name() == name)
+            }
+            None => None,
+        }
+    }
+
+    /// Clear the mapping. Must be called after the columns are inserted, removed or renamed.
+    pub(crate) fn invalidate(&mut self) {
+        *self.0.get_mut().unwrap() = None;
+    }
+}
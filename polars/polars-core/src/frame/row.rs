@@ -1,6 +1,6 @@
+use crate::chunked_array::builder::get_list_builder;
 use crate::prelude::*;
 use itertools::Itertools;
-use std::fmt::{Debug, Formatter};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Row<'a>(pub Vec<AnyValue<'a>>);
@@ -47,32 +47,69 @@ impl DataFrame {
     /// as this is a lot slower than creating the `Series` in a columnar fashion
     #[cfg_attr(docsrs, doc(cfg(feature = "rows")))]
     pub fn from_rows_and_schema(rows: &[Row], schema: &Schema) -> Result<Self> {
-        let capacity = rows.len();
-        let mut buffers: Vec<_> = schema
-            .fields()
-            .iter()
-            .map(|fld| {
-                let buf: Buffer = (fld.data_type(), capacity).into();
-                buf
-            })
-            .collect();
+        let mut buffers = new_buffers(schema, rows.len())?;
+        for (row_idx, row) in rows.iter().enumerate() {
+            add_row(
+                &mut buffers,
+                schema,
+                row.0.iter().cloned(),
+                row.0.len(),
+                row_idx,
+            )?;
+        }
+        DataFrame::new(finish_buffers(buffers, schema))
+    }
 
-        rows.iter().try_for_each::<_, Result<()>>(|row| {
-            for (value, buf) in row.0.iter().zip(&mut buffers) {
-                buf.add(value.clone())?
+    /// Create a new DataFrame from an iterator of rows. The rows are appended in chunks of
+    /// `chunk_size` rows, so only a single chunk of rows is buffered in the builders.
+    ///
+    /// `AnyValue::Null` is accepted for every column, any other value should match the dtype of
+    /// its column in the `schema`.
+    #[cfg_attr(docsrs, doc(cfg(feature = "rows")))]
+    pub fn from_rows_iter<'a, I>(rows: I, schema: &Schema, chunk_size: usize) -> Result<Self>
+    where
+        I: IntoIterator<Item = Row<'a>>,
+    {
+        let chunk_size = std::cmp::max(chunk_size, 1);
+        let mut buffers = new_buffers(schema, chunk_size)?;
+        let mut columns: Option<Vec<Series>> = None;
+        let mut n_buffered = 0;
+
+        for (row_idx, row) in rows.into_iter().enumerate() {
+            if n_buffered == chunk_size {
+                let chunk = std::mem::replace(&mut buffers, new_buffers(schema, chunk_size)?);
+                append_chunk(&mut columns, finish_buffers(chunk, schema))?;
+                n_buffered = 0;
             }
-            Ok(())
-        })?;
-        let v = buffers
-            .into_iter()
-            .zip(schema.fields())
-            .map(|(b, fld)| {
-                let mut s = b.into_series();
-                s.rename(fld.name());
-                s
-            })
+            let width = row.0.len();
+            add_row(&mut buffers, schema, row.0.into_iter(), width, row_idx)?;
+            n_buffered += 1;
+        }
+        if n_buffered > 0 || columns.is_none() {
+            append_chunk(&mut columns, finish_buffers(buffers, schema))?;
+        }
+        DataFrame::new(columns.unwrap())
+    }
+
+    /// Create a new DataFrame from an iterator of rows without a schema. The schema is inferred
+    /// from the first `infer_schema_length` rows, see [DataFrame::from_rows_iter].
+    #[cfg_attr(docsrs, doc(cfg(feature = "rows")))]
+    pub fn from_rows_iter_infer<'a, I>(
+        rows: I,
+        infer_schema_length: usize,
+        chunk_size: usize,
+    ) -> Result<Self>
+    where
+        I: IntoIterator<Item = Row<'a>>,
+    {
+        let mut rows = rows.into_iter();
+        let head: Vec<_> = rows
+            .by_ref()
+            .take(std::cmp::max(infer_schema_length, 1))
             .collect();
-        DataFrame::new(v)
+        let schema = infer_rows_schema(&head, infer_schema_length);
+        check_inferred_schema(&schema)?;
+        Self::from_rows_iter(head.into_iter().chain(rows), &schema, chunk_size)
     }
 
     /// Create a new DataFrame from rows. This should only be used when you have row wise data,
@@ -80,29 +117,109 @@ impl DataFrame {
     #[cfg_attr(docsrs, doc(cfg(feature = "rows")))]
     pub fn from_rows(rows: &[Row]) -> Result<Self> {
         let schema = rows_to_schema(rows);
-        let has_nulls = schema
-            .fields()
-            .iter()
-            .any(|fld| matches!(fld.data_type(), DataType::Null));
-        if has_nulls {
-            return Err(PolarsError::HasNullValues(
-                "Could not infer row types, because of the null values".into(),
+        check_inferred_schema(&schema)?;
+        Self::from_rows_and_schema(rows, &schema)
+    }
+}
+
+fn check_inferred_schema(schema: &Schema) -> Result<()> {
+    let has_nulls = schema
+        .fields()
+        .iter()
+        .any(|fld| matches!(fld.data_type(), DataType::Null));
+    if has_nulls {
+        return Err(PolarsError::HasNullValues(
+            "Could not infer row types, because of the null values".into(),
+        ));
+    }
+    Ok(())
+}
+
+fn new_buffers(schema: &Schema, capacity: usize) -> Result<Vec<Buffer>> {
+    schema
+        .fields()
+        .iter()
+        .map(|fld| Buffer::new(fld.data_type(), capacity))
+        .collect()
+}
+
+fn add_row<'a>(
+    buffers: &mut [Buffer],
+    schema: &Schema,
+    values: impl Iterator<Item = AnyValue<'a>>,
+    width: usize,
+    row_idx: usize,
+) -> Result<()> {
+    if width != buffers.len() {
+        return Err(PolarsError::ShapeMisMatch(
+            format!(
+                "row {} has {} values, but the schema has {} columns",
+                row_idx,
+                width,
+                buffers.len()
+            )
+            .into(),
+        ));
+    }
+    for ((value, buf), fld) in values.zip(buffers.iter_mut()).zip(schema.fields()) {
+        if let Err(value) = buf.add(value) {
+            return Err(PolarsError::DataTypeMisMatch(
+                format!(
+                    "could not append {:?} of row {} to column '{}' of dtype {:?}",
+                    value,
+                    row_idx,
+                    fld.name(),
+                    fld.data_type()
+                )
+                .into(),
             ));
         }
-        Self::from_rows_and_schema(rows, &schema)
     }
+    Ok(())
+}
+
+fn finish_buffers(buffers: Vec<Buffer>, schema: &Schema) -> Vec<Series> {
+    buffers
+        .into_iter()
+        .zip(schema.fields())
+        .map(|(b, fld)| {
+            let mut s = b.into_series();
+            s.rename(fld.name());
+            s
+        })
+        .collect()
+}
+
+fn append_chunk(columns: &mut Option<Vec<Series>>, chunk: Vec<Series>) -> Result<()> {
+    match columns {
+        None => *columns = Some(chunk),
+        Some(columns) => {
+            for (s, chunk) in columns.iter_mut().zip(&chunk) {
+                s.append(chunk)?;
+            }
+        }
+    }
+    Ok(())
 }
 
 /// Infer schema from rows.
 pub fn rows_to_schema(rows: &[Row]) -> Schema {
     // no of rows to use to infer dtype
-    let max_infer = std::cmp::min(rows.len(), 50);
-    let mut schema: Schema = (&rows[0]).into();
+    infer_rows_schema(rows, 50)
+}
+
+/// Infer schema from the first `infer_schema_length` rows. The dtype of a column is taken from
+/// the first row that has a value for it, columns that only have nulls get dtype `Null`.
+pub fn infer_rows_schema(rows: &[Row], infer_schema_length: usize) -> Schema {
+    let max_infer = std::cmp::min(rows.len(), std::cmp::max(infer_schema_length, 1));
+    let mut schema: Schema = match rows.first() {
+        Some(row) => row.into(),
+        None => return Schema::new(vec![]),
+    };
     // the first row that has no nulls will be used to infer the schema.
     // if there is a null, we check the next row and see if we can update the schema
 
     for row in rows.iter().take(max_infer).skip(1) {
-        // for i in 1..max_infer {
         let nulls: Vec<_> = schema
             .fields()
             .iter()
@@ -135,14 +252,19 @@ impl<'a> From<&AnyValue<'a>> for Field {
             Null => Field::new("", DataType::Null),
             Boolean(_) => Field::new("", DataType::Boolean),
             Utf8(_) => Field::new("", DataType::Utf8),
+            UInt8(_) => Field::new("", DataType::UInt8),
+            UInt16(_) => Field::new("", DataType::UInt16),
             UInt32(_) => Field::new("", DataType::UInt32),
             UInt64(_) => Field::new("", DataType::UInt64),
+            Int8(_) => Field::new("", DataType::Int8),
+            Int16(_) => Field::new("", DataType::Int16),
             Int32(_) => Field::new("", DataType::Int32),
             Int64(_) => Field::new("", DataType::Int64),
             Float32(_) => Field::new("", DataType::Float32),
             Float64(_) => Field::new("", DataType::Float64),
             Date32(_) => Field::new("", DataType::Date32),
             Date64(_) => Field::new("", DataType::Date64),
+            List(s) => Field::new("", DataType::List(s.dtype().to_arrow())),
             _ => unimplemented!(),
         }
     }
@@ -166,8 +288,16 @@ impl From<&Row<'_>> for Schema {
 
 pub(crate) enum Buffer {
     Boolean(BooleanChunkedBuilder),
+    #[cfg(feature = "dtype-i8")]
+    Int8(PrimitiveChunkedBuilder<Int8Type>),
+    #[cfg(feature = "dtype-i16")]
+    Int16(PrimitiveChunkedBuilder<Int16Type>),
     Int32(PrimitiveChunkedBuilder<Int32Type>),
     Int64(PrimitiveChunkedBuilder<Int64Type>),
+    #[cfg(feature = "dtype-u8")]
+    UInt8(PrimitiveChunkedBuilder<UInt8Type>),
+    #[cfg(feature = "dtype-u16")]
+    UInt16(PrimitiveChunkedBuilder<UInt16Type>),
     UInt32(PrimitiveChunkedBuilder<UInt32Type>),
     #[cfg(feature = "dtype-u64")]
     UInt64(PrimitiveChunkedBuilder<UInt64Type>),
@@ -178,39 +308,75 @@ pub(crate) enum Buffer {
     Float32(PrimitiveChunkedBuilder<Float32Type>),
     Float64(PrimitiveChunkedBuilder<Float64Type>),
     Utf8(Utf8ChunkedBuilder),
+    /// The builder and the dtype of the values.
+    List(Box<dyn ListBuilderTrait>, DataType),
 }
 
-impl Debug for Buffer {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        use Buffer::*;
-        match self {
-            Boolean(_) => f.write_str("boolean"),
-            Int32(_) => f.write_str("i32"),
-            Int64(_) => f.write_str("i64"),
-            UInt32(_) => f.write_str("u32"),
+impl Buffer {
+    fn new(dtype: &DataType, capacity: usize) -> Result<Self> {
+        use DataType::*;
+        let buf = match dtype {
+            Boolean => Buffer::Boolean(BooleanChunkedBuilder::new("", capacity)),
+            #[cfg(feature = "dtype-i8")]
+            Int8 => Buffer::Int8(PrimitiveChunkedBuilder::new("", capacity)),
+            #[cfg(feature = "dtype-i16")]
+            Int16 => Buffer::Int16(PrimitiveChunkedBuilder::new("", capacity)),
+            Int32 => Buffer::Int32(PrimitiveChunkedBuilder::new("", capacity)),
+            Int64 => Buffer::Int64(PrimitiveChunkedBuilder::new("", capacity)),
+            #[cfg(feature = "dtype-u8")]
+            UInt8 => Buffer::UInt8(PrimitiveChunkedBuilder::new("", capacity)),
+            #[cfg(feature = "dtype-u16")]
+            UInt16 => Buffer::UInt16(PrimitiveChunkedBuilder::new("", capacity)),
+            UInt32 => Buffer::UInt32(PrimitiveChunkedBuilder::new("", capacity)),
             #[cfg(feature = "dtype-u64")]
-            UInt64(_) => f.write_str("u64"),
+            UInt64 => Buffer::UInt64(PrimitiveChunkedBuilder::new("", capacity)),
             #[cfg(feature = "dtype-date32")]
-            Date32(_) => f.write_str("date32"),
+            Date32 => Buffer::Date32(PrimitiveChunkedBuilder::new("", capacity)),
             #[cfg(feature = "dtype-date64")]
-            Date64(_) => f.write_str("date64"),
-            Float32(_) => f.write_str("f32"),
-            Float64(_) => f.write_str("f64"),
-            Utf8(_) => f.write_str("utf8"),
-        }
+            Date64 => Buffer::Date64(PrimitiveChunkedBuilder::new("", capacity)),
+            Float32 => Buffer::Float32(PrimitiveChunkedBuilder::new("", capacity)),
+            Float64 => Buffer::Float64(PrimitiveChunkedBuilder::new("", capacity)),
+            Utf8 => Buffer::Utf8(Utf8ChunkedBuilder::new("", capacity, capacity * 5)),
+            List(inner) => {
+                let inner: DataType = inner.into();
+                let builder = get_list_builder(&inner, capacity * 5, capacity, "");
+                Buffer::List(builder, inner)
+            }
+            dt => {
+                return Err(PolarsError::InvalidOperation(
+                    format!("cannot create a column of dtype {:?} from rows", dt).into(),
+                ))
+            }
+        };
+        Ok(buf)
     }
-}
 
-impl Buffer {
-    fn add(&mut self, val: AnyValue) -> Result<()> {
+    /// Append a value, the value is returned if it doesn't match the dtype of the buffer.
+    fn add<'a>(&mut self, val: AnyValue<'a>) -> std::result::Result<(), AnyValue<'a>> {
         use Buffer::*;
         match (self, val) {
             (Boolean(builder), AnyValue::Boolean(v)) => builder.append_value(v),
             (Boolean(builder), AnyValue::Null) => builder.append_null(),
+            #[cfg(feature = "dtype-i8")]
+            (Int8(builder), AnyValue::Int8(v)) => builder.append_value(v),
+            #[cfg(feature = "dtype-i8")]
+            (Int8(builder), AnyValue::Null) => builder.append_null(),
+            #[cfg(feature = "dtype-i16")]
+            (Int16(builder), AnyValue::Int16(v)) => builder.append_value(v),
+            #[cfg(feature = "dtype-i16")]
+            (Int16(builder), AnyValue::Null) => builder.append_null(),
             (Int32(builder), AnyValue::Int32(v)) => builder.append_value(v),
             (Int32(builder), AnyValue::Null) => builder.append_null(),
             (Int64(builder), AnyValue::Int64(v)) => builder.append_value(v),
             (Int64(builder), AnyValue::Null) => builder.append_null(),
+            #[cfg(feature = "dtype-u8")]
+            (UInt8(builder), AnyValue::UInt8(v)) => builder.append_value(v),
+            #[cfg(feature = "dtype-u8")]
+            (UInt8(builder), AnyValue::Null) => builder.append_null(),
+            #[cfg(feature = "dtype-u16")]
+            (UInt16(builder), AnyValue::UInt16(v)) => builder.append_value(v),
+            #[cfg(feature = "dtype-u16")]
+            (UInt16(builder), AnyValue::Null) => builder.append_null(),
             (UInt32(builder), AnyValue::UInt32(v)) => builder.append_value(v),
             (UInt32(builder), AnyValue::Null) => builder.append_null(),
             #[cfg(feature = "dtype-u64")]
@@ -218,14 +384,24 @@ impl Buffer {
             #[cfg(feature = "dtype-u64")]
             (UInt64(builder), AnyValue::Null) => builder.append_null(),
             #[cfg(feature = "dtype-date32")]
+            (Date32(builder), AnyValue::Date32(v)) => builder.append_value(v),
+            #[cfg(feature = "dtype-date32")]
             (Date32(builder), AnyValue::Null) => builder.append_null(),
             #[cfg(feature = "dtype-date64")]
             (Date64(builder), AnyValue::Date64(v)) => builder.append_value(v),
+            #[cfg(feature = "dtype-date64")]
+            (Date64(builder), AnyValue::Null) => builder.append_null(),
+            (Float32(builder), AnyValue::Float32(v)) => builder.append_value(v),
             (Float32(builder), AnyValue::Null) => builder.append_null(),
             (Float64(builder), AnyValue::Float64(v)) => builder.append_value(v),
+            (Float64(builder), AnyValue::Null) => builder.append_null(),
             (Utf8(builder), AnyValue::Utf8(v)) => builder.append_value(v),
             (Utf8(builder), AnyValue::Null) => builder.append_null(),
-            (buf, val) => return Err(PolarsError::ValueError(format!("Could not append {:?} to builder {:?}; make sure that all rows have the same schema.", val, std::mem::discriminant(buf)).into()))
+            (List(builder, inner), AnyValue::List(s)) if s.dtype() == &*inner => {
+                builder.append_series(&s)
+            }
+            (List(builder, _), AnyValue::Null) => builder.append_null(),
+            (_, val) => return Err(val),
         };
 
         Ok(())
@@ -235,8 +411,16 @@ impl Buffer {
         use Buffer::*;
         match self {
             Boolean(b) => b.finish().into_series(),
+            #[cfg(feature = "dtype-i8")]
+            Int8(b) => b.finish().into_series(),
+            #[cfg(feature = "dtype-i16")]
+            Int16(b) => b.finish().into_series(),
             Int32(b) => b.finish().into_series(),
             Int64(b) => b.finish().into_series(),
+            #[cfg(feature = "dtype-u8")]
+            UInt8(b) => b.finish().into_series(),
+            #[cfg(feature = "dtype-u16")]
+            UInt16(b) => b.finish().into_series(),
             UInt32(b) => b.finish().into_series(),
             #[cfg(feature = "dtype-u64")]
             UInt64(b) => b.finish().into_series(),
@@ -247,30 +431,134 @@ impl Buffer {
             Float32(b) => b.finish().into_series(),
             Float64(b) => b.finish().into_series(),
             Utf8(b) => b.finish().into_series(),
+            List(mut b, _) => b.finish().into_series(),
         }
     }
 }
 
-// datatype and length
-impl From<(&DataType, usize)> for Buffer {
-    fn from(a: (&DataType, usize)) -> Self {
-        let (dt, len) = a;
-        use DataType::*;
-        match dt {
-            Boolean => Buffer::Boolean(BooleanChunkedBuilder::new("", len)),
-            Int32 => Buffer::Int32(PrimitiveChunkedBuilder::new("", len)),
-            Int64 => Buffer::Int64(PrimitiveChunkedBuilder::new("", len)),
-            UInt32 => Buffer::UInt32(PrimitiveChunkedBuilder::new("", len)),
-            #[cfg(feature = "dtype-u64")]
-            UInt64 => Buffer::UInt64(PrimitiveChunkedBuilder::new("", len)),
-            #[cfg(feature = "dtype-date32")]
-            Date32 => Buffer::Date32(PrimitiveChunkedBuilder::new("", len)),
-            #[cfg(feature = "dtype-date64")]
-            Date64 => Buffer::Date64(PrimitiveChunkedBuilder::new("", len)),
-            Float32 => Buffer::Float32(PrimitiveChunkedBuilder::new("", len)),
-            Float64 => Buffer::Float64(PrimitiveChunkedBuilder::new("", len)),
-            Utf8 => Buffer::Utf8(Utf8ChunkedBuilder::new("", len, len * 5)),
-            _ => unimplemented!(),
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn to_rows(df: &DataFrame) -> Vec<Row> {
+        (0..df.height()).map(|idx| df.get_row(idx)).collect()
+    }
+
+    fn example_df() -> DataFrame {
+        let list = Series::new(
+            "list",
+            &[
+                Series::new("", &[1i64, 2]),
+                Series::new("", &[3i64]),
+                Series::new("", &[4i64, 5, 6]),
+                Series::new("", &[7i64]),
+                Series::new("", &[8i64, 9]),
+            ],
+        );
+        let mut columns = vec![
+            Series::new("bool", &[Some(true), None, Some(false), Some(true), None]),
+            Series::new("i32", &[Some(1i32), Some(2), None, Some(4), Some(5)]),
+            Series::new("i64", &[Some(1i64), None, Some(3), Some(4), Some(5)]),
+            Series::new("u32", &[None, Some(2u32), Some(3), Some(4), Some(5)]),
+            Series::new(
+                "f32",
+                &[Some(1.0f32), Some(2.5), None, Some(4.0), Some(5.0)],
+            ),
+            Series::new(
+                "f64",
+                &[Some(1.0f64), None, Some(3.5), Some(4.0), Some(5.0)],
+            ),
+            Series::new("utf8", &[Some("a"), Some("b"), None, Some("d"), Some("e")]),
+            list,
+        ];
+        #[cfg(feature = "dtype-u64")]
+        columns.push(Series::new(
+            "u64",
+            &[Some(1u64), None, Some(3), Some(4), Some(5)],
+        ));
+        #[cfg(feature = "dtype-date32")]
+        columns.push(
+            Date32Chunked::new_from_opt_slice(
+                "date32",
+                &[Some(1), None, Some(3), Some(4), Some(5)],
+            )
+            .into_series(),
+        );
+        #[cfg(feature = "dtype-date64")]
+        columns.push(
+            Date64Chunked::new_from_opt_slice(
+                "date64",
+                &[Some(1), None, Some(3), Some(4), Some(5)],
+            )
+            .into_series(),
+        );
+        DataFrame::new(columns).unwrap()
+    }
+
+    #[test]
+    fn test_rows_round_trip() -> Result<()> {
+        let df = example_df();
+        let schema = df.schema();
+        let rows = to_rows(&df);
+
+        let out = DataFrame::from_rows_and_schema(&rows, &schema)?;
+        assert!(out.frame_equal_missing(&df));
+
+        for &chunk_size in &[1, 2, 5, 100] {
+            let out = DataFrame::from_rows_iter(rows.iter().cloned(), &schema, chunk_size)?;
+            assert!(out.frame_equal_missing(&df));
+            assert_eq!(out.n_chunks()?, (df.height() + chunk_size - 1) / chunk_size);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_rows_iter_infer() -> Result<()> {
+        let rows = vec![
+            Row(vec![AnyValue::Int32(1), AnyValue::Null]),
+            Row(vec![AnyValue::Null, AnyValue::Null]),
+            Row(vec![AnyValue::Int32(3), AnyValue::Utf8("c")]),
+            Row(vec![AnyValue::Int32(4), AnyValue::Utf8("d")]),
+        ];
+        let df = DataFrame::from_rows_iter_infer(rows.clone(), 3, 2)?;
+        assert_eq!(df.dtypes(), &[DataType::Int32, DataType::Utf8]);
+        assert_eq!(
+            Vec::from(df.column("column_0")?.i32()?),
+            &[Some(1), None, Some(3), Some(4)]
+        );
+
+        // the second column only has nulls in the scanned rows
+        assert!(matches!(
+            DataFrame::from_rows_iter_infer(rows, 2, 2),
+            Err(PolarsError::HasNullValues(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_rows_errors() {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int32),
+            Field::new("b", DataType::Utf8),
+        ]);
+        let rows = vec![
+            Row(vec![AnyValue::Int32(1), AnyValue::Utf8("a")]),
+            Row(vec![AnyValue::Int32(2), AnyValue::Int64(2)]),
+        ];
+        match DataFrame::from_rows_iter(rows, &schema, 1) {
+            Err(PolarsError::DataTypeMisMatch(msg)) => {
+                assert!(msg.contains("row 1"));
+                assert!(msg.contains("'b'"));
+                assert!(msg.contains("Utf8"));
+                assert!(msg.contains("Int64(2)"));
+            }
+            _ => panic!("expected a dtype mismatch"),
         }
+
+        let rows = vec![Row(vec![AnyValue::Int32(1)])];
+        assert!(matches!(
+            DataFrame::from_rows_and_schema(&rows, &schema),
+            Err(PolarsError::ShapeMisMatch(_))
+        ));
     }
 }
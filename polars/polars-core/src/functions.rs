@@ -5,9 +5,9 @@
 #[cfg(feature = "sort_multiple")]
 use crate::chunked_array::ops::sort::prepare_argsort;
 use crate::prelude::*;
-#[cfg(feature = "concat_str")]
-use itertools::Itertools;
+use crate::utils::concat_df;
 use num::{Float, NumCast};
+use std::convert::TryFrom;
 use std::ops::Div;
 
 /// Compute the covariance between two columns.
@@ -40,7 +40,7 @@ where
 /// That means that the first `Series` will be used to determine the ordering
 /// until duplicates are found. Once duplicates are found, the next `Series` will
 /// be used and so on.
-pub fn argsort_by(by: &[Series], reverse: &[bool]) -> Result<UInt32Chunked> {
+pub fn argsort_by(by: &[Series], reverse: &[bool]) -> Result<IdxCa> {
     if by.len() != reverse.len() {
         return Err(PolarsError::ValueError(
             format!(
@@ -56,75 +56,226 @@ pub fn argsort_by(by: &[Series], reverse: &[bool]) -> Result<UInt32Chunked> {
     first.argsort_multiple(&by, &reverse)
 }
 
-/// Casts all series to string data and will concat them in linear time.
+/// Casts all series to string data and will concat them row-wise in linear time.
 /// The concatenated strings are separated by a `delimiter`.
 /// If no `delimiter` is needed, an empty &str should be passed as argument.
+///
+/// Series of length 1 are broadcast to the length of the other series. A null value makes the
+/// whole row null, unless `null_as_empty` is set, in which case it is concatenated as an empty
+/// string. The output has the name of the first series.
 #[cfg(feature = "concat_str")]
-pub fn concat_str(s: &[Series], delimiter: &str) -> Result<Utf8Chunked> {
+pub fn concat_str(s: &[Series], delimiter: &str, null_as_empty: bool) -> Result<Utf8Chunked> {
     if s.is_empty() {
         return Err(PolarsError::NoData(
             "expected multiple series in concat_str function".into(),
         ));
     }
     let len = s.iter().map(|s| s.len()).max().unwrap();
+    if s.iter().any(|s| s.len() != len && s.len() != 1) {
+        return Err(PolarsError::ValueError(
+            "all series in concat_str function should have equal length or length 1".into(),
+        ));
+    }
 
     let cas = s
         .iter()
         .map(|s| {
             let s = s.cast::<Utf8Type>()?;
-            let mut ca = s.utf8()?.clone();
-            // broadcast
-            if ca.len() == 1 && len > 1 {
-                ca = ca.expand_at_index(0, len)
-            }
-
-            Ok(ca)
+            Ok(s.utf8()?.clone())
         })
         .collect::<Result<Vec<_>>>()?;
+    let delimiters_len = delimiter.len() * (cas.len() - 1);
+    let is_valid = |row: &[Option<&str>]| null_as_empty || row.iter().all(|v| v.is_some());
 
-    if !s.iter().map(|s| s.len()).all_equal() {
-        return Err(PolarsError::ValueError(
-            "all series in concat_str function should have equal length".into(),
-        ));
-    }
-    let mut iters = cas.iter().map(|ca| ca.into_iter()).collect::<Vec<_>>();
-    let bytes_cap = cas.iter().map(|ca| ca.get_values_size()).sum();
-    let mut builder = Utf8ChunkedBuilder::new(s[0].name(), len, bytes_cap);
+    // first determine the total size, so that the values buffer is allocated only once
+    let mut bytes_cap = 0;
+    for_each_row(&cas, len, |row| {
+        if is_valid(row) {
+            bytes_cap += row.iter().flatten().map(|v| v.len()).sum::<usize>() + delimiters_len;
+        }
+    });
 
+    let mut builder = Utf8ChunkedBuilder::new(s[0].name(), len, bytes_cap);
     // use a string buffer, to amortize alloc
     let mut buf = String::with_capacity(128);
-
-    for i in 0..len {
-        let mut has_null = false;
-        if i > 0 {
-            buf.push_str(delimiter);
+    for_each_row(&cas, len, |row| {
+        if is_valid(row) {
+            buf.clear();
+            for (i, v) in row.iter().enumerate() {
+                if i > 0 {
+                    buf.push_str(delimiter);
+                }
+                buf.push_str(v.unwrap_or(""));
+            }
+            builder.append_value(&buf);
+        } else {
+            builder.append_null();
         }
+    });
+    Ok(builder.finish())
+}
 
-        iters.iter_mut().for_each(|it| {
-            match it.next() {
-                Some(Some(s)) => buf.push_str(s),
-                Some(None) => has_null = true,
-                None => {
-                    // should not happen as the out loop counts to length
-                    unreachable!()
-                }
+/// Call `f` with the values of every row of `cas`. Arrays of length 1 are broadcast to `len`.
+#[cfg(feature = "concat_str")]
+fn for_each_row<'a, F>(cas: &'a [Utf8Chunked], len: usize, mut f: F)
+where
+    F: FnMut(&[Option<&'a str>]),
+{
+    let mut iters = cas
+        .iter()
+        .map(|ca| -> Box<dyn Iterator<Item = Option<&'a str>> + 'a> {
+            if ca.len() == 1 && len != 1 {
+                Box::new(std::iter::repeat(TakeRandom::get(&ca, 0)))
+            } else {
+                Box::new(ca.into_iter())
             }
-        });
+        })
+        .collect::<Vec<_>>();
 
-        if has_null {
-            builder.append_null();
-        } else {
-            builder.append_value(&buf)
+    // reuse the row buffer for every row
+    let mut row = Vec::with_capacity(cas.len());
+    for _ in 0..len {
+        row.clear();
+        // the iterators have at least `len` values
+        row.extend(iters.iter_mut().map(|it| it.next().unwrap()));
+        f(&row);
+    }
+}
+
+/// Create a Date64 range from `start` to `stop` with steps of `interval`.
+/// `closed` determines if the `start` and `stop` boundaries are included in the range.
+///
+/// Month and year intervals follow the calendar, e.g. monthly steps starting on the 31st
+/// produce the last day of every shorter month.
+/// A negative `interval` produces a descending range from `start` down to `stop`.
+#[cfg(feature = "temporal")]
+#[cfg_attr(docsrs, doc(cfg(feature = "temporal")))]
+pub fn date_range(
+    start: i64,
+    stop: i64,
+    interval: Duration,
+    closed: ClosedWindow,
+    name: &str,
+) -> Result<Date64Chunked> {
+    if interval.is_zero() {
+        return Err(PolarsError::ValueError(
+            "date_range interval may not be zero".into(),
+        ));
+    }
+    if interval.has_mixed_sign() {
+        return Err(PolarsError::ValueError(
+            "date_range interval should have months and milliseconds of the same sign".into(),
+        ));
+    }
+    let descending = interval.is_negative();
+
+    let mut values = AlignedVec::default();
+    let mut i = 0;
+    loop {
+        // always offset from `start` so that month clipping doesn't accumulate
+        let t = interval.add_date64(start, i)?;
+        i += 1;
+        if (!descending && t > stop) || (descending && t < stop) {
+            break;
         }
-        buf.truncate(0)
+        if (t == start && !closed.include_start()) || (t == stop && !closed.include_stop()) {
+            continue;
+        }
+        values.push(t);
     }
-    Ok(builder.finish())
+    Ok(Date64Chunked::new_from_aligned_vec(name, values))
+}
+
+/// Create a range of integers from `start` up to (but excluding) `stop` with steps of `step`.
+/// A negative `step` produces a descending range.
+pub fn arange(start: i64, stop: i64, step: i64) -> Result<Int64Chunked> {
+    if step == 0 {
+        return Err(PolarsError::ValueError(
+            "arange step may not be zero".into(),
+        ));
+    }
+    // the distance between the bounds doesn't always fit in an i64, so the length is computed
+    // in i128, where these operations can't overflow
+    let (start_, stop_, step_) = (start as i128, stop as i128, step as i128);
+    let len = if step > 0 && stop > start {
+        (stop_ - start_ + step_ - 1) / step_
+    } else if step < 0 && start > stop {
+        (start_ - stop_ - step_ - 1) / -step_
+    } else {
+        0
+    };
+    let len = usize::try_from(len).map_err(|_| {
+        PolarsError::ValueError(format!("arange of length {} doesn't fit in memory", len).into())
+    })?;
+
+    let mut values = AlignedVec::with_capacity_aligned(len);
+    // every value is in between the bounds, but `i * step` may not fit in an i64
+    values.extend((0..len as i128).map(|i| (start_ + i * step_) as i64));
+    Ok(Int64Chunked::new_from_aligned_vec("arange", values))
+}
+
+/// Concatenate `DataFrame`s vertically, where the columns don't have to match.
+///
+/// The output schema is the union of the input schemas, in order of first occurrence.
+/// Columns missing in an input are filled with nulls and columns that have different data types
+/// in different inputs are cast to their supertype.
+pub fn concat_df_diagonal(dfs: &[DataFrame]) -> Result<DataFrame> {
+    let schema = Schema::try_merge(dfs.iter().map(|df| df.schema()))?;
+
+    let dfs = dfs
+        .iter()
+        .map(|df| {
+            let height = df.height();
+            let columns = schema
+                .fields()
+                .iter()
+                .map(|field| match df.column(field.name()) {
+                    Ok(s) if s.dtype() == field.data_type() => Ok(s.clone()),
+                    Ok(s) => s.cast_with_dtype(field.data_type()),
+                    Err(_) => Ok(Series::full_null(field.name(), height, field.data_type())),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok(DataFrame::new_no_checks(columns))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    concat_df(&dfs)
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    #[cfg(feature = "concat_str")]
+    fn test_concat_str() -> Result<()> {
+        let a = Series::new("a", &[Some("fóó"), None, Some("bär")]);
+        let b = Series::new("b", &[Some(1i32), Some(2), None]);
+        let c = Series::new("c", &[1.5f64, 2.5, 3.5]);
+        let flag = Series::new("flag", &[true]);
+
+        let out = concat_str(&[a.clone(), b.clone(), c.clone()], "→", false)?;
+        assert_eq!(out.name(), "a");
+        assert_eq!(Vec::from(&out), &[Some("fóó→1→1.5"), None, None]);
+
+        let out = concat_str(&[a.clone(), b, c, flag], "-", true)?;
+        assert_eq!(
+            Vec::from(&out),
+            &[
+                Some("fóó-1-1.5-true"),
+                Some("-2-2.5-true"),
+                Some("bär--3.5-true")
+            ]
+        );
+
+        // a single series without delimiter is a cast
+        let out = concat_str(&[a.clone()], "", false)?;
+        assert_eq!(Vec::from(&out), &[Some("fóó"), None, Some("bär")]);
+
+        assert!(concat_str(&[], "-", false).is_err());
+        assert!(concat_str(&[a, Series::new("d", &[1, 2])], "-", false).is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_pearson_corr() {
         let a = Series::new("a", &[1.0f32, 2.0]);
@@ -132,4 +283,104 @@ mod test {
         assert!((cov(&a.f32().unwrap(), &b.f32().unwrap()).unwrap() - 0.5).abs() < 0.001);
         assert!((pearson_corr(&a.f32().unwrap(), &b.f32().unwrap()).unwrap() - 1.0).abs() < 0.001);
     }
+
+    #[test]
+    fn test_arange() {
+        let ca = arange(0, 5, 2).unwrap();
+        assert_eq!(Vec::from(&ca), &[Some(0), Some(2), Some(4)]);
+        let ca = arange(5, 0, -2).unwrap();
+        assert_eq!(Vec::from(&ca), &[Some(5), Some(3), Some(1)]);
+        assert_eq!(arange(5, 0, 1).unwrap().len(), 0);
+        assert!(arange(0, 5, 0).is_err());
+
+        // the distance between the bounds doesn't fit in an i64
+        let ca = arange(i64::MIN, i64::MAX, i64::MAX).unwrap();
+        assert_eq!(
+            Vec::from(&ca),
+            &[Some(i64::MIN), Some(-1), Some(i64::MAX - 1)]
+        );
+        let ca = arange(i64::MAX, i64::MIN, i64::MIN).unwrap();
+        assert_eq!(Vec::from(&ca), &[Some(i64::MAX), Some(-1)]);
+    }
+
+    #[test]
+    #[cfg(feature = "temporal")]
+    fn test_date_range() {
+        use chrono::NaiveDate;
+        let start = NaiveDate::from_ymd(2021, 1, 31).and_hms(0, 0, 0);
+        let stop = NaiveDate::from_ymd(2021, 4, 30).and_hms(0, 0, 0);
+        let start = naive_datetime_to_date64(&start);
+        let stop = naive_datetime_to_date64(&stop);
+
+        let ca = date_range(
+            start,
+            stop,
+            Duration::from_months(1),
+            ClosedWindow::Both,
+            "date",
+        )
+        .unwrap();
+        let days = ca.day();
+        assert_eq!(Vec::from(&days), &[Some(31), Some(28), Some(31), Some(30)]);
+
+        let ca = date_range(
+            start,
+            stop,
+            Duration::from_months(1),
+            ClosedWindow::None,
+            "date",
+        )
+        .unwrap();
+        assert_eq!(ca.len(), 2);
+
+        // descending
+        let ca = date_range(
+            stop,
+            start,
+            Duration::from_days(-30),
+            ClosedWindow::Left,
+            "date",
+        )
+        .unwrap();
+        assert_eq!(ca.len(), 3);
+        assert!(date_range(
+            start,
+            stop,
+            Duration::from_days(0),
+            ClosedWindow::Both,
+            "date"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_concat_df_diagonal() -> Result<()> {
+        let january = df![
+            "id" => [1, 2],
+            "value" => [1, 2],
+        ]?;
+        let february = df![
+            "value" => [1.5, 2.5],
+            "id" => [3, 4],
+            "note" => ["a", "b"],
+        ]?;
+
+        let out = concat_df_diagonal(&[january, february])?;
+        assert_eq!(out.get_column_names(), &["id", "value", "note"]);
+        assert_eq!(out.column("value")?.dtype(), &DataType::Float64);
+        assert_eq!(
+            Vec::from(out.column("value")?.f64()?),
+            &[Some(1.0), Some(2.0), Some(1.5), Some(2.5)]
+        );
+        assert_eq!(
+            Vec::from(out.column("note")?.utf8()?),
+            &[None, None, Some("a"), Some("b")]
+        );
+
+        // a list column cannot be merged with a numeric column
+        let values = Series::new("value", &[Series::new("", &[1, 2])]);
+        let other = DataFrame::new(vec![values])?;
+        assert!(concat_df_diagonal(&[out, other]).is_err());
+        Ok(())
+    }
 }
@@ -6,7 +6,7 @@ pub mod datatypes;
 #[cfg(feature = "docs")]
 pub mod doc;
 pub mod error;
-mod fmt;
+pub mod fmt;
 pub mod frame;
 pub mod functions;
 pub mod prelude;
@@ -16,10 +16,13 @@ pub mod serde;
 pub mod series;
 pub mod testing;
 pub(crate) mod vector_hasher;
+pub mod verbose;
 
-use ahash::AHashMap;
+use crate::datatypes::PlHashMap;
 use lazy_static::lazy_static;
 use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::collections::BTreeMap;
+use std::hash::{BuildHasher, Hash, Hasher};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Mutex, MutexGuard};
 
@@ -35,9 +38,20 @@ lazy_static! {
         .expect("could not spawn threads");
 }
 
-struct SCacheInner {
-    map: AHashMap<String, u32>,
-    uuid: u128,
+pub(crate) struct SCacheEntry {
+    idx: u32,
+    /// The last generation in which the entry was used.
+    generation: u32,
+}
+
+pub(crate) struct SCacheInner {
+    map: PlHashMap<String, SCacheEntry>,
+    pub(crate) uuid: u128,
+    /// Indices are never reused, as pruned entries may still be referred to by categoricals.
+    next_idx: u32,
+    generation: u32,
+    /// The number of live categoricals per generation in which they were created.
+    live_generations: BTreeMap<u32, usize>,
 }
 
 impl Default for SCacheInner {
@@ -48,14 +62,51 @@ impl Default for SCacheInner {
                 .duration_since(UNIX_EPOCH)
                 .unwrap()
                 .as_nanos(),
+            next_idx: 0,
+            generation: 0,
+            live_generations: BTreeMap::new(),
+        }
+    }
+}
+
+impl SCacheInner {
+    /// Get the index of `s`, inserting it if it is not in the cache. The hash is computed once
+    /// and used for both the lookup and the insert.
+    pub(crate) fn get_or_insert(&mut self, s: &str) -> u32 {
+        let mut hasher = self.map.hasher().build_hasher();
+        s.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        let generation = self.generation;
+        let next_idx = self.next_idx;
+        match self.map.raw_entry_mut().from_key_hashed_nocheck(hash, s) {
+            hashbrown::hash_map::RawEntryMut::Occupied(mut entry) => {
+                let entry = entry.get_mut();
+                entry.generation = generation;
+                entry.idx
+            }
+            hashbrown::hash_map::RawEntryMut::Vacant(entry) => {
+                entry.insert_hashed_nocheck(
+                    hash,
+                    s.to_string(),
+                    SCacheEntry {
+                        idx: next_idx,
+                        generation,
+                    },
+                );
+                self.next_idx = next_idx
+                    .checked_add(1)
+                    .expect("not more than u32::MAX categories supported");
+                next_idx
+            }
         }
     }
 }
 
 /// Used by categorical data that need to share global categories.
 /// In *eager* you need to specifically toggle global string cache to have a global effect.
-/// In *lazy* it is toggled on at the start of a computation run and turned of (deleted) when a
-/// result is produced.
+/// In *lazy* a query that combines categoricals of different inputs holds a [StringCacheHolder]
+/// during its execution.
 pub(crate) struct StringCache(pub(crate) Mutex<SCacheInner>);
 
 impl StringCache {
@@ -76,8 +127,12 @@ impl Default for StringCache {
 }
 
 pub(crate) static USE_STRING_CACHE: AtomicBool = AtomicBool::new(false);
+/// Set while there is at least one live [StringCacheHolder].
+static STRING_CACHE_HELD: AtomicBool = AtomicBool::new(false);
 lazy_static! {
     pub(crate) static ref STRING_CACHE: StringCache = Default::default();
+    /// The number of live [StringCacheHolder]s.
+    static ref STRING_CACHE_HOLDERS: Mutex<usize> = Mutex::new(0);
 }
 
 #[cfg(test)]
@@ -93,19 +148,142 @@ use std::time::{SystemTime, UNIX_EPOCH};
 /// This is used to cache the string categories locally.
 /// This allows join operations on categorical types.
 pub fn toggle_string_cache(toggle: bool) {
+    let holders = STRING_CACHE_HOLDERS.lock().unwrap();
     USE_STRING_CACHE.store(toggle, Ordering::Release);
 
-    if !toggle {
+    // a holder still uses the cache, it is cleared when the last holder is dropped
+    if !toggle && *holders == 0 {
         STRING_CACHE.clear()
     }
 }
 
+/// Uses the global string cache for the Categorical Types as long as it is alive.
+///
+/// Holders can be nested and held by multiple threads at once. When the last holder is dropped
+/// the cache is turned off and cleared, unless it was turned on with [toggle_string_cache].
+pub struct StringCacheHolder {
+    // prevent construction without `hold`
+    _private: (),
+}
+
+impl StringCacheHolder {
+    /// Use the global string cache until the returned holder is dropped.
+    pub fn hold() -> Self {
+        let mut holders = STRING_CACHE_HOLDERS.lock().unwrap();
+        *holders += 1;
+        STRING_CACHE_HELD.store(true, Ordering::Release);
+        StringCacheHolder { _private: () }
+    }
+}
+
+impl Drop for StringCacheHolder {
+    fn drop(&mut self) {
+        let mut holders = STRING_CACHE_HOLDERS.lock().unwrap();
+        *holders -= 1;
+        if *holders == 0 {
+            STRING_CACHE_HELD.store(false, Ordering::Release);
+            if !USE_STRING_CACHE.load(Ordering::Acquire) {
+                STRING_CACHE.clear()
+            }
+        }
+    }
+}
+
 /// Reset the global string cache used for the Categorical Types.
 pub fn reset_string_cache() {
     STRING_CACHE.clear()
 }
 
+/// The number of strings in the global string cache.
+pub fn string_cache_len() -> usize {
+    STRING_CACHE.lock_map().map.len()
+}
+
+/// Estimate the number of bytes used by the global string cache: the bytes of the strings plus
+/// the allocation of the map.
+pub fn string_cache_memory_usage() -> usize {
+    let cache = STRING_CACHE.lock_map();
+    let keys: usize = cache.map.keys().map(|s| s.capacity()).sum();
+    // hashbrown stores a control byte per bucket next to the (key, value) pair
+    let buckets = cache.map.capacity() * (std::mem::size_of::<(String, SCacheEntry)>() + 1);
+    keys + buckets
+}
+
+/// Remove the strings from the global string cache that are not used by any live Categorical
+/// array, and return the number of removed strings.
+///
+/// Every categorical that is created under the global string cache keeps the generation of the
+/// cache in which it was created alive, and every cache entry tracks the last generation in
+/// which it was used. An entry that was last used before the oldest live generation can't be
+/// referred to by a live categorical, so it is removed. Pruning starts a new generation.
+///
+/// Categoricals keep their own copy of their categories, so they remain valid after pruning,
+/// and the indices of pruned strings are never reused. A pruned string that is seen again gets
+/// a new index.
+pub fn prune_string_cache() -> usize {
+    let mut cache = STRING_CACHE.lock_map();
+    let oldest_live = cache
+        .live_generations
+        .keys()
+        .next()
+        .copied()
+        .unwrap_or(cache.generation + 1);
+    let len = cache.map.len();
+    cache.map.retain(|_, entry| entry.generation >= oldest_live);
+    cache.map.shrink_to_fit();
+    cache.generation += 1;
+    len - cache.map.len()
+}
+
+/// Keeps the entries of the global string cache that a categorical refers to from being
+/// pruned, see [prune_string_cache].
+pub struct StringCacheGeneration {
+    uuid: u128,
+    generation: u32,
+}
+
+impl StringCacheGeneration {
+    pub(crate) fn new(cache: &mut SCacheInner) -> Self {
+        *cache.live_generations.entry(cache.generation).or_insert(0) += 1;
+        StringCacheGeneration {
+            uuid: cache.uuid,
+            generation: cache.generation,
+        }
+    }
+}
+
+impl Drop for StringCacheGeneration {
+    fn drop(&mut self) {
+        let mut cache = STRING_CACHE.lock_map();
+        // the cache may be reset in the meantime
+        if cache.uuid != self.uuid {
+            return;
+        }
+        if let Some(count) = cache.live_generations.get_mut(&self.generation) {
+            *count -= 1;
+            if *count == 0 {
+                cache.live_generations.remove(&self.generation);
+            }
+        }
+    }
+}
+
+static BITWISE_FLOAT_KEYS: AtomicBool = AtomicBool::new(false);
+
+/// Group and join float keys by their exact bits.
+///
+/// By default float keys are made canonical before they are hashed and compared: all NaN's are
+/// a single key, whatever their payload, and `-0.0` is the same key as `0.0`. With this toggle
+/// NaN's with different bits and `-0.0` and `0.0` are different keys.
+pub fn toggle_bitwise_float_keys(toggle: bool) {
+    BITWISE_FLOAT_KEYS.store(toggle, Ordering::Release);
+}
+
+pub(crate) fn use_bitwise_float_keys() -> bool {
+    BITWISE_FLOAT_KEYS.load(Ordering::Acquire)
+}
+
 /// Check if string cache is set.
 pub(crate) fn use_string_cache() -> bool {
-    USE_STRING_CACHE.load(Ordering::Acquire)
+    USE_STRING_CACHE.load(Ordering::Acquire) || STRING_CACHE_HELD.load(Ordering::Acquire)
 }
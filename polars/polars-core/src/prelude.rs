@@ -1,18 +1,25 @@
 //! Everything you need to get started with Polars.
+pub use crate::chunked_array::ops::dummies::DummiesOptions;
+#[cfg(feature = "ewma")]
+pub use crate::chunked_array::ops::ewm::EWMOptions;
+#[cfg(feature = "search_sorted")]
+pub use crate::chunked_array::ops::search_sorted::{SearchSortedOptions, SearchSortedSide};
 pub(crate) use crate::frame::groupby::aggregations::*;
 pub use crate::{
     chunked_array::{
         arithmetic::Pow,
+        boolean::MaskBuffer,
         builder::{
             BooleanChunkedBuilder, ChunkedBuilder, ListBooleanChunkedBuilder, ListBuilderTrait,
-            ListPrimitiveChunkedBuilder, ListUtf8ChunkedBuilder, NewChunkedArray,
-            PrimitiveChunkedBuilder, Utf8ChunkedBuilder,
+            ListPrimitiveChunkedBuilder, ListSlicesChunkedBuilder, ListUtf8ChunkedBuilder,
+            NewChunkedArray, PrimitiveChunkedBuilder, UnknownCategory, Utf8ChunkedBuilder,
         },
         comparison::NumComp,
         iterator::{IntoNoNullIterator, PolarsIterator},
         ops::{
             aggregate::*,
             chunkops::ChunkOps,
+            is_between::ClosedInterval,
             take::AsTakeIndex,
             take_random::{IntoTakeRandom, NumTakeRandomChunked, NumTakeRandomCont},
             window::InitFold,
@@ -39,7 +46,13 @@ pub use std::sync::Arc;
 
 #[cfg(feature = "object")]
 pub use crate::chunked_array::object::PolarsObject;
+#[cfg(feature = "dtype-struct")]
+pub use crate::chunked_array::struct_::StructChunked;
 #[cfg(feature = "temporal")]
 pub use crate::chunked_array::temporal::conversion::*;
+#[cfg(feature = "temporal")]
+pub use crate::chunked_array::temporal::{ClosedWindow, Duration};
+#[cfg(feature = "ndarray")]
+pub use crate::chunked_array::IndexOrder;
 #[cfg(feature = "checked_arithmetic")]
-pub use crate::series::arithmetic::checked::NumOpsDispatchChecked;
+pub use crate::series::arithmetic::checked::{check_overflow, NumOpsDispatchChecked};
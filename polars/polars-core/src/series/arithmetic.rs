@@ -111,19 +111,27 @@ impl NumOpsDispatch for CategoricalChunked {}
 #[cfg(feature = "checked_arithmetic")]
 pub mod checked {
     use super::*;
-    use crate::utils::align_chunks_binary;
-    use num::{CheckedDiv, ToPrimitive, Zero};
+    use crate::chunked_array::arithmetic::arithmetic_helper_opt;
+    use num::{
+        CheckedAdd, CheckedDiv, CheckedMul, CheckedRem, CheckedSub, Float, One, ToPrimitive, Zero,
+    };
 
-    pub trait NumOpsDispatchChecked: Debug {
-        /// Checked integer division. Computes self / rhs, returning None if rhs == 0 or the division results in overflow.
-        fn checked_div(&self, rhs: &Series) -> Result<Series> {
+    macro_rules! not_supported {
+        ($op:expr, $lhs:expr, $rhs:expr) => {
             Err(PolarsError::InvalidOperation(
                 format!(
-                    "checked division operation not supported for {:?} and {:?}",
-                    self, rhs
+                    "checked {} operation not supported for {:?} and {:?}",
+                    $op, $lhs, $rhs
                 )
                 .into(),
             ))
+        };
+    }
+
+    pub trait NumOpsDispatchChecked: Debug {
+        /// Checked integer division. Computes self / rhs, returning None if rhs == 0 or the division results in overflow.
+        fn checked_div(&self, rhs: &Series) -> Result<Series> {
+            not_supported!("division", self, rhs)
         }
         fn checked_div_num<T: ToPrimitive>(&self, _rhs: T) -> Result<Series> {
             Err(PolarsError::InvalidOperation(
@@ -134,101 +142,133 @@ pub mod checked {
                 .into(),
             ))
         }
+        /// Checked addition. Computes self + rhs, returning None if the addition results in overflow.
+        fn checked_add(&self, rhs: &Series) -> Result<Series> {
+            not_supported!("addition", self, rhs)
+        }
+        /// Checked subtraction. Computes self - rhs, returning None if the subtraction results in overflow.
+        fn checked_sub(&self, rhs: &Series) -> Result<Series> {
+            not_supported!("subtraction", self, rhs)
+        }
+        /// Checked multiplication. Computes self * rhs, returning None if the multiplication results in overflow.
+        fn checked_mul(&self, rhs: &Series) -> Result<Series> {
+            not_supported!("multiplication", self, rhs)
+        }
+        /// Floor division. Computes self / rhs rounded towards negative infinity, returning None if
+        /// rhs == 0 or the division results in overflow.
+        ///
+        /// This differs from the truncating `/` if the operands have opposite signs: `-7 / 2 == -3`,
+        /// but the floor division of `-7` by `2` is `-4`.
+        fn floor_div(&self, rhs: &Series) -> Result<Series> {
+            not_supported!("floor division", self, rhs)
+        }
+    }
+
+    /// Integer floor division, i.e. the quotient is rounded towards negative infinity.
+    fn checked_floor_div<T>(lhs: T, rhs: T) -> Option<T>
+    where
+        T: CheckedDiv + CheckedRem + CheckedSub + Zero + One + PartialOrd,
+    {
+        let quotient = lhs.checked_div(&rhs)?;
+        let remainder = lhs.checked_rem(&rhs)?;
+        // the truncated quotient is one too high if the remainder and divisor have opposite signs
+        if !remainder.is_zero() && ((remainder < T::zero()) != (rhs < T::zero())) {
+            quotient.checked_sub(&T::one())
+        } else {
+            Some(quotient)
+        }
     }
 
     impl<T> NumOpsDispatchChecked for ChunkedArray<T>
     where
         T: PolarsIntegerType,
-        T::Native:
-            CheckedDiv<Output = T::Native> + CheckedDiv<Output = T::Native> + num::Zero + num::One,
+        T::Native: CheckedDiv<Output = T::Native>
+            + CheckedRem<Output = T::Native>
+            + CheckedAdd<Output = T::Native>
+            + CheckedSub<Output = T::Native>
+            + CheckedMul<Output = T::Native>
+            + num::Zero
+            + num::One
+            + PartialOrd,
         ChunkedArray<T>: IntoSeries,
     {
         fn checked_div(&self, rhs: &Series) -> Result<Series> {
             let rhs = unsafe { self.unpack_series_matching_physical_type(rhs)? };
-            let (l, r) = align_chunks_binary(self, rhs);
-
-            Ok((l)
-                .downcast_iter()
-                .zip(r.downcast_iter())
-                .map(|(l_arr, r_arr)| {
-                    l_arr
-                        .into_iter()
-                        .zip(r_arr)
-                        // we don't use a kernel, because the checked div also supplies nulls.
-                        // so the usual bit combining is not enough.
-                        .map(|(opt_l, opt_r)| match (opt_l, opt_r) {
-                            (Some(l), Some(r)) => l.checked_div(&r),
-                            _ => None,
-                        })
-                })
-                .flatten()
-                .collect::<ChunkedArray<T>>()
-                .into_series())
+            // we don't use a kernel, because the checked div also supplies nulls.
+            // so the usual bit combining is not enough.
+            Ok(arithmetic_helper_opt(self, rhs, |l, r| l.checked_div(&r)).into_series())
         }
-    }
 
-    impl NumOpsDispatchChecked for Float32Chunked {
-        fn checked_div(&self, rhs: &Series) -> Result<Series> {
+        fn checked_add(&self, rhs: &Series) -> Result<Series> {
             let rhs = unsafe { self.unpack_series_matching_physical_type(rhs)? };
-            let (l, r) = align_chunks_binary(self, rhs);
-
-            Ok((l)
-                .downcast_iter()
-                .zip(r.downcast_iter())
-                .map(|(l_arr, r_arr)| {
-                    l_arr
-                        .into_iter()
-                        .zip(r_arr)
-                        // we don't use a kernel, because the checked div also supplies nulls.
-                        // so the usual bit combining is not enough.
-                        .map(|(opt_l, opt_r)| match (opt_l, opt_r) {
-                            (Some(l), Some(r)) => {
-                                if r.is_zero() {
-                                    None
-                                } else {
-                                    Some(l / r)
-                                }
-                            }
-                            _ => None,
-                        })
-                })
-                .flatten()
-                .collect::<Float32Chunked>()
-                .into_series())
+            Ok(arithmetic_helper_opt(self, rhs, |l, r| l.checked_add(&r)).into_series())
         }
-    }
 
-    impl NumOpsDispatchChecked for Float64Chunked {
-        fn checked_div(&self, rhs: &Series) -> Result<Series> {
+        fn checked_sub(&self, rhs: &Series) -> Result<Series> {
             let rhs = unsafe { self.unpack_series_matching_physical_type(rhs)? };
-            let (l, r) = align_chunks_binary(self, rhs);
-
-            Ok((l)
-                .downcast_iter()
-                .zip(r.downcast_iter())
-                .map(|(l_arr, r_arr)| {
-                    l_arr
-                        .into_iter()
-                        .zip(r_arr)
-                        // we don't use a kernel, because the checked div also supplies nulls.
-                        // so the usual bit combining is not enough.
-                        .map(|(opt_l, opt_r)| match (opt_l, opt_r) {
-                            (Some(l), Some(r)) => {
-                                if r.is_zero() {
-                                    None
-                                } else {
-                                    Some(l / r)
-                                }
-                            }
-                            _ => None,
-                        })
-                })
-                .flatten()
-                .collect::<Float64Chunked>()
-                .into_series())
+            Ok(arithmetic_helper_opt(self, rhs, |l, r| l.checked_sub(&r)).into_series())
+        }
+
+        fn checked_mul(&self, rhs: &Series) -> Result<Series> {
+            let rhs = unsafe { self.unpack_series_matching_physical_type(rhs)? };
+            Ok(arithmetic_helper_opt(self, rhs, |l, r| l.checked_mul(&r)).into_series())
+        }
+
+        fn floor_div(&self, rhs: &Series) -> Result<Series> {
+            let rhs = unsafe { self.unpack_series_matching_physical_type(rhs)? };
+            Ok(arithmetic_helper_opt(self, rhs, checked_floor_div).into_series())
         }
     }
 
+    /// Floats don't overflow, only a division by zero results in a null value.
+    macro_rules! impl_checked_float {
+        ($ca:ty) => {
+            impl NumOpsDispatchChecked for $ca {
+                fn checked_div(&self, rhs: &Series) -> Result<Series> {
+                    let rhs = unsafe { self.unpack_series_matching_physical_type(rhs)? };
+                    Ok(arithmetic_helper_opt(self, rhs, |l, r| {
+                        if r.is_zero() {
+                            None
+                        } else {
+                            Some(l / r)
+                        }
+                    })
+                    .into_series())
+                }
+
+                fn checked_add(&self, rhs: &Series) -> Result<Series> {
+                    let rhs = unsafe { self.unpack_series_matching_physical_type(rhs)? };
+                    Ok(arithmetic_helper_opt(self, rhs, |l, r| Some(l + r)).into_series())
+                }
+
+                fn checked_sub(&self, rhs: &Series) -> Result<Series> {
+                    let rhs = unsafe { self.unpack_series_matching_physical_type(rhs)? };
+                    Ok(arithmetic_helper_opt(self, rhs, |l, r| Some(l - r)).into_series())
+                }
+
+                fn checked_mul(&self, rhs: &Series) -> Result<Series> {
+                    let rhs = unsafe { self.unpack_series_matching_physical_type(rhs)? };
+                    Ok(arithmetic_helper_opt(self, rhs, |l, r| Some(l * r)).into_series())
+                }
+
+                fn floor_div(&self, rhs: &Series) -> Result<Series> {
+                    let rhs = unsafe { self.unpack_series_matching_physical_type(rhs)? };
+                    Ok(arithmetic_helper_opt(self, rhs, |l, r| {
+                        if r.is_zero() {
+                            None
+                        } else {
+                            Some(Float::floor(l / r))
+                        }
+                    })
+                    .into_series())
+                }
+            }
+        };
+    }
+
+    impl_checked_float!(Float32Chunked);
+    impl_checked_float!(Float64Chunked);
+
     impl NumOpsDispatchChecked for BooleanChunked {}
     impl NumOpsDispatchChecked for ListChunked {}
     impl NumOpsDispatchChecked for CategoricalChunked {}
@@ -240,6 +280,26 @@ pub mod checked {
             lhs.as_ref().as_ref().checked_div(rhs.as_ref())
         }
 
+        fn checked_add(&self, rhs: &Series) -> Result<Series> {
+            let (lhs, rhs) = coerce_lhs_rhs(self, rhs)?;
+            lhs.as_ref().as_ref().checked_add(rhs.as_ref())
+        }
+
+        fn checked_sub(&self, rhs: &Series) -> Result<Series> {
+            let (lhs, rhs) = coerce_lhs_rhs(self, rhs)?;
+            lhs.as_ref().as_ref().checked_sub(rhs.as_ref())
+        }
+
+        fn checked_mul(&self, rhs: &Series) -> Result<Series> {
+            let (lhs, rhs) = coerce_lhs_rhs(self, rhs)?;
+            lhs.as_ref().as_ref().checked_mul(rhs.as_ref())
+        }
+
+        fn floor_div(&self, rhs: &Series) -> Result<Series> {
+            let (lhs, rhs) = coerce_lhs_rhs(self, rhs)?;
+            lhs.as_ref().as_ref().floor_div(rhs.as_ref())
+        }
+
         fn checked_div_num<T: ToPrimitive>(&self, rhs: T) -> Result<Series> {
             use DataType::*;
             let s = self.to_physical_repr();
@@ -323,6 +383,69 @@ pub mod checked {
             out.cast_with_dtype(self.dtype())
         }
     }
+
+    /// Check the output of a checked operation for overflows. Returns an error with the row index
+    /// of the first overflow, i.e. the first row where `out` is null while both operands are valid.
+    ///
+    /// The operands may be broadcasted, i.e. have a length of 1.
+    pub fn check_overflow(lhs: &Series, rhs: &Series, out: &Series) -> Result<()> {
+        if out.null_count() == 0 {
+            return Ok(());
+        }
+        let lhs_null = lhs.is_null();
+        let rhs_null = rhs.is_null();
+        let is_valid = |mask: &BooleanChunked, idx: usize| {
+            let idx = if mask.len() == 1 { 0 } else { idx };
+            mask.get(idx) == Some(false)
+        };
+        let first_overflow =
+            out.is_null()
+                .into_no_null_iter()
+                .enumerate()
+                .find(|(idx, is_null)| {
+                    *is_null && is_valid(&lhs_null, *idx) && is_valid(&rhs_null, *idx)
+                });
+
+        match first_overflow {
+            Some((idx, _)) => Err(PolarsError::ComputeError(
+                format!(
+                    "arithmetic overflow or division by zero in row {} of {} and {}",
+                    idx,
+                    lhs.name(),
+                    rhs.name()
+                )
+                .into(),
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Arithmetic with temporal types is not done on a supertype; the operation determines the
+/// output type, e.g. `Date64 - Date64 = Duration` and `Date64 + Duration = Date64`. The operands
+/// are left as is, except for the integer factor of a `Duration`, which is cast to `Int64`.
+#[allow(clippy::type_complexity)]
+fn coerce_temporal_lhs_rhs<'a>(
+    lhs: &'a Series,
+    rhs: &'a Series,
+) -> Result<Option<(Cow<'a, Series>, Cow<'a, Series>)>> {
+    use DataType::*;
+    let out = match (lhs.dtype(), rhs.dtype()) {
+        (Date64, Date64) | (Date64, Duration(_)) | (Duration(_), Date64) => {
+            Some((Cow::Borrowed(lhs), Cow::Borrowed(rhs)))
+        }
+        (Duration(tu_l), Duration(tu_r)) if tu_l == tu_r => {
+            Some((Cow::Borrowed(lhs), Cow::Borrowed(rhs)))
+        }
+        (Duration(_), dt) if dt.is_integer() => {
+            Some((Cow::Borrowed(lhs), Cow::Owned(rhs.cast_with_dtype(&Int64)?)))
+        }
+        (dt, Duration(_)) if dt.is_integer() => {
+            Some((Cow::Owned(lhs.cast_with_dtype(&Int64)?), Cow::Borrowed(rhs)))
+        }
+        _ => None,
+    };
+    Ok(out)
 }
 
 pub(crate) fn coerce_lhs_rhs<'a>(
@@ -343,11 +466,22 @@ pub(crate) fn coerce_lhs_rhs<'a>(
     Ok((left, right))
 }
 
+#[allow(clippy::type_complexity)]
+fn coerce_arithmetic_lhs_rhs<'a>(
+    lhs: &'a Series,
+    rhs: &'a Series,
+) -> Result<(Cow<'a, Series>, Cow<'a, Series>)> {
+    match coerce_temporal_lhs_rhs(lhs, rhs)? {
+        Some(out) => Ok(out),
+        None => coerce_lhs_rhs(lhs, rhs),
+    }
+}
+
 impl ops::Sub for &Series {
     type Output = Series;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        let (lhs, rhs) = coerce_lhs_rhs(self, rhs).expect("cannot coerce datatypes");
+        let (lhs, rhs) = coerce_arithmetic_lhs_rhs(self, rhs).expect("cannot coerce datatypes");
         lhs.subtract(rhs.as_ref()).expect("data types don't match")
     }
 }
@@ -356,7 +490,7 @@ impl ops::Add for &Series {
     type Output = Series;
 
     fn add(self, rhs: Self) -> Self::Output {
-        let (lhs, rhs) = coerce_lhs_rhs(self, rhs).expect("cannot coerce datatypes");
+        let (lhs, rhs) = coerce_arithmetic_lhs_rhs(self, rhs).expect("cannot coerce datatypes");
         lhs.add_to(rhs.as_ref()).expect("data types don't match")
     }
 }
@@ -370,7 +504,13 @@ impl ops::Mul for &Series {
     /// let out = &s * &s;
     /// ```
     fn mul(self, rhs: Self) -> Self::Output {
-        let (lhs, rhs) = coerce_lhs_rhs(self, rhs).expect("cannot coerce datatypes");
+        let (lhs, rhs) = coerce_arithmetic_lhs_rhs(self, rhs).expect("cannot coerce datatypes");
+        // an integer factor times a duration is a duration
+        if matches!(rhs.dtype(), DataType::Duration(_))
+            && !matches!(lhs.dtype(), DataType::Duration(_))
+        {
+            return rhs.multiply(lhs.as_ref()).expect("data types don't match");
+        }
         lhs.multiply(rhs.as_ref()).expect("data types don't match")
     }
 }
@@ -384,7 +524,7 @@ impl ops::Div for &Series {
     /// let out = &s / &s;
     /// ```
     fn div(self, rhs: Self) -> Self::Output {
-        let (lhs, rhs) = coerce_lhs_rhs(self, rhs).expect("cannot coerce datatypes");
+        let (lhs, rhs) = coerce_arithmetic_lhs_rhs(self, rhs).expect("cannot coerce datatypes");
         lhs.divide(rhs.as_ref()).expect("data types don't match")
     }
 }
@@ -718,4 +858,66 @@ mod test {
         let out = s_f64.checked_div_num(0.0f64).unwrap();
         assert_eq!(Vec::from(out.f64().unwrap()), &[None, None, None]);
     }
+
+    #[test]
+    #[cfg(feature = "checked_arithmetic")]
+    fn test_checked_arithmetic() -> Result<()> {
+        let a = Series::new("a", [i32::MAX, -7, 7, -7, i32::MIN]);
+        let b = Series::new("b", [1i32, 2, -2, -2, -1]);
+
+        let out = a.checked_add(&b)?;
+        assert_eq!(
+            Vec::from(out.i32()?),
+            &[None, Some(-5), Some(5), Some(-9), Some(i32::MAX)]
+        );
+        assert_eq!(out.name(), "a");
+        let out = a.checked_sub(&b)?;
+        assert_eq!(
+            Vec::from(out.i32()?),
+            &[Some(i32::MAX - 1), Some(-9), Some(9), Some(-5), None]
+        );
+        let out = a.checked_mul(&b)?;
+        assert_eq!(
+            Vec::from(out.i32()?),
+            &[Some(i32::MAX), Some(-14), Some(-14), Some(14), None]
+        );
+        // i32::MIN / -1 overflows
+        let out = a.checked_div(&b)?;
+        assert_eq!(
+            Vec::from(out.i32()?),
+            &[Some(i32::MAX), Some(-3), Some(-3), Some(3), None]
+        );
+        // rounds towards negative infinity, `/` truncates towards zero
+        let out = a.floor_div(&b)?;
+        assert_eq!(
+            Vec::from(out.i32()?),
+            &[Some(i32::MAX), Some(-4), Some(-4), Some(3), None]
+        );
+        let out = &a.slice(1, 3) / &b.slice(1, 3);
+        assert_eq!(Vec::from(out.i32()?), &[Some(-3), Some(-3), Some(3)]);
+
+        // broadcasting and division by zero
+        let zero = Series::new("zero", [0i32]);
+        let out = a.floor_div(&zero)?;
+        assert_eq!(out.null_count(), 5);
+        let out = b.checked_mul(&Series::new("", [3i64]))?;
+        assert_eq!(out.dtype(), &DataType::Int64);
+        assert_eq!(
+            Vec::from(out.i64()?),
+            &[Some(3), Some(6), Some(-6), Some(-6), Some(-3)]
+        );
+
+        let out = Series::new("f", [-7.0f64, 7.0]).floor_div(&Series::new("", [2.0f64]))?;
+        assert_eq!(Vec::from(out.f64()?), &[Some(-4.0), Some(3.0)]);
+
+        // nulls in the input are not reported as overflow
+        let a = Series::new("a", [Some(1i32), None, Some(i32::MAX), Some(i32::MAX)]);
+        let b = Series::new("b", [1i32, 1, 1, 1]);
+        let out = a.checked_add(&b)?;
+        let err = check_overflow(&a, &b, &out).unwrap_err();
+        assert!(format!("{:?}", err).contains("row 2"));
+        let out = a.checked_sub(&b)?;
+        assert!(check_overflow(&a, &b, &out).is_ok());
+        Ok(())
+    }
 }
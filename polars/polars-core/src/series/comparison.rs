@@ -2,8 +2,11 @@
 
 use super::Series;
 use crate::apply_method_numeric_series;
+use crate::chunked_array::builder::categorical::RevMapping;
 use crate::prelude::*;
 use crate::series::arithmetic::coerce_lhs_rhs;
+use arrow::array::Array;
+use std::ops::Deref;
 
 macro_rules! impl_compare {
     ($self:expr, $rhs:expr, $method:ident) => {{
@@ -38,46 +41,209 @@ macro_rules! impl_compare {
                 .unwrap()
                 .$method(rhs.duration_millisecond().unwrap()),
             DataType::List(_) => lhs.list().unwrap().$method(rhs.list().unwrap()),
+            // compare the string values
+            DataType::Categorical => {
+                let lhs = lhs.cast_with_dtype(&DataType::Utf8).unwrap();
+                let rhs = rhs.cast_with_dtype(&DataType::Utf8).unwrap();
+                lhs.utf8().unwrap().$method(rhs.utf8().unwrap())
+            }
             _ => unimplemented!(),
         }
     }};
 }
 
-fn compare_cat_to_str_value<Compare>(
-    cat: &Series,
-    value: &str,
-    name: &str,
-    compare: Compare,
-    fill_value: bool,
-) -> BooleanChunked
-where
-    Compare: Fn(&Series, u32) -> BooleanChunked,
-{
+/// A comparison of a Categorical by the string values of the categories.
+#[derive(Copy, Clone, PartialEq)]
+enum StrCompare {
+    Eq,
+    NotEq,
+    Gt,
+    GtEq,
+    Lt,
+    LtEq,
+}
+
+impl StrCompare {
+    fn compare(self, lhs: &str, rhs: &str) -> bool {
+        match self {
+            StrCompare::Eq => lhs == rhs,
+            StrCompare::NotEq => lhs != rhs,
+            StrCompare::Gt => lhs > rhs,
+            StrCompare::GtEq => lhs >= rhs,
+            StrCompare::Lt => lhs < rhs,
+            StrCompare::LtEq => lhs <= rhs,
+        }
+    }
+
+    /// The comparison with the operands swapped.
+    fn swap(self) -> Self {
+        match self {
+            StrCompare::Gt => StrCompare::Lt,
+            StrCompare::GtEq => StrCompare::LtEq,
+            StrCompare::Lt => StrCompare::Gt,
+            StrCompare::LtEq => StrCompare::GtEq,
+            op => op,
+        }
+    }
+
+    fn is_equality(self) -> bool {
+        matches!(self, StrCompare::Eq | StrCompare::NotEq)
+    }
+
+    fn compare_series(self, lhs: &Series, rhs: &Series) -> BooleanChunked {
+        match self {
+            StrCompare::Eq => lhs.eq(rhs),
+            StrCompare::NotEq => lhs.neq(rhs),
+            StrCompare::Gt => lhs.gt(rhs),
+            StrCompare::GtEq => lhs.gt_eq(rhs),
+            StrCompare::Lt => lhs.lt(rhs),
+            StrCompare::LtEq => lhs.lt_eq(rhs),
+        }
+    }
+
+    fn compare_utf8_value(self, lhs: &Utf8Chunked, rhs: &str) -> BooleanChunked {
+        match self {
+            StrCompare::Eq => lhs.eq(rhs),
+            StrCompare::NotEq => lhs.neq(rhs),
+            StrCompare::Gt => lhs.gt(rhs),
+            StrCompare::GtEq => lhs.gt_eq(rhs),
+            StrCompare::Lt => lhs.lt(rhs),
+            StrCompare::LtEq => lhs.lt_eq(rhs),
+        }
+    }
+}
+
+/// The categories with their physical index.
+fn categories(map: &RevMapping) -> Box<dyn Iterator<Item = (u32, &str)> + '_> {
+    match map {
+        RevMapping::Local(a) => Box::new((0..a.len()).map(move |i| (i as u32, a.value(i)))),
+        RevMapping::Global(idx_map, a, _, _) => Box::new(
+            idx_map
+                .iter()
+                .map(move |(idx, i)| (*idx, a.value(*i as usize))),
+        ),
+    }
+}
+
+/// Compare every category with `value` once, the outcomes are looked up per row by the physical
+/// index of the category.
+fn compare_categories(cat: &CategoricalChunked, value: &str, op: StrCompare) -> BooleanChunked {
+    let map = match cat.get_categorical_map() {
+        Some(map) => map,
+        // no categories, so all values are null
+        None => return BooleanChunked::full_null(cat.name(), cat.len()),
+    };
+    let len = categories(map)
+        .map(|(idx, _)| idx as usize + 1)
+        .max()
+        .unwrap_or(0);
+    let mut table = vec![false; len];
+    for (idx, category) in categories(map) {
+        table[idx as usize] = op.compare(category, value);
+    }
+    let codes: &UInt32Chunked = cat.deref();
+    codes
+        .into_iter()
+        .map(|opt_idx| opt_idx.map(|idx| table[idx as usize]))
+        .collect()
+}
+
+/// Compare a Categorical with a string value. For (in)equality the value is looked up in the
+/// categories once and the physical indexes are compared.
+fn compare_cat_to_str_value(cat: &Series, value: &str, op: StrCompare) -> BooleanChunked {
     let cat = cat.categorical().expect("should be categorical");
-    let cat_map = cat.get_categorical_map().unwrap();
-    match cat_map.find(value) {
-        None => BooleanChunked::full(name, fill_value, cat.len()),
-        Some(cat_idx) => {
-            let cat = cat.cast_with_dtype(&DataType::UInt32).unwrap();
-            compare(&cat, cat_idx)
+    let idx = match cat.get_categorical_map() {
+        Some(map) if op.is_equality() => map.find(value),
+        _ => None,
+    };
+    let codes: &UInt32Chunked = cat.deref();
+    let mut out = match (op, idx) {
+        (StrCompare::Eq, Some(idx)) => codes.eq(idx),
+        (StrCompare::NotEq, Some(idx)) => codes.neq(idx),
+        // not a category, or an ordering of the strings
+        _ => compare_categories(cat, value, op),
+    };
+    out.rename(cat.name());
+    out
+}
+
+/// Compare a Categorical with a Utf8 column by the string values.
+///
+/// For (in)equality the strings are translated to the physical indexes of the categories, if
+/// there are less categories than rows. Otherwise the categories of the rows are materialized
+/// as strings.
+fn compare_cat_to_str_series(cat: &Series, string: &Series, op: StrCompare) -> BooleanChunked {
+    let string_ca = string.utf8().expect("should be utf8 column");
+    if string.len() == 1 {
+        return match string_ca.get(0) {
+            Some(value) => compare_cat_to_str_value(cat, value, op),
+            None => match op {
+                StrCompare::Eq => cat.is_null(),
+                StrCompare::NotEq => cat.is_not_null(),
+                _ => BooleanChunked::full(cat.name(), false, cat.len()),
+            },
+        };
+    }
+    let ca = cat.categorical().expect("should be categorical");
+    match ca.get_categorical_map() {
+        Some(map) if op.is_equality() && map.len() <= string.len() && cat.len() == string.len() => {
+            let index: PlHashMap<&str, u32> = categories(map).map(|(idx, s)| (s, idx)).collect();
+            let eq = op == StrCompare::Eq;
+            let codes: &UInt32Chunked = ca.deref();
+            // a string that isn't a category is not equal to any row
+            codes
+                .into_iter()
+                .zip(string_ca.into_iter())
+                .map(|(opt_idx, opt_s)| match (opt_idx, opt_s) {
+                    (Some(idx), Some(s)) => Some((index.get(s) == Some(&idx)) == eq),
+                    _ => None,
+                })
+                .collect()
+        }
+        _ => {
+            let cat_strings = cat.cast_with_dtype(&DataType::Utf8).unwrap();
+            match cat_strings.utf8().unwrap().get(0) {
+                // broadcast the single category
+                Some(value) if cat.len() == 1 => op.swap().compare_utf8_value(string_ca, value),
+                _ => op.compare_series(&cat_strings, string),
+            }
         }
     }
 }
 
-fn compare_cat_to_str_series<Compare>(
-    cat: &Series,
-    string: &Series,
-    name: &str,
-    compare: Compare,
-    fill_value: bool,
-) -> BooleanChunked
-where
-    Compare: Fn(&Series, u32) -> BooleanChunked,
-{
-    match string.utf8().expect("should be utf8 column").get(0) {
-        None => cat.is_null(),
-        Some(value) => compare_cat_to_str_value(cat, value, name, compare, fill_value),
+/// Compare two Categoricals. The physical indexes are compared for (in)equality if both have
+/// the same categories for the same indexes, otherwise the string values are compared.
+fn compare_cat_to_cat(lhs: &Series, rhs: &Series, op: StrCompare) -> BooleanChunked {
+    let lhs_ca = lhs.categorical().unwrap();
+    let rhs_ca = rhs.categorical().unwrap();
+    if let (Some(l), Some(r)) = (lhs_ca.get_categorical_map(), rhs_ca.get_categorical_map()) {
+        let compatible = Arc::ptr_eq(l, r) || l.same_src(r) || l.extends(r) || r.extends(l);
+        if op.is_equality() && compatible {
+            let lhs_codes: &UInt32Chunked = lhs_ca.deref();
+            let rhs_codes: &UInt32Chunked = rhs_ca.deref();
+            return match op {
+                StrCompare::Eq => lhs_codes.eq(rhs_codes),
+                _ => lhs_codes.neq(rhs_codes),
+            };
+        }
     }
+    let lhs = lhs.cast_with_dtype(&DataType::Utf8).unwrap();
+    let rhs = rhs.cast_with_dtype(&DataType::Utf8).unwrap();
+    op.compare_series(&lhs, &rhs)
+}
+
+/// Compare by the string values if either side is Categorical and the other side is
+/// Categorical or Utf8.
+fn compare_categorical(lhs: &Series, rhs: &Series, op: StrCompare) -> Option<BooleanChunked> {
+    use DataType::*;
+    let mut out = match (lhs.dtype(), rhs.dtype()) {
+        (Categorical, Utf8) => compare_cat_to_str_series(lhs, rhs, op),
+        (Utf8, Categorical) => compare_cat_to_str_series(rhs, lhs, op.swap()),
+        (Categorical, Categorical) => compare_cat_to_cat(lhs, rhs, op),
+        _ => return None,
+    };
+    out.rename(lhs.name());
+    Some(out)
 }
 
 impl ChunkCompare<&Series> for Series {
@@ -87,78 +253,38 @@ impl ChunkCompare<&Series> for Series {
 
     /// Create a boolean mask by checking for equality.
     fn eq(&self, rhs: &Series) -> BooleanChunked {
-        use DataType::*;
-        match (self.dtype(), rhs.dtype(), self.len(), rhs.len()) {
-            (Categorical, Utf8, _, 1) => {
-                return compare_cat_to_str_series(
-                    self,
-                    rhs,
-                    self.name(),
-                    |s, idx| s.eq(idx),
-                    false,
-                );
-            }
-            (Utf8, Categorical, 1, _) => {
-                return compare_cat_to_str_series(
-                    rhs,
-                    self,
-                    self.name(),
-                    |s, idx| s.eq(idx),
-                    false,
-                );
-            }
-            _ => {
-                impl_compare!(self, rhs, eq)
-            }
-        }
+        compare_categorical(self, rhs, StrCompare::Eq)
+            .unwrap_or_else(|| impl_compare!(self, rhs, eq))
     }
 
     /// Create a boolean mask by checking for inequality.
     fn neq(&self, rhs: &Series) -> BooleanChunked {
-        use DataType::*;
-        match (self.dtype(), rhs.dtype(), self.len(), rhs.len()) {
-            (Categorical, Utf8, _, 1) => {
-                return compare_cat_to_str_series(
-                    self,
-                    rhs,
-                    self.name(),
-                    |s, idx| s.neq(idx),
-                    true,
-                );
-            }
-            (Utf8, Categorical, 1, _) => {
-                return compare_cat_to_str_series(
-                    rhs,
-                    self,
-                    self.name(),
-                    |s, idx| s.neq(idx),
-                    true,
-                );
-            }
-            _ => {
-                impl_compare!(self, rhs, neq)
-            }
-        }
+        compare_categorical(self, rhs, StrCompare::NotEq)
+            .unwrap_or_else(|| impl_compare!(self, rhs, neq))
     }
 
     /// Create a boolean mask by checking if self > rhs.
     fn gt(&self, rhs: &Series) -> BooleanChunked {
-        impl_compare!(self, rhs, gt)
+        compare_categorical(self, rhs, StrCompare::Gt)
+            .unwrap_or_else(|| impl_compare!(self, rhs, gt))
     }
 
     /// Create a boolean mask by checking if self >= rhs.
     fn gt_eq(&self, rhs: &Series) -> BooleanChunked {
-        impl_compare!(self, rhs, gt_eq)
+        compare_categorical(self, rhs, StrCompare::GtEq)
+            .unwrap_or_else(|| impl_compare!(self, rhs, gt_eq))
     }
 
     /// Create a boolean mask by checking if self < rhs.
     fn lt(&self, rhs: &Series) -> BooleanChunked {
-        impl_compare!(self, rhs, lt)
+        compare_categorical(self, rhs, StrCompare::Lt)
+            .unwrap_or_else(|| impl_compare!(self, rhs, lt))
     }
 
     /// Create a boolean mask by checking if self <= rhs.
     fn lt_eq(&self, rhs: &Series) -> BooleanChunked {
-        impl_compare!(self, rhs, lt_eq)
+        compare_categorical(self, rhs, StrCompare::LtEq)
+            .unwrap_or_else(|| impl_compare!(self, rhs, lt_eq))
     }
 }
 
@@ -204,9 +330,7 @@ impl ChunkCompare<&str> for Series {
         use DataType::*;
         match self.dtype() {
             Utf8 => self.utf8().unwrap().eq(rhs),
-            Categorical => {
-                compare_cat_to_str_value(self, rhs, self.name(), |lhs, idx| lhs.eq(idx), false)
-            }
+            Categorical => compare_cat_to_str_value(self, rhs, StrCompare::Eq),
             _ => BooleanChunked::full(self.name(), false, self.len()),
         }
     }
@@ -215,42 +339,165 @@ impl ChunkCompare<&str> for Series {
         use DataType::*;
         match self.dtype() {
             Utf8 => self.utf8().unwrap().neq(rhs),
-            Categorical => {
-                compare_cat_to_str_value(self, rhs, self.name(), |lhs, idx| lhs.neq(idx), true)
-            }
+            Categorical => compare_cat_to_str_value(self, rhs, StrCompare::NotEq),
             _ => BooleanChunked::full(self.name(), false, self.len()),
         }
     }
 
     fn gt(&self, rhs: &str) -> BooleanChunked {
-        if let Ok(a) = self.utf8() {
-            a.gt(rhs)
-        } else {
-            BooleanChunked::full(self.name(), false, self.len())
-        }
+        compare_str_value(self, rhs, StrCompare::Gt)
     }
 
     fn gt_eq(&self, rhs: &str) -> BooleanChunked {
-        if let Ok(a) = self.utf8() {
-            a.gt_eq(rhs)
-        } else {
-            BooleanChunked::full(self.name(), false, self.len())
-        }
+        compare_str_value(self, rhs, StrCompare::GtEq)
     }
 
     fn lt(&self, rhs: &str) -> BooleanChunked {
-        if let Ok(a) = self.utf8() {
-            a.lt(rhs)
-        } else {
-            BooleanChunked::full(self.name(), false, self.len())
-        }
+        compare_str_value(self, rhs, StrCompare::Lt)
     }
 
     fn lt_eq(&self, rhs: &str) -> BooleanChunked {
-        if let Ok(a) = self.utf8() {
-            a.lt_eq(rhs)
-        } else {
-            BooleanChunked::full(self.name(), false, self.len())
+        compare_str_value(self, rhs, StrCompare::LtEq)
+    }
+}
+
+fn compare_str_value(s: &Series, value: &str, op: StrCompare) -> BooleanChunked {
+    match s.dtype() {
+        DataType::Utf8 => op.compare_utf8_value(s.utf8().unwrap(), value),
+        DataType::Categorical => compare_cat_to_str_value(s, value, op),
+        _ => BooleanChunked::full(s.name(), false, s.len()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    use crate::{reset_string_cache, toggle_string_cache, SINGLE_LOCK};
+
+    fn to_vec(ca: &BooleanChunked) -> Vec<Option<bool>> {
+        ca.into_iter().collect()
+    }
+
+    fn cat(name: &str, values: &[Option<&str>]) -> Series {
+        Series::new(name, values)
+            .cast_with_dtype(&DataType::Categorical)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_categorical_compare_str() {
+        let _lock = SINGLE_LOCK.lock();
+        for &use_cache in &[false, true] {
+            reset_string_cache();
+            toggle_string_cache(use_cache);
+            if use_cache {
+                // the global indexes don't start at 0
+                let _ = cat("other", &[Some("x"), Some("y")]);
+            }
+            let s = cat("a", &[Some("b"), None, Some("a"), Some("c"), Some("b")]);
+
+            assert_eq!(
+                to_vec(&s.eq("b")),
+                &[Some(true), None, Some(false), Some(false), Some(true)]
+            );
+            assert_eq!(
+                to_vec(&s.neq("b")),
+                &[Some(false), None, Some(true), Some(true), Some(false)]
+            );
+            // not a category
+            assert_eq!(
+                to_vec(&s.eq("z")),
+                &[Some(false), None, Some(false), Some(false), Some(false)]
+            );
+            assert_eq!(
+                to_vec(&s.neq("z")),
+                &[Some(true), None, Some(true), Some(true), Some(true)]
+            );
+            // ordering by the string values
+            assert_eq!(
+                to_vec(&s.gt("a")),
+                &[Some(true), None, Some(false), Some(true), Some(true)]
+            );
+            assert_eq!(
+                to_vec(&s.lt_eq("b")),
+                &[Some(true), None, Some(true), Some(false), Some(true)]
+            );
+
+            // a single string column is broadcast, on both sides
+            let value = Series::new("v", &["c"]);
+            assert_eq!(to_vec(&s.eq(&value)), to_vec(&s.eq("c")));
+            assert_eq!(to_vec(&value.gt(&s)), to_vec(&s.lt("c")));
+            let null = Series::new("v", &[None::<&str>]);
+            assert_eq!(to_vec(&s.eq(&null)), to_vec(&s.is_null()));
+            assert_eq!(to_vec(&s.neq(&null)), to_vec(&s.is_not_null()));
         }
+        toggle_string_cache(false);
+    }
+
+    #[test]
+    fn test_categorical_compare_utf8_column() {
+        let s = cat("a", &[Some("b"), None, Some("a"), Some("c"), Some("b")]);
+        let strings = Series::new("s", &[Some("b"), Some("a"), Some("b"), Some("z"), None]);
+
+        assert_eq!(
+            to_vec(&s.eq(&strings)),
+            &[Some(true), None, Some(false), Some(false), None]
+        );
+        assert_eq!(
+            to_vec(&strings.neq(&s)),
+            &[Some(false), None, Some(true), Some(true), None]
+        );
+        assert_eq!(
+            to_vec(&s.gt(&strings)),
+            &[Some(false), None, Some(false), Some(false), None]
+        );
+        assert_eq!(
+            to_vec(&strings.gt(&s)),
+            &[Some(false), None, Some(true), Some(true), None]
+        );
+        // more categories than rows, the categories are compared as strings
+        let (s, strings) = (s.slice(0, 2), strings.slice(0, 2));
+        assert_eq!(to_vec(&s.eq(&strings)), &[Some(true), None]);
+        assert_eq!(to_vec(&strings.neq(&s)), &[Some(false), None]);
+    }
+
+    #[test]
+    fn test_categorical_compare_categorical() {
+        let _lock = SINGLE_LOCK.lock();
+        // local mappings with different categories
+        let a = cat("a", &[Some("x"), Some("y"), None, Some("z")]);
+        let b = cat("b", &[Some("y"), Some("y"), Some("x"), Some("z")]);
+        assert_eq!(
+            to_vec(&a.eq(&b)),
+            &[Some(false), Some(true), None, Some(true)]
+        );
+        assert_eq!(
+            to_vec(&a.neq(&b)),
+            &[Some(true), Some(false), None, Some(false)]
+        );
+        assert_eq!(
+            to_vec(&a.lt(&b)),
+            &[Some(true), Some(false), None, Some(false)]
+        );
+        // same mapping
+        let c = a.slice(1, 3);
+        let a2 = a.slice(0, 3);
+        assert_eq!(to_vec(&a2.eq(&c)), &[Some(false), None, None]);
+        assert_eq!(to_vec(&a.eq_missing(&a)), &[Some(true); 4]);
+
+        // global mapping
+        reset_string_cache();
+        toggle_string_cache(true);
+        let a = cat("a", &[Some("x"), Some("y"), None, Some("z")]);
+        let b = cat("b", &[Some("y"), Some("y"), Some("x"), Some("z")]);
+        assert_eq!(
+            to_vec(&a.eq(&b)),
+            &[Some(false), Some(true), None, Some(true)]
+        );
+        assert_eq!(
+            to_vec(&a.gt_eq(&b)),
+            &[Some(false), Some(true), None, Some(true)]
+        );
+        toggle_string_cache(false);
     }
 }
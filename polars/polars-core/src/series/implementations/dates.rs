@@ -10,7 +10,7 @@ use super::private;
 use super::IntoSeries;
 use super::SeriesTrait;
 use super::SeriesWrap;
-use crate::chunked_array::{comparison::*, AsSinglePtr, ChunkIdIter};
+use crate::chunked_array::{comparison::*, AsSinglePtr, ChunkIdIter, Statistics};
 use crate::fmt::FmtList;
 #[cfg(feature = "pivot")]
 use crate::frame::groupby::pivot::*;
@@ -34,6 +34,15 @@ impl<T> ChunkedArray<T> {
     }
 }
 
+/// Get the physical values of a `Duration` Series in milliseconds.
+fn duration_as_millis(s: &Series) -> Result<Series> {
+    let physical = s.cast_with_dtype(&DataType::Int64)?;
+    match s.dtype() {
+        DataType::Duration(TimeUnit::Nanosecond) => Ok(&physical / 1_000_000),
+        _ => Ok(physical),
+    }
+}
+
 /// Dispatch the method call to the physical type and coerce back to logical type
 macro_rules! physical_dispatch {
     ($s: expr, $method: ident, $($args:expr),*) => {{
@@ -108,7 +117,7 @@ macro_rules! impl_dyn_series {
 
         impl private::PrivateSeries for SeriesWrap<$ca> {
             #[cfg(feature = "asof_join")]
-            fn join_asof(&self, other: &Series) -> Result<Vec<Option<u32>>> {
+            fn join_asof(&self, other: &Series) -> Result<Vec<Option<IdxSize>>> {
                 cast_and_apply!(self, join_asof, other)
             }
 
@@ -116,6 +125,18 @@ macro_rules! impl_dyn_series {
                 self.0.set_sorted(reverse)
             }
 
+            fn sorted_flag(&self, reverse: bool) -> bool {
+                if reverse {
+                    self.0.is_sorted_reverse()
+                } else {
+                    self.0.is_sorted()
+                }
+            }
+
+            fn statistics(&self) -> Option<&Statistics> {
+                Some(self.0.statistics())
+            }
+
             unsafe fn equal_element(
                 &self,
                 idx_self: usize,
@@ -138,60 +159,73 @@ macro_rules! impl_dyn_series {
                 cast_and_apply!(self, vec_hash_combine, build_hasher, hashes)
             }
 
-            fn agg_mean(&self, _groups: &[(u32, Vec<u32>)]) -> Option<Series> {
-                // does not make sense on dates
-                None
+            fn agg_mean(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
+                match self.dtype() {
+                    // the mean of a duration is truncated to the time unit
+                    DataType::Duration(_) => cast_and_apply!(self, agg_mean, groups)
+                        .map(|s| s.cast_with_dtype(&DataType::Int64).unwrap())
+                        .map(|s| s.cast_with_dtype(self.dtype()).unwrap()),
+                    // does not make sense on dates
+                    _ => None,
+                }
             }
 
-            fn agg_min(&self, groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+            fn agg_min(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
                 opt_physical_dispatch!(self, agg_min, groups)
             }
 
-            fn agg_max(&self, groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+            fn agg_max(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
                 opt_physical_dispatch!(self, agg_max, groups)
             }
 
-            fn agg_sum(&self, _groups: &[(u32, Vec<u32>)]) -> Option<Series> {
-                // does not make sense on dates
-                None
+            fn agg_sum(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
+                match self.dtype() {
+                    DataType::Duration(_) => opt_physical_dispatch!(self, agg_sum, groups),
+                    // does not make sense on dates
+                    _ => None,
+                }
             }
 
-            fn agg_first(&self, groups: &[(u32, Vec<u32>)]) -> Series {
+            fn agg_first(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Series {
                 physical_dispatch!(self, agg_first, groups)
             }
 
-            fn agg_last(&self, groups: &[(u32, Vec<u32>)]) -> Series {
+            fn agg_last(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Series {
                 physical_dispatch!(self, agg_last, groups)
             }
 
-            fn agg_std(&self, _groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+            fn agg_std(&self, _groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
                 // does not make sense on dates
                 None
             }
 
-            fn agg_var(&self, _groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+            fn agg_var(&self, _groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
                 // does not make sense on dates
                 None
             }
 
-            fn agg_n_unique(&self, groups: &[(u32, Vec<u32>)]) -> Option<UInt32Chunked> {
+            fn agg_n_unique(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<UInt32Chunked> {
                 cast_and_apply!(self, agg_n_unique, groups)
             }
 
-            fn agg_list(&self, groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+            fn agg_list(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
                 // we cannot cast and dispatch as the inner type of the list would be incorrect
                 self.0.agg_list(groups)
             }
 
-            fn agg_quantile(&self, groups: &[(u32, Vec<u32>)], quantile: f64) -> Option<Series> {
+            fn agg_quantile(
+                &self,
+                groups: &[(IdxSize, Vec<IdxSize>)],
+                quantile: f64,
+            ) -> Option<Series> {
                 opt_physical_dispatch!(self, agg_quantile, groups, quantile)
             }
 
-            fn agg_median(&self, groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+            fn agg_median(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
                 opt_physical_dispatch!(self, agg_median, groups)
             }
             #[cfg(feature = "lazy")]
-            fn agg_valid_count(&self, groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+            fn agg_valid_count(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
                 opt_physical_dispatch!(self, agg_valid_count, groups)
             }
 
@@ -200,7 +234,7 @@ macro_rules! impl_dyn_series {
                 &self,
                 pivot_series: &'a Series,
                 keys: Vec<Series>,
-                groups: &[(u32, Vec<u32>)],
+                groups: &[(IdxSize, Vec<IdxSize>)],
                 agg_type: PivotAgg,
             ) -> Result<DataFrame> {
                 self.0.pivot(pivot_series, keys, groups, agg_type)
@@ -211,26 +245,26 @@ macro_rules! impl_dyn_series {
                 &self,
                 pivot_series: &'a Series,
                 keys: Vec<Series>,
-                groups: &[(u32, Vec<u32>)],
+                groups: &[(IdxSize, Vec<IdxSize>)],
             ) -> Result<DataFrame> {
                 self.0.pivot_count(pivot_series, keys, groups)
             }
-            fn hash_join_inner(&self, other: &Series) -> Vec<(u32, u32)> {
+            fn hash_join_inner(&self, other: &Series) -> Vec<(IdxSize, IdxSize)> {
                 let other = other.to_physical_repr();
                 cast_and_apply!(self, hash_join_inner, &other)
             }
-            fn hash_join_left(&self, other: &Series) -> Vec<(u32, Option<u32>)> {
+            fn hash_join_left(&self, other: &Series) -> Vec<(IdxSize, Option<IdxSize>)> {
                 let other = other.to_physical_repr();
                 cast_and_apply!(self, hash_join_left, &other)
             }
-            fn hash_join_outer(&self, other: &Series) -> Vec<(Option<u32>, Option<u32>)> {
+            fn hash_join_outer(&self, other: &Series) -> Vec<(Option<IdxSize>, Option<IdxSize>)> {
                 let other = other.to_physical_repr();
                 cast_and_apply!(self, hash_join_outer, &other)
             }
             fn zip_outer_join_column(
                 &self,
                 right_column: &Series,
-                opt_join_tuples: &[(Option<u32>, Option<u32>)],
+                opt_join_tuples: &[(Option<IdxSize>, Option<IdxSize>)],
             ) -> Series {
                 let right_column = right_column.to_physical_repr();
                 physical_dispatch!(self, zip_outer_join_column, &right_column, opt_join_tuples)
@@ -245,7 +279,18 @@ macro_rules! impl_dyn_series {
                     (DataType::Date64, DataType::Date64) => {
                         let lhs = self.cast_with_dtype(&DataType::Int64).unwrap();
                         let rhs = rhs.cast_with_dtype(&DataType::Int64).unwrap();
-                        Ok(lhs.subtract(&rhs)?.into_series())
+                        lhs.subtract(&rhs)?
+                            .cast_with_dtype(&DataType::Duration(TimeUnit::Millisecond))
+                    }
+                    (DataType::Date64, DataType::Duration(_)) => {
+                        let lhs = self.cast_with_dtype(&DataType::Int64).unwrap();
+                        let rhs = duration_as_millis(rhs)?;
+                        lhs.subtract(&rhs)?.cast_with_dtype(&DataType::Date64)
+                    }
+                    (DataType::Duration(tu_l), DataType::Duration(tu_r)) if tu_l == tu_r => {
+                        let lhs = self.cast_with_dtype(&DataType::Int64).unwrap();
+                        let rhs = rhs.cast_with_dtype(&DataType::Int64).unwrap();
+                        lhs.subtract(&rhs)?.cast_with_dtype(self.dtype())
                     }
                     (dtl, dtr) => Err(PolarsError::Other(
                         format!(
@@ -256,16 +301,51 @@ macro_rules! impl_dyn_series {
                     )),
                 }
             }
-            fn add_to(&self, _rhs: &Series) -> Result<Series> {
-                Err(PolarsError::Other("cannot do addition on dates".into()))
+            fn add_to(&self, rhs: &Series) -> Result<Series> {
+                match (self.dtype(), rhs.dtype()) {
+                    (DataType::Date64, DataType::Duration(_)) => {
+                        let lhs = self.cast_with_dtype(&DataType::Int64).unwrap();
+                        let rhs = duration_as_millis(rhs)?;
+                        lhs.add_to(&rhs)?.cast_with_dtype(&DataType::Date64)
+                    }
+                    (DataType::Duration(_), DataType::Date64) => {
+                        rhs.add_to(&self.0.clone().into_series())
+                    }
+                    (DataType::Duration(tu_l), DataType::Duration(tu_r)) if tu_l == tu_r => {
+                        let lhs = self.cast_with_dtype(&DataType::Int64).unwrap();
+                        let rhs = rhs.cast_with_dtype(&DataType::Int64).unwrap();
+                        lhs.add_to(&rhs)?.cast_with_dtype(self.dtype())
+                    }
+                    (dtl, dtr) => Err(PolarsError::Other(
+                        format!(
+                            "cannot do addition on these date types: {:?}, {:?}",
+                            dtl, dtr
+                        )
+                        .into(),
+                    )),
+                }
             }
-            fn multiply(&self, _rhs: &Series) -> Result<Series> {
-                Err(PolarsError::Other(
-                    "cannot do multiplication on dates".into(),
-                ))
+            fn multiply(&self, rhs: &Series) -> Result<Series> {
+                match self.dtype() {
+                    DataType::Duration(_) if rhs.dtype().is_integer() => {
+                        let lhs = self.cast_with_dtype(&DataType::Int64).unwrap();
+                        let rhs = rhs.cast_with_dtype(&DataType::Int64)?;
+                        lhs.multiply(&rhs)?.cast_with_dtype(self.dtype())
+                    }
+                    _ => Err(PolarsError::Other(
+                        "cannot do multiplication on dates".into(),
+                    )),
+                }
             }
-            fn divide(&self, _rhs: &Series) -> Result<Series> {
-                Err(PolarsError::Other("cannot do division on dates".into()))
+            fn divide(&self, rhs: &Series) -> Result<Series> {
+                match self.dtype() {
+                    DataType::Duration(_) if rhs.dtype().is_integer() => {
+                        let lhs = self.cast_with_dtype(&DataType::Int64).unwrap();
+                        let rhs = rhs.cast_with_dtype(&DataType::Int64)?;
+                        lhs.divide(&rhs)?.cast_with_dtype(self.dtype())
+                    }
+                    _ => Err(PolarsError::Other("cannot do division on dates".into())),
+                }
             }
             fn remainder(&self, _rhs: &Series) -> Result<Series> {
                 Err(PolarsError::Other(
@@ -276,7 +356,7 @@ macro_rules! impl_dyn_series {
                 cast_and_apply!(self, group_tuples, multithreaded)
             }
             #[cfg(feature = "sort_multiple")]
-            fn argsort_multiple(&self, by: &[Series], reverse: &[bool]) -> Result<UInt32Chunked> {
+            fn argsort_multiple(&self, by: &[Series], reverse: &[bool]) -> Result<IdxCa> {
                 let phys_type = self.0.physical_type();
                 let s = self.cast_with_dtype(&phys_type).unwrap();
 
@@ -444,7 +524,7 @@ macro_rules! impl_dyn_series {
                 try_physical_dispatch!(self, filter, filter)
             }
 
-            fn take(&self, indices: &UInt32Chunked) -> Series {
+            fn take(&self, indices: &IdxCa) -> Series {
                 physical_dispatch!(self, take, indices)
             }
 
@@ -460,7 +540,7 @@ macro_rules! impl_dyn_series {
                 physical_dispatch!(self, take_iter_unchecked, iter)
             }
 
-            unsafe fn take_unchecked(&self, idx: &UInt32Chunked) -> Result<Series> {
+            unsafe fn take_unchecked(&self, idx: &IdxCa) -> Result<Series> {
                 try_physical_dispatch!(self, take_unchecked, idx)
             }
 
@@ -499,8 +579,8 @@ macro_rules! impl_dyn_series {
                 self.0.cast_with_dtype(data_type)
             }
 
-            fn to_dummies(&self) -> Result<DataFrame> {
-                cast_and_apply!(self, to_dummies,)
+            fn to_dummies_with_options(&self, options: &DummiesOptions) -> Result<DataFrame> {
+                cast_and_apply!(self, to_dummies_with_options, options)
             }
 
             fn value_counts(&self) -> Result<DataFrame> {
@@ -524,7 +604,7 @@ macro_rules! impl_dyn_series {
                 physical_dispatch!(self, sort, reverse)
             }
 
-            fn argsort(&self, reverse: bool) -> UInt32Chunked {
+            fn argsort(&self, reverse: bool) -> IdxCa {
                 cast_and_apply!(self, argsort, reverse)
             }
 
@@ -540,7 +620,7 @@ macro_rules! impl_dyn_series {
                 cast_and_apply!(self, n_unique,)
             }
 
-            fn arg_unique(&self) -> Result<UInt32Chunked> {
+            fn arg_unique(&self) -> Result<IdxCa> {
                 cast_and_apply!(self, arg_unique,)
             }
 
@@ -552,7 +632,7 @@ macro_rules! impl_dyn_series {
                 cast_and_apply!(self, arg_max,)
             }
 
-            fn arg_true(&self) -> Result<UInt32Chunked> {
+            fn arg_true(&self) -> Result<IdxCa> {
                 let ca: &BooleanChunked = self.bool()?;
                 Ok(ca.arg_true())
             }
@@ -594,7 +674,10 @@ macro_rules! impl_dyn_series {
             }
 
             fn sum_as_series(&self) -> Series {
-                panic!("cannot compute sum of dates")
+                match self.dtype() {
+                    DataType::Duration(_) => physical_dispatch!(self, sum_as_series,),
+                    _ => panic!("cannot compute sum of dates"),
+                }
             }
             fn max_as_series(&self) -> Series {
                 physical_dispatch!(self, max_as_series,)
@@ -603,7 +686,14 @@ macro_rules! impl_dyn_series {
                 physical_dispatch!(self, min_as_series,)
             }
             fn mean_as_series(&self) -> Series {
-                panic!("cannot compute mean of dates")
+                match self.dtype() {
+                    DataType::Duration(_) => cast_and_apply!(self, mean_as_series,)
+                        .cast_with_dtype(&DataType::Int64)
+                        .unwrap()
+                        .cast_with_dtype(self.dtype())
+                        .unwrap(),
+                    _ => panic!("cannot compute mean of dates"),
+                }
             }
             fn median_as_series(&self) -> Series {
                 panic!("cannot compute median of dates")
@@ -716,6 +806,14 @@ macro_rules! impl_dyn_series {
             fn repeat_by(&self, by: &UInt32Chunked) -> ListChunked {
                 RepeatBy::repeat_by(&self.0, by)
             }
+            #[cfg(feature = "search_sorted")]
+            fn search_sorted_with_options(
+                &self,
+                values: &Series,
+                options: &SearchSortedOptions,
+            ) -> Result<IdxCa> {
+                ChunkSearchSorted::search_sorted(&self.0, values, options)
+            }
             #[cfg(feature = "is_first")]
             fn is_first(&self) -> Result<BooleanChunked> {
                 cast_and_apply!(self, is_first,)
@@ -811,7 +909,59 @@ mod test {
         let s = s.cast_with_dtype(&DataType::Date64)?;
 
         let out = s.subtract(&s)?;
-        assert!(matches!(out.dtype(), DataType::Int64));
+        assert!(matches!(
+            out.dtype(),
+            DataType::Duration(TimeUnit::Millisecond)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "dtype-date64", feature = "temporal"))]
+    fn test_duration_arithmetic() -> Result<()> {
+        let dates = Series::new("dates", &[0i64, 86_400_000, 172_800_000])
+            .cast_with_dtype(&DataType::Date64)?;
+        let offset = DurationMillisecondChunked::from_hours("offset", &[1, 2, 3]).into_series();
+
+        let shifted = &dates + &offset;
+        assert_eq!(shifted.dtype(), &DataType::Date64);
+        let diff = &shifted - &dates;
+        assert_eq!(diff.dtype(), &DataType::Duration(TimeUnit::Millisecond));
+        assert!(diff.series_equal(&offset));
+
+        let two = Series::new("two", &[2i32, 2, 2]);
+        let doubled = &two * &offset;
+        assert_eq!(doubled.dtype(), &DataType::Duration(TimeUnit::Millisecond));
+        assert!((&doubled - &offset).series_equal(&offset));
+        assert!((&doubled / &two).series_equal(&offset));
+        assert_eq!(
+            Vec::from(&doubled.gt(&offset)),
+            &[Some(true), Some(true), Some(true)]
+        );
+
+        let d = DurationMillisecondChunked::from_millis(
+            "d",
+            &[(2 * 24 + 3) * 3_600_000 + 900_000, 0, i64::MAX],
+        )
+        .into_series();
+        assert_eq!(format!("{}", d.get(0)), "2d 3h 15m");
+        assert_eq!(format!("{}", d.get(1)), "0ms");
+        // the value in nanoseconds doesn't fit in an i64
+        assert_eq!(format!("{}", d.get(2)), "106751991167d 7h 12m 55s 807ms");
+
+        let groups = vec![(0, vec![0, 1]), (2, vec![2])];
+        let sum = offset.agg_sum(&groups).unwrap();
+        assert_eq!(sum.dtype(), &DataType::Duration(TimeUnit::Millisecond));
+        assert_eq!(
+            sum.cast_with_dtype(&DataType::Int64)?.i64()?.get(0),
+            Some(3 * 3_600_000)
+        );
+        let mean = offset.agg_mean(&groups).unwrap();
+        assert_eq!(mean.dtype(), &DataType::Duration(TimeUnit::Millisecond));
+        assert_eq!(
+            mean.cast_with_dtype(&DataType::Int64)?.i64()?.get(0),
+            Some(5_400_000)
+        );
         Ok(())
     }
 }
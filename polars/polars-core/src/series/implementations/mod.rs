@@ -8,6 +8,8 @@
 pub mod dates;
 #[cfg(feature = "object")]
 pub mod object;
+#[cfg(feature = "dtype-struct")]
+pub mod struct_;
 #[cfg(feature = "object")]
 use std::any::Any;
 
@@ -20,7 +22,7 @@ use crate::chunked_array::{
         aggregate::{ChunkAggSeries, VarAggSeries},
         compare_inner::{IntoPartialEqInner, IntoPartialOrdInner, PartialEqInner, PartialOrdInner},
     },
-    AsSinglePtr, ChunkIdIter,
+    AsSinglePtr, ChunkIdIter, Statistics,
 };
 use crate::fmt::FmtList;
 #[cfg(feature = "asof_join")]
@@ -65,7 +67,7 @@ macro_rules! impl_dyn_series {
 
         impl private::PrivateSeries for SeriesWrap<$ca> {
             #[cfg(feature = "asof_join")]
-            fn join_asof(&self, other: &Series) -> Result<Vec<Option<u32>>> {
+            fn join_asof(&self, other: &Series) -> Result<Vec<Option<IdxSize>>> {
                 self.0.join_asof(other.as_ref().as_ref())
             }
 
@@ -73,6 +75,18 @@ macro_rules! impl_dyn_series {
                 self.0.set_sorted(reverse)
             }
 
+            fn sorted_flag(&self, reverse: bool) -> bool {
+                if reverse {
+                    self.0.is_sorted_reverse()
+                } else {
+                    self.0.is_sorted()
+                }
+            }
+
+            fn statistics(&self) -> Option<&Statistics> {
+                Some(self.0.statistics())
+            }
+
             unsafe fn equal_element(
                 &self,
                 idx_self: usize,
@@ -102,55 +116,59 @@ macro_rules! impl_dyn_series {
                 self.0.vec_hash_combine(build_hasher, hashes)
             }
 
-            fn agg_mean(&self, groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+            fn agg_mean(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
                 self.0.agg_mean(groups)
             }
 
-            fn agg_min(&self, groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+            fn agg_min(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
                 self.0.agg_min(groups)
             }
 
-            fn agg_max(&self, groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+            fn agg_max(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
                 self.0.agg_max(groups)
             }
 
-            fn agg_sum(&self, groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+            fn agg_sum(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
                 self.0.agg_sum(groups)
             }
 
-            fn agg_first(&self, groups: &[(u32, Vec<u32>)]) -> Series {
+            fn agg_first(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Series {
                 self.0.agg_first(groups)
             }
 
-            fn agg_last(&self, groups: &[(u32, Vec<u32>)]) -> Series {
+            fn agg_last(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Series {
                 self.0.agg_last(groups)
             }
 
-            fn agg_std(&self, groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+            fn agg_std(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
                 self.0.agg_std(groups)
             }
 
-            fn agg_var(&self, groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+            fn agg_var(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
                 self.0.agg_var(groups)
             }
 
-            fn agg_n_unique(&self, groups: &[(u32, Vec<u32>)]) -> Option<UInt32Chunked> {
+            fn agg_n_unique(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<UInt32Chunked> {
                 self.0.agg_n_unique(groups)
             }
 
-            fn agg_list(&self, groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+            fn agg_list(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
                 self.0.agg_list(groups)
             }
 
-            fn agg_quantile(&self, groups: &[(u32, Vec<u32>)], quantile: f64) -> Option<Series> {
+            fn agg_quantile(
+                &self,
+                groups: &[(IdxSize, Vec<IdxSize>)],
+                quantile: f64,
+            ) -> Option<Series> {
                 self.0.agg_quantile(groups, quantile)
             }
 
-            fn agg_median(&self, groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+            fn agg_median(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
                 self.0.agg_median(groups)
             }
             #[cfg(feature = "lazy")]
-            fn agg_valid_count(&self, groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+            fn agg_valid_count(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
                 self.0.agg_valid_count(groups)
             }
 
@@ -159,7 +177,7 @@ macro_rules! impl_dyn_series {
                 &self,
                 pivot_series: &'a Series,
                 keys: Vec<Series>,
-                groups: &[(u32, Vec<u32>)],
+                groups: &[(IdxSize, Vec<IdxSize>)],
                 agg_type: PivotAgg,
             ) -> Result<DataFrame> {
                 self.0.pivot(pivot_series, keys, groups, agg_type)
@@ -170,23 +188,23 @@ macro_rules! impl_dyn_series {
                 &self,
                 pivot_series: &'a Series,
                 keys: Vec<Series>,
-                groups: &[(u32, Vec<u32>)],
+                groups: &[(IdxSize, Vec<IdxSize>)],
             ) -> Result<DataFrame> {
                 self.0.pivot_count(pivot_series, keys, groups)
             }
-            fn hash_join_inner(&self, other: &Series) -> Vec<(u32, u32)> {
+            fn hash_join_inner(&self, other: &Series) -> Vec<(IdxSize, IdxSize)> {
                 HashJoin::hash_join_inner(&self.0, other.as_ref().as_ref())
             }
-            fn hash_join_left(&self, other: &Series) -> Vec<(u32, Option<u32>)> {
+            fn hash_join_left(&self, other: &Series) -> Vec<(IdxSize, Option<IdxSize>)> {
                 HashJoin::hash_join_left(&self.0, other.as_ref().as_ref())
             }
-            fn hash_join_outer(&self, other: &Series) -> Vec<(Option<u32>, Option<u32>)> {
+            fn hash_join_outer(&self, other: &Series) -> Vec<(Option<IdxSize>, Option<IdxSize>)> {
                 HashJoin::hash_join_outer(&self.0, other.as_ref().as_ref())
             }
             fn zip_outer_join_column(
                 &self,
                 right_column: &Series,
-                opt_join_tuples: &[(Option<u32>, Option<u32>)],
+                opt_join_tuples: &[(Option<IdxSize>, Option<IdxSize>)],
             ) -> Series {
                 ZipOuterJoinColumn::zip_outer_join_column(&self.0, right_column, opt_join_tuples)
             }
@@ -210,7 +228,7 @@ macro_rules! impl_dyn_series {
             }
 
             #[cfg(feature = "sort_multiple")]
-            fn argsort_multiple(&self, by: &[Series], reverse: &[bool]) -> Result<UInt32Chunked> {
+            fn argsort_multiple(&self, by: &[Series], reverse: &[bool]) -> Result<IdxCa> {
                 self.0.argsort_multiple(by, reverse)
             }
 
@@ -583,7 +601,7 @@ macro_rules! impl_dyn_series {
                 self.0.median()
             }
 
-            fn take(&self, indices: &UInt32Chunked) -> Series {
+            fn take(&self, indices: &IdxCa) -> Series {
                 let indices = if indices.chunks.len() > 1 {
                     Cow::Owned(indices.rechunk())
                 } else {
@@ -604,7 +622,7 @@ macro_rules! impl_dyn_series {
                 ChunkTake::take_unchecked(&self.0, iter.into()).into_series()
             }
 
-            unsafe fn take_unchecked(&self, idx: &UInt32Chunked) -> Result<Series> {
+            unsafe fn take_unchecked(&self, idx: &IdxCa) -> Result<Series> {
                 let idx = if idx.chunks.len() > 1 {
                     Cow::Owned(idx.rechunk())
                 } else {
@@ -648,8 +666,8 @@ macro_rules! impl_dyn_series {
                 self.0.cast_with_dtype(data_type)
             }
 
-            fn to_dummies(&self) -> Result<DataFrame> {
-                ToDummies::to_dummies(&self.0)
+            fn to_dummies_with_options(&self, options: &DummiesOptions) -> Result<DataFrame> {
+                ToDummies::to_dummies(&self.0, options)
             }
 
             fn value_counts(&self) -> Result<DataFrame> {
@@ -673,7 +691,7 @@ macro_rules! impl_dyn_series {
                 ChunkSort::sort(&self.0, reverse).into_series()
             }
 
-            fn argsort(&self, reverse: bool) -> UInt32Chunked {
+            fn argsort(&self, reverse: bool) -> IdxCa {
                 ChunkSort::argsort(&self.0, reverse)
             }
 
@@ -689,7 +707,7 @@ macro_rules! impl_dyn_series {
                 ChunkUnique::n_unique(&self.0)
             }
 
-            fn arg_unique(&self) -> Result<UInt32Chunked> {
+            fn arg_unique(&self) -> Result<IdxCa> {
                 ChunkUnique::arg_unique(&self.0)
             }
 
@@ -701,7 +719,7 @@ macro_rules! impl_dyn_series {
                 ArgAgg::arg_max(&self.0)
             }
 
-            fn arg_true(&self) -> Result<UInt32Chunked> {
+            fn arg_true(&self) -> Result<IdxCa> {
                 let ca: &BooleanChunked = self.bool()?;
                 Ok(ca.arg_true())
             }
@@ -860,11 +878,35 @@ macro_rules! impl_dyn_series {
             fn repeat_by(&self, by: &UInt32Chunked) -> ListChunked {
                 RepeatBy::repeat_by(&self.0, by)
             }
+            #[cfg(feature = "search_sorted")]
+            fn search_sorted_with_options(
+                &self,
+                values: &Series,
+                options: &SearchSortedOptions,
+            ) -> Result<IdxCa> {
+                ChunkSearchSorted::search_sorted(&self.0, values, options)
+            }
 
             #[cfg(feature = "checked_arithmetic")]
             fn checked_div(&self, rhs: &Series) -> Result<Series> {
                 self.0.checked_div(rhs)
             }
+            #[cfg(feature = "checked_arithmetic")]
+            fn checked_add(&self, rhs: &Series) -> Result<Series> {
+                self.0.checked_add(rhs)
+            }
+            #[cfg(feature = "checked_arithmetic")]
+            fn checked_sub(&self, rhs: &Series) -> Result<Series> {
+                self.0.checked_sub(rhs)
+            }
+            #[cfg(feature = "checked_arithmetic")]
+            fn checked_mul(&self, rhs: &Series) -> Result<Series> {
+                self.0.checked_mul(rhs)
+            }
+            #[cfg(feature = "checked_arithmetic")]
+            fn floor_div(&self, rhs: &Series) -> Result<Series> {
+                self.0.floor_div(rhs)
+            }
 
             #[cfg(feature = "is_first")]
             fn is_first(&self) -> Result<BooleanChunked> {
@@ -1,4 +1,4 @@
-use crate::chunked_array::ChunkIdIter;
+use crate::chunked_array::{ChunkIdIter, Statistics};
 use crate::fmt::FmtList;
 use crate::prelude::*;
 use crate::series::implementations::SeriesWrap;
@@ -28,12 +28,24 @@ impl<T> PrivateSeries for SeriesWrap<ObjectChunked<T>>
 where
     T: PolarsObject,
 {
+    fn statistics(&self) -> Option<&Statistics> {
+        Some(self.0.statistics())
+    }
+
     fn str_value(&self, index: usize) -> Cow<str> {
         match (&self.0).get(index) {
             None => Cow::Borrowed("null"),
             Some(val) => Cow::Owned(format!("{}", val)),
         }
     }
+
+    fn agg_first(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Series {
+        self.0.agg_first(groups)
+    }
+
+    fn agg_last(&self, groups: &[(IdxSize, Vec<IdxSize>)]) -> Series {
+        self.0.agg_last(groups)
+    }
 }
 #[cfg(feature = "object")]
 #[cfg_attr(docsrs, doc(cfg(feature = "object")))]
@@ -100,7 +112,7 @@ where
         ChunkTake::take_unchecked(&self.0, iter.into()).into_series()
     }
 
-    unsafe fn take_unchecked(&self, idx: &UInt32Chunked) -> Result<Series> {
+    unsafe fn take_unchecked(&self, idx: &IdxCa) -> Result<Series> {
         let idx = if idx.chunks.len() > 1 {
             Cow::Owned(idx.rechunk())
         } else {
@@ -116,8 +128,8 @@ where
         ChunkTake::take_unchecked(&self.0, SeriesWrap(iter).into()).into_series()
     }
 
-    fn take_opt_iter(&self, _iter: &mut dyn Iterator<Item = Option<usize>>) -> Series {
-        todo!()
+    fn take_opt_iter(&self, iter: &mut dyn Iterator<Item = Option<usize>>) -> Series {
+        ChunkTake::take(&self.0, SeriesWrap(iter).into()).into_series()
     }
 
     fn len(&self) -> usize {
@@ -150,8 +162,8 @@ where
         ))
     }
 
-    fn to_dummies(&self) -> Result<DataFrame> {
-        ToDummies::to_dummies(&self.0)
+    fn to_dummies_with_options(&self, options: &DummiesOptions) -> Result<DataFrame> {
+        ToDummies::to_dummies(&self.0, options)
     }
 
     fn value_counts(&self) -> Result<DataFrame> {
@@ -170,7 +182,7 @@ where
         ChunkSort::sort(&self.0, reverse).into_series()
     }
 
-    fn argsort(&self, reverse: bool) -> UInt32Chunked {
+    fn argsort(&self, reverse: bool) -> IdxCa {
         ChunkSort::argsort(&self.0, reverse)
     }
 
@@ -186,7 +198,7 @@ where
         ChunkUnique::n_unique(&self.0)
     }
 
-    fn arg_unique(&self) -> Result<UInt32Chunked> {
+    fn arg_unique(&self) -> Result<IdxCa> {
         ChunkUnique::arg_unique(&self.0)
     }
 
@@ -296,4 +308,30 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn test_expand_and_agg_object() {
+        let s = ObjectChunked::new_from_vec("a", vec![0i32, 1, 2]).into_series();
+
+        let expanded = s.expand_at_index(1, 4);
+        assert_eq!(expanded.len(), 4);
+        assert_eq!(expanded.name(), "a");
+        let ca = expanded
+            .as_any()
+            .downcast_ref::<ObjectChunked<i32>>()
+            .unwrap();
+        assert!((0..4).all(|i| ca.get(i) == Some(&1)));
+
+        let groups = vec![(0, vec![0, 2]), (1, vec![1])];
+        let first = s.agg_first(&groups);
+        let last = s.agg_last(&groups);
+        let first = first.as_any().downcast_ref::<ObjectChunked<i32>>().unwrap();
+        let last = last.as_any().downcast_ref::<ObjectChunked<i32>>().unwrap();
+        assert_eq!((first.get(0), first.get(1)), (Some(&0), Some(&1)));
+        assert_eq!((last.get(0), last.get(1)), (Some(&2), Some(&1)));
+
+        let nulls = ObjectChunked::<i32>::full_null("a", 3).into_series();
+        assert_eq!(nulls.null_count(), 3);
+        assert_eq!(nulls.expand_at_index(0, 5).null_count(), 5);
+    }
 }
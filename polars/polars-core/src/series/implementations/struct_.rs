@@ -0,0 +1,236 @@
+use crate::chunked_array::ChunkIdIter;
+use crate::fmt::FmtList;
+use crate::prelude::*;
+use crate::series::implementations::SeriesWrap;
+use crate::series::private::{PrivateSeries, PrivateSeriesNumeric};
+use arrow::array::{ArrayData, ArrayRef};
+use arrow::buffer::Buffer;
+use std::any::Any;
+use std::borrow::Cow;
+
+impl IntoSeries for StructChunked {
+    fn into_series(self) -> Series {
+        Series(Arc::new(SeriesWrap(self)))
+    }
+}
+
+impl PrivateSeriesNumeric for SeriesWrap<StructChunked> {}
+
+impl PrivateSeries for SeriesWrap<StructChunked> {
+    fn str_value(&self, index: usize) -> Cow<str> {
+        Cow::Owned(format!("{}", self.0.get_any_value(index)))
+    }
+}
+
+impl SeriesWrap<StructChunked> {
+    /// Take the rows of the fields. If the indices contain nulls, the rows are null.
+    unsafe fn take_rows(&self, idx: &IdxCa) -> Result<Series> {
+        self.0
+            .try_apply_fields(idx.null_count() > 0, |s| s.take_unchecked(idx))
+            .map(|ca| ca.into_series())
+    }
+
+    fn check_bounds(&self, idx: &IdxCa) -> Result<()> {
+        let len = self.0.len() as IdxSize;
+        if idx.into_iter().flatten().any(|i| i >= len) {
+            Err(PolarsError::OutOfBounds(
+                "take indices are out of bounds".into(),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl SeriesTrait for SeriesWrap<StructChunked> {
+    fn rename(&mut self, name: &str) {
+        self.0.rename(name)
+    }
+
+    fn array_data(&self) -> Vec<&ArrayData> {
+        self.0.chunks().iter().map(|arr| arr.data()).collect()
+    }
+
+    fn chunk_lengths(&self) -> ChunkIdIter {
+        self.0.chunks().iter().map(|arr| arr.len())
+    }
+
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    fn field(&self) -> &Field {
+        self.0.ref_field()
+    }
+
+    fn dtype(&self) -> &DataType {
+        self.0.dtype()
+    }
+
+    fn chunks(&self) -> &Vec<ArrayRef> {
+        self.0.chunks()
+    }
+
+    fn struct_(&self) -> Result<&StructChunked> {
+        Ok(&self.0)
+    }
+
+    // structs have no ordering, the minimum and maximum are null
+    fn min_as_series(&self) -> Series {
+        Series::full_null(self.name(), 1, self.dtype())
+    }
+
+    fn max_as_series(&self) -> Series {
+        Series::full_null(self.name(), 1, self.dtype())
+    }
+
+    fn slice(&self, offset: i64, length: usize) -> Series {
+        self.0
+            .apply_fields(false, |s| s.slice(offset, length))
+            .into_series()
+    }
+
+    fn append(&mut self, other: &Series) -> Result<()> {
+        self.0.append(other.struct_()?)
+    }
+
+    fn filter(&self, filter: &BooleanChunked) -> Result<Series> {
+        self.0
+            .try_apply_fields(false, |s| s.filter(filter))
+            .map(|ca| ca.into_series())
+    }
+
+    fn take_iter(&self, iter: &mut dyn Iterator<Item = usize>) -> Series {
+        let idx = iter.map(|i| Some(i as IdxSize)).collect::<IdxCa>();
+        self.take(&idx)
+    }
+
+    unsafe fn take_iter_unchecked(&self, iter: &mut dyn Iterator<Item = usize>) -> Series {
+        let idx = iter.map(|i| Some(i as IdxSize)).collect::<IdxCa>();
+        self.take_rows(&idx).unwrap()
+    }
+
+    unsafe fn take_unchecked(&self, idx: &IdxCa) -> Result<Series> {
+        self.take_rows(idx)
+    }
+
+    unsafe fn take_opt_iter_unchecked(
+        &self,
+        iter: &mut dyn Iterator<Item = Option<usize>>,
+    ) -> Series {
+        let idx = iter.map(|opt| opt.map(|i| i as IdxSize)).collect::<IdxCa>();
+        self.take_rows(&idx).unwrap()
+    }
+
+    fn take_opt_iter(&self, iter: &mut dyn Iterator<Item = Option<usize>>) -> Series {
+        let idx = iter.map(|opt| opt.map(|i| i as IdxSize)).collect::<IdxCa>();
+        self.take(&idx)
+    }
+
+    fn take(&self, indices: &IdxCa) -> Series {
+        self.check_bounds(indices).unwrap();
+        // Safety:
+        // the indices are checked on bounds
+        unsafe { self.take_rows(indices).unwrap() }
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn rechunk(&self) -> Series {
+        self.0.apply_fields(false, |s| s.rechunk()).into_series()
+    }
+
+    fn head(&self, length: Option<usize>) -> Series {
+        self.0.apply_fields(false, |s| s.head(length)).into_series()
+    }
+
+    fn tail(&self, length: Option<usize>) -> Series {
+        self.0.apply_fields(false, |s| s.tail(length)).into_series()
+    }
+
+    fn take_every(&self, n: usize) -> Series {
+        self.0
+            .apply_fields(false, |s| s.take_every(n))
+            .into_series()
+    }
+
+    fn expand_at_index(&self, index: usize, length: usize) -> Series {
+        self.0
+            .apply_fields(false, |s| s.expand_at_index(index, length))
+            .into_series()
+    }
+
+    fn cast_with_dtype(&self, data_type: &DataType) -> Result<Series> {
+        if data_type == self.dtype() {
+            Ok(self.0.clone().into_series())
+        } else {
+            Err(PolarsError::InvalidOperation(
+                format!("cannot cast a struct Series to {:?}", data_type).into(),
+            ))
+        }
+    }
+
+    fn get(&self, index: usize) -> AnyValue {
+        self.0.get_any_value(index)
+    }
+
+    fn null_count(&self) -> usize {
+        self.0.null_count()
+    }
+
+    fn is_null(&self) -> BooleanChunked {
+        self.0.is_null()
+    }
+
+    fn is_not_null(&self) -> BooleanChunked {
+        self.0.is_not_null()
+    }
+
+    fn null_bits(&self) -> Vec<(usize, Option<&Buffer>)> {
+        self.0
+            .chunks()
+            .iter()
+            .map(|arr| (arr.null_count(), arr.data().null_buffer()))
+            .collect()
+    }
+
+    fn reverse(&self) -> Series {
+        self.0.apply_fields(false, |s| s.reverse()).into_series()
+    }
+
+    fn shift(&self, periods: i64) -> Series {
+        self.0
+            .apply_fields(true, |s| s.shift(periods))
+            .into_series()
+    }
+
+    fn fmt_list(&self) -> String {
+        FmtList::fmt_list(&self.0)
+    }
+
+    fn clone_inner(&self) -> Arc<dyn SeriesTrait> {
+        Arc::new(SeriesWrap(Clone::clone(&self.0)))
+    }
+
+    #[cfg(feature = "random")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "random")))]
+    fn sample_n(&self, n: usize, with_replacement: bool) -> Result<Series> {
+        self.0
+            .sample_n(n, with_replacement)
+            .map(|ca| ca.into_series())
+    }
+
+    #[cfg(feature = "random")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "random")))]
+    fn sample_frac(&self, frac: f64, with_replacement: bool) -> Result<Series> {
+        self.0
+            .sample_frac(frac, with_replacement)
+            .map(|ca| ca.into_series())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        &self.0
+    }
+}
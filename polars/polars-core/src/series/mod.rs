@@ -7,8 +7,11 @@ mod comparison;
 pub mod implementations;
 pub(crate) mod iterator;
 
-use crate::chunked_array::{builder::get_list_builder, ChunkIdIter};
-use crate::utils::{split_ca, split_series};
+use crate::chunked_array::{
+    builder::get_list_builder, estimated_array_data_size, ChunkIdIter, ChunkMinMax, Statistics,
+};
+use crate::datatypes::large_list_values_dtype;
+use crate::utils::{common_chunk_lengths, split_ca, split_series, split_series_at};
 use crate::{series::arithmetic::coerce_lhs_rhs, POOL};
 use arrow::array::ArrayData;
 use arrow::compute::cast;
@@ -50,7 +53,7 @@ pub(crate) mod private {
 
     pub trait PrivateSeries {
         #[cfg(feature = "asof_join")]
-        fn join_asof(&self, _other: &Series) -> Result<Vec<Option<u32>>> {
+        fn join_asof(&self, _other: &Series) -> Result<Vec<Option<IdxSize>>> {
             unimplemented!()
         }
 
@@ -58,6 +61,15 @@ pub(crate) mod private {
             unimplemented!()
         }
 
+        fn sorted_flag(&self, _reverse: bool) -> bool {
+            false
+        }
+
+        /// The cached statistics, `None` if the data type doesn't cache statistics.
+        fn statistics(&self) -> Option<&Statistics> {
+            None
+        }
+
         unsafe fn equal_element(
             &self,
             _idx_self: usize,
@@ -80,44 +92,48 @@ pub(crate) mod private {
         fn vec_hash_combine(&self, _build_hasher: RandomState, _hashes: &mut [u64]) {
             unimplemented!()
         }
-        fn agg_mean(&self, _groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+        fn agg_mean(&self, _groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
             unimplemented!()
         }
-        fn agg_min(&self, _groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+        fn agg_min(&self, _groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
             unimplemented!()
         }
-        fn agg_max(&self, _groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+        fn agg_max(&self, _groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
             unimplemented!()
         }
-        fn agg_sum(&self, _groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+        fn agg_sum(&self, _groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
             unimplemented!()
         }
-        fn agg_std(&self, _groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+        fn agg_std(&self, _groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
             unimplemented!()
         }
-        fn agg_var(&self, _groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+        fn agg_var(&self, _groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
             unimplemented!()
         }
-        fn agg_first(&self, _groups: &[(u32, Vec<u32>)]) -> Series {
+        fn agg_first(&self, _groups: &[(IdxSize, Vec<IdxSize>)]) -> Series {
             unimplemented!()
         }
-        fn agg_last(&self, _groups: &[(u32, Vec<u32>)]) -> Series {
+        fn agg_last(&self, _groups: &[(IdxSize, Vec<IdxSize>)]) -> Series {
             unimplemented!()
         }
-        fn agg_n_unique(&self, _groups: &[(u32, Vec<u32>)]) -> Option<UInt32Chunked> {
+        fn agg_n_unique(&self, _groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<UInt32Chunked> {
             unimplemented!()
         }
-        fn agg_list(&self, _groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+        fn agg_list(&self, _groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
             unimplemented!()
         }
-        fn agg_quantile(&self, _groups: &[(u32, Vec<u32>)], _quantile: f64) -> Option<Series> {
+        fn agg_quantile(
+            &self,
+            _groups: &[(IdxSize, Vec<IdxSize>)],
+            _quantile: f64,
+        ) -> Option<Series> {
             unimplemented!()
         }
-        fn agg_median(&self, _groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+        fn agg_median(&self, _groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
             unimplemented!()
         }
         #[cfg(feature = "lazy")]
-        fn agg_valid_count(&self, _groups: &[(u32, Vec<u32>)]) -> Option<Series> {
+        fn agg_valid_count(&self, _groups: &[(IdxSize, Vec<IdxSize>)]) -> Option<Series> {
             unimplemented!()
         }
         #[cfg(feature = "pivot")]
@@ -125,7 +141,7 @@ pub(crate) mod private {
             &self,
             _pivot_series: &'a Series,
             _keys: Vec<Series>,
-            _groups: &[(u32, Vec<u32>)],
+            _groups: &[(IdxSize, Vec<IdxSize>)],
             _agg_type: PivotAgg,
         ) -> Result<DataFrame> {
             unimplemented!()
@@ -136,24 +152,24 @@ pub(crate) mod private {
             &self,
             _pivot_series: &'a Series,
             _keys: Vec<Series>,
-            _groups: &[(u32, Vec<u32>)],
+            _groups: &[(IdxSize, Vec<IdxSize>)],
         ) -> Result<DataFrame> {
             unimplemented!()
         }
 
-        fn hash_join_inner(&self, _other: &Series) -> Vec<(u32, u32)> {
+        fn hash_join_inner(&self, _other: &Series) -> Vec<(IdxSize, IdxSize)> {
             unimplemented!()
         }
-        fn hash_join_left(&self, _other: &Series) -> Vec<(u32, Option<u32>)> {
+        fn hash_join_left(&self, _other: &Series) -> Vec<(IdxSize, Option<IdxSize>)> {
             unimplemented!()
         }
-        fn hash_join_outer(&self, _other: &Series) -> Vec<(Option<u32>, Option<u32>)> {
+        fn hash_join_outer(&self, _other: &Series) -> Vec<(Option<IdxSize>, Option<IdxSize>)> {
             unimplemented!()
         }
         fn zip_outer_join_column(
             &self,
             _right_column: &Series,
-            _opt_join_tuples: &[(Option<u32>, Option<u32>)],
+            _opt_join_tuples: &[(Option<IdxSize>, Option<IdxSize>)],
         ) -> Series {
             unimplemented!()
         }
@@ -180,7 +196,7 @@ pub(crate) mod private {
             unimplemented!()
         }
         #[cfg(feature = "sort_multiple")]
-        fn argsort_multiple(&self, _by: &[Series], _reverse: &[bool]) -> Result<UInt32Chunked> {
+        fn argsort_multiple(&self, _by: &[Series], _reverse: &[bool]) -> Result<IdxCa> {
             Err(PolarsError::InvalidOperation(
                 "argsort_multiple is not implemented for this Series".into(),
             ))
@@ -401,6 +417,15 @@ pub trait SeriesTrait:
         ))
     }
 
+    /// Unpack to StructChunked
+    #[cfg(feature = "dtype-struct")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "dtype-struct")))]
+    fn struct_(&self) -> Result<&StructChunked> {
+        Err(PolarsError::DataTypeMisMatch(
+            format!("{:?} != struct", self.dtype()).into(),
+        ))
+    }
+
     /// Check if underlying physical data is numeric.
     ///
     /// Date types and Categoricals are also considered numeric.
@@ -411,6 +436,8 @@ pub trait SeriesTrait:
             DataType::Utf8 | DataType::List(_) | DataType::Boolean | DataType::Null => false,
             #[cfg(feature = "object")]
             DataType::Object(_) => false,
+            #[cfg(feature = "dtype-struct")]
+            DataType::Struct(_) => false,
             _ => true,
         }
     }
@@ -431,6 +458,8 @@ pub trait SeriesTrait:
             | DataType::Null => false,
             #[cfg(feature = "object")]
             DataType::Object(_) => false,
+            #[cfg(feature = "dtype-struct")]
+            DataType::Struct(_) => false,
             _ => true,
         }
     }
@@ -481,19 +510,21 @@ pub trait SeriesTrait:
         unimplemented!()
     }
 
-    /// Take by index if ChunkedArray contains a single chunk.
+    /// Take by index. Null indices produce null values.
+    /// See [take_checked](Series::take_checked) for a bound checked variant.
     ///
     /// # Safety
-    /// This doesn't check any bounds.
-    unsafe fn take_unchecked(&self, _idx: &UInt32Chunked) -> Result<Series> {
+    /// This doesn't check any bounds. Out of bounds indices read out of bounds memory.
+    unsafe fn take_unchecked(&self, _idx: &IdxCa) -> Result<Series> {
         unimplemented!()
     }
 
-    /// Take by index from an iterator. This operation clones the data.
+    /// Take by index from an iterator that may produce None values. A None index produces a
+    /// null value. This operation clones the data.
     ///
     /// # Safety
     ///
-    /// This doesn't check any bounds.
+    /// This doesn't check any bounds. Out of bounds indices read out of bounds memory.
     unsafe fn take_opt_iter_unchecked(
         &self,
         _iter: &mut dyn Iterator<Item = Option<usize>>,
@@ -501,11 +532,12 @@ pub trait SeriesTrait:
         unimplemented!()
     }
 
-    /// Take by index from an iterator. This operation clones the data.
+    /// Take by index from an iterator that may produce None values. A None index produces a
+    /// null value. This operation clones the data.
     ///
-    /// # Safety
+    /// # Panics
     ///
-    /// Out of bounds access doesn't Error but will return a Null value for that element
+    /// Panics if an index is out of bounds.
     fn take_opt_iter(&self, _iter: &mut dyn Iterator<Item = Option<usize>>) -> Series {
         unimplemented!()
     }
@@ -515,7 +547,7 @@ pub trait SeriesTrait:
     /// # Safety
     ///
     /// Out of bounds access doesn't Error but will return a Null value for that element.
-    fn take(&self, _indices: &UInt32Chunked) -> Series {
+    fn take(&self, _indices: &IdxCa) -> Series {
         unimplemented!()
     }
 
@@ -588,6 +620,11 @@ pub trait SeriesTrait:
 
     /// Create dummy variables. See [DataFrame](DataFrame::to_dummies)
     fn to_dummies(&self) -> Result<DataFrame> {
+        self.to_dummies_with_options(&DummiesOptions::default())
+    }
+
+    /// Create dummy variables. See [DummiesOptions] for the options.
+    fn to_dummies_with_options(&self, _options: &DummiesOptions) -> Result<DataFrame> {
         unimplemented!()
     }
 
@@ -620,7 +657,7 @@ pub trait SeriesTrait:
     }
 
     /// Retrieve the indexes needed for a sort.
-    fn argsort(&self, _reverse: bool) -> UInt32Chunked {
+    fn argsort(&self, _reverse: bool) -> IdxCa {
         unimplemented!()
     }
 
@@ -640,7 +677,7 @@ pub trait SeriesTrait:
     }
 
     /// Get first indexes of unique values.
-    fn arg_unique(&self) -> Result<UInt32Chunked> {
+    fn arg_unique(&self) -> Result<IdxCa> {
         unimplemented!()
     }
 
@@ -655,7 +692,7 @@ pub trait SeriesTrait:
     }
 
     /// Get indexes that evaluate true
-    fn arg_true(&self) -> Result<UInt32Chunked> {
+    fn arg_true(&self) -> Result<IdxCa> {
         Err(PolarsError::InvalidOperation(
             "arg_true can only be called for boolean dtype".into(),
         ))
@@ -850,6 +887,29 @@ pub trait SeriesTrait:
         "fmt implemented".into()
     }
 
+    #[cfg(feature = "strings")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "strings")))]
+    /// Get the length of the string values in bytes.
+    fn str_lengths(&self) -> Result<UInt32Chunked> {
+        self.utf8().map(|ca| ca.str_lengths())
+    }
+
+    #[cfg(feature = "strings")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "strings")))]
+    /// Get the length of the string values in chars.
+    fn str_n_chars(&self) -> Result<UInt32Chunked> {
+        self.utf8().map(|ca| ca.str_n_chars())
+    }
+
+    #[cfg(feature = "strings")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "strings")))]
+    /// Take a substring of the string values, see [Utf8Chunked::str_slice].
+    fn str_slice(&self, start: i64, length: Option<u64>) -> Result<Series> {
+        self.utf8()
+            .and_then(|ca| ca.str_slice(start, length))
+            .map(|ca| ca.into_series())
+    }
+
     #[cfg(feature = "temporal")]
     #[cfg_attr(docsrs, doc(cfg(feature = "temporal")))]
     /// Extract hour from underlying NaiveDateTime representation.
@@ -1052,10 +1112,50 @@ pub trait SeriesTrait:
     fn repeat_by(&self, _by: &UInt32Chunked) -> ListChunked {
         unimplemented!()
     }
+    /// Find the indices at which the `values` would be inserted to keep this sorted Series sorted.
+    #[cfg(feature = "search_sorted")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search_sorted")))]
+    fn search_sorted_with_options(
+        &self,
+        _values: &Series,
+        _options: &SearchSortedOptions,
+    ) -> Result<IdxCa> {
+        unimplemented!()
+    }
     #[cfg(feature = "checked_arithmetic")]
     #[cfg_attr(docsrs, doc(cfg(feature = "checked_arithmetic")))]
     fn checked_div(&self, _rhs: &Series) -> Result<Series> {
-        unimplemented!()
+        Err(PolarsError::InvalidOperation(
+            format!("checked_div is not supported for dtype {:?}", self.dtype()).into(),
+        ))
+    }
+    #[cfg(feature = "checked_arithmetic")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "checked_arithmetic")))]
+    fn checked_add(&self, _rhs: &Series) -> Result<Series> {
+        Err(PolarsError::InvalidOperation(
+            format!("checked_add is not supported for dtype {:?}", self.dtype()).into(),
+        ))
+    }
+    #[cfg(feature = "checked_arithmetic")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "checked_arithmetic")))]
+    fn checked_sub(&self, _rhs: &Series) -> Result<Series> {
+        Err(PolarsError::InvalidOperation(
+            format!("checked_sub is not supported for dtype {:?}", self.dtype()).into(),
+        ))
+    }
+    #[cfg(feature = "checked_arithmetic")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "checked_arithmetic")))]
+    fn checked_mul(&self, _rhs: &Series) -> Result<Series> {
+        Err(PolarsError::InvalidOperation(
+            format!("checked_mul is not supported for dtype {:?}", self.dtype()).into(),
+        ))
+    }
+    #[cfg(feature = "checked_arithmetic")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "checked_arithmetic")))]
+    fn floor_div(&self, _rhs: &Series) -> Result<Series> {
+        Err(PolarsError::InvalidOperation(
+            format!("floor_div is not supported for dtype {:?}", self.dtype()).into(),
+        ))
     }
 
     #[cfg(feature = "is_first")]
@@ -1181,7 +1281,63 @@ impl<'a> (dyn SeriesTrait + 'a) {
 #[derive(Clone)]
 pub struct Series(pub Arc<dyn SeriesTrait>);
 
+/// The value of a Series with a single (aggregated) value as statistic. Integers and temporal
+/// values are kept exact.
+fn stat_value(s: &Series) -> Option<StatValue> {
+    if s.is_empty() {
+        return None;
+    }
+    use AnyValue::*;
+    match s.get(0) {
+        Boolean(v) => Some(StatValue::Int(v as i128)),
+        UInt8(v) => Some(StatValue::Int(v as i128)),
+        UInt16(v) => Some(StatValue::Int(v as i128)),
+        UInt32(v) => Some(StatValue::Int(v as i128)),
+        UInt64(v) => Some(StatValue::Int(v as i128)),
+        Int8(v) => Some(StatValue::Int(v as i128)),
+        Int16(v) => Some(StatValue::Int(v as i128)),
+        Int32(v) | Date32(v) => Some(StatValue::Int(v as i128)),
+        Int64(v) | Date64(v) | Time64(v, _) | Duration(v, _) => Some(StatValue::Int(v as i128)),
+        Float32(v) => Some(StatValue::Float(v as f64)),
+        Float64(v) => Some(StatValue::Float(v)),
+        _ => None,
+    }
+}
+
 impl Series {
+    /// Create a new Series of length `size` filled with null values of the given `dtype`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `dtype` cannot be represented as a null Series, e.g. `Object`.
+    pub fn full_null(name: &str, size: usize, dtype: &DataType) -> Series {
+        match dtype {
+            DataType::List(inner) => {
+                ListChunked::full_null_with_dtype(name, size, inner).into_series()
+            }
+            DataType::Utf8 => Utf8Chunked::full_null(name, size).into_series(),
+            DataType::Boolean => BooleanChunked::full_null(name, size).into_series(),
+            DataType::Categorical => UInt32Chunked::full_null(name, size)
+                .cast::<CategoricalType>()
+                .unwrap()
+                .into_series(),
+            #[cfg(feature = "dtype-struct")]
+            DataType::Struct(fields) => {
+                let fields = fields
+                    .iter()
+                    .map(|fld| Series::full_null(fld.name(), size, fld.data_type()))
+                    .collect();
+                let validity = BooleanChunked::full("", false, size);
+                StructChunked::new_with_validity(name, fields, Some(validity))
+                    .unwrap()
+                    .into_series()
+            }
+            _ => UInt32Chunked::full_null(name, size)
+                .cast_with_dtype(dtype)
+                .unwrap_or_else(|_| panic!("cannot create a null Series of dtype {:?}", dtype)),
+        }
+    }
+
     pub(crate) fn get_inner_mut(&mut self) -> &mut dyn SeriesTrait {
         if Arc::weak_count(&self.0) + Arc::strong_count(&self.0) != 1 {
             self.0 = self.0.clone_inner();
@@ -1212,6 +1368,154 @@ impl Series {
         Ok(self)
     }
 
+    /// Split the chunks of this Series and `other` at the chunk boundaries of the other Series,
+    /// such that both have the same chunk lengths.
+    ///
+    /// Unlike a rechunk this doesn't copy any data; the arrays are only sliced.
+    pub fn align_chunks_with(&self, other: &Series) -> Result<(Series, Series)> {
+        if self.len() != other.len() {
+            return Err(PolarsError::ShapeMisMatch(
+                format!(
+                    "cannot align the chunks of Series of different lengths: {} and {}",
+                    self.len(),
+                    other.len()
+                )
+                .into(),
+            ));
+        }
+        let chunk_lengths = common_chunk_lengths(vec![self.chunk_lengths(), other.chunk_lengths()]);
+        Ok((
+            self.split_chunks(&chunk_lengths),
+            other.split_chunks(&chunk_lengths),
+        ))
+    }
+
+    /// Split the chunks at the boundaries given by `chunk_lengths`. This is zero-copy.
+    pub(crate) fn split_chunks(&self, chunk_lengths: &[usize]) -> Series {
+        if chunk_lengths.is_empty() || self.chunk_lengths().eq(chunk_lengths.iter().copied()) {
+            return self.clone();
+        }
+        let mut parts = split_series_at(self, chunk_lengths).into_iter();
+        let mut out = parts.next().unwrap();
+        for part in parts {
+            out.append(&part).expect("slices have the same dtype");
+        }
+        out
+    }
+
+    /// Rechunk the Series into chunks of `target_chunk_size`, only the last chunk may be smaller.
+    ///
+    /// Chunks that are larger than the target are split by zero-copy slicing, data is only copied
+    /// where smaller chunks need to be merged. The chunk boundaries only depend on the length of
+    /// the Series, so Series of equal length end up with the same chunk lengths.
+    pub fn rechunk_to(&self, target_chunk_size: usize) -> Series {
+        let target_chunk_size = std::cmp::max(target_chunk_size, 1);
+        let len = self.len();
+        if self
+            .chunk_lengths()
+            .all(|chunk_len| chunk_len == target_chunk_size)
+            || (len <= target_chunk_size && self.n_chunks() == 1)
+        {
+            return self.clone();
+        }
+
+        let mut out: Option<Series> = None;
+        let mut offset = 0;
+        while offset < len {
+            let chunk_len = std::cmp::min(target_chunk_size, len - offset);
+            // a slice within a single chunk is not copied by the rechunk
+            let chunk = self.slice(offset as i64, chunk_len).rechunk();
+            match &mut out {
+                None => out = Some(chunk),
+                Some(out) => {
+                    out.append(&chunk).expect("same dtype");
+                }
+            }
+            offset += chunk_len;
+        }
+        out.unwrap_or_else(|| self.clone())
+    }
+
+    /// Estimate the number of bytes of the Series, see
+    /// [ChunkedArray::estimated_size](crate::chunked_array::ChunkedArray::estimated_size).
+    pub fn estimated_size(&self) -> usize {
+        match self.dtype() {
+            DataType::Categorical => self.categorical().unwrap().estimated_size(),
+            #[cfg(feature = "object")]
+            DataType::Object(_) => self
+                .chunks()
+                .iter()
+                .map(|arr| arr.get_buffer_memory_size())
+                .sum(),
+            _ => {
+                let mut seen = PlHashSet::default();
+                self.chunks()
+                    .iter()
+                    .map(|arr| estimated_array_data_size(arr.data(), &mut seen))
+                    .sum()
+            }
+        }
+    }
+
+    /// Cast an integer Series to the smallest integer type that can hold all its values. At the
+    /// same width an unsigned type is preferred. Other Series are returned as is.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use polars_core::prelude::*;
+    /// let s = Series::new("a", &[1i64, 20, 70_000]);
+    /// assert_eq!(s.shrink_dtype().unwrap().dtype(), &DataType::UInt32);
+    /// ```
+    pub fn shrink_dtype(&self) -> Result<Series> {
+        use DataType::*;
+        let width = match self.dtype() {
+            UInt8 | Int8 => 1,
+            UInt16 | Int16 => 2,
+            UInt32 | Int32 => 4,
+            UInt64 | Int64 => 8,
+            _ => return Ok(self.clone()),
+        };
+        let (min, max) = match (self.cached_min::<f64>(), self.cached_max::<f64>()) {
+            (Some(min), Some(max)) => (min, max),
+            _ => return Ok(self.clone()),
+        };
+        // ordered by width, all bounds are exactly representable as f64
+        let candidates = [
+            #[cfg(feature = "dtype-u8")]
+            (UInt8, 1, u8::MIN as f64, u8::MAX as f64),
+            #[cfg(feature = "dtype-i8")]
+            (Int8, 1, i8::MIN as f64, i8::MAX as f64),
+            #[cfg(feature = "dtype-u16")]
+            (UInt16, 2, u16::MIN as f64, u16::MAX as f64),
+            #[cfg(feature = "dtype-i16")]
+            (Int16, 2, i16::MIN as f64, i16::MAX as f64),
+            (UInt32, 4, u32::MIN as f64, u32::MAX as f64),
+            (Int32, 4, i32::MIN as f64, i32::MAX as f64),
+        ];
+        match candidates
+            .iter()
+            .find(|(_, _, lower, upper)| min >= *lower && max <= *upper)
+        {
+            Some((dtype, candidate_width, _, _)) if *candidate_width < width => {
+                self.cast_with_dtype(dtype)
+            }
+            _ => Ok(self.clone()),
+        }
+    }
+
+    /// Like [shrink_dtype](Series::shrink_dtype), but also casts a Utf8 Series to Categorical
+    /// if the number of distinct values divided by the length is at most `max_distinct_ratio`.
+    pub fn shrink_dtype_with_categorical(&self, max_distinct_ratio: f64) -> Result<Series> {
+        if self.dtype() == &DataType::Utf8 && !self.is_empty() {
+            let distinct_ratio = self.n_unique()? as f64 / self.len() as f64;
+            if distinct_ratio <= max_distinct_ratio {
+                return self.cast_with_dtype(&DataType::Categorical);
+            }
+        }
+        self.shrink_dtype()
+    }
+
     /// Sort in place.
     pub fn sort_in_place(&mut self, reverse: bool) -> &mut Self {
         self.get_inner_mut().sort_in_place(reverse);
@@ -1281,6 +1585,98 @@ impl Series {
             .and_then(|s| s.f64().unwrap().get(0).and_then(T::from))
     }
 
+    /// Check if the Series is flagged as sorted in the given direction, e.g. because it is the
+    /// result of a sort. A `false` doesn't mean that the data isn't sorted.
+    pub fn is_sorted_flag(&self, reverse: bool) -> bool {
+        self.0.sorted_flag(reverse)
+    }
+
+    /// Returns the minimum value in the array, like [min](Series::min). The result is cached,
+    /// so subsequent calls don't traverse the data.
+    /// ```
+    /// # use polars_core::prelude::*;
+    /// let s = Series::new("days", [1, 2, 3].as_ref());
+    /// assert_eq!(s.cached_min(), Some(1));
+    /// ```
+    pub fn cached_min<T>(&self) -> Option<T>
+    where
+        T: NumCast,
+    {
+        let compute = || stat_value(&self.min_as_series());
+        match self.statistics() {
+            Some(stats) => stats.min_or_init(compute),
+            None => compute(),
+        }
+        .and_then(StatValue::cast)
+    }
+
+    /// Returns the maximum value in the array, like [max](Series::max). The result is cached,
+    /// so subsequent calls don't traverse the data.
+    /// ```
+    /// # use polars_core::prelude::*;
+    /// let s = Series::new("days", [1, 2, 3].as_ref());
+    /// assert_eq!(s.cached_max(), Some(3));
+    /// ```
+    pub fn cached_max<T>(&self) -> Option<T>
+    where
+        T: NumCast,
+    {
+        let compute = || stat_value(&self.max_as_series());
+        match self.statistics() {
+            Some(stats) => stats.max_or_init(compute),
+            None => compute(),
+        }
+        .and_then(StatValue::cast)
+    }
+
+    /// Get the number of unique values, like [n_unique](SeriesTrait::n_unique).
+    /// The result is cached, so subsequent calls don't traverse the data.
+    pub fn cached_n_unique(&self) -> Result<usize> {
+        let stats = match self.statistics() {
+            Some(stats) => stats,
+            None => return self.n_unique(),
+        };
+        if let Some(n) = stats.n_unique() {
+            return Ok(n);
+        }
+        let n = self.n_unique()?;
+        Ok(stats.n_unique_or_init(|| n))
+    }
+
+    /// Get the minimum and maximum of every chunk, computed on the physical values. For a
+    /// Categorical these are the minimum and maximum category indexes. The result is cached,
+    /// so subsequent calls don't traverse the data.
+    ///
+    /// Returns `None` if the data is not numeric.
+    /// ```
+    /// # use polars_core::prelude::*;
+    /// let mut s = Series::new("a", [1, 2].as_ref());
+    /// s.append(&Series::new("a", &[None, Some(5), Some(3)])).unwrap();
+    /// let min_max = s.cached_chunk_min_max().unwrap();
+    /// assert_eq!(min_max.as_slice(), &[Some((1.0, 2.0)), Some((3.0, 5.0))]);
+    /// ```
+    pub fn cached_chunk_min_max(&self) -> Option<Arc<Vec<ChunkMinMax>>> {
+        if !self.is_numeric_physical() {
+            return None;
+        }
+        let compute = || {
+            self.chunks()
+                .iter()
+                .map(|arr| {
+                    let s = Series::try_from(("", arr.clone())).ok()?;
+                    match (s.min::<f64>(), s.max::<f64>()) {
+                        (Some(min), Some(max)) => Some(Some((min, max))),
+                        _ => Some(None),
+                    }
+                })
+                .collect()
+        };
+        match self.statistics() {
+            Some(stats) => stats.chunk_min_max_or_init(compute),
+            None => compute().map(Arc::new),
+        }
+    }
+
     /// Explode a list or utf8 Series. This expands every item to a new row..
     pub fn explode(&self) -> Result<Series> {
         match self.dtype() {
@@ -1356,6 +1752,29 @@ impl Series {
         }
     }
 
+    /// Concatenate the string values into a single string, separated by `delimiter`.
+    ///
+    /// On a `List<Utf8>` `Series`, e.g. the result of a groupby aggregation, the values of
+    /// every list are concatenated instead, producing a string per list.
+    /// Null values are skipped, unless a `null_value` placeholder is given.
+    #[cfg(feature = "concat_str")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "concat_str")))]
+    pub fn str_concat(&self, delimiter: &str, null_value: Option<&str>) -> Result<Series> {
+        match self.dtype() {
+            DataType::List(_) => self.lst_join(delimiter, null_value),
+            _ => Ok(self.utf8()?.str_concat(delimiter, null_value).into_series()),
+        }
+    }
+
+    /// Join the string values of every list into a single string, separated by `delimiter`.
+    #[cfg(feature = "concat_str")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "concat_str")))]
+    pub fn lst_join(&self, delimiter: &str, null_value: Option<&str>) -> Result<Series> {
+        self.list()?
+            .lst_join(delimiter, null_value)
+            .map(|ca| ca.into_series())
+    }
+
     /// Create a new ChunkedArray with values from self where the mask evaluates `true` and values
     /// from `other` where the mask evaluates `false`
     #[cfg(feature = "zip_with")]
@@ -1365,6 +1784,71 @@ impl Series {
         lhs.zip_with_same_type(mask, rhs.as_ref())
     }
 
+    /// Cast the single `value` used in [Series::set] and [Series::set_at_idx] to our dtype.
+    fn set_value(&self, value: &Series) -> Result<Series> {
+        match self.dtype() {
+            DataType::List(_) | DataType::Categorical | DataType::Null => {
+                return Err(PolarsError::InvalidOperation(
+                    format!("`set` operation not supported for dtype {:?}", self.dtype()).into(),
+                ))
+            }
+            #[cfg(feature = "object")]
+            DataType::Object(_) => {
+                return Err(PolarsError::InvalidOperation(
+                    "`set` operation not supported for dtype Object".into(),
+                ))
+            }
+            _ => {}
+        }
+        if value.len() != 1 {
+            return Err(PolarsError::ShapeMisMatch(
+                format!(
+                    "the value in a `set` operation should have length 1, got length {}",
+                    value.len()
+                )
+                .into(),
+            ));
+        }
+        if value.null_count() == 1 {
+            Ok(Series::full_null(value.name(), 1, self.dtype()))
+        } else {
+            value.cast_with_dtype(self.dtype())
+        }
+    }
+
+    /// Set the values where the mask evaluates to `true` to `value`. A null `value` sets
+    /// those values to null.
+    ///
+    /// `value` should have length 1 and is cast to the dtype of this Series.
+    pub fn set(&self, mask: &BooleanChunked, value: &Series) -> Result<Series> {
+        let value = self.set_value(value)?;
+        macro_rules! set {
+            ($ca:expr, $value:expr, $mask:expr) => {{
+                let value = $ca.unpack_series_matching_type($value)?.get(0);
+                $ca.set($mask, value).map(|ca| ca.into_series())
+            }};
+        }
+        match_arrow_data_type_apply_macro_ca!(self, set, set, set, &value, mask)
+    }
+
+    /// Set the values at the indexes `idx` to `value`. A null `value` sets those values to null.
+    ///
+    /// `value` should have length 1 and is cast to the dtype of this Series.
+    pub fn set_at_idx<I: IntoIterator<Item = usize>>(
+        &self,
+        idx: I,
+        value: &Series,
+    ) -> Result<Series> {
+        let value = self.set_value(value)?;
+        macro_rules! set_at_idx {
+            ($ca:expr, $value:expr, $idx:expr) => {{
+                let value = $ca.unpack_series_matching_type($value)?.get(0);
+                $ca.set_at_idx($idx, value).map(|ca| ca.into_series())
+            }};
+        }
+        match_arrow_data_type_apply_macro_ca!(self, set_at_idx, set_at_idx, set_at_idx, &value, idx)
+    }
+
     /// Cast a datelike Series to their physical representation.
     /// Primitives remain unchanged
     ///
@@ -1385,15 +1869,41 @@ impl Series {
         out.unwrap()
     }
 
+    /// Unpack to the ChunkedArray of the row index type [IdxSize].
+    pub fn idx(&self) -> Result<&IdxCa> {
+        #[cfg(not(feature = "bigidx"))]
+        {
+            self.u32()
+        }
+        #[cfg(feature = "bigidx")]
+        {
+            self.u64()
+        }
+    }
+
+    /// Take by index. Null indices produce null values. Returns an error with the first out of
+    /// bounds index if any index is out of bounds.
+    pub fn take_checked(&self, idx: &IdxCa) -> Result<Series> {
+        let len = self.len();
+        if let Some(oob) = idx.into_iter().flatten().find(|i| *i as usize >= len) {
+            return Err(PolarsError::OutOfBounds(
+                format!(
+                    "take index {} is out of bounds for a Series of length {}",
+                    oob, len
+                )
+                .into(),
+            ));
+        }
+        // Safety:
+        // the indices are bound checked above.
+        unsafe { self.take_unchecked(idx) }
+    }
+
     /// Take by index if ChunkedArray contains a single chunk.
     ///
     /// # Safety
     /// This doesn't check any bounds. Null validity is checked.
-    pub unsafe fn take_unchecked_threaded(
-        &self,
-        idx: &UInt32Chunked,
-        rechunk: bool,
-    ) -> Result<Series> {
+    pub unsafe fn take_unchecked_threaded(&self, idx: &IdxCa, rechunk: bool) -> Result<Series> {
         let n_threads = POOL.current_num_threads();
         let idx = split_ca(idx, n_threads)?;
 
@@ -1419,7 +1929,7 @@ impl Series {
     /// # Safety
     ///
     /// Out of bounds access doesn't Error but will return a Null value
-    pub fn take_threaded(&self, idx: &UInt32Chunked, rechunk: bool) -> Series {
+    pub fn take_threaded(&self, idx: &IdxCa, rechunk: bool) -> Series {
         let n_threads = POOL.current_num_threads();
         let idx = split_ca(idx, n_threads).unwrap();
 
@@ -1473,6 +1983,16 @@ impl Series {
         }
     }
 
+    /// Find the indices at which the `values` would be inserted to keep this sorted Series sorted.
+    /// Returns an error if this Series isn't sorted, see
+    /// [search_sorted_with_options](SeriesTrait::search_sorted_with_options) to skip that check.
+    #[cfg(feature = "search_sorted")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search_sorted")))]
+    pub fn search_sorted(&self, values: &Series, side: SearchSortedSide) -> Result<IdxCa> {
+        let options = SearchSortedOptions::default().and_side(side);
+        self.search_sorted_with_options(values, &options)
+    }
+
     /// Round underlying floating point array to given decimal.
     #[cfg(feature = "round_series")]
     #[cfg_attr(docsrs, doc(cfg(feature = "round_series")))]
@@ -1507,6 +2027,14 @@ impl Series {
     pub fn hash(&self, build_hasher: ahash::RandomState) -> UInt64Chunked {
         UInt64Chunked::new_from_aligned_vec(self.name(), self.0.vec_hash(build_hasher))
     }
+
+    #[cfg(feature = "row_hash")]
+    /// Get a stable hash of this Series. The hashes only depend on the values and the `seed`,
+    /// see [DataFrame::hash_rows](crate::frame::DataFrame::hash_rows).
+    pub fn stable_hash(&self, seed: u64) -> Result<UInt64Chunked> {
+        let hashes = crate::vector_hasher::series_to_stable_hashes(self, seed)?;
+        Ok(UInt64Chunked::new_from_aligned_vec(self.name(), hashes))
+    }
 }
 
 impl Deref for Series {
@@ -1631,10 +2159,19 @@ impl std::convert::TryFrom<(&str, Vec<ArrayRef>)> for Series {
                 Ok(Utf8Chunked::new_from_chunks(name, chunks).into_series())
             }
             ArrowDataType::List(fld) => {
+                // first cast the values, e.g. utf8 to large-utf8, then the offsets
+                let fld = Box::new(ArrowField::new(
+                    fld.name(),
+                    large_list_values_dtype(fld.data_type()),
+                    fld.is_nullable(),
+                ));
                 let chunks = chunks
                     .iter()
-                    .map(|arr| cast(arr, &ArrowDataType::LargeList(fld.clone())).unwrap())
-                    .collect();
+                    .map(|arr| {
+                        let arr = cast(arr, &ArrowDataType::List(fld.clone()))?;
+                        cast(&arr, &ArrowDataType::LargeList(fld.clone()))
+                    })
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
                 Ok(ListChunked::new_from_chunks(name, chunks).into_series())
             }
             ArrowDataType::Boolean => {
@@ -1678,6 +2215,14 @@ impl std::convert::TryFrom<(&str, Vec<ArrayRef>)> for Series {
             ArrowDataType::LargeList(_) => {
                 Ok(ListChunked::new_from_chunks(name, chunks).into_series())
             }
+            ArrowDataType::Dictionary(_, _) => {
+                CategoricalChunked::from_arrow_dictionary(name, &chunks).map(|ca| ca.into_series())
+            }
+            #[cfg(feature = "dtype-struct")]
+            ArrowDataType::Struct(_) => {
+                crate::chunked_array::struct_::struct_from_arrow(name, &chunks)
+                    .map(|ca| ca.into_series())
+            }
             ArrowDataType::Null => {
                 // we don't support null types yet so we use a small digit type filled with nulls
                 let len = chunks.iter().fold(0, |acc, array| acc + array.len());
@@ -1833,4 +2378,219 @@ mod test {
         let ca = out.f64().unwrap();
         assert_eq!(ca.get(0), Some(1.0));
     }
+
+    #[test]
+    fn test_full_null() {
+        for dtype in &[
+            DataType::Boolean,
+            DataType::Int32,
+            DataType::Float64,
+            DataType::Utf8,
+            DataType::Categorical,
+            DataType::List(ArrowDataType::Int64),
+        ] {
+            let s = Series::full_null("a", 3, dtype);
+            assert_eq!(s.dtype(), dtype);
+            assert_eq!(s.len(), 3);
+            assert_eq!(s.null_count(), 3);
+        }
+    }
+
+    #[test]
+    fn test_expand_at_index_list() {
+        let s = Series::new(
+            "a",
+            &[Series::new("", &[1i64, 2]), Series::new("", &[3i64])],
+        );
+        let expanded = s.expand_at_index(0, 3);
+        assert_eq!(expanded.len(), 3);
+        assert_eq!(expanded.dtype(), s.dtype());
+        let ca = expanded.list().unwrap();
+        for i in 0..3 {
+            assert!(ca
+                .get(i)
+                .unwrap()
+                .series_equal(&Series::new("", &[1i64, 2])));
+        }
+
+        // a null list keeps the inner type
+        let nulls = Series::full_null("a", 1, &DataType::List(ArrowDataType::Int64));
+        let expanded = nulls.expand_at_index(0, 4);
+        assert_eq!(expanded.dtype(), &DataType::List(ArrowDataType::Int64));
+        assert_eq!(expanded.null_count(), 4);
+        assert_eq!(ListChunked::full_null("a", 2).null_count(), 2);
+    }
+
+    #[test]
+    fn test_align_chunks_with() {
+        let mut a = Series::new("a", &[1, 2, 3]);
+        a.append(&Series::new("a", &[4, 5, 6])).unwrap();
+        let mut b = Series::new("b", &[1]);
+        b.append(&Series::new("b", &[2, 3, 4, 5, 6])).unwrap();
+
+        let (a_aligned, b_aligned) = a.align_chunks_with(&b).unwrap();
+        assert_eq!(a_aligned.chunk_lengths().collect::<Vec<_>>(), &[1, 2, 3]);
+        assert_eq!(b_aligned.chunk_lengths().collect::<Vec<_>>(), &[1, 2, 3]);
+        // the arrays are sliced, not copied
+        let ptr = |s: &Series, i: usize| s.chunks()[i].data().buffers()[0].as_ptr();
+        assert_eq!(ptr(&a_aligned, 0), ptr(&a, 0));
+        assert_eq!(ptr(&a_aligned, 1), ptr(&a, 0));
+        assert_eq!(ptr(&a_aligned, 2), ptr(&a, 1));
+        assert_eq!(ptr(&b_aligned, 1), ptr(&b, 1));
+        assert!(a_aligned.series_equal(&a));
+
+        // the kernels align the chunks in the same way
+        let out = &a + &b;
+        assert!(out.series_equal(&(&a.rechunk() + &b.rechunk())));
+        assert_eq!(out.chunk_lengths().collect::<Vec<_>>(), &[1, 2, 3]);
+        let out = a.eq(&b);
+        assert_eq!(out.chunk_lengths().collect::<Vec<_>>(), &[1, 2, 3]);
+        assert!(out.all_true());
+
+        assert!(a.align_chunks_with(&b.slice(0, 2)).is_err());
+    }
+
+    #[test]
+    fn test_rechunk_to() {
+        let lengths = |s: &Series| s.chunk_lengths().collect::<Vec<_>>();
+        let ptr = |s: &Series, i: usize| s.chunks()[i].data().buffers()[0].as_ptr();
+
+        // a large chunk is split without copying
+        let s = Series::new("a", &(0..7).collect::<Vec<i32>>());
+        let out = s.rechunk_to(3);
+        assert_eq!(lengths(&out), &[3, 3, 1]);
+        assert_eq!(ptr(&out, 0), ptr(&s, 0));
+        assert_eq!(ptr(&out, 2), ptr(&s, 0));
+        assert!(out.series_equal(&s));
+        assert_eq!(out.name(), "a");
+
+        // single element chunks are merged
+        let mut s = Series::new("a", &[0]);
+        for i in 1..5 {
+            s.append(&Series::new("a", &[i])).unwrap();
+        }
+        let out = s.rechunk_to(2);
+        assert_eq!(lengths(&out), &[2, 2, 1]);
+        assert!(out.series_equal(&s));
+        let out = s.rechunk_to(1);
+        assert_eq!(lengths(&out), &[1, 1, 1, 1, 1]);
+        assert_eq!(ptr(&out, 3), ptr(&s, 3));
+
+        // already ideal chunking is kept
+        let mut s = Series::new("a", &["a", "b"]);
+        s.append(&Series::new("a", &["c", "d"])).unwrap();
+        s.append(&Series::new("a", &["e"])).unwrap();
+        let out = s.rechunk_to(2);
+        assert_eq!(lengths(&out), &[2, 2, 1]);
+        for i in 0..3 {
+            assert_eq!(ptr(&out, i), ptr(&s, i));
+        }
+        let out = s.rechunk_to(10);
+        assert_eq!(lengths(&out), &[5]);
+        assert!(out.series_equal(&s));
+    }
+
+    #[test]
+    fn test_series_set() {
+        let s = Series::new("a", &[Some(1), None, Some(3)]);
+        let mask = BooleanChunked::new_from_slice("mask", &[true, true, false]);
+        let out = s.set(&mask, &Series::new("v", &[10.0])).unwrap();
+        assert_eq!(
+            Vec::from(out.i32().unwrap()),
+            &[Some(10), Some(10), Some(3)]
+        );
+        let out = s
+            .set_at_idx(vec![2], &Series::new("v", &[None::<i32>]))
+            .unwrap();
+        assert_eq!(Vec::from(out.i32().unwrap()), &[Some(1), None, None]);
+
+        let s = Series::new("a", &["a", "b"]);
+        let out = s.set_at_idx(vec![1], &Series::new("v", &["c"])).unwrap();
+        assert_eq!(Vec::from(out.utf8().unwrap()), &[Some("a"), Some("c")]);
+
+        assert!(s
+            .set_at_idx(vec![0], &Series::new("v", &["c", "d"]))
+            .is_err());
+    }
+
+    #[test]
+    fn test_estimated_size() {
+        let s = Int32Chunked::new_from_slice("a", &[1, 2, 3]).into_series();
+        assert_eq!(s.estimated_size(), 12);
+        // a slice shares the buffer of its parent
+        assert_eq!(s.slice(1, 2).estimated_size(), 12);
+        // the appended chunk is the same array
+        let mut appended = s.clone();
+        appended.append(&s).unwrap();
+        assert_eq!(appended.n_chunks(), 2);
+        assert_eq!(appended.estimated_size(), 12);
+
+        // one byte of validity
+        let s = Int32Chunked::new_from_opt_slice("a", &[Some(1), None, Some(3)]).into_series();
+        assert_eq!(s.estimated_size(), 13);
+
+        // 3 i64 offsets and 3 bytes of string data
+        let s = Series::new("a", &["a", "bc"]);
+        assert_eq!(s.estimated_size(), 27);
+
+        let s = Series::new("a", &["a", "b", "a"])
+            .cast_with_dtype(&DataType::Categorical)
+            .unwrap();
+        let map = s.categorical().unwrap().get_categorical_map().unwrap();
+        if let crate::chunked_array::builder::categorical::RevMapping::Local(_) = map.as_ref() {
+            assert_eq!(map.estimated_size(), 26);
+        }
+        assert_eq!(s.estimated_size(), 12 + map.estimated_size());
+
+        let df = DataFrame::new(vec![
+            Int32Chunked::new_from_slice("a", &[1, 2]).into_series(),
+            Series::new("b", &["a", "bc"]),
+        ])
+        .unwrap();
+        assert_eq!(df.estimated_size(), 8 + 27);
+    }
+
+    #[test]
+    fn test_shrink_dtype() {
+        let s = Series::new("a", &[1i64, 20, 70_000]);
+        let out = s.shrink_dtype().unwrap();
+        assert_eq!(out.dtype(), &DataType::UInt32);
+        assert_eq!(
+            Vec::from(out.u32().unwrap()),
+            &[Some(1), Some(20), Some(70_000)]
+        );
+
+        let s = Series::new("a", &[Some(-1i64), None, Some(70_000)]);
+        let out = s.shrink_dtype().unwrap();
+        assert_eq!(out.dtype(), &DataType::Int32);
+        assert_eq!(
+            Vec::from(out.i32().unwrap()),
+            &[Some(-1), None, Some(70_000)]
+        );
+
+        // the values don't fit in a smaller type
+        let s = Series::new("a", &[-1i64, i64::MAX]);
+        assert_eq!(s.shrink_dtype().unwrap().dtype(), &DataType::Int64);
+        // an unsigned type of the same width isn't smaller
+        let s = Series::new("a", &[0i32, 70_000]);
+        assert_eq!(s.shrink_dtype().unwrap().dtype(), &DataType::Int32);
+        let s = Series::new("a", &[1.0f64, 2.0]);
+        assert_eq!(s.shrink_dtype().unwrap().dtype(), &DataType::Float64);
+        let s = Series::new("a", &[None::<i64>, None]);
+        assert_eq!(s.shrink_dtype().unwrap().dtype(), &DataType::Int64);
+
+        #[cfg(all(feature = "dtype-u8", feature = "dtype-i8"))]
+        {
+            let s = Series::new("a", &[0i64, 255]);
+            assert_eq!(s.shrink_dtype().unwrap().dtype(), &DataType::UInt8);
+            let s = Series::new("a", &[-128i64, 127]);
+            assert_eq!(s.shrink_dtype().unwrap().dtype(), &DataType::Int8);
+        }
+
+        let s = Series::new("a", &["a", "a", "a", "b"]);
+        let out = s.shrink_dtype_with_categorical(0.5).unwrap();
+        assert_eq!(out.dtype(), &DataType::Categorical);
+        let out = s.shrink_dtype_with_categorical(0.1).unwrap();
+        assert_eq!(out.dtype(), &DataType::Utf8);
+    }
 }
@@ -90,50 +90,115 @@ pub(crate) fn get_iter_capacity<T, I: Iterator<Item = T>>(iter: &I) -> usize {
     }
 }
 
+/// Slice the array in consecutive parts of the given lengths. This is zero-copy.
 macro_rules! split_array {
-    ($ca: expr, $n: expr, $ty : ty) => {{
-        if $n == 1 {
-            return Ok(vec![$ca.clone()]);
-        }
-        let total_len = $ca.len();
-        let chunk_size = total_len / $n;
-
-        let v = (0..$n)
-            .map(|i| {
-                let offset = i * chunk_size;
-                let len = if i == ($n - 1) {
-                    total_len - offset
-                } else {
-                    chunk_size
-                };
-                $ca.slice((i * chunk_size) as $ty, len)
+    ($ca: expr, $lengths: expr, $ty : ty) => {{
+        let mut offset = 0;
+        $lengths
+            .into_iter()
+            .map(|len| {
+                let part = $ca.slice(offset as $ty, len);
+                offset += len;
+                part
             })
-            .collect();
-        Ok(v)
+            .collect()
     }};
 }
 
+/// The lengths of `n` parts of about equal length, only the last part may be longer.
+#[cfg(feature = "private")]
+fn split_lengths(total_len: usize, n: usize) -> impl Iterator<Item = usize> {
+    let chunk_size = total_len / n;
+    (0..n).map(move |i| {
+        if i == (n - 1) {
+            total_len - i * chunk_size
+        } else {
+            chunk_size
+        }
+    })
+}
+
 #[cfg(feature = "private")]
 pub fn split_ca<T>(ca: &ChunkedArray<T>, n: usize) -> Result<Vec<ChunkedArray<T>>> {
-    split_array!(ca, n, i64)
+    if n == 1 {
+        return Ok(vec![ca.clone()]);
+    }
+    Ok(split_array!(ca, split_lengths(ca.len(), n), i64))
 }
 
 #[cfg(feature = "private")]
 pub fn split_series(s: &Series, n: usize) -> Result<Vec<Series>> {
-    split_array!(s, n, i64)
+    if n == 1 {
+        return Ok(vec![s.clone()]);
+    }
+    Ok(split_array!(s, split_lengths(s.len(), n), i64))
+}
+
+/// Split the ChunkedArray in consecutive parts of the given lengths. This is zero-copy.
+pub(crate) fn split_ca_at<T, I>(ca: &ChunkedArray<T>, lengths: I) -> Vec<ChunkedArray<T>>
+where
+    I: IntoIterator<Item = usize>,
+{
+    split_array!(ca, lengths, i64)
 }
 
+/// Split the Series in consecutive parts of the given lengths. This is zero-copy.
+pub(crate) fn split_series_at(s: &Series, lengths: &[usize]) -> Vec<Series> {
+    split_array!(s, lengths.iter().copied(), i64)
+}
+
+/// Split the DataFrame in `n` parts of about equal length.
+///
+/// If all columns share their chunk boundaries and a boundary is close to a split point, the
+/// DataFrame is split at that boundary instead of in the middle of a chunk.
 #[cfg(feature = "private")]
 pub fn split_df(df: &DataFrame, n: usize) -> Result<Vec<DataFrame>> {
-    trait Len {
-        fn len(&self) -> usize;
-    }
-    impl Len for DataFrame {
-        fn len(&self) -> usize {
-            self.height()
+    if n == 1 {
+        return Ok(vec![df.clone()]);
+    }
+    let total_len = df.height();
+    let chunk_size = total_len / n;
+    // a boundary is used if it deviates at most 10% of the chunk size from the split point
+    let tolerance = chunk_size / 10;
+
+    let columns = df.get_columns();
+    let boundaries: Vec<usize> = match columns.first() {
+        Some(first)
+            if columns[1..]
+                .iter()
+                .all(|s| s.chunk_lengths().eq(first.chunk_lengths())) =>
+        {
+            first
+                .chunk_lengths()
+                .scan(0, |offset, len| {
+                    *offset += len;
+                    Some(*offset)
+                })
+                .collect()
         }
+        _ => vec![],
+    };
+
+    let mut offsets = Vec::with_capacity(n + 1);
+    offsets.push(0);
+    for i in 1..n {
+        let split = i * chunk_size;
+        let prev = *offsets.last().unwrap();
+        let split = boundaries
+            .iter()
+            .copied()
+            .filter(|b| *b > prev && *b < total_len)
+            .min_by_key(|b| (*b as i64 - split as i64).abs())
+            .filter(|b| (*b as i64 - split as i64).abs() as usize <= tolerance)
+            .unwrap_or(split);
+        offsets.push(std::cmp::max(split, prev));
     }
-    split_array!(df, n, i64)
+    offsets.push(total_len);
+
+    Ok(offsets
+        .windows(2)
+        .map(|w| df.slice(w[0] as i64, w[1] - w[0]))
+        .collect())
 }
 
 #[inline]
@@ -481,6 +546,18 @@ macro_rules! df {
     }
 }
 
+/// The datatype of the sum of values of `dtype`. Integers smaller than 32 bits are summed as 64 bit
+/// integers, so that the sum doesn't overflow, and `Float32` is summed as `Float64`.
+pub fn get_sum_dtype(dtype: &DataType) -> DataType {
+    use DataType::*;
+    match dtype {
+        Int8 | Int16 => Int64,
+        UInt8 | UInt16 => UInt64,
+        Float32 => Float64,
+        dt => dt.clone(),
+    }
+}
+
 /// Given two datatypes, determine the supertype that both types can safely be cast to
 #[cfg(feature = "private")]
 pub fn get_supertype(l: &DataType, r: &DataType) -> Result<DataType> {
@@ -671,7 +748,9 @@ where
     I: IntoIterator<Item = DataFrame>,
 {
     let mut iter = dfs.into_iter();
-    let mut acc_df = iter.next().unwrap();
+    let mut acc_df = iter.next().ok_or_else(|| {
+        PolarsError::NoData("expected at least one DataFrame to accumulate".into())
+    })?;
     for df in iter {
         acc_df.vstack_mut(&df)?;
     }
@@ -684,7 +763,10 @@ where
     I: IntoIterator<Item = &'a DataFrame>,
 {
     let mut iter = dfs.into_iter();
-    let mut acc_df = iter.next().unwrap().clone();
+    let mut acc_df = iter
+        .next()
+        .ok_or_else(|| PolarsError::NoData("expected at least one DataFrame to concat".into()))?
+        .clone();
     for df in iter {
         acc_df.vstack_mut(df)?;
     }
@@ -700,6 +782,30 @@ pub fn accumulate_dataframes_horizontal(dfs: Vec<DataFrame>) -> Result<DataFrame
     Ok(acc_df)
 }
 
+/// The first column name that occurs more than once in `columns`.
+pub(crate) fn find_duplicate_name(columns: &[Series]) -> Option<&str> {
+    let mut names = PlHashSet::with_capacity(columns.len());
+    columns
+        .iter()
+        .map(|s| s.name())
+        .find(|name| !names.insert(*name))
+}
+
+/// Check that the names of the `columns` produced by `operation` are unique, so that they can
+/// form a `DataFrame`.
+pub fn ensure_unique_names(columns: &[Series], operation: &str) -> Result<()> {
+    match find_duplicate_name(columns) {
+        Some(name) => Err(PolarsError::Duplicate(
+            format!(
+                "{} produced more than one column with name '{}'",
+                operation, name
+            )
+            .into(),
+        )),
+        None => Ok(()),
+    }
+}
+
 /// Simple wrapper to parallelize functions that can be divided over threads aggregated and
 /// finally aggregated in the main thread. This can be done for sum, min, max, etc.
 #[cfg(feature = "private")]
@@ -722,37 +828,74 @@ where
     f(out)
 }
 
+/// Determine the chunk lengths of the coarsest chunking that has all the chunk boundaries of the
+/// inputs. The inputs should have the same length.
+pub(crate) fn common_chunk_lengths<I, J>(chunk_ids: I) -> Vec<usize>
+where
+    I: IntoIterator<Item = J>,
+    J: Iterator<Item = usize>,
+{
+    let mut boundaries = chunk_ids
+        .into_iter()
+        .flat_map(|chunk_id| {
+            chunk_id.scan(0, |offset, len| {
+                *offset += len;
+                Some(*offset)
+            })
+        })
+        .collect::<Vec<_>>();
+    boundaries.sort_unstable();
+    boundaries.dedup();
+
+    let mut offset = 0;
+    boundaries
+        .into_iter()
+        .filter(|end| *end > 0)
+        .map(|end| {
+            let len = end - offset;
+            offset = end;
+            len
+        })
+        .collect()
+}
+
+/// Split the chunks of `ca` such that they match `chunk_lengths`. This only slices the arrays
+/// and doesn't copy any data.
+fn split_chunks_cow<'a, T>(
+    ca: &'a ChunkedArray<T>,
+    chunk_lengths: &[usize],
+) -> Cow<'a, ChunkedArray<T>> {
+    if ca.chunk_id().eq(chunk_lengths.iter().copied()) || chunk_lengths.is_empty() {
+        Cow::Borrowed(ca)
+    } else {
+        Cow::Owned(ca.split_chunks(chunk_lengths.iter().copied()))
+    }
+}
+
+/// Align the chunks of two ChunkedArrays of equal length, such that their chunks can be zipped.
+///
+/// Instead of rechunking, chunks are split at the chunk boundaries of the other array. This is
+/// zero-copy.
 pub(crate) fn align_chunks_binary<'a, T, B>(
     left: &'a ChunkedArray<T>,
     right: &'a ChunkedArray<B>,
 ) -> (Cow<'a, ChunkedArray<T>>, Cow<'a, ChunkedArray<B>>)
 where
-    ChunkedArray<B>: ChunkOps,
-    ChunkedArray<T>: ChunkOps,
     B: PolarsDataType,
     T: PolarsDataType,
 {
-    match (left.chunks.len(), right.chunks.len()) {
-        (1, 1) => (Cow::Borrowed(left), Cow::Borrowed(right)),
-        (_, 1) => (
-            Cow::Borrowed(left),
-            Cow::Owned(right.match_chunks(left.chunk_id())),
-        ),
-        (1, _) => (
-            Cow::Owned(left.match_chunks(right.chunk_id())),
-            Cow::Borrowed(right),
-        ),
-        (_, _) => {
-            // could optimize to choose to rechunk a primitive and not a string or list type
-            let left = left.rechunk();
-            (
-                Cow::Owned(left.match_chunks(right.chunk_id())),
-                Cow::Borrowed(right),
-            )
-        }
+    debug_assert_eq!(left.len(), right.len());
+    if left.chunk_id().eq(right.chunk_id()) {
+        return (Cow::Borrowed(left), Cow::Borrowed(right));
     }
+    let chunk_lengths = common_chunk_lengths(vec![left.chunk_id(), right.chunk_id()]);
+    (
+        split_chunks_cow(left, &chunk_lengths),
+        split_chunks_cow(right, &chunk_lengths),
+    )
 }
 
+/// Align the chunks of three ChunkedArrays of equal length. See [align_chunks_binary].
 #[allow(clippy::type_complexity)]
 pub(crate) fn align_chunks_ternary<'a, A, B, C>(
     a: &'a ChunkedArray<A>,
@@ -764,65 +907,20 @@ pub(crate) fn align_chunks_ternary<'a, A, B, C>(
     Cow<'a, ChunkedArray<C>>,
 )
 where
-    ChunkedArray<A>: ChunkOps,
-    ChunkedArray<B>: ChunkOps,
-    ChunkedArray<C>: ChunkOps,
     A: PolarsDataType,
     B: PolarsDataType,
     C: PolarsDataType,
 {
-    match (a.chunks.len(), b.chunks.len(), c.chunks.len()) {
-        (1, 1, 1) => (Cow::Borrowed(a), Cow::Borrowed(b), Cow::Borrowed(c)),
-        (_, 1, 1) => (
-            Cow::Borrowed(a),
-            Cow::Owned(b.match_chunks(a.chunk_id())),
-            Cow::Owned(c.match_chunks(a.chunk_id())),
-        ),
-        (1, 1, _) => (
-            Cow::Owned(a.match_chunks(c.chunk_id())),
-            Cow::Owned(b.match_chunks(c.chunk_id())),
-            Cow::Borrowed(c),
-        ),
-        (1, _, 1) => (
-            Cow::Owned(a.match_chunks(b.chunk_id())),
-            Cow::Borrowed(b),
-            Cow::Owned(c.match_chunks(b.chunk_id())),
-        ),
-        (1, _, _) => {
-            let b = b.rechunk();
-            (
-                Cow::Owned(a.match_chunks(c.chunk_id())),
-                Cow::Owned(b.match_chunks(c.chunk_id())),
-                Cow::Borrowed(c),
-            )
-        }
-        (_, 1, _) => {
-            let a = a.rechunk();
-            (
-                Cow::Owned(a.match_chunks(c.chunk_id())),
-                Cow::Owned(b.match_chunks(c.chunk_id())),
-                Cow::Borrowed(c),
-            )
-        }
-        (_, _, 1) => {
-            let b = b.rechunk();
-            (
-                Cow::Borrowed(a),
-                Cow::Owned(b.match_chunks(a.chunk_id())),
-                Cow::Owned(c.match_chunks(a.chunk_id())),
-            )
-        }
-        _ => {
-            // could optimize to choose to rechunk a primitive and not a string or list type
-            let a = a.rechunk();
-            let b = b.rechunk();
-            (
-                Cow::Owned(a.match_chunks(c.chunk_id())),
-                Cow::Owned(b.match_chunks(c.chunk_id())),
-                Cow::Borrowed(c),
-            )
-        }
-    }
+    debug_assert!(a.len() == b.len() && b.len() == c.len());
+    if a.chunk_id().eq(b.chunk_id()) && b.chunk_id().eq(c.chunk_id()) {
+        return (Cow::Borrowed(a), Cow::Borrowed(b), Cow::Borrowed(c));
+    }
+    let chunk_lengths = common_chunk_lengths(vec![a.chunk_id(), b.chunk_id(), c.chunk_id()]);
+    (
+        split_chunks_cow(a, &chunk_lengths),
+        split_chunks_cow(b, &chunk_lengths),
+        split_chunks_cow(c, &chunk_lengths),
+    )
 }
 
 pub trait IntoVec<T> {
@@ -1,12 +1,14 @@
+use crate::chunked_array::ops::bit_repr::float_keys_to_bits;
 use crate::datatypes::UInt64Chunked;
+use crate::frame::hash_join::BuildIdx;
 use crate::prelude::*;
 use crate::POOL;
 use ahash::RandomState;
-use arrow::array::ArrayRef;
+use arrow::array::{Array, ArrayRef};
 use hashbrown::{hash_map::RawEntryMut, HashMap};
 use itertools::Itertools;
 use rayon::prelude::*;
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
 use std::hash::{BuildHasher, BuildHasherDefault, Hash, Hasher};
 
 // Read more:
@@ -145,7 +147,60 @@ impl VecHash for Float64Chunked {
     }
 }
 
-impl VecHash for ListChunked {}
+/// The Series of which the values are hashed for the elements of a list. Floats are hashed by
+/// their key bits, so that the hash agrees with the equality of the elements.
+fn list_values_to_hash(values: &Series) -> Series {
+    match values.dtype() {
+        DataType::Categorical => values.cast::<UInt32Type>().unwrap(),
+        _ => float_keys_to_bits(&values.to_physical_repr()),
+    }
+}
+
+/// Whether the elements of two lists are equal by the same rules as their hash, see
+/// [list_values_to_hash]. Float elements are compared by their key bits, so NaN equals NaN and
+/// -0.0 equals 0.0, unless the keys are compared bitwise.
+pub(crate) fn list_values_eq(a: &Series, b: &Series) -> bool {
+    a.len() == b.len() && list_values_to_hash(a).series_equal_missing(&list_values_to_hash(b))
+}
+
+impl VecHash for ListChunked {
+    fn vec_hash(&self, random_state: RandomState) -> AlignedVec<u64> {
+        let mut av = AlignedVec::with_capacity_aligned(self.len());
+        self.downcast_iter().for_each(|arr| {
+            // hash all the values of the chunk at once and combine the hashes of every list
+            let values = Series::try_from(("", arr.values())).unwrap();
+            let value_hashes = list_values_to_hash(&values).vec_hash(random_state.clone());
+            let value_hashes = value_hashes.as_slice();
+            let offsets = arr.value_offsets();
+
+            av.extend((0..arr.len()).map(|idx| {
+                let mut hasher = random_state.build_hasher();
+                if arr.is_null(idx) {
+                    None::<usize>.hash(&mut hasher);
+                    hasher.finish()
+                } else {
+                    let start = offsets[idx] as usize;
+                    let end = offsets[idx + 1] as usize;
+                    Some(end - start).hash(&mut hasher);
+                    value_hashes[start..end]
+                        .iter()
+                        .fold(hasher.finish(), |h, value_h| {
+                            boost_hash_combine(*value_h, h)
+                        })
+                }
+            }))
+        });
+        av
+    }
+
+    fn vec_hash_combine(&self, random_state: RandomState, hashes: &mut [u64]) {
+        let list_hashes = self.vec_hash(random_state);
+        hashes
+            .iter_mut()
+            .zip(list_hashes.as_slice())
+            .for_each(|(h, list_h)| *h = boost_hash_combine(*list_h, *h))
+    }
+}
 
 // Used to to get a u64 from the hashing keys
 // We need to modify the hashing algorithm to use the hash for this and only compute the hash once.
@@ -254,7 +309,7 @@ pub type IdBuildHasher = BuildHasherDefault<IdHasher>;
 /// accidental quadratic behavior. So do not use an Identity function!
 pub(crate) struct IdxHash {
     // idx in row of Series, DataFrame
-    pub(crate) idx: u32,
+    pub(crate) idx: IdxSize,
     // precomputed hash of T
     hash: u64,
 }
@@ -267,7 +322,7 @@ impl Hash for IdxHash {
 
 impl IdxHash {
     #[inline]
-    pub(crate) fn new(idx: u32, hash: u64) -> Self {
+    pub(crate) fn new(idx: IdxSize, hash: u64) -> Self {
         IdxHash { idx, hash }
     }
 }
@@ -374,7 +429,7 @@ pub(crate) fn this_partition(h: u64, thread_no: u64, n_partitions: u64) -> bool
 
 pub(crate) fn prepare_hashed_relation_threaded<T, I>(
     iters: Vec<I>,
-) -> Vec<HashMap<T, Vec<u32>, RandomState>>
+) -> Vec<HashMap<T, BuildIdx, RandomState>>
 where
     I: Iterator<Item = T> + Send,
     T: Send + Hash + Eq + Sync + Copy,
@@ -390,7 +445,7 @@ where
             let build_hasher = build_hasher.clone();
             let hashes_and_keys = &hashes_and_keys;
             let thread_no = thread_no as u64;
-            let mut hash_tbl: HashMap<T, Vec<u32>, RandomState> =
+            let mut hash_tbl: HashMap<T, BuildIdx, RandomState> =
                 HashMap::with_hasher(build_hasher);
 
             let n_threads = (n_threads as u64).into();
@@ -401,7 +456,7 @@ where
                     .iter()
                     .enumerate()
                     .for_each(|(idx, (h, k))| {
-                        let idx = idx as u32;
+                        let idx = idx as IdxSize;
                         // partition hashes by thread no.
                         // So only a part of the hashes go to this hashmap
                         if this_thread(*h, thread_no, n_threads) {
@@ -413,7 +468,7 @@ where
 
                             match entry {
                                 RawEntryMut::Vacant(entry) => {
-                                    entry.insert_hashed_nocheck(*h, *k, vec![idx]);
+                                    entry.insert_hashed_nocheck(*h, *k, BuildIdx::new(idx));
                                 }
                                 RawEntryMut::Occupied(mut entry) => {
                                     let (_k, v) = entry.get_key_value_mut();
@@ -423,7 +478,7 @@ where
                         }
                     });
 
-                offset += len as u32;
+                offset += len as IdxSize;
             }
             hash_tbl
         })
@@ -500,3 +555,259 @@ pub(crate) fn df_rows_to_hashes(
     let chunks = vec![Arc::new(hashes.into_primitive_array::<UInt64Type>(None)) as ArrayRef];
     (UInt64Chunked::new_from_chunks("", chunks), build_hasher)
 }
+
+// Stable row hashing.
+//
+// Contrary to the hashes above, these hashes don't depend on a `RandomState` and must remain
+// the same between polars versions and platforms. Every value is serialized to a fixed byte
+// representation and hashed with XXH64:
+//
+//  * integers, dates and times are widened to 64 bits (sign extended if signed) and hashed as
+//    8 little endian bytes, so `1i32`, `1i64` and `1u8` have the same hash.
+//  * floats are widened to `f64` and hashed as the 8 little endian bytes of their bit pattern.
+//    `-0.0` is hashed as `0.0` and every `NaN` as `0x7ff8_0000_0000_0000`.
+//  * booleans are hashed as a single byte, `0` or `1`.
+//  * strings are hashed as their utf8 bytes. Categoricals are hashed by their string value.
+//  * nulls don't get hashed but have the fixed hash `STABLE_NULL_HASH`, regardless of the seed.
+//  * other dtypes, e.g. lists and objects, cannot be hashed.
+//
+// The hashes of multiple columns are combined from left to right with:
+//
+//  `acc = h ^ (acc + 0x9e3779b9 + (h << 6) + (acc >> 2))` (wrapping arithmetic)
+//
+// where `acc` starts as the hash of the first column and `h` is the hash of the next column.
+// This combination is order dependent, so swapping columns changes the row hashes.
+//
+// Changing any of the above is a breaking change!
+#[cfg(feature = "row_hash")]
+pub(crate) const STABLE_NULL_HASH: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// Combine the hash `acc` of the previous columns with the hash `h` of the next column.
+/// This has its own copy of the formula, so that `boost_hash_combine` can change without
+/// changing the stable hashes.
+#[cfg(feature = "row_hash")]
+#[inline]
+fn stable_hash_combine(acc: u64, h: u64) -> u64 {
+    h ^ acc.wrapping_add(0x9e3779b9u64.wrapping_add(h << 6).wrapping_add(acc >> 2))
+}
+
+#[cfg(feature = "row_hash")]
+mod xxh64 {
+    //! XXH64 as specified in https://github.com/Cyan4973/xxHash/blob/dev/doc/xxhash_spec.md
+    use std::convert::TryInto;
+
+    const PRIME64_1: u64 = 0x9E37_79B1_85EB_CA87;
+    const PRIME64_2: u64 = 0xC2B2_AE3D_27D4_EB4F;
+    const PRIME64_3: u64 = 0x1656_67B1_9E37_79F9;
+    const PRIME64_4: u64 = 0x85EB_CA77_C2B2_AE63;
+    const PRIME64_5: u64 = 0x27D4_EB2F_1656_67C5;
+
+    #[inline]
+    fn round(acc: u64, input: u64) -> u64 {
+        acc.wrapping_add(input.wrapping_mul(PRIME64_2))
+            .rotate_left(31)
+            .wrapping_mul(PRIME64_1)
+    }
+
+    #[inline]
+    fn merge_round(acc: u64, val: u64) -> u64 {
+        (acc ^ round(0, val))
+            .wrapping_mul(PRIME64_1)
+            .wrapping_add(PRIME64_4)
+    }
+
+    #[inline]
+    fn avalanche(mut h: u64) -> u64 {
+        h ^= h >> 33;
+        h = h.wrapping_mul(PRIME64_2);
+        h ^= h >> 29;
+        h = h.wrapping_mul(PRIME64_3);
+        h ^ (h >> 32)
+    }
+
+    #[inline]
+    fn read_u64(input: &[u8]) -> u64 {
+        u64::from_le_bytes(input[..8].try_into().unwrap())
+    }
+
+    #[inline]
+    fn read_u32(input: &[u8]) -> u64 {
+        u32::from_le_bytes(input[..4].try_into().unwrap()) as u64
+    }
+
+    pub(crate) fn xxh64(mut input: &[u8], seed: u64) -> u64 {
+        let len = input.len() as u64;
+        let mut h = if input.len() >= 32 {
+            let mut v1 = seed.wrapping_add(PRIME64_1).wrapping_add(PRIME64_2);
+            let mut v2 = seed.wrapping_add(PRIME64_2);
+            let mut v3 = seed;
+            let mut v4 = seed.wrapping_sub(PRIME64_1);
+            while input.len() >= 32 {
+                v1 = round(v1, read_u64(input));
+                v2 = round(v2, read_u64(&input[8..]));
+                v3 = round(v3, read_u64(&input[16..]));
+                v4 = round(v4, read_u64(&input[24..]));
+                input = &input[32..];
+            }
+            let mut h = v1
+                .rotate_left(1)
+                .wrapping_add(v2.rotate_left(7))
+                .wrapping_add(v3.rotate_left(12))
+                .wrapping_add(v4.rotate_left(18));
+            h = merge_round(h, v1);
+            h = merge_round(h, v2);
+            h = merge_round(h, v3);
+            merge_round(h, v4)
+        } else {
+            seed.wrapping_add(PRIME64_5)
+        };
+        h = h.wrapping_add(len);
+
+        while input.len() >= 8 {
+            h ^= round(0, read_u64(input));
+            h = h
+                .rotate_left(27)
+                .wrapping_mul(PRIME64_1)
+                .wrapping_add(PRIME64_4);
+            input = &input[8..];
+        }
+        if input.len() >= 4 {
+            h ^= read_u32(input).wrapping_mul(PRIME64_1);
+            h = h
+                .rotate_left(23)
+                .wrapping_mul(PRIME64_2)
+                .wrapping_add(PRIME64_3);
+            input = &input[4..];
+        }
+        for &b in input {
+            h ^= (b as u64).wrapping_mul(PRIME64_5);
+            h = h.rotate_left(11).wrapping_mul(PRIME64_1);
+        }
+        avalanche(h)
+    }
+
+    /// Equal to `xxh64(&v.to_le_bytes(), seed)`, but without the branches.
+    #[inline]
+    pub(crate) fn xxh64_u64(v: u64, seed: u64) -> u64 {
+        let mut h = seed.wrapping_add(PRIME64_5).wrapping_add(8);
+        h ^= round(0, v);
+        h = h
+            .rotate_left(27)
+            .wrapping_mul(PRIME64_1)
+            .wrapping_add(PRIME64_4);
+        avalanche(h)
+    }
+}
+
+#[cfg(feature = "row_hash")]
+fn extend_stable_hashes<I, T, F>(av: &mut AlignedVec<u64>, iter: I, f: F)
+where
+    I: Iterator<Item = Option<T>>,
+    F: Fn(T) -> u64,
+{
+    av.extend(iter.map(|opt_v| opt_v.map_or(STABLE_NULL_HASH, &f)))
+}
+
+/// Compute the stable hash of every value in the `Series`.
+#[cfg(feature = "row_hash")]
+pub(crate) fn series_to_stable_hashes(s: &Series, seed: u64) -> Result<AlignedVec<u64>> {
+    use self::xxh64::{xxh64, xxh64_u64};
+    use DataType::*;
+
+    let mut av = AlignedVec::with_capacity_aligned(s.len());
+    match s.dtype() {
+        Boolean => s.bool()?.downcast_iter().for_each(|arr| {
+            extend_stable_hashes(&mut av, arr.into_iter(), |v| xxh64(&[v as u8], seed))
+        }),
+        Utf8 => s.utf8()?.downcast_iter().for_each(|arr| {
+            extend_stable_hashes(&mut av, arr.into_iter(), |v| xxh64(v.as_bytes(), seed))
+        }),
+        Categorical => return series_to_stable_hashes(&s.cast::<Utf8Type>()?, seed),
+        Null => av.extend(std::iter::repeat(STABLE_NULL_HASH).take(s.len())),
+        Float32 | Float64 => {
+            let s = s.cast::<Float64Type>()?;
+            s.f64()?.downcast_iter().for_each(|arr| {
+                extend_stable_hashes(&mut av, arr.into_iter(), |v| {
+                    let v = if v.is_nan() {
+                        f64::NAN
+                    } else if v == 0.0 {
+                        0.0
+                    } else {
+                        v
+                    };
+                    xxh64_u64(v.to_bits(), seed)
+                })
+            })
+        }
+        UInt64 => {
+            let ca = s.u64()?;
+            ca.downcast_iter().for_each(|arr| {
+                extend_stable_hashes(&mut av, arr.into_iter(), |v| xxh64_u64(v, seed))
+            })
+        }
+        Int8 | Int16 | Int32 | Int64 | UInt8 | UInt16 | UInt32 | Date32 | Date64 | Time64(_)
+        | Duration(_) => {
+            let s = s.cast::<Int64Type>()?;
+            s.i64()?.downcast_iter().for_each(|arr| {
+                extend_stable_hashes(&mut av, arr.into_iter(), |v| xxh64_u64(v as u64, seed))
+            })
+        }
+        dt => {
+            return Err(PolarsError::InvalidOperation(
+                format!("stable hashing is not supported for dtype {:?}", dt).into(),
+            ))
+        }
+    }
+    Ok(av)
+}
+
+#[cfg(feature = "row_hash")]
+pub(crate) fn df_rows_to_stable_hashes(keys: &DataFrame, seed: u64) -> Result<UInt64Chunked> {
+    let mut iter = keys.iter();
+    let first = iter.next().expect("at least one key");
+    let mut hashes = series_to_stable_hashes(first, seed)?;
+    let hslice = hashes.as_mut_slice();
+
+    for keys in iter {
+        let h = series_to_stable_hashes(keys, seed)?;
+        hslice
+            .iter_mut()
+            .zip(h.as_slice())
+            .for_each(|(acc, h)| *acc = stable_hash_combine(*acc, *h));
+    }
+
+    Ok(UInt64Chunked::new_from_aligned_vec("", hashes))
+}
+
+/// Same as [`df_rows_to_hashes_threaded`], but computes the stable hashes.
+#[cfg(feature = "row_hash")]
+pub(crate) fn df_rows_to_stable_hashes_threaded(
+    keys: &[DataFrame],
+    seed: u64,
+) -> Result<Vec<UInt64Chunked>> {
+    POOL.install(|| {
+        keys.into_par_iter()
+            .map(|df| df_rows_to_stable_hashes(df, seed))
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    #[cfg(feature = "row_hash")]
+    fn test_xxh64() {
+        use super::xxh64::{xxh64, xxh64_u64};
+        // reference values of the xxHash implementation
+        assert_eq!(xxh64(b"", 0), 0xEF46_DB37_51D8_E999);
+        assert_eq!(xxh64(b"a", 0), 0xD24E_C4F1_A98C_6E5B);
+        assert_eq!(xxh64(b"abc", 0), 0x44BC_2CF5_AD77_0999);
+        assert_eq!(
+            xxh64(b"Nobody inspects the spammish repetition", 0),
+            0xFBCE_A83C_8A37_8BF1
+        );
+        for v in &[0u64, 1, 12345, u64::MAX] {
+            assert_eq!(xxh64(&v.to_le_bytes(), 7), xxh64_u64(*v, 7));
+        }
+    }
+}
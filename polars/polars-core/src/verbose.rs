@@ -0,0 +1,125 @@
+//! Logging of the verbose messages of the query engine. Verbose logging is enabled by setting the
+//! `POLARS_VERBOSE` environment variable. The messages are written to stderr, or with the `tracing`
+//! feature they are emitted as debug level `tracing` events with the fields of [VerboseEvent].
+use lazy_static::lazy_static;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+pub const POLARS_VERBOSE: &str = "POLARS_VERBOSE";
+
+static CAPTURING: AtomicBool = AtomicBool::new(false);
+lazy_static! {
+    static ref CAPTURED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+    // only one caller can capture at a time
+    static ref CAPTURE_LOCK: Mutex<()> = Mutex::new(());
+}
+
+/// Check if verbose messages should be logged.
+pub fn verbose() -> bool {
+    CAPTURING.load(Ordering::Acquire) || std::env::var(POLARS_VERBOSE).is_ok()
+}
+
+/// A verbose message of an operation in the query engine.
+pub struct VerboseEvent<'a> {
+    /// The operation that logs the message, e.g. `"filter"` or `"csv scan"`.
+    pub node: &'static str,
+    /// The file that is read by the operation.
+    pub path: Option<&'a str>,
+    /// The number of rows that are produced by the operation.
+    pub rows: Option<usize>,
+    pub message: fmt::Arguments<'a>,
+}
+
+impl<'a> VerboseEvent<'a> {
+    pub fn new(node: &'static str, message: fmt::Arguments<'a>) -> Self {
+        VerboseEvent {
+            node,
+            path: None,
+            rows: None,
+            message,
+        }
+    }
+
+    pub fn with_path(mut self, path: &'a str) -> Self {
+        self.path = Some(path);
+        self
+    }
+
+    pub fn with_rows(mut self, rows: usize) -> Self {
+        self.rows = Some(rows);
+        self
+    }
+}
+
+impl fmt::Display for VerboseEvent<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.node, self.message)
+    }
+}
+
+/// Log a verbose message. This should only be called if [verbose] returns `true`.
+pub fn log_verbose(event: VerboseEvent) {
+    if CAPTURING.load(Ordering::Acquire) {
+        CAPTURED.lock().unwrap().push(event.to_string());
+    } else {
+        emit(event)
+    }
+}
+
+#[cfg(not(feature = "tracing"))]
+fn emit(event: VerboseEvent) {
+    eprintln!("{}", event)
+}
+
+#[cfg(feature = "tracing")]
+fn emit(event: VerboseEvent) {
+    tracing::debug!(
+        target: "polars",
+        node = event.node,
+        path = event.path,
+        rows = event.rows,
+        "{}",
+        event.message
+    )
+}
+
+struct CaptureGuard;
+
+impl Drop for CaptureGuard {
+    fn drop(&mut self) {
+        CAPTURING.store(false, Ordering::Release);
+    }
+}
+
+/// Run `f` with verbose logging enabled and return the messages that are logged while it runs,
+/// instead of writing them. This is a hook for the tests to assert on the activity of the query
+/// engine. Messages that other threads log at the same time are captured as well.
+#[doc(hidden)]
+pub fn capture_verbose<R, F: FnOnce() -> R>(f: F) -> (R, Vec<String>) {
+    let _lock = CAPTURE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    CAPTURED.lock().unwrap().clear();
+    let out = {
+        let _guard = CaptureGuard;
+        CAPTURING.store(true, Ordering::Release);
+        f()
+    };
+    let messages = std::mem::take(&mut *CAPTURED.lock().unwrap());
+    (out, messages)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_capture_verbose() {
+        let (out, messages) = capture_verbose(|| {
+            assert!(verbose());
+            log_verbose(VerboseEvent::new("test", format_args!("read {} rows", 3)).with_rows(3));
+            1
+        });
+        assert_eq!(out, 1);
+        assert_eq!(messages, &["test: read 3 rows"]);
+    }
+}
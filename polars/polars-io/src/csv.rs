@@ -42,14 +42,16 @@
 //! ```
 //!
 use crate::csv_core::csv::{build_csv_reader, SequentialReader};
+use crate::csv_core::parser::{next_row_position, QUOTE_CHAR};
 use crate::mmap::MmapBytesReader;
 use crate::utils::{resolve_homedir, to_arrow_compatible_df};
-use crate::{SerReader, SerWriter};
+use crate::{apply_row_count, RowCount, SerReader, SerWriter};
 pub use arrow::csv::WriterBuilder;
 use polars_core::prelude::*;
 use std::fs::File;
 use std::io::{Read, Seek, Write};
-use std::path::PathBuf;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 /// Write a DataFrame to csv.
@@ -124,6 +126,99 @@ where
     }
 }
 
+/// Options for writing csv files.
+#[derive(Clone, Debug)]
+pub struct CsvWriterOptions {
+    pub has_header: bool,
+    pub delimiter: u8,
+    pub date_format: Option<String>,
+    pub time_format: Option<String>,
+    pub timestamp_format: Option<String>,
+}
+
+impl Default for CsvWriterOptions {
+    fn default() -> Self {
+        CsvWriterOptions {
+            has_header: true,
+            delimiter: b',',
+            date_format: None,
+            time_format: None,
+            timestamp_format: None,
+        }
+    }
+}
+
+impl CsvWriterOptions {
+    /// Set whether to write headers
+    pub fn with_header(mut self, has_header: bool) -> Self {
+        self.has_header = has_header;
+        self
+    }
+
+    /// Set the CSV file's column delimiter as a byte character
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Set the CSV file's date format
+    pub fn with_date_format(mut self, format: Option<String>) -> Self {
+        self.date_format = format;
+        self
+    }
+
+    /// Set the CSV file's time format
+    pub fn with_time_format(mut self, format: Option<String>) -> Self {
+        self.time_format = format;
+        self
+    }
+
+    /// Set the CSV file's timestamp format
+    pub fn with_timestamp_format(mut self, format: Option<String>) -> Self {
+        self.timestamp_format = format;
+        self
+    }
+
+    fn writer_builder(&self) -> WriterBuilder {
+        let mut builder = WriterBuilder::new()
+            .has_headers(self.has_header)
+            .with_delimiter(self.delimiter);
+        if let Some(format) = &self.date_format {
+            builder = builder.with_date_format(format.clone());
+        }
+        if let Some(format) = &self.time_format {
+            builder = builder.with_time_format(format.clone());
+        }
+        if let Some(format) = &self.timestamp_format {
+            builder = builder.with_timestamp_format(format.clone());
+        }
+        builder
+    }
+}
+
+/// Write DataFrames with the same schema to a single csv file, one batch at a time.
+/// The header is written with the first batch.
+pub struct BatchedCsvWriter<W: Write> {
+    writer: arrow::csv::Writer<W>,
+}
+
+impl<W: Write> BatchedCsvWriter<W> {
+    pub fn new(writer: W, options: &CsvWriterOptions) -> Self {
+        BatchedCsvWriter {
+            writer: options.writer_builder().build(writer),
+        }
+    }
+
+    /// Write the chunks of `df` to the file.
+    pub fn write_batch(&mut self, df: &DataFrame) -> Result<()> {
+        let df = to_arrow_compatible_df(df);
+        for batch in df.iter_record_batches() {
+            self.writer.write(&batch)?
+        }
+        Ok(())
+    }
+}
+
 #[derive(Copy, Clone)]
 pub enum CsvEncoding {
     /// Utf8 encoding
@@ -132,7 +227,7 @@ pub enum CsvEncoding {
     LossyUtf8,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Hash)]
 pub enum NullValues {
     /// A single value that's used for all columns
     AllColumns(String),
@@ -161,6 +256,134 @@ impl NullValues {
     }
 }
 
+/// Options that control which data types are produced by CSV schema inference.
+#[derive(Clone, Debug)]
+pub struct InferenceOptions {
+    /// Data types that inference may produce, from narrowest to widest. A value gets the first
+    /// data type it fits in. If a column has values of multiple numeric types, the one that
+    /// comes last wins; any other conflict defaults to `Utf8`.
+    pub dtypes: Vec<DataType>,
+    /// Treat empty fields as null. If `false`, empty fields are inferred as `Utf8`.
+    pub empty_as_null: bool,
+    /// Data type of columns that only have nulls in the rows read for inference.
+    pub null_dtype: DataType,
+}
+
+impl Default for InferenceOptions {
+    fn default() -> Self {
+        InferenceOptions {
+            dtypes: vec![
+                DataType::Boolean,
+                DataType::Int64,
+                DataType::Float64,
+                DataType::Utf8,
+            ],
+            empty_as_null: true,
+            null_dtype: DataType::Utf8,
+        }
+    }
+}
+
+impl InferenceOptions {
+    /// Set the data types that inference may produce, from narrowest to widest.
+    /// Only `Boolean`, `UInt8`, `UInt16`, `Int32`, `Int64`, `UInt32`, `UInt64`, `Float32`,
+    /// `Float64` and `Utf8` are supported. `UInt8` and `UInt16` need the `dtype-u8` and
+    /// `dtype-u16` features.
+    pub fn with_dtypes(mut self, dtypes: Vec<DataType>) -> Self {
+        self.dtypes = dtypes;
+        self
+    }
+
+    /// Set whether empty fields are treated as null during inference.
+    pub fn with_empty_as_null(mut self, toggle: bool) -> Self {
+        self.empty_as_null = toggle;
+        self
+    }
+
+    /// Set the data type of columns that have no values in the rows read for inference.
+    pub fn with_null_dtype(mut self, dtype: DataType) -> Self {
+        self.null_dtype = dtype;
+        self
+    }
+}
+
+/// Infer the schema of a CSV file by reading at most `max_read_records` records.
+/// If `max_read_records` is `None`, the whole file is read.
+///
+/// This uses the same inference as [CsvReader](CsvReader), so the returned schema can be passed
+/// to [CsvReader::with_schema](CsvReader::with_schema) when reading similar files.
+/// The reader is rewound to the start of the file.
+///
+/// Returns the inferred schema and the number of records read.
+pub fn infer_file_schema<R: Read + Seek>(
+    reader: &mut R,
+    delimiter: u8,
+    max_read_records: Option<usize>,
+    has_header: bool,
+    skip_rows: usize,
+    comment_char: Option<u8>,
+) -> Result<(Schema, usize)> {
+    infer_file_schema_with_options(
+        reader,
+        delimiter,
+        max_read_records,
+        has_header,
+        skip_rows,
+        comment_char,
+        &InferenceOptions::default(),
+    )
+}
+
+/// Infer the schema of a CSV file with the given [InferenceOptions](InferenceOptions).
+/// See [infer_file_schema](infer_file_schema).
+pub fn infer_file_schema_with_options<R: Read + Seek>(
+    reader: &mut R,
+    delimiter: u8,
+    max_read_records: Option<usize>,
+    has_header: bool,
+    skip_rows: usize,
+    comment_char: Option<u8>,
+    options: &InferenceOptions,
+) -> Result<(Schema, usize)> {
+    crate::csv_core::utils::infer_file_schema(
+        reader,
+        delimiter,
+        max_read_records,
+        has_header,
+        None,
+        skip_rows,
+        comment_char,
+        b'\n',
+        options,
+    )
+}
+
+/// Read the column names from the header of a CSV file, without reading any data.
+///
+/// The column names are split in the same way as by [CsvReader](CsvReader): a leading utf8-bom
+/// is skipped and delimiters and new lines in names that are quoted with `quote_char` are kept.
+/// A name that occurs more than once is renamed to `{name}_duplicated_{n}`, unless
+/// `error_on_duplicates` is set, in which case an error is returned.
+pub fn read_header<R: Read>(
+    reader: R,
+    delimiter: u8,
+    quote_char: u8,
+    error_on_duplicates: bool,
+) -> Result<Vec<String>> {
+    crate::csv_core::utils::read_header(reader, delimiter, quote_char, error_on_duplicates)
+}
+
+/// Count the rows of a CSV file, excluding the header if `has_header` is set.
+///
+/// The rows are counted without parsing their fields. They are split in the same way as by
+/// [CsvReader](CsvReader), so new lines in fields that are quoted with `quote_char` don't start
+/// a new row and empty lines are not counted. As the fields are not split, no delimiter is
+/// needed. The file is split in chunks that are counted in parallel.
+pub fn count_rows<P: AsRef<Path>>(path: P, quote_char: u8, has_header: bool) -> Result<usize> {
+    let path = resolve_homedir(path.as_ref());
+    crate::csv_core::utils::count_rows(&path, quote_char, has_header)
+}
+
 /// Create a new DataFrame by reading a csv file.
 ///
 /// # Example
@@ -185,11 +408,15 @@ where
     reader: R,
     /// Aggregates chunk afterwards to a single chunk.
     pub rechunk: bool,
+    /// Add a row count column as first column.
+    pub row_count: Option<RowCount>,
     /// Stop reading from the csv after this number of rows is reached
     stop_after_n_rows: Option<usize>,
     // used by error ignore logic
     max_records: Option<usize>,
     skip_rows: usize,
+    skip_rows_after_header: usize,
+    byte_range: Option<Range<u64>>,
     /// Optional indexes of the columns to project
     projection: Option<Vec<usize>>,
     /// Optional column names to project/ select.
@@ -207,6 +434,8 @@ where
     low_memory: bool,
     comment_char: Option<u8>,
     null_values: Option<NullValues>,
+    eol_char: u8,
+    inference_options: InferenceOptions,
 }
 
 impl<'a, R> CsvReader<'a, R>
@@ -232,6 +461,11 @@ where
         self
     }
 
+    /// Read at most `n_rows` rows. Alias for [with_stop_after_n_rows](Self::with_stop_after_n_rows).
+    pub fn with_n_rows(self, n_rows: Option<usize>) -> Self {
+        self.with_stop_after_n_rows(n_rows)
+    }
+
     /// Continue with next batch when a ParserError is encountered.
     pub fn with_ignore_parser_errors(mut self, ignore: bool) -> Self {
         self.ignore_parser_errors = ignore;
@@ -253,12 +487,41 @@ where
         self
     }
 
+    /// Skip `n` rows after the header. In contrast to [with_skip_rows](Self::with_skip_rows),
+    /// new line characters in quoted fields are respected.
+    pub fn with_skip_rows_after_header(mut self, n: usize) -> Self {
+        self.skip_rows_after_header = n;
+        self
+    }
+
+    /// Only read the rows that start in the given byte range of the file.
+    ///
+    /// The reader moves the start and the end of the range forward to the next row boundary,
+    /// taking quoted fields into account. This makes it possible to split a file over multiple
+    /// workers: if the file is split in consecutive ranges, every row is read by exactly one
+    /// worker. Determining whether the start is in a quoted field requires a scan for quotes
+    /// over the bytes that precede the range.
+    ///
+    /// The header is always read from the start of the file, so every worker uses the same
+    /// schema. To skip schema inference, pass the schema with [with_schema](Self::with_schema).
+    pub fn with_byte_range(mut self, range: Range<u64>) -> Self {
+        self.byte_range = Some(range);
+        self
+    }
+
     /// Rechunk the DataFrame to contiguous memory after the CSV is parsed.
     pub fn with_rechunk(mut self, rechunk: bool) -> Self {
         self.rechunk = rechunk;
         self
     }
 
+    /// Add a `UInt32` column with the row index as first column. The rows are counted after a
+    /// predicate is applied.
+    pub fn with_row_count(mut self, row_count: Option<RowCount>) -> Self {
+        self.row_count = row_count;
+        self
+    }
+
     /// Set whether the CSV file has headers
     pub fn has_header(mut self, has_header: bool) -> Self {
         self.has_header = has_header;
@@ -283,6 +546,13 @@ where
         self
     }
 
+    /// Set the character that ends a line. With the default `b'\n'`, `\r\n` and a
+    /// bare `\r` also end a line.
+    pub fn with_eol_char(mut self, eol_char: u8) -> Self {
+        self.eol_char = eol_char;
+        self
+    }
+
     /// Overwrite the schema with the dtypes in this given Schema. The given schema may be a subset
     /// of the total schema.
     pub fn with_dtypes(mut self, schema: Option<&'a Schema>) -> Self {
@@ -301,6 +571,12 @@ where
         self
     }
 
+    /// Set the options that control which data types schema inference may produce.
+    pub fn with_inference_options(mut self, options: InferenceOptions) -> Self {
+        self.inference_options = options;
+        self
+    }
+
     /// Set the reader's column projection. This counts from 0, meaning that
     /// `vec![0, 4]` would select the 1st and 5th column.
     pub fn with_projection(mut self, projection: Option<Vec<usize>>) -> Self {
@@ -308,7 +584,7 @@ where
         self
     }
 
-    /// Columns to select/ project
+    /// Columns to select/ project. The columns are returned in the given order.
     pub fn with_columns(mut self, columns: Option<Vec<String>>) -> Self {
         self.columns = columns;
         self
@@ -350,6 +626,8 @@ where
             self.reader,
             self.stop_after_n_rows,
             self.skip_rows,
+            self.skip_rows_after_header,
+            self.byte_range,
             self.projection,
             self.max_records,
             self.delimiter,
@@ -366,6 +644,8 @@ where
             self.low_memory,
             self.comment_char,
             self.null_values,
+            self.eol_char,
+            &self.inference_options,
         )
     }
 }
@@ -379,6 +659,21 @@ impl<'a> CsvReader<'a, File> {
     }
 }
 
+/// Cast a column that is read with the inferred dtype. Dates that are read as strings are
+/// parsed with the format that [Utf8Chunked::as_date64] infers, so that the reader and the
+/// `strptime` kernels agree on what is a valid date.
+fn cast_to_dtype(s: &Series, dtype: &DataType) -> Result<Series> {
+    match (s.dtype(), dtype) {
+        #[cfg(any(feature = "dtype-date32", feature = "dtype-date64"))]
+        (DataType::Utf8, DataType::Date32) | (DataType::Utf8, DataType::Date64)
+            if s.null_count() < s.len() =>
+        {
+            s.utf8()?.strptime(dtype, None, false)
+        }
+        _ => s.cast_with_dtype(dtype),
+    }
+}
+
 impl<'a, R> SerReader<R> for CsvReader<'a, R>
 where
     R: Read + Seek + Sync + Send + MmapBytesReader,
@@ -388,9 +683,12 @@ where
         CsvReader {
             reader,
             rechunk: true,
+            row_count: None,
             stop_after_n_rows: None,
             max_records: Some(128),
             skip_rows: 0,
+            skip_rows_after_header: 0,
+            byte_range: None,
             projection: None,
             delimiter: None,
             has_header: true,
@@ -406,14 +704,22 @@ where
             low_memory: false,
             comment_char: None,
             null_values: None,
+            eol_char: b'\n',
+            inference_options: InferenceOptions::default(),
         }
     }
 
+    fn set_rechunk(mut self, rechunk: bool) -> Self {
+        self.rechunk = rechunk;
+        self
+    }
+
     /// Read the file and create the DataFrame.
     fn finish(self) -> Result<DataFrame> {
         let rechunk = self.rechunk;
+        let row_count = self.row_count.clone();
 
-        let mut df = if let Some(schema) = self.schema_overwrite {
+        let df = if let Some(schema) = self.schema_overwrite {
             // This branch we check if there are dtypes we cannot parse.
             // We only support a few dtypes in the parser and later cast to the required dtype
             let mut to_cast = Vec::with_capacity(schema.len());
@@ -446,6 +752,8 @@ where
                 self.reader,
                 self.stop_after_n_rows,
                 self.skip_rows,
+                self.skip_rows_after_header,
+                self.byte_range,
                 self.projection,
                 self.max_records,
                 self.delimiter,
@@ -462,18 +770,21 @@ where
                 self.low_memory,
                 self.comment_char,
                 self.null_values,
+                self.eol_char,
+                &self.inference_options,
             )?;
             let mut df = csv_reader.as_df(None, None)?;
 
             // cast to the original dtypes in the schema
             for fld in to_cast {
-                df.may_apply(fld.name(), |s| s.cast_with_dtype(fld.data_type()))?;
+                df.may_apply(fld.name(), |s| cast_to_dtype(s, fld.data_type()))?;
             }
             df
         } else {
             let mut csv_reader = self.build_inner_reader()?;
             csv_reader.as_df(None, None)?
         };
+        let mut df = apply_row_count(df, row_count.as_ref())?;
 
         // Important that this rechunk is never done in parallel.
         // As that leads to great memory overhead.
@@ -484,6 +795,47 @@ where
     }
 }
 
+/// Split csv data into consecutive byte ranges of at least `batch_size` bytes, for use with
+/// [CsvReader::with_byte_range]. Every range ends at the end of a row, where line endings in
+/// quoted fields don't end a row, so the ranges never split a row.
+///
+/// The quotes are counted once while walking the data, instead of once per range.
+pub fn row_aligned_byte_ranges(bytes: &[u8], batch_size: usize, eol_char: u8) -> Vec<Range<u64>> {
+    let batch_size = std::cmp::max(batch_size, 1);
+    let mut ranges = vec![];
+    let mut start = 0;
+    while start < bytes.len() {
+        let last = std::cmp::min(start + batch_size, bytes.len()) - 1;
+        // a range starts at the start of a row, which is never in a quoted field
+        let in_quotes = bytes[start..last]
+            .iter()
+            .filter(|&&b| b == QUOTE_CHAR)
+            .count()
+            % 2
+            == 1;
+        let end = next_row_position(&bytes[last..], in_quotes, QUOTE_CHAR, eol_char)
+            .map(|pos| last + pos)
+            .unwrap_or_else(|| bytes.len());
+        ranges.push(start as u64..end as u64);
+        start = end;
+    }
+    ranges
+}
+
+/// [row_aligned_byte_ranges] of a csv file, which is memory mapped.
+pub fn file_row_aligned_byte_ranges<P: AsRef<Path>>(
+    path: P,
+    batch_size: usize,
+    eol_char: u8,
+) -> Result<Vec<Range<u64>>> {
+    let file = File::open(resolve_homedir(path.as_ref()))?;
+    if file.metadata()?.len() == 0 {
+        return Ok(vec![]);
+    }
+    let mmap = unsafe { memmap::Mmap::map(&file)? };
+    Ok(row_aligned_byte_ranges(&mmap[..], batch_size, eol_char))
+}
+
 #[cfg(test)]
 mod test {
     use crate::prelude::*;
@@ -688,6 +1040,32 @@ mod test {
         )));
     }
 
+    #[test]
+    fn test_escape_minority_of_fields() {
+        // only some of the quoted fields have escaped quotes, the others are copied directly
+        let csv = r#"a,b
+"plain",1
+"with ""quotes""",2
+unquoted,3
+"",4
+"ünïcode, ""ok""",5
+"comma, no quotes",6
+"#;
+        let file = Cursor::new(csv);
+        let df = CsvReader::new(file).finish().unwrap();
+        assert_eq!(
+            Vec::from(df.column("a").unwrap().utf8().unwrap()),
+            &[
+                Some("plain"),
+                Some(r#"with "quotes""#),
+                Some("unquoted"),
+                Some(""),
+                Some(r#"ünïcode, "ok""#),
+                Some("comma, no quotes"),
+            ]
+        );
+    }
+
     #[test]
     fn test_escape_2() {
         // this is is harder than it looks.
@@ -818,6 +1196,17 @@ id090,id048,id0000067778,24,2,51862,4,9,
         assert!(result.is_ok())
     }
 
+    #[test]
+    fn test_with_columns_order() -> Result<()> {
+        let csv = "a,b,c\n1,foo,2.0\n3,bar,4.0\n";
+        let df = CsvReader::new(Cursor::new(csv))
+            .with_columns(Some(vec!["c".to_string(), "a".to_string()]))
+            .finish()?;
+        assert_eq!(df.get_column_names(), &["c", "a"]);
+        assert_eq!(df.dtypes(), &[DataType::Float64, DataType::Int64]);
+        Ok(())
+    }
+
     #[test]
     fn test_carriage_return() {
         let csv =
@@ -876,6 +1265,28 @@ AUDCAD,1616455921,0.96212,0.95666,1
         Ok(())
     }
 
+    #[test]
+    #[cfg(feature = "dtype-date64")]
+    fn test_parse_dates() -> Result<()> {
+        let csv = r#"a,b
+2021-03-19T10:15:30.250,1
+2021-03-19T10:15:31,2
+"#;
+        let df = CsvReader::new(Cursor::new(csv))
+            .with_dtypes(Some(&Schema::new(vec![Field::new("a", DataType::Date64)])))
+            .finish()?;
+        let expected =
+            Utf8Chunked::new_from_slice("a", &["2021-03-19T10:15:30.250", "2021-03-19T10:15:31"])
+                .as_date64(None)?;
+        assert_eq!(df.column("a")?.dtype(), &DataType::Date64);
+        assert_eq!(
+            Vec::from(df.column("a")?.date64()?),
+            &[Some(1616148930250), Some(1616148931000)]
+        );
+        assert!(df.column("a")?.series_equal(&expected.into_series()));
+        Ok(())
+    }
+
     #[test]
     fn test_skip_rows() -> Result<()> {
         let csv = r"#doc source pos typeindex type topic
@@ -979,4 +1390,331 @@ null-value,b,bar,
         assert!(df.get_columns()[0].null_count() > 0);
         Ok(())
     }
+
+    #[test]
+    fn test_header_only() -> Result<()> {
+        let csv = "a,b,c\n";
+
+        let file = Cursor::new(csv);
+        let df = CsvReader::new(file).has_header(true).finish()?;
+        assert_eq!(df.shape(), (0, 3));
+        assert_eq!(df.get_column_names(), &["a", "b", "c"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bom_and_line_endings() -> Result<()> {
+        let csv = "\u{feff}id,name\r\n1,foo\r\n2,bar\r\n";
+        let file = Cursor::new(csv);
+        let df = CsvReader::new(file).finish()?;
+        assert_eq!(df.get_column_names(), &["id", "name"]);
+        assert_eq!(df.shape(), (2, 2));
+        assert_eq!(Vec::from(df.column("id")?.i64()?), &[Some(1), Some(2)]);
+
+        // old mac line endings
+        let csv = "id,name\r1,foo\r2,bar";
+        let file = Cursor::new(csv);
+        let df = CsvReader::new(file).finish()?;
+        assert_eq!(df.shape(), (2, 2));
+        assert_eq!(
+            Vec::from(df.column("name")?.utf8()?),
+            &[Some("foo"), Some("bar")]
+        );
+
+        let csv = "id,name|1,foo|2,bar|";
+        let file = Cursor::new(csv);
+        let df = CsvReader::new(file).with_eol_char(b'|').finish()?;
+        assert_eq!(df.get_column_names(), &["id", "name"]);
+        assert_eq!(df.shape(), (2, 2));
+        Ok(())
+    }
+
+    #[test]
+    fn test_byte_range() -> Result<()> {
+        let csv = "id,text,value
+1,\"hello, world\",1.5
+2,\"multi
+line
+text\",2.5
+3,plain,3.5
+4,\"quote \"\" inside
+and newline\",4.5
+5,last,5.5
+";
+        let expected = CsvReader::new(Cursor::new(csv)).finish()?;
+        assert_eq!(expected.shape(), (5, 3));
+
+        let read_range = |start: usize, end: usize| {
+            CsvReader::new(Cursor::new(csv))
+                .with_byte_range(start as u64..end as u64)
+                .finish()
+        };
+        let len = csv.len();
+        // split at every offset, including mid-field and in quoted new lines
+        for split in 0..=len {
+            let mut df = read_range(0, split)?;
+            df.vstack_mut(&read_range(split, len)?)?;
+            assert!(df.frame_equal_missing(&expected), "split at {}", split);
+        }
+        // ranges that are aligned to the rows never split a quoted field
+        for batch_size in 0..=len {
+            let ranges = row_aligned_byte_ranges(csv.as_bytes(), batch_size, b'\n');
+            assert_eq!(ranges.first().unwrap().start, 0);
+            assert_eq!(ranges.last().unwrap().end, len as u64);
+            let mut df = read_range(0, 0)?;
+            for range in ranges {
+                let bytes = &csv.as_bytes()[range.start as usize..range.end as usize];
+                assert!(bytes.ends_with(b"\n"));
+                df.vstack_mut(&read_range(range.start as usize, range.end as usize)?)?;
+            }
+            assert!(
+                df.frame_equal_missing(&expected),
+                "batch size {}",
+                batch_size
+            );
+        }
+        // three workers with awkward offsets
+        for split in 0..len - 7 {
+            let mut df = read_range(0, split)?;
+            df.vstack_mut(&read_range(split, split + 7)?)?;
+            df.vstack_mut(&read_range(split + 7, len + 10)?)?;
+            assert!(df.frame_equal_missing(&expected), "split at {}", split);
+        }
+
+        let df = CsvReader::new(Cursor::new(csv))
+            .with_skip_rows_after_header(2)
+            .with_n_rows(Some(2))
+            .finish()?;
+        assert_eq!(Vec::from(df.column("id")?.i64()?), &[Some(3), Some(4)]);
+
+        // `\r\n` line endings
+        let csv = "id,name\r\n1,a\r\n2,\"b\r\nc\"\r\n3,d\r\n4,e\r\n";
+        let df = CsvReader::new(Cursor::new(csv))
+            .with_skip_rows_after_header(2)
+            .finish()?;
+        assert_eq!(Vec::from(df.column("id")?.i64()?), &[Some(3), Some(4)]);
+        assert_eq!(
+            Vec::from(df.column("name")?.utf8()?),
+            &[Some("d"), Some("e")]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_error_row() {
+        let csv = "a,b\n1,2\n# comment\n3,4\n5,x\n";
+        let file = Cursor::new(csv);
+        let err = CsvReader::new(file)
+            .infer_schema(Some(1))
+            .with_comment_char(Some(b'#'))
+            .finish()
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            PolarsError::Parse { dtype: DataType::Int64, ref value, row: 2 } if value == "x"
+        ));
+    }
+
+    #[test]
+    fn test_infer_file_schema() -> Result<()> {
+        let csv = "a,b,c,d\n1,,x,\n2,,y,\n3,,z,1.5\n";
+
+        let (schema, n) = infer_file_schema(&mut Cursor::new(csv), b',', None, true, 0, None)?;
+        assert_eq!(n, 3);
+        let dtypes: Vec<_> = schema
+            .fields()
+            .iter()
+            .map(|f| f.data_type().clone())
+            .collect();
+        assert_eq!(
+            dtypes,
+            &[
+                DataType::Int64,
+                DataType::Utf8,
+                DataType::Utf8,
+                DataType::Float64
+            ]
+        );
+        // agrees with the reader
+        let df = CsvReader::new(Cursor::new(csv)).finish()?;
+        assert_eq!(df.schema(), schema);
+
+        // `d` is empty in the sample but populated later
+        let (schema, n) = infer_file_schema(&mut Cursor::new(csv), b',', Some(2), true, 0, None)?;
+        assert_eq!(n, 2);
+        assert_eq!(schema.field_with_name("d")?.data_type(), &DataType::Utf8);
+        let options = InferenceOptions::default().with_null_dtype(DataType::Float64);
+        let (schema, _) = infer_file_schema_with_options(
+            &mut Cursor::new(csv),
+            b',',
+            Some(2),
+            true,
+            0,
+            None,
+            &options,
+        )?;
+        assert_eq!(schema.field_with_name("b")?.data_type(), &DataType::Float64);
+        assert_eq!(schema.field_with_name("d")?.data_type(), &DataType::Float64);
+        let df = CsvReader::new(Cursor::new(csv))
+            .infer_schema(Some(2))
+            .with_inference_options(options)
+            .finish()?;
+        assert_eq!(df.column("d")?.dtype(), &DataType::Float64);
+        assert_eq!(df.column("d")?.null_count(), 2);
+
+        // header only
+        let (schema, n) = infer_file_schema(&mut Cursor::new("a,b\n"), b',', None, true, 0, None)?;
+        assert_eq!(n, 0);
+        assert_eq!(schema.len(), 2);
+        assert_eq!(schema.field(0).unwrap().data_type(), &DataType::Utf8);
+        assert!(infer_file_schema(&mut Cursor::new(""), b',', None, true, 0, None).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_inference_options() -> Result<()> {
+        let csv = "a,b,c\n1,-1,\n2,3000000000,x\n";
+
+        let options = InferenceOptions::default().with_dtypes(vec![
+            DataType::Int32,
+            DataType::Int64,
+            DataType::Float64,
+            DataType::Utf8,
+        ]);
+        let (schema, _) = infer_file_schema_with_options(
+            &mut Cursor::new(csv),
+            b',',
+            None,
+            true,
+            0,
+            None,
+            &options,
+        )?;
+        assert_eq!(schema.field_with_name("a")?.data_type(), &DataType::Int32);
+        // widened to the last matching type in the priority list
+        assert_eq!(schema.field_with_name("b")?.data_type(), &DataType::Int64);
+
+        let options = InferenceOptions::default()
+            .with_dtypes(vec![DataType::Float64, DataType::Utf8])
+            .with_empty_as_null(false);
+        let df = CsvReader::new(Cursor::new(csv))
+            .with_inference_options(options)
+            .finish()?;
+        assert_eq!(df.column("a")?.dtype(), &DataType::Float64);
+        assert_eq!(df.column("b")?.dtype(), &DataType::Float64);
+        assert_eq!(df.column("c")?.dtype(), &DataType::Utf8);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "dtype-u8", feature = "dtype-u16"))]
+    fn test_infer_small_unsigned() -> Result<()> {
+        let csv = "a,b,c,d
+0,255,1,x
+255,256,-1,
+";
+        // small unsigned integers are only inferred if they are in the priority list
+        let df = CsvReader::new(Cursor::new(csv)).finish()?;
+        assert_eq!(df.column("a")?.dtype(), &DataType::Int64);
+
+        let options = InferenceOptions::default().with_dtypes(vec![
+            DataType::Boolean,
+            DataType::UInt8,
+            DataType::UInt16,
+            DataType::Int64,
+            DataType::Float64,
+            DataType::Utf8,
+        ]);
+        let df = CsvReader::new(Cursor::new(csv))
+            .with_inference_options(options)
+            .finish()?;
+        assert_eq!(df.column("a")?.dtype(), &DataType::UInt8);
+        assert_eq!(Vec::from(df.column("a")?.u8()?), &[Some(0), Some(255)]);
+        assert_eq!(df.column("b")?.dtype(), &DataType::UInt16);
+        assert_eq!(Vec::from(df.column("b")?.u16()?), &[Some(255), Some(256)]);
+        assert_eq!(df.column("c")?.dtype(), &DataType::Int64);
+        assert_eq!(df.column("d")?.dtype(), &DataType::Utf8);
+        Ok(())
+    }
+
+    #[test]
+    fn test_row_count() -> Result<()> {
+        let csv = "a,b
+1,x
+2,y
+3,z
+";
+        let df = CsvReader::new(Cursor::new(csv))
+            .with_row_count(Some(RowCount {
+                name: "id".into(),
+                offset: 10,
+            }))
+            .finish()?;
+        assert_eq!(df.get_column_names(), &["id", "a", "b"]);
+        assert_eq!(df.column("id")?.dtype(), &DataType::UInt32);
+        assert_eq!(
+            Vec::from(df.column("id")?.u32()?),
+            &[Some(10), Some(11), Some(12)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_header() -> Result<()> {
+        let csv = "\u{feff}a,\"b,c\",\"d\ne\",a\n1,2,3,4\n";
+        assert_eq!(
+            read_header(Cursor::new(csv), b',', b'"', false)?,
+            &["a", "b,c", "d\ne", "a_duplicated_0"]
+        );
+        assert!(matches!(
+            read_header(Cursor::new(csv), b',', b'"', true),
+            Err(PolarsError::Duplicate(_))
+        ));
+        assert_eq!(
+            read_header(Cursor::new("'a;b';c\n"), b';', b'\'', false)?,
+            &["a;b", "c"]
+        );
+        assert!(read_header(Cursor::new(""), b',', b'"', false).is_err());
+
+        // the reader renames the duplicates in the same way
+        let df = CsvReader::new(Cursor::new(csv)).finish()?;
+        assert_eq!(
+            df.get_column_names(),
+            &["a", "b,c", "d\ne", "a_duplicated_0"]
+        );
+        assert_eq!(df.column("a_duplicated_0")?.i64()?.get(0), Some(4));
+        Ok(())
+    }
+
+    #[test]
+    fn test_count_rows() -> Result<()> {
+        let dir = crate::temp_dir::TempDir::new("count-rows")?;
+        let path = dir.file_path("data.csv");
+        let mut csv = String::from("id,\"text\nheader\"\n\n");
+        for i in 0..10_000 {
+            csv.push_str(&format!("{},\"line\n{}\"\r\n", i, i));
+            if i % 1000 == 0 {
+                csv.push('\n');
+            }
+        }
+        std::fs::write(&path, &csv)?;
+        assert_eq!(count_rows(&path, b'"', true)?, 10_000);
+        assert_eq!(count_rows(&path, b'"', false)?, 10_001);
+
+        // the reader agrees
+        let path = dir.file_path("small.csv");
+        std::fs::write(&path, "\u{feff}a,b\n1,\"x\ny\"\n\n2,\"z\"\r\n3,\n")?;
+        let df = CsvReader::from_path(path.clone())?.finish()?;
+        assert_eq!(count_rows(&path, b'"', true)?, df.height());
+        assert_eq!(df.height(), 3);
+
+        let path = "../../examples/aggregate_multiple_files_in_chunks/datasets/foods1.csv";
+        let df = CsvReader::from_path(path)?.finish()?;
+        assert_eq!(count_rows(path, b'"', true)?, df.height());
+
+        let path = dir.file_path("empty.csv");
+        std::fs::write(&path, "")?;
+        assert_eq!(count_rows(&path, b'"', true)?, 0);
+        Ok(())
+    }
 }
@@ -1,75 +1,10 @@
 use crate::csv::CsvEncoding;
 use crate::csv_core::csv::RunningSize;
+use crate::csv_core::parse::{parse_bool, PrimitiveParser};
 use crate::csv_core::parser::{drop_quotes, skip_whitespace};
 use arrow::array::{ArrayData, LargeStringArray};
 use polars_arrow::builder::BooleanBufferBuilder;
 use polars_core::prelude::*;
-use std::fmt::Debug;
-
-trait ToPolarsError: Debug {
-    fn to_polars_err(&self) -> PolarsError {
-        PolarsError::Other(
-            format!(
-                "Could not parse primitive type during csv parsing: {:?}.\
-                This can occur when a column was inferred as integer type but we stumbled upon a floating point value\
-                You could pass a predefined schema or set `with_ignore_parser_errors` to `true`",
-                self
-            )
-            .into(),
-        )
-    }
-}
-
-impl ToPolarsError for lexical::Error {}
-impl ToPolarsError for fast_float::Error {}
-
-pub(crate) trait PrimitiveParser: ArrowPrimitiveType {
-    fn parse(bytes: &[u8]) -> Result<Self::Native>;
-}
-
-impl PrimitiveParser for Float32Type {
-    #[inline]
-    fn parse(bytes: &[u8]) -> Result<f32> {
-        let a = fast_float::parse(bytes).map_err(|e| e.to_polars_err())?;
-        Ok(a)
-    }
-}
-impl PrimitiveParser for Float64Type {
-    #[inline]
-    fn parse(bytes: &[u8]) -> Result<f64> {
-        let a = fast_float::parse(bytes).map_err(|e| e.to_polars_err())?;
-        Ok(a)
-    }
-}
-
-impl PrimitiveParser for UInt32Type {
-    #[inline]
-    fn parse(bytes: &[u8]) -> Result<u32> {
-        let a = lexical::parse(bytes).map_err(|e| e.to_polars_err())?;
-        Ok(a)
-    }
-}
-impl PrimitiveParser for UInt64Type {
-    #[inline]
-    fn parse(bytes: &[u8]) -> Result<u64> {
-        let a = lexical::parse(bytes).map_err(|e| e.to_polars_err())?;
-        Ok(a)
-    }
-}
-impl PrimitiveParser for Int32Type {
-    #[inline]
-    fn parse(bytes: &[u8]) -> Result<i32> {
-        let a = lexical::parse(bytes).map_err(|e| e.to_polars_err())?;
-        Ok(a)
-    }
-}
-impl PrimitiveParser for Int64Type {
-    #[inline]
-    fn parse(bytes: &[u8]) -> Result<i64> {
-        let a = lexical::parse(bytes).map_err(|e| e.to_polars_err())?;
-        Ok(a)
-    }
-}
 
 trait ParsedBuffer<T> {
     fn parse_bytes(
@@ -151,10 +86,10 @@ impl ParsedBuffer<Utf8Type> for Utf8Field {
         // first check utf8 validity
         #[cfg(feature = "simdutf8")]
         let parse_result = simdutf8::basic::from_utf8(bytes)
-            .map_err(|_| PolarsError::Other("invalid utf8 data".into()));
+            .map_err(|_| PolarsError::ComputeError("invalid utf8 data".into()));
         #[cfg(not(feature = "simdutf8"))]
-        let parse_result =
-            std::str::from_utf8(bytes).map_err(|_| PolarsError::Other("invalid utf8 data".into()));
+        let parse_result = std::str::from_utf8(bytes)
+            .map_err(|_| PolarsError::ComputeError("invalid utf8 data".into()));
         let data_len = self.data.len();
 
         // check if field fits in the str data buffer
@@ -187,6 +122,8 @@ impl ParsedBuffer<Utf8Type> for Utf8Field {
             let (_, _, n_written) = self.rdr.read_field(bytes, out_buf);
             n_written
         } else {
+            // unquoted fields and quoted fields without escaped quotes are copied as is
+            let bytes = drop_quotes(bytes);
             self.data.extend_from_slice(bytes);
             bytes.len()
         };
@@ -230,18 +167,16 @@ impl ParsedBuffer<BooleanType> for BooleanChunkedBuilder {
         _encoding: CsvEncoding,
         _needs_escaping: bool,
     ) -> Result<()> {
-        if bytes.eq_ignore_ascii_case(b"false") {
-            self.append_value(false);
-        } else if bytes.eq_ignore_ascii_case(b"true") {
-            self.append_value(true);
+        if let Some(v) = parse_bool(bytes) {
+            self.append_value(v);
         } else if ignore_errors || bytes.is_empty() {
             self.append_null();
         } else {
-            return Err(PolarsError::Other(
+            return Err(PolarsError::ComputeError(
                 format!(
                     "Error while parsing value {} at byte position {} as boolean",
-                    start_pos,
-                    String::from_utf8_lossy(bytes)
+                    String::from_utf8_lossy(bytes),
+                    start_pos
                 )
                 .into(),
             ));
@@ -282,6 +217,14 @@ pub(crate) fn init_buffers(
                 &DataType::Int64 => {
                     Buffer::Int64(PrimitiveChunkedBuilder::new(field.name(), capacity))
                 }
+                #[cfg(feature = "dtype-u8")]
+                &DataType::UInt8 => {
+                    Buffer::UInt8(PrimitiveChunkedBuilder::new(field.name(), capacity))
+                }
+                #[cfg(feature = "dtype-u16")]
+                &DataType::UInt16 => {
+                    Buffer::UInt16(PrimitiveChunkedBuilder::new(field.name(), capacity))
+                }
                 &DataType::UInt32 => {
                     Buffer::UInt32(PrimitiveChunkedBuilder::new(field.name(), capacity))
                 }
@@ -302,7 +245,7 @@ pub(crate) fn init_buffers(
                     delimiter,
                 )),
                 other => {
-                    return Err(PolarsError::Other(
+                    return Err(PolarsError::InvalidOperation(
                         format!("Unsupported data type {:?} when reading a csv", other).into(),
                     ))
                 }
@@ -317,6 +260,10 @@ pub(crate) enum Buffer {
     Boolean(BooleanChunkedBuilder),
     Int32(PrimitiveChunkedBuilder<Int32Type>),
     Int64(PrimitiveChunkedBuilder<Int64Type>),
+    #[cfg(feature = "dtype-u8")]
+    UInt8(PrimitiveChunkedBuilder<UInt8Type>),
+    #[cfg(feature = "dtype-u16")]
+    UInt16(PrimitiveChunkedBuilder<UInt16Type>),
     UInt32(PrimitiveChunkedBuilder<UInt32Type>),
     #[cfg(feature = "dtype-u64")]
     UInt64(PrimitiveChunkedBuilder<UInt64Type>),
@@ -327,11 +274,33 @@ pub(crate) enum Buffer {
 }
 
 impl Buffer {
+    pub(crate) fn dtype(&self) -> DataType {
+        match self {
+            Buffer::Boolean(_) => DataType::Boolean,
+            Buffer::Int32(_) => DataType::Int32,
+            Buffer::Int64(_) => DataType::Int64,
+            #[cfg(feature = "dtype-u8")]
+            Buffer::UInt8(_) => DataType::UInt8,
+            #[cfg(feature = "dtype-u16")]
+            Buffer::UInt16(_) => DataType::UInt16,
+            Buffer::UInt32(_) => DataType::UInt32,
+            #[cfg(feature = "dtype-u64")]
+            Buffer::UInt64(_) => DataType::UInt64,
+            Buffer::Float32(_) => DataType::Float32,
+            Buffer::Float64(_) => DataType::Float64,
+            Buffer::Utf8(_) => DataType::Utf8,
+        }
+    }
+
     pub(crate) fn into_series(self) -> Series {
         match self {
             Buffer::Boolean(v) => v.finish().into_series(),
             Buffer::Int32(v) => v.finish().into_series(),
             Buffer::Int64(v) => v.finish().into_series(),
+            #[cfg(feature = "dtype-u8")]
+            Buffer::UInt8(v) => v.finish().into_series(),
+            #[cfg(feature = "dtype-u16")]
+            Buffer::UInt16(v) => v.finish().into_series(),
             Buffer::UInt32(v) => v.finish().into_series(),
             #[cfg(feature = "dtype-u64")]
             Buffer::UInt64(v) => v.finish().into_series(),
@@ -359,6 +328,10 @@ impl Buffer {
             Buffer::Boolean(v) => v.append_null(),
             Buffer::Int32(v) => v.append_null(),
             Buffer::Int64(v) => v.append_null(),
+            #[cfg(feature = "dtype-u8")]
+            Buffer::UInt8(v) => v.append_null(),
+            #[cfg(feature = "dtype-u16")]
+            Buffer::UInt16(v) => v.append_null(),
             Buffer::UInt32(v) => v.append_null(),
             #[cfg(feature = "dtype-u64")]
             Buffer::UInt64(v) => v.append_null(),
@@ -421,6 +394,28 @@ impl Buffer {
                     needs_escaping,
                 )
             }
+            #[cfg(feature = "dtype-u8")]
+            UInt8(buf) => {
+                <PrimitiveChunkedBuilder<UInt8Type> as ParsedBuffer<UInt8Type>>::parse_bytes(
+                    buf,
+                    bytes,
+                    ignore_errors,
+                    start_pos,
+                    encoding,
+                    needs_escaping,
+                )
+            }
+            #[cfg(feature = "dtype-u16")]
+            UInt16(buf) => {
+                <PrimitiveChunkedBuilder<UInt16Type> as ParsedBuffer<UInt16Type>>::parse_bytes(
+                    buf,
+                    bytes,
+                    ignore_errors,
+                    start_pos,
+                    encoding,
+                    needs_escaping,
+                )
+            }
             UInt32(buf) => {
                 <PrimitiveChunkedBuilder<UInt32Type> as ParsedBuffer<UInt32Type>>::parse_bytes(
                     buf,
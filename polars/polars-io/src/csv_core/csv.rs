@@ -1,18 +1,21 @@
-use crate::csv::{CsvEncoding, NullValues};
+use crate::csv::{CsvEncoding, InferenceOptions, NullValues};
 use crate::csv_core::utils::*;
 use crate::csv_core::{buffer::*, parser::*};
 use crate::mmap::MmapBytesReader;
-use crate::PhysicalIoExpr;
+use crate::utils::to_projection_order;
 use crate::ScanAggregation;
+use crate::{predicate_to_mask, PhysicalIoExpr};
 use csv::ByteRecordsIntoIter;
 use polars_arrow::array::*;
 use polars_core::utils::accumulate_dataframes_vertical;
+use polars_core::verbose::{log_verbose, verbose, VerboseEvent};
 use polars_core::{prelude::*, POOL};
 use rayon::prelude::*;
 use rayon::ThreadPoolBuilder;
 use std::borrow::Cow;
 use std::fmt;
 use std::io::{Read, Seek};
+use std::ops::Range;
 use std::path::PathBuf;
 use std::sync::atomic::Ordering;
 use std::sync::{atomic::AtomicUsize, Arc};
@@ -29,6 +32,8 @@ pub struct SequentialReader<R: Read + MmapBytesReader> {
     line_number: usize,
     ignore_parser_errors: bool,
     skip_rows: usize,
+    skip_rows_after_header: usize,
+    byte_range: Option<Range<u64>>,
     n_rows: Option<usize>,
     encoding: CsvEncoding,
     n_threads: Option<usize>,
@@ -40,6 +45,7 @@ pub struct SequentialReader<R: Read + MmapBytesReader> {
     low_memory: bool,
     comment_char: Option<u8>,
     null_values: Option<Vec<String>>,
+    eol_char: u8,
 }
 
 impl<R> fmt::Debug for SequentialReader<R>
@@ -120,24 +126,49 @@ impl<R: Read + Sync + Send + MmapBytesReader> SequentialReader<R> {
     }
 
     fn find_starting_point<'a>(&self, mut bytes: &'a [u8]) -> Result<&'a [u8]> {
-        // Skip all leading white space and the occasional utf8-bom
-        bytes = skip_line_ending(skip_whitespace(skip_bom(bytes)).0).0;
-
-        // If there is a header we skip it.
-        if self.has_header {
-            bytes = skip_header(bytes).0;
-        }
+        let file_len = bytes.len();
+        // Skip all leading white space, the occasional utf8-bom and the header.
+        bytes = skip_to_data(bytes, self.has_header, QUOTE_CHAR, self.eol_char);
 
         if self.skip_rows > 0 {
             for _ in 0..self.skip_rows {
                 // This does not check embedding of new line chars in string quotes.
                 // TODO create a state machine/ or use that of csv crate to skip lines with proper
                 // escaping
-                let pos = next_line_position_naive(bytes)
+                let pos = next_line_position_naive(bytes, self.eol_char)
                     .ok_or_else(|| PolarsError::NoData("not enough lines to skip".into()))?;
                 bytes = &bytes[pos..];
             }
         }
+
+        for _ in 0..self.skip_rows_after_header {
+            if bytes.is_empty() {
+                return Err(PolarsError::NoData(
+                    "not enough rows to skip after the header".into(),
+                ));
+            }
+            let pos =
+                next_row_position(bytes, false, QUOTE_CHAR, self.eol_char).unwrap_or(bytes.len());
+            bytes = &bytes[pos..];
+        }
+
+        // Only keep the rows that start in the byte range. The offsets are relative to the
+        // start of the file, so we first translate them to offsets in the data.
+        if let Some(range) = &self.byte_range {
+            let data_offset = file_len - bytes.len();
+            let to_data_offset = |pos: u64| {
+                (std::cmp::min(pos, file_len as u64) as usize).saturating_sub(data_offset)
+            };
+            let start = row_start_at_or_after(
+                bytes,
+                to_data_offset(range.start),
+                QUOTE_CHAR,
+                self.eol_char,
+            );
+            let end =
+                row_start_at_or_after(bytes, to_data_offset(range.end), QUOTE_CHAR, self.eol_char);
+            bytes = &bytes[start..std::cmp::max(start, end)];
+        }
         Ok(bytes)
     }
 
@@ -147,7 +178,7 @@ impl<R: Read + Sync + Send + MmapBytesReader> SequentialReader<R> {
         bytes: &[u8],
         predicate: Option<&Arc<dyn PhysicalIoExpr>>,
     ) -> Result<DataFrame> {
-        let logging = std::env::var("POLARS_VERBOSE").is_ok();
+        let logging = verbose();
         // Make the variable mutable so that we can reassign the sliced file to this variable.
         let mut bytes = self.find_starting_point(bytes)?;
 
@@ -155,9 +186,12 @@ impl<R: Read + Sync + Send + MmapBytesReader> SequentialReader<R> {
         let mut total_rows = 128;
 
         // if None, there are less then 128 rows in the file and the statistics don't matter that much
-        if let Some((mean, std)) = get_line_stats(bytes, self.sample_size) {
+        if let Some((mean, std)) = get_line_stats(bytes, self.sample_size, self.eol_char) {
             if logging {
-                eprintln!("avg line length: {}\nstd. dev. line length: {}", mean, std);
+                log_verbose(VerboseEvent::new(
+                    "csv parser",
+                    format_args!("avg line length: {}\nstd. dev. line length: {}", mean, std),
+                ));
             }
 
             // x % upper bound of byte length per line assuming normally distributed
@@ -177,20 +211,28 @@ impl<R: Read + Sync + Send + MmapBytesReader> SequentialReader<R> {
                         &bytes[n_bytes..],
                         self.schema.fields().len(),
                         self.delimiter,
+                        QUOTE_CHAR,
+                        self.eol_char,
                     ) {
                         bytes = &bytes[..n_bytes + pos]
                     }
                 }
             }
             if logging {
-                eprintln!("initial row estimate: {}", total_rows)
+                log_verbose(VerboseEvent::new(
+                    "csv parser",
+                    format_args!("initial row estimate: {}", total_rows),
+                ));
             }
         }
         if total_rows == 128 {
             n_threads = 1;
 
             if logging {
-                eprintln!("file < 128 rows, no statistics determined")
+                log_verbose(VerboseEvent::new(
+                    "csv parser",
+                    format_args!("file < 128 rows, no statistics determined"),
+                ));
             }
         }
 
@@ -208,10 +250,13 @@ impl<R: Read + Sync + Send + MmapBytesReader> SequentialReader<R> {
         let chunk_size = std::cmp::min(self.chunk_size, total_rows);
         let n_chunks = total_rows / chunk_size;
         if logging {
-            eprintln!(
-                "no. of chunks: {} processed by: {} threads at 1 chunk/thread",
-                n_chunks, n_threads
-            );
+            log_verbose(VerboseEvent::new(
+                "csv parser",
+                format_args!(
+                    "no. of chunks: {} processed by: {} threads at 1 chunk/thread",
+                    n_chunks, n_threads
+                ),
+            ));
         }
 
         // keep track of the maximum capacity that needs to be allocated for the utf8-builder
@@ -232,8 +277,14 @@ impl<R: Read + Sync + Send + MmapBytesReader> SequentialReader<R> {
 
         // split the file by the nearest new line characters such that every thread processes
         // approximately the same number of rows.
-        let file_chunks =
-            get_file_chunks(bytes, n_threads, self.schema.fields().len(), self.delimiter);
+        let file_chunks = get_file_chunks(
+            bytes,
+            n_threads,
+            self.schema.fields().len(),
+            self.delimiter,
+            QUOTE_CHAR,
+            self.eol_char,
+        );
 
         // If the number of threads given by the user is lower than our global thread pool we create
         // new one.
@@ -250,6 +301,22 @@ impl<R: Read + Sync + Send + MmapBytesReader> SequentialReader<R> {
             &POOL
         };
 
+        // An empty file with a schema should return an empty DataFrame with that schema
+        if bytes.is_empty() {
+            let str_capacities: Vec<_> = str_columns.iter().map(|_| RunningSize::new(0)).collect();
+            let buffers = init_buffers(
+                &projection,
+                0,
+                &self.schema,
+                &str_capacities,
+                self.delimiter,
+            )?;
+            let df = DataFrame::new_no_checks(
+                buffers.into_iter().map(|buf| buf.into_series()).collect(),
+            );
+            return Ok(df);
+        }
+
         // all the buffers returned from the threads
         // Structure:
         //      the inner vec has got buffers from all the columns.
@@ -262,21 +329,6 @@ impl<R: Read + Sync + Send + MmapBytesReader> SequentialReader<R> {
                 .map(|_| RunningSize::new(init_str_bytes))
                 .collect();
 
-            // An empty file with a schema should return an empty DataFrame with that schema
-            if bytes.is_empty() {
-                let buffers = init_buffers(
-                    &projection,
-                    0,
-                    &self.schema,
-                    &str_capacities,
-                    self.delimiter,
-                )?;
-                let df = DataFrame::new_no_checks(
-                    buffers.into_iter().map(|buf| buf.into_series()).collect(),
-                );
-                return Ok(df);
-            }
-
             let dfs = pool.install(|| {
                 file_chunks
                     .into_par_iter()
@@ -315,15 +367,24 @@ impl<R: Read + Sync + Send + MmapBytesReader> SequentialReader<R> {
                                 ignore_parser_errors,
                                 self.encoding,
                                 chunk_size,
-                            )?;
+                                self.eol_char,
+                            )
+                            .map_err(|err| {
+                                offset_parse_error(
+                                    err,
+                                    &bytes[..read],
+                                    self.comment_char,
+                                    QUOTE_CHAR,
+                                    self.eol_char,
+                                )
+                            })?;
 
                             let mut local_df = DataFrame::new_no_checks(
                                 buffers.into_iter().map(|buf| buf.into_series()).collect(),
                             );
                             if let Some(predicate) = predicate {
                                 let s = predicate.evaluate(&local_df)?;
-                                let mask =
-                                    s.bool().expect("filter predicates was not of type boolean");
+                                let mask = predicate_to_mask(&s)?;
                                 local_df = local_df.filter(mask)?;
                             }
 
@@ -339,17 +400,23 @@ impl<R: Read + Sync + Send + MmapBytesReader> SequentialReader<R> {
                                         str_capacities[str_index].update(str_bytes_len);
                                     if logging {
                                         if size_hint < str_bytes_len {
-                                            eprintln!(
-                                                "probably needed to reallocate column: {}\
+                                            log_verbose(VerboseEvent::new(
+                                                "csv parser",
+                                                format_args!(
+                                                    "probably needed to reallocate column: {}\
                                     \nprevious capacity was: {}\
                                     \nneeded capacity was: {}",
-                                                name, size_hint, str_bytes_len
-                                            );
+                                                    name, size_hint, str_bytes_len
+                                                ),
+                                            ));
                                         }
-                                        eprintln!(
+                                        log_verbose(VerboseEvent::new(
+                                            "csv parser",
+                                            format_args!(
                                             "column {} statistics: \nmax: {}\navg: {}\nlast: {}",
                                             name, max, avg, last
-                                        )
+                                        ),
+                                        ));
                                     }
                                 }
                             }
@@ -422,7 +489,17 @@ impl<R: Read + Sync + Send + MmapBytesReader> SequentialReader<R> {
                                 // chunk size doesn't really matter anymore,
                                 // less calls if we increase the size
                                 chunk_size * 320000,
-                            )?;
+                                self.eol_char,
+                            )
+                            .map_err(|err| {
+                                offset_parse_error(
+                                    err,
+                                    &bytes[..read],
+                                    self.comment_char,
+                                    QUOTE_CHAR,
+                                    self.eol_char,
+                                )
+                            })?;
                         }
                         Ok(DataFrame::new_no_checks(
                             buffers.into_iter().map(|buf| buf.into_series()).collect(),
@@ -441,6 +518,14 @@ impl<R: Read + Sync + Send + MmapBytesReader> SequentialReader<R> {
         aggregate: Option<&[ScanAggregation]>,
     ) -> Result<DataFrame> {
         let n_threads = self.n_threads.unwrap_or_else(|| POOL.current_num_threads());
+        // the projected columns are parsed in the order of the file, we restore the requested order
+        let column_order = match (&self.projection, aggregate) {
+            (Some(projection), None) => projection
+                .iter()
+                .map(|i| self.schema.fields().get(*i).map(|fld| fld.name().clone()))
+                .collect::<Option<Vec<_>>>(),
+            _ => None,
+        };
 
         let mut df = match (&self.path, self.record_iter.is_some()) {
             // we have a path so we can mmap
@@ -470,10 +555,8 @@ impl<R: Read + Sync + Send + MmapBytesReader> SequentialReader<R> {
                         // we have to read to an owned buffer to get the bytes.
                         let mut bytes = Vec::with_capacity(1024 * 128);
                         r.read_to_end(&mut bytes)?;
-                        if !bytes.is_empty()
-                            && (bytes[bytes.len() - 1] != b'\n' || bytes[bytes.len() - 1] != b'\r')
-                        {
-                            bytes.push(b'\n')
+                        if !bytes.is_empty() && !is_eol(bytes[bytes.len() - 1], self.eol_char) {
+                            bytes.push(self.eol_char)
                         }
                         Cow::Owned(bytes)
                     };
@@ -499,7 +582,10 @@ impl<R: Read + Sync + Send + MmapBytesReader> SequentialReader<R> {
                 df = df.slice(0, n_rows)
             }
         }
-        Ok(df)
+        match column_order {
+            Some(columns) => to_projection_order(df, &columns),
+            None => Ok(df),
+        }
     }
 }
 
@@ -508,6 +594,8 @@ pub fn build_csv_reader<R: Read + Seek + Sync + Send + MmapBytesReader>(
     mut reader: R,
     n_rows: Option<usize>,
     skip_rows: usize,
+    skip_rows_after_header: usize,
+    byte_range: Option<Range<u64>>,
     mut projection: Option<Vec<usize>>,
     max_records: Option<usize>,
     delimiter: Option<u8>,
@@ -524,6 +612,8 @@ pub fn build_csv_reader<R: Read + Seek + Sync + Send + MmapBytesReader>(
     low_memory: bool,
     comment_char: Option<u8>,
     null_values: Option<NullValues>,
+    eol_char: u8,
+    inference_options: &InferenceOptions,
 ) -> Result<SequentialReader<R>> {
     // check if schema should be inferred
     let delimiter = delimiter.unwrap_or(b',');
@@ -538,6 +628,8 @@ pub fn build_csv_reader<R: Read + Seek + Sync + Send + MmapBytesReader>(
                 schema_overwrite,
                 skip_rows,
                 comment_char,
+                eol_char,
+                inference_options,
             )?;
             Arc::new(inferred_schema)
         }
@@ -554,7 +646,14 @@ pub fn build_csv_reader<R: Read + Seek + Sync + Send + MmapBytesReader>(
         projection = Some(prj);
     }
 
-    let csv_reader = init_csv_reader(reader, has_header, delimiter, comment_char);
+    let csv_reader = init_csv_reader(
+        reader,
+        has_header,
+        delimiter,
+        QUOTE_CHAR,
+        comment_char,
+        eol_char,
+    );
     let record_iter = Some(csv_reader.into_byte_records());
 
     Ok(SequentialReader {
@@ -564,6 +663,8 @@ pub fn build_csv_reader<R: Read + Seek + Sync + Send + MmapBytesReader>(
         line_number: if has_header { 1 } else { 0 },
         ignore_parser_errors,
         skip_rows,
+        skip_rows_after_header,
+        byte_range,
         n_rows,
         encoding,
         n_threads,
@@ -575,5 +676,6 @@ pub fn build_csv_reader<R: Read + Seek + Sync + Send + MmapBytesReader>(
         low_memory,
         comment_char,
         null_values,
+        eol_char,
     })
 }
@@ -1,4 +1,5 @@
 pub(crate) mod buffer;
 pub mod csv;
+pub mod parse;
 pub(crate) mod parser;
 pub mod utils;
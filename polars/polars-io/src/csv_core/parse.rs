@@ -0,0 +1,241 @@
+//! Parsing of string values to numeric and boolean values.
+//!
+//! The CSV reader and the [Utf8Parse] kernels use the same parsers, so that both
+//! agree on what is a valid value.
+use polars_core::prelude::*;
+use std::fmt::Debug;
+
+trait ToPolarsError: Debug {
+    fn to_polars_err(&self) -> PolarsError {
+        PolarsError::ComputeError(
+            format!(
+                "Could not parse primitive type during csv parsing: {:?}.\
+                This can occur when a column was inferred as integer type but we stumbled upon a floating point value\
+                You could pass a predefined schema or set `with_ignore_parser_errors` to `true`",
+                self
+            )
+            .into(),
+        )
+    }
+}
+
+impl ToPolarsError for lexical::Error {}
+impl ToPolarsError for fast_float::Error {}
+
+pub(crate) trait PrimitiveParser: ArrowPrimitiveType {
+    fn parse(bytes: &[u8]) -> Result<Self::Native>;
+}
+
+impl PrimitiveParser for Float32Type {
+    #[inline]
+    fn parse(bytes: &[u8]) -> Result<f32> {
+        let a = fast_float::parse(bytes).map_err(|e| e.to_polars_err())?;
+        Ok(a)
+    }
+}
+impl PrimitiveParser for Float64Type {
+    #[inline]
+    fn parse(bytes: &[u8]) -> Result<f64> {
+        let a = fast_float::parse(bytes).map_err(|e| e.to_polars_err())?;
+        Ok(a)
+    }
+}
+
+#[cfg(feature = "dtype-u8")]
+impl PrimitiveParser for UInt8Type {
+    #[inline]
+    fn parse(bytes: &[u8]) -> Result<u8> {
+        let a = lexical::parse(bytes).map_err(|e| e.to_polars_err())?;
+        Ok(a)
+    }
+}
+#[cfg(feature = "dtype-u16")]
+impl PrimitiveParser for UInt16Type {
+    #[inline]
+    fn parse(bytes: &[u8]) -> Result<u16> {
+        let a = lexical::parse(bytes).map_err(|e| e.to_polars_err())?;
+        Ok(a)
+    }
+}
+impl PrimitiveParser for UInt32Type {
+    #[inline]
+    fn parse(bytes: &[u8]) -> Result<u32> {
+        let a = lexical::parse(bytes).map_err(|e| e.to_polars_err())?;
+        Ok(a)
+    }
+}
+impl PrimitiveParser for UInt64Type {
+    #[inline]
+    fn parse(bytes: &[u8]) -> Result<u64> {
+        let a = lexical::parse(bytes).map_err(|e| e.to_polars_err())?;
+        Ok(a)
+    }
+}
+impl PrimitiveParser for Int32Type {
+    #[inline]
+    fn parse(bytes: &[u8]) -> Result<i32> {
+        let a = lexical::parse(bytes).map_err(|e| e.to_polars_err())?;
+        Ok(a)
+    }
+}
+impl PrimitiveParser for Int64Type {
+    #[inline]
+    fn parse(bytes: &[u8]) -> Result<i64> {
+        let a = lexical::parse(bytes).map_err(|e| e.to_polars_err())?;
+        Ok(a)
+    }
+}
+
+/// Parse `true` or `false`, case insensitive.
+#[inline]
+pub(crate) fn parse_bool(bytes: &[u8]) -> Option<bool> {
+    if bytes.eq_ignore_ascii_case(b"false") {
+        Some(false)
+    } else if bytes.eq_ignore_ascii_case(b"true") {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+/// Options that determine how string values are parsed by [Utf8Parse].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Strip leading and trailing whitespace before parsing.
+    pub strip_whitespace: bool,
+    /// Remove this separator between groups of digits, e.g. the `,` in `1,000,000`.
+    pub thousands_separator: Option<u8>,
+    /// Return an error if a value cannot be parsed, instead of a null value.
+    pub strict: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            strip_whitespace: true,
+            thousands_separator: None,
+            strict: false,
+        }
+    }
+}
+
+/// Parse string values to numeric or boolean values.
+///
+/// Null values and empty strings result in null values.
+pub trait Utf8Parse {
+    fn parse_int64(&self, options: &ParseOptions) -> Result<Int64Chunked>;
+
+    fn parse_float64(&self, options: &ParseOptions) -> Result<Float64Chunked>;
+
+    fn parse_boolean(&self, options: &ParseOptions) -> Result<BooleanChunked>;
+}
+
+fn parse_utf8<T, N, B, F>(
+    ca: &Utf8Chunked,
+    options: &ParseOptions,
+    mut builder: B,
+    parse: F,
+) -> Result<ChunkedArray<T>>
+where
+    T: PolarsDataType,
+    B: ChunkedBuilder<N, T>,
+    F: Fn(&[u8]) -> Option<N>,
+{
+    // scratch buffer used to remove the thousands separators
+    let mut buf = Vec::new();
+
+    for (idx, opt_s) in ca.into_iter().enumerate() {
+        let s = match opt_s {
+            Some(s) if options.strip_whitespace => s.trim(),
+            Some(s) => s,
+            None => {
+                builder.append_null();
+                continue;
+            }
+        };
+        let mut bytes = s.as_bytes();
+        if let Some(sep) = options.thousands_separator {
+            if bytes.contains(&sep) {
+                buf.clear();
+                buf.extend(bytes.iter().copied().filter(|b| *b != sep));
+                bytes = &buf;
+            }
+        }
+
+        match parse(bytes) {
+            Some(v) => builder.append_value(v),
+            None if options.strict && !bytes.is_empty() => {
+                return Err(PolarsError::Parse {
+                    dtype: T::get_dtype(),
+                    value: s.to_string(),
+                    row: idx,
+                })
+            }
+            None => builder.append_null(),
+        }
+    }
+    Ok(builder.finish())
+}
+
+impl Utf8Parse for Utf8Chunked {
+    fn parse_int64(&self, options: &ParseOptions) -> Result<Int64Chunked> {
+        let builder = PrimitiveChunkedBuilder::new(self.name(), self.len());
+        parse_utf8(self, options, builder, |bytes| Int64Type::parse(bytes).ok())
+    }
+
+    fn parse_float64(&self, options: &ParseOptions) -> Result<Float64Chunked> {
+        let builder = PrimitiveChunkedBuilder::new(self.name(), self.len());
+        parse_utf8(self, options, builder, |bytes| {
+            Float64Type::parse(bytes).ok()
+        })
+    }
+
+    fn parse_boolean(&self, options: &ParseOptions) -> Result<BooleanChunked> {
+        let builder = BooleanChunkedBuilder::new(self.name(), self.len());
+        parse_utf8(self, options, builder, parse_bool)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_utf8_parse() -> Result<()> {
+        let ca = Utf8Chunked::new_from_opt_slice(
+            "a",
+            &[Some(" 1,000 "), Some("12"), None, Some(""), Some("foo")],
+        );
+        let options = ParseOptions {
+            thousands_separator: Some(b','),
+            ..Default::default()
+        };
+        let out = ca.parse_int64(&options)?;
+        assert_eq!(Vec::from(&out), &[Some(1000), Some(12), None, None, None]);
+        let out = ca.parse_float64(&options)?;
+        assert_eq!(
+            Vec::from(&out),
+            &[Some(1000.0), Some(12.0), None, None, None]
+        );
+
+        // without the separator the first value is invalid
+        let out = ca.parse_int64(&ParseOptions::default())?;
+        assert_eq!(Vec::from(&out), &[None, Some(12), None, None, None]);
+
+        let options = ParseOptions {
+            thousands_separator: Some(b','),
+            strict: true,
+            ..Default::default()
+        };
+        let err = ca.parse_int64(&options).unwrap_err();
+        assert!(matches!(
+            err,
+            PolarsError::Parse { dtype: DataType::Int64, ref value, row: 4 } if value == "foo"
+        ));
+
+        let ca = Utf8Chunked::new_from_slice("a", &["True", " false", "1"]);
+        let out = ca.parse_boolean(&ParseOptions::default())?;
+        assert_eq!(Vec::from(&out), &[Some(true), Some(false), None]);
+        Ok(())
+    }
+}
@@ -3,6 +3,9 @@ use crate::csv::CsvEncoding;
 use num::traits::Pow;
 use polars_core::prelude::*;
 
+/// The quote character of the [CsvReader](crate::csv::CsvReader).
+pub(crate) const QUOTE_CHAR: u8 = b'"';
+
 /// Skip the utf-8 Byte Order Mark.
 /// credits to csv-core
 pub(crate) fn skip_bom(input: &[u8]) -> &[u8] {
@@ -13,22 +16,32 @@ pub(crate) fn skip_bom(input: &[u8]) -> &[u8] {
     }
 }
 
+/// Check if `b` ends a line. With the default `\n` end of line character, a bare `\r`
+/// (and thus also `\r\n`) ends a line as well.
+#[inline]
+pub(crate) fn is_eol(b: u8, eol_char: u8) -> bool {
+    b == eol_char || (eol_char == b'\n' && b == b'\r')
+}
+
+/// Find the position after the first line ending, where `\r\n` counts as a single line ending.
+#[inline]
+fn end_of_line_position(input: &[u8], eol_char: u8) -> Option<usize> {
+    let pos = input.iter().position(|b| is_eol(*b, eol_char))?;
+    if eol_char == b'\n' && input[pos] == b'\r' && input.get(pos + 1) == Some(&b'\n') {
+        Some(pos + 2)
+    } else {
+        Some(pos + 1)
+    }
+}
+
 /// Find the nearest next line position.
 /// Does not check for new line characters embedded in String fields.
-pub(crate) fn next_line_position_naive(input: &[u8]) -> Option<usize> {
-    let pos = input.iter().position(|b| *b == b'\n')? + 1;
+pub(crate) fn next_line_position_naive(input: &[u8], eol_char: u8) -> Option<usize> {
+    let pos = end_of_line_position(input, eol_char)?;
     if input.len() - pos == 0 {
         return None;
     }
-    input.get(pos + 1).and_then(|&b| {
-        Option::from({
-            if b == b'\r' {
-                pos + 1
-            } else {
-                pos
-            }
-        })
-    })
+    Some(pos)
 }
 
 /// Find the nearest next line position that is not embedded in a String field.
@@ -36,31 +49,29 @@ pub(crate) fn next_line_position(
     mut input: &[u8],
     expected_fields: usize,
     delimiter: u8,
+    quote_char: u8,
+    eol_char: u8,
 ) -> Option<usize> {
     let mut total_pos = 0;
     if input.is_empty() {
         return None;
     }
     loop {
-        let pos = input.iter().position(|b| *b == b'\n')? + 1;
+        let pos = end_of_line_position(input, eol_char)?;
         if input.len() - pos == 0 {
             return None;
         }
-        let line = SplitLines::new(&input[pos..], b'\n').next();
+        let line = SplitLines::new(&input[pos..], quote_char, eol_char).next();
         if let Some(line) = line {
-            if SplitFields::new(line, delimiter).into_iter().count() == expected_fields {
-                return input.get(pos + 1).and_then(|&b| {
-                    Option::from({
-                        if b == b'\r' {
-                            total_pos + pos + 1
-                        } else {
-                            total_pos + pos
-                        }
-                    })
-                });
+            if SplitFields::new(line, delimiter, quote_char)
+                .into_iter()
+                .count()
+                == expected_fields
+            {
+                return Some(total_pos + pos);
             } else {
-                input = &input[pos + 1..];
-                total_pos += pos + 1;
+                input = &input[pos..];
+                total_pos += pos;
             }
         } else {
             return None;
@@ -68,6 +79,53 @@ pub(crate) fn next_line_position(
     }
 }
 
+/// Find the position after the first line ending that is not embedded in a quoted field,
+/// where `\r\n` counts as a single line ending. `in_quotes` is the quote state at the start
+/// of `input`.
+///
+/// Quotes are tracked in the same way as rows are split by the parser, so this is exact.
+pub(crate) fn next_row_position(
+    input: &[u8],
+    mut in_quotes: bool,
+    quote_char: u8,
+    eol_char: u8,
+) -> Option<usize> {
+    for (i, &c) in input.iter().enumerate() {
+        if c == quote_char {
+            in_quotes = !in_quotes;
+        } else if !in_quotes && is_eol(c, eol_char) {
+            if eol_char == b'\n' && c == b'\r' && input.get(i + 1) == Some(&b'\n') {
+                return Some(i + 2);
+            }
+            return Some(i + 1);
+        }
+    }
+    None
+}
+
+/// Get the position of the first row that starts at or after `offset`.
+///
+/// Every position gets the same answer, no matter from which worker it is requested. So if a
+/// file is split into consecutive byte ranges, the rows that start in those ranges cover
+/// the file exactly once. To know if `offset` is in a quoted field, the quotes that precede
+/// `offset` are counted.
+pub(crate) fn row_start_at_or_after(
+    bytes: &[u8],
+    offset: usize,
+    quote_char: u8,
+    eol_char: u8,
+) -> usize {
+    if offset == 0 || offset >= bytes.len() {
+        return std::cmp::min(offset, bytes.len());
+    }
+    // a row starts at `offset` if the byte before it ends a row
+    let prev = offset - 1;
+    let n_quotes = bytes[..prev].iter().filter(|&&b| b == quote_char).count();
+    next_row_position(&bytes[prev..], n_quotes % 2 == 1, quote_char, eol_char)
+        .map(|pos| prev + pos)
+        .unwrap_or_else(|| bytes.len())
+}
+
 pub(crate) fn is_line_ending(b: u8) -> bool {
     b == b'\n' || b == b'\r'
 }
@@ -100,14 +158,29 @@ where
 ///     'field_1,field_2'
 /// and not with
 ///     '\nfield_1,field_1'
-pub(crate) fn skip_header(input: &[u8]) -> (&[u8], usize) {
-    let mut pos = next_line_position_naive(input).expect("no lines in the file");
-    if input[pos] == b'\n' {
-        pos += 1;
-    }
+///
+/// Line endings in quoted header names don't end the header.
+pub(crate) fn skip_header(input: &[u8], quote_char: u8, eol_char: u8) -> (&[u8], usize) {
+    // a file with only a header has no next line
+    let pos = next_row_position(input, false, quote_char, eol_char).unwrap_or(input.len());
     (&input[pos..], pos)
 }
 
+/// Skip the utf-8 BOM, the leading white space and empty lines, and the header if `has_header`.
+/// The returned bytes start at the first row of data.
+pub(crate) fn skip_to_data(
+    mut input: &[u8],
+    has_header: bool,
+    quote_char: u8,
+    eol_char: u8,
+) -> &[u8] {
+    input = skip_line_ending(skip_whitespace(skip_bom(input)).0).0;
+    if has_header {
+        input = skip_header(input, quote_char, eol_char).0;
+    }
+    input
+}
+
 /// Remove whitespace and line endings from the start of file.
 #[inline]
 pub(crate) fn skip_whitespace(input: &[u8]) -> (&[u8], usize) {
@@ -137,7 +210,7 @@ pub(crate) fn skip_line_ending(input: &[u8]) -> (&[u8], usize) {
 }
 
 /// Get the mean and standard deviation of length of lines in bytes
-pub(crate) fn get_line_stats(bytes: &[u8], n_lines: usize) -> Option<(f32, f32)> {
+pub(crate) fn get_line_stats(bytes: &[u8], n_lines: usize, eol_char: u8) -> Option<(f32, f32)> {
     let mut n_read = 0;
     let mut lengths = Vec::with_capacity(n_lines);
     let file_len = bytes.len();
@@ -148,10 +221,10 @@ pub(crate) fn get_line_stats(bytes: &[u8], n_lines: usize) -> Option<(f32, f32)>
             return None;
         }
         bytes_trunc = &bytes[n_read..];
-        match bytes_trunc.iter().position(|&b| b == b'\n') {
+        match end_of_line_position(bytes_trunc, eol_char) {
             Some(position) => {
-                n_read += position + 1;
-                lengths.push(position + 1);
+                n_read += position;
+                lengths.push(position);
             }
             None => {
                 return None;
@@ -178,13 +251,15 @@ pub(crate) fn get_line_stats(bytes: &[u8], n_lines: usize) -> Option<(f32, f32)>
 /// For instance: "This is a valid field\nI have multiples lines" is a valid string field, that contains multiple lines.
 struct SplitLines<'a> {
     v: &'a [u8],
+    quote_char: u8,
     end_line_char: u8,
 }
 
 impl<'a> SplitLines<'a> {
-    fn new(slice: &'a [u8], end_line_char: u8) -> Self {
+    fn new(slice: &'a [u8], quote_char: u8, end_line_char: u8) -> Self {
         Self {
             v: slice,
+            quote_char,
             end_line_char,
         }
     }
@@ -201,24 +276,31 @@ impl<'a> Iterator for SplitLines<'a> {
 
         // denotes if we are in a string field, started with a quote
         let mut in_field = false;
-        let mut pos = 0u32;
+        let mut pos = 0usize;
+        let mut found_eol = false;
         for &c in self.v {
-            pos += 1;
-            if c == b'"' {
+            if c == self.quote_char {
                 // toggle between string field enclosure
                 //      if we encounter a starting '"' -> in_field = true;
                 //      if we encounter a closing '"' -> in_field = false;
                 in_field = !in_field;
             }
             // if we are not in a string and we encounter '\n' we can stop at this position.
-            if c == self.end_line_char && !in_field {
+            if is_eol(c, self.end_line_char) && !in_field {
+                found_eol = true;
                 break;
             }
+            pos += 1;
         }
         // return line up to this position
-        let ret = Some(&self.v[..(pos - 1) as usize]);
+        let ret = Some(&self.v[..pos]);
         // skip the '\n' token and update slice.
-        self.v = &self.v[pos as usize..];
+        // the last line doesn't need to end with a line ending.
+        self.v = if found_eol {
+            &self.v[pos + 1..]
+        } else {
+            &self.v[pos..]
+        };
         ret
     }
 }
@@ -228,14 +310,16 @@ impl<'a> Iterator for SplitLines<'a> {
 struct SplitFields<'a> {
     v: &'a [u8],
     delimiter: u8,
+    quote_char: u8,
     finished: bool,
 }
 
 impl<'a> SplitFields<'a> {
-    fn new(slice: &'a [u8], delimiter: u8) -> Self {
+    fn new(slice: &'a [u8], delimiter: u8, quote_char: u8) -> Self {
         Self {
             v: slice,
             delimiter,
+            quote_char,
             finished: false,
         }
     }
@@ -251,7 +335,8 @@ impl<'a> SplitFields<'a> {
 }
 
 impl<'a> Iterator for SplitFields<'a> {
-    // the bool is used to indicate that it requires escaping
+    // the bool is used to indicate that the field has escaped quotes and requires unescaping.
+    // Quoted fields without escaped quotes only need their outer quotes dropped.
     type Item = (&'a [u8], bool);
 
     #[inline]
@@ -263,8 +348,7 @@ impl<'a> Iterator for SplitFields<'a> {
         let mut needs_escaping = false;
         // There can be strings with delimiters:
         // "Street, City",
-        let pos = if !self.v.is_empty() && self.v[0] == b'"' {
-            needs_escaping = true;
+        let pos = if !self.v.is_empty() && self.v[0] == self.quote_char {
             // There can be pair of double-quotes within string.
             // Each of the embedded double-quote characters must be represented
             // by a pair of double-quote characters:
@@ -272,26 +356,30 @@ impl<'a> Iterator for SplitFields<'a> {
 
             // To find the last double-quote we check for the last uneven double-quote
             // character followed by a comma.
-            let mut previous_char = b'"';
+            let quote_char = self.quote_char;
+            let mut previous_char = quote_char;
             let mut idx = 0u32;
             let mut current_idx = 0u32;
             // micro optimizations
             #[allow(clippy::explicit_counter_loop)]
             for &current_char in self.v.iter() {
-                if current_char == self.delimiter && previous_char == b'"' {
+                if current_char == self.delimiter && previous_char == quote_char {
                     idx = current_idx;
                     break;
                 }
-                if current_char == b'"' && previous_char != b'"' {
-                    previous_char = b'"';
+                if current_char == quote_char && previous_char != quote_char {
+                    previous_char = quote_char;
                 } else {
+                    // A quote that follows an uneven quote is escaped, except for the opening
+                    // quote at the start of the field.
+                    needs_escaping |= current_char == quote_char && current_idx != 0;
                     // Replace previous char by '#' when the number of double-quote is even.
                     previous_char = b'#';
                 }
                 current_idx += 1;
             }
 
-            if idx == 0 && previous_char == b'"' {
+            if idx == 0 && previous_char == quote_char {
                 return self.finish(needs_escaping);
             }
 
@@ -330,6 +418,7 @@ pub(crate) fn parse_lines(
     ignore_parser_errors: bool,
     encoding: CsvEncoding,
     n_lines: usize,
+    eol_char: u8,
 ) -> Result<usize> {
     // This variable will store the number of bytes we read. It is important to do this bookkeeping
     // to be able to correctly parse the strings later.
@@ -341,11 +430,14 @@ pub(crate) fn parse_lines(
     // String types are not parsed. We store strings the starting index in the bytes array and store
     // the length of the string field. We also store the total length of processed string fields per column.
     // Later we use that meta information to exactly allocate the required buffers and parse the strings.
-    let iter_lines = SplitLines::new(bytes, b'\n');
-    for mut line in iter_lines.take(n_lines) {
+    let iter_lines = SplitLines::new(bytes, QUOTE_CHAR, eol_char);
+    // The index of the data row in `bytes`, used in the parse errors.
+    let mut row = 0;
+    for line in iter_lines.take(n_lines) {
         let len = line.len();
 
-        // two adjacent '\n\n' will lead to an empty line.
+        // two adjacent '\n\n' will lead to an empty line. This is also the case for
+        // '\r\n', as '\r' ends a line as well.
         if len == 0 {
             read += 1;
             continue;
@@ -353,10 +445,6 @@ pub(crate) fn parse_lines(
         // including the '\n' character
         let line_length = len + 1;
 
-        let trailing_byte = line[len - 1];
-        if trailing_byte == b'\r' {
-            line = &line[..len - 1];
-        }
         // read at start of the line
         let read_sol = read;
         // // +1 is the split character
@@ -379,7 +467,7 @@ pub(crate) fn parse_lines(
             .expect("at least one column should be projected");
         let mut processed_fields = 0;
 
-        let iter = SplitFields::new(line, delimiter);
+        let iter = SplitFields::new(line, delimiter, QUOTE_CHAR);
 
         for (idx, (field, needs_escaping)) in iter.enumerate() {
             if idx == next_projected {
@@ -402,16 +490,10 @@ pub(crate) fn parse_lines(
                     buf.add_null()
                 } else {
                     buf.add(field, ignore_parser_errors, read, encoding, needs_escaping)
-                        .map_err(|e| {
-                            PolarsError::Other(
-                                format!(
-                                    "{:?} on thread line {}; on input: {}",
-                                    e,
-                                    idx,
-                                    String::from_utf8_lossy(field)
-                                )
-                                .into(),
-                            )
+                        .map_err(|_| PolarsError::Parse {
+                            dtype: buf.dtype(),
+                            value: String::from_utf8_lossy(field).into_owned(),
+                            row,
                         })?;
                 }
 
@@ -446,10 +528,47 @@ pub(crate) fn parse_lines(
         // this way we also include the trailing '\n' or '\r\n' in the bytes read
         // and any skipped fields.
         read = read_sol + line_length;
+        row += 1;
     }
     Ok(read)
 }
 
+/// The rows in the [PolarsError::Parse] errors of [parse_lines] are relative to the parsed
+/// bytes. This adds the number of data rows in `bytes_before`, the bytes preceding the parsed
+/// bytes, so that the row is relative to the start of the data.
+pub(crate) fn offset_parse_error(
+    err: PolarsError,
+    bytes_before: &[u8],
+    comment_char: Option<u8>,
+    quote_char: u8,
+    eol_char: u8,
+) -> PolarsError {
+    match err {
+        PolarsError::Parse { dtype, value, row } => {
+            let rows_before = count_data_rows(bytes_before, comment_char, quote_char, eol_char);
+            PolarsError::Parse {
+                dtype,
+                value,
+                row: rows_before + row,
+            }
+        }
+        err => err,
+    }
+}
+
+/// Count the rows in `bytes` in the same way as [parse_lines] splits them. Empty lines and
+/// comments are not counted.
+pub(crate) fn count_data_rows(
+    bytes: &[u8],
+    comment_char: Option<u8>,
+    quote_char: u8,
+    eol_char: u8,
+) -> usize {
+    SplitLines::new(bytes, quote_char, eol_char)
+        .filter(|line| !line.is_empty() && comment_char.map_or(true, |c| line[0] != c))
+        .count()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -464,4 +583,80 @@ mod test {
         hello";
         assert_eq!(skip_whitespace(input).0, b"hello");
     }
+
+    #[test]
+    fn test_line_endings() {
+        let input = b"a,b\r\nc,d\re,f\ng,h";
+        let lines: Vec<_> = SplitLines::new(input, QUOTE_CHAR, b'\n')
+            .filter(|line| !line.is_empty())
+            .collect();
+        assert_eq!(lines, &[&b"a,b"[..], b"c,d", b"e,f", b"g,h"]);
+
+        assert_eq!(next_line_position_naive(input, b'\n'), Some(5));
+        assert_eq!(skip_header(input, QUOTE_CHAR, b'\n').0, b"c,d\re,f\ng,h");
+        // a position between '\r' and '\n' moves to the next line
+        assert_eq!(
+            next_line_position(&input[4..], 2, b',', QUOTE_CHAR, b'\n'),
+            Some(1)
+        );
+
+        let input = b"a,b;c,d";
+        assert_eq!(skip_header(input, QUOTE_CHAR, b';').0, b"c,d");
+        assert_eq!(skip_header(b"a,b,c\n", QUOTE_CHAR, b'\n').0, b"");
+        assert_eq!(skip_header(b"", QUOTE_CHAR, b'\n').0, b"");
+        // a quoted new line in the header
+        let input = b"\"a\nb\",c\r\n1,2\n";
+        assert_eq!(skip_header(input, QUOTE_CHAR, b'\n').0, b"1,2\n");
+    }
+
+    #[test]
+    fn test_row_start_at_or_after() {
+        let input = b"a,\"b\nc\"\nd,e\n";
+        assert_eq!(row_start_at_or_after(input, 0, QUOTE_CHAR, b'\n'), 0);
+        // in the quoted new line
+        assert_eq!(row_start_at_or_after(input, 4, QUOTE_CHAR, b'\n'), 8);
+        assert_eq!(row_start_at_or_after(input, 5, QUOTE_CHAR, b'\n'), 8);
+        assert_eq!(row_start_at_or_after(input, 8, QUOTE_CHAR, b'\n'), 8);
+        assert_eq!(
+            row_start_at_or_after(input, 9, QUOTE_CHAR, b'\n'),
+            input.len()
+        );
+        assert_eq!(
+            row_start_at_or_after(input, 100, QUOTE_CHAR, b'\n'),
+            input.len()
+        );
+        // other quote characters
+        let input = b"a,'b\nc'\nd,e\n";
+        assert_eq!(row_start_at_or_after(input, 4, b'\'', b'\n'), 8);
+        assert_eq!(row_start_at_or_after(input, 4, QUOTE_CHAR, b'\n'), 5);
+        // `\r\n` is a single line ending
+        let input = b"a,b\r\nc,d\r\n";
+        assert_eq!(next_row_position(input, false, QUOTE_CHAR, b'\n'), Some(5));
+        assert_eq!(row_start_at_or_after(input, 4, QUOTE_CHAR, b'\n'), 5);
+        assert_eq!(row_start_at_or_after(input, 5, QUOTE_CHAR, b'\n'), 5);
+    }
+
+    #[test]
+    fn test_count_data_rows() {
+        let input = b"a,\"b\nc\"\n\n#comment\nd,e\r\nf,g";
+        assert_eq!(count_data_rows(input, Some(b'#'), QUOTE_CHAR, b'\n'), 3);
+        assert_eq!(count_data_rows(input, None, QUOTE_CHAR, b'\n'), 4);
+        assert_eq!(count_data_rows(input, None, b'\'', b'\n'), 5);
+    }
+
+    #[test]
+    fn test_split_fields_escaping() {
+        let input = b"a,\"b, c\",\"d \"\"e\"\"\",\"\",\"\"\"\"";
+        let fields: Vec<_> = SplitFields::new(input, b',', QUOTE_CHAR).collect();
+        assert_eq!(
+            fields,
+            &[
+                (&b"a"[..], false),
+                (&b"\"b, c\""[..], false),
+                (&b"\"d \"\"e\"\"\""[..], true),
+                (&b"\"\""[..], false),
+                (&b"\"\"\"\""[..], true),
+            ]
+        );
+    }
 }
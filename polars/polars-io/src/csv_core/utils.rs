@@ -1,22 +1,35 @@
-use crate::csv::CsvEncoding;
-use crate::csv_core::parser::next_line_position;
+use crate::csv::{CsvEncoding, InferenceOptions};
+use crate::csv_core::parser::{
+    count_data_rows, next_line_position, next_row_position, skip_to_data, QUOTE_CHAR,
+};
+use csv::ByteRecord;
 use lazy_static::lazy_static;
-use polars_core::datatypes::PlHashSet;
+use polars_core::datatypes::{PlHashMap, PlHashSet};
 use polars_core::prelude::*;
+use polars_core::POOL;
+use rayon::prelude::*;
 use regex::{Regex, RegexBuilder};
 use std::borrow::Cow;
 use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
 
 pub(crate) fn init_csv_reader<R: Read>(
     reader: R,
     has_header: bool,
     delimiter: u8,
+    quote_char: u8,
     comment_char: Option<u8>,
+    eol_char: u8,
 ) -> csv::Reader<R> {
     let mut reader_builder = csv::ReaderBuilder::new();
     reader_builder.has_headers(has_header);
     reader_builder.delimiter(delimiter);
+    reader_builder.quote(quote_char);
     reader_builder.comment(comment_char);
+    // the default terminator already handles '\n', '\r' and '\r\n'
+    if eol_char != b'\n' {
+        reader_builder.terminator(csv::Terminator::Any(eol_char));
+    }
     // don't error on shorter fields.
     reader_builder.flexible(true);
     reader_builder.from_reader(reader)
@@ -27,6 +40,8 @@ pub(crate) fn get_file_chunks(
     n_threads: usize,
     expected_fields: usize,
     delimiter: u8,
+    quote_char: u8,
+    eol_char: u8,
 ) -> Vec<(usize, usize)> {
     let mut last_pos = 0;
     let total_len = bytes.len();
@@ -39,19 +54,89 @@ pub(crate) fn get_file_chunks(
             break;
         }
 
-        let end_pos = match next_line_position(&bytes[search_pos..], expected_fields, delimiter) {
+        let end_pos = match next_line_position(
+            &bytes[search_pos..],
+            expected_fields,
+            delimiter,
+            quote_char,
+            eol_char,
+        ) {
             Some(pos) => search_pos + pos,
             None => {
                 break;
             }
         };
-        offsets.push((last_pos, end_pos + 1));
+        // the chunks don't overlap, `end_pos` is the start of the next line
+        offsets.push((last_pos, end_pos));
         last_pos = end_pos;
     }
     offsets.push((last_pos, total_len));
     offsets
 }
 
+/// Split `bytes` in about `n_chunks` chunks that start at a row. Unlike [get_file_chunks], the
+/// quotes are tracked from the start of `bytes`, so a chunk never starts in a quoted field.
+///
+/// The quotes of the chunks are counted in parallel. A chunk starts at the first row after
+/// every multiple of the chunk size, which is in a quoted field if the number of quotes before
+/// it is odd.
+pub(crate) fn get_row_chunks(
+    bytes: &[u8],
+    n_chunks: usize,
+    quote_char: u8,
+    eol_char: u8,
+) -> Vec<(usize, usize)> {
+    let chunk_size = std::cmp::max(bytes.len() / std::cmp::max(n_chunks, 1), 1);
+    let search_positions = (1..)
+        .map(|i| i * chunk_size)
+        .take_while(|&pos| pos < bytes.len())
+        .collect::<Vec<_>>();
+
+    let row_starts = POOL.install(|| {
+        let quote_counts = search_positions
+            .par_iter()
+            .map(|&pos| {
+                bytes[pos - chunk_size..pos]
+                    .iter()
+                    .filter(|&&b| b == quote_char)
+                    .count()
+            })
+            .collect::<Vec<_>>();
+        let mut n_quotes = 0;
+        let in_quotes = quote_counts
+            .iter()
+            .map(|n| {
+                n_quotes += n;
+                n_quotes % 2 == 1
+            })
+            .collect::<Vec<_>>();
+        search_positions
+            .par_iter()
+            .zip(in_quotes.par_iter())
+            .map(|(&pos, &in_quotes)| {
+                next_row_position(&bytes[pos..], in_quotes, quote_char, eol_char)
+                    .map(|row_pos| pos + row_pos)
+            })
+            .collect::<Vec<_>>()
+    });
+
+    let mut offsets = Vec::with_capacity(n_chunks);
+    let mut last_pos = 0;
+    for row_start in row_starts {
+        match row_start {
+            // a row can span multiple chunks
+            Some(pos) if pos <= last_pos => {}
+            Some(pos) => {
+                offsets.push((last_pos, pos));
+                last_pos = pos;
+            }
+            None => break,
+        }
+    }
+    offsets.push((last_pos, bytes.len()));
+    offsets
+}
+
 lazy_static! {
     static ref DECIMAL_RE: Regex = Regex::new(r"^\s*-?(\d+\.\d+)$").unwrap();
     static ref INTEGER_RE: Regex = Regex::new(r"^\s*-?(\d+)$").unwrap();
@@ -61,22 +146,72 @@ lazy_static! {
         .unwrap();
 }
 
+/// Check if a record can be parsed as the given data type.
+fn fits_dtype(string: &str, dtype: &DataType) -> bool {
+    match dtype {
+        DataType::Boolean => BOOLEAN_RE.is_match(string),
+        DataType::Int32 => INTEGER_RE.is_match(string) && string.trim().parse::<i32>().is_ok(),
+        DataType::Int64 => INTEGER_RE.is_match(string) && string.trim().parse::<i64>().is_ok(),
+        DataType::UInt8 => INTEGER_RE.is_match(string) && string.trim().parse::<u8>().is_ok(),
+        DataType::UInt16 => INTEGER_RE.is_match(string) && string.trim().parse::<u16>().is_ok(),
+        DataType::UInt32 => INTEGER_RE.is_match(string) && string.trim().parse::<u32>().is_ok(),
+        DataType::UInt64 => INTEGER_RE.is_match(string) && string.trim().parse::<u64>().is_ok(),
+        DataType::Float32 | DataType::Float64 => {
+            DECIMAL_RE.is_match(string) || INTEGER_RE.is_match(string)
+        }
+        DataType::Utf8 => true,
+        _ => false,
+    }
+}
+
 /// Infer the data type of a record
-fn infer_field_schema(string: &str) -> DataType {
+fn infer_field_schema(string: &str, dtypes: &[DataType]) -> DataType {
     // when quoting is enabled in the reader, these quotes aren't escaped, we default to
     // Utf8 for them
     if string.starts_with('"') {
         return DataType::Utf8;
     }
-    // match regex in a particular order
-    if BOOLEAN_RE.is_match(string) {
-        DataType::Boolean
-    } else if DECIMAL_RE.is_match(string) {
-        DataType::Float64
-    } else if INTEGER_RE.is_match(string) {
-        DataType::Int64
-    } else {
-        DataType::Utf8
+    // take the first data type in order of priority
+    dtypes
+        .iter()
+        .find(|dtype| fits_dtype(string, dtype))
+        .cloned()
+        .unwrap_or(DataType::Utf8)
+}
+
+/// Determine the data type of a column from the data types inferred for its records.
+fn resolve_dtype(possibilities: &PlHashSet<DataType>, options: &InferenceOptions) -> DataType {
+    match possibilities.len() {
+        0 => options.null_dtype.clone(),
+        1 => possibilities.iter().next().unwrap().clone(),
+        _ => {
+            let is_numeric = |dtype: &DataType| {
+                matches!(
+                    dtype,
+                    DataType::Int32
+                        | DataType::Int64
+                        | DataType::UInt8
+                        | DataType::UInt16
+                        | DataType::UInt32
+                        | DataType::UInt64
+                        | DataType::Float32
+                        | DataType::Float64
+                )
+            };
+            if possibilities.iter().all(is_numeric) {
+                // widen to the numeric type that comes last in the priority list
+                options
+                    .dtypes
+                    .iter()
+                    .rev()
+                    .find(|dtype| possibilities.contains(dtype))
+                    .cloned()
+                    .unwrap_or(DataType::Utf8)
+            } else {
+                // default to Utf8 for conflicting datatypes (e.g bool and int)
+                DataType::Utf8
+            }
+        }
     }
 }
 
@@ -91,12 +226,115 @@ pub(crate) fn parse_bytes_with_encoding(bytes: &[u8], encoding: CsvEncoding) ->
     Ok(s)
 }
 
+/// Rename the repeated occurrences of a column name to `{name}_duplicated_{n}`, where `n` counts
+/// from zero per name. The new names never collide with names in the header. If
+/// `error_on_duplicates` is set, repeated names are an error instead.
+pub(crate) fn dedup_column_names(
+    names: Vec<String>,
+    error_on_duplicates: bool,
+) -> Result<Vec<String>> {
+    let header: PlHashSet<String> = names.iter().cloned().collect();
+    if header.len() == names.len() {
+        return Ok(names);
+    }
+
+    let mut taken = PlHashSet::with_capacity(names.len());
+    let mut n_duplicates: PlHashMap<String, usize> = PlHashMap::default();
+    names
+        .into_iter()
+        .map(|name| {
+            if taken.insert(name.clone()) {
+                return Ok(name);
+            }
+            if error_on_duplicates {
+                return Err(PolarsError::Duplicate(
+                    format!("column '{}' occurs more than once in the csv header", name).into(),
+                ));
+            }
+            let n = n_duplicates.entry(name.clone()).or_insert(0);
+            loop {
+                let new_name = format!("{}_duplicated_{}", name, n);
+                *n += 1;
+                if !header.contains(&new_name) && taken.insert(new_name.clone()) {
+                    return Ok(new_name);
+                }
+            }
+        })
+        .collect()
+}
+
+/// Get the column names from a header record.
+fn header_names(
+    record: &ByteRecord,
+    encoding: CsvEncoding,
+    error_on_duplicates: bool,
+) -> Result<Vec<String>> {
+    let names = record
+        .iter()
+        .map(|slice| {
+            let s = parse_bytes_with_encoding(slice, encoding)?;
+            Ok(s.into())
+        })
+        .collect::<Result<_>>()?;
+    dedup_column_names(names, error_on_duplicates)
+}
+
+/// Skip the utf8-bom, otherwise it ends up in the first column name.
+fn skip_bom_reader<R: Read>(reader: R) -> Result<BufReader<R>> {
+    let mut reader = BufReader::new(reader);
+    if reader.fill_buf()?.starts_with(b"\xef\xbb\xbf") {
+        reader.consume(3);
+    }
+    Ok(reader)
+}
+
+/// Read the column names from the header of a CSV file, without reading any data.
+pub(crate) fn read_header<R: Read>(
+    reader: R,
+    delimiter: u8,
+    quote_char: u8,
+    error_on_duplicates: bool,
+) -> Result<Vec<String>> {
+    let reader = skip_bom_reader(reader)?;
+    let csv_reader = init_csv_reader(reader, false, delimiter, quote_char, None, b'\n');
+    match csv_reader.into_byte_records().next() {
+        Some(record) => {
+            let record = record.map_err(anyhow::Error::from)?;
+            header_names(&record, CsvEncoding::LossyUtf8, error_on_duplicates)
+        }
+        None => Err(PolarsError::NoData("empty csv".into())),
+    }
+}
+
+/// Count the rows of a CSV file without parsing them. The rows are split in the same way as by
+/// the [CsvReader](crate::csv::CsvReader) and the chunks of the file are counted in parallel.
+pub(crate) fn count_rows(path: &Path, quote_char: u8, has_header: bool) -> Result<usize> {
+    let eol_char = b'\n';
+    let file = std::fs::File::open(path)?;
+    // an empty file cannot be memory mapped on all platforms
+    if file.metadata()?.len() == 0 {
+        return Ok(0);
+    }
+    let mmap = unsafe { memmap::Mmap::map(&file)? };
+    let bytes = skip_to_data(&mmap[..], has_header, quote_char, eol_char);
+
+    let file_chunks = get_row_chunks(bytes, POOL.current_num_threads(), quote_char, eol_char);
+    let n_rows = POOL.install(|| {
+        file_chunks
+            .into_par_iter()
+            .map(|(start, stop)| count_data_rows(&bytes[start..stop], None, quote_char, eol_char))
+            .sum()
+    });
+    Ok(n_rows)
+}
+
 /// Infer the schema of a CSV file by reading through the first n records of the file,
 /// with `max_read_records` controlling the maximum number of records to read.
 ///
 /// If `max_read_records` is not set, the whole file is read to infer its schema.
 ///
 /// Return inferred schema and number of records used for inference.
+#[allow(clippy::too_many_arguments)]
 pub fn infer_file_schema<R: Read + Seek>(
     reader: &mut R,
     delimiter: u8,
@@ -105,8 +343,10 @@ pub fn infer_file_schema<R: Read + Seek>(
     schema_overwrite: Option<&Schema>,
     skip_rows: usize,
     comment_char: Option<u8>,
+    eol_char: u8,
+    inference_options: &InferenceOptions,
 ) -> Result<(Schema, usize)> {
-    let mut reader = BufReader::new(reader);
+    let mut reader = skip_bom_reader(reader)?;
     let mut line = String::new();
     for _ in 0..skip_rows {
         reader.read_line(&mut line)?;
@@ -117,7 +357,7 @@ pub fn infer_file_schema<R: Read + Seek>(
     // It may later.
     let encoding = CsvEncoding::LossyUtf8;
     // set headers to false otherwise the csv crate, skips them.
-    let csv_reader = init_csv_reader(reader, false, delimiter, comment_char);
+    let csv_reader = init_csv_reader(reader, false, delimiter, QUOTE_CHAR, comment_char, eol_char);
 
     let mut records = csv_reader.into_byte_records();
     let header_length;
@@ -128,13 +368,8 @@ pub fn infer_file_schema<R: Read + Seek>(
         let byterecord = byterecord.map_err(anyhow::Error::from)?;
         header_length = byterecord.len();
         if has_header {
-            byterecord
-                .iter()
-                .map(|slice| {
-                    let s = parse_bytes_with_encoding(slice, encoding)?;
-                    Ok(s.into())
-                })
-                .collect::<Result<_>>()?
+            // duplicate names would make the columns ambiguous
+            header_names(&byterecord, encoding, false)?
         } else {
             (0..header_length)
                 .map(|i| format!("column_{}", i + 1))
@@ -146,9 +381,6 @@ pub fn infer_file_schema<R: Read + Seek>(
 
     // keep track of inferred field types
     let mut column_types: Vec<PlHashSet<DataType>> = vec![PlHashSet::new(); header_length];
-    // keep track of columns with nulls
-    let mut nulls: Vec<bool> = vec![false; header_length];
-
     let mut records_count = 0;
     let mut fields = Vec::with_capacity(header_length);
 
@@ -161,12 +393,11 @@ pub fn infer_file_schema<R: Read + Seek>(
 
         for i in 0..header_length {
             if let Some(slice) = record.get(i) {
-                if slice.is_empty() {
-                    nulls[i] = true;
-                } else {
-                    let s = parse_bytes_with_encoding(slice, encoding)?;
-                    column_types[i].insert(infer_field_schema(&s));
+                if slice.is_empty() && inference_options.empty_as_null {
+                    continue;
                 }
+                let s = parse_bytes_with_encoding(slice, encoding)?;
+                column_types[i].insert(infer_field_schema(&s, &inference_options.dtypes));
             }
         }
     }
@@ -183,27 +414,10 @@ pub fn infer_file_schema<R: Read + Seek>(
             }
         }
 
-        // determine data type based on possible types
-        // if there are incompatible types, use DataType::Utf8
-        match possibilities.len() {
-            1 => {
-                for dtype in possibilities.iter() {
-                    fields.push(Field::new(field_name, dtype.clone()));
-                }
-            }
-            2 => {
-                if possibilities.contains(&DataType::Int64)
-                    && possibilities.contains(&DataType::Float64)
-                {
-                    // we have an integer and double, fall down to double
-                    fields.push(Field::new(field_name, DataType::Float64));
-                } else {
-                    // default to Utf8 for conflicting datatypes (e.g bool and int)
-                    fields.push(Field::new(field_name, DataType::Utf8));
-                }
-            }
-            _ => fields.push(Field::new(field_name, DataType::Utf8)),
-        }
+        fields.push(Field::new(
+            field_name,
+            resolve_dtype(possibilities, inference_options),
+        ));
     }
     let csv_reader = records.into_reader();
 
@@ -223,7 +437,61 @@ mod test {
         let s = std::fs::read_to_string(path).unwrap();
         let bytes = s.as_bytes();
         // can be within -1 / +1 bounds.
-        assert!((get_file_chunks(bytes, 10, 4, b',').len() as i32 - 10).abs() <= 1);
-        assert!((get_file_chunks(bytes, 8, 4, b',').len() as i32 - 8).abs() <= 1);
+        assert!(
+            (get_file_chunks(bytes, 10, 4, b',', QUOTE_CHAR, b'\n').len() as i32 - 10).abs() <= 1
+        );
+        assert!(
+            (get_file_chunks(bytes, 8, 4, b',', QUOTE_CHAR, b'\n').len() as i32 - 8).abs() <= 1
+        );
+    }
+
+    #[test]
+    fn test_get_file_chunks_crlf() {
+        let bytes = b"a,b\r\nc,d\r\ne,f\r\ng,h\r\ni,j\r\n";
+        let chunks = get_file_chunks(bytes, 3, 2, b',', QUOTE_CHAR, b'\n');
+        // the chunks are contiguous and every chunk starts at a new line
+        let mut last = 0;
+        for (start, stop) in chunks {
+            assert_eq!(start, last);
+            assert!(start == 0 || bytes[start - 1] == b'\n');
+            last = stop;
+        }
+        assert_eq!(last, bytes.len());
+    }
+
+    #[test]
+    fn test_dedup_column_names() -> Result<()> {
+        let names = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            dedup_column_names(names(&["a", "b", "a", "a"]), false)?,
+            names(&["a", "b", "a_duplicated_0", "a_duplicated_1"])
+        );
+        // generated names don't collide with the header
+        assert_eq!(
+            dedup_column_names(names(&["a", "a", "a_duplicated_0"]), false)?,
+            names(&["a", "a_duplicated_1", "a_duplicated_0"])
+        );
+        assert!(dedup_column_names(names(&["a", "a"]), true).is_err());
+        assert!(dedup_column_names(names(&["a", "b"]), true).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_row_chunks() {
+        let bytes = b"a,\"b\n\nc\"\r\nd,e\n\"f\ng\",h\n";
+        for n_chunks in 1..bytes.len() + 2 {
+            let chunks = get_row_chunks(bytes, n_chunks, QUOTE_CHAR, b'\n');
+            let mut last = 0;
+            for &(start, stop) in &chunks {
+                assert_eq!(start, last);
+                last = stop;
+            }
+            assert_eq!(last, bytes.len());
+            let n_rows: usize = chunks
+                .iter()
+                .map(|&(start, stop)| count_data_rows(&bytes[start..stop], None, QUOTE_CHAR, b'\n'))
+                .sum();
+            assert_eq!(n_rows, 3, "{} chunks", n_chunks);
+        }
     }
 }
@@ -34,11 +34,13 @@
 //! ```
 use super::{finish_reader, ArrowReader, ArrowResult, RecordBatch};
 use crate::prelude::*;
-use crate::utils::to_arrow_compatible_df;
+use arrow::array::ArrayRef;
 use arrow::ipc::{
-    reader::FileReader as ArrowIPCFileReader, writer::FileWriter as ArrowIPCFileWriter,
+    reader::FileReader as ArrowIPCFileReader, reader::StreamReader as ArrowIPCStreamReader,
+    writer::FileWriter as ArrowIPCFileWriter, writer::StreamWriter as ArrowIPCStreamWriter,
 };
 use polars_core::prelude::*;
+use std::convert::TryFrom;
 use std::io::{Read, Seek, Write};
 use std::sync::Arc;
 
@@ -131,12 +133,13 @@ where
     }
 
     fn finish(self, df: &DataFrame) -> Result<()> {
-        let df = to_arrow_compatible_df(df);
-        let mut ipc_writer = ArrowIPCFileWriter::try_new(self.writer, &df.schema().to_arrow())?;
+        let (schema, columns) = to_ipc_columns(df)?;
+        let mut ipc_writer = ArrowIPCFileWriter::try_new(self.writer, &schema)?;
 
-        let iter = df.iter_record_batches();
-
-        for batch in iter {
+        let schema = Arc::new(schema);
+        for idx in 0..df.n_chunks().unwrap_or(0) {
+            let batch_cols = columns.iter().map(|chunks| chunks[idx].clone()).collect();
+            let batch = RecordBatch::try_new(schema.clone(), batch_cols)?;
             ipc_writer.write(&batch)?
         }
         let _ = ipc_writer.finish()?;
@@ -144,6 +147,175 @@ where
     }
 }
 
+/// The arrow field of a column of the IPC format. Categorical columns are dictionary fields,
+/// every column gets its own dictionary id.
+fn to_ipc_field(dict_id: usize, field: &Field) -> ArrowField {
+    match field.data_type() {
+        DataType::Categorical => {
+            let dtype = ArrowDataType::Dictionary(
+                Box::new(ArrowDataType::UInt32),
+                Box::new(ArrowDataType::LargeUtf8),
+            );
+            ArrowField::new_dict(field.name(), dtype, true, dict_id as i64, false)
+        }
+        _ => field.to_arrow(),
+    }
+}
+
+/// Get the arrow schema and the chunks of the columns of `df`.
+///
+/// Categorical columns are written as dictionary arrays, so that the codes of the categories
+/// survive a round trip. All chunks of a column share the dictionary of the column.
+fn to_ipc_columns(df: &DataFrame) -> Result<(ArrowSchema, Vec<Vec<ArrayRef>>)> {
+    let mut fields = Vec::with_capacity(df.width());
+    let mut columns = Vec::with_capacity(df.width());
+    for (dict_id, s) in df.get_columns().iter().enumerate() {
+        fields.push(to_ipc_field(dict_id, s.field()));
+        match s.dtype() {
+            DataType::Categorical => columns.push(s.categorical()?.to_arrow_dictionary()),
+            _ => columns.push(s.chunks().clone()),
+        }
+    }
+    Ok((ArrowSchema::new(fields), columns))
+}
+
+/// Write DataFrames incrementally in Arrow's IPC streaming format, so that readers can start
+/// reading before all data is written.
+///
+/// Every chunk of a written DataFrame is a record batch of the stream. The dictionary of a
+/// Categorical column is written before the first batch that uses it, and written again when a
+/// later batch has a different mapping.
+///
+/// # Example
+///
+/// ```
+/// use polars_core::prelude::*;
+/// use polars_io::ipc::{IpcStreamReader, IpcStreamWriter};
+///
+/// fn example(dfs: &[DataFrame]) -> Result<Vec<DataFrame>> {
+///     let mut buf: Vec<u8> = Vec::new();
+///     let mut writer = IpcStreamWriter::new(&mut buf, &dfs[0].schema())?;
+///     for df in dfs {
+///         writer.write_batch(df)?;
+///     }
+///     writer.finish()?;
+///     // release the borrow of `buf`
+///     drop(writer);
+///
+///     IpcStreamReader::new(buf.as_slice())?.collect()
+/// }
+/// ```
+pub struct IpcStreamWriter<W: Write> {
+    writer: ArrowIPCStreamWriter<W>,
+    schema: Schema,
+    arrow_schema: Arc<ArrowSchema>,
+}
+
+impl<W> IpcStreamWriter<W>
+where
+    W: Write,
+{
+    /// Write the schema of the stream. All written DataFrames must have this schema.
+    pub fn new(writer: W, schema: &Schema) -> Result<Self> {
+        let arrow_schema = ArrowSchema::new(
+            schema
+                .fields()
+                .iter()
+                .enumerate()
+                .map(|(dict_id, field)| to_ipc_field(dict_id, field))
+                .collect(),
+        );
+        let writer = ArrowIPCStreamWriter::try_new(writer, &arrow_schema)?;
+        Ok(IpcStreamWriter {
+            writer,
+            schema: schema.clone(),
+            arrow_schema: Arc::new(arrow_schema),
+        })
+    }
+
+    /// Write the chunks of `df` as record batches. The columns are rechunked if their chunks
+    /// don't have the same lengths.
+    pub fn write_batch(&mut self, df: &DataFrame) -> Result<()> {
+        let schema = df.schema();
+        if schema != self.schema {
+            return Err(PolarsError::SchemaMisMatch {
+                expected: format!("{:?}", self.schema).into(),
+                found: format!("{:?}", schema).into(),
+            });
+        }
+        let aligned_df;
+        let df = if chunks_aligned(df) {
+            df
+        } else {
+            aligned_df = df.agg_chunks();
+            &aligned_df
+        };
+        let (_, columns) = to_ipc_columns(df)?;
+
+        for idx in 0..df.n_chunks().unwrap_or(0) {
+            let batch_cols = columns.iter().map(|chunks| chunks[idx].clone()).collect();
+            let batch = RecordBatch::try_new(self.arrow_schema.clone(), batch_cols)?;
+            self.writer.write(&batch)?
+        }
+        Ok(())
+    }
+
+    /// Write the end of stream marker, no batches can be written afterwards.
+    pub fn finish(&mut self) -> Result<()> {
+        self.writer.finish()?;
+        Ok(())
+    }
+}
+
+/// Whether the chunks of all columns have the same lengths.
+fn chunks_aligned(df: &DataFrame) -> bool {
+    let chunk_lengths = |s: &Series| s.chunks().iter().map(|arr| arr.len()).collect::<Vec<_>>();
+    let mut columns = df.get_columns().iter();
+    match columns.next() {
+        Some(first) => {
+            let lengths = chunk_lengths(first);
+            columns.all(|s| chunk_lengths(s) == lengths)
+        }
+        None => true,
+    }
+}
+
+/// Read Arrow's IPC streaming format batch by batch. Every record batch of the stream is
+/// yielded as a DataFrame.
+pub struct IpcStreamReader<R: Read> {
+    reader: ArrowIPCStreamReader<R>,
+}
+
+impl<R> IpcStreamReader<R>
+where
+    R: Read,
+{
+    /// Read the schema of the stream.
+    pub fn new(reader: R) -> Result<Self> {
+        let reader = ArrowIPCStreamReader::try_new(reader)?;
+        Ok(IpcStreamReader { reader })
+    }
+
+    /// The schema of the DataFrames in the stream.
+    pub fn schema(&self) -> Schema {
+        (&*self.reader.schema()).into()
+    }
+}
+
+impl<R> Iterator for IpcStreamReader<R>
+where
+    R: Read,
+{
+    type Item = Result<DataFrame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.reader.next().map(|batch| {
+            let batch = batch?;
+            DataFrame::try_from(batch)
+        })
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::prelude::*;
@@ -165,4 +337,101 @@ mod test {
         let df_read = IpcReader::new(buf).finish().unwrap();
         assert!(df.frame_equal(&df_read));
     }
+
+    #[test]
+    fn write_and_read_ipc_categorical() -> Result<()> {
+        let s = Series::new("cat", &[Some("b"), None, Some("a"), Some("b"), Some("c")])
+            .cast::<CategoricalType>()?;
+        // two chunks that share a single mapping
+        let mut cat = s.clone();
+        cat.append(&s)?;
+        let mut v = Series::new("v", &[1, 2, 3, 4, 5]);
+        v.append(&v.clone())?;
+        let df = DataFrame::new(vec![cat, v])?;
+
+        let mut buf: Cursor<Vec<u8>> = Cursor::new(Vec::new());
+        IpcWriter::new(&mut buf).finish(&df)?;
+        buf.set_position(0);
+        let df_read = IpcReader::new(buf).set_rechunk(false).finish()?;
+
+        let cat = df.column("cat")?;
+        let cat_read = df_read.column("cat")?;
+        assert_eq!(cat_read.dtype(), &DataType::Categorical);
+        assert_eq!(cat_read.n_chunks(), 2);
+        // the codes are kept, not only the strings
+        assert!(cat_read
+            .cast::<UInt32Type>()?
+            .series_equal_missing(&cat.cast::<UInt32Type>()?));
+        assert!(cat_read
+            .cast::<Utf8Type>()?
+            .series_equal_missing(&cat.cast::<Utf8Type>()?));
+        assert!(df_read.column("v")?.series_equal(df.column("v")?));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "dtype-struct")]
+    fn write_and_read_ipc_struct() -> Result<()> {
+        let df = create_df();
+        let payload = StructChunked::new_with_validity(
+            "payload",
+            df.get_columns().to_vec(),
+            Some((0..df.height()).map(|i| i % 2 == 0).collect()),
+        )?;
+        let df = DataFrame::new(vec![payload.into_series()])?;
+
+        let mut buf: Cursor<Vec<u8>> = Cursor::new(Vec::new());
+        IpcWriter::new(&mut buf).finish(&df)?;
+        buf.set_position(0);
+        let df_read = IpcReader::new(buf).finish()?;
+
+        let s = df_read.column("payload")?;
+        assert_eq!(s.dtype(), df.column("payload")?.dtype());
+        assert_eq!(s.null_count(), df.column("payload")?.null_count());
+        assert!(df_read
+            .unnest(&["payload"])?
+            .frame_equal_missing(&df.unnest(&["payload"])?));
+        Ok(())
+    }
+
+    #[test]
+    fn write_and_read_ipc_stream() -> Result<()> {
+        let cat = |values: &[Option<&str>]| Series::new("cat", values).cast::<CategoricalType>();
+        let df0 = DataFrame::new(vec![
+            cat(&[Some("b"), None, Some("a")])?,
+            Series::new("v", &[1, 2, 3]),
+        ])?;
+        // a second batch of two chunks with a different categorical mapping
+        let mut df1 = DataFrame::new(vec![
+            cat(&[Some("c"), Some("a")])?,
+            Series::new("v", &[4, 5]),
+        ])?;
+        df1.vstack_mut(&df1.clone())?;
+        assert_eq!(df1.n_chunks()?, 2);
+
+        let mut buf: Vec<u8> = Vec::new();
+        let mut writer = IpcStreamWriter::new(&mut buf, &df0.schema())?;
+        writer.write_batch(&df0)?;
+        writer.write_batch(&df1)?;
+        let wrong_schema = DataFrame::new(vec![Series::new("v", &[1])])?;
+        assert!(writer.write_batch(&wrong_schema).is_err());
+        writer.finish()?;
+        drop(writer);
+
+        let reader = IpcStreamReader::new(buf.as_slice())?;
+        assert_eq!(reader.schema(), df0.schema());
+        let dfs = reader.collect::<Result<Vec<_>>>()?;
+        assert_eq!(dfs.len(), 3);
+
+        let expected = [df0, df1.slice(0, 2), df1.slice(2, 2)];
+        for (df, expected) in dfs.iter().zip(expected.iter()) {
+            assert_eq!(df.column("cat")?.dtype(), &DataType::Categorical);
+            assert!(df
+                .column("cat")?
+                .cast::<Utf8Type>()?
+                .series_equal_missing(&expected.column("cat")?.cast::<Utf8Type>()?));
+            assert!(df.column("v")?.series_equal(expected.column("v")?));
+        }
+        Ok(())
+    }
 }
@@ -1,5 +1,8 @@
 //! # (De)serialize JSON files.
 //!
+//! Two layouts are supported, see [JsonFormat]: newline delimited JSON with an object per line
+//! (the default) and a single JSON array of objects.
+//!
 //! ## Write a DataFrame to a JSON file
 //!
 //! ## Example
@@ -11,7 +14,7 @@
 //!
 //! fn example(df: &mut DataFrame) -> Result<()> {
 //!     let mut file = File::create("example.csv").expect("could not create file");
-//!     
+//!
 //!     JsonWriter::new(&mut file)
 //!     .finish(df)
 //! }
@@ -77,19 +80,62 @@
 //! +-----+--------+-------+--------+
 //! ```
 //!
+//! ## Read a JSON array of objects
+//!
+//! The fields of nested objects become columns named `"<field>.<nested field>"`.
+//!
+//! ```
+//! use polars_core::prelude::*;
+//! use polars_io::prelude::*;
+//! use std::io::Cursor;
+//!
+//! let json = r#"[
+//!     {"user": {"id": 1, "name": "ann"}, "tags": ["a", "b"]},
+//!     {"user": {"id": 2}, "score": 2.5}
+//! ]"#;
+//! let df = JsonReader::new(Cursor::new(json))
+//!     .with_json_format(JsonFormat::Json)
+//!     .finish()
+//!     .unwrap();
+//! assert_eq!(df.get_column_names(), &["user.id", "user.name", "tags", "score"]);
+//! ```
 use crate::finish_reader;
 use crate::prelude::*;
 use crate::utils::to_arrow_compatible_df;
 pub use arrow::json::LineDelimitedWriter;
 pub use arrow::json::ReaderBuilder;
+use polars_core::chunked_array::builder::get_list_builder;
 use polars_core::prelude::*;
-use std::io::{Read, Seek, Write};
+use serde::de::{Deserializer, Error as _, SeqAccess, Visitor};
+use serde_json::{Map, Value};
+use std::fmt;
+use std::io::{BufReader, BufWriter, Read, Seek, Write};
 use std::sync::Arc;
 
+/// The layout of a JSON document.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum JsonFormat {
+    /// A single JSON array, every element is an object that is read as a row.
+    Json,
+    /// Newline delimited JSON, every line is an object that is read as a row.
+    JsonLines,
+}
+
+/// How [JsonReader] reads values that are nested deeper than the single level of objects it
+/// flattens into columns, in the [Json](JsonFormat::Json) format.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NestedFields {
+    /// Store the value as a JSON string in a Utf8 column.
+    AsString,
+    /// Return an error.
+    Error,
+}
+
 // Write a DataFrame to JSON
 pub struct JsonWriter<'a, W: Write> {
     /// File or Stream handler
     buffer: &'a mut W,
+    json_format: JsonFormat,
 }
 
 impl<'a, W> SerWriter<'a, W> for JsonWriter<'a, W>
@@ -97,21 +143,122 @@ where
     W: Write,
 {
     fn new(buffer: &'a mut W) -> Self {
-        JsonWriter { buffer }
+        JsonWriter {
+            buffer,
+            json_format: JsonFormat::JsonLines,
+        }
     }
 
     fn finish(self, df: &DataFrame) -> Result<()> {
-        let df = to_arrow_compatible_df(df);
-        let mut json_writer = LineDelimitedWriter::new(self.buffer);
+        match self.json_format {
+            JsonFormat::JsonLines => {
+                let df = to_arrow_compatible_df(df);
+                let mut json_writer = LineDelimitedWriter::new(self.buffer);
 
-        let batches = df.as_record_batches()?;
-        json_writer.write_batches(&batches)?;
-        json_writer.finish()?;
+                let batches = df.as_record_batches()?;
+                json_writer.write_batches(&batches)?;
+                json_writer.finish()?;
+            }
+            JsonFormat::Json => {
+                let mut df = to_arrow_compatible_df(df);
+                df.rechunk();
+                let mut writer = BufWriter::new(self.buffer);
 
+                writer.write_all(b"[")?;
+                for idx in 0..df.height() {
+                    if idx > 0 {
+                        writer.write_all(b",")?;
+                    }
+                    writer.write_all(b"{")?;
+                    for (i, s) in df.get_columns().iter().enumerate() {
+                        if i > 0 {
+                            writer.write_all(b",")?;
+                        }
+                        serde_json::to_writer(&mut writer, s.name()).map_err(to_compute_err)?;
+                        writer.write_all(b":")?;
+                        let value = any_value_to_json(s.get(idx), s.dtype())?;
+                        serde_json::to_writer(&mut writer, &value).map_err(to_compute_err)?;
+                    }
+                    writer.write_all(b"}")?;
+                }
+                writer.write_all(b"]")?;
+                writer.flush()?;
+            }
+        }
         Ok(())
     }
 }
 
+impl<'a, W> JsonWriter<'a, W>
+where
+    W: Write,
+{
+    /// Set the layout of the written JSON, newline delimited JSON by default.
+    pub fn with_json_format(mut self, json_format: JsonFormat) -> Self {
+        self.json_format = json_format;
+        self
+    }
+}
+
+fn to_compute_err(e: serde_json::Error) -> PolarsError {
+    PolarsError::ComputeError(format!("JSON error: {}", e).into())
+}
+
+/// Convert a value of a column to JSON. Temporal values are written as their integer
+/// representation and List values as JSON arrays.
+fn any_value_to_json(av: AnyValue, dtype: &DataType) -> Result<Value> {
+    let value = match av {
+        AnyValue::Null => Value::Null,
+        AnyValue::Boolean(v) => v.into(),
+        AnyValue::Utf8(v) => v.into(),
+        AnyValue::UInt8(v) => v.into(),
+        AnyValue::UInt16(v) => v.into(),
+        AnyValue::UInt32(v) => v.into(),
+        AnyValue::UInt64(v) => v.into(),
+        AnyValue::Int8(v) => v.into(),
+        AnyValue::Int16(v) => v.into(),
+        AnyValue::Int32(v) => v.into(),
+        AnyValue::Int64(v) => v.into(),
+        // non finite floats are written as null
+        AnyValue::Float32(v) => v.into(),
+        AnyValue::Float64(v) => v.into(),
+        AnyValue::Date32(v) => v.into(),
+        AnyValue::Date64(v) => v.into(),
+        AnyValue::Time64(v, _) => v.into(),
+        AnyValue::Duration(v, _) => v.into(),
+        AnyValue::List(s) => {
+            let dtype = s.dtype();
+            (0..s.len())
+                .map(|idx| any_value_to_json(s.get(idx), dtype))
+                .collect::<Result<Vec<_>>>()?
+                .into()
+        }
+        #[cfg(feature = "dtype-struct")]
+        AnyValue::Struct(values) => match dtype {
+            DataType::Struct(fields) => Value::Object(
+                fields
+                    .iter()
+                    .zip(values)
+                    .map(|(field, av)| {
+                        Ok((
+                            field.name().clone(),
+                            any_value_to_json(av, field.data_type())?,
+                        ))
+                    })
+                    .collect::<Result<_>>()?,
+            ),
+            _ => unreachable!(),
+        },
+        #[allow(unreachable_patterns)]
+        _ => {
+            return Err(PolarsError::InvalidOperation(
+                format!("cannot write values of dtype {:?} to JSON", dtype).into(),
+            ))
+        }
+    };
+    Ok(value)
+}
+
 pub struct JsonReader<R>
 where
     R: Read + Seek,
@@ -119,6 +266,11 @@ where
     reader: R,
     reader_builder: ReaderBuilder,
     rechunk: bool,
+    json_format: JsonFormat,
+    nested_fields: NestedFields,
+    schema: Option<Schema>,
+    infer_schema_len: Option<usize>,
+    projection: Option<Vec<String>>,
 }
 
 impl<R> SerReader<R> for JsonReader<R>
@@ -130,6 +282,11 @@ where
             reader,
             reader_builder: ReaderBuilder::new(),
             rechunk: true,
+            json_format: JsonFormat::JsonLines,
+            nested_fields: NestedFields::AsString,
+            schema: None,
+            infer_schema_len: None,
+            projection: None,
         }
     }
 
@@ -139,14 +296,32 @@ where
     }
 
     fn finish(self) -> Result<DataFrame> {
-        let rechunk = self.rechunk;
-        finish_reader(
-            self.reader_builder.build(self.reader)?,
-            rechunk,
-            None,
-            None,
-            None,
-        )
+        match self.json_format {
+            JsonFormat::JsonLines => {
+                let rechunk = self.rechunk;
+                finish_reader(
+                    self.reader_builder.build(self.reader)?,
+                    rechunk,
+                    None,
+                    None,
+                    None,
+                )
+            }
+            JsonFormat::Json => {
+                let mut columns = JsonColumns::new(self.nested_fields, self.infer_schema_len);
+                if let Some(projection) = &self.projection {
+                    columns = columns.with_projection(projection);
+                }
+                let mut df = read_json_array(self.reader, columns)?;
+                if let Some(schema) = &self.schema {
+                    df = apply_schema(df, schema)?;
+                }
+                if let Some(projection) = &self.projection {
+                    df = df.select(projection)?;
+                }
+                Ok(df)
+            }
+        }
     }
 }
 
@@ -157,32 +332,403 @@ where
     /// Set the JSON file's schema
     pub fn with_schema(mut self, schema: &Schema) -> Self {
         self.reader_builder = self.reader_builder.with_schema(Arc::new(schema.to_arrow()));
+        self.schema = Some(schema.clone());
         self
     }
 
-    /// Set the JSON reader to infer the schema of the file
+    /// Set the JSON reader to infer the schema of the file from the first `max_records` rows,
+    /// or from all rows if `None`.
+    ///
+    /// In the [Json](JsonFormat::Json) format, values after the first `max_records` rows that
+    /// don't fit the inferred type of their column are read as null.
     pub fn infer_schema(mut self, max_records: Option<usize>) -> Self {
         self.reader_builder = self.reader_builder.infer_schema(max_records);
+        self.infer_schema_len = max_records;
         self
     }
 
     /// Set the batch size (number of records to load at one time)
     /// This heavily influences loading time.
+    ///
+    /// Not used in the [Json](JsonFormat::Json) format, where the array is read in one pass.
     pub fn with_batch_size(mut self, batch_size: usize) -> Self {
         self.reader_builder = self.reader_builder.with_batch_size(batch_size);
         self
     }
 
-    /// Set the reader's column projection
+    /// Set the reader's column projection. In the [Json](JsonFormat::Json) format, the names
+    /// of flattened fields are `"<key>.<field>"`.
     pub fn with_projection(mut self, projection: Vec<String>) -> Self {
-        self.reader_builder = self.reader_builder.with_projection(projection);
+        self.reader_builder = self.reader_builder.with_projection(projection.clone());
+        self.projection = Some(projection);
         self
     }
+
+    /// Set the layout of the JSON document, newline delimited JSON by default.
+    pub fn with_json_format(mut self, json_format: JsonFormat) -> Self {
+        self.json_format = json_format;
+        self
+    }
+
+    /// Set how values nested deeper than a single level of objects are read, as JSON strings by
+    /// default. Only used in the [Json](JsonFormat::Json) format.
+    pub fn with_nested_fields(mut self, nested_fields: NestedFields) -> Self {
+        self.nested_fields = nested_fields;
+        self
+    }
+}
+
+/// A parsed JSON value of a column.
+enum Cell {
+    Null,
+    Boolean(bool),
+    Int64(i64),
+    Float64(f64),
+    Utf8(String),
+    /// An array of scalar values.
+    List(Vec<Cell>),
+}
+
+/// The inferred type of the scalar values of a column.
+#[derive(Copy, Clone, PartialEq)]
+enum ScalarType {
+    Boolean,
+    Int64,
+    Float64,
+    Utf8,
+}
+
+impl ScalarType {
+    /// Integers and floats resolve to Float64, other mixed types to Utf8.
+    fn merge(self, other: Self) -> Self {
+        use ScalarType::*;
+        match (self, other) {
+            (l, r) if l == r => l,
+            (Int64, Float64) | (Float64, Int64) => Float64,
+            _ => Utf8,
+        }
+    }
+
+    fn to_dtype(self) -> DataType {
+        match self {
+            ScalarType::Boolean => DataType::Boolean,
+            ScalarType::Int64 => DataType::Int64,
+            ScalarType::Float64 => DataType::Float64,
+            ScalarType::Utf8 => DataType::Utf8,
+        }
+    }
+}
+
+/// The inferred type of a column, a column with both arrays and scalar values is Utf8.
+#[derive(Copy, Clone, PartialEq)]
+enum CellType {
+    Scalar(ScalarType),
+    /// The type of the values of the arrays, `None` if all arrays are empty or only have nulls.
+    List(Option<ScalarType>),
+}
+
+fn merge_option(l: Option<ScalarType>, r: Option<ScalarType>) -> Option<ScalarType> {
+    match (l, r) {
+        (Some(l), Some(r)) => Some(l.merge(r)),
+        (l, None) => l,
+        (None, r) => r,
+    }
+}
+
+impl CellType {
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (CellType::Scalar(l), CellType::Scalar(r)) => CellType::Scalar(l.merge(r)),
+            (CellType::List(l), CellType::List(r)) => CellType::List(merge_option(l, r)),
+            _ => CellType::Scalar(ScalarType::Utf8),
+        }
+    }
+}
+
+impl Cell {
+    fn scalar_type(&self) -> Option<ScalarType> {
+        match self {
+            Cell::Null | Cell::List(_) => None,
+            Cell::Boolean(_) => Some(ScalarType::Boolean),
+            Cell::Int64(_) => Some(ScalarType::Int64),
+            Cell::Float64(_) => Some(ScalarType::Float64),
+            Cell::Utf8(_) => Some(ScalarType::Utf8),
+        }
+    }
+
+    fn cell_type(&self) -> Option<CellType> {
+        match self {
+            Cell::List(values) => Some(CellType::List(
+                values
+                    .iter()
+                    .map(|v| v.scalar_type())
+                    .fold(None, merge_option),
+            )),
+            cell => cell.scalar_type().map(CellType::Scalar),
+        }
+    }
+
+    fn from_scalar(value: Value) -> Self {
+        match value {
+            Value::Bool(v) => Cell::Boolean(v),
+            // integers that don't fit an i64 are read as floats
+            Value::Number(v) => match v.as_i64() {
+                Some(v) => Cell::Int64(v),
+                None => Cell::Float64(v.as_f64().unwrap_or(f64::NAN)),
+            },
+            Value::String(v) => Cell::Utf8(v),
+            _ => Cell::Null,
+        }
+    }
+
+    /// The value as a string, arrays are formatted as JSON.
+    fn into_string(self) -> Option<String> {
+        match self {
+            Cell::Null => None,
+            Cell::Boolean(v) => Some(v.to_string()),
+            Cell::Int64(v) => Some(v.to_string()),
+            Cell::Float64(v) => Some(Value::from(v).to_string()),
+            Cell::Utf8(v) => Some(v),
+            Cell::List(values) => Some(
+                Value::from(values.into_iter().map(Value::from).collect::<Vec<_>>()).to_string(),
+            ),
+        }
+    }
+}
+
+impl From<Cell> for Value {
+    fn from(cell: Cell) -> Self {
+        match cell {
+            Cell::Null => Value::Null,
+            Cell::Boolean(v) => v.into(),
+            Cell::Int64(v) => v.into(),
+            Cell::Float64(v) => v.into(),
+            Cell::Utf8(v) => v.into(),
+            Cell::List(values) => values.into_iter().map(Value::from).collect(),
+        }
+    }
+}
+
+fn is_scalar(value: &Value) -> bool {
+    !matches!(value, Value::Array(_) | Value::Object(_))
+}
+
+/// The columns of a JSON array of objects. Columns are added in the order in which their keys
+/// are first seen, missing keys are null.
+struct JsonColumns {
+    names: PlHashMap<String, usize>,
+    columns: Vec<(String, Vec<Cell>)>,
+    n_rows: usize,
+    nested_fields: NestedFields,
+    /// The number of rows the type of a column is inferred from, all rows if `None`.
+    infer_schema_len: Option<usize>,
+    /// The names of the columns to read, all columns if `None`.
+    projection: Option<PlHashSet<String>>,
+}
+
+impl JsonColumns {
+    fn new(nested_fields: NestedFields, infer_schema_len: Option<usize>) -> Self {
+        JsonColumns {
+            names: PlHashMap::default(),
+            columns: vec![],
+            n_rows: 0,
+            nested_fields,
+            infer_schema_len,
+            projection: None,
+        }
+    }
+
+    fn with_projection(mut self, projection: &[String]) -> Self {
+        self.projection = Some(projection.iter().cloned().collect());
+        self
+    }
+
+    fn to_cell(&self, name: &str, value: Value) -> Result<Cell> {
+        match value {
+            Value::Array(values) if values.iter().all(is_scalar) => Ok(Cell::List(
+                values.into_iter().map(Cell::from_scalar).collect(),
+            )),
+            Value::Array(_) | Value::Object(_) => match self.nested_fields {
+                NestedFields::AsString => Ok(Cell::Utf8(value.to_string())),
+                NestedFields::Error => Err(PolarsError::ComputeError(
+                    format!("JSON field '{}' is nested more than one level deep", name).into(),
+                )),
+            },
+            value => Ok(Cell::from_scalar(value)),
+        }
+    }
+
+    fn push(&mut self, name: String, value: Value) -> Result<()> {
+        if let Some(projection) = &self.projection {
+            if !projection.contains(&name) {
+                return Ok(());
+            }
+        }
+        let cell = self.to_cell(&name, value)?;
+        let n_rows = self.n_rows;
+        let columns = &mut self.columns;
+        let idx = *self.names.entry(name).or_insert_with_key(|name| {
+            let mut cells = Vec::with_capacity(n_rows + 1);
+            cells.resize_with(n_rows, || Cell::Null);
+            columns.push((name.clone(), cells));
+            columns.len() - 1
+        });
+        let cells = &mut columns[idx].1;
+        // a duplicate key in an object, the last value is kept
+        if cells.len() > n_rows {
+            cells[n_rows] = cell
+        } else {
+            cells.push(cell)
+        }
+        Ok(())
+    }
+
+    fn push_row(&mut self, row: Map<String, Value>) -> Result<()> {
+        for (key, value) in row {
+            match value {
+                Value::Object(fields) => {
+                    for (field, value) in fields {
+                        self.push(format!("{}.{}", key, field), value)?;
+                    }
+                }
+                value => self.push(key, value)?,
+            }
+        }
+        self.n_rows += 1;
+        for (_, cells) in self.columns.iter_mut() {
+            if cells.len() < self.n_rows {
+                cells.push(Cell::Null)
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<DataFrame> {
+        let infer_schema_len = self.infer_schema_len.unwrap_or(usize::MAX);
+        let columns = self
+            .columns
+            .into_iter()
+            .map(|(name, cells)| {
+                let cell_type = cells
+                    .iter()
+                    .take(infer_schema_len)
+                    .filter_map(|cell| cell.cell_type())
+                    .fold(None, |acc: Option<CellType>, tp| {
+                        Some(acc.map_or(tp, |acc| acc.merge(tp)))
+                    })
+                    // a column without values is read as Utf8
+                    .unwrap_or(CellType::Scalar(ScalarType::Utf8));
+                let mut s = cells_to_series(cells, cell_type);
+                s.rename(&name);
+                s
+            })
+            .collect();
+        DataFrame::new(columns)
+    }
+}
+
+fn cells_to_series(cells: Vec<Cell>, cell_type: CellType) -> Series {
+    match cell_type {
+        CellType::Scalar(ScalarType::Boolean) => cells
+            .into_iter()
+            .map(|cell| match cell {
+                Cell::Boolean(v) => Some(v),
+                _ => None,
+            })
+            .collect::<BooleanChunked>()
+            .into_series(),
+        CellType::Scalar(ScalarType::Int64) => cells
+            .into_iter()
+            .map(|cell| match cell {
+                Cell::Int64(v) => Some(v),
+                _ => None,
+            })
+            .collect::<Int64Chunked>()
+            .into_series(),
+        CellType::Scalar(ScalarType::Float64) => cells
+            .into_iter()
+            .map(|cell| match cell {
+                Cell::Int64(v) => Some(v as f64),
+                Cell::Float64(v) => Some(v),
+                _ => None,
+            })
+            .collect::<Float64Chunked>()
+            .into_series(),
+        CellType::Scalar(ScalarType::Utf8) => cells
+            .into_iter()
+            .map(Cell::into_string)
+            .collect::<Utf8Chunked>()
+            .into_series(),
+        CellType::List(inner) => {
+            let inner = CellType::Scalar(inner.unwrap_or(ScalarType::Utf8));
+            let mut builder =
+                get_list_builder(&inner_dtype(inner), cells.len() * 5, cells.len(), "");
+            for cell in cells {
+                match cell {
+                    Cell::List(values) => builder.append_series(&cells_to_series(values, inner)),
+                    _ => builder.append_opt_series(None),
+                }
+            }
+            builder.finish().into_series()
+        }
+    }
+}
+
+fn inner_dtype(cell_type: CellType) -> DataType {
+    match cell_type {
+        CellType::Scalar(tp) => tp.to_dtype(),
+        CellType::List(_) => unreachable!(),
+    }
+}
+
+/// Visits the elements of the JSON array one object at a time, without collecting the array.
+struct RowVisitor<'a>(&'a mut JsonColumns);
+
+impl<'de, 'a> Visitor<'de> for RowVisitor<'a> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an array of JSON objects")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> std::result::Result<(), A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        while let Some(row) = seq.next_element::<Map<String, Value>>()? {
+            self.0.push_row(row).map_err(A::Error::custom)?;
+        }
+        Ok(())
+    }
+}
+
+fn read_json_array<R: Read>(reader: R, mut columns: JsonColumns) -> Result<DataFrame> {
+    let mut de = serde_json::Deserializer::from_reader(BufReader::new(reader));
+    (&mut de)
+        .deserialize_seq(RowVisitor(&mut columns))
+        .map_err(to_compute_err)?;
+    de.end().map_err(to_compute_err)?;
+    columns.finish()
+}
+
+/// Cast the columns to the data types of the schema and add the missing columns as nulls.
+fn apply_schema(df: DataFrame, schema: &Schema) -> Result<DataFrame> {
+    let height = df.height();
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|field| match df.column(field.name()) {
+            Ok(s) => s.cast_with_dtype(field.data_type()),
+            Err(_) => Ok(Series::full_null(field.name(), height, field.data_type())),
+        })
+        .collect::<Result<_>>()?;
+    DataFrame::new(columns)
 }
 
 #[cfg(test)]
 mod test {
     use crate::prelude::*;
+    use polars_core::{df, prelude::*};
     use std::io::Cursor;
 
     #[test]
@@ -230,4 +776,135 @@ mod test {
         assert_eq!("d", df.get_columns()[3].name());
         assert_eq!((12, 4), df.shape());
     }
+
+    #[test]
+    fn read_json_array() -> Result<()> {
+        let json = r#"[
+            {"id": 1, "user": {"name": "ann", "address": {"city": "a"}}, "score": 1, "tags": [1, 2]},
+            {"id": 2, "user": {"name": "bob"}, "score": 2.5, "flag": true},
+            {"id": 3, "score": null, "flag": "no", "tags": [], "user": {"name": null}}
+        ]"#;
+        let df = JsonReader::new(Cursor::new(json))
+            .with_json_format(JsonFormat::Json)
+            .finish()?;
+        assert_eq!(
+            df.get_column_names(),
+            &["id", "user.name", "user.address", "score", "tags", "flag"]
+        );
+        assert_eq!(
+            Vec::from(df.column("id")?.i64()?),
+            &[Some(1), Some(2), Some(3)]
+        );
+        assert_eq!(
+            Vec::from(df.column("user.name")?.utf8()?),
+            &[Some("ann"), Some("bob"), None]
+        );
+        // deeper nesting is stored as a JSON string
+        assert_eq!(
+            Vec::from(df.column("user.address")?.utf8()?),
+            &[Some(r#"{"city":"a"}"#), None, None]
+        );
+        // integers and floats resolve to floats, other mixed types to strings
+        assert_eq!(
+            Vec::from(df.column("score")?.f64()?),
+            &[Some(1.0), Some(2.5), None]
+        );
+        assert_eq!(
+            Vec::from(df.column("flag")?.utf8()?),
+            &[None, Some("true"), Some("no")]
+        );
+        let tags = df.column("tags")?.list()?;
+        assert_eq!(Vec::from(tags.get(0).unwrap().i64()?), &[Some(1), Some(2)]);
+        assert!(tags.get(1).is_none());
+        assert_eq!(tags.get(2).unwrap().len(), 0);
+
+        let out = JsonReader::new(Cursor::new(json))
+            .with_json_format(JsonFormat::Json)
+            .with_nested_fields(NestedFields::Error)
+            .finish();
+        assert!(out.is_err());
+
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int32),
+            Field::new("missing", DataType::Float64),
+        ]);
+        let df = JsonReader::new(Cursor::new(json))
+            .with_json_format(JsonFormat::Json)
+            .with_schema(&schema)
+            .finish()?;
+        assert_eq!(df.schema(), schema);
+        assert_eq!(df.column("missing")?.null_count(), 3);
+
+        let out = JsonReader::new(Cursor::new(r#"[{"a": 1}, 2]"#))
+            .with_json_format(JsonFormat::Json)
+            .finish();
+        assert!(out.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn json_array_round_trip() -> Result<()> {
+        let mut df = df![
+            "a" => [Some(1i64), None, Some(3)],
+            "b" => [Some("x"), Some("y"), None],
+            "c" => [0.5, 1.0, -2.0]
+        ]?;
+        let list = vec![
+            Series::new("", &[1i64, 2]),
+            Series::new("", &[3i64]),
+            Series::new("", &[4i64, 5, 6]),
+        ]
+        .into_iter()
+        .collect::<ListChunked>();
+        let mut d = list.into_series();
+        d.rename("d");
+        df.with_column(d)?;
+
+        let mut buf: Vec<u8> = Vec::new();
+        JsonWriter::new(&mut buf)
+            .with_json_format(JsonFormat::Json)
+            .finish(&df)?;
+        assert_eq!(
+            std::str::from_utf8(&buf).unwrap(),
+            r#"[{"a":1,"b":"x","c":0.5,"d":[1,2]},{"a":null,"b":"y","c":1.0,"d":[3]},{"a":3,"b":null,"c":-2.0,"d":[4,5,6]}]"#
+        );
+
+        let out = JsonReader::new(Cursor::new(buf))
+            .with_json_format(JsonFormat::Json)
+            .finish()?;
+        assert_eq!(out.schema(), df.schema());
+        assert!(out
+            .select(&["a", "b", "c"])?
+            .frame_equal_missing(&df.select(&["a", "b", "c"])?));
+        let d = out.column("d")?.list()?;
+        assert!(d
+            .get(2)
+            .unwrap()
+            .series_equal(&Series::new("", &[4i64, 5, 6])));
+        Ok(())
+    }
+
+    #[test]
+    fn json_array_projection_and_infer_len() -> Result<()> {
+        let json = r#"[
+            {"a": 1, "b": {"c": "x", "d": 1}},
+            {"a": 2, "b": {"c": "y", "d": [1, {"e": 2}]}},
+            {"a": 2.5, "b": {"c": "z"}}
+        ]"#;
+        // fields that are not projected are skipped, so they can't fail the read
+        let df = JsonReader::new(Cursor::new(json))
+            .with_json_format(JsonFormat::Json)
+            .with_nested_fields(NestedFields::Error)
+            .with_projection(vec!["b.c".into(), "a".into()])
+            .finish()?;
+        assert_eq!(df.get_column_names(), &["b.c", "a"]);
+        assert_eq!(df.column("a")?.dtype(), &DataType::Float64);
+
+        let df = JsonReader::new(Cursor::new(json))
+            .with_json_format(JsonFormat::Json)
+            .infer_schema(Some(2))
+            .finish()?;
+        assert_eq!(Vec::from(df.column("a")?.i64()?), &[Some(1), Some(2), None]);
+        Ok(())
+    }
 }
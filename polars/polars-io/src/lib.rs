@@ -17,19 +17,38 @@ pub mod mmap;
 #[cfg(feature = "parquet")]
 #[cfg_attr(docsrs, doc(cfg(feature = "feature")))]
 pub mod parquet;
+#[cfg(any(feature = "csv-file", feature = "parquet"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "csv-file", feature = "parquet"))))]
+pub mod partition;
 pub mod prelude;
+pub mod temp_dir;
 pub(crate) mod utils;
+pub use utils::resolve_homedir;
 
 use arrow::{
     error::Result as ArrowResult, json::Reader as ArrowJsonReader, record_batch::RecordBatch,
 };
 
+use polars_core::chunked_array::ChunkMinMax;
 use polars_core::prelude::*;
 use std::io::{Read, Seek, Write};
 use std::sync::Arc;
 
+/// The statistics of a column in a batch of a file, e.g. a parquet row group.
+pub struct ColumnStats {
+    pub dtype: DataType,
+    /// The minimum and maximum of the physical values, `None` if all values are null.
+    pub min_max: ChunkMinMax,
+}
+
 pub trait PhysicalIoExpr: Send + Sync {
     fn evaluate(&self, df: &DataFrame) -> Result<Series>;
+
+    /// Check if no row of a batch can match the predicate, based on the statistics of the
+    /// columns of that batch. `stats` returns `None` for a column without statistics.
+    fn skip_batch(&self, _stats: &dyn Fn(&str) -> Option<ColumnStats>) -> bool {
+        false
+    }
 }
 
 pub trait SerReader<R>
@@ -39,6 +58,8 @@ where
     fn new(reader: R) -> Self;
 
     /// Rechunk to a single chunk after Reading file.
+    ///
+    /// The csv, parquet and ipc readers rechunk by default.
     fn set_rechunk(self, _rechunk: bool) -> Self
     where
         Self: std::marker::Sized,
@@ -74,6 +95,14 @@ impl<R: Read> ArrowReader for ArrowJsonReader<R> {
     }
 }
 
+/// Get the mask of a filter predicate, which should be of dtype Boolean.
+pub fn predicate_to_mask(s: &Series) -> Result<&BooleanChunked> {
+    s.bool().map_err(|_| PolarsError::SchemaMisMatch {
+        expected: "filter predicate of dtype Boolean".into(),
+        found: format!("{:?}", s.dtype()).into(),
+    })
+}
+
 #[cfg(any(feature = "ipc", feature = "parquet", feature = "json"))]
 pub(crate) fn finish_reader<R: ArrowReader>(
     mut reader: R,
@@ -95,7 +124,7 @@ pub(crate) fn finish_reader<R: ArrowReader>(
 
         if let Some(predicate) = &predicate {
             let s = predicate.evaluate(&df)?;
-            let mask = s.bool().expect("filter predicates was not of type boolean");
+            let mask = predicate_to_mask(&s)?;
             df = df.filter(mask)?;
         }
 
@@ -118,7 +147,12 @@ pub(crate) fn finish_reader<R: ArrowReader>(
             }
         }
     }
-    let mut df = accumulate_dataframes_vertical(parsed_dfs)?;
+    // a file without record batches should still return a DataFrame with the file's schema
+    let mut df = if parsed_dfs.is_empty() {
+        DataFrame::new_empty(&reader.schema())
+    } else {
+        accumulate_dataframes_vertical(parsed_dfs)?
+    };
 
     if let Some(aggregate) = aggregate {
         let cols = aggregate
@@ -134,7 +168,29 @@ pub(crate) fn finish_reader<R: ArrowReader>(
     }
 }
 
+/// Add a `UInt32` column with the row index as first column of a read `DataFrame`. The rows are
+/// counted after a predicate is applied, so the index has no gaps.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RowCount {
+    /// Name of the row count column.
+    pub name: String,
+    /// Index of the first row.
+    pub offset: u32,
+}
+
+/// Add the row count column to the `DataFrame` of a reader.
+#[cfg(any(feature = "csv-file", feature = "parquet"))]
+pub fn apply_row_count(df: DataFrame, row_count: Option<&RowCount>) -> Result<DataFrame> {
+    match row_count {
+        Some(rc) => df.with_row_count(&rc.name, Some(rc.offset)),
+        None => Ok(df),
+    }
+}
+
+#[derive(Debug, Hash)]
 pub enum ScanAggregation {
+    /// The sum of a column. Integers smaller than 32 bits are summed as 64 bit integers and `Float32`
+    /// as `Float64`, as in the aggregations of a `DataFrame`.
     Sum {
         column: String,
         alias: Option<String>,
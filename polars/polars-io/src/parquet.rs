@@ -17,18 +17,51 @@
 use super::{finish_reader, ArrowReader, ArrowResult, RecordBatch};
 use crate::prelude::*;
 use crate::utils::to_arrow_compatible_df;
-use crate::{PhysicalIoExpr, ScanAggregation};
-use arrow::{compute::cast, record_batch::RecordBatchReader};
+#[cfg(feature = "lazy")]
+use crate::utils::to_projection_order;
+#[cfg(feature = "lazy")]
+use crate::ColumnStats;
+use crate::{apply_row_count, PhysicalIoExpr, ScanAggregation};
+use arrow::{
+    array::{make_array, Array, ArrayRef, UInt32Array},
+    compute::{cast, take},
+    record_batch::RecordBatchReader,
+};
 use parquet_lib::file::reader::{FileReader, SerializedFileReader};
 pub use parquet_lib::file::serialized_reader::SliceableCursor;
 use parquet_lib::{
+    arrow::arrow_to_parquet_schema,
     arrow::{
         arrow_reader::ParquetRecordBatchReader, arrow_writer::ArrowWriter as ParquetArrowWriter,
         ArrowReader as ParquetArrowReader, ParquetFileArrowReader,
     },
-    file::writer::TryClone,
+    basic::Compression,
+    column::writer::ColumnWriter,
+    file::{
+        properties::WriterProperties,
+        writer::{FileWriter, RowGroupWriter, SerializedFileWriter, TryClone},
+    },
+};
+#[cfg(feature = "lazy")]
+use parquet_lib::{
+    errors::{ParquetError, Result as ParquetResult},
+    file::{
+        metadata::{ParquetMetaData, RowGroupMetaData},
+        reader::{ChunkReader, RowGroupReader},
+        statistics::Statistics,
+    },
+    record::reader::RowIter,
+    schema::types::{SchemaDescriptor, Type as SchemaType},
 };
 use polars_core::prelude::*;
+#[cfg(feature = "lazy")]
+use polars_core::{
+    utils::accumulate_dataframes_vertical,
+    verbose::{log_verbose, verbose, VerboseEvent},
+    POOL,
+};
+#[cfg(feature = "lazy")]
+use rayon::prelude::*;
 use std::io::{Read, Seek, Write};
 use std::sync::Arc;
 
@@ -42,11 +75,436 @@ fn set_batch_size(max_rows: usize, stop_after_n_rows: Option<usize>) -> usize {
     batch_size
 }
 
+/// Depth of the List and Struct nesting of a column. The fields of a struct that is flattened
+/// don't add a level.
+fn nesting_depth(dtype: &ArrowDataType, flatten_structs: bool) -> usize {
+    match dtype {
+        ArrowDataType::List(f)
+        | ArrowDataType::LargeList(f)
+        | ArrowDataType::FixedSizeList(f, _) => 1 + nesting_depth(f.data_type(), false),
+        ArrowDataType::Struct(fields) => {
+            let depth = fields
+                .iter()
+                .map(|f| nesting_depth(f.data_type(), flatten_structs))
+                .max()
+                .unwrap_or(0);
+            if flatten_structs {
+                depth
+            } else {
+                depth + 1
+            }
+        }
+        _ => 0,
+    }
+}
+
+/// Errors if a column of the file can't be read into a polars type.
+fn check_nesting(schema: &ArrowSchema, flatten_structs: bool) -> Result<()> {
+    for field in schema.fields() {
+        let depth = nesting_depth(field.data_type(), flatten_structs);
+        if depth > 1 {
+            return Err(PolarsError::InvalidOperation(
+                format!(
+                    "column '{}' has a nesting depth of {}, only a single level of List or Struct nesting can be read from parquet",
+                    field.name(),
+                    depth
+                )
+                .into(),
+            ));
+        }
+        if cfg!(not(feature = "dtype-struct"))
+            && !flatten_structs
+            && matches!(field.data_type(), ArrowDataType::Struct(_))
+        {
+            return Err(PolarsError::InvalidOperation(
+                format!(
+                    "column '{}' is a struct, activate the 'dtype-struct' feature or read it with ParquetReader::with_flatten_structs",
+                    field.name()
+                )
+                .into(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Add the field, or the fields of a struct named `<name>.<field>`, to `fields`.
+fn flatten_struct_field(name: &str, field: &ArrowField, fields: &mut Vec<ArrowField>) {
+    match field.data_type() {
+        ArrowDataType::Struct(children) => {
+            for child in children {
+                flatten_struct_field(&format!("{}.{}", name, child.name()), child, fields)
+            }
+        }
+        dtype => fields.push(ArrowField::new(name, dtype.clone(), true)),
+    }
+}
+
+/// Add the array, or the field arrays of a struct array, to `arrays`.
+fn flatten_struct_array(array: &ArrayRef, arrays: &mut Vec<ArrayRef>) -> ArrowResult<()> {
+    match array.data_type() {
+        ArrowDataType::Struct(_) => {
+            // a null struct is a null in every field
+            let indices = if array.null_count() > 0 {
+                Some(
+                    (0..array.len())
+                        .map(|i| {
+                            if array.is_valid(i) {
+                                Some(i as u32)
+                            } else {
+                                None
+                            }
+                        })
+                        .collect::<UInt32Array>(),
+                )
+            } else {
+                None
+            };
+            for child in array.data().child_data() {
+                // the children are not sliced with the struct array, so we apply its offset
+                let mut child = make_array(child.clone()).slice(array.offset(), array.len());
+                if let Some(indices) = &indices {
+                    child = take(child.as_ref(), indices, None)?;
+                }
+                flatten_struct_array(&child, arrays)?;
+            }
+        }
+        _ => arrays.push(array.clone()),
+    }
+    Ok(())
+}
+
+fn flatten_struct_schema(schema: &ArrowSchema) -> ArrowSchema {
+    let mut fields = Vec::with_capacity(schema.fields().len());
+    for field in schema.fields() {
+        flatten_struct_field(field.name(), field, &mut fields)
+    }
+    ArrowSchema::new(fields)
+}
+
+/// Reads the fields of struct columns as top-level columns named `<column>.<field>`.
+struct FlattenStructs {
+    reader: ParquetRecordBatchReader,
+    schema: Arc<ArrowSchema>,
+}
+
+impl FlattenStructs {
+    fn new(reader: ParquetRecordBatchReader) -> Self {
+        let schema = flatten_struct_schema(&RecordBatchReader::schema(&reader));
+        FlattenStructs {
+            reader,
+            schema: Arc::new(schema),
+        }
+    }
+}
+
+impl ArrowReader for FlattenStructs {
+    fn next_record_batch(&mut self) -> ArrowResult<Option<RecordBatch>> {
+        let batch = match self.reader.next_record_batch()? {
+            Some(batch) => batch,
+            None => return Ok(None),
+        };
+        let mut columns = Vec::with_capacity(self.schema.fields().len());
+        for column in batch.columns() {
+            flatten_struct_array(column, &mut columns)?;
+        }
+        RecordBatch::try_new(self.schema.clone(), columns).map(Some)
+    }
+
+    fn schema(&self) -> Arc<Schema> {
+        Arc::new((&*self.schema).into())
+    }
+}
+
+fn finish_parquet_reader(
+    reader: ParquetRecordBatchReader,
+    flatten_structs: bool,
+    rechunk: bool,
+    stop_after_n_rows: Option<usize>,
+    predicate: Option<Arc<dyn PhysicalIoExpr>>,
+    aggregate: Option<&[ScanAggregation]>,
+) -> Result<DataFrame> {
+    if flatten_structs {
+        finish_reader(
+            FlattenStructs::new(reader),
+            rechunk,
+            stop_after_n_rows,
+            predicate,
+            aggregate,
+        )
+    } else {
+        finish_reader(reader, rechunk, stop_after_n_rows, predicate, aggregate)
+    }
+}
+
+#[cfg(feature = "lazy")]
+/// Number of leaf columns of a node of the parquet schema.
+fn num_leaves(tp: &SchemaType) -> usize {
+    if tp.is_primitive() {
+        1
+    } else {
+        tp.get_fields().iter().map(|f| num_leaves(f)).sum()
+    }
+}
+
+#[cfg(feature = "lazy")]
+/// Map the indices of top-level columns to the indices of their leaf columns in the parquet
+/// schema, which is what the arrow reader projects on. A nested column has multiple leaves.
+fn projection_to_leaves(schema: &SchemaDescriptor, projection: &[usize]) -> Vec<usize> {
+    let mut offset = 0;
+    let leaves = schema
+        .root_schema()
+        .get_fields()
+        .iter()
+        .map(|field| {
+            let n = num_leaves(field);
+            offset += n;
+            offset - n..offset
+        })
+        .collect::<Vec<_>>();
+    projection.iter().flat_map(|i| leaves[*i].clone()).collect()
+}
+
+#[cfg(feature = "lazy")]
+/// A [FileReader] that exposes a single row group of a parquet file, such that the row groups of
+/// a file can be decoded independently of each other.
+struct RowGroupFileReader<R: ChunkReader> {
+    file_reader: Arc<SerializedFileReader<R>>,
+    row_group: usize,
+    metadata: ParquetMetaData,
+}
+
+#[cfg(feature = "lazy")]
+impl<R: 'static + ChunkReader> RowGroupFileReader<R> {
+    fn new(file_reader: Arc<SerializedFileReader<R>>, row_group: usize) -> Self {
+        let metadata = file_reader.metadata();
+        let metadata = ParquetMetaData::new(
+            metadata.file_metadata().clone(),
+            vec![metadata.row_group(row_group).clone()],
+        );
+        RowGroupFileReader {
+            file_reader,
+            row_group,
+            metadata,
+        }
+    }
+}
+
+#[cfg(feature = "lazy")]
+impl<R: 'static + ChunkReader> FileReader for RowGroupFileReader<R> {
+    fn metadata(&self) -> &ParquetMetaData {
+        &self.metadata
+    }
+
+    fn num_row_groups(&self) -> usize {
+        1
+    }
+
+    fn get_row_group(&self, i: usize) -> ParquetResult<Box<dyn RowGroupReader + '_>> {
+        if i != 0 {
+            return Err(ParquetError::General(format!(
+                "row group index {} out of bounds for a single row group",
+                i
+            )));
+        }
+        self.file_reader.get_row_group(self.row_group)
+    }
+
+    fn get_row_iter(&self, projection: Option<SchemaType>) -> ParquetResult<RowIter> {
+        RowIter::from_file(projection, self)
+    }
+}
+
+#[cfg(feature = "lazy")]
+/// Get the statistics of the column `name` of a row group. The fields of a flattened struct are
+/// found by their `<column>.<field>` path.
+fn row_group_column_stats(
+    row_group: &RowGroupMetaData,
+    schema: &ArrowSchema,
+    name: &str,
+) -> Option<ColumnStats> {
+    let dtype: DataType = schema.field_with_name(name).ok()?.data_type().into();
+    // unsigned 32 and 64 bit integers are stored as signed integers, their statistics are not
+    // ordered by the unsigned value
+    if matches!(dtype, DataType::UInt32 | DataType::UInt64) {
+        return None;
+    }
+    let column = row_group
+        .columns()
+        .iter()
+        .find(|column| column.column_path().string() == name)?;
+    let stats = column.statistics()?;
+    if stats.null_count() == row_group.num_rows() as u64 {
+        return Some(ColumnStats {
+            dtype,
+            min_max: None,
+        });
+    }
+    if !stats.has_min_max_set() {
+        return None;
+    }
+    let min_max = match stats {
+        Statistics::Int32(stats) => (*stats.min() as f64, *stats.max() as f64),
+        Statistics::Int64(stats) => (*stats.min() as f64, *stats.max() as f64),
+        Statistics::Float(stats) => (*stats.min() as f64, *stats.max() as f64),
+        Statistics::Double(stats) => (*stats.min(), *stats.max()),
+        _ => return None,
+    };
+    Some(ColumnStats {
+        dtype,
+        min_max: Some(min_max),
+    })
+}
+
+#[cfg(feature = "lazy")]
+/// Get the row groups that need to be read and the number of rows to read from every row group.
+/// The row groups of which the statistics show that no row matches the `predicate` are skipped,
+/// the number of skipped row groups is returned as well.
+///
+/// The rows of skipped row groups count towards `stop_after_n_rows`, as the limit applies to the
+/// rows of the file before they are filtered.
+fn row_groups_to_read<R: 'static + ChunkReader>(
+    file_reader: &SerializedFileReader<R>,
+    schema: &ArrowSchema,
+    stop_after_n_rows: Option<usize>,
+    predicate: Option<&dyn PhysicalIoExpr>,
+) -> (Vec<(usize, usize)>, usize) {
+    let mut row_groups = Vec::with_capacity(file_reader.num_row_groups());
+    let mut n_rows = 0;
+    let mut skipped = 0;
+    for (i, row_group) in file_reader.metadata().row_groups().iter().enumerate() {
+        let mut rows = row_group.num_rows() as usize;
+        if let Some(n) = stop_after_n_rows {
+            if n_rows >= n {
+                break;
+            }
+            rows = std::cmp::min(rows, n - n_rows);
+        }
+        n_rows += rows;
+        if let Some(predicate) = predicate {
+            if predicate.skip_batch(&|name| row_group_column_stats(row_group, schema, name)) {
+                skipped += 1;
+                continue;
+            }
+        }
+        if rows > 0 {
+            row_groups.push((i, rows));
+        }
+    }
+    (row_groups, skipped)
+}
+
+#[cfg(feature = "lazy")]
+/// The output of a scan of which all row groups are skipped: the projected columns without rows,
+/// or the aggregations of those.
+fn empty_scan(
+    schema: &ArrowSchema,
+    projection: Option<&[usize]>,
+    aggregate: Option<&[ScanAggregation]>,
+) -> Result<DataFrame> {
+    let mut df = DataFrame::new_empty(&schema.into());
+    if let Some(projection) = projection {
+        let columns = projection
+            .iter()
+            .map(|i| schema.field(*i).name().as_str())
+            .collect::<Vec<_>>();
+        df = df.select(columns)?;
+    }
+    match aggregate {
+        Some(aggregate) => {
+            let cols = aggregate
+                .iter()
+                .map(|scan_agg| scan_agg.finish(&df))
+                .collect::<Result<_>>()?;
+            Ok(DataFrame::new_no_checks(cols))
+        }
+        None => Ok(df),
+    }
+}
+
+#[cfg(feature = "lazy")]
+/// Decode the row groups in parallel on the thread pool. Every task decodes a single row group
+/// and applies the predicate and the aggregations to it. At most one row group per thread is
+/// decoded at a time, which bounds the memory of the decoded but not yet filtered row groups.
+///
+/// The DataFrames of the row groups are appended in the order of the file.
+fn read_row_groups_parallel<R>(
+    file_reader: Arc<SerializedFileReader<R>>,
+    row_groups: &[(usize, usize)],
+    rechunk: bool,
+    predicate: Option<Arc<dyn PhysicalIoExpr>>,
+    aggregate: Option<&[ScanAggregation]>,
+    projection: Option<&[usize]>,
+    flatten_structs: bool,
+) -> Result<DataFrame>
+where
+    R: 'static + ChunkReader + Send + Sync,
+{
+    let read_row_group = |&(row_group, n_rows): &(usize, usize)| {
+        let reader = RowGroupFileReader::new(file_reader.clone(), row_group);
+        let mut arrow_reader = ParquetFileArrowReader::new(Arc::new(reader));
+        let record_reader = match projection {
+            Some(projection) => {
+                arrow_reader.get_record_reader_by_columns(projection.iter().copied(), n_rows)
+            }
+            None => arrow_reader.get_record_reader(n_rows),
+        }?;
+        let df = finish_parquet_reader(
+            record_reader,
+            flatten_structs,
+            false,
+            Some(n_rows),
+            predicate.clone(),
+            None,
+        )?;
+
+        match aggregate {
+            Some(aggregate) => {
+                let cols = aggregate
+                    .iter()
+                    .map(|scan_agg| scan_agg.evaluate_batch(&df))
+                    .collect::<Result<_>>()?;
+                Ok(DataFrame::new_no_checks(cols))
+            }
+            None => Ok(df),
+        }
+    };
+
+    let n_threads = POOL.current_num_threads();
+    let mut parsed_dfs = Vec::with_capacity(row_groups.len());
+    for row_groups in row_groups.chunks(n_threads) {
+        let dfs = POOL.install(|| {
+            row_groups
+                .par_iter()
+                .map(read_row_group)
+                .collect::<Result<Vec<_>>>()
+        })?;
+        parsed_dfs.extend(dfs);
+    }
+    let mut df = accumulate_dataframes_vertical(parsed_dfs)?;
+
+    if let Some(aggregate) = aggregate {
+        let cols = aggregate
+            .iter()
+            .map(|scan_agg| scan_agg.finish(&df))
+            .collect::<Result<_>>()?;
+        df = DataFrame::new_no_checks(cols)
+    }
+
+    match rechunk {
+        true => Ok(df.agg_chunks()),
+        false => Ok(df),
+    }
+}
+
 /// Read Apache parquet format into a DataFrame.
 pub struct ParquetReader<R> {
     reader: R,
     rechunk: bool,
     stop_after_n_rows: Option<usize>,
+    parallel: bool,
+    flatten_structs: bool,
+    row_count: Option<RowCount>,
 }
 
 impl<R> ParquetReader<R>
@@ -55,15 +513,82 @@ where
 {
     #[cfg(feature = "lazy")]
     // todo! hoist to lazy crate
+    /// Read the file, applying the predicate, the aggregations and the projection during the
+    /// scan. Files with multiple row groups are decoded in parallel, one row group per task,
+    /// unless this is turned off with [ParquetReader::read_parallel].
+    ///
+    /// Without aggregations, the projected columns are returned in the order of `projection`.
     pub fn finish_with_scan_ops(
         mut self,
         predicate: Option<Arc<dyn PhysicalIoExpr>>,
         aggregate: Option<&[ScanAggregation]>,
         projection: Option<&[usize]>,
-    ) -> Result<DataFrame> {
+    ) -> Result<DataFrame>
+    where
+        R: Send + Sync,
+    {
         let rechunk = self.rechunk;
 
         let file_reader = Arc::new(SerializedFileReader::new(self.reader)?);
+        let schema = read_arrow_schema(file_reader.clone(), self.flatten_structs)?;
+        // the projected columns are read in the order of the file, we restore the requested order
+        let column_order = match (projection, aggregate) {
+            (Some(projection), None) if !self.flatten_structs => projection
+                .iter()
+                .map(|i| schema.fields().get(*i).map(|fld| fld.name().clone()))
+                .collect::<Option<Vec<_>>>(),
+            _ => None,
+        };
+        let row_count = self.row_count.clone();
+        let finish_df = |df: DataFrame| {
+            let df = match &column_order {
+                Some(columns) => to_projection_order(df, columns)?,
+                None => df,
+            };
+            apply_row_count(df, row_count.as_ref())
+        };
+        let column_projection = projection;
+        // the arrow reader projects on the leaf columns of the parquet schema
+        let projection = projection.map(|projection| {
+            projection_to_leaves(
+                file_reader.metadata().file_metadata().schema_descr(),
+                projection,
+            )
+        });
+        let projection = projection.as_deref();
+        if self.parallel || predicate.is_some() {
+            let (row_groups, skipped) = row_groups_to_read(
+                &file_reader,
+                &schema,
+                self.stop_after_n_rows,
+                predicate.as_deref(),
+            );
+            if skipped > 0 && verbose() {
+                log_verbose(VerboseEvent::new(
+                    "parquet scan",
+                    format_args!(
+                        "skipped {} of {} row groups based on their statistics",
+                        skipped,
+                        file_reader.num_row_groups()
+                    ),
+                ));
+            }
+            if row_groups.is_empty() && skipped > 0 {
+                return finish_df(empty_scan(&schema, column_projection, aggregate)?);
+            }
+            if (self.parallel && row_groups.len() > 1) || skipped > 0 {
+                let df = read_row_groups_parallel(
+                    file_reader,
+                    &row_groups,
+                    rechunk,
+                    predicate,
+                    aggregate,
+                    projection,
+                    self.flatten_structs,
+                )?;
+                return finish_df(df);
+            }
+        }
         let rows_in_file = file_reader.metadata().file_metadata().num_rows() as usize;
 
         if let Some(stop_after_n_rows) = self.stop_after_n_rows {
@@ -85,13 +610,15 @@ where
             }
             None => arrow_reader.get_record_reader(batch_size),
         }?;
-        finish_reader(
+        finish_parquet_reader(
             record_reader,
+            self.flatten_structs,
             rechunk,
             self.stop_after_n_rows,
             predicate,
             aggregate,
         )
+        .and_then(finish_df)
     }
 
     /// Stop parsing when `n` rows are parsed. By settings this parameter the csv will be parsed
@@ -101,11 +628,45 @@ where
         self
     }
 
+    /// Decode the row groups of the file in parallel. This is the default. The output is the same
+    /// as that of the sequential read.
+    pub fn read_parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    /// Read the fields of struct columns as separate columns named `<column>.<field>`, instead
+    /// of a single Struct column. A null struct is read as a null in every field.
+    pub fn with_flatten_structs(mut self, toggle: bool) -> Self {
+        self.flatten_structs = toggle;
+        self
+    }
+
+    /// Add a `UInt32` column with the row index as first column.
+    pub fn with_row_count(mut self, row_count: Option<RowCount>) -> Self {
+        self.row_count = row_count;
+        self
+    }
+
     pub fn schema(self) -> Result<Schema> {
         let file_reader = Arc::new(SerializedFileReader::new(self.reader)?);
-        let mut arrow_reader = ParquetFileArrowReader::new(file_reader);
-        let schema = arrow_reader.get_schema()?;
-        Ok(schema.into())
+        let schema = read_arrow_schema(file_reader, self.flatten_structs)?;
+        Ok((&schema).into())
+    }
+}
+
+/// Read the arrow schema of the file. Errors if a column is nested deeper than polars can read.
+fn read_arrow_schema<R: 'static + parquet_lib::file::reader::ChunkReader>(
+    file_reader: Arc<SerializedFileReader<R>>,
+    flatten_structs: bool,
+) -> Result<ArrowSchema> {
+    let mut arrow_reader = ParquetFileArrowReader::new(file_reader);
+    let schema = arrow_reader.get_schema()?;
+    check_nesting(&schema, flatten_structs)?;
+    if flatten_structs {
+        Ok(flatten_struct_schema(&schema))
+    } else {
+        Ok(schema)
     }
 }
 
@@ -128,8 +689,11 @@ where
     fn new(reader: R) -> Self {
         ParquetReader {
             reader,
-            rechunk: false,
+            rechunk: true,
             stop_after_n_rows: None,
+            parallel: true,
+            flatten_structs: false,
+            row_count: None,
         }
     }
 
@@ -141,11 +705,81 @@ where
     fn finish(self) -> Result<DataFrame> {
         let rechunk = self.rechunk;
         let file_reader = Arc::new(SerializedFileReader::new(self.reader)?);
+        read_arrow_schema(file_reader.clone(), self.flatten_structs)?;
         let n_rows = file_reader.metadata().file_metadata().num_rows() as usize;
         let batch_size = set_batch_size(n_rows, self.stop_after_n_rows);
         let mut arrow_reader = ParquetFileArrowReader::new(file_reader);
         let record_reader = arrow_reader.get_record_reader(batch_size)?;
-        finish_reader(record_reader, rechunk, self.stop_after_n_rows, None, None)
+        let df = finish_parquet_reader(
+            record_reader,
+            self.flatten_structs,
+            rechunk,
+            self.stop_after_n_rows,
+            None,
+            None,
+        )?;
+        apply_row_count(df, self.row_count.as_ref())
+    }
+}
+
+/// The compression codec used to write parquet files.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ParquetCompression {
+    Uncompressed,
+    Snappy,
+    Gzip,
+    Lz4,
+    Zstd,
+}
+
+impl From<ParquetCompression> for Compression {
+    fn from(compression: ParquetCompression) -> Self {
+        match compression {
+            ParquetCompression::Uncompressed => Compression::UNCOMPRESSED,
+            ParquetCompression::Snappy => Compression::SNAPPY,
+            ParquetCompression::Gzip => Compression::GZIP,
+            ParquetCompression::Lz4 => Compression::LZ4,
+            ParquetCompression::Zstd => Compression::ZSTD,
+        }
+    }
+}
+
+/// Options for writing parquet files.
+#[derive(Clone, Debug)]
+pub struct ParquetWriterOptions {
+    pub compression: ParquetCompression,
+    /// Maximum number of rows in a row group. Defaults to the parquet writer default.
+    pub row_group_size: Option<usize>,
+}
+
+impl Default for ParquetWriterOptions {
+    fn default() -> Self {
+        ParquetWriterOptions {
+            compression: ParquetCompression::Uncompressed,
+            row_group_size: None,
+        }
+    }
+}
+
+impl ParquetWriterOptions {
+    /// Set the compression codec.
+    pub fn with_compression(mut self, compression: ParquetCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Set the maximum number of rows in a row group.
+    pub fn with_row_group_size(mut self, row_group_size: Option<usize>) -> Self {
+        self.row_group_size = row_group_size;
+        self
+    }
+
+    fn writer_properties(&self) -> WriterProperties {
+        let mut builder = WriterProperties::builder().set_compression(self.compression.into());
+        if let Some(size) = self.row_group_size {
+            builder = builder.set_max_row_group_size(size);
+        }
+        builder.build()
     }
 }
 
@@ -156,6 +790,7 @@ where
 ///
 pub struct ParquetWriter<W> {
     writer: W,
+    options: ParquetWriterOptions,
 }
 
 impl<W> ParquetWriter<W>
@@ -167,63 +802,251 @@ where
     where
         W: 'static + Write + Seek + TryClone,
     {
-        ParquetWriter { writer }
+        ParquetWriter {
+            writer,
+            options: ParquetWriterOptions::default(),
+        }
+    }
+
+    /// Set the options of the written file.
+    pub fn with_options(mut self, options: ParquetWriterOptions) -> Self {
+        self.options = options;
+        self
     }
 
     /// Write the given DataFrame in the the writer `W`.
     pub fn finish(self, df: &DataFrame) -> Result<()> {
-        let df = to_arrow_compatible_df(df);
-        let mut fields = df.schema().to_arrow().fields().clone();
+        let mut writer = BatchedParquetWriter::new(self.writer, &df.schema(), &self.options)?;
+        writer.write_batch(df)?;
+        writer.finish()
+    }
+}
+
+/// Write DataFrames with the same schema to a single parquet file, one batch at a time.
+/// Only the batch that is written is held in memory.
+pub struct BatchedParquetWriter<W: 'static + Write + Seek + TryClone> {
+    writer: FileWriterKind<W>,
+}
 
-        // date64 is not supported by parquet and will be be truncated to date32
-        // We coerce these to timestamp(ms)
-        let date64_columns = df
-            .get_columns()
+enum FileWriterKind<W: 'static + Write + Seek + TryClone> {
+    Arrow {
+        writer: ParquetArrowWriter<W>,
+        schema: Arc<ArrowSchema>,
+        date64_columns: Vec<usize>,
+    },
+    Numeric(NumericParquetWriter<W>),
+}
+
+impl<W> BatchedParquetWriter<W>
+where
+    W: 'static + Write + Seek + TryClone,
+{
+    /// Create a writer for DataFrames with the given `schema`.
+    pub fn new(writer: W, schema: &Schema, options: &ParquetWriterOptions) -> Result<Self> {
+        if NumericParquetWriter::<W>::supports(schema) {
+            let writer = NumericParquetWriter::new(writer, schema, options.writer_properties())?;
+            return Ok(BatchedParquetWriter {
+                writer: FileWriterKind::Numeric(writer),
+            });
+        }
+
+        let mut date64_columns = vec![];
+        let fields = schema
+            .fields()
             .iter()
             .enumerate()
-            .filter_map(|(i, s)| match s.dtype() {
+            .map(|(i, f)| match f.data_type() {
+                // Our categorical type is not known to parquet and is written as utf8
+                DataType::Categorical => ArrowField::new(f.name(), ArrowDataType::LargeUtf8, true),
+                // date64 is not supported by parquet and will be be truncated to date32
+                // We coerce these to timestamp(ms)
                 DataType::Date64 => {
-                    fields[i] = ArrowField::new(
-                        s.name(),
+                    date64_columns.push(i);
+                    ArrowField::new(
+                        f.name(),
                         ArrowDataType::Timestamp(TimeUnit::Millisecond, None),
-                        s.null_count() > 0,
-                    );
-                    Some(i)
+                        true,
+                    )
                 }
-                _ => None,
+                _ => f.to_arrow(),
             })
+            .collect();
+        let schema = Arc::new(ArrowSchema::new(fields));
+        let writer =
+            ParquetArrowWriter::try_new(writer, schema.clone(), Some(options.writer_properties()))?;
+        Ok(BatchedParquetWriter {
+            writer: FileWriterKind::Arrow {
+                writer,
+                schema,
+                date64_columns,
+            },
+        })
+    }
+
+    /// Write the chunks of `df` as record batches.
+    pub fn write_batch(&mut self, df: &DataFrame) -> Result<()> {
+        let (writer, schema, date64_columns) = match &mut self.writer {
+            FileWriterKind::Numeric(writer) => return writer.write_batch(df),
+            FileWriterKind::Arrow {
+                writer,
+                schema,
+                date64_columns,
+            } => (writer, schema, date64_columns),
+        };
+        let df = to_arrow_compatible_df(df);
+        for rb in df.iter_record_batches() {
+            let mut columns = rb.columns().to_vec();
+            for i in date64_columns.iter() {
+                let array = cast(&columns[*i], &ArrowDataType::Int64)?;
+                columns[*i] = cast(
+                    &array,
+                    &ArrowDataType::Timestamp(TimeUnit::Millisecond, None),
+                )?;
+            }
+            let batch = RecordBatch::try_new(schema.clone(), columns)?;
+            writer.write(&batch)?
+        }
+        Ok(())
+    }
+
+    /// Write the footer of the file.
+    pub fn finish(self) -> Result<()> {
+        match self.writer {
+            FileWriterKind::Arrow { mut writer, .. } => {
+                let _ = writer.close()?;
+            }
+            FileWriterKind::Numeric(mut writer) => {
+                let _ = writer.writer.close()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Write the non-null values of a numeric column chunk, with the cached minimum and maximum of
+/// the Series as statistics.
+macro_rules! write_numeric_column {
+    ($writer:expr, $ca:expr, $s:expr) => {{
+        let ca = $ca;
+        let def_levels = ca
+            .into_iter()
+            .map(|opt_v| opt_v.is_some() as i16)
             .collect::<Vec<_>>();
-        let column_names = df
-            .get_columns()
+        let values = ca.into_iter().flatten().collect::<Vec<_>>();
+        $writer.write_batch_with_statistics(
+            &values,
+            Some(&def_levels),
+            None,
+            &$s.cached_min(),
+            &$s.cached_max(),
+            Some($s.null_count() as u64),
+            None,
+        )?;
+    }};
+}
+
+/// Writes files of which every column is a flat Int32, Int64, Float32 or Float64 column.
+///
+/// The arrow writer always computes the statistics of the column chunks itself. This writer
+/// writes the minimum and maximum that are cached on the Series instead (see
+/// [Series::cached_min]), so statistics that are already known are not computed again.
+struct NumericParquetWriter<W: 'static + Write + Seek + TryClone> {
+    writer: SerializedFileWriter<W>,
+    max_row_group_size: usize,
+}
+
+impl<W> NumericParquetWriter<W>
+where
+    W: 'static + Write + Seek + TryClone,
+{
+    fn supports(schema: &Schema) -> bool {
+        !schema.fields().is_empty()
+            && schema.fields().iter().all(|f| {
+                matches!(
+                    f.data_type(),
+                    DataType::Int32 | DataType::Int64 | DataType::Float32 | DataType::Float64
+                )
+            })
+    }
+
+    fn new(writer: W, schema: &Schema, properties: WriterProperties) -> Result<Self> {
+        let fields = schema
+            .fields()
             .iter()
-            .map(|s| s.name().to_string())
-            .collect::<Vec<_>>();
+            .map(|f| ArrowField::new(f.name(), f.data_type().to_arrow(), true))
+            .collect();
+        let parquet_schema = arrow_to_parquet_schema(&ArrowSchema::new(fields))?;
+        let max_row_group_size = properties.max_row_group_size();
+        let writer = SerializedFileWriter::new(
+            writer,
+            parquet_schema.root_schema_ptr(),
+            Arc::new(properties),
+        )?;
+        Ok(NumericParquetWriter {
+            writer,
+            max_row_group_size,
+        })
+    }
 
-        let iter = df.iter_record_batches().map(|rb| {
-            if !date64_columns.is_empty() {
-                let mut columns = rb.columns().to_vec();
-                for i in &date64_columns {
-                    let array = cast(&columns[*i], &ArrowDataType::Int64).unwrap();
-                    let array = cast(
-                        &array,
-                        &ArrowDataType::Timestamp(TimeUnit::Millisecond, None),
-                    )
-                    .unwrap();
-                    columns[*i] = array;
+    fn write_row_group(&mut self, columns: &[Series]) -> Result<()> {
+        let mut row_group = self.writer.next_row_group()?;
+        for s in columns {
+            let mut column = row_group.next_column()?.ok_or_else(|| {
+                PolarsError::ShapeMisMatch(
+                    "the DataFrame has more columns than the parquet schema".into(),
+                )
+            })?;
+            match &mut column {
+                ColumnWriter::Int32ColumnWriter(writer) => {
+                    write_numeric_column!(writer, s.i32()?, s)
                 }
-                RecordBatch::try_from_iter(column_names.iter().zip(columns)).unwrap()
-            } else {
-                rb
+                ColumnWriter::Int64ColumnWriter(writer) => {
+                    write_numeric_column!(writer, s.i64()?, s)
+                }
+                ColumnWriter::FloatColumnWriter(writer) => {
+                    write_numeric_column!(writer, s.f32()?, s)
+                }
+                ColumnWriter::DoubleColumnWriter(writer) => {
+                    write_numeric_column!(writer, s.f64()?, s)
+                }
+                _ => unreachable!(),
             }
-        });
+            row_group.close_column(column)?;
+        }
+        self.writer.close_row_group(row_group)?;
+        Ok(())
+    }
 
-        let mut parquet_writer =
-            ParquetArrowWriter::try_new(self.writer, Arc::new(ArrowSchema::new(fields)), None)?;
+    /// Every chunk is written as one or more row groups, like the arrow writer does.
+    fn write_batch(&mut self, df: &DataFrame) -> Result<()> {
+        // the columns are written as they are, so that their cached statistics are used
+        if df.height() <= self.max_row_group_size
+            && df.get_columns().iter().all(|s| s.n_chunks() == 1)
+        {
+            return match df.height() {
+                0 => Ok(()),
+                _ => self.write_row_group(df.get_columns()),
+            };
+        }
 
-        for batch in iter {
-            parquet_writer.write(&batch)?
+        let mut df = df.clone();
+        df.align_chunks();
+        let chunk_lengths = df.get_columns()[0].chunk_lengths().collect::<Vec<_>>();
+        let mut offset = 0;
+        for chunk_len in chunk_lengths {
+            let mut chunk_offset = 0;
+            while chunk_offset < chunk_len {
+                let len = std::cmp::min(self.max_row_group_size, chunk_len - chunk_offset);
+                let columns = df
+                    .get_columns()
+                    .iter()
+                    .map(|s| s.slice((offset + chunk_offset) as i64, len))
+                    .collect::<Vec<_>>();
+                self.write_row_group(&columns)?;
+                chunk_offset += len;
+            }
+            offset += chunk_len;
         }
-        let _ = parquet_writer.close()?;
         Ok(())
     }
 }
@@ -232,6 +1055,7 @@ where
 mod test {
     use crate::prelude::*;
     use parquet_lib::file::writer::InMemoryWriteableCursor;
+    use polars_core::chunked_array::builder::get_list_builder;
     use polars_core::{df, prelude::*};
     use std::fs::File;
 
@@ -266,4 +1090,276 @@ mod test {
         assert!(read.frame_equal_missing(&df));
         Ok(())
     }
+
+    #[test]
+    fn test_parquet_numeric_statistics() -> Result<()> {
+        use parquet_lib::file::{
+            reader::{FileReader, SerializedFileReader},
+            statistics::Statistics,
+        };
+        let f: InMemoryWriteableCursor = Default::default();
+
+        // these values are not exact as f64
+        let big = 1i64 << 60;
+        let mut df = df![
+            "a" => [Some(big + 1), None, Some(big + 3)],
+            "b" => [Some(1.5f64), Some(-2.0), None]
+        ]?;
+        // the statistics that are cached on the Series are written
+        assert_eq!(df.column("a")?.cached_max::<i64>(), Some(big + 3));
+        // every chunk is a row group
+        df.vstack_mut(&df![
+            "a" => [Some(big)],
+            "b" => [Some(4.0f64)]
+        ]?)?;
+
+        ParquetWriter::new(f.clone()).finish(&df)?;
+        let data = f.data();
+
+        let reader = SerializedFileReader::new(SliceableCursor::new(data.clone()))?;
+        let metadata = reader.metadata();
+        assert_eq!(metadata.num_row_groups(), 2);
+        let row_group = metadata.row_group(0);
+        match row_group.column(0).statistics() {
+            Some(Statistics::Int64(stats)) => {
+                assert_eq!((*stats.min(), *stats.max()), (big + 1, big + 3));
+                assert_eq!(stats.null_count(), 1);
+            }
+            _ => panic!("expected int64 statistics"),
+        }
+        match row_group.column(1).statistics() {
+            Some(Statistics::Double(stats)) => {
+                assert_eq!((*stats.min(), *stats.max()), (-2.0, 1.5));
+            }
+            _ => panic!("expected double statistics"),
+        }
+
+        let read = ParquetReader::new(SliceableCursor::new(data)).finish()?;
+        assert!(read.frame_equal_missing(&df));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parquet_empty_round_trip() -> Result<()> {
+        let f: InMemoryWriteableCursor = Default::default();
+
+        let df = df![
+            "a" => [1i32, 2],
+            "b" => ["foo", "bar"]
+        ]?;
+        let df = df.slice(0, 0);
+
+        ParquetWriter::new(f.clone()).finish(&df)?;
+        let f = SliceableCursor::new(f.data());
+
+        let read = ParquetReader::new(f).finish()?;
+        assert_eq!(read.height(), 0);
+        assert_eq!(read.schema(), df.schema());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "dtype-struct")]
+    fn test_parquet_struct_round_trip() -> Result<()> {
+        let f: InMemoryWriteableCursor = Default::default();
+
+        let fields = df![
+            "a" => [Some(1i32), None, Some(3), Some(4)],
+            "b" => [Some(1.5f64), Some(2.5), None, None],
+            "c" => [Some("x"), Some("y"), Some("z"), None]
+        ]?;
+        // the third row is null
+        let validity = BooleanChunked::new_from_slice("", &[true, true, false, true]);
+        let payload = StructChunked::new_with_validity(
+            "payload",
+            fields.get_columns().to_vec(),
+            Some(validity.clone()),
+        )?;
+        let df = DataFrame::new(vec![
+            payload.into_series(),
+            Series::new("id", &[1, 2, 3, 4]),
+        ])?;
+
+        ParquetWriter::new(f.clone()).finish(&df)?;
+        let read = ParquetReader::new(SliceableCursor::new(f.data())).finish()?;
+
+        assert_eq!(read.schema(), df.schema());
+        let s = read.column("payload")?;
+        assert_eq!(s.null_count(), 1);
+        // the fields of a null struct are not written, only compare the valid rows
+        assert!(read
+            .filter(&validity)?
+            .unnest(&["payload"])?
+            .frame_equal_missing(&df.filter(&validity)?.unnest(&["payload"])?));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "lazy")]
+    fn test_parquet_parallel_row_groups() -> Result<()> {
+        use polars_core::utils::accumulate_dataframes_vertical;
+        let f: InMemoryWriteableCursor = Default::default();
+
+        // every chunk is written as a row group
+        let chunks = (0..8i32)
+            .map(|i| {
+                let a = (i * 10_000..(i + 1) * 10_000).collect::<Vec<_>>();
+                let b = a.iter().map(|v| *v as f64 / 3.0).collect::<Vec<_>>();
+                let c = a
+                    .iter()
+                    .map(|v| {
+                        if v % 7 == 0 {
+                            None
+                        } else {
+                            Some(v.to_string())
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                df!["a" => a, "b" => b, "c" => c]
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let df = accumulate_dataframes_vertical(chunks)?;
+
+        ParquetWriter::new(f.clone()).finish(&df)?;
+        let data = f.data();
+        let read =
+            |parallel: bool, stop_after_n_rows: Option<usize>, projection: Option<&[usize]>| {
+                ParquetReader::new(SliceableCursor::new(data.clone()))
+                    .read_parallel(parallel)
+                    .set_rechunk(false)
+                    .with_stop_after_n_rows(stop_after_n_rows)
+                    .finish_with_scan_ops(None, None, projection)
+            };
+
+        let out = read(true, None, None)?;
+        assert!(out.frame_equal_missing(&df));
+        // the row groups are appended without rechunking
+        assert_eq!(out.n_chunks()?, 8);
+        assert!(out.frame_equal_missing(&read(false, None, None)?));
+
+        for n in &[0, 5, 10_000, 25_000, 100_000] {
+            let parallel = read(true, Some(*n), Some(&[2, 0]))?;
+            let sequential = read(false, Some(*n), Some(&[2, 0]))?;
+            assert_eq!(parallel.height(), std::cmp::min(*n, 80_000));
+            assert!(parallel.frame_equal_missing(&sequential));
+        }
+        Ok(())
+    }
+
+    fn write_parquet(df: &DataFrame) -> Result<Vec<u8>> {
+        let f: InMemoryWriteableCursor = Default::default();
+        ParquetWriter::new(f.clone()).finish(df)?;
+        Ok(f.data())
+    }
+
+    #[test]
+    fn test_parquet_nested() -> Result<()> {
+        let mut ints = get_list_builder(&DataType::Int32, 4, 4, "ints");
+        ints.append_series(&Series::new("", &[Some(1i32), None]));
+        ints.append_null();
+        ints.append_series(&Series::new("", &[3i32]));
+        ints.append_series(&Series::new("", &[] as &[i32]));
+
+        let mut tags = get_list_builder(&DataType::Utf8, 4, 4, "tags");
+        tags.append_series(&Series::new("", &["a", "b"]));
+        tags.append_series(&Series::new("", &[] as &[&str]));
+        tags.append_series(&Series::new("", &["c"]));
+        tags.append_null();
+
+        #[allow(unused_mut)]
+        let mut columns = vec![ints.finish().into_series(), tags.finish().into_series()];
+        #[cfg(feature = "dtype-struct")]
+        {
+            let lat = Series::new("lat", &[1.0, 2.0, 3.0, 4.0]);
+            let lon = Series::new("lon", &[Some(5.0), None, Some(7.0), Some(8.0)]);
+            // the third point is null
+            let point = StructChunked::new_with_validity(
+                "point",
+                vec![lat, lon],
+                Some(BooleanChunked::new_from_slice(
+                    "",
+                    &[true, true, false, true],
+                )),
+            )?;
+            columns.push(point.into_series());
+        }
+        let data = write_parquet(&DataFrame::new(columns)?)?;
+
+        let df = ParquetReader::new(SliceableCursor::new(data.clone()))
+            .with_flatten_structs(true)
+            .finish()?;
+
+        let ints = df.column("ints")?.list()?.into_iter().collect::<Vec<_>>();
+        assert_eq!(
+            Vec::from(ints[0].as_ref().unwrap().i32()?),
+            &[Some(1), None]
+        );
+        assert!(ints[1].is_none());
+        assert_eq!(Vec::from(ints[2].as_ref().unwrap().i32()?), &[Some(3)]);
+        assert_eq!(ints[3].as_ref().unwrap().len(), 0);
+
+        let tags = df.column("tags")?.list()?.into_iter().collect::<Vec<_>>();
+        assert_eq!(
+            Vec::from(tags[0].as_ref().unwrap().utf8()?),
+            &[Some("a"), Some("b")]
+        );
+        assert_eq!(tags[1].as_ref().unwrap().len(), 0);
+        assert!(tags[3].is_none());
+
+        #[cfg(feature = "dtype-struct")]
+        {
+            assert_eq!(
+                df.get_column_names(),
+                &["ints", "tags", "point.lat", "point.lon"]
+            );
+            assert_eq!(
+                Vec::from(df.column("point.lat")?.f64()?),
+                &[Some(1.0), Some(2.0), None, Some(4.0)]
+            );
+            assert_eq!(
+                Vec::from(df.column("point.lon")?.f64()?),
+                &[Some(5.0), None, None, Some(8.0)]
+            );
+
+            let df = ParquetReader::new(SliceableCursor::new(data.clone())).finish()?;
+            let point = df.column("point")?.struct_()?;
+            let lat = point.field_by_name("lat")?;
+            assert_eq!(lat.f64()?.get(0), Some(1.0));
+            assert_eq!(lat.f64()?.get(3), Some(4.0));
+            assert_eq!(point.null_count(), 1);
+        }
+
+        #[cfg(feature = "lazy")]
+        {
+            // the projection selects the nested column by its top-level index
+            let df = ParquetReader::new(SliceableCursor::new(data))
+                .with_flatten_structs(true)
+                .finish_with_scan_ops(None, None, Some(&[1]))?;
+            assert_eq!(df.get_column_names(), &["tags"]);
+            assert_eq!(df.column("tags")?.len(), 4);
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "dtype-struct")]
+    fn test_parquet_nesting_depth() -> Result<()> {
+        // a struct with a list field is nested two levels deep
+        let mut values = get_list_builder(&DataType::Int32, 1, 1, "values");
+        values.append_series(&Series::new("", &[1i32]));
+        let nested = StructChunked::new("nested", &[values.finish().into_series()])?;
+        let data = write_parquet(&DataFrame::new(vec![nested.into_series()])?)?;
+
+        let err = ParquetReader::new(SliceableCursor::new(data.clone()))
+            .finish()
+            .unwrap_err();
+        assert!(matches!(err, PolarsError::InvalidOperation(_)));
+
+        // the list is a single level deep once the struct is flattened
+        let df = ParquetReader::new(SliceableCursor::new(data))
+            .with_flatten_structs(true)
+            .finish()?;
+        assert_eq!(df.get_column_names(), &["nested.values"]);
+        Ok(())
+    }
 }
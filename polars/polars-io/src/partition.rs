@@ -0,0 +1,342 @@
+//! # Writing a DataFrame to a directory tree of partitions.
+//!
+//! Every distinct combination of the key columns is written to its own directory, named
+//! `key=value` per key column, e.g. `out/year=2021/month=03/part-0.parquet`.
+//!
+//! ## Example
+//!
+//! ```no_run
+//! use polars_core::prelude::*;
+//! use polars_io::prelude::*;
+//!
+//! fn example(df: &DataFrame) -> Result<DataFrame> {
+//!     df.write_partitioned(
+//!         "out",
+//!         &["year", "month"],
+//!         FileFormat::Parquet,
+//!         &PartitionOptions::default(),
+//!     )
+//! }
+//! ```
+use crate::prelude::*;
+use polars_core::prelude::*;
+use polars_core::POOL;
+use rayon::prelude::*;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Directory name used for partitions with a null key.
+pub const NULL_PARTITION: &str = "__null__";
+
+/// The file format of the written partitions.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum FileFormat {
+    #[cfg(feature = "parquet")]
+    Parquet,
+    #[cfg(feature = "csv-file")]
+    Csv,
+}
+
+impl FileFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "parquet")]
+            FileFormat::Parquet => "parquet",
+            #[cfg(feature = "csv-file")]
+            FileFormat::Csv => "csv",
+        }
+    }
+
+    fn write(&self, df: &DataFrame, path: &Path) -> Result<()> {
+        let mut file = File::create(path)?;
+        match self {
+            #[cfg(feature = "parquet")]
+            FileFormat::Parquet => ParquetWriter::new(file).finish(df),
+            #[cfg(feature = "csv-file")]
+            FileFormat::Csv => CsvWriter::new(&mut file).has_headers(true).finish(df),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PartitionOptions {
+    /// Don't write the key columns to the files, as their values are in the directory names.
+    pub drop_partition_columns: bool,
+    /// Split a partition in multiple files `part-0`, `part-1`, ... with at most this many rows.
+    pub max_rows_per_file: Option<usize>,
+}
+
+impl Default for PartitionOptions {
+    fn default() -> Self {
+        PartitionOptions {
+            drop_partition_columns: true,
+            max_rows_per_file: None,
+        }
+    }
+}
+
+impl PartitionOptions {
+    /// Set whether the key columns are dropped from the written files.
+    pub fn with_drop_partition_columns(mut self, toggle: bool) -> Self {
+        self.drop_partition_columns = toggle;
+        self
+    }
+
+    /// Set the maximum number of rows per file.
+    pub fn with_max_rows_per_file(mut self, max_rows: Option<usize>) -> Self {
+        self.max_rows_per_file = max_rows;
+        self
+    }
+}
+
+/// Make a key value safe to use as a directory name. Path separators, control characters and
+/// `%`, `=` and `:` are percent-encoded, such that the original value can be recovered.
+fn sanitize_partition_value(value: Option<&str>) -> String {
+    let value = match value {
+        Some(value) => value,
+        None => return NULL_PARTITION.to_string(),
+    };
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '/' | '\\' | '%' | '=' | ':' => out.push_str(&format!("%{:02X}", c as u32)),
+            c if c.is_control() => out.push_str(&format!("%{:02X}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// A file that is written to a temporary name and renamed once all partitions are written.
+struct PartFile<'a> {
+    dir: PathBuf,
+    name: String,
+    idx: &'a [IdxSize],
+}
+
+impl PartFile<'_> {
+    fn path(&self) -> PathBuf {
+        self.dir.join(&self.name)
+    }
+
+    fn tmp_path(&self, suffix: &str) -> PathBuf {
+        self.dir.join(format!(".{}.{}", self.name, suffix))
+    }
+}
+
+pub trait PartitionedWriter {
+    /// Write every distinct combination of the values in the `by` columns to its own directory
+    /// under `dir`. The partitions are written in parallel.
+    ///
+    /// Files are first written under a temporary name. If writing any of them fails, the
+    /// temporary files are removed and `dir` is left unchanged. Otherwise they are renamed to
+    /// their final name and the part files of an earlier write to the same partitions are
+    /// removed. The renames are not atomic as a whole, so a reader of `dir` may observe a
+    /// partially replaced partition in the meantime.
+    ///
+    /// Returns a DataFrame with the `path` and the number of `rows` of every written file.
+    fn write_partitioned<P: AsRef<Path>>(
+        &self,
+        dir: P,
+        by: &[&str],
+        format: FileFormat,
+        options: &PartitionOptions,
+    ) -> Result<DataFrame>;
+}
+
+impl PartitionedWriter for DataFrame {
+    fn write_partitioned<P: AsRef<Path>>(
+        &self,
+        dir: P,
+        by: &[&str],
+        format: FileFormat,
+        options: &PartitionOptions,
+    ) -> Result<DataFrame> {
+        if by.is_empty() {
+            return Err(PolarsError::ValueError(
+                "at least one partition column is required".into(),
+            ));
+        }
+        let dir = dir.as_ref();
+        let gb = self.groupby_stable(by.to_vec())?;
+        let keys = by
+            .iter()
+            .map(|name| self.column(name)?.cast_with_dtype(&DataType::Utf8))
+            .collect::<Result<Vec<_>>>()?;
+        let keys = keys.iter().map(|s| s.utf8()).collect::<Result<Vec<_>>>()?;
+
+        let df = if options.drop_partition_columns {
+            let mut df = self.clone();
+            for name in by {
+                df = df.drop(name)?;
+            }
+            df
+        } else {
+            self.clone()
+        };
+
+        let max_rows = options.max_rows_per_file.unwrap_or(usize::MAX).max(1);
+        let mut files = Vec::with_capacity(gb.get_groups().len());
+        for (first, idx) in gb.get_groups() {
+            let mut partition_dir = dir.to_path_buf();
+            for (name, ca) in by.iter().zip(&keys) {
+                let value = sanitize_partition_value(ca.get(*first as usize));
+                partition_dir.push(format!("{}={}", name, value));
+            }
+            std::fs::create_dir_all(&partition_dir)?;
+            for (i, idx) in idx.chunks(max_rows).enumerate() {
+                files.push(PartFile {
+                    dir: partition_dir.clone(),
+                    name: format!("part-{}.{}", i, format.extension()),
+                    idx,
+                });
+            }
+        }
+
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let suffix = format!(
+            "tmp-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+        let results = POOL.install(|| {
+            files
+                .par_iter()
+                .map(|file| {
+                    let part = df.take_iter(file.idx.iter().map(|i| *i as usize));
+                    format.write(&part, &file.tmp_path(&suffix))
+                })
+                .collect::<Vec<_>>()
+        });
+
+        if let Err(e) = results.into_iter().collect::<Result<Vec<_>>>() {
+            for file in &files {
+                let _ = std::fs::remove_file(file.tmp_path(&suffix));
+            }
+            return Err(e);
+        }
+
+        // remove the stale part files of an earlier write, e.g. when a partition has less files
+        let new_names = files
+            .iter()
+            .map(|file| file.path())
+            .collect::<PlHashSet<_>>();
+        let partition_dirs = files.iter().map(|file| &file.dir).collect::<PlHashSet<_>>();
+        let extension = format!(".{}", format.extension());
+        for partition_dir in partition_dirs {
+            for entry in std::fs::read_dir(partition_dir)? {
+                let path = entry?.path();
+                let is_part = path.file_name().map_or(false, |name| {
+                    let name = name.to_string_lossy();
+                    name.starts_with("part-") && name.ends_with(&extension)
+                });
+                if is_part && !new_names.contains(&path) {
+                    std::fs::remove_file(&path)?;
+                }
+            }
+        }
+
+        let mut paths = Vec::with_capacity(files.len());
+        let mut rows = Vec::with_capacity(files.len());
+        for file in &files {
+            let path = file.path();
+            std::fs::rename(file.tmp_path(&suffix), &path)?;
+            paths.push(path.to_string_lossy().into_owned());
+            rows.push(file.idx.len() as u32);
+        }
+        DataFrame::new(vec![Series::new("path", paths), Series::new("rows", rows)])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    #[test]
+    fn test_sanitize_partition_value() {
+        assert_eq!(sanitize_partition_value(None), NULL_PARTITION);
+        assert_eq!(sanitize_partition_value(Some("2021")), "2021");
+        assert_eq!(sanitize_partition_value(Some("a/b=c")), "a%2Fb%3Dc");
+        assert_eq!(sanitize_partition_value(Some("..\\%")), "..%5C%25");
+    }
+
+    #[test]
+    #[cfg(feature = "csv-file")]
+    fn test_write_partitioned() -> Result<()> {
+        let dir = TempDir::new("write-partitioned")?;
+        let df = df![
+            "year" => [Some("2021"), Some("2021"), Some("2021"), Some("2022"), None],
+            "month" => ["03", "03", "04", "a/b", "01"],
+            "value" => [1, 2, 3, 4, 5]
+        ]?;
+
+        let options = PartitionOptions::default().with_max_rows_per_file(Some(1));
+        let out =
+            df.write_partitioned(dir.path(), &["year", "month"], FileFormat::Csv, &options)?;
+        assert_eq!(out.height(), 5);
+        assert_eq!(out.column("rows")?.sum::<u32>(), Some(5));
+
+        let part = dir.path().join("year=2021").join("month=03");
+        assert!(part.join("part-0.csv").exists());
+        assert!(part.join("part-1.csv").exists());
+        assert!(dir.path().join("year=2022/month=a%2Fb/part-0.csv").exists());
+        assert!(dir
+            .path()
+            .join("year=__null__/month=01/part-0.csv")
+            .exists());
+
+        let read = CsvReader::from_path(part.join("part-1.csv"))?.finish()?;
+        assert_eq!(read.get_column_names(), &["value"]);
+        assert_eq!(Vec::from(read.column("value")?.i64()?), &[Some(2)]);
+
+        // no temporary files are left behind
+        for entry in std::fs::read_dir(&part)? {
+            assert!(!entry?.file_name().to_string_lossy().starts_with('.'));
+        }
+
+        let options = PartitionOptions::default().with_drop_partition_columns(false);
+        let out = df.write_partitioned(dir.path(), &["year"], FileFormat::Csv, &options)?;
+        assert_eq!(out.height(), 3);
+        let read = CsvReader::from_path(dir.path().join("year=2021/part-0.csv"))?.finish()?;
+        assert_eq!(read.shape(), (3, 3));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "parquet")]
+    fn test_write_partitioned_parquet() -> Result<()> {
+        let dir = TempDir::new("write-partitioned-parquet")?;
+        let df = df![
+            "key" => ["a", "a", "a", "b"],
+            "value" => [1.0, 2.0, 3.0, 4.0]
+        ]?;
+
+        let options = PartitionOptions::default().with_max_rows_per_file(Some(2));
+        let out = df.write_partitioned(dir.path(), &["key"], FileFormat::Parquet, &options)?;
+        assert_eq!(out.height(), 3);
+        let part = dir.path().join("key=a");
+        assert!(part.join("part-1.parquet").exists());
+
+        let read = ParquetReader::new(File::open(part.join("part-0.parquet"))?).finish()?;
+        assert_eq!(read.get_column_names(), &["value"]);
+        assert_eq!(
+            Vec::from(read.column("value")?.f64()?),
+            &[Some(1.0), Some(2.0)]
+        );
+
+        // a rewrite of the partition with less files removes the stale part files
+        let out = df.write_partitioned(
+            dir.path(),
+            &["key"],
+            FileFormat::Parquet,
+            &PartitionOptions::default(),
+        )?;
+        assert_eq!(out.height(), 2);
+        assert!(!part.join("part-1.parquet").exists());
+        let read = ParquetReader::new(File::open(part.join("part-0.parquet"))?).finish()?;
+        assert_eq!(read.height(), 3);
+        Ok(())
+    }
+}
@@ -1,13 +1,17 @@
-pub use crate::{SerReader, SerWriter};
+pub use crate::{RowCount, SerReader, SerWriter};
 
 #[cfg(feature = "csv-file")]
 pub use crate::csv::*;
+#[cfg(feature = "csv-file")]
+pub use crate::csv_core::parse::{ParseOptions, Utf8Parse};
 #[cfg(feature = "ipc")]
 pub use crate::ipc::*;
 #[cfg(feature = "json")]
 pub use crate::json::*;
 #[cfg(feature = "parquet")]
 pub use crate::parquet::*;
+#[cfg(any(feature = "csv-file", feature = "parquet"))]
+pub use crate::partition::*;
 
 #[cfg(test)]
 use polars_core::prelude::*;
@@ -0,0 +1,63 @@
+//! A temporary directory that is removed with all its content when dropped. This is used to
+//! spill intermediate results to disk.
+use polars_core::prelude::*;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A uniquely named directory in [std::env::temp_dir]. The directory and the files in it are
+/// removed when the `TempDir` is dropped, also if the thread unwinds from a panic.
+#[derive(Debug)]
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    /// Create a new directory. The `prefix` is part of the directory name, to make it recognizable.
+    pub fn new(prefix: &str) -> Result<Self> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "polars-{}-{}-{}",
+            prefix,
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::create_dir(&path)?;
+        Ok(TempDir { path })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Get the path of a file with name `name` in this directory.
+    pub fn file_path(&self, name: &str) -> PathBuf {
+        self.path.join(name)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_temp_dir() -> Result<()> {
+        let dir = TempDir::new("test")?;
+        let other = TempDir::new("test")?;
+        assert_ne!(dir.path(), other.path());
+
+        let path = dir.file_path("a.txt");
+        std::fs::write(&path, "a")?;
+        assert!(path.exists());
+
+        let dir_path = dir.path().to_path_buf();
+        drop(dir);
+        assert!(!dir_path.exists());
+        Ok(())
+    }
+}
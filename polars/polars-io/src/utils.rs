@@ -15,8 +15,28 @@ pub(crate) fn to_arrow_compatible_df(df: &DataFrame) -> DataFrame {
     DataFrame::new_no_checks(cols)
 }
 
-pub(crate) fn resolve_homedir(path: &Path) -> PathBuf {
-    // replace "~" with home directory
+#[cfg(any(feature = "csv-file", feature = "parquet"))]
+/// Reorder the columns of a DataFrame that was read with a projection to the order in which the
+/// columns were requested. The readers decode the projected columns in the order of the file.
+pub(crate) fn to_projection_order(df: DataFrame, columns: &[String]) -> Result<DataFrame> {
+    if df.width() != columns.len()
+        || df
+            .get_column_names()
+            .iter()
+            .zip(columns)
+            .all(|(name, column)| *name == column)
+    {
+        return Ok(df);
+    }
+    let cols = columns
+        .iter()
+        .map(|name| df.column(name).map(|s| s.clone()))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(DataFrame::new_no_checks(cols))
+}
+
+/// Replace a leading `~` of `path` with the home directory.
+pub fn resolve_homedir(path: &Path) -> PathBuf {
     if path.starts_with("~") {
         if let Some(homedir) = home_dir() {
             return homedir.join(path.strip_prefix("~").unwrap());
@@ -29,6 +29,10 @@ pub fn to_datafusion_lit(lit: LiteralValue) -> Result<ScalarValue> {
         Float64(v) => ScalarValue::Float64(Some(v)),
         #[cfg(all(feature = "temporal", feature = "dtype-date64"))]
         DateTime(v) => ScalarValue::Date64(Some(v.timestamp_millis())),
+        #[cfg(all(feature = "temporal", feature = "dtype-date32"))]
+        Date(v) => ScalarValue::Date32(Some(
+            polars_core::chunked_array::temporal::conversion::naive_date_to_date32(v),
+        )),
         lit => {
             return Err(PolarsError::Other(
                 format!("Literal conversion for literal {:?} not yet supported", lit).into(),
@@ -191,12 +195,20 @@ pub fn to_datafusion_lp(lp: LogicalPlan) -> Result<DLogicalPlan> {
             aggs,
             schema,
             apply,
+            drop_null_keys,
+            ..
         } => {
             if apply.is_some() {
                 return Err(PolarsError::Other(
                     "Custom functions not yet supported in Polars to DataFusion conversion".into(),
                 ));
             }
+            if drop_null_keys {
+                return Err(PolarsError::Other(
+                    "Dropping null keys not yet supported in Polars to DataFusion conversion"
+                        .into(),
+                ));
+            }
             DLogicalPlan::Aggregate {
                 input: Arc::new(to_datafusion_lp(*input)?),
                 group_expr: keys
@@ -234,12 +246,12 @@ pub fn to_datafusion_lp(lp: LogicalPlan) -> Result<DLogicalPlan> {
             input_left,
             input_right,
             schema,
-            how,
             left_on,
             right_on,
+            options,
             ..
         } => {
-            let join_type = match how {
+            let join_type = match options.how {
                 JoinType::Inner => lpmod::JoinType::Inner,
                 JoinType::Left => lpmod::JoinType::Left,
                 JoinType::Outer => {
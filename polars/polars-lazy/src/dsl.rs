@@ -2,7 +2,11 @@
 use crate::logical_plan::Context;
 use crate::prelude::*;
 use crate::utils::{has_expr, output_name};
+use polars_core::frame::groupby::GroupTuples;
 use polars_core::prelude::*;
+#[cfg(feature = "checked_arithmetic")]
+use polars_core::utils::get_supertype;
+use polars_io::csv_core::parse::{ParseOptions, Utf8Parse};
 
 #[cfg(feature = "temporal")]
 use polars_core::utils::chrono::{NaiveDate, NaiveDateTime};
@@ -56,6 +60,139 @@ impl Debug for dyn SeriesBinaryUdf {
     }
 }
 
+/// An expression that is implemented outside of Polars. Use [plugin](crate::functions::plugin)
+/// to use it in a query.
+pub trait ExprPlugin: Send + Sync {
+    /// The name that is shown in the query plan.
+    fn name(&self) -> &str;
+
+    /// Compute the output from the evaluated inputs.
+    fn evaluate(&self, inputs: &[Series], state: &ExecutionState) -> Result<Series>;
+
+    /// Determine the output field from the fields of the inputs.
+    fn to_field(&self, fields: &[Field]) -> Result<Field>;
+
+    /// Every output value only depends on the input values of the same row. Predicates can be
+    /// pushed down past an elementwise expression, and in a groupby context it is evaluated
+    /// once for all groups.
+    fn is_elementwise(&self) -> bool {
+        false
+    }
+
+    /// The output is a single value. In a groupby context this produces a value per group,
+    /// otherwise the output of every group is collected in a list.
+    fn is_aggregation(&self) -> bool {
+        false
+    }
+
+    /// Aggregate the inputs directly on the group indices, instead of calling
+    /// [evaluate](ExprPlugin::evaluate) once per group. This is only called for aggregations
+    /// whose inputs are not changed per group. Return `None` to fall back to the default.
+    fn aggregate_groups(
+        &self,
+        _inputs: &[Series],
+        _groups: &GroupTuples,
+        _state: &ExecutionState,
+    ) -> Option<Result<Series>> {
+        None
+    }
+
+    /// Expand multi-output inputs, e.g. `col("*").exclude(vec!["id"])`, to an input per
+    /// selected column, instead of expanding the expression to an expression per column.
+    fn expand_inputs(&self) -> bool {
+        false
+    }
+
+    /// The parameters that, together with the [name](ExprPlugin::name), determine the output.
+    /// Two plugin expressions are only equal if they share the plugin, or if both have the same
+    /// name and return equal `Some` parameters.
+    fn params(&self) -> Option<String> {
+        None
+    }
+
+    /// The output data type depends on the values, not only on the input fields. The field of
+    /// [to_field](ExprPlugin::to_field) is used during optimization, and
+    /// [LazyFrame::schema](crate::frame::LazyFrame::schema) returns an error for a query that
+    /// outputs the expression.
+    fn dtype_depends_on_data(&self) -> bool {
+        false
+    }
+}
+
+/// Casts an integer column to the smallest integer type that can hold its values. See
+/// [Expr::shrink_dtype].
+struct ShrinkDtype;
+
+impl ExprPlugin for ShrinkDtype {
+    fn name(&self) -> &str {
+        "shrink_dtype"
+    }
+
+    fn evaluate(&self, inputs: &[Series], _state: &ExecutionState) -> Result<Series> {
+        inputs[0].shrink_dtype()
+    }
+
+    fn to_field(&self, fields: &[Field]) -> Result<Field> {
+        Ok(fields[0].clone())
+    }
+
+    fn is_elementwise(&self) -> bool {
+        true
+    }
+
+    fn dtype_depends_on_data(&self) -> bool {
+        true
+    }
+}
+
+/// Folds the values of a group, starting from an initial state. See [Expr::agg_fold].
+struct AggFold<T: PolarsNumericType, F> {
+    init: T::Native,
+    f: F,
+}
+
+impl<T, F> ExprPlugin for AggFold<T, F>
+where
+    T: PolarsNumericType,
+    ChunkedArray<T>: IntoSeries,
+    F: Fn(T::Native, T::Native) -> T::Native + Send + Sync,
+{
+    fn name(&self) -> &str {
+        "agg_fold"
+    }
+
+    fn evaluate(&self, inputs: &[Series], _state: &ExecutionState) -> Result<Series> {
+        let s = inputs[0].cast::<T>()?;
+        let ca = s.unpack::<T>()?;
+        let out = ca
+            .into_iter()
+            .flatten()
+            .fold(None, |acc, v| Some((self.f)(acc.unwrap_or(self.init), v)));
+        Ok(ChunkedArray::<T>::new_from_opt_slice(ca.name(), &[out]).into_series())
+    }
+
+    fn to_field(&self, fields: &[Field]) -> Result<Field> {
+        Ok(Field::new(fields[0].name(), T::get_dtype()))
+    }
+
+    fn is_aggregation(&self) -> bool {
+        true
+    }
+
+    fn aggregate_groups(
+        &self,
+        inputs: &[Series],
+        groups: &GroupTuples,
+        _state: &ExecutionState,
+    ) -> Option<Result<Series>> {
+        let out = inputs[0].cast::<T>().and_then(|s| {
+            let ca = s.unpack::<T>()?;
+            Ok(ca.agg_fold(groups, self.init, &self.f).into_series())
+        });
+        Some(out)
+    }
+}
+
 #[derive(Clone)]
 /// Wrapper type that indicates that the inner type is not equal to anything
 pub struct NoEq<T>(T);
@@ -111,6 +248,64 @@ where
     }
 }
 
+/// Determines the output field of a [Function](Expr::Function) from the fields of its inputs.
+pub trait FunctionOutputField: Send + Sync {
+    fn get_field(&self, input_schema: &Schema, cntxt: Context, fields: &[Field]) -> Result<Field>;
+}
+
+impl<F> FunctionOutputField for F
+where
+    F: Fn(&Schema, Context, &[Field]) -> Result<Field> + Send + Sync,
+{
+    fn get_field(&self, input_schema: &Schema, cntxt: Context, fields: &[Field]) -> Result<Field> {
+        self(input_schema, cntxt, fields)
+    }
+}
+
+/// The output field of a [Function](Expr::Function).
+#[derive(Clone, PartialEq, Debug)]
+pub enum GetOutput {
+    /// The field of the first input.
+    SameType,
+    /// The name of the first input with the given data type.
+    ToType(DataType),
+    /// Delays output type evaluation until the fields of the inputs are known.
+    Map(NoEq<Arc<dyn FunctionOutputField>>),
+}
+
+impl From<Option<DataType>> for GetOutput {
+    fn from(dtype: Option<DataType>) -> Self {
+        match dtype {
+            Some(dtype) => GetOutput::ToType(dtype),
+            None => GetOutput::SameType,
+        }
+    }
+}
+
+impl GetOutput {
+    /// Determine the output field. `input_field` resolves the field of the input with the given
+    /// index.
+    pub(crate) fn get_field<F>(
+        &self,
+        input_schema: &Schema,
+        cntxt: Context,
+        n_inputs: usize,
+        input_field: F,
+    ) -> Result<Field>
+    where
+        F: Fn(usize) -> Result<Field>,
+    {
+        match self {
+            GetOutput::SameType => input_field(0),
+            GetOutput::ToType(dtype) => Ok(Field::new(input_field(0)?.name(), dtype.clone())),
+            GetOutput::Map(f) => {
+                let fields = (0..n_inputs).map(input_field).collect::<Result<Vec<_>>>()?;
+                f.get_field(input_schema, cntxt, &fields)
+            }
+        }
+    }
+}
+
 #[derive(PartialEq, Clone)]
 pub enum AggExpr {
     Min(Box<Expr>),
@@ -121,7 +316,9 @@ pub enum AggExpr {
     Last(Box<Expr>),
     Mean(Box<Expr>),
     List(Box<Expr>),
+    Flatten(Box<Expr>),
     Count(Box<Expr>),
+    NullCount(Box<Expr>),
     Quantile { expr: Box<Expr>, quantile: f64 },
     Sum(Box<Expr>),
     AggGroups(Box<Expr>),
@@ -141,7 +338,9 @@ impl AsRef<Expr> for AggExpr {
             Last(e) => e,
             Mean(e) => e,
             List(e) => e,
+            Flatten(e) => e,
             Count(e) => e,
+            NullCount(e) => e,
             Quantile { expr, .. } => expr,
             Sum(e) => e,
             AggGroups(e) => e,
@@ -185,8 +384,8 @@ pub enum Expr {
     },
     SortBy {
         expr: Box<Expr>,
-        by: Box<Expr>,
-        reverse: bool,
+        by: Vec<Expr>,
+        reverse: Vec<bool>,
     },
     Agg(AggExpr),
     /// A ternary operation
@@ -201,12 +400,17 @@ pub enum Expr {
         input: Vec<Expr>,
         /// function to apply
         function: NoEq<Arc<dyn SeriesUdf>>,
-        /// output dtype of the function
-        output_type: Option<DataType>,
+        /// output field of the function
+        output_type: GetOutput,
         /// if the groups should aggregated to list before
         /// execution of the function.
         collect_groups: bool,
     },
+    /// An expression implemented by an [ExprPlugin].
+    Extension {
+        input: Vec<Expr>,
+        plugin: NoEq<Arc<dyn ExprPlugin>>,
+    },
     Shift {
         input: Box<Expr>,
         periods: i64,
@@ -219,6 +423,8 @@ pub enum Expr {
         input: Box<Expr>,
         by: Box<Expr>,
     },
+    /// Filter the input by its own validity, the input is evaluated once.
+    DropNulls(Box<Expr>),
     /// See postgres window functions
     Window {
         /// Also has the input. i.e. avg("foo")
@@ -242,6 +448,19 @@ pub enum Expr {
     },
     /// Can be used in a select statement to exclude a column from selection
     Except(Box<Expr>),
+    /// Select multiple columns by name. Expanded to one expression per column.
+    Columns(Vec<String>),
+    /// Select all columns of the given data types. Expanded to one expression per column.
+    DtypeColumn(Vec<DataType>),
+    /// Remove columns from a multi-output (wildcard, regex, dtype or `cols`) selection.
+    Exclude(Box<Expr>, Vec<Excluded>),
+}
+
+/// A column name or a data type that is removed from a multi-output selection.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum Excluded {
+    Name(Arc<String>),
+    Dtype(DataType),
 }
 
 impl Expr {
@@ -282,13 +501,13 @@ impl fmt::Debug for Expr {
                 true => write!(f, "{:?} DESC", expr),
                 false => write!(f, "{:?} ASC", expr),
             },
-            SortBy { expr, by, reverse } => match reverse {
-                true => write!(f, "{:?} DESC BY {:?}", expr, by),
-                false => write!(f, "{:?} ASC BY {:?}", expr, by),
-            },
+            SortBy { expr, by, reverse } => {
+                write!(f, "{:?} SORT BY {:?} REVERSE {:?}", expr, by, reverse)
+            }
             Filter { input, by } => {
                 write!(f, "FILTER {:?} BY {:?}", input, by)
             }
+            DropNulls(expr) => write!(f, "DROP NULLS {:?}", expr),
             Take { expr, idx } => {
                 write!(f, "TAKE {:?} AT {:?}", expr, idx)
             }
@@ -302,10 +521,12 @@ impl fmt::Debug for Expr {
                     First(expr) => write!(f, "AGG FIRST {:?}", expr),
                     Last(expr) => write!(f, "AGG LAST {:?}", expr),
                     List(expr) => write!(f, "AGG LIST {:?}", expr),
+                    Flatten(expr) => write!(f, "AGG FLATTEN {:?}", expr),
                     NUnique(expr) => write!(f, "AGG N UNIQUE {:?}", expr),
                     Sum(expr) => write!(f, "AGG SUM {:?}", expr),
                     AggGroups(expr) => write!(f, "AGG GROUPS {:?}", expr),
                     Count(expr) => write!(f, "AGG COUNT {:?}", expr),
+                    NullCount(expr) => write!(f, "AGG NULL COUNT {:?}", expr),
                     Var(expr) => write!(f, "AGG VAR {:?}", expr),
                     Std(expr) => write!(f, "AGG STD {:?}", expr),
                     Quantile { expr, .. } => write!(f, "AGG QUANTILE {:?}", expr),
@@ -322,6 +543,7 @@ impl fmt::Debug for Expr {
                 predicate, truthy, falsy
             ),
             Function { input, .. } => write!(f, "APPLY({:?})", input),
+            Extension { input, plugin } => write!(f, "{}({:?})", plugin.name(), input),
             BinaryFunction {
                 input_a, input_b, ..
             } => write!(f, "BinaryFunction({:?}, {:?})", input_a, input_b),
@@ -333,6 +555,9 @@ impl fmt::Debug for Expr {
             } => write!(f, "SLICE {:?} offset: {} len: {}", input, offset, length),
             Wildcard => write!(f, "*"),
             Except(column) => write!(f, "EXCEPT {:?}", column),
+            Columns(names) => write!(f, "COLUMNS({:?})", names),
+            DtypeColumn(dtypes) => write!(f, "DTYPE_COLUMNS({:?})", dtypes),
+            Exclude(expr, names) => write!(f, "{:?} EXCLUDE {:?}", expr, names),
         }
     }
 }
@@ -360,7 +585,7 @@ pub fn except(name: &str) -> Expr {
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Operator {
     Eq,
     NotEq,
@@ -549,10 +774,41 @@ impl Expr {
     }
 
     /// Rename Column.
+    ///
+    /// A multi-output expression that expands to more than one column cannot be renamed.
+    /// This is reported as an error when the query is collected.
     pub fn alias(self, name: &str) -> Expr {
         Expr::Alias(Box::new(self), Arc::new(name.into()))
     }
 
+    /// Exclude columns from a multi-output expression.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use polars_core::prelude::*;
+    /// use polars_lazy::prelude::*;
+    ///
+    /// // Sum all columns except "id".
+    /// fn example(df: DataFrame) -> LazyFrame {
+    ///       df.lazy()
+    ///         .select(&[col("*").exclude(&["id"]).sum()])
+    /// }
+    /// ```
+    pub fn exclude(self, columns: &[&str]) -> Expr {
+        let columns = columns
+            .iter()
+            .map(|s| Excluded::Name(Arc::new(s.to_string())))
+            .collect();
+        Expr::Exclude(Box::new(self), columns)
+    }
+
+    /// Exclude the columns of the given data types from a multi-output expression.
+    pub fn exclude_dtype(self, dtypes: &[DataType]) -> Expr {
+        let dtypes = dtypes.iter().cloned().map(Excluded::Dtype).collect();
+        Expr::Exclude(Box::new(self), dtypes)
+    }
+
     /// Run is_null operation on `Expr`.
     #[allow(clippy::wrong_self_convention)]
     pub fn is_null(self) -> Self {
@@ -610,6 +866,12 @@ impl Expr {
         AggExpr::List(Box::new(self)).into()
     }
 
+    /// Aggregate the lists of a List column in the group to a single list, by concatenating
+    /// them. Null lists are skipped.
+    pub fn flatten(self) -> Self {
+        AggExpr::Flatten(Box::new(self)).into()
+    }
+
     /// Compute the quantile per group.
     pub fn quantile(self, quantile: f64) -> Self {
         AggExpr::Quantile {
@@ -664,7 +926,7 @@ impl Expr {
         }
         self.apply(
             |s: Series| s.arg_unique().map(|ca| ca.into_series()),
-            Some(DataType::UInt32),
+            Some(IDX_DTYPE),
         )
     }
 
@@ -675,7 +937,7 @@ impl Expr {
         }
         self.apply(
             move |s: Series| Ok(s.argsort(reverse).into_series()),
-            Some(DataType::UInt32),
+            Some(IDX_DTYPE),
         )
     }
 
@@ -728,7 +990,7 @@ impl Expr {
         Expr::Function {
             input: vec![self],
             function: NoEq::new(Arc::new(f)),
-            output_type,
+            output_type: output_type.into(),
             collect_groups: false,
         }
     }
@@ -751,11 +1013,123 @@ impl Expr {
         Expr::Function {
             input: vec![self],
             function: NoEq::new(Arc::new(f)),
-            output_type,
+            output_type: output_type.into(),
             collect_groups: true,
         }
     }
 
+    /// Apply a custom function over a rolling window. See
+    /// [the eager implementation](polars_core::prelude::ChunkWindowCustom::rolling_apply).
+    ///
+    /// The values are cast to `T` first. In a groupby context the windows don't cross the
+    /// group boundaries.
+    pub fn rolling_apply<T, F>(self, window_size: u32, f: F, min_periods: u32) -> Self
+    where
+        T: PolarsNumericType,
+        ChunkedArray<T>: IntoSeries,
+        F: Fn(&[Option<T::Native>]) -> Option<T::Native> + 'static + Send + Sync,
+    {
+        self.apply(
+            move |s: Series| {
+                let s = s.cast::<T>()?;
+                let ca = s.unpack::<T>()?;
+                ca.rolling_apply(window_size, &f, min_periods)
+                    .map(|ca| ca.into_series())
+            },
+            Some(T::get_dtype()),
+        )
+    }
+
+    /// Fold the values of every group, starting from `init`. Null values are skipped and a
+    /// group without valid values is null. See
+    /// [the eager implementation](polars_core::frame::groupby::GroupBy::agg_fold).
+    ///
+    /// The values are cast to `T` first. Outside of a groupby context the whole column is
+    /// folded into a single value. This aggregation is never computed in partitions, as the
+    /// initial state would be folded in once for every partition.
+    pub fn agg_fold<T, F>(self, init: T::Native, f: F) -> Self
+    where
+        T: PolarsNumericType,
+        ChunkedArray<T>: IntoSeries,
+        F: Fn(T::Native, T::Native) -> T::Native + 'static + Send + Sync,
+    {
+        Expr::Extension {
+            input: vec![self],
+            plugin: NoEq::new(Arc::new(AggFold::<T, F> { init, f })),
+        }
+    }
+
+    /// Set the values where `mask` evaluates to `true` to `value`. `value` should evaluate to a
+    /// single value, e.g. a literal, and is cast to the dtype of this expression.
+    pub fn set(self, mask: Expr, value: Expr) -> Self {
+        let function = NoEq::new(Arc::new(move |s: &mut [Series]| {
+            let mask = s[1].bool()?;
+            s[0].set(mask, &s[2])
+        }) as Arc<dyn SeriesUdf>);
+
+        Expr::Function {
+            input: vec![self, mask, value],
+            function,
+            output_type: GetOutput::SameType,
+            collect_groups: false,
+        }
+    }
+
+    /// Get a mask of the values that lie between `low` and `high`. `closed` determines if the
+    /// bounds are inclusive. The bounds may be single values or have the length of this
+    /// expression. A null value results in null. A null bound results in null, or in `false` if
+    /// the value is outside the other bound.
+    #[allow(clippy::wrong_self_convention)]
+    pub fn is_between(self, low: Expr, high: Expr, closed: ClosedInterval) -> Self {
+        let function = NoEq::new(Arc::new(move |s: &mut [Series]| {
+            Ok(s[0].is_between(&s[1], &s[2], closed).into_series())
+        }) as Arc<dyn SeriesUdf>);
+
+        Expr::Function {
+            input: vec![self, low, high],
+            function,
+            output_type: GetOutput::ToType(DataType::Boolean),
+            collect_groups: false,
+        }
+    }
+
+    /// Saturate the values to the bounds `min` and `max`. The bounds may be single values or have
+    /// the length of this expression and are cast to the dtype of this expression. A null bound
+    /// doesn't bound the values on that side.
+    pub fn clip(self, min: Expr, max: Expr) -> Self {
+        let function = NoEq::new(
+            Arc::new(move |s: &mut [Series]| s[0].clip(&s[1], &s[2])) as Arc<dyn SeriesUdf>
+        );
+
+        Expr::Function {
+            input: vec![self, min, max],
+            function,
+            output_type: GetOutput::SameType,
+            collect_groups: false,
+        }
+    }
+
+    /// Compute the absolute values. Signed integers saturate, e.g. the absolute value of
+    /// `i64::MIN` is `i64::MAX`.
+    pub fn abs(self) -> Self {
+        self.map(|s: Series| s.abs(), None)
+    }
+
+    /// Get the indices of the `true` values of a boolean expression. Null values are not
+    /// considered `true`.
+    ///
+    /// This changes the length of the column and can only be used in a select context, an error
+    /// is returned in an aggregation context.
+    pub fn arg_true(self) -> Self {
+        let function = |s: Series| match s.dtype() {
+            DataType::List(_) => Err(PolarsError::InvalidOperation(
+                "arg_true is not supported in an aggregation context".into(),
+            )),
+            _ => s.arg_true().map(|ca| ca.into_series()),
+        };
+        self.map(function, Some(IDX_DTYPE))
+    }
+
     /// Get mask of finite values if dtype is Float
     #[allow(clippy::wrong_self_convention)]
     pub fn is_finite(self) -> Self {
@@ -792,6 +1166,19 @@ impl Expr {
         )
     }
 
+    /// Cast an integer column to the smallest integer type that can hold its values. See
+    /// [the eager implementation](polars_core::series::Series::shrink_dtype).
+    ///
+    /// The output type depends on the data, so [LazyFrame::schema](crate::frame::LazyFrame::schema)
+    /// returns an error for a query that outputs the expression. During optimization the input
+    /// type is assumed.
+    pub fn shrink_dtype(self) -> Self {
+        Expr::Extension {
+            input: vec![self],
+            plugin: NoEq::new(Arc::new(ShrinkDtype)),
+        }
+    }
+
     /// Shift the values in the array by some period. See [the eager implementation](polars_core::series::SeriesTrait::shift).
     pub fn shift(self, periods: i64) -> Self {
         Expr::Shift {
@@ -848,6 +1235,39 @@ impl Expr {
         self.apply(move |s: Series| Ok(s.cum_max(reverse)), None)
     }
 
+    /// Compute the exponentially weighted mean. In an aggregation or window context this is
+    /// computed per group. The output is Float64.
+    #[cfg(feature = "ewma")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ewma")))]
+    pub fn ewm_mean(self, options: EWMOptions) -> Self {
+        self.apply(
+            move |s: Series| s.ewm_mean(&options)?.cast::<Float64Type>(),
+            Some(DataType::Float64),
+        )
+    }
+
+    /// Compute the exponentially weighted variance. In an aggregation or window context this is
+    /// computed per group. The output is Float64.
+    #[cfg(feature = "ewma")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ewma")))]
+    pub fn ewm_var(self, options: EWMOptions) -> Self {
+        self.apply(
+            move |s: Series| s.ewm_var(&options)?.cast::<Float64Type>(),
+            Some(DataType::Float64),
+        )
+    }
+
+    /// Compute the exponentially weighted standard deviation. In an aggregation or window
+    /// context this is computed per group. The output is Float64.
+    #[cfg(feature = "ewma")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ewma")))]
+    pub fn ewm_std(self, options: EWMOptions) -> Self {
+        self.apply(
+            move |s: Series| s.ewm_std(&options)?.cast::<Float64Type>(),
+            Some(DataType::Float64),
+        )
+    }
+
     /// Fill missing value with next non-null.
     pub fn backward_fill(self) -> Self {
         self.apply(
@@ -953,6 +1373,26 @@ impl Expr {
         AggExpr::Count(Box::new(self)).into()
     }
 
+    /// Count the null values of the Series
+    /// or
+    /// Get the null counts of the group by operation.
+    pub fn null_count(self) -> Self {
+        AggExpr::NullCount(Box::new(self)).into()
+    }
+
+    /// Check if the Series or the groups of the group by operation have null values.
+    pub fn has_nulls(self) -> Self {
+        self.null_count().gt(lit(0u32))
+    }
+
+    /// Drop the null values.
+    /// In the aggregation context the null values are dropped from the groups, such that they can
+    /// be aggregated further, e.g. `col("foo").drop_nulls().first()` gets the first value that
+    /// is not null.
+    pub fn drop_nulls(self) -> Self {
+        Expr::DropNulls(Box::new(self))
+    }
+
     /// Standard deviation of the values of the Series
     pub fn std(self) -> Self {
         AggExpr::Std(Box::new(self)).into()
@@ -1022,6 +1462,17 @@ impl Expr {
         )
     }
 
+    /// Compute a stable hash of every value, using `seed` to initialize the hasher.
+    ///
+    /// The hashes only depend on the data and the `seed`, not on the polars version or platform.
+    /// See [DataFrame::hash_rows](polars_core::frame::DataFrame::hash_rows) for the algorithm.
+    #[cfg(feature = "row_hash")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "row_hash")))]
+    pub fn hash(self, seed: u64) -> Expr {
+        let function = move |s: Series| s.stable_hash(seed).map(|ca| ca.into_series());
+        self.map(function, Some(DataType::UInt64))
+    }
+
     /// Get the year of a Date32/Date64
     #[cfg(feature = "temporal")]
     pub fn year(self) -> Expr {
@@ -1073,12 +1524,34 @@ impl Expr {
         self.map(function, Some(DataType::UInt32))
     }
 
-    /// Sort this column by the ordering of another column.
-    /// Can also be used in a groupby context to sort the groups.
-    pub fn sort_by(self, by: Expr, reverse: bool) -> Expr {
+    /// Sort this column by the ordering of other columns.
+    ///
+    /// The first expression in `by` determines the ordering until it has equal values, those are
+    /// ordered by the next expression and so on. `reverse` must have a boolean for every
+    /// expression in `by`. The sort is stable, so rows that are equal in all `by` expressions
+    /// keep their order. Null values in `by` are sorted first, or last if reversed.
+    ///
+    /// In a selection context the whole column is sorted. In a groupby context the values are
+    /// sorted within their groups, so that subsequent aggregations like `first` and `last`
+    /// operate on the sorted groups.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use polars_core::prelude::*;
+    /// use polars_lazy::prelude::*;
+    ///
+    /// // the earliest price per product
+    /// fn example(df: DataFrame) -> LazyFrame {
+    ///     df.lazy()
+    ///         .groupby(vec![col("product")])
+    ///         .agg(vec![col("price").sort_by(vec![col("ts")], vec![false]).first()])
+    /// }
+    /// ```
+    pub fn sort_by(self, by: Vec<Expr>, reverse: Vec<bool>) -> Expr {
         Expr::SortBy {
             expr: Box::new(self),
-            by: Box::new(by),
+            by,
             reverse,
         }
     }
@@ -1098,6 +1571,20 @@ impl Expr {
         })
     }
 
+    /// Find the indices at which the values of `element` would be inserted to keep this sorted
+    /// column sorted. Equal values are inserted on the left.
+    #[cfg(feature = "search_sorted")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "search_sorted")))]
+    pub fn search_sorted(self, element: Expr) -> Expr {
+        let function = |s: Series, element: Series| {
+            Ok(s.search_sorted(&element, SearchSortedSide::Left)?
+                .into_series())
+        };
+        map_binary_lazy_field(self, element, function, |_schema, _ctxt, l, _r| {
+            Some(Field::new(l.name(), IDX_DTYPE))
+        })
+    }
+
     #[cfg(feature = "is_first")]
     #[cfg_attr(docsrs, doc(cfg(feature = "is_first")))]
     #[allow(clippy::wrong_self_convention)]
@@ -1118,9 +1605,276 @@ impl Expr {
             Some(Field::new(l.name(), l.data_type().clone()))
         })
     }
+
+    /// Add `other`. The result is null where the addition overflows.
+    #[cfg(feature = "checked_arithmetic")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "checked_arithmetic")))]
+    pub fn checked_add(self, other: Expr) -> Expr {
+        checked_arithmetic(self, other, |a, b| a.checked_add(b))
+    }
+
+    /// Subtract `other`. The result is null where the subtraction overflows.
+    #[cfg(feature = "checked_arithmetic")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "checked_arithmetic")))]
+    pub fn checked_sub(self, other: Expr) -> Expr {
+        checked_arithmetic(self, other, |a, b| a.checked_sub(b))
+    }
+
+    /// Multiply by `other`. The result is null where the multiplication overflows.
+    #[cfg(feature = "checked_arithmetic")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "checked_arithmetic")))]
+    pub fn checked_mul(self, other: Expr) -> Expr {
+        checked_arithmetic(self, other, |a, b| a.checked_mul(b))
+    }
+
+    /// Divide by `other`. The result is null where the divisor is zero or the division overflows.
+    #[cfg(feature = "checked_arithmetic")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "checked_arithmetic")))]
+    pub fn checked_div(self, other: Expr) -> Expr {
+        checked_arithmetic(self, other, |a, b| a.checked_div(b))
+    }
+
+    /// Divide by `other` and round towards negative infinity. The result is null where the divisor
+    /// is zero or the division overflows.
+    ///
+    /// Note that the `/` operator truncates integer division towards zero.
+    #[cfg(feature = "checked_arithmetic")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "checked_arithmetic")))]
+    pub fn floor_div(self, other: Expr) -> Expr {
+        checked_arithmetic(self, other, |a, b| a.floor_div(b))
+    }
+
+    /// Make the arithmetic operators `+`, `-`, `*` and `/` of this expression strict. Instead of
+    /// overflowing, an error with the row index of the first overflow is returned. A division by
+    /// zero is an error as well.
+    ///
+    /// Only the operators that are nested in other operators or aliases are affected, not
+    /// the operators in the inputs of functions or aggregations.
+    #[cfg(feature = "checked_arithmetic")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "checked_arithmetic")))]
+    pub fn strict_overflow(self) -> Expr {
+        match self {
+            Expr::BinaryExpr { left, op, right } => {
+                let left = left.strict_overflow();
+                let right = right.strict_overflow();
+                let function: fn(&Series, &Series) -> Result<Series> = match op {
+                    Operator::Plus => |a, b| a.checked_add(b),
+                    Operator::Minus => |a, b| a.checked_sub(b),
+                    Operator::Multiply => |a, b| a.checked_mul(b),
+                    Operator::Divide => |a, b| a.checked_div(b),
+                    _ => return binary_expr(left, op, right),
+                };
+                checked_arithmetic(left, right, move |a, b| {
+                    let out = function(a, b)?;
+                    check_overflow(a, b, &out)?;
+                    Ok(out)
+                })
+            }
+            Expr::Alias(expr, name) => Expr::Alias(Box::new(expr.strict_overflow()), name),
+            expr => expr,
+        }
+    }
+
+    /// Concatenate the string values, separated by `delimiter`.
+    ///
+    /// In a groupby aggregation this produces a single string per group, in the order of
+    /// the values within the group. Null values are skipped, unless a `null_value` placeholder is given.
+    #[cfg(feature = "concat_str")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "concat_str")))]
+    pub fn str_concat(self, delimiter: &str, null_value: Option<&str>) -> Expr {
+        let delimiter = delimiter.to_string();
+        let null_value = null_value.map(|s| s.to_string());
+        self.map(
+            move |s| s.str_concat(&delimiter, null_value.as_deref()),
+            Some(DataType::Utf8),
+        )
+    }
+
+    /// Join the string values of every list, separated by `delimiter`.
+    ///
+    /// Null values are skipped, unless a `null_value` placeholder is given.
+    #[cfg(feature = "concat_str")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "concat_str")))]
+    pub fn lst_join(self, delimiter: &str, null_value: Option<&str>) -> Expr {
+        let delimiter = delimiter.to_string();
+        let null_value = null_value.map(|s| s.to_string());
+        self.map(
+            move |s| s.lst_join(&delimiter, null_value.as_deref()),
+            Some(DataType::Utf8),
+        )
+    }
+
+    /// Get the string namespace of this expression.
+    pub fn str(self) -> StringNameSpace {
+        StringNameSpace {
+            expr: self,
+            options: ParseOptions::default(),
+        }
+    }
+
+    /// Get the struct namespace of this expression.
+    #[cfg(feature = "dtype-struct")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "dtype-struct")))]
+    pub fn struct_(self) -> StructNameSpace {
+        StructNameSpace { expr: self }
+    }
+}
+
+/// Specialized expressions for Utf8 columns.
+pub struct StringNameSpace {
+    expr: Expr,
+    options: ParseOptions,
+}
+
+impl StringNameSpace {
+    /// Set the options that are used by the parsing expressions, e.g. whitespace stripping
+    /// or a thousands separator. The `strict` flag of the parsing expressions takes precedence.
+    pub fn with_parse_options(mut self, options: ParseOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    fn parse<F>(self, strict: bool, f: F, dtype: DataType) -> Expr
+    where
+        F: Fn(&Utf8Chunked, &ParseOptions) -> Result<Series> + Send + Sync + 'static,
+    {
+        let options = ParseOptions {
+            strict,
+            ..self.options
+        };
+        self.expr.map(move |s| f(s.utf8()?, &options), Some(dtype))
+    }
+
+    /// Parse the string values to `Int64`.
+    ///
+    /// If `strict`, a value that cannot be parsed raises an error, otherwise it becomes null.
+    pub fn to_integer(self, strict: bool) -> Expr {
+        self.parse(
+            strict,
+            |ca, options| ca.parse_int64(options).map(|ca| ca.into_series()),
+            DataType::Int64,
+        )
+    }
+
+    /// Parse the string values to `Float64`.
+    ///
+    /// If `strict`, a value that cannot be parsed raises an error, otherwise it becomes null.
+    pub fn to_float(self, strict: bool) -> Expr {
+        self.parse(
+            strict,
+            |ca, options| ca.parse_float64(options).map(|ca| ca.into_series()),
+            DataType::Float64,
+        )
+    }
+
+    /// Parse the string values `"true"` and `"false"` (case insensitive) to `Boolean`.
+    /// Other values become null.
+    pub fn to_boolean(self) -> Expr {
+        let strict = self.options.strict;
+        self.parse(
+            strict,
+            |ca, options| ca.parse_boolean(options).map(|ca| ca.into_series()),
+            DataType::Boolean,
+        )
+    }
+
+    /// Parse the string values to a `Date32` or `Date64` `dtype` with a strptime `fmt`. If `fmt`
+    /// is `None`, the format is inferred from the first non-null values.
+    ///
+    /// Values that cannot be parsed become null, unless the parse options are strict.
+    #[cfg(feature = "temporal")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "temporal")))]
+    pub fn strptime(self, dtype: DataType, fmt: Option<&str>) -> Expr {
+        let fmt = fmt.map(|fmt| fmt.to_string());
+        let strict = self.options.strict;
+        let output_type = dtype.clone();
+        self.expr.map(
+            move |s| s.utf8()?.strptime(&dtype, fmt.as_deref(), strict),
+            Some(output_type),
+        )
+    }
+
+    /// Get the length of the string values in bytes.
+    #[cfg(feature = "strings")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "strings")))]
+    pub fn lengths(self) -> Expr {
+        self.expr.map(
+            |s| s.str_lengths().map(|ca| ca.into_series()),
+            Some(DataType::UInt32),
+        )
+    }
+
+    /// Get the length of the string values in chars.
+    #[cfg(feature = "strings")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "strings")))]
+    pub fn n_chars(self) -> Expr {
+        self.expr.map(
+            |s| s.str_n_chars().map(|ca| ca.into_series()),
+            Some(DataType::UInt32),
+        )
+    }
+
+    /// Take a substring of the string values that starts at byte `offset` and has at most
+    /// `length` bytes. A negative `offset` counts from the end of the string. The slice never
+    /// splits a char.
+    #[cfg(feature = "strings")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "strings")))]
+    pub fn slice(self, offset: i64, length: Option<u64>) -> Expr {
+        self.expr
+            .map(move |s| s.str_slice(offset, length), Some(DataType::Utf8))
+    }
+}
+
+/// Specialized expressions for Struct columns.
+#[cfg(feature = "dtype-struct")]
+#[cfg_attr(docsrs, doc(cfg(feature = "dtype-struct")))]
+pub struct StructNameSpace {
+    expr: Expr,
+}
+
+#[cfg(feature = "dtype-struct")]
+impl StructNameSpace {
+    /// Get the field with the given `name` as a column with that name.
+    pub fn field(self, name: &str) -> Expr {
+        let alias = name.to_string();
+        let name = alias.clone();
+        let field_name = alias.clone();
+        let function = move |s: &mut [Series]| s[0].struct_()?.field_by_name(&name);
+        let output_field =
+            move |_: &Schema, _: Context, fields: &[Field]| match fields[0].data_type() {
+                DataType::Struct(struct_fields) => struct_fields
+                    .iter()
+                    .find(|fld| fld.name() == &field_name)
+                    .cloned()
+                    .ok_or_else(|| {
+                        PolarsError::NotFound(format!(
+                            "struct field {} not found in {}",
+                            field_name,
+                            fields[0].name()
+                        ))
+                    }),
+                dt => Err(PolarsError::DataTypeMisMatch(
+                    format!("expected a struct, got {:?}", dt).into(),
+                )),
+            };
+        Expr::Function {
+            input: vec![self.expr],
+            function: NoEq::new(Arc::new(function)),
+            output_type: GetOutput::Map(NoEq::new(Arc::new(output_field))),
+            collect_groups: false,
+        }
+        .alias(&alias)
+    }
 }
 
 /// Create a Column Expression based on a column name.
+///
+/// `col("*")` selects all columns. A name that starts with `^` and ends with `$` is
+/// interpreted as a regular expression and selects all columns that match it, e.g.
+/// `col("^sensor_\\d+$")`.
+///
+/// Multi-output expressions are expanded to one expression per selected column, before
+/// the query is optimized.
 pub fn col(name: &str) -> Expr {
     match name {
         "*" => Expr::Wildcard,
@@ -1128,6 +1882,21 @@ pub fn col(name: &str) -> Expr {
     }
 }
 
+/// Select multiple columns by name.
+pub fn cols(names: &[&str]) -> Expr {
+    Expr::Columns(names.iter().map(|name| name.to_string()).collect())
+}
+
+/// Select all columns that have the given data type.
+pub fn dtype_col(dtype: &DataType) -> Expr {
+    Expr::DtypeColumn(vec![dtype.clone()])
+}
+
+/// Select all columns that have one of the given data types.
+pub fn dtype_cols(dtypes: &[DataType]) -> Expr {
+    Expr::DtypeColumn(dtypes.to_vec())
+}
+
 /// Count the number of values in this Expression.
 pub fn count(name: &str) -> Expr {
     match name {
@@ -1186,6 +1955,24 @@ where
     }
 }
 
+/// Binary arithmetic with the same output field as the arithmetic operators.
+#[cfg(feature = "checked_arithmetic")]
+fn checked_arithmetic<F: 'static>(a: Expr, b: Expr, f: F) -> Expr
+where
+    F: Fn(&Series, &Series) -> Result<Series> + Send + Sync,
+{
+    map_binary_lazy_field(
+        a,
+        b,
+        move |a, b| f(&a, &b),
+        |_schema, _ctxt, l, r| {
+            get_supertype(l.data_type(), r.data_type())
+                .ok()
+                .map(|dtype| Field::new(l.name(), dtype))
+        },
+    )
+}
+
 /// Binary function where the output type is determined at runtime when the schema is known.
 pub fn map_binary_lazy_field<F: 'static, Fld: 'static>(
     a: Expr,
@@ -1299,6 +2086,29 @@ make_literal!(u64, UInt64);
 /// The literal Null
 pub struct Null {}
 
+/// The literal Null, e.g. `lit(NULL)`.
+///
+/// A Null literal adopts the data type of the other operand in binary expressions.
+/// To create a null column, give it a data type with [lit_null] or [Expr::cast].
+pub const NULL: Null = Null {};
+
+/// A Null literal of the given data type.
+///
+/// # Example
+///
+/// ```rust
+/// use polars_core::prelude::*;
+/// use polars_lazy::prelude::*;
+///
+/// // Add a placeholder column with only null values.
+/// fn example(lf: LazyFrame) -> LazyFrame {
+///     lf.with_column(lit_null(DataType::Float64).alias("placeholder"))
+/// }
+/// ```
+pub fn lit_null(dtype: DataType) -> Expr {
+    lit(NULL).cast(dtype)
+}
+
 impl Literal for Null {
     fn lit(self) -> Expr {
         Expr::Literal(LiteralValue::Null)
@@ -1319,6 +2129,16 @@ impl Literal for NaiveDate {
     }
 }
 
+/// A [Duration](DataType::Duration) literal in milliseconds, the sum of the given components.
+/// E.g. `col("date") + duration(1, 12, 0, 0, 0)` shifts the dates a day and a half forward.
+#[cfg(all(feature = "temporal", feature = "dtype-date64"))]
+pub fn duration(days: i64, hours: i64, minutes: i64, seconds: i64, milliseconds: i64) -> Expr {
+    let millis = (((days * 24 + hours) * 60 + minutes) * 60 + seconds) * 1000 + milliseconds;
+    DurationMillisecondChunked::from_millis("duration", &[millis])
+        .into_series()
+        .lit()
+}
+
 impl Literal for Series {
     fn lit(self) -> Expr {
         Expr::Literal(LiteralValue::Series(NoEq::new(self)))
@@ -1,26 +1,44 @@
 //! Lazy variant of a [DataFrame](polars_core::frame::DataFrame).
 #[cfg(any(feature = "parquet", feature = "csv-file"))]
 use polars_core::datatypes::PlHashMap;
+use polars_core::frame::groupby::GroupByMethod;
 use polars_core::frame::hash_join::JoinType;
 use polars_core::prelude::*;
-use polars_core::toggle_string_cache;
+use polars_core::StringCacheHolder;
 use std::sync::Arc;
 
+use crate::logical_plan::iterator::ArenaExprIter;
 use crate::logical_plan::optimizer::aggregate_pushdown::AggregatePushdown;
 #[cfg(any(feature = "parquet", feature = "csv-file"))]
 use crate::logical_plan::optimizer::aggregate_scan_projections::AggScanProjection;
+#[cfg(feature = "private")]
+use crate::logical_plan::optimizer::join_pruning::JoinPrune;
 use crate::logical_plan::optimizer::simplify_expr::SimplifyExprRule;
+use crate::logical_plan::optimizer::sort_pruning::SortPrune;
 use crate::logical_plan::optimizer::stack_opt::{OptimizationRule, StackOptimizer};
 use crate::logical_plan::optimizer::{
     predicate_pushdown::PredicatePushDown, projection_pushdown::ProjectionPushDown,
 };
+#[cfg(feature = "csv-file")]
+use crate::logical_plan::SpooledFile;
 use crate::physical_plan::state::ExecutionState;
 #[cfg(any(feature = "parquet", feature = "csv-file"))]
 use crate::prelude::aggregate_scan_projections::agg_projection;
 use crate::prelude::simplify_expr::SimplifyBooleanRule;
-use crate::utils::combine_predicates_expr;
-use crate::{logical_plan::FETCH_ROWS, prelude::*};
-use polars_io::csv::NullValues;
+use crate::prelude::*;
+use crate::utils::{combine_predicates_expr, string_cache_reason};
+#[cfg(feature = "csv-file")]
+use polars_io::csv::{
+    file_row_aligned_byte_ranges, row_aligned_byte_ranges, BatchedCsvWriter, CsvWriterOptions,
+};
+use polars_io::csv::{InferenceOptions, NullValues};
+#[cfg(feature = "parquet")]
+use polars_io::parquet::{BatchedParquetWriter, ParquetWriterOptions};
+use polars_io::RowCount;
+#[cfg(feature = "csv-file")]
+use std::io::{BufWriter, Write};
+#[cfg(any(feature = "parquet", feature = "csv-file"))]
+use std::{fs::File, path::Path};
 
 #[derive(Clone)]
 #[cfg(feature = "csv-file")]
@@ -37,6 +55,9 @@ pub struct LazyCsvReader<'a> {
     low_memory: bool,
     comment_char: Option<u8>,
     null_values: Option<NullValues>,
+    eol_char: u8,
+    spooled: Option<Arc<SpooledFile>>,
+    inference_options: InferenceOptions,
 }
 
 #[cfg(feature = "csv-file")]
@@ -55,9 +76,52 @@ impl<'a> LazyCsvReader<'a> {
             low_memory: false,
             comment_char: None,
             null_values: None,
+            eol_char: b'\n',
+            spooled: None,
+            inference_options: InferenceOptions::default(),
         }
     }
 
+    /// Scan CSV data from a reader, e.g. stdin. A stream of at most
+    /// [SPOOL_MEMORY_LIMIT](LazyCsvReader::SPOOL_MEMORY_LIMIT) bytes is held in memory, a larger
+    /// stream is copied to a temporary file, without holding it in memory. The query can be
+    /// optimized like a scan of a file and can be executed multiple times. The temporary file is
+    /// removed once the last [LazyFrame] that scans it is dropped.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use polars_core::prelude::*;
+    /// use polars_lazy::prelude::*;
+    ///
+    /// fn example() -> Result<DataFrame> {
+    ///     LazyCsvReader::from_reader(std::io::stdin())?
+    ///         .finish()
+    ///         .filter(col("a").gt(lit(1)))
+    ///         .select(vec![col("b")])
+    ///         .collect()
+    /// }
+    /// ```
+    pub fn from_reader<R: std::io::Read>(reader: R) -> Result<Self> {
+        Self::from_reader_with_memory_limit(reader, Self::SPOOL_MEMORY_LIMIT)
+    }
+
+    /// The number of bytes of a stream that [from_reader](LazyCsvReader::from_reader) holds in
+    /// memory.
+    pub const SPOOL_MEMORY_LIMIT: usize = 64 * 1024 * 1024;
+
+    /// Scan CSV data from a reader, holding a stream of at most `memory_limit` bytes in memory.
+    /// See [from_reader](LazyCsvReader::from_reader).
+    pub fn from_reader_with_memory_limit<R: std::io::Read>(
+        reader: R,
+        memory_limit: usize,
+    ) -> Result<Self> {
+        let spooled = SpooledFile::from_reader(reader, memory_limit)?;
+        let mut csv_reader = Self::new(spooled.path().to_string_lossy().into_owned());
+        csv_reader.spooled = Some(Arc::new(spooled));
+        Ok(csv_reader)
+    }
+
     /// Try to stop parsing when `n` rows are parsed. During multithreaded parsing the upper bound `n` cannot
     /// be guaranteed.
     pub fn with_stop_after_n_rows(mut self, num_rows: Option<usize>) -> Self {
@@ -114,6 +178,20 @@ impl<'a> LazyCsvReader<'a> {
         self
     }
 
+    /// Set the character that ends a line. With the default `b'\n'`, `\r\n` and a
+    /// bare `\r` also end a line.
+    pub fn with_eol_char(mut self, eol_char: u8) -> Self {
+        self.eol_char = eol_char;
+        self
+    }
+
+    /// Set the options that control which data types schema inference may produce. This has no
+    /// effect if the schema is given with [with_schema](LazyCsvReader::with_schema).
+    pub fn with_inference_options(mut self, options: InferenceOptions) -> Self {
+        self.inference_options = options;
+        self
+    }
+
     /// Cache the DataFrame after reading.
     pub fn with_cache(mut self, cache: bool) -> Self {
         self.cache = cache;
@@ -127,8 +205,8 @@ impl<'a> LazyCsvReader<'a> {
     }
 
     pub fn finish(self) -> LazyFrame {
-        let mut lf: LazyFrame = LogicalPlanBuilder::scan_csv(
-            self.path,
+        let lp = LogicalPlanBuilder::scan_csv_spooled(
+            self.path.into(),
             self.delimiter,
             self.has_header,
             self.ignore_errors,
@@ -140,29 +218,91 @@ impl<'a> LazyCsvReader<'a> {
             self.low_memory,
             self.comment_char,
             self.null_values,
+            self.eol_char,
+            self.spooled,
+            &self.inference_options,
         )
-        .build()
-        .into();
+        .build();
+        let mut lf: LazyFrame = lp.into();
         lf.opt_state.agg_scan_projection = true;
         lf
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+/// Options of a join between two LazyFrames. See [join_with_options](LazyFrame::join_with_options).
+#[derive(Clone, Debug)]
 pub struct JoinOptions {
+    pub how: JoinType,
+    /// Suffix of the columns of the right frame that also exist in the left frame.
+    pub suffix: String,
+    /// Allow to execute both inputs of the join in parallel. This is skipped if both inputs are
+    /// in memory DataFrames or when they read from the same source.
     pub allow_parallel: bool,
+    /// Always execute both inputs of the join in parallel.
     pub force_parallel: bool,
 }
 
 impl Default for JoinOptions {
     fn default() -> Self {
         JoinOptions {
+            how: JoinType::Inner,
+            suffix: "_right".into(),
             allow_parallel: true,
             force_parallel: false,
         }
     }
 }
 
+impl JoinOptions {
+    /// Check that the options are valid. The suffix may not be empty, as the suffixed columns
+    /// would clash with the columns of the left frame.
+    pub(crate) fn validate(&self) -> Result<()> {
+        if self.suffix.is_empty() {
+            return Err(PolarsError::ValueError(
+                "the suffix of a join may not be empty".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Options of [collect_with](LazyFrame::collect_with).
+///
+/// The default doesn't rechunk, so the chunks produced by the query are returned as is.
+#[derive(Clone, Debug)]
+pub struct CollectOptions {
+    /// Rechunk every column of the result into a single chunk.
+    pub rechunk: bool,
+    /// Rechunk the columns in parallel. This is faster, but may use more memory at peak.
+    pub parallel_rechunk: bool,
+}
+
+impl Default for CollectOptions {
+    fn default() -> Self {
+        CollectOptions {
+            rechunk: false,
+            parallel_rechunk: true,
+        }
+    }
+}
+
+/// Hold the global string cache during the execution of a query, if it is turned on with
+/// [with_string_cache](LazyFrame::with_string_cache) or the plan combines categoricals of
+/// different inputs.
+fn hold_string_cache(
+    opt_state: &OptState,
+    lp_top: Node,
+    lp_arena: &Arena<ALogicalPlan>,
+    expr_arena: &Arena<AExpr>,
+) -> Option<StringCacheHolder> {
+    if opt_state.global_string_cache || string_cache_reason(lp_top, lp_arena, expr_arena).is_some()
+    {
+        Some(StringCacheHolder::hold())
+    } else {
+        None
+    }
+}
+
 pub trait IntoLazy {
     fn lazy(self) -> LazyFrame;
 }
@@ -211,8 +351,28 @@ pub struct OptState {
     /// Make sure that all needed columns are scannedn
     pub agg_scan_projection: bool,
     pub aggregate_pushdown: bool,
+    /// Execute the query under the global string cache. A query that combines categoricals of
+    /// different inputs in a join or a concatenation always uses the cache.
     pub global_string_cache: bool,
     pub join_pruning: bool,
+    /// Remove sorts of which the input is already sorted and sort before projections that widen
+    /// the frame.
+    pub sort_pruning: bool,
+    /// Skip the chunks of which the statistics show that they don't match a filter predicate.
+    pub chunk_pruning: bool,
+    /// Intern a single Utf8 groupby key once per query, so that the groupbys and window
+    /// expressions on that key group the interned codes instead of hashing the strings.
+    pub intern_groupby_keys: bool,
+    /// Spill sorts and groupbys with an input larger than this number of bytes to disk.
+    #[cfg(feature = "spill")]
+    pub memory_budget: Option<usize>,
+    /// Number of bytes of the scanned file that are processed per batch by
+    /// [sink_batches](LazyFrame::sink_batches).
+    pub sink_batch_size: usize,
+    /// Abort the query if a node outputs more rows than this.
+    pub max_output_rows: Option<usize>,
+    /// Abort the query if the estimated size of the output of a node exceeds this number of bytes.
+    pub memory_limit_bytes: Option<usize>,
 }
 
 impl Default for OptState {
@@ -222,17 +382,182 @@ impl Default for OptState {
             predicate_pushdown: true,
             type_coercion: true,
             simplify_expr: true,
-            global_string_cache: true,
+            global_string_cache: false,
             join_pruning: true,
+            sort_pruning: true,
+            chunk_pruning: true,
+            intern_groupby_keys: false,
             // will be toggled by a scan operation such as csv scan or parquet scan
             agg_scan_projection: false,
             aggregate_pushdown: false,
+            #[cfg(feature = "spill")]
+            memory_budget: None,
+            sink_batch_size: 16 * 1024 * 1024,
+            max_output_rows: None,
+            memory_limit_bytes: None,
+        }
+    }
+}
+
+impl OptState {
+    fn execution_state(&self, fetch_rows: Option<usize>) -> ExecutionState {
+        let mut state = ExecutionState::new();
+        state.fetch_rows = fetch_rows;
+        state.chunk_pruning = self.chunk_pruning;
+        state.intern_keys = self.intern_groupby_keys;
+        #[cfg(feature = "spill")]
+        if self.memory_budget.is_some() {
+            state.memory_budget = self.memory_budget;
+        }
+        state.max_output_rows = self.max_output_rows;
+        state.memory_limit = self.memory_limit_bytes;
+        state
+    }
+}
+
+/// Whether every output row of the expression only depends on the input values of the same
+/// row, such that it can be evaluated on any batch of rows.
+fn is_elementwise(node: Node, expr_arena: &Arena<AExpr>) -> bool {
+    let mut has_column = false;
+    let elementwise = expr_arena.iter(node).all(|(_, e)| match e {
+        AExpr::Column(_) => {
+            has_column = true;
+            true
+        }
+        AExpr::Literal(lv) => !matches!(lv, LiteralValue::Series(_) | LiteralValue::Range { .. }),
+        AExpr::Extension { plugin, .. } => plugin.is_elementwise() && !plugin.is_aggregation(),
+        AExpr::Alias(..)
+        | AExpr::BinaryExpr { .. }
+        | AExpr::Not(_)
+        | AExpr::IsNull(_)
+        | AExpr::IsNotNull(_)
+        | AExpr::Cast { .. }
+        | AExpr::Ternary { .. } => true,
+        _ => false,
+    });
+    // an expression without columns, e.g. `lit(1)`, produces a single row per batch
+    elementwise && has_column
+}
+
+/// Find the csv scan at the bottom of a chain of elementwise projections and filters. Such a
+/// plan can be executed on any part of the file.
+fn streamable_csv_scan(
+    mut node: Node,
+    lp_arena: &Arena<ALogicalPlan>,
+    expr_arena: &Arena<AExpr>,
+) -> Option<Node> {
+    use ALogicalPlan::*;
+    loop {
+        match lp_arena.get(node) {
+            Selection { input, predicate } if is_elementwise(*predicate, expr_arena) => {
+                node = *input
+            }
+            Projection { input, expr, .. }
+            | LocalProjection { input, expr, .. }
+            | HStack {
+                input, exprs: expr, ..
+            } if expr.iter().all(|e| is_elementwise(*e, expr_arena)) => node = *input,
+            #[cfg(feature = "csv-file")]
+            CsvScan {
+                options,
+                predicate,
+                aggregate,
+                ..
+            } if aggregate.is_empty()
+                && predicate.map_or(true, |p| is_elementwise(p, expr_arena))
+                && options.stop_after_n_rows.is_none()
+                && options.skip_rows == 0 =>
+            {
+                return Some(node)
+            }
+            _ => return None,
         }
     }
 }
 
-/// AllowedOptimizations
-pub type AllowedOptimizations = OptState;
+/// Execute the plan on consecutive byte ranges of the file scanned by the csv scan `scan`.
+/// The ranges are aligned to the rows of the file, so a row is never split over two batches.
+#[cfg(feature = "csv-file")]
+fn sink_csv_scan<F>(
+    opt_state: &OptState,
+    lp_top: Node,
+    scan: Node,
+    lp_arena: &Arena<ALogicalPlan>,
+    expr_arena: &Arena<AExpr>,
+    f: &mut F,
+    n_rows: &mut usize,
+) -> Result<()>
+where
+    F: FnMut(DataFrame) -> Result<()>,
+{
+    let batch_size = opt_state.sink_batch_size;
+    let ranges = match lp_arena.get(scan) {
+        ALogicalPlan::CsvScan { path, options, .. } => {
+            match options.spooled.as_ref().and_then(|spooled| spooled.data()) {
+                Some(data) => row_aligned_byte_ranges(&data[..], batch_size, options.eol_char),
+                None => file_row_aligned_byte_ranges(path, batch_size, options.eol_char)?,
+            }
+        }
+        _ => unreachable!(),
+    };
+
+    for range in ranges {
+        // the planner takes the nodes out of the arenas, so every batch gets its own copy
+        let mut lp_arena = lp_arena.clone();
+        let mut expr_arena = expr_arena.clone();
+        if let ALogicalPlan::CsvScan { options, .. } = lp_arena.get_mut(scan) {
+            options.byte_range = Some(range);
+            options.cache = false;
+        }
+        let mut physical_plan = DefaultPlanner::default().create_physical_plan(
+            lp_top,
+            &mut lp_arena,
+            &mut expr_arena,
+        )?;
+        let df = physical_plan.execute(&opt_state.execution_state(None))?;
+        sink_chunks(df, f, n_rows)?;
+    }
+    Ok(())
+}
+
+/// Pass every chunk of `df` to `f`.
+fn sink_chunks<F>(mut df: DataFrame, f: &mut F, n_rows: &mut usize) -> Result<()>
+where
+    F: FnMut(DataFrame) -> Result<()>,
+{
+    df.align_chunks();
+    let chunk_lengths = match df.get_columns().first() {
+        Some(s) => s.chunk_lengths().collect::<Vec<_>>(),
+        None => return Ok(()),
+    };
+    let mut offset = 0;
+    for len in chunk_lengths.into_iter().filter(|len| *len > 0) {
+        f(df.slice(offset as i64, len))?;
+        offset += len;
+        *n_rows += len;
+    }
+    Ok(())
+}
+
+/// The optimizations that are allowed to pass a [map](LazyFrame::map) node. By default both are
+/// blocked and the node is an optimization barrier.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct AllowedOptimizations {
+    /// Allow projection pushdown to pass the node.
+    pub projection_pushdown: bool,
+    /// Allow predicate pushdown to pass the node.
+    pub predicate_pushdown: bool,
+}
+
+impl AllowedOptimizations {
+    /// Allow all optimizations to pass the node.
+    pub fn all() -> Self {
+        AllowedOptimizations {
+            projection_pushdown: true,
+            predicate_pushdown: true,
+        }
+    }
+}
 
 impl LazyFrame {
     /// Create a LazyFrame directly from a parquet scan.
@@ -283,7 +608,7 @@ impl LazyFrame {
     pub(crate) fn into_alp(self) -> (Node, Arena<AExpr>, Arena<ALogicalPlan>) {
         let mut expr_arena = Arena::with_capacity(64);
         let mut lp_arena = Arena::with_capacity(32);
-        let root = to_alp(self.logical_plan, &mut expr_arena, &mut lp_arena);
+        let root = to_alp(self.logical_plan, &mut expr_arena, &mut lp_arena).unwrap();
         (root, expr_arena, lp_arena)
     }
 
@@ -317,7 +642,9 @@ impl LazyFrame {
         self
     }
 
-    /// Toggle global string cache.
+    /// Toggle global string cache. If off, the cache is still used by a query that combines
+    /// categoricals of different inputs, see
+    /// [describe_optimized_plan](LazyFrame::describe_optimized_plan).
     pub fn with_string_cache(mut self, toggle: bool) -> Self {
         self.opt_state.global_string_cache = toggle;
         self
@@ -329,18 +656,127 @@ impl LazyFrame {
         self
     }
 
+    /// Toggle the removal of a sort of which the input is already sorted by the same columns,
+    /// and sorting before a projection that adds columns computed row by row.
+    pub fn with_sort_pruning(mut self, toggle: bool) -> Self {
+        self.opt_state.sort_pruning = toggle;
+        self
+    }
+
+    /// Toggle skipping the chunks of which the minimum and maximum show that they can't match
+    /// a `col(..) <op> lit(..)` filter predicate.
+    pub fn with_chunk_pruning(mut self, toggle: bool) -> Self {
+        self.opt_state.chunk_pruning = toggle;
+        self
+    }
+
+    /// Toggle interning a single Utf8 groupby key once per query. This pays off if the query
+    /// groups by the same key more than once, e.g. in multiple window expressions.
+    pub fn with_interned_groupby_keys(mut self, toggle: bool) -> Self {
+        self.opt_state.intern_groupby_keys = toggle;
+        self
+    }
+
+    /// Toggle the aggregation of the columns needed by all scans of the same file, so that
+    /// such a file is only scanned once.
+    pub fn with_agg_scan_projection(mut self, toggle: bool) -> Self {
+        self.opt_state.agg_scan_projection = toggle;
+        self
+    }
+
+    /// Set the memory budget in bytes of the sorts and groupbys. Inputs that are estimated to be
+    /// larger than the budget are processed in parts that are spilled to disk. This overrides the
+    /// `POLARS_MEMORY_BUDGET` environment variable.
+    #[cfg(feature = "spill")]
+    pub fn with_memory_budget(mut self, budget: Option<usize>) -> Self {
+        self.opt_state.memory_budget = budget;
+        self
+    }
+
+    /// Set the number of bytes of the scanned file that are processed per batch when the query
+    /// is written to a file with [sink_csv](LazyFrame::sink_csv) or
+    /// [sink_parquet](LazyFrame::sink_parquet).
+    pub fn with_sink_batch_size(mut self, size: usize) -> Self {
+        self.opt_state.sink_batch_size = size;
+        self
+    }
+
+    /// Abort the query with [PolarsError::ResourceExhausted] if the scans, joins, groupbys or
+    /// other nodes that can grow the data output more than `max_rows` rows.
+    ///
+    /// The limits are enforced during execution: a join counts its output before the result is
+    /// materialized, and checks the result, and a groupby checks its aggregations as they are
+    /// computed.
+    pub fn with_max_output_rows(mut self, max_rows: usize) -> Self {
+        self.opt_state.max_output_rows = Some(max_rows);
+        self
+    }
+
+    /// Abort the query with [PolarsError::ResourceExhausted] if the output of a node is
+    /// estimated to be larger than `limit` bytes, see
+    /// [with_max_output_rows](LazyFrame::with_max_output_rows).
+    pub fn with_memory_limit_bytes(mut self, limit: usize) -> Self {
+        self.opt_state.memory_limit_bytes = Some(limit);
+        self
+    }
+
+    /// Set all the optimization toggles at once.
+    pub fn with_optimizations(mut self, opt_state: OptState) -> Self {
+        self.opt_state = opt_state;
+        self
+    }
+
+    /// Turn off all optimizations. The global string cache setting is kept, as it doesn't
+    /// optimize the query plan.
+    pub fn without_optimizations(self) -> Self {
+        self.with_projection_pushdown(false)
+            .with_predicate_pushdown(false)
+            .with_type_coercion(false)
+            .with_simplify_expr(false)
+            .with_aggregate_pushdown(false)
+            .with_join_pruning(false)
+            .with_sort_pruning(false)
+            .with_chunk_pruning(false)
+            .with_agg_scan_projection(false)
+    }
+
+    /// Get the schema of the output of the query without executing it. The files of the scans are
+    /// not read again, the schemas determined when the scans were created are used.
+    ///
+    /// An error is returned if the plan contains a groupby apply or a [map](LazyFrame::map)
+    /// without an explicit `schema`, as their output schema depends on the applied function.
+    pub fn schema(&self) -> Result<Schema> {
+        self.logical_plan.resolved_schema()
+    }
+
     /// Describe the logical plan.
     pub fn describe_plan(&self) -> String {
         self.logical_plan.describe()
     }
 
     /// Describe the optimized logical plan.
+    ///
+    /// If the query is executed under the global string cache, the plan is preceded by a
+    /// `GLOBAL STRING CACHE` line with the reason.
     pub fn describe_optimized_plan(&self) -> Result<String> {
         let mut expr_arena = Arena::with_capacity(512);
         let mut lp_arena = Arena::with_capacity(512);
+        let use_string_cache = self.opt_state.global_string_cache;
         let lp_top = self.clone().optimize(&mut lp_arena, &mut expr_arena)?;
+        let reason = if use_string_cache {
+            Some("turned on with with_string_cache".to_string())
+        } else {
+            string_cache_reason(lp_top, &lp_arena, &expr_arena)
+        };
         let logical_plan = node_to_lp(lp_top, &mut expr_arena, &mut lp_arena);
-        Ok(logical_plan.describe())
+        Ok(match reason {
+            Some(reason) => format!(
+                "GLOBAL STRING CACHE: {}\n{}",
+                reason,
+                logical_plan.describe()
+            ),
+            None => logical_plan.describe(),
+        })
     }
 
     /// Add a sort operation to the logical plan.
@@ -423,7 +859,7 @@ impl LazyFrame {
             df.rename(&existing_name, &new_name)?;
             Ok(df)
         };
-        init.map(f, Some(AllowedOptimizations::default()), Some(schema))
+        init.map(f, AllowedOptimizations::all(), Some(schema))
     }
 
     /// Shift the values by a given period and fill the parts that will be empty due to this operation
@@ -464,10 +900,7 @@ impl LazyFrame {
     /// Filter, join operations and a lower number of rows available in the scanned file influence
     /// the final number of rows.
     pub fn fetch(self, n_rows: usize) -> Result<DataFrame> {
-        FETCH_ROWS.with(|fetch_rows| fetch_rows.set(Some(n_rows)));
-        let res = self.collect();
-        FETCH_ROWS.with(|fetch_rows| fetch_rows.set(None));
-        res
+        self.collect_with_fetch(Some(n_rows), None)
     }
 
     pub fn optimize(
@@ -484,6 +917,9 @@ impl LazyFrame {
         #[cfg(any(feature = "parquet", feature = "csv-file"))]
         let agg_scan_projection = self.opt_state.agg_scan_projection;
         let aggregate_pushdown = self.opt_state.aggregate_pushdown;
+        #[cfg(feature = "private")]
+        let join_pruning = self.opt_state.join_pruning;
+        let sort_pruning = self.opt_state.sort_pruning;
 
         let logical_plan = self.get_plan_builder().build();
 
@@ -497,7 +933,22 @@ impl LazyFrame {
         #[cfg(debug_assertions)]
         let prev_schema = logical_plan.schema().clone();
 
-        let mut lp_top = to_alp(logical_plan, expr_arena, lp_arena);
+        let mut lp_top = to_alp(logical_plan, expr_arena, lp_arena)?;
+
+        // simplify the expressions before the pushdowns, such that simplified predicates
+        // can be pushed down further
+        if simplify_expr {
+            let mut simplify_rules: [Box<dyn OptimizationRule>; 2] = [
+                Box::new(SimplifyExprRule {}),
+                Box::new(SimplifyBooleanRule {}),
+            ];
+            lp_top = StackOptimizer {}.optimize_loop(
+                &mut simplify_rules,
+                expr_arena,
+                lp_arena,
+                lp_top,
+            )?;
+        }
 
         if projection_pushdown {
             let alp = lp_arena.take(lp_top);
@@ -526,6 +977,13 @@ impl LazyFrame {
         if aggregate_pushdown {
             rules.push(Box::new(AggregatePushdown::new()))
         }
+        #[cfg(feature = "private")]
+        if join_pruning {
+            rules.push(Box::new(JoinPrune {}))
+        }
+        if sort_pruning {
+            rules.push(Box::new(SortPrune {}))
+        }
 
         #[cfg(any(feature = "parquet", feature = "csv-file"))]
         if agg_scan_projection {
@@ -539,7 +997,7 @@ impl LazyFrame {
         }
 
         let opt = StackOptimizer {};
-        lp_top = opt.optimize_loop(&mut rules, expr_arena, lp_arena, lp_top);
+        lp_top = opt.optimize_loop(&mut rules, expr_arena, lp_arena, lp_top)?;
 
         // during debug we check if the optimizations have not modified the final schema
         #[cfg(debug_assertions)]
@@ -566,6 +1024,7 @@ impl LazyFrame {
 
     /// Execute all the lazy operations and collect them into a [DataFrame](polars_core::frame::DataFrame).
     /// Before execution the query is being optimized.
+    /// The result is not rechunked, see [collect_with](LazyFrame::collect_with).
     ///
     /// # Example
     ///
@@ -582,25 +1041,147 @@ impl LazyFrame {
     /// }
     /// ```
     pub fn collect(self) -> Result<DataFrame> {
-        let use_string_cache = self.opt_state.global_string_cache;
+        self.collect_with_fetch(None, None)
+    }
+
+    /// Execute the query like [collect](LazyFrame::collect), but share the cached scans with
+    /// `state`. `collect` starts every query with an empty cache, a `state` that is kept between
+    /// queries serves the scans of files that didn't change since they were cached. A file that
+    /// is rewritten is read again, as its modification time and size are part of the cache key.
+    ///
+    /// Only the file scans are shared. They are keyed on the projection, the number of rows and
+    /// the schema of the scan, and files that were modified in the last two seconds are not
+    /// shared at all. The `cache` nodes are only valid within a single query.
+    pub fn collect_with_state(self, state: &ExecutionState) -> Result<DataFrame> {
+        self.collect_with_fetch(None, Some(state))
+    }
+
+    /// Clear the cached scans of `state` and execute the query with it, see
+    /// [collect_with_state](LazyFrame::collect_with_state). All files are read again.
+    pub fn collect_fresh(self, state: &ExecutionState) -> Result<DataFrame> {
+        state.clear_scan_cache();
+        self.collect_with_state(state)
+    }
+
+    /// Execute the query like [collect](LazyFrame::collect), with the given [CollectOptions].
+    ///
+    /// `collect` and `CollectOptions::default()` don't rechunk the result, e.g. a vertical
+    /// concatenation of three scans returns columns of three chunks.
+    pub fn collect_with(self, options: CollectOptions) -> Result<DataFrame> {
+        let mut df = self.collect_with_fetch(None, None)?;
+        if options.rechunk {
+            if options.parallel_rechunk {
+                df.as_single_chunk_par();
+            } else {
+                df.as_single_chunk();
+            }
+        }
+        Ok(df)
+    }
+
+    /// Collect and overwrite the number of rows read by the scans with `fetch_rows`. The scans
+    /// are cached in `scan_cache` if given.
+    fn collect_with_fetch(
+        self,
+        fetch_rows: Option<usize>,
+        scan_cache: Option<&ExecutionState>,
+    ) -> Result<DataFrame> {
+        let opt_state = self.opt_state;
         let mut expr_arena = Arena::with_capacity(256);
         let mut lp_arena = Arena::with_capacity(128);
         let lp_top = self.optimize(&mut lp_arena, &mut expr_arena)?;
 
-        // if string cache was already set, we skip this and global settings are respected
-        if use_string_cache {
-            toggle_string_cache(use_string_cache);
-        }
+        let _string_cache = hold_string_cache(&opt_state, lp_top, &lp_arena, &expr_arena);
         let planner = DefaultPlanner::default();
         let mut physical_plan =
             planner.create_physical_plan(lp_top, &mut lp_arena, &mut expr_arena)?;
 
-        let state = ExecutionState::new();
-        let out = physical_plan.execute(&state);
-        if use_string_cache {
-            toggle_string_cache(!use_string_cache);
+        let mut state = opt_state.execution_state(fetch_rows);
+        if let Some(scan_cache) = scan_cache {
+            state = state.with_scan_cache_of(scan_cache);
+        }
+        physical_plan.execute(&state).and_then(|df| {
+            state.check_output("query result", &df)?;
+            Ok(df)
+        })
+    }
+
+    /// Execute the query and pass the result to `f` in batches, such that the full result
+    /// doesn't have to be held in memory. Every batch has a single chunk per column.
+    ///
+    /// A query that only consists of elementwise projections and filters over a csv scan is
+    /// executed on consecutive byte ranges of the file, see
+    /// [with_sink_batch_size](LazyFrame::with_sink_batch_size). Any other query is collected
+    /// first and passed to `f` chunk by chunk.
+    ///
+    /// If the query has no rows, `f` is called once with an empty DataFrame.
+    /// Returns the number of rows.
+    pub fn sink_batches<F>(self, mut f: F) -> Result<usize>
+    where
+        F: FnMut(DataFrame) -> Result<()>,
+    {
+        let opt_state = self.opt_state;
+        let mut expr_arena = Arena::with_capacity(256);
+        let mut lp_arena = Arena::with_capacity(128);
+        let lp_top = self.optimize(&mut lp_arena, &mut expr_arena)?;
+        let schema = lp_arena.get(lp_top).schema(&lp_arena).clone();
+
+        let string_cache = hold_string_cache(&opt_state, lp_top, &lp_arena, &expr_arena);
+        let mut n_rows = 0;
+        let out = match streamable_csv_scan(lp_top, &lp_arena, &expr_arena) {
+            #[cfg(feature = "csv-file")]
+            Some(scan) => sink_csv_scan(
+                &opt_state,
+                lp_top,
+                scan,
+                &lp_arena,
+                &expr_arena,
+                &mut f,
+                &mut n_rows,
+            ),
+            _ => DefaultPlanner::default()
+                .create_physical_plan(lp_top, &mut lp_arena, &mut expr_arena)
+                .and_then(|mut physical_plan| {
+                    physical_plan.execute(&opt_state.execution_state(None))
+                })
+                .and_then(|df| sink_chunks(df, &mut f, &mut n_rows)),
+        };
+        drop(string_cache);
+        out?;
+
+        if n_rows == 0 {
+            f(DataFrame::new_empty(&schema))?;
         }
-        out
+        Ok(n_rows)
+    }
+
+    /// Execute the query and write the result to a csv file in batches, see
+    /// [sink_batches](LazyFrame::sink_batches). Returns the number of written rows.
+    #[cfg(feature = "csv-file")]
+    pub fn sink_csv<P: AsRef<Path>>(self, path: P, options: CsvWriterOptions) -> Result<usize> {
+        let mut file = BufWriter::new(File::create(path)?);
+        let mut writer = BatchedCsvWriter::new(&mut file, &options);
+        let n_rows = self.sink_batches(|df| writer.write_batch(&df))?;
+        drop(writer);
+        file.flush()?;
+        Ok(n_rows)
+    }
+
+    /// Execute the query and write the result to a parquet file in batches, see
+    /// [sink_batches](LazyFrame::sink_batches). Every batch is written as one or more row
+    /// groups. Returns the number of written rows.
+    #[cfg(feature = "parquet")]
+    pub fn sink_parquet<P: AsRef<Path>>(
+        self,
+        path: P,
+        options: ParquetWriterOptions,
+    ) -> Result<usize> {
+        let file = File::create(path)?;
+        let schema = self.schema()?;
+        let mut writer = BatchedParquetWriter::new(file, &schema, &options)?;
+        let n_rows = self.sink_batches(|df| writer.write_batch(&df))?;
+        writer.finish()?;
+        Ok(n_rows)
     }
 
     /// Filter by some predicate expression.
@@ -628,6 +1209,9 @@ impl LazyFrame {
     /// Columns can be selected with [col](crate::dsl::col);
     /// If you want to select all columns use `col("*")`.
     ///
+    /// The output columns are in the order of the expressions. This order is guaranteed, also
+    /// if the optimizer pushes the projection down to a scan.
+    ///
     /// # Example
     ///
     /// ```rust
@@ -666,6 +1250,13 @@ impl LazyFrame {
         Self::from_logical_plan(lp, opt_state)
     }
 
+    /// Merge two LazyFrames that are sorted in ascending order by the column `key` into a single
+    /// LazyFrame that is sorted by `key`. See [DataFrame::merge_sorted].
+    #[cfg(feature = "merge_sorted")]
+    pub fn merge_sorted(self, other: LazyFrame, key: &str) -> Result<LazyFrame> {
+        crate::functions::concat(&[self, other], ConcatHow::MergeSorted(Arc::new(key.into())))
+    }
+
     /// Group by and aggregate.
     ///
     /// # Example
@@ -691,6 +1282,8 @@ impl LazyFrame {
             logical_plan: self.logical_plan,
             opt_state,
             keys: by,
+            expand_keys: false,
+            drop_null_keys: false,
         }
     }
 
@@ -776,6 +1369,39 @@ impl LazyFrame {
             .finish()
     }
 
+    /// Generic join function where the join type, the suffix of duplicate column names and the
+    /// parallel execution of the inputs are set by [JoinOptions].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use polars_core::prelude::*;
+    /// use polars_lazy::prelude::*;
+    ///
+    /// fn example(ldf: LazyFrame, other: LazyFrame) -> LazyFrame {
+    ///     let options = JoinOptions {
+    ///         how: JoinType::Left,
+    ///         suffix: "_other".into(),
+    ///         ..Default::default()
+    ///     };
+    ///     ldf.join_with_options(other, vec![col("foo")], vec![col("foo")], options)
+    /// }
+    /// ```
+    pub fn join_with_options(
+        self,
+        other: LazyFrame,
+        left_on: Vec<Expr>,
+        right_on: Vec<Expr>,
+        options: JoinOptions,
+    ) -> LazyFrame {
+        let opt_state = self.opt_state;
+        let lp = self
+            .get_plan_builder()
+            .join(other.logical_plan, left_on, right_on, options)
+            .build();
+        LazyFrame::from_logical_plan(lp, opt_state)
+    }
+
     pub fn join_builder(self) -> JoinBuilder {
         JoinBuilder::new(self)
     }
@@ -864,23 +1490,23 @@ impl LazyFrame {
     }
 
     /// Apply explode operation. [See eager explode](polars_core::frame::DataFrame::explode).
+    ///
+    /// Besides columns, the list results of expressions can be exploded, e.g.
+    /// `col("text").str().split(" ")`. These are added under their output names and exploded,
+    /// the other columns are repeated for every exploded value.
     pub fn explode(self, columns: &[Expr]) -> LazyFrame {
-        let columns = columns
-            .iter()
-            .map(|e| {
-                if let Expr::Column(name) = e {
-                    (**name).clone()
-                } else {
-                    panic!("expected column expression")
-                }
-            })
-            .collect();
         // Note: this operation affects multiple columns. Therefore it isn't implemented as expression.
         let opt_state = self.get_opt_state();
-        let lp = self.get_plan_builder().explode(columns).build();
+        let lp = self.get_plan_builder().explode(columns.to_vec()).build();
         Self::from_logical_plan(lp, opt_state)
     }
 
+    /// [Explode](LazyFrame::explode) after adding a row count column named `name`, so every
+    /// exploded row has the index of the row it originates from.
+    pub fn explode_with_index(self, columns: &[Expr], name: &str) -> LazyFrame {
+        self.with_row_count(name, None).explode(columns)
+    }
+
     /// Drop duplicate rows. [See eager](polars_core::prelude::DataFrame::drop_duplicates).
     pub fn drop_duplicates(self, maintain_order: bool, subset: Option<Vec<String>>) -> LazyFrame {
         let opt_state = self.get_opt_state();
@@ -938,6 +1564,22 @@ impl LazyFrame {
         Self::from_logical_plan(lp, opt_state)
     }
 
+    /// Add a `UInt32` column named `name` with the row index as first column. The rows are
+    /// counted at this point of the query, so a filter before the row count gives consecutive
+    /// indexes and a filter after it keeps the original indexes. A scan directly below adds the
+    /// column while reading.
+    pub fn with_row_count(self, name: &str, offset: Option<u32>) -> LazyFrame {
+        let opt_state = self.get_opt_state();
+        let lp = self
+            .get_plan_builder()
+            .with_row_count(RowCount {
+                name: name.to_string(),
+                offset: offset.unwrap_or(0),
+            })
+            .build();
+        Self::from_logical_plan(lp, opt_state)
+    }
+
     /// Limit the DataFrame to the first `n` rows. Note if you don't want the rows to be scanned,
     /// use [fetch](LazyFrame::fetch).
     pub fn limit(self, n: usize) -> LazyFrame {
@@ -946,15 +1588,32 @@ impl LazyFrame {
 
     /// Apply a function/closure once the logical plan get executed.
     ///
+    /// By default the node is an optimization barrier: projections and predicates are not pushed
+    /// past it. Use `optimizations` to allow this if the function doesn't depend on the columns
+    /// or rows that may be pruned.
+    ///
     /// ## Warning
     /// This can blow up in your face if the schema is changed due to the operation. The optimizer
-    /// relies on a correct schema.
+    /// relies on a correct schema, so pass the output `schema` if it differs from the input schema.
+    ///
+    /// # Example
     ///
-    /// You can toggle certain optimizations off.
+    /// ```rust
+    /// use polars_core::prelude::*;
+    /// use polars_lazy::prelude::*;
+    ///
+    /// fn example(lf: LazyFrame) -> LazyFrame {
+    ///     lf.map(
+    ///         |df: DataFrame| df.drop_nulls(None),
+    ///         AllowedOptimizations::default(),
+    ///         None,
+    ///     )
+    /// }
+    /// ```
     pub fn map<F>(
         self,
         function: F,
-        optimizations: Option<AllowedOptimizations>,
+        optimizations: AllowedOptimizations,
         schema: Option<Schema>,
     ) -> LazyFrame
     where
@@ -963,11 +1622,7 @@ impl LazyFrame {
         let opt_state = self.get_opt_state();
         let lp = self
             .get_plan_builder()
-            .map(
-                function,
-                optimizations.unwrap_or_default(),
-                schema.map(Arc::new),
-            )
+            .map(function, optimizations, schema.map(Arc::new))
             .build();
         Self::from_logical_plan(lp, opt_state)
     }
@@ -978,13 +1633,30 @@ pub struct LazyGroupBy {
     pub(crate) logical_plan: LogicalPlan,
     opt_state: OptState,
     keys: Vec<Expr>,
+    expand_keys: bool,
+    drop_null_keys: bool,
 }
 
 impl LazyGroupBy {
+    /// Also aggregate the groupby keys in multi-output aggregations like `col("*").sum()`.
+    /// By default the output columns of the keys are excluded from the expansion.
+    pub fn with_expanded_keys(mut self, toggle: bool) -> Self {
+        self.expand_keys = toggle;
+        self
+    }
+
+    /// Exclude the rows of which any of the keys is null, instead of aggregating them in a null
+    /// group. This is cheaper than filtering the null keys before the groupby.
+    pub fn with_drop_null_keys(mut self, toggle: bool) -> Self {
+        self.drop_null_keys = toggle;
+        self
+    }
+
     /// Group by and aggregate.
     ///
     /// Select a column with [col](crate::dsl::col) and choose an aggregation.
-    /// If you want to aggregate all columns use `col("*")`.
+    /// If you want to aggregate all columns use `col("*")`. The groupby keys are excluded from
+    /// such a multi-output aggregation, see [with_expanded_keys](Self::with_expanded_keys).
     ///
     /// # Example
     ///
@@ -1005,11 +1677,85 @@ impl LazyGroupBy {
     /// ```
     pub fn agg(self, aggs: Vec<Expr>) -> LazyFrame {
         let lp = LogicalPlanBuilder::from(self.logical_plan)
-            .groupby(Arc::new(self.keys), aggs, None)
+            .groupby(
+                Arc::new(self.keys),
+                aggs,
+                None,
+                self.expand_keys,
+                self.drop_null_keys,
+            )
+            .build();
+        LazyFrame::from_logical_plan(lp, self.opt_state)
+    }
+
+    /// Get the row indexes that belong to every group, without running an aggregation.
+    ///
+    /// The result has the key columns and a `"groups"` column of type `List<UInt32>`.
+    pub fn groups(self) -> LazyFrame {
+        // without keys there are no groups, and the groupby returns an error
+        let aggs = match self.keys.first() {
+            Some(key) => vec![key.clone().agg_groups()],
+            None => vec![],
+        };
+        self.agg(aggs)
+    }
+
+    fn agg_all(self, method: GroupByMethod) -> LazyFrame {
+        let lp = LogicalPlanBuilder::from(self.logical_plan)
+            .groupby_all(
+                Arc::new(self.keys),
+                method,
+                self.expand_keys,
+                self.drop_null_keys,
+            )
             .build();
         LazyFrame::from_logical_plan(lp, self.opt_state)
     }
 
+    /// Aggregate the mean of every numeric column in the groups.
+    ///
+    /// The columns of which the data type doesn't support the aggregation are skipped, like the
+    /// eager `GroupBy` does. The skipped columns are listed in the `SKIPPED` part of the
+    /// aggregation in [describe_optimized_plan](LazyFrame::describe_optimized_plan).
+    pub fn mean(self) -> LazyFrame {
+        self.agg_all(GroupByMethod::Mean)
+    }
+
+    /// Aggregate the sum of every numeric and boolean column in the groups.
+    pub fn sum(self) -> LazyFrame {
+        self.agg_all(GroupByMethod::Sum)
+    }
+
+    /// Aggregate the minimum of every numeric, boolean and temporal column in the groups.
+    pub fn min(self) -> LazyFrame {
+        self.agg_all(GroupByMethod::Min)
+    }
+
+    /// Aggregate the maximum of every numeric, boolean and temporal column in the groups.
+    pub fn max(self) -> LazyFrame {
+        self.agg_all(GroupByMethod::Max)
+    }
+
+    /// Aggregate the median of every numeric and temporal column in the groups.
+    pub fn median(self) -> LazyFrame {
+        self.agg_all(GroupByMethod::Median)
+    }
+
+    /// Aggregate the number of rows of every column in the groups.
+    pub fn count(self) -> LazyFrame {
+        self.agg_all(GroupByMethod::Count)
+    }
+
+    /// Aggregate the first value of every column in the groups.
+    pub fn first(self) -> LazyFrame {
+        self.agg_all(GroupByMethod::First)
+    }
+
+    /// Aggregate the last value of every column in the groups.
+    pub fn last(self) -> LazyFrame {
+        self.agg_all(GroupByMethod::Last)
+    }
+
     /// Apply a function over the groups as a new `DataFrame`. It is not recommended that you use
     /// this as materializing the `DataFrame` is quite expensive.
     pub fn apply<F>(self, f: F) -> LazyFrame
@@ -1017,7 +1763,13 @@ impl LazyGroupBy {
         F: 'static + Fn(DataFrame) -> Result<DataFrame> + Send + Sync,
     {
         let lp = LogicalPlanBuilder::from(self.logical_plan)
-            .groupby(Arc::new(self.keys), vec![], Some(Arc::new(f)))
+            .groupby(
+                Arc::new(self.keys),
+                vec![],
+                Some(Arc::new(f)),
+                self.expand_keys,
+                self.drop_null_keys,
+            )
             .build();
         LazyFrame::from_logical_plan(lp, self.opt_state)
     }
@@ -1031,6 +1783,7 @@ pub struct JoinBuilder {
     right_on: Vec<Expr>,
     allow_parallel: bool,
     force_parallel: bool,
+    suffix: Option<String>,
 }
 impl JoinBuilder {
     fn new(lf: LazyFrame) -> Self {
@@ -1042,6 +1795,7 @@ impl JoinBuilder {
             right_on: vec![],
             allow_parallel: true,
             force_parallel: false,
+            suffix: None,
         }
     }
 
@@ -1068,26 +1822,31 @@ impl JoinBuilder {
         self.allow_parallel = allow;
         self
     }
-    pub fn force_parallel(mut self, allow: bool) -> Self {
-        self.allow_parallel = allow;
+    pub fn force_parallel(mut self, force: bool) -> Self {
+        self.force_parallel = force;
+        self
+    }
+    /// Suffix of the columns of the right frame that also exist in the left frame.
+    pub fn suffix(mut self, suffix: &str) -> Self {
+        self.suffix = Some(suffix.to_string());
         self
     }
     pub fn finish(self) -> LazyFrame {
-        let opt_state = self.lf.opt_state;
-
-        let lp = self
-            .lf
-            .get_plan_builder()
-            .join(
-                self.other.expect("with not set").logical_plan,
-                self.how,
-                self.left_on,
-                self.right_on,
-                self.allow_parallel,
-                self.force_parallel,
-            )
-            .build();
-        LazyFrame::from_logical_plan(lp, opt_state)
+        let mut options = JoinOptions {
+            how: self.how,
+            allow_parallel: self.allow_parallel,
+            force_parallel: self.force_parallel,
+            ..Default::default()
+        };
+        if let Some(suffix) = self.suffix {
+            options.suffix = suffix;
+        }
+        self.lf.join_with_options(
+            self.other.expect("with not set"),
+            self.left_on,
+            self.right_on,
+            options,
+        )
     }
 }
 
@@ -1097,7 +1856,7 @@ mod test {
     use polars_core::utils::chrono::{NaiveDate, NaiveDateTime, NaiveTime};
     use polars_core::*;
 
-    use crate::functions::{argsort_by, pearson_corr};
+    use crate::functions::{arange, argsort_by, concat, pearson_corr, plugin};
     use crate::tests::get_df;
 
     use super::*;
@@ -1108,6 +1867,67 @@ mod test {
         LazyCsvReader::new(path.to_string()).finish()
     }
 
+    #[test]
+    fn test_scan_projection_order() -> Result<()> {
+        // the scans of the same file are combined in one scan with a local projection per scan
+        for _ in 0..20 {
+            let left = scan_foods_csv().select(vec![col("sugars_g"), col("category")]);
+            let right =
+                scan_foods_csv().select(vec![col("fats_g"), col("calories"), col("category")]);
+            let out = left
+                .inner_join(right, col("category"), col("category"))
+                .collect()?;
+            assert_eq!(
+                out.get_column_names(),
+                &["sugars_g", "category", "fats_g", "calories"]
+            );
+        }
+
+        let out = scan_foods_csv()
+            .select(vec![col("fats_g"), col("category")])
+            .collect()?;
+        assert_eq!(out.get_column_names(), &["fats_g", "category"]);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "strings")]
+    fn test_lazy_str_lengths_slice() -> Result<()> {
+        let df = df![
+            "s" => [Some("héllo"), None, Some(""), Some("a😀")]
+        ]?;
+        let lf = df.lazy().select(vec![
+            col("s").str().lengths().alias("bytes"),
+            col("s").str().n_chars().alias("chars"),
+            col("s").str().slice(-2, Some(1)).alias("slice"),
+        ]);
+        let schema = lf.schema()?;
+        assert_eq!(
+            schema.field_with_name("bytes")?.data_type(),
+            &DataType::UInt32
+        );
+        assert_eq!(
+            schema.field_with_name("slice")?.data_type(),
+            &DataType::Utf8
+        );
+
+        let out = lf.collect()?;
+        assert_eq!(
+            Vec::from(out.column("bytes")?.u32()?),
+            &[Some(6), None, Some(0), Some(5)]
+        );
+        assert_eq!(
+            Vec::from(out.column("chars")?.u32()?),
+            &[Some(5), None, Some(0), Some(2)]
+        );
+        // the start is rounded down to the first byte of the emoji, which is longer than 1 byte
+        assert_eq!(
+            Vec::from(out.column("slice")?.utf8()?),
+            &[Some("l"), None, Some(""), Some("")]
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_lazy_ternary() {
         let df = get_df()
@@ -1656,8 +2476,10 @@ mod test {
         let rules: &mut [Box<dyn OptimizationRule>] = &mut [Box::new(SimplifyExprRule {})];
 
         let optimizer = StackOptimizer {};
-        let mut lp_top = to_alp(plan, &mut expr_arena, &mut lp_arena);
-        lp_top = optimizer.optimize_loop(rules, &mut expr_arena, &mut lp_arena, lp_top);
+        let mut lp_top = to_alp(plan, &mut expr_arena, &mut lp_arena).unwrap();
+        lp_top = optimizer
+            .optimize_loop(rules, &mut expr_arena, &mut lp_arena, lp_top)
+            .unwrap();
         let plan = node_to_lp(lp_top, &mut expr_arena, &mut lp_arena);
         assert!(
             matches!(plan, LogicalPlan::Projection{ expr, ..} if matches!(&expr[0], Expr::BinaryExpr{left, ..} if **left == Expr::Literal(LiteralValue::Float32(2.0))))
@@ -1676,7 +2498,7 @@ mod test {
             .agg(vec![col("*").sum(), col("*").first()])
             .collect()
             .unwrap();
-        assert_eq!(new.shape(), (3, 6));
+        assert_eq!(new.shape(), (3, 5));
     }
 
     #[test]
@@ -1829,6 +2651,62 @@ mod test {
         dbg!(out);
     }
 
+    #[test]
+    fn test_window_scatter_by_row() -> Result<()> {
+        let df = df![
+            "a" => [Some("x"), Some("y"), Some("x"), None, Some("y"), None],
+            "b" => [1, 1, 2, 2, 1, 1],
+            "v" => [1, 2, 3, 4, 5, 6]
+        ]?;
+
+        // two windows with different keys, and duplicate and null keys
+        let out = df
+            .clone()
+            .lazy()
+            .with_columns(vec![
+                col("v").sum().over(vec![col("a")]).alias("sum_a"),
+                col("v").max().over(vec![col("b")]).alias("max_b"),
+            ])
+            .with_column(
+                col("v")
+                    .min()
+                    .over(vec![col("a"), col("b")])
+                    .alias("min_ab"),
+            )
+            .collect()?;
+        assert_eq!(
+            Vec::from(out.column("sum_a")?.i32()?),
+            &[Some(4), Some(7), Some(4), Some(10), Some(7), Some(10)]
+        );
+        assert_eq!(
+            Vec::from(out.column("max_b")?.i32()?),
+            &[Some(6), Some(6), Some(4), Some(4), Some(6), Some(6)]
+        );
+        assert_eq!(
+            Vec::from(out.column("min_ab")?.i32()?),
+            &[Some(1), Some(2), Some(3), Some(4), Some(2), Some(6)]
+        );
+
+        // a window over a filtered frame, the rows are not the rows of the scan
+        let out = df
+            .lazy()
+            .filter(col("v").neq(lit(2)))
+            .select(vec![
+                col("v"),
+                col("v").sum().over(vec![col("a")]).alias("sum_a"),
+            ])
+            .collect()?;
+        assert_eq!(
+            Vec::from(out.column("v")?.i32()?),
+            &[Some(1), Some(3), Some(4), Some(5), Some(6)]
+        );
+        assert_eq!(
+            Vec::from(out.column("sum_a")?.i32()?),
+            &[Some(4), Some(4), Some(10), Some(5), Some(10)]
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_lazy_double_projection() {
         let df = df! {
@@ -1857,8 +2735,10 @@ mod test {
         let rules: &mut [Box<dyn OptimizationRule>] = &mut [Box::new(TypeCoercionRule {})];
 
         let optimizer = StackOptimizer {};
-        let mut lp_top = to_alp(lp, &mut expr_arena, &mut lp_arena);
-        lp_top = optimizer.optimize_loop(rules, &mut expr_arena, &mut lp_arena, lp_top);
+        let mut lp_top = to_alp(lp, &mut expr_arena, &mut lp_arena).unwrap();
+        lp_top = optimizer
+            .optimize_loop(rules, &mut expr_arena, &mut lp_arena, lp_top)
+            .unwrap();
         let lp = node_to_lp(lp_top, &mut expr_arena, &mut lp_arena);
 
         if let LogicalPlan::Projection { expr, .. } = lp {
@@ -2078,7 +2958,7 @@ mod test {
         let out = df
             .lazy()
             .groupby(vec![col("a")])
-            .agg(vec![col("b").sort_by(col("c"), true).first()])
+            .agg(vec![col("b").sort_by(vec![col("c")], vec![true]).first()])
             .collect()
             .unwrap()
             .sort("a", false)
@@ -2277,7 +3157,7 @@ mod test {
             .collect()?;
 
         assert_eq!(
-            Vec::from(out.column("int")?.u32()?),
+            Vec::from(out.column("int")?.idx()?),
             [2, 4, 1, 3, 0]
                 .iter()
                 .copied()
@@ -2318,6 +3198,54 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_lazy_groupby_list_column() -> Result<()> {
+        let df = df![
+            "user" => ["a", "a", "b", "b", "a"],
+            "day" => [1, 1, 1, 2, 2],
+            "tag" => [1, 2, 3, 4, 5]
+        ]?;
+
+        let tags = df
+            .lazy()
+            .groupby(vec![col("user"), col("day")])
+            .agg(vec![col("tag").list().alias("tags")])
+            .sort_by_exprs(vec![col("user"), col("day")], vec![false, false]);
+
+        let out = tags
+            .clone()
+            .groupby(vec![col("user")])
+            .agg(vec![
+                col("tags").flatten().alias("flat"),
+                col("tags").list().alias("nested"),
+            ])
+            .sort("user", false)
+            .collect()?;
+
+        let flat = out.column("flat")?;
+        assert_eq!(flat.dtype(), &DataType::List(ArrowDataType::Int32));
+        let flat = flat
+            .list()?
+            .into_iter()
+            .map(|opt_s| Vec::from(opt_s.unwrap().i32().unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            flat,
+            &[vec![Some(1), Some(2), Some(5)], vec![Some(3), Some(4)]]
+        );
+
+        let nested = out.column("nested")?.list()?.get(0).unwrap();
+        assert_eq!(nested.len(), 2);
+        assert!(matches!(
+            nested.dtype(),
+            DataType::List(ArrowDataType::LargeList(_))
+        ));
+
+        // flatten is an aggregation and cannot be used in a select
+        assert!(tags.select(vec![col("tags").flatten()]).collect().is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_filter_and_alias() -> Result<()> {
         let df = df![
@@ -2411,4 +3339,3397 @@ mod test {
         assert_eq!(out.shape(), (6, 4));
         Ok(())
     }
+
+    #[test]
+    fn test_lazy_arange() -> Result<()> {
+        let df = df![
+            "a" => [1, 2, 3],
+        ]?;
+
+        let out = df
+            .clone()
+            .lazy()
+            .with_column(arange(lit(0i64), col("a").count(), 1).alias("idx"))
+            .collect()?;
+        assert_eq!(
+            Vec::from(out.column("idx")?.i64()?),
+            &[Some(0), Some(1), Some(2)]
+        );
+
+        // a range that doesn't match the frame height cannot be added
+        let out = df
+            .lazy()
+            .with_column(arange(lit(0i64), lit(2i64), 1).alias("idx"))
+            .collect();
+        assert!(out.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_agg_empty_input() -> Result<()> {
+        let df = df![
+            "a" => ["a", "b", "a"],
+            "b" => [1, 2, 3],
+        ]?;
+
+        let lf = df.lazy().filter(lit(false));
+
+        let q = lf
+            .clone()
+            .groupby(vec![col("a")])
+            .agg(vec![col("b").sum(), col("b").list()]);
+        let schema = q.logical_plan.schema().clone();
+        let out = q.collect()?;
+        assert_eq!(out.height(), 0);
+        for s in out.get_columns() {
+            assert_eq!(
+                s.dtype(),
+                schema.field_with_name(s.name())?.data_type(),
+                "dtype of {} does not match the schema",
+                s.name()
+            );
+        }
+
+        let out = lf
+            .clone()
+            .join(lf.clone(), vec![col("a")], vec![col("a")], JoinType::Inner)
+            .collect()?;
+        assert_eq!(out.shape(), (0, 3));
+
+        let out = lf.select(vec![col("b").sum()]).collect()?;
+        assert_eq!(out.column("b")?.dtype(), &DataType::Int32);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "csv-file", feature = "parquet"))]
+    fn test_lazy_agg_empty_input_matrix() -> Result<()> {
+        use polars_io::prelude::ParquetWriter;
+
+        let df = df![
+            "k" => [Some("a"), None, Some("a")],
+            "i32" => [1i32, 2, 3],
+            "i64" => [Some(1i64), None, Some(3)],
+            "f64" => [1.0, 2.0, 3.0],
+            "str" => [Some("x"), None, Some("z")],
+            "bool" => [Some(true), None, Some(false)],
+            "null" => [None::<i32>; 3]
+        ]?;
+
+        // a header only csv file and a parquet file without rows
+        let csv_path = std::env::temp_dir().join("polars_test_lazy_agg_empty_input_matrix.csv");
+        std::fs::write(&csv_path, "k,i32,i64,f64,str,bool,null\n")?;
+        let pq_path = std::env::temp_dir().join("polars_test_lazy_agg_empty_input_matrix.parquet");
+        ParquetWriter::new(File::create(&pq_path)?).finish(&df.slice(0, 0))?;
+
+        let schema = Arc::new(df.schema());
+        let sources: Vec<(&str, LazyFrame)> = vec![
+            ("filter", df.clone().lazy().filter(lit(false))),
+            (
+                "csv",
+                LazyCsvReader::new(csv_path.to_string_lossy().to_string())
+                    .with_schema(schema)
+                    .finish(),
+            ),
+            (
+                "parquet",
+                LazyFrame::new_from_parquet(pq_path.to_string_lossy().to_string(), None, false),
+            ),
+        ];
+
+        let groupby = |lf: LazyFrame| {
+            lf.groupby(vec![col("k")]).agg(vec![
+                col("i32").sum(),
+                col("i32").mean(),
+                col("i64").min(),
+                col("i64").max(),
+                col("f64").sum(),
+                col("f64").first(),
+                col("str").first(),
+                col("str").n_unique(),
+                col("str").list(),
+                col("bool").last(),
+                col("bool").count(),
+                col("bool").list(),
+                col("null").sum(),
+                col("null").mean(),
+                col("null").list(),
+            ])
+        };
+        let join = |lf: LazyFrame| lf.clone().left_join(lf, col("k"), col("k"));
+        let select = |lf: LazyFrame| {
+            lf.select(vec![
+                col("i32").sum().alias("i32_sum"),
+                col("i64").mean().alias("i64_mean"),
+                col("f64").max(),
+                col("str").count(),
+                col("bool").count(),
+                col("null").sum(),
+            ])
+        };
+        let queries: Vec<(&str, &dyn Fn(LazyFrame) -> LazyFrame)> =
+            vec![("groupby", &groupby), ("join", &join), ("select", &select)];
+
+        for (source, lf) in &sources {
+            for (query, f) in &queries {
+                // the empty result has the columns and data types of the non-empty result
+                let expected = f(df.clone().lazy()).collect()?;
+                let q = f(lf.clone());
+                let schema = q.schema()?;
+                let out = q.collect()?;
+
+                assert_eq!(
+                    out.get_column_names(),
+                    expected.get_column_names(),
+                    "{} on {}",
+                    query,
+                    source
+                );
+                for s in out.get_columns() {
+                    assert_eq!(
+                        s.dtype(),
+                        expected.column(s.name())?.dtype(),
+                        "dtype of {} in {} on {}",
+                        s.name(),
+                        query,
+                        source
+                    );
+                    assert_eq!(
+                        s.dtype(),
+                        schema.field_with_name(s.name())?.data_type(),
+                        "schema of {} in {} on {}",
+                        s.name(),
+                        query,
+                        source
+                    );
+                }
+                // reductions without a groupby return a single row
+                let height = if *query == "select" { 1 } else { 0 };
+                assert_eq!(out.height(), height, "{} on {}", query, source);
+            }
+        }
+
+        std::fs::remove_file(&csv_path)?;
+        std::fs::remove_file(&pq_path)?;
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "concat_str")]
+    fn test_lazy_groupby_str_concat() -> Result<()> {
+        let df = df![
+            "a" => ["x", "y", "x", "y", "x"],
+            "tag" => [Some("1"), Some("2"), Some("3"), None, Some("5")],
+        ]?;
+
+        let out = df
+            .clone()
+            .lazy()
+            .groupby(vec![col("a")])
+            .agg(vec![
+                col("tag").str_concat("-", None).alias("concat"),
+                col("tag")
+                    .list()
+                    .lst_join("-", Some("null"))
+                    .alias("joined"),
+            ])
+            .sort("a", false)
+            .collect()?;
+
+        // the order of the values within a group is preserved
+        assert_eq!(
+            Vec::from(out.column("concat")?.utf8()?),
+            &[Some("1-3-5"), Some("2")]
+        );
+        assert_eq!(
+            Vec::from(out.column("joined")?.utf8()?),
+            &[Some("1-3-5"), Some("2-null")]
+        );
+
+        let out = df
+            .lazy()
+            .select(vec![col("tag").str_concat(", ", None)])
+            .collect()?;
+        assert_eq!(Vec::from(out.column("tag")?.utf8()?), &[Some("1, 2, 3, 5")]);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "concat_str")]
+    fn test_lazy_concat_str() -> Result<()> {
+        let df = df![
+            "id" => [1, 2, 3],
+            "name" => [Some("ünï"), None, Some("çødé")],
+            "score" => [1.5, 2.5, 3.5],
+        ]?;
+
+        // the wildcard expands to the inputs of concat_str, not to a column per input
+        let out = df
+            .clone()
+            .lazy()
+            .select(vec![concat_str(
+                vec![lit("row"), col("*").exclude(vec!["id"])],
+                "|",
+            )
+            .alias("key")])
+            .collect()?;
+        assert_eq!(out.get_column_names(), &["key"]);
+        assert_eq!(
+            Vec::from(out.column("key")?.utf8()?),
+            &[Some("row|ünï|1.5"), None, Some("row|çødé|3.5")]
+        );
+
+        let lf = df
+            .clone()
+            .lazy()
+            .with_column(
+                concat_str_null_as_empty(vec![col("id"), col("name")], "-", true).alias("key"),
+            )
+            .filter(col("id").gt(lit(1)));
+        assert!(lf.describe_optimized_plan()?.contains("concat_str"));
+        let out = lf.collect()?;
+        assert_eq!(
+            Vec::from(out.column("key")?.utf8()?),
+            &[Some("2-"), Some("3-çødé")]
+        );
+
+        let out = df
+            .lazy()
+            .with_column(col("id").gt(lit(1)).alias("g"))
+            .groupby(vec![col("g")])
+            .agg(vec![
+                concat_str(vec![col("name"), col("id")], ":").alias("key")
+            ])
+            .sort("g", false)
+            .collect()?;
+        let key = out.column("key")?.list()?.get(1).unwrap();
+        assert_eq!(Vec::from(key.utf8()?), &[None, Some("çødé:3")]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_duplicate_output_names() -> Result<()> {
+        let df = df![
+            "a" => [1, 2, 2],
+            "b" => [1, 2, 3],
+        ]?;
+
+        // previously these silently produced a DataFrame with two columns "a"
+        let err = df
+            .clone()
+            .lazy()
+            .groupby(vec![col("a")])
+            .agg(vec![col("b").sum().alias("a")])
+            .collect()
+            .unwrap_err();
+        assert!(format!("{:?}", err).contains("groupby aggregation"));
+
+        let err = df
+            .clone()
+            .lazy()
+            .select(vec![col("a"), col("b").alias("a")])
+            .collect()
+            .unwrap_err();
+        assert!(format!("{:?}", err).contains("projection"));
+
+        let out = df
+            .lazy()
+            .groupby(vec![col("a")])
+            .agg(vec![col("b").sum().alias("b")])
+            .collect()?;
+        assert_eq!(out.get_column_names(), &["a", "b"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_concat_diagonal() -> Result<()> {
+        let january = df![
+            "id" => [1, 2],
+            "value" => [1, 2],
+        ]?;
+        let february = df![
+            "value" => [1.5, 2.5],
+            "id" => [3, 4],
+            "note" => ["a", "b"],
+        ]?;
+
+        let lf = concat(
+            &[january.clone().lazy(), february.clone().lazy()],
+            ConcatHow::Diagonal,
+        )?;
+        let out = lf.clone().collect()?;
+        assert_eq!(out.get_column_names(), &["id", "value", "note"]);
+        assert_eq!(out.column("value")?.dtype(), &DataType::Float64);
+
+        let out = lf
+            .filter(col("id").gt(lit(1)))
+            .select(vec![col("value"), col("note")])
+            .collect()?;
+        assert_eq!(out.get_column_names(), &["value", "note"]);
+        assert_eq!(
+            Vec::from(out.column("value")?.f64()?),
+            &[Some(2.0), Some(1.5), Some(2.5)]
+        );
+        assert_eq!(
+            Vec::from(out.column("note")?.utf8()?),
+            &[None, Some("a"), Some("b")]
+        );
+
+        // a vertical concatenation requires equal schemas
+        assert!(concat(
+            &[january.clone().lazy(), february.lazy()],
+            ConcatHow::Vertical
+        )
+        .is_err());
+        let out = concat(
+            &[january.clone().lazy(), january.lazy()],
+            ConcatHow::Vertical,
+        )?
+        .filter(col("id").eq(lit(2)))
+        .collect()?;
+        assert_eq!(out.shape(), (2, 2));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "merge_sorted")]
+    fn test_lazy_merge_sorted() -> Result<()> {
+        let left = df![
+            "ts" => [1, 4, 6],
+            "value" => ["a", "b", "c"],
+        ]?;
+        let right = df![
+            "ts" => [2, 4, 5],
+            "value" => ["d", "e", "f"],
+        ]?;
+
+        // the key is not projected, but is needed for the merge
+        let out = left
+            .lazy()
+            .merge_sorted(right.lazy(), "ts")?
+            .select(vec![col("value")])
+            .collect()?;
+        assert_eq!(out.get_column_names(), &["value"]);
+        assert_eq!(
+            Vec::from(out.column("value")?.utf8()?),
+            &[
+                Some("a"),
+                Some("d"),
+                Some("b"),
+                Some("e"),
+                Some("f"),
+                Some("c")
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_toggle_optimizations() -> Result<()> {
+        let df = df![
+            "a" => [1, 2, 3],
+            "b" => [1, 2, 3],
+        ]?;
+        let q = df
+            .lazy()
+            .with_column((col("b") * lit(2)).alias("c"))
+            .filter(col("a").gt(lit(1)));
+
+        // with predicate pushdown the filter is applied at the scan level
+        let optimized = q.describe_optimized_plan()?;
+        let q_no_pd = q.clone().with_predicate_pushdown(false);
+        let not_optimized = q_no_pd.describe_optimized_plan()?;
+        assert_ne!(optimized, not_optimized);
+        assert!(not_optimized.starts_with("FILTER"));
+        assert!(q
+            .clone()
+            .without_optimizations()
+            .describe_optimized_plan()?
+            .starts_with("FILTER"));
+
+        let out = q.collect()?;
+        assert!(out.frame_equal(&q_no_pd.collect()?));
+        assert_eq!(out.shape(), (2, 3));
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_str_parse() -> Result<()> {
+        let df = df![
+            "a" => ["1,000", " 2", "x"],
+            "b" => ["1.5", "TRUE", "false"],
+        ]?;
+        let out = df
+            .clone()
+            .lazy()
+            .select(vec![
+                col("a")
+                    .str()
+                    .with_parse_options(ParseOptions {
+                        thousands_separator: Some(b','),
+                        ..Default::default()
+                    })
+                    .to_integer(false),
+                col("b").str().to_float(false).alias("float"),
+                col("b").str().to_boolean().alias("bool"),
+            ])
+            .collect()?;
+        assert_eq!(
+            Vec::from(out.column("a")?.i64()?),
+            &[Some(1000), Some(2), None]
+        );
+        assert_eq!(
+            Vec::from(out.column("float")?.f64()?),
+            &[Some(1.5), None, None]
+        );
+        assert_eq!(
+            Vec::from(out.column("bool")?.bool()?),
+            &[None, Some(true), Some(false)]
+        );
+
+        let out = df
+            .lazy()
+            .select(vec![col("a").str().to_integer(true)])
+            .collect();
+        assert!(out.is_err());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "temporal")]
+    fn test_lazy_strptime() -> Result<()> {
+        let df = df![
+            "a" => ["2021-03-19T10:15:30.250Z", "2021-03-20T00:00:00Z", "x"],
+        ]?;
+        let out = df
+            .clone()
+            .lazy()
+            .select(vec![
+                col("a").str().strptime(DataType::Date64, None),
+                col("a")
+                    .str()
+                    .strptime(DataType::Date32, None)
+                    .alias("date"),
+            ])
+            .collect()?;
+        assert_eq!(
+            Vec::from(out.column("a")?.date64()?),
+            &[Some(1616148930250), Some(1616198400000), None]
+        );
+        assert_eq!(
+            Vec::from(out.column("date")?.date32()?),
+            &[Some(18705), Some(18706), None]
+        );
+
+        let out = df
+            .lazy()
+            .select(vec![col("a")
+                .str()
+                .with_parse_options(ParseOptions {
+                    strict: true,
+                    ..Default::default()
+                })
+                .strptime(DataType::Date64, None)])
+            .collect();
+        assert!(out.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_groupby_groups() -> Result<()> {
+        let df = df![
+            "a" => [Some("foo"), None, Some("foo"), None, Some("bar")],
+            "b" => [1, 1, 1, 1, 2],
+        ]?;
+        let out = df
+            .lazy()
+            .groupby(vec![col("a"), col("b")])
+            .groups()
+            .collect()?;
+        assert_eq!(out.get_column_names(), &["a", "b", "groups"]);
+        assert_eq!(
+            out.column("groups")?.dtype(),
+            &DataType::List(IDX_DTYPE.to_arrow())
+        );
+        let mut groups = out
+            .column("a")?
+            .utf8()?
+            .into_iter()
+            .zip(out.column("groups")?.list()?.into_iter())
+            .map(|(key, opt_s)| (key, Vec::from(opt_s.unwrap().idx().unwrap())))
+            .collect::<Vec<_>>();
+        groups.sort();
+        assert_eq!(
+            groups,
+            &[
+                (None, vec![Some(1), Some(3)]),
+                (Some("bar"), vec![Some(4)]),
+                (Some("foo"), vec![Some(0), Some(2)]),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_scan_csv_bom() -> Result<()> {
+        let path = std::env::temp_dir().join("polars_test_lazy_scan_csv_bom.csv");
+        std::fs::write(&path, "\u{feff}id,name,value\r\n1,foo,1.5\r\n2,bar,2.5\r\n")?;
+
+        let out = LazyCsvReader::new(path.to_string_lossy().to_string())
+            .finish()
+            .select(vec![col("id"), col("value")])
+            .filter(col("id").gt(lit(1)))
+            .collect()?;
+        std::fs::remove_file(&path)?;
+
+        assert_eq!(out.get_column_names(), &["id", "value"]);
+        assert_eq!(Vec::from(out.column("value")?.f64()?), &[Some(2.5)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_scan_csv_inference_options() -> Result<()> {
+        let path = std::env::temp_dir().join("polars_test_lazy_scan_csv_inference_options.csv");
+        std::fs::write(&path, "a,b\n1,\n2,\n")?;
+        let scan = || LazyCsvReader::new(path.to_string_lossy().to_string());
+
+        let schema = scan().finish().schema()?;
+        assert_eq!(schema.field_with_name("b")?.data_type(), &DataType::Utf8);
+
+        let options = InferenceOptions::default()
+            .with_dtypes(vec![DataType::Float64, DataType::Utf8])
+            .with_null_dtype(DataType::Float64);
+        let out = scan().with_inference_options(options).finish().collect()?;
+        std::fs::remove_file(&path)?;
+        assert_eq!(out.column("a")?.dtype(), &DataType::Float64);
+        assert_eq!(out.column("b")?.dtype(), &DataType::Float64);
+        assert_eq!(out.column("b")?.null_count(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_scan_cache_file_rewritten() -> Result<()> {
+        let path = std::env::temp_dir().join("polars_test_lazy_scan_cache_file_rewritten.csv");
+        let scan = || LazyCsvReader::new(path.to_string_lossy().to_string()).finish();
+        std::fs::write(&path, "a\n1\n2\n")?;
+        let state = ExecutionState::new();
+
+        let out = scan().collect_with_state(&state)?;
+        assert_eq!(Vec::from(out.column("a")?.i64()?), &[Some(1), Some(2)]);
+
+        // the file changed since it was cached
+        std::fs::write(&path, "a\n10\n20\n30\n")?;
+        let out = scan().collect_with_state(&state)?;
+        assert_eq!(
+            Vec::from(out.column("a")?.i64()?),
+            &[Some(10), Some(20), Some(30)]
+        );
+        let out = scan().collect_fresh(&state)?;
+        assert_eq!(out.height(), 3);
+
+        // a removed file returns the error of opening the file
+        let lf = scan();
+        std::fs::remove_file(&path)?;
+        assert!(matches!(
+            lf.collect_with_state(&state),
+            Err(PolarsError::Io(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_scan_cache_keyed_on_scan_options() -> Result<()> {
+        let path = std::env::temp_dir().join("polars_test_lazy_scan_cache_keyed_on_options.csv");
+        let scan = || LazyCsvReader::new(path.to_string_lossy().to_string()).finish();
+        std::fs::write(&path, "a,b\n1,x\n2,y\n3,z\n")?;
+        // files that were just written are not shared between queries
+        std::thread::sleep(std::time::Duration::from_secs(2));
+        let state = ExecutionState::new();
+
+        let out = scan().select(vec![col("a")]).collect_with_state(&state)?;
+        assert_eq!(out.get_column_names(), &["a"]);
+        let out = scan().select(vec![col("b")]).collect_with_state(&state)?;
+        assert_eq!(out.get_column_names(), &["b"]);
+        let out = scan().limit(1).collect_with_state(&state)?;
+        assert_eq!(out.shape(), (1, 2));
+        let out = scan().collect_with_state(&state)?;
+        assert_eq!(out.shape(), (3, 2));
+
+        // the predicates only differ in a literal and in an operator
+        let out = scan()
+            .filter(col("a").gt(lit(1)))
+            .collect_with_state(&state)?;
+        assert_eq!(out.height(), 2);
+        let out = scan()
+            .filter(col("a").gt(lit(2)))
+            .collect_with_state(&state)?;
+        assert_eq!(out.height(), 1);
+        let out = scan()
+            .filter(col("a").gt_eq(lit(2)))
+            .collect_with_state(&state)?;
+        assert_eq!(out.height(), 2);
+
+        // the `cache` nodes are not shared
+        let out = scan().cache().collect_with_state(&state)?;
+        assert_eq!(out.shape(), (3, 2));
+        let out = df![
+            "a" => [10, 20],
+            "b" => ["u", "v"]
+        ]?
+        .lazy()
+        .cache()
+        .collect_with_state(&state)?;
+        assert_eq!(Vec::from(out.column("a")?.i32()?), &[Some(10), Some(20)]);
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_physical_expr_as_expression() -> Result<()> {
+        use crate::logical_plan::Context;
+
+        let planner = DefaultPlanner::default();
+        let mut expr_arena = Arena::with_capacity(64);
+
+        let default_exprs = vec![
+            col("a"),
+            lit(1),
+            col("a") + lit(1),
+            col("a").not(),
+            col("a").alias("b"),
+            col("a").is_null(),
+            col("a").is_not_null(),
+            col("a").sort(true),
+            col("a").sort_by(vec![col("b")], vec![false]),
+            col("a").filter(col("b").gt(lit(1))),
+            col("a").take(col("idx")),
+            col("a").slice(1, 2),
+            col("a").cast(DataType::Float64),
+            when(col("a").gt(lit(1))).then(lit(1)).otherwise(lit(2)),
+            col("a").sum(),
+            col("a").quantile(0.5),
+            col("a").sum().over(vec![col("b")]),
+        ];
+        let agg_exprs = vec![
+            col("a").min(),
+            col("a").mean(),
+            col("a").list(),
+            col("a").agg_groups(),
+            col("a").quantile(0.5),
+            col("a").n_unique(),
+            col("a").count(),
+        ];
+        // functions cannot be compared, so we compare their formatting
+        let function_exprs = vec![
+            col("a").shift(1),
+            col("a").reverse(),
+            col("a").is_unique(),
+            col("a").map(Ok, None),
+            map_binary(col("a"), col("b"), |a, _b| Ok(a), None),
+        ];
+
+        for (exprs, ctxt) in vec![
+            (&default_exprs, Context::Default),
+            (&agg_exprs, Context::Aggregation),
+        ] {
+            for expr in exprs {
+                let node = to_aexpr(expr.clone(), &mut expr_arena);
+                let phys = planner.create_physical_expr(node, ctxt, &mut expr_arena)?;
+                assert!(phys.as_expression() == expr, "{:?}", expr);
+                assert_eq!(format!("{:?}", phys), format!("{:?}", expr));
+            }
+        }
+        for expr in &function_exprs {
+            let node = to_aexpr(expr.clone(), &mut expr_arena);
+            let phys = planner.create_physical_expr(node, Context::Default, &mut expr_arena)?;
+            assert_eq!(format!("{:?}", phys), format!("{:?}", expr));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_multi_output_selection() -> Result<()> {
+        let df = df![
+            "id" => [1, 2, 3],
+            "sensor_1" => [1.0, 2.0, 3.0],
+            "sensor_2" => [4.0, 5.0, 6.0],
+            "sensor_x" => [7.0, 8.0, 9.0],
+            "name" => ["a", "b", "a"]
+        ]?;
+
+        let out = df
+            .clone()
+            .lazy()
+            .select(vec![col("^sensor_\\d+$").sum()])
+            .collect()?;
+        assert_eq!(out.get_column_names(), &["sensor_1", "sensor_2"]);
+        assert_eq!(out.column("sensor_2")?.f64()?.get(0), Some(15.0));
+
+        let out = df
+            .clone()
+            .lazy()
+            .select(vec![
+                dtype_col(&DataType::Float64).exclude(&["sensor_x"]) * lit(2.0),
+            ])
+            .collect()?;
+        assert_eq!(out.get_column_names(), &["sensor_1", "sensor_2"]);
+
+        let out = df
+            .clone()
+            .lazy()
+            .select(vec![col("*").exclude(&["id", "name"]).max()])
+            .collect()?;
+        assert_eq!(
+            out.get_column_names(),
+            &["sensor_1", "sensor_2", "sensor_x"]
+        );
+
+        // expansion inside an aggregation
+        let out = df
+            .clone()
+            .lazy()
+            .groupby(vec![col("name")])
+            .agg(vec![
+                cols(&["sensor_1", "sensor_x"]).sum(),
+                col("id").count(),
+            ])
+            .sort("name", false)
+            .collect()?;
+        assert_eq!(
+            out.get_column_names(),
+            &["name", "sensor_1_sum", "sensor_x_sum", "id_count"]
+        );
+        assert_eq!(
+            Vec::from(out.column("sensor_1_sum")?.f64()?),
+            &[Some(4.0), Some(2.0)]
+        );
+
+        // expansion inside with_columns replaces the selected columns
+        let out = df
+            .lazy()
+            .with_columns(vec![col("^sensor_.*$").exclude(&["sensor_x"]) * lit(10.0)])
+            .collect()?;
+        assert_eq!(
+            out.get_column_names(),
+            &["id", "sensor_1", "sensor_2", "sensor_x", "name"]
+        );
+        assert_eq!(
+            Vec::from(out.column("sensor_2")?.f64()?),
+            &[Some(40.0), Some(50.0), Some(60.0)]
+        );
+        assert_eq!(
+            Vec::from(out.column("sensor_x")?.f64()?),
+            &[Some(7.0), Some(8.0), Some(9.0)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_lazy_multi_output_alias() {
+        let df = df![
+            "a" => [1, 2, 3],
+            "b" => [1, 2, 3]
+        ]
+        .unwrap();
+        let _ = df.lazy().select(vec![col("*").sum().alias("sum")]);
+    }
+
+    #[test]
+    fn test_lazy_with_columns_wide() -> Result<()> {
+        // Add n / 5 columns and replace n / 5 columns of a frame of n columns. Only the execution
+        // of the plan is timed, as that is where the columns are added.
+        let execute = |n: usize| -> Result<(DataFrame, std::time::Duration)> {
+            let columns = (0..n)
+                .map(|i| Series::new(&format!("c{}", i), [i as i32, i as i32]))
+                .collect();
+            let df = DataFrame::new(columns)?;
+            let m = n / 5;
+            let exprs = (0..m)
+                .map(|i| (col(&format!("c{}", i)) * lit(2)).alias(&format!("new{}", i)))
+                .chain((0..m).map(|i| col(&format!("c{}", i)) + lit(1)))
+                .collect();
+
+            let mut expr_arena = Arena::new();
+            let mut lp_arena = Arena::new();
+            let lp_top = df
+                .lazy()
+                .with_columns(exprs)
+                .optimize(&mut lp_arena, &mut expr_arena)?;
+            let mut physical_plan = DefaultPlanner::default().create_physical_plan(
+                lp_top,
+                &mut lp_arena,
+                &mut expr_arena,
+            )?;
+            let now = std::time::Instant::now();
+            let out = physical_plan.execute(&ExecutionState::new())?;
+            Ok((out, now.elapsed()))
+        };
+        // the fastest of a few runs, to reduce the noise of the timings
+        let fastest = |n: usize| -> Result<(DataFrame, f64)> {
+            let mut best = execute(n)?;
+            for _ in 0..4 {
+                let run = execute(n)?;
+                if run.1 < best.1 {
+                    best = run;
+                }
+            }
+            Ok((best.0, best.1.as_secs_f64()))
+        };
+
+        let (_, small) = fastest(2000)?;
+        let (out, large) = fastest(8000)?;
+        // four times the columns: linear scaling takes ~4x as long, quadratic scaling ~16x
+        assert!(
+            large < small * 10.0,
+            "adding columns scales quadratically: {}s for 2000 columns, {}s for 8000 columns",
+            small,
+            large
+        );
+
+        assert_eq!(out.shape(), (2, 9600));
+        assert_eq!(out.get_column_names()[9599], "new1599");
+        assert_eq!(out.column("new1599")?.i32()?.get(0), Some(3198));
+        assert_eq!(out.column("c1599")?.i32()?.get(0), Some(1600));
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_null_literal() -> Result<()> {
+        let df = df![
+            "a" => [1.0, 2.0, 3.0],
+            "b" => ["x", "y", "z"]
+        ]?;
+
+        let out = df
+            .clone()
+            .lazy()
+            .with_columns(vec![
+                lit_null(DataType::Float64).alias("placeholder"),
+                lit_null(DataType::Utf8).alias("str"),
+                lit_null(DataType::Categorical).alias("cat"),
+                lit_null(DataType::List(ArrowDataType::Int64)).alias("list"),
+                (col("a") + lit(NULL)).alias("sum"),
+                col("b").eq(lit(NULL)).alias("eq"),
+            ])
+            .collect()?;
+        assert_eq!(out.column("placeholder")?.dtype(), &DataType::Float64);
+        assert_eq!(out.column("str")?.dtype(), &DataType::Utf8);
+        assert_eq!(out.column("cat")?.dtype(), &DataType::Categorical);
+        assert_eq!(
+            out.column("list")?.dtype(),
+            &DataType::List(ArrowDataType::Int64)
+        );
+        assert_eq!(out.column("sum")?.dtype(), &DataType::Float64);
+        assert_eq!(out.column("eq")?.dtype(), &DataType::Boolean);
+        for name in &["placeholder", "str", "cat", "list", "sum"] {
+            assert_eq!(out.column(name)?.null_count(), 3, "{}", name);
+        }
+
+        // the typed null column can be combined with other data
+        let mut stacked = out.select(("a", "placeholder"))?;
+        stacked.vstack_mut(&df![
+            "a" => [4.0],
+            "placeholder" => [1.0]
+        ]?)?;
+        assert_eq!(stacked.column("placeholder")?.null_count(), 3);
+
+        // an untyped null column is an error
+        let out = df.lazy().select(vec![lit(NULL).alias("x")]).collect();
+        assert!(out.is_err());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "row_hash")]
+    fn test_lazy_hash() -> Result<()> {
+        let df = df![
+            "a" => [1, 2, 1],
+            "b" => ["foo", "bar", "foo"]
+        ]?;
+        let out = df
+            .clone()
+            .lazy()
+            .select(vec![col("a").hash(0), col("b").hash(42)])
+            .collect()?;
+        assert_eq!(out.column("a")?.dtype(), &DataType::UInt64);
+        assert!(out
+            .column("a")?
+            .series_equal(&df.column("a")?.stable_hash(0)?.into_series()));
+        assert!(out
+            .column("b")?
+            .series_equal(&df.column("b")?.stable_hash(42)?.into_series()));
+
+        // equal values have equal hashes
+        let a = out.column("a")?.u64()?;
+        assert_eq!(a.get(0), a.get(2));
+        assert_ne!(a.get(0), a.get(1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_groupby_sort_by_multiple() -> Result<()> {
+        let df = df![
+            "a" => ["a", "a", "a", "a", "b", "b", "b"],
+            "val" => [1, 2, 3, 4, 5, 6, 7],
+            "ts" => [Some(3), None, Some(1), Some(3), Some(2), Some(2), None],
+            "ts2" => [2, 0, 0, 1, 9, 8, 0]
+        ]?;
+
+        let out = df
+            .clone()
+            .lazy()
+            .groupby(vec![col("a")])
+            .agg(vec![
+                // nulls first and ties keep their order
+                col("val")
+                    .sort_by(vec![col("ts")], vec![false])
+                    .list()
+                    .alias("by_ts"),
+                col("val")
+                    .sort_by(vec![col("ts")], vec![false])
+                    .first()
+                    .alias("first"),
+                // ties are broken by the second column
+                col("val")
+                    .sort_by(vec![col("ts"), col("ts2")], vec![false, false])
+                    .alias("by_ts_ts2"),
+                col("val")
+                    .sort_by(vec![col("ts"), col("ts2")], vec![false, false])
+                    .last()
+                    .alias("last"),
+                // nulls last when reversed
+                col("val")
+                    .sort_by(vec![col("ts")], vec![true])
+                    .list()
+                    .alias("by_ts_rev"),
+                col("val")
+                    .sort_by(vec![col("ts"), col("ts2")], vec![true, false])
+                    .first()
+                    .alias("first_rev"),
+            ])
+            .sort("a", false)
+            .collect()?;
+
+        let get_lists = |name: &str| -> Vec<Vec<i32>> {
+            out.column(name)
+                .unwrap()
+                .list()
+                .unwrap()
+                .into_iter()
+                .map(|opt_s| opt_s.unwrap().i32().unwrap().into_no_null_iter().collect())
+                .collect()
+        };
+        assert_eq!(get_lists("by_ts"), &[vec![2, 3, 1, 4], vec![7, 5, 6]]);
+        assert_eq!(get_lists("by_ts_ts2"), &[vec![2, 3, 4, 1], vec![7, 6, 5]]);
+        assert_eq!(get_lists("by_ts_rev"), &[vec![1, 4, 3, 2], vec![5, 6, 7]]);
+        assert_eq!(Vec::from(out.column("first")?.i32()?), &[Some(2), Some(7)]);
+        assert_eq!(Vec::from(out.column("last")?.i32()?), &[Some(1), Some(5)]);
+        assert_eq!(
+            Vec::from(out.column("first_rev")?.i32()?),
+            &[Some(4), Some(6)]
+        );
+
+        // in a selection context the whole column is sorted
+        let out = df
+            .clone()
+            .lazy()
+            .select(vec![
+                col("val").sort_by(vec![col("ts"), col("ts2")], vec![false, false])
+            ])
+            .collect()?;
+        assert_eq!(
+            Vec::from(out.column("val")?.i32()?),
+            [2, 7, 3, 6, 5, 4, 1]
+                .iter()
+                .copied()
+                .map(Some)
+                .collect::<Vec<_>>()
+        );
+
+        // the number of reverse flags must match
+        let out = df
+            .lazy()
+            .select(vec![
+                col("val").sort_by(vec![col("ts"), col("ts2")], vec![false])
+            ])
+            .collect();
+        assert!(out.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_fetch_does_not_leak_after_panic() -> Result<()> {
+        let df = df![
+            "a" => [1, 2, 3, 4, 5]
+        ]?;
+
+        let lf = df.clone().lazy();
+        let out = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            lf.select(vec![col("a").map(
+                |_s: Series| -> Result<Series> { panic!("failing udf") },
+                None,
+            )])
+            .fetch(2)
+        }));
+        assert!(out.is_err());
+
+        let out = df.clone().lazy().select(vec![col("a")]).collect()?;
+        assert_eq!(out.height(), 5);
+        let out = df.lazy().fetch(2)?;
+        assert_eq!(out.height(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_set() -> Result<()> {
+        let df = df! {
+            "a" => [Some(1), None, Some(3), Some(4)],
+            "b" => [1, 2, 3, 4]
+        }?;
+
+        let out = df
+            .lazy()
+            .select(vec![col("a").set(col("b").gt(lit(2)), lit(10))])
+            .collect()?;
+        assert_eq!(
+            Vec::from(out.column("a")?.i32()?),
+            &[Some(1), None, Some(10), Some(10)]
+        );
+
+        let out = df
+            .lazy()
+            .select(vec![col("a").set(col("b").eq(lit(1)), lit(NULL))])
+            .collect()?;
+        assert_eq!(
+            Vec::from(out.column("a")?.i32()?),
+            &[None, None, Some(3), Some(4)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_groupby_wildcard_excludes_keys() -> Result<()> {
+        let df = df! {
+            "k1" => [1, 1, 2],
+            "k2" => ["a", "a", "b"],
+            "v1" => [1, 2, 3],
+            "v2" => [1.0, 2.0, 3.0],
+            "v3" => [4, 5, 6]
+        }?;
+
+        // two keys: default groupby
+        let lf = df
+            .clone()
+            .lazy()
+            .groupby(vec![col("k1"), col("k2")])
+            .agg(vec![col("*").sum()]);
+        // the wildcard is expanded when the plan is resolved, the schema is already known
+        match &lf.logical_plan {
+            LogicalPlan::Aggregate { aggs, .. } => assert_eq!(aggs, &[col("*").sum()]),
+            lp => panic!("expected an aggregation, got {:?}", lp),
+        }
+        assert_eq!(lf.schema()?.fields().len(), 5);
+        let out = lf.collect()?;
+        assert_eq!(out.get_column_names(), &["k1", "k2", "v1", "v2", "v3"]);
+
+        // a single computed key: partitioned groupby
+        let out = df
+            .clone()
+            .lazy()
+            .groupby(vec![col("k1") * lit(10)])
+            .agg(vec![col("*").exclude(&["k2"]).sum()])
+            .sort("k1", false)
+            .collect()?;
+        assert_eq!(out.get_column_names(), &["k1", "v1", "v2", "v3"]);
+        assert_eq!(Vec::from(out.column("v3")?.i32()?), &[Some(9), Some(6)]);
+
+        let out = df
+            .lazy()
+            .groupby(vec![col("k1"), col("k2")])
+            .with_expanded_keys(true)
+            .agg(vec![col("*").exclude_dtype(&[DataType::Utf8]).sum()])
+            .collect()?;
+        assert_eq!(out.width(), 6);
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_map_optimization_barrier() -> Result<()> {
+        let df = df![
+            "a" => [1, 2, 3],
+            "b" => [1, 2, 3],
+        ]?;
+        let f = |df: DataFrame| -> Result<DataFrame> { Ok(df.head(Some(2))) };
+
+        // the filter may not pass the map, as that changes the result
+        let q = df
+            .clone()
+            .lazy()
+            .map(f, AllowedOptimizations::default(), None)
+            .filter(col("a").gt(lit(1)));
+        assert!(q.describe_optimized_plan()?.starts_with("FILTER"));
+        let out = q.collect()?;
+        assert_eq!(Vec::from(out.column("a")?.i32()?), &[Some(2)]);
+
+        let q = df
+            .lazy()
+            .map(f, AllowedOptimizations::all(), None)
+            .filter(col("a").gt(lit(1)));
+        assert!(q.describe_optimized_plan()?.starts_with("MAP UDF"));
+        let out = q.collect()?;
+        assert_eq!(Vec::from(out.column("a")?.i32()?), &[Some(2), Some(3)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_join_with_options() -> Result<()> {
+        let left = df![
+            "id" => [1, 2, 3],
+            "value" => [1, 2, 3],
+        ]?;
+        let right = df![
+            "id" => [1, 2, 4],
+            "value" => [10, 20, 40],
+        ]?;
+        let options = JoinOptions {
+            how: JoinType::Left,
+            suffix: "_other".into(),
+            force_parallel: true,
+            ..Default::default()
+        };
+        let lf =
+            left.lazy()
+                .join_with_options(right.lazy(), vec![col("id")], vec![col("id")], options);
+        let out = lf.clone().collect()?;
+        assert_eq!(out.get_column_names(), &["id", "value", "value_other"]);
+
+        // the suffixed column is pushed down without the suffix
+        let out = lf.select(vec![col("value_other")]).collect()?;
+        assert_eq!(
+            Vec::from(out.column("value_other")?.i32()?),
+            &[Some(10), Some(20), None]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_join_empty_suffix() -> Result<()> {
+        let df = df![
+            "id" => [1, 2, 3],
+        ]?;
+        let options = JoinOptions {
+            suffix: "".into(),
+            ..Default::default()
+        };
+        let out = df
+            .clone()
+            .lazy()
+            .join_with_options(df.lazy(), vec![col("id")], vec![col("id")], options)
+            .collect();
+        assert!(matches!(out, Err(PolarsError::ValueError(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_groupby_without_keys() -> Result<()> {
+        let df = df![
+            "a" => [1, 1, 2],
+        ]?;
+        let out = df.clone().lazy().groupby(vec![]).groups().collect();
+        assert!(matches!(out, Err(PolarsError::ValueError(_))));
+        let out = df
+            .lazy()
+            .groupby(vec![])
+            .agg(vec![col("a").sum()])
+            .collect();
+        assert!(matches!(out, Err(PolarsError::ValueError(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_groupby_conflicting_schema() -> Result<()> {
+        let df = df![
+            "a" => [1, 1, 2],
+            "b" => [1, 2, 3],
+        ]?;
+        // the key and the aggregation have the same name but no common supertype
+        let out = df
+            .lazy()
+            .groupby(vec![col("a")])
+            .agg(vec![col("b").list().alias("a")])
+            .collect();
+        assert!(matches!(out, Err(PolarsError::DataTypeMisMatch(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_join_parallel_heuristic() -> Result<()> {
+        use polars_core::verbose::capture_verbose;
+
+        let left = df![
+            "id" => [1, 2, 3],
+        ]?;
+        let right = df![
+            "id" => [1, 2, 4],
+        ]?;
+        let join = |options: JoinOptions| {
+            capture_verbose(|| {
+                left.clone()
+                    .lazy()
+                    .join_with_options(
+                        right.clone().lazy(),
+                        vec![col("id")],
+                        vec![col("id")],
+                        options,
+                    )
+                    .collect()
+            })
+        };
+
+        // two in memory DataFrames are not worth executing in parallel
+        let (out, messages) = join(JoinOptions::default());
+        assert_eq!(out?.height(), 2);
+        // other tests may log at the same time
+        assert!(messages
+            .iter()
+            .any(|m| m == "join: executing the inputs sequentially"));
+
+        let (out, messages) = join(JoinOptions {
+            force_parallel: true,
+            ..Default::default()
+        });
+        assert_eq!(out?.height(), 2);
+        assert!(messages
+            .iter()
+            .any(|m| m == "join: executing the inputs in parallel"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_join_on_expression() -> Result<()> {
+        let left = df![
+            "id" => [1, 2, 3],
+            "value" => [1, 2, 3],
+        ]?;
+        let right = df![
+            "id" => [20, 30, 40],
+            "other" => [20, 30, 40],
+        ]?;
+
+        // the key is evaluated and not added to the output
+        let out = left
+            .clone()
+            .lazy()
+            .inner_join(
+                right.clone().lazy(),
+                (col("id") * lit(10)).alias("key"),
+                col("id"),
+            )
+            .sort("value", false)
+            .collect()?;
+        assert_eq!(out.get_column_names(), &["id", "value", "other"]);
+        assert_eq!(
+            Vec::from(out.column("other")?.i32()?),
+            &[Some(20), Some(30)]
+        );
+
+        // with a projection pushed down the id column is projected, not the key
+        let out = left
+            .clone()
+            .lazy()
+            .inner_join(right.clone().lazy(), col("id") * lit(10), col("id"))
+            .select(&[col("id"), col("other")])
+            .sort("id", false)
+            .collect()?;
+        assert_eq!(Vec::from(out.column("id")?.i32()?), &[Some(2), Some(3)]);
+
+        // a column that a right key is computed from is in the output
+        let out = left
+            .clone()
+            .lazy()
+            .inner_join(right.clone().lazy(), col("value"), col("id") / lit(10))
+            .sort("value", false)
+            .collect()?;
+        assert_eq!(
+            out.get_column_names(),
+            &["id", "value", "id_right", "other"]
+        );
+        assert_eq!(
+            Vec::from(out.column("id_right")?.i32()?),
+            &[Some(20), Some(30)]
+        );
+
+        // an outer join coalesces the keys of both sides into the output column of the left key
+        let out = left
+            .clone()
+            .lazy()
+            .outer_join(right.clone().lazy(), col("id") * lit(10), col("id"))
+            .sort("id", false)
+            .collect()?;
+        assert_eq!(out.get_column_names(), &["id", "value", "other"]);
+        assert_eq!(
+            Vec::from(out.column("id")?.i32()?),
+            &[Some(10), Some(20), Some(30), Some(40)]
+        );
+        assert_eq!(
+            Vec::from(out.column("other")?.i32()?),
+            &[None, Some(20), Some(30), Some(40)]
+        );
+
+        let out = left
+            .lazy()
+            .outer_join(right.lazy(), (col("id") * lit(10)).alias("key"), col("id"))
+            .select(&[col("key"), col("value")])
+            .sort("key", false)
+            .collect()?;
+        assert_eq!(out.get_column_names(), &["key", "value"]);
+        assert_eq!(
+            Vec::from(out.column("key")?.i32()?),
+            &[Some(10), Some(20), Some(30), Some(40)]
+        );
+        assert_eq!(
+            Vec::from(out.column("value")?.i32()?),
+            &[Some(1), Some(2), Some(3), None]
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "checked_arithmetic")]
+    fn test_lazy_checked_arithmetic() -> Result<()> {
+        let df = df![
+            "a" => [i64::MAX, -7, 7, -7],
+            "b" => [2i64, 2, -2, 0]
+        ]?;
+
+        let out = df
+            .clone()
+            .lazy()
+            .select(vec![
+                col("a").checked_mul(col("b")).alias("mul"),
+                col("a").checked_add(lit(1i64)).alias("add"),
+                (col("a") / col("b")).alias("div"),
+                col("a").floor_div(col("b")).alias("floor_div"),
+            ])
+            .collect()?;
+        assert_eq!(
+            Vec::from(out.column("mul")?.i64()?),
+            &[None, Some(-14), Some(-14), Some(0)]
+        );
+        assert_eq!(
+            Vec::from(out.column("add")?.i64()?),
+            &[None, Some(-6), Some(8), Some(-6)]
+        );
+        // `/` truncates towards zero, floor_div rounds towards negative infinity
+        assert_eq!(
+            Vec::from(out.column("div")?.i64()?),
+            &[Some(i64::MAX / 2), Some(-3), Some(-3), None]
+        );
+        assert_eq!(
+            Vec::from(out.column("floor_div")?.i64()?),
+            &[Some(i64::MAX / 2), Some(-4), Some(-4), None]
+        );
+
+        let out = df
+            .clone()
+            .lazy()
+            .select(vec![(col("a") * col("b")).strict_overflow()])
+            .collect();
+        let err = out.unwrap_err();
+        assert!(format!("{:?}", err).contains("row 0"));
+
+        let out = df
+            .lazy()
+            .filter(col("b").neq(lit(0i64)))
+            .select(vec![((col("a") - lit(1i64)) / col("b"))
+                .alias("c")
+                .strict_overflow()])
+            .collect()?;
+        assert_eq!(
+            Vec::from(out.column("c")?.i64()?),
+            &[Some((i64::MAX - 1) / 2), Some(-4), Some(-3)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "temporal", feature = "dtype-date32"))]
+    fn test_lazy_filter_date_str_literal() -> Result<()> {
+        let date = Date32Chunked::parse_from_str_slice(
+            "date",
+            &["2020-12-31", "2021-01-01", "2021-01-02", "2021-01-03"],
+            "%Y-%m-%d",
+        )
+        .into_series();
+        let df = DataFrame::new(vec![date, Series::new("value", &[1, 2, 3, 4])])?;
+
+        let q = df
+            .clone()
+            .lazy()
+            .filter(col("date").gt_eq(lit("2021-01-01")))
+            .filter(lit("2021-01-03").gt(col("date")));
+        // the string literals are parsed to typed dates and the column is not cast to Utf8
+        let optimized = q.describe_optimized_plan()?;
+        assert!(optimized.contains("Date(2021-01-01)"));
+        assert!(optimized.contains("Date(2021-01-03)"));
+        assert!(!optimized.contains("Utf8"));
+
+        let out = q.collect()?;
+        assert_eq!(Vec::from(out.column("value")?.i32()?), &[Some(2), Some(3)]);
+        assert_eq!(out.column("date")?.dtype(), &DataType::Date32);
+
+        let out = df
+            .clone()
+            .lazy()
+            .filter(col("date").eq(lit("2021-01-02")))
+            .collect()?;
+        assert_eq!(Vec::from(out.column("value")?.i32()?), &[Some(3)]);
+
+        let err = df
+            .lazy()
+            .filter(col("date").lt(lit("01/02/2021")))
+            .collect()
+            .unwrap_err();
+        assert!(format!("{:?}", err).contains("%Y-%m-%d"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_is_between_clip_abs() -> Result<()> {
+        let df = df![
+            "g" => ["a", "a", "b", "b"],
+            "x" => [Some(-3), Some(1), None, Some(5)],
+            "low" => [0, 0, 0, 6]
+        ]?;
+
+        let out = df
+            .clone()
+            .lazy()
+            .select(vec![
+                col("x")
+                    .is_between(col("low"), lit(5), ClosedInterval::Both)
+                    .alias("between"),
+                col("x").clip(lit(-1), lit(2)).alias("clip"),
+                col("x").abs().alias("abs"),
+            ])
+            .collect()?;
+        assert_eq!(out.column("between")?.dtype(), &DataType::Boolean);
+        assert_eq!(
+            Vec::from(out.column("between")?.bool()?),
+            &[Some(false), Some(true), None, Some(false)]
+        );
+        assert_eq!(
+            Vec::from(out.column("clip")?.i32()?),
+            &[Some(-1), Some(1), None, Some(2)]
+        );
+        assert_eq!(
+            Vec::from(out.column("abs")?.i32()?),
+            &[Some(3), Some(1), None, Some(5)]
+        );
+
+        // elementwise within the groups and on the aggregated values
+        let out = df
+            .lazy()
+            .groupby(vec![col("g")])
+            .agg(vec![
+                col("x").clip(lit(0), lit(2)).sum().alias("clip_sum"),
+                col("x").abs().max().alias("abs_max"),
+                col("x").sum().abs().alias("sum_abs"),
+            ])
+            .sort("g", false)
+            .collect()?;
+        assert_eq!(
+            Vec::from(out.column("clip_sum")?.i32()?),
+            &[Some(1), Some(2)]
+        );
+        assert_eq!(
+            Vec::from(out.column("abs_max")?.i32()?),
+            &[Some(3), Some(5)]
+        );
+        assert_eq!(
+            Vec::from(out.column("sum_abs")?.i32()?),
+            &[Some(2), Some(5)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_schema() -> Result<()> {
+        let left = df![
+            "k" => [1, 2, 3],
+            "v" => [1.0, 2.0, 3.0],
+            "s" => ["a", "b", "a"]
+        ]?;
+        let right = df![
+            "k" => [1, 2, 4],
+            "v" => [10.0, 20.0, 40.0]
+        ]?;
+
+        let lf = left
+            .clone()
+            .lazy()
+            .left_join(right.lazy(), col("k"), col("k"))
+            .groupby(vec![col("s")])
+            .agg(vec![
+                col("v").sum().alias("v"),
+                col("v_right").mean().alias("v_right"),
+                col("k").first().alias("k"),
+            ])
+            .with_columns(vec![
+                (col("v") * lit(2.0)).alias("v2"),
+                lit("x").alias("lit"),
+            ]);
+        let schema = lf.schema()?;
+        assert_eq!(
+            schema
+                .fields()
+                .iter()
+                .map(|f| f.name().as_str())
+                .collect::<Vec<_>>(),
+            &["s", "v", "v_right", "k", "v2", "lit"]
+        );
+        assert_eq!(schema, lf.collect()?.schema());
+
+        // exploded list columns get the inner dtype
+        let lf = left
+            .clone()
+            .lazy()
+            .groupby(vec![col("s")])
+            .agg(vec![col("v").list().alias("v")])
+            .explode(&[col("v")]);
+        let schema = lf.schema()?;
+        assert_eq!(schema.field_with_name("v")?.data_type(), &DataType::Float64);
+        assert_eq!(schema, lf.collect()?.schema());
+
+        // the output of a groupby apply is only known after execution
+        let lf = left.clone().lazy().groupby(vec![col("s")]).apply(Ok);
+        assert!(lf.schema().is_err());
+        // as is the output of a map without a schema
+        let lf = left
+            .clone()
+            .lazy()
+            .map(Ok, AllowedOptimizations::default(), None);
+        assert!(lf.schema().is_err());
+        let lf = left.lazy().map(
+            Ok,
+            AllowedOptimizations::default(),
+            Some(Schema::new(vec![Field::new("s", DataType::Utf8)])),
+        );
+        assert_eq!(lf.schema()?.fields().len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_arg_true() -> Result<()> {
+        let df = df![
+            "g" => ["a", "a", "b", "b"],
+            "v" => [Some(1), None, Some(3), Some(0)],
+            "m" => [Some(true), None, Some(false), Some(true)]
+        ]?;
+
+        let out = df
+            .clone()
+            .lazy()
+            .select(vec![
+                col("v").gt(lit(0)).arg_true().alias("idx"),
+                col("m").arg_true().alias("idx_m"),
+            ])
+            .collect()?;
+        assert_eq!(Vec::from(out.column("idx")?.idx()?), &[Some(0), Some(2)]);
+        assert_eq!(Vec::from(out.column("idx_m")?.idx()?), &[Some(0), Some(3)]);
+
+        let out = df
+            .lazy()
+            .groupby(vec![col("g")])
+            .agg(vec![col("m").arg_true()])
+            .collect();
+        assert!(out.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_partitioned_groupby_expression_keys() -> Result<()> {
+        // collect the (key, sum) pairs by position, as the key may collide with the output name
+        let pairs = |out: DataFrame| -> Result<Vec<(String, i32)>> {
+            let keys = out.select_at_idx(0).unwrap().cast::<Utf8Type>()?;
+            let sums = out.select_at_idx(1).unwrap().i32()?.clone();
+            let mut pairs = keys
+                .utf8()?
+                .into_iter()
+                .zip(sums.into_iter())
+                .map(|(k, v)| (k.unwrap().to_string(), v.unwrap()))
+                .collect::<Vec<_>>();
+            pairs.sort();
+            Ok(pairs)
+        };
+
+        // small frames run the partitioned groupby on the general branch
+        let df = df![
+            "a" => [1, 11, 2, 12, 3, 21],
+            "v" => [1, 2, 3, 4, 5, 6]
+        ]?;
+        let expected = vec![
+            ("10".to_string(), 9),
+            ("20".to_string(), 7),
+            ("30".to_string(), 5),
+        ];
+        let out = df
+            .clone()
+            .lazy()
+            .groupby(vec![(col("a") % lit(10)) * lit(10)])
+            .agg(vec![col("v").sum()])
+            .collect()?;
+        assert_eq!(out.get_column_names(), &["a", "v_sum"]);
+        assert_eq!(pairs(out)?, expected);
+
+        let out = df
+            .lazy()
+            .groupby(vec![((col("a") % lit(10)) * lit(10)).alias("v_sum")])
+            .agg(vec![col("v").sum()])
+            .collect()?;
+        assert_eq!(pairs(out)?, expected);
+
+        // a categorical key with few categories runs the categorical branch
+        let df = df![
+            "s" => (0..1000).map(|i| if i % 2 == 0 { "a" } else { "b" }).collect::<Vec<_>>(),
+            "v" => (0..1000).collect::<Vec<i32>>()
+        ]?;
+        let expected = vec![("a".to_string(), 249500), ("b".to_string(), 250000)];
+        let out = df
+            .clone()
+            .lazy()
+            .groupby(vec![col("s").cast(DataType::Categorical)])
+            .agg(vec![col("v").sum()])
+            .collect()?;
+        assert_eq!(out.get_column_names(), &["s", "v_sum"]);
+        assert_eq!(pairs(out)?, expected);
+
+        let out = df
+            .lazy()
+            .groupby(vec![col("s").cast(DataType::Categorical).alias("v_sum")])
+            .agg(vec![col("v").sum()])
+            .collect()?;
+        assert_eq!(pairs(out)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "ewma")]
+    fn test_lazy_ewm() -> Result<()> {
+        let df = df![
+            "key" => ["a", "b", "a", "b", "a"],
+            "price" => [1, 10, 2, 20, 4]
+        ]?;
+        let options = EWMOptions::new(0.5).and_adjust(false);
+
+        let out = df
+            .clone()
+            .lazy()
+            .select(vec![col("price").ewm_mean(options)])
+            .collect()?;
+        assert_eq!(
+            Vec::from(out.column("price")?.f64()?),
+            &[Some(1.0), Some(5.5), Some(3.75), Some(11.875), Some(7.9375)]
+        );
+
+        let out = df
+            .clone()
+            .lazy()
+            .groupby(vec![col("key")])
+            .agg(vec![col("price").ewm_mean(options)])
+            .sort("key", false)
+            .collect()?;
+        let agg = out.column("price")?.list()?;
+        let a = agg.get(0).unwrap();
+        assert_eq!(Vec::from(a.f64()?), &[Some(1.0), Some(1.5), Some(2.75)]);
+
+        let out = df
+            .lazy()
+            .select(vec![col("price").ewm_mean(options).over(vec![col("key")])])
+            .collect()?;
+        let agg = out.column("price")?.list()?;
+        let b = agg.get(1).unwrap();
+        assert_eq!(Vec::from(b.f64()?), &[Some(10.0), Some(15.0)]);
+
+        // the output matches the schema for Float32 input
+        let lf = df![
+            "price" => [1.0f32, 2.0, 4.0]
+        ]?
+        .lazy()
+        .select(vec![col("price").ewm_std(options)]);
+        assert_eq!(
+            lf.schema().field_with_name("price")?.data_type(),
+            &DataType::Float64
+        );
+        assert_eq!(lf.collect()?.column("price")?.dtype(), &DataType::Float64);
+        Ok(())
+    }
+
+    #[test]
+    fn test_simplify_expr_rules() -> Result<()> {
+        let df = df![
+            "a" => [Some(1), None, Some(3)],
+            "f" => [Some(-0.0), Some(f64::NAN), None],
+            "b" => [Some(true), None, Some(false)]
+        ]?;
+
+        // optimize a projection of a single expression and return the optimized expression
+        let optimized = |e: Expr| -> Result<Expr> {
+            let mut expr_arena = Arena::new();
+            let mut lp_arena = Arena::new();
+            let lp_top = df
+                .clone()
+                .lazy()
+                .select(vec![e])
+                .optimize(&mut lp_arena, &mut expr_arena)?;
+            match node_to_lp(lp_top, &mut expr_arena, &mut lp_arena) {
+                LogicalPlan::Projection { mut expr, .. } => Ok(expr.pop().unwrap()),
+                lp => panic!("expected a projection, got {:?}", lp),
+            }
+        };
+
+        // literals are folded with type promotion
+        assert_eq!(
+            optimized(lit(2) + lit(3.0))?,
+            Expr::Literal(LiteralValue::Float64(5.0))
+        );
+        assert_eq!(
+            optimized(lit(7) % lit(4))?,
+            Expr::Literal(LiteralValue::Int32(3))
+        );
+        assert_eq!(
+            optimized(lit(1).lt_eq(lit(1)))?,
+            Expr::Literal(LiteralValue::Boolean(true))
+        );
+        // integer overflow and division by zero are left to the runtime
+        assert!(matches!(
+            optimized(lit(1) / lit(0))?,
+            Expr::BinaryExpr { .. }
+        ));
+        assert!(matches!(
+            optimized(lit(i32::MAX) + lit(1))?,
+            Expr::BinaryExpr { .. }
+        ));
+
+        // algebraic no-ops
+        assert_eq!(optimized(col("a") * lit(1))?, col("a"));
+        assert_eq!(optimized(col("f") * lit(1.0))?, col("f"));
+        assert_eq!(optimized(col("f") - lit(0.0))?, col("f"));
+        // -0.0 + 0.0 is 0.0
+        assert!(matches!(
+            optimized(col("f") + lit(0.0))?,
+            Expr::BinaryExpr { .. }
+        ));
+        // the output type changes, only the cast inserted by the type coercion remains
+        assert_eq!(
+            optimized(col("a") * lit(1.0))?,
+            col("a").cast(DataType::Float64)
+        );
+        // null * 0 is null
+        assert!(matches!(
+            optimized(col("a") * lit(0))?,
+            Expr::BinaryExpr { .. }
+        ));
+
+        // boolean short-circuiting
+        assert_eq!(optimized(col("b").and(lit(true)))?, col("b"));
+        assert_eq!(optimized(col("b").or(lit(false)))?, col("b"));
+        assert_eq!(
+            optimized(lit(true).and(col("b")))?,
+            col("b").alias("literal")
+        );
+        // the literal is broadcast to the length of the input and keeps the output name
+        let is_broadcast_lit = |e: Expr, expected: &str| {
+            matches!(e, Expr::Alias(e, name)
+                if matches!(e.as_ref(), Expr::Ternary { .. }) && name.as_str() == expected)
+        };
+        assert!(is_broadcast_lit(
+            optimized(lit(false).and(col("b")))?,
+            "literal"
+        ));
+        assert!(is_broadcast_lit(optimized(col("b").or(lit(true)))?, "b"));
+        assert_eq!(
+            optimized(lit(true).or(lit(false)))?,
+            Expr::Literal(LiteralValue::Boolean(true))
+        );
+        assert_eq!(optimized(col("b").not().not())?, col("b"));
+        assert_eq!(
+            optimized(when(lit(true)).then(col("a")).otherwise(col("f")))?,
+            col("a").cast(DataType::Float64)
+        );
+        assert_eq!(
+            optimized(when(lit(false)).then(col("a")).otherwise(col("f")))?,
+            col("f").alias("a")
+        );
+        assert_eq!(optimized(lit(1) * col("a"))?, col("a").alias("literal"));
+
+        // the simplified expressions give the same results as the runtime, `null & false` is
+        // false and `null | true` is true under Kleene logic
+        let exprs = vec![
+            (col("a") * lit(1)).alias("mul"),
+            (col("f") * lit(1.0)).alias("fmul"),
+            col("b").and(lit(false)).alias("and"),
+            col("b").or(lit(true)).alias("or"),
+            col("b").not().not().alias("not"),
+        ];
+        let out = df.clone().lazy().with_columns(exprs.clone()).collect()?;
+        let expected = df
+            .clone()
+            .lazy()
+            .with_simplify_expr(false)
+            .with_columns(exprs)
+            .collect()?;
+        // NaN doesn't equal NaN
+        assert!(out.drop("f")?.frame_equal_missing(&expected.drop("f")?));
+        assert_eq!(
+            Vec::from(out.column("and")?.bool()?),
+            &[Some(false), Some(false), Some(false)]
+        );
+        assert_eq!(
+            Vec::from(out.column("or")?.bool()?),
+            &[Some(true), Some(true), Some(true)]
+        );
+
+        let out = df
+            .clone()
+            .lazy()
+            .select(vec![col("b").or(lit(true)), lit(false).and(col("b"))])
+            .collect()?;
+        assert_eq!(out.shape(), (3, 2));
+        assert_eq!(out.get_column_names(), &["b", "literal"]);
+        let out = df.lazy().filter(col("b").or(lit(true))).collect()?;
+        assert_eq!(out.height(), 3);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "spill")]
+    fn test_lazy_spill() -> Result<()> {
+        let n = 1000;
+        let a = (0..n).map(|i| (i * 7919) % n).collect::<Vec<_>>();
+        let df = df![
+            "a" => &a,
+            "b" => a.iter().map(|v| format!("s{}", v % 17)).collect::<Vec<_>>(),
+            "g" => (0..n).map(|i| i % 13).collect::<Vec<_>>(),
+            "v" => (0..n).map(|i| i as f64 * 0.5).collect::<Vec<_>>()
+        ]?;
+        // a tiny budget forces every sort and groupby to spill
+        let collect = |lf: LazyFrame| -> Result<(DataFrame, DataFrame)> {
+            let spilled = lf.clone().with_memory_budget(Some(100)).collect()?;
+            let in_memory = lf.with_memory_budget(Some(usize::MAX)).collect()?;
+            Ok((spilled, in_memory))
+        };
+
+        let (spilled, in_memory) = collect(df.clone().lazy().sort("a", true))?;
+        assert_eq!(spilled.height(), n as usize);
+        assert!(spilled.frame_equal(&in_memory));
+
+        let (spilled, in_memory) = collect(
+            df.clone()
+                .lazy()
+                .sort_by_exprs(vec![col("b"), col("a") * lit(-1)], vec![false, true]),
+        )?;
+        assert!(spilled.frame_equal(&in_memory));
+
+        // partitionable aggregations
+        let (spilled, in_memory) = collect(
+            df.clone()
+                .lazy()
+                .groupby(vec![col("g")])
+                .agg(vec![col("v").sum(), col("a").max()])
+                .sort("g", false),
+        )?;
+        assert_eq!(spilled.height(), 13);
+        assert!(spilled.frame_equal(&in_memory));
+
+        // default hash aggregation on multiple keys
+        let (spilled, in_memory) = collect(
+            df.clone()
+                .lazy()
+                .groupby(vec![col("g"), col("b")])
+                .agg(vec![col("v").mean(), col("a").count()])
+                .sort_by_exprs(vec![col("g"), col("b")], vec![false, false]),
+        )?;
+        assert!(spilled.frame_equal(&in_memory));
+
+        // no groups are left, the output still has the dtypes of the schema
+        let (spilled, in_memory) = collect(
+            df.lazy()
+                .with_column(lit_null(DataType::Int32).alias("n"))
+                .groupby(vec![col("n")])
+                .with_drop_null_keys(true)
+                .agg(vec![col("v").list()]),
+        )?;
+        assert_eq!(spilled.height(), 0);
+        assert_eq!(spilled.schema(), in_memory.schema());
+        assert_eq!(
+            spilled.column("v")?.dtype(),
+            &DataType::List(ArrowDataType::Float64)
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "dtype-struct")]
+    fn test_lazy_struct() -> Result<()> {
+        use crate::functions::struct_;
+        let df = df![
+            "a" => [1, 2, 3],
+            "b" => ["x", "y", "z"],
+            "c" => [1.0, 2.0, 3.0]
+        ]?;
+
+        let lf = df
+            .lazy()
+            .select(vec![
+                struct_(vec![col("a"), col("b")]).alias("payload"),
+                col("c"),
+            ])
+            .filter(col("c").gt(lit(1.0)));
+        let schema = lf.schema()?;
+        let dtype = schema.field_with_name("payload")?.data_type();
+        assert_eq!(
+            dtype,
+            &DataType::Struct(vec![
+                Field::new("a", DataType::Int32),
+                Field::new("b", DataType::Utf8)
+            ])
+        );
+
+        let out = lf
+            .clone()
+            .select(vec![col("payload").struct_().field("b"), col("c")])
+            .collect()?;
+        assert_eq!(out.get_column_names(), &["b", "c"]);
+        assert_eq!(Vec::from(out.column("b")?.utf8()?), &[Some("y"), Some("z")]);
+
+        let out = lf.collect()?.unnest(&["payload"])?;
+        assert_eq!(out.get_column_names(), &["a", "b", "c"]);
+        assert_eq!(Vec::from(out.column("a")?.i32()?), &[Some(2), Some(3)]);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "parquet", feature = "dtype-i16"))]
+    fn test_aggregate_pushdown_sum_small_int() -> Result<()> {
+        use polars_io::prelude::ParquetWriter;
+
+        // the sum doesn't fit in an i16
+        let s = Int16Chunked::new_from_slice("a", &[30_000; 4]).into_series();
+        let df = DataFrame::new(vec![s])?;
+        let path =
+            std::env::temp_dir().join("polars_test_aggregate_pushdown_sum_small_int.parquet");
+        ParquetWriter::new(std::fs::File::create(&path)?).finish(&df)?;
+
+        let scan = || {
+            LazyFrame::new_from_parquet(path.to_string_lossy().to_string(), None, false)
+                .select(vec![col("a").sum()])
+        };
+        let pushed = scan().with_aggregate_pushdown(true).collect()?;
+        let unpushed = scan().with_aggregate_pushdown(false).collect()?;
+        std::fs::remove_file(&path)?;
+
+        assert_eq!(Vec::from(pushed.column("a")?.i64()?), &[Some(120_000)]);
+        assert!(pushed.frame_equal(&unpushed));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "parquet")]
+    fn test_parquet_row_group_pruning() -> Result<()> {
+        use polars_core::verbose::capture_verbose;
+        use polars_io::prelude::ParquetWriter;
+
+        let df = df![
+            "a" => (0..30i64).collect::<Vec<_>>(),
+            "b" => (0..30).map(|v| v as f64 * 0.5).collect::<Vec<_>>()
+        ]?;
+        let path = std::env::temp_dir().join("polars_test_parquet_row_group_pruning.parquet");
+        ParquetWriter::new(std::fs::File::create(&path)?)
+            .with_options(ParquetWriterOptions::default().with_row_group_size(Some(10)))
+            .finish(&df)?;
+        let scan = || LazyFrame::new_from_parquet(path.to_string_lossy().to_string(), None, false);
+
+        let (pruned, messages) =
+            capture_verbose(|| scan().filter(col("a").gt(lit(25i64))).collect());
+        let pruned = pruned?;
+        let unpruned = scan()
+            .filter(col("a").gt(lit(25i64)))
+            .with_chunk_pruning(false)
+            .collect()?;
+        assert!(messages
+            .iter()
+            .any(|m| m == "parquet scan: skipped 2 of 3 row groups based on their statistics"));
+        assert_eq!(
+            Vec::from(pruned.column("a")?.i64()?),
+            &[Some(26), Some(27), Some(28), Some(29)]
+        );
+        assert!(pruned.frame_equal(&unpruned));
+
+        // the rows of a skipped row group count towards the row limit of the scan
+        let limited = || {
+            LazyFrame::new_from_parquet(path.to_string_lossy().to_string(), Some(15), false)
+                .filter(col("a").gt(lit(12i64)))
+        };
+        let pruned = limited().collect()?;
+        let unpruned = limited().with_chunk_pruning(false).collect()?;
+        assert_eq!(Vec::from(pruned.column("a")?.i64()?), &[Some(13), Some(14)]);
+        assert!(pruned.frame_equal(&unpruned));
+
+        // all row groups are skipped
+        let out = scan()
+            .filter(col("b").lt(lit(-1.0)))
+            .select(vec![col("b"), col("a")])
+            .collect()?;
+        std::fs::remove_file(&path)?;
+        assert_eq!(out.shape(), (0, 2));
+        assert_eq!(out.get_column_names(), &["b", "a"]);
+        assert_eq!(out.column("a")?.dtype(), &DataType::Int64);
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_verbose_logging() -> Result<()> {
+        use polars_core::verbose::capture_verbose;
+
+        let left = df![
+            "a" => [1, 2, 3, 4],
+            "b" => ["a", "b", "c", "d"]
+        ]?;
+        let right = df![
+            "a" => [2, 3, 5],
+            "c" => [1.0, 2.0, 3.0]
+        ]?;
+        let (out, messages) = capture_verbose(|| {
+            left.lazy()
+                .filter(col("a").gt(lit(1)))
+                .inner_join(right.lazy(), col("a"), col("a"))
+                .with_predicate_pushdown(false)
+                .collect()
+        });
+        assert_eq!(out?.height(), 2);
+        // other tests may log at the same time
+        assert!(messages
+            .iter()
+            .any(|m| m == "filter: dataframe filtered from 4 to 3 rows"));
+        assert!(messages
+            .iter()
+            .any(|m| m == "join: Inner join of 3 and 3 rows finished with 2 rows"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_select_order() -> Result<()> {
+        let expected = &["sugars_g", "category", "calories"];
+        for pushdown in &[true, false] {
+            let out = scan_foods_csv()
+                .select(vec![col("sugars_g"), col("category"), col("calories")])
+                .with_projection_pushdown(*pushdown)
+                .collect()?;
+            assert_eq!(out.get_column_names(), expected);
+
+            let out = scan_foods_csv()
+                .with_column(col("fats_g").alias("fats"))
+                .select(vec![col("fats"), col("calories"), col("category")])
+                .with_projection_pushdown(*pushdown)
+                .collect()?;
+            assert_eq!(out.get_column_names(), &["fats", "calories", "category"]);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_groupby_drop_null_keys() -> Result<()> {
+        let df = df![
+            "a" => [Some(1), None, Some(1), Some(2), None, Some(2), Some(1)],
+            "b" => [Some("x"), Some("x"), None, Some("y"), Some("y"), Some("y"), Some("x")],
+            "v" => [1, 2, 3, 4, 5, 6, 7]
+        ]?;
+
+        // a sum can run partitioned, an aggregation over a filter cannot
+        let aggs = vec![
+            col("v").sum().alias("sum"),
+            col("v").filter(col("v").gt(lit(1))).sum().alias("filtered"),
+        ];
+        for key_names in &[vec!["a"], vec!["a", "b"]] {
+            for agg in &aggs {
+                let keys = key_names.iter().map(|name| col(name)).collect::<Vec<_>>();
+                let out = df
+                    .clone()
+                    .lazy()
+                    .groupby(keys.clone())
+                    .with_drop_null_keys(true)
+                    .agg(vec![agg.clone()])
+                    .sort_by_exprs(keys.clone(), vec![false; keys.len()])
+                    .collect()?;
+
+                let mut predicate = col(key_names[0]).is_not_null();
+                for name in &key_names[1..] {
+                    predicate = predicate.and(col(name).is_not_null());
+                }
+                let expected = df
+                    .clone()
+                    .lazy()
+                    .filter(predicate)
+                    .groupby(keys.clone())
+                    .agg(vec![agg.clone()])
+                    .sort_by_exprs(keys.clone(), vec![false; keys.len()])
+                    .collect()?;
+                assert!(out.frame_equal_missing(&expected));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_null_count_drop_nulls() -> Result<()> {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let df = df![
+            "g" => ["a", "a", "b", "b", "b", "c", "d"],
+            "v" => [None, Some(1), Some(2), None, None, None, Some(4)]
+        ]?;
+
+        // selection context
+        let out = df
+            .clone()
+            .lazy()
+            .select(vec![
+                col("v").null_count().alias("null_count"),
+                col("v").has_nulls().alias("has_nulls"),
+                col("v").drop_nulls().sum().alias("sum"),
+            ])
+            .collect()?;
+        assert_eq!(Vec::from(out.column("null_count")?.u32()?), &[Some(4)]);
+        assert_eq!(Vec::from(out.column("has_nulls")?.bool()?), &[Some(true)]);
+        assert_eq!(Vec::from(out.column("sum")?.i32()?), &[Some(7)]);
+
+        // the input is evaluated once
+        let calls = Arc::new(AtomicUsize::new(0));
+        let calls_ = calls.clone();
+        let input = col("v").map(
+            move |s: Series| {
+                calls_.fetch_add(1, Ordering::Relaxed);
+                Ok(s)
+            },
+            None,
+        );
+        let out = df
+            .clone()
+            .lazy()
+            .select(vec![input.drop_nulls()])
+            .collect()?;
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+        assert_eq!(
+            Vec::from(out.column("v")?.i32()?),
+            &[Some(1), Some(2), Some(4)]
+        );
+
+        // aggregation context, a single null_count runs as a partitioned groupby
+        let out = df
+            .clone()
+            .lazy()
+            .groupby(vec![col("g")])
+            .agg(vec![col("v").null_count()])
+            .sort("g", false)
+            .collect()?;
+        assert_eq!(out.get_column_names(), &["g", "v_null_count"]);
+        assert_eq!(out.column("v_null_count")?.dtype(), &DataType::UInt32);
+        assert_eq!(
+            Vec::from(out.column("v_null_count")?.u32()?),
+            &[Some(1), Some(2), Some(1), Some(0)]
+        );
+
+        let out = df
+            .lazy()
+            .groupby(vec![col("g")])
+            .agg(vec![
+                col("v").null_count().alias("null_count"),
+                col("v").has_nulls().alias("has_nulls"),
+                col("v").drop_nulls().first().alias("first"),
+                col("v").drop_nulls().count().alias("count"),
+            ])
+            .sort("g", false)
+            .collect()?;
+        assert_eq!(
+            Vec::from(out.column("null_count")?.u32()?),
+            &[Some(1), Some(2), Some(1), Some(0)]
+        );
+        assert_eq!(
+            Vec::from(out.column("has_nulls")?.bool()?),
+            &[Some(true), Some(true), Some(true), Some(false)]
+        );
+        assert_eq!(
+            Vec::from(out.column("first")?.i32()?),
+            &[Some(1), Some(2), None, Some(4)]
+        );
+        assert_eq!(
+            Vec::from(out.column("count")?.u32()?),
+            &[Some(1), Some(1), Some(0), Some(1)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "temporal", feature = "dtype-date64"))]
+    fn test_lazy_duration_arithmetic() -> Result<()> {
+        let df = df! {
+            "a" => ["a", "a", "b"],
+            "date" => [0i64, 86_400_000, 172_800_000]
+        }?;
+        let lf = df
+            .lazy()
+            .with_column(col("date").cast(DataType::Date64))
+            .with_column((col("date") + duration(0, 1, 30, 0, 0)).alias("later"))
+            .with_column((col("later") - col("date")).alias("diff"));
+
+        let schema = lf.schema()?;
+        assert_eq!(
+            schema.field_with_name("later")?.data_type(),
+            &DataType::Date64
+        );
+        assert_eq!(
+            schema.field_with_name("diff")?.data_type(),
+            &DataType::Duration(TimeUnit::Millisecond)
+        );
+        let out = lf.clone().collect()?;
+        assert_eq!(
+            Vec::from(out.column("diff")?.cast::<Int64Type>()?.i64()?),
+            &[Some(5_400_000), Some(5_400_000), Some(5_400_000)]
+        );
+
+        let out = lf
+            .groupby(vec![col("a")])
+            .agg(vec![
+                col("diff").sum().alias("sum"),
+                col("diff").mean().alias("mean"),
+                col("diff").max().alias("max"),
+            ])
+            .sort("a", false)
+            .collect()?;
+        for name in &["sum", "mean", "max"] {
+            assert_eq!(
+                out.column(name)?.dtype(),
+                &DataType::Duration(TimeUnit::Millisecond)
+            );
+        }
+        assert_eq!(
+            Vec::from(out.column("sum")?.cast::<Int64Type>()?.i64()?),
+            &[Some(10_800_000), Some(5_400_000)]
+        );
+        Ok(())
+    }
+
+    struct StrReverse;
+
+    impl ExprPlugin for StrReverse {
+        fn name(&self) -> &str {
+            "str_reverse"
+        }
+
+        fn evaluate(&self, inputs: &[Series], _state: &ExecutionState) -> Result<Series> {
+            let ca: Utf8Chunked = inputs[0]
+                .utf8()?
+                .into_iter()
+                .map(|opt_v| opt_v.map(|v| v.chars().rev().collect::<String>()))
+                .collect();
+            Ok(ca.into_series())
+        }
+
+        fn to_field(&self, fields: &[Field]) -> Result<Field> {
+            Ok(fields[0].clone())
+        }
+
+        fn is_elementwise(&self) -> bool {
+            true
+        }
+    }
+
+    struct StrTotalLen;
+
+    impl ExprPlugin for StrTotalLen {
+        fn name(&self) -> &str {
+            "str_total_len"
+        }
+
+        fn evaluate(&self, inputs: &[Series], _state: &ExecutionState) -> Result<Series> {
+            let len: u32 = inputs[0]
+                .utf8()?
+                .into_iter()
+                .flatten()
+                .map(|v| v.len() as u32)
+                .sum();
+            Ok(Series::new(inputs[0].name(), &[len]))
+        }
+
+        fn to_field(&self, fields: &[Field]) -> Result<Field> {
+            Ok(Field::new(fields[0].name(), DataType::UInt32))
+        }
+
+        fn is_aggregation(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn test_lazy_expr_plugin() -> Result<()> {
+        let df = df! {
+            "g" => ["a", "a", "b"],
+            "s" => ["ab", "cd", "xyz"]
+        }?;
+        let reverse = || plugin(vec![col("s")], Arc::new(StrReverse));
+        let total_len = || plugin(vec![col("s")], Arc::new(StrTotalLen));
+
+        let lf = df
+            .clone()
+            .lazy()
+            .with_column(reverse().alias("rev"))
+            .filter(col("g").eq(lit("a")));
+        assert!(lf.describe_plan().contains("str_reverse"));
+        let out = lf.collect()?;
+        assert_eq!(
+            Vec::from(out.column("rev")?.utf8()?),
+            &[Some("ba"), Some("dc")]
+        );
+
+        let out = df.clone().lazy().select(vec![total_len()]).collect()?;
+        assert_eq!(Vec::from(out.column("s")?.u32()?), &[Some(7)]);
+
+        let out = df
+            .lazy()
+            .groupby(vec![col("g")])
+            .agg(vec![reverse().alias("rev"), total_len().alias("len")])
+            .sort("g", false)
+            .collect()?;
+        // the elementwise plugin produces a list per group
+        let rev = out.column("rev")?.list()?.get(0).unwrap();
+        assert_eq!(Vec::from(rev.utf8()?), &[Some("ba"), Some("dc")]);
+        // the aggregation produces a value per group
+        assert_eq!(Vec::from(out.column("len")?.u32()?), &[Some(4), Some(3)]);
+        assert_eq!(out.column("len")?.n_chunks(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_join_categorical_scans() -> Result<()> {
+        let schema = Schema::new(vec![Field::new("category", DataType::Categorical)]);
+        let query = |overwrite: Option<&Schema>| {
+            let scan = |i: usize| {
+                let path = format!(
+                    "../../examples/aggregate_multiple_files_in_chunks/datasets/foods{}.csv",
+                    i
+                );
+                LazyCsvReader::new(path)
+                    .with_dtype_overwrite(overwrite)
+                    .finish()
+            };
+            scan(1).select(vec![col("category"), col("calories")]).join(
+                scan(2).select(vec![col("category"), col("fats_g")]),
+                vec![col("category")],
+                vec![col("category")],
+                JoinType::Inner,
+            )
+        };
+
+        let q = query(Some(&schema));
+        let plan = q.describe_optimized_plan()?;
+        assert!(plan.starts_with("GLOBAL STRING CACHE: join on categorical keys [\"category\"]"));
+        assert!(!query(None)
+            .describe_optimized_plan()?
+            .starts_with("GLOBAL STRING CACHE"));
+
+        let out = q.collect()?;
+        let category = out.column("category")?;
+        assert_eq!(category.dtype(), &DataType::Categorical);
+        // equal categories have equal codes
+        let codes = category.cast::<UInt32Type>()?;
+        assert_eq!(codes.n_unique()?, category.cast::<Utf8Type>()?.n_unique()?);
+
+        let sorted = |lf: LazyFrame| {
+            lf.with_column(col("category").cast(DataType::Utf8))
+                .sort_by_exprs(
+                    vec![col("category"), col("calories"), col("fats_g")],
+                    vec![false, false, false],
+                )
+                .collect()
+        };
+        let expected = sorted(query(None))?;
+        assert!(expected.height() > 0);
+        assert!(sorted(out.lazy())?.frame_equal(&expected));
+        // the same result as a query that is executed under the global string cache
+        assert!(sorted(query(Some(&schema)).with_string_cache(true))?.frame_equal(&expected));
+        Ok(())
+    }
+
+    #[test]
+    fn test_collect_with_rechunk() -> Result<()> {
+        let scans = || {
+            concat(
+                &[scan_foods_csv(), scan_foods_csv(), scan_foods_csv()],
+                ConcatHow::Vertical,
+            )
+        };
+        let df = scans()?.collect_with(CollectOptions::default())?;
+        assert_eq!(df.n_chunks()?, 3);
+        assert_eq!(scans()?.collect()?.n_chunks()?, 3);
+
+        for parallel_rechunk in &[true, false] {
+            let out = scans()?.collect_with(CollectOptions {
+                rechunk: true,
+                parallel_rechunk: *parallel_rechunk,
+            })?;
+            assert_eq!(out.n_chunks()?, 1);
+            assert!(out.frame_equal(&df));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_explode_expr_with_index() -> Result<()> {
+        let df = df![
+            "text" => ["a b", "c", "d e f"],
+            "id" => [1, 2, 3],
+            "other" => ["x", "y", "z"],
+            "unused" => [0.1, 0.2, 0.3]
+        ]?;
+        let split = col("text")
+            .map(
+                |s: Series| {
+                    let ca: ListChunked = s
+                        .utf8()?
+                        .into_iter()
+                        .map(|opt_v| {
+                            opt_v.map(|v| Series::new("", v.split(' ').collect::<Vec<_>>()))
+                        })
+                        .collect();
+                    Ok(ca.into_series())
+                },
+                Some(DataType::List(ArrowDataType::LargeUtf8)),
+            )
+            .alias("word");
+
+        let lf = df.lazy().explode_with_index(&[split], "row").select(vec![
+            col("row"),
+            col("word"),
+            col("id"),
+            col("other"),
+        ]);
+        assert_eq!(
+            lf.schema()?.field_with_name("word")?.data_type(),
+            &DataType::Utf8
+        );
+
+        // the input of the exploded expression survives projection pushdown
+        let out = lf.with_projection_pushdown(true).collect()?;
+        assert_eq!(out.get_column_names(), &["row", "word", "id", "other"]);
+        assert_eq!(
+            Vec::from(out.column("word")?.utf8()?),
+            &[
+                Some("a"),
+                Some("b"),
+                Some("c"),
+                Some("d"),
+                Some("e"),
+                Some("f")
+            ]
+        );
+        assert_eq!(
+            Vec::from(out.column("row")?.u32()?),
+            &[Some(0), Some(0), Some(1), Some(2), Some(2), Some(2)]
+        );
+        assert_eq!(
+            Vec::from(out.column("id")?.i32()?),
+            &[Some(1), Some(1), Some(2), Some(3), Some(3), Some(3)]
+        );
+        assert_eq!(
+            Vec::from(out.column("other")?.utf8()?),
+            &[
+                Some("x"),
+                Some("x"),
+                Some("y"),
+                Some("z"),
+                Some("z"),
+                Some("z")
+            ]
+        );
+
+        // an expression without an output name is an error, not a panic
+        let df = df![
+            "id" => [1, 2, 3]
+        ]?;
+        assert!(df.lazy().explode(&[lit(1)]).collect().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_agg_fold_rolling_apply() -> Result<()> {
+        let df = df! {
+            "g" => ["a", "a", "b", "a", "b"],
+            "v" => [Some(1), Some(2), None, Some(3), None]
+        }?;
+
+        let out = df
+            .clone()
+            .lazy()
+            .groupby(vec![col("g")])
+            .agg(vec![
+                col("v")
+                    .agg_fold::<Int64Type, _>(10, |acc, v| acc + v)
+                    .alias("fold"),
+                col("v")
+                    .rolling_apply::<Int64Type, _>(2, |w| w.iter().flatten().max().copied(), 1)
+                    .alias("roll"),
+            ])
+            .sort("g", false)
+            .collect()?;
+        assert_eq!(Vec::from(out.column("fold")?.i64()?), &[Some(16), None]);
+        let roll = out.column("roll")?.list()?.get(0).unwrap();
+        assert_eq!(Vec::from(roll.i64()?), &[Some(1), Some(2), Some(3)]);
+
+        let out = df
+            .clone()
+            .lazy()
+            .select(vec![col("v").agg_fold::<Int64Type, _>(1, |acc, v| acc * v)])
+            .collect()?;
+        assert_eq!(Vec::from(out.column("v")?.i64()?), &[Some(6)]);
+
+        let out = df
+            .lazy()
+            .select(vec![col("v")
+                .rolling_apply::<Int64Type, _>(2, |w| w.iter().flatten().copied().min(), 2)
+                .alias("roll")])
+            .collect()?;
+        assert_eq!(
+            Vec::from(out.column("roll")?.i64()?),
+            &[None, Some(1), None, None, None]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_filter_chunk_pruning() -> Result<()> {
+        // clustered chunks, of which one has min == max
+        let mut df = df!["a" => [1, 2, 3], "b" => [1.0, 2.0, 3.0]]?;
+        df.vstack_mut(&df!["a" => [5, 5], "b" => [5.0, 5.0]]?)?;
+        df.vstack_mut(&df!["a" => [6, 7], "b" => [f64::NAN, 5.0]]?)?;
+        df.vstack_mut(&df!["a" => [None::<i32>, None], "b" => [None::<f64>, None]]?)?;
+        assert_eq!(df.n_chunks()?, 4);
+
+        for column in &["a", "b"] {
+            let predicates = vec![
+                col(column).eq(lit(5)),
+                col(column).neq(lit(5)),
+                col(column).lt(lit(5)),
+                col(column).lt_eq(lit(5)),
+                col(column).gt(lit(5)),
+                col(column).gt_eq(lit(5)),
+                lit(5).lt(col(column)),
+                col(column).eq(lit(4)),
+                col(column).eq(lit(5.0)),
+                col(column).gt(lit(5.0)),
+            ];
+            for predicate in predicates {
+                let expected = df
+                    .clone()
+                    .lazy()
+                    .with_chunk_pruning(false)
+                    .filter(predicate.clone())
+                    .collect()?;
+                let out = df.clone().lazy().filter(predicate).collect()?;
+                // the frames contain NaN, so only compare the integer column
+                assert_eq!(out.height(), expected.height());
+                assert!(out.column("a")?.series_equal_missing(expected.column("a")?));
+            }
+        }
+
+        let out = df.lazy().filter(col("a").eq(lit(5))).collect()?;
+        assert_eq!(Vec::from(out.column("a")?.i32()?), &[Some(5), Some(5)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_sink_csv() -> Result<()> {
+        use polars_io::{csv::CsvReader, SerReader};
+
+        let path = std::env::temp_dir().join("polars_test_lazy_sink_csv_in.csv");
+        let out_path = std::env::temp_dir().join("polars_test_lazy_sink_csv_out.csv");
+        let mut csv = String::from("id,value,name\n");
+        for i in 0..20_000 {
+            csv.push_str(&format!("{},{},name{}\n", i, i as f64 * 0.5, i % 7));
+        }
+        std::fs::write(&path, csv)?;
+
+        let query = || {
+            LazyCsvReader::new(path.to_string_lossy().to_string())
+                .finish()
+                .filter(col("id").gt_eq(lit(100)))
+                .select(vec![col("id"), (col("value") * lit(2.0)).alias("value")])
+                .with_sink_batch_size(16 * 1024)
+        };
+        let expected = query().collect()?;
+        assert_eq!(expected.height(), 19_900);
+
+        // the filtered scan is executed per byte range and only a batch is passed at a time
+        let mut batches = 0;
+        let mut max_height = 0;
+        let mut first_ids = vec![];
+        let n_rows = query().sink_batches(|df| {
+            batches += 1;
+            max_height = std::cmp::max(max_height, df.height());
+            first_ids.push(df.column("id")?.i64()?.get(0));
+            Ok(())
+        })?;
+        assert_eq!(n_rows, expected.height());
+        assert!(batches > 10);
+        assert!(max_height < 2_000);
+        // batches are passed in the order of the file
+        assert_eq!(first_ids[0], Some(100));
+        assert!(first_ids.windows(2).all(|w| w[0] < w[1]));
+
+        let n_rows = query().sink_csv(&out_path, CsvWriterOptions::default())?;
+        assert_eq!(n_rows, expected.height());
+        let out = CsvReader::from_path(&out_path)?.finish()?;
+        assert!(out.frame_equal(&expected));
+
+        // a sort is blocking, so the result is collected first
+        let mut batches = 0;
+        let n_rows = query().sort("id", true).sink_batches(|df| {
+            batches += 1;
+            assert_eq!(df.get_columns()[0].n_chunks(), 1);
+            Ok(())
+        })?;
+        assert_eq!(n_rows, expected.height());
+        assert!(batches >= 1);
+
+        // an empty result still writes the header
+        let n_rows = query()
+            .filter(col("id").lt(lit(0)))
+            .sink_csv(&out_path, CsvWriterOptions::default())?;
+        assert_eq!(n_rows, 0);
+        assert_eq!(std::fs::read_to_string(&out_path)?, "id,value\n");
+
+        // the batches never split a quoted field with new lines
+        let mut csv = String::from("id,text\n");
+        for i in 0..200 {
+            csv.push_str(&format!("{},\"line\nbreak, \"\"{}\"\"\n\"\n", i, i));
+        }
+        std::fs::write(&path, csv)?;
+        let query = || {
+            LazyCsvReader::new(path.to_string_lossy().to_string())
+                .finish()
+                .filter(col("id").gt_eq(lit(10)))
+                .with_sink_batch_size(50)
+        };
+        let expected = query().collect()?;
+        assert_eq!(expected.height(), 190);
+        let mut out = DataFrame::new_empty(&expected.schema());
+        query().sink_batches(|df| out.vstack_mut(&df).map(|_| ()))?;
+        assert!(out.frame_equal(&expected));
+
+        std::fs::remove_file(&path)?;
+        std::fs::remove_file(&out_path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_scan_mask_reuse() -> Result<()> {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let calls_ = calls.clone();
+        let df = df![
+            "a" => [1, 2, 3, 2],
+            "b" => [1, 2, 3, 4],
+        ]?;
+        let predicate = col("a").map(
+            move |s: Series| {
+                calls_.fetch_add(1, Ordering::Relaxed);
+                Ok(s.gt(1).into_series())
+            },
+            Some(DataType::Boolean),
+        );
+        let lf = df.lazy().filter(predicate);
+
+        // both scans of the self join share the frame and the predicate
+        let out = lf.clone().inner_join(lf, col("a"), col("a")).collect()?;
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+        assert_eq!(out.height(), 5);
+        assert_eq!(out.column("b_right")?.sum::<i32>(), Some(15));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(
+        feature = "dtype-u8",
+        feature = "dtype-u16",
+        feature = "dtype-u64",
+        feature = "dtype-i8",
+        feature = "dtype-i16"
+    ))]
+    fn test_integer_dtype_matrix() -> Result<()> {
+        use DataType::*;
+        let df = df![
+            "key" => [3i32, 1, 2, 1, 3],
+            "value" => [10i32, 20, 30, 40, 50],
+        ]?;
+        let other = df![
+            "key" => [1i32, 3],
+            "name" => ["a", "c"],
+        ]?;
+        let as_i64 = |s: &Series| -> Result<Vec<Option<i64>>> {
+            Ok(Vec::from(s.cast_with_dtype(&Int64)?.i64()?))
+        };
+
+        for dtype in &[UInt8, UInt16, UInt32, UInt64, Int8, Int16, Int32, Int64] {
+            let lf = df
+                .clone()
+                .lazy()
+                .with_column(col("key").cast(dtype.clone()));
+
+            let out = lf
+                .clone()
+                .filter(col("key").gt(lit(1).cast(dtype.clone())))
+                .collect()?;
+            assert_eq!(out.column("key")?.dtype(), dtype);
+            assert_eq!(
+                Vec::from(out.column("value")?.i32()?),
+                &[Some(10), Some(30), Some(50)]
+            );
+
+            let out = lf.clone().sort("key", false).collect()?;
+            assert_eq!(
+                as_i64(out.column("key")?)?,
+                &[Some(1), Some(1), Some(2), Some(3), Some(3)]
+            );
+
+            let out = lf
+                .clone()
+                .groupby(vec![col("key")])
+                .agg(vec![col("value").sum(), col("value").mean().alias("mean")])
+                .sort("key", false)
+                .collect()?;
+            assert_eq!(out.column("key")?.dtype(), dtype);
+            assert_eq!(as_i64(out.column("key")?)?, &[Some(1), Some(2), Some(3)]);
+            assert_eq!(
+                as_i64(out.column("value_sum")?)?,
+                &[Some(60), Some(30), Some(60)]
+            );
+
+            // small integers are summed as 64 bit integers
+            let out = lf.clone().select(vec![col("key").sum()]).collect()?;
+            let sum_dtype = match dtype {
+                UInt8 | UInt16 => &UInt64,
+                Int8 | Int16 => &Int64,
+                dtype => dtype,
+            };
+            assert_eq!(out.column("key")?.dtype(), sum_dtype);
+            assert_eq!(as_i64(out.column("key")?)?, &[Some(10)]);
+
+            let right = other
+                .clone()
+                .lazy()
+                .with_column(col("key").cast(dtype.clone()));
+            let out = lf
+                .inner_join(right, col("key"), col("key"))
+                .sort("value", false)
+                .collect()?;
+            assert_eq!(
+                Vec::from(out.column("name")?.utf8()?),
+                &[Some("c"), Some("a"), Some("a"), Some("c")]
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_with_row_count() -> Result<()> {
+        let ids =
+            |df: &DataFrame| -> Result<Vec<Option<u32>>> { Ok(Vec::from(df.column("id")?.u32()?)) };
+        let in_memory = scan_foods_csv().collect()?;
+        let sources = [scan_foods_csv(), in_memory.lazy()];
+
+        for lf in sources.iter() {
+            // a filter after the row count keeps the original row indexes
+            let out = lf
+                .clone()
+                .with_row_count("id", None)
+                .filter(col("calories").gt(lit(100)))
+                .collect()?;
+            assert_eq!(out.get_column_names()[0], "id");
+            assert_eq!(
+                ids(&out)?,
+                [1u32, 4, 5, 8, 10, 16, 17, 19, 21, 22, 23]
+                    .iter()
+                    .map(|&i| Some(i))
+                    .collect::<Vec<_>>()
+            );
+
+            // a filter before the row count gives consecutive row indexes
+            let out = lf
+                .clone()
+                .filter(col("calories").gt(lit(100)))
+                .with_row_count("id", Some(10))
+                .collect()?;
+            assert_eq!(ids(&out)?, (10u32..21).map(Some).collect::<Vec<_>>());
+
+            // the row count and another column are selected
+            let out = lf
+                .clone()
+                .with_row_count("id", None)
+                .select(vec![col("id"), col("category")])
+                .limit(3)
+                .collect()?;
+            assert_eq!(out.get_column_names(), &["id", "category"]);
+            assert_eq!(ids(&out)?, &[Some(0), Some(1), Some(2)]);
+
+            let out = lf.clone().with_row_count("id", None).fetch(3)?;
+            assert_eq!(ids(&out)?, &[Some(0), Some(1), Some(2)]);
+
+            // only the row count column is selected, a single column is read for it
+            let q = lf
+                .clone()
+                .with_row_count("id", None)
+                .select(vec![col("id")]);
+            assert!(q.describe_optimized_plan()?.contains("PROJECT 1/4 COLUMNS"));
+            let out = q.collect()?;
+            assert_eq!(out.get_column_names(), &["id"]);
+            assert_eq!(out.height(), in_memory.height());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_groupby_interned_utf8_key() -> Result<()> {
+        use polars_core::verbose::capture_verbose;
+
+        let keys = (0..100)
+            .map(|i| format!("{}: a long key that is expensive to hash", i % 4))
+            .collect::<Vec<_>>();
+        let df = df![
+            "test_intern_key" => keys.iter().map(|s| s.as_str()).collect::<Vec<_>>(),
+            "v" => (0..100).collect::<Vec<i32>>()
+        ]?;
+
+        let (out, messages) = capture_verbose(|| {
+            let over = || vec![col("test_intern_key")];
+            df.lazy()
+                .with_interned_groupby_keys(true)
+                .with_columns(vec![
+                    col("v").sum().over(over()).alias("sum"),
+                    col("v").min().over(over()).alias("min"),
+                    col("v").max().over(over()).alias("max"),
+                ])
+                // a median cannot run partitioned, so this groups the full key as well
+                .groupby(vec![col("test_intern_key")])
+                .agg(vec![
+                    col("sum").first().alias("sum"),
+                    col("min").first().alias("min"),
+                    col("max").first().alias("max"),
+                    col("v").median().alias("median"),
+                ])
+                .sort("test_intern_key", false)
+                .collect()
+        });
+        let out = out?;
+
+        let n_interned = messages
+            .iter()
+            .filter(|m| m.starts_with("groupby: interned Utf8 key 'test_intern_key'"))
+            .count();
+        assert_eq!(n_interned, 1);
+
+        assert_eq!(out.column("test_intern_key")?.dtype(), &DataType::Utf8);
+        assert_eq!(
+            out.column("test_intern_key")?.utf8()?.get(1),
+            Some("1: a long key that is expensive to hash")
+        );
+        assert_eq!(
+            Vec::from(out.column("sum")?.i32()?),
+            &[Some(1200), Some(1225), Some(1250), Some(1275)]
+        );
+        assert_eq!(
+            Vec::from(out.column("min")?.i32()?),
+            &[Some(0), Some(1), Some(2), Some(3)]
+        );
+        assert_eq!(
+            Vec::from(out.column("max")?.i32()?),
+            &[Some(96), Some(97), Some(98), Some(99)]
+        );
+        assert_eq!(
+            Vec::from(out.column("median")?.f64()?),
+            &[Some(48.0), Some(49.0), Some(50.0), Some(51.0)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_output_limits() -> Result<()> {
+        // every left row matches every right row: the join has 400 million rows
+        let n = 20_000;
+        let left = df![
+            "key" => vec![1i32; n],
+            "a" => (0..n as i32).collect::<Vec<_>>()
+        ]?;
+        let right = df![
+            "key" => vec![1i32; n],
+            "b" => (0..n as i32).collect::<Vec<_>>()
+        ]?;
+        let join = || {
+            left.clone()
+                .lazy()
+                .inner_join(right.clone().lazy(), col("key"), col("key"))
+        };
+
+        let start = std::time::Instant::now();
+        let err = join()
+            .with_max_output_rows(1_000_000)
+            .collect()
+            .unwrap_err();
+        // the join is aborted before the join tuples are materialized
+        assert!(start.elapsed() < std::time::Duration::from_secs(10));
+        assert!(matches!(
+            err,
+            PolarsError::ResourceExhausted(ref msg)
+                if msg.contains("Inner join") && msg.contains("400000000 rows")
+        ));
+
+        let err = join()
+            .with_memory_limit_bytes(64 * 1024 * 1024)
+            .collect()
+            .unwrap_err();
+        assert!(matches!(err, PolarsError::ResourceExhausted(_)));
+
+        // within the limits the query runs as usual
+        let out = left
+            .clone()
+            .lazy()
+            .left_join(right.clone().lazy().slice(0, 2), col("a"), col("b"))
+            .with_max_output_rows(n)
+            .with_memory_limit_bytes(64 * 1024 * 1024)
+            .collect()?;
+        assert_eq!(out.height(), n);
+
+        // the rows with null keys don't make the count exceed the output
+        let left_nulls = df![
+            "key" => [Some(1), None, None, Some(2)]
+        ]?;
+        let right_nulls = df![
+            "key" => [Some(1), None, Some(3)]
+        ]?;
+        for how in &[JoinType::Inner, JoinType::Left, JoinType::Outer] {
+            let join = || {
+                left_nulls.clone().lazy().join(
+                    right_nulls.clone().lazy(),
+                    vec![col("key")],
+                    vec![col("key")],
+                    *how,
+                )
+            };
+            let expected = join().collect()?.height();
+            assert_eq!(
+                join().with_max_output_rows(expected).collect()?.height(),
+                expected
+            );
+            assert!(join().with_max_output_rows(expected - 1).collect().is_err());
+        }
+
+        // the limits also hold for the other nodes
+        let err = left
+            .lazy()
+            .groupby(vec![col("a")])
+            .agg(vec![col("key").sum()])
+            .with_max_output_rows(n - 1)
+            .collect()
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            PolarsError::ResourceExhausted(ref msg) if msg.starts_with("dataframe scan")
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_broadcast_list_literal() -> Result<()> {
+        let df = df![
+            "a" => (0..1000i32).collect::<Vec<_>>()
+        ]?;
+        let list = Series::new("", &[Series::new("", &[1i32, 2, 3])]);
+        let empty = df.head(Some(0));
+
+        let out = df
+            .lazy()
+            .with_columns(vec![lit(list.clone()).alias("l")])
+            .collect()?;
+        assert_eq!(out.shape(), (1000, 2));
+        let l = out.column("l")?.list()?;
+        assert_eq!(l.null_count(), 0);
+        for i in [0, 500, 999].iter() {
+            assert!(l
+                .get(*i)
+                .unwrap()
+                .series_equal(&Series::new("", &[1i32, 2, 3])));
+        }
+
+        // a literal is broadcast to the zero rows of an empty frame
+        let out = empty
+            .clone()
+            .lazy()
+            .with_columns(vec![lit(list.clone()).alias("l")])
+            .collect()?;
+        assert_eq!(out.shape(), (0, 2));
+        let out = empty
+            .lazy()
+            .select(vec![col("a"), lit(list).alias("l")])
+            .collect()?;
+        assert_eq!(out.shape(), (0, 2));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "object")]
+    fn test_lazy_window_object_first() -> Result<()> {
+        #[derive(Clone, Debug, Default, PartialEq)]
+        struct Point(i32, i32);
+
+        impl std::fmt::Display for Point {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "({}, {})", self.0, self.1)
+            }
+        }
+
+        impl PolarsObject for Point {
+            fn type_name() -> &'static str {
+                "point"
+            }
+        }
+
+        let points = vec![Point(0, 0), Point(1, 1), Point(2, 2), Point(3, 3)];
+        let mut df = df![
+            "g" => ["a", "b", "a", "b"]
+        ]?;
+        df.with_column(ObjectChunked::new_from_vec("p", points).into_series())?;
+
+        let out = df
+            .clone()
+            .lazy()
+            .groupby(vec![col("g")])
+            .agg(vec![col("p").first()])
+            .sort("g", false)
+            .collect()?;
+        let p = out.column("p_first")?;
+        let p = p.as_any().downcast_ref::<ObjectChunked<Point>>().unwrap();
+        assert_eq!(p.get(0), Some(&Point(0, 0)));
+        assert_eq!(p.get(1), Some(&Point(1, 1)));
+
+        let out = df
+            .lazy()
+            .select(vec![
+                col("g"),
+                col("p").first().over(vec![col("g")]).alias("p_first"),
+            ])
+            .collect()?;
+        let p = out.column("p_first")?;
+        let p = p.as_any().downcast_ref::<ObjectChunked<Point>>().unwrap();
+        let expected = [Point(0, 0), Point(1, 1), Point(0, 0), Point(1, 1)];
+        for (i, point) in expected.iter().enumerate() {
+            assert_eq!(p.get(i), Some(point));
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "search_sorted")]
+    fn test_lazy_search_sorted() -> Result<()> {
+        let df = df![
+            "a" => [1, 2, 2, 3]
+        ]?;
+        let out = df
+            .lazy()
+            .select(vec![col("a").search_sorted(lit(2))])
+            .collect()?;
+        assert_eq!(out.column("a")?.dtype(), &IDX_DTYPE);
+        assert_eq!(Vec::from(out.column("a")?.idx()?), &[Some(1)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_groupby_float_keys() -> Result<()> {
+        let values = [
+            Some(f64::NAN),
+            Some(-f64::NAN),
+            Some(0.0),
+            Some(-0.0),
+            Some(1.0),
+            None,
+        ];
+        // low cardinality keys take the partitioned groupby
+        let mut f: Float64Chunked = values
+            .iter()
+            .cycle()
+            .take(values.len() * 200)
+            .copied()
+            .collect();
+        f.rename("f");
+        let df = DataFrame::new(vec![f.into_series()])?;
+        let df = df.lazy().with_column(lit(1).alias("v")).collect()?;
+
+        let out = df
+            .lazy()
+            .groupby(vec![col("f")])
+            .agg(vec![col("v").sum()])
+            .sort("v_sum", true)
+            .collect()?;
+        assert_eq!(
+            Vec::from(out.column("v_sum")?.i32()?),
+            &[Some(400), Some(400), Some(200), Some(200)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_groupby_all_columns() -> Result<()> {
+        let df = df![
+            "k" => ["a", "b", "a", "b", "a"],
+            "int" => [Some(1), None, Some(3), Some(4), Some(5)],
+            "float" => [1.0, 2.0, 3.0, 4.0, 5.0],
+            "str" => ["x", "y", "z", "x", "y"],
+            "bool" => [Some(true), Some(false), None, Some(true), Some(true)]
+        ]?;
+
+        for method in &[
+            "mean", "sum", "min", "max", "median", "count", "first", "last",
+        ] {
+            let lazy_gb = df.clone().lazy().groupby(vec![col("k")]);
+            let gb = df.groupby("k")?;
+            let (q, expected) = match *method {
+                "mean" => (lazy_gb.mean(), gb.mean()?),
+                "sum" => (lazy_gb.sum(), gb.sum()?),
+                "min" => (lazy_gb.min(), gb.min()?),
+                "max" => (lazy_gb.max(), gb.max()?),
+                "median" => (lazy_gb.median(), gb.median()?),
+                "count" => (lazy_gb.count(), gb.count()?),
+                "first" => (lazy_gb.first(), gb.first()?),
+                _ => (lazy_gb.last(), gb.last()?),
+            };
+            let q = q.sort("k", false);
+            let expected = expected.sort("k", false)?;
+            let out = q.clone().collect()?;
+            assert_eq!(out.schema(), expected.schema());
+            assert_eq!(q.schema()?, expected.schema());
+            assert!(out.frame_equal_missing(&expected));
+        }
+
+        // the skipped columns are listed in the plan and not scanned
+        let plan = df
+            .lazy()
+            .groupby(vec![col("k")])
+            .mean()
+            .describe_optimized_plan()?;
+        assert!(plan.contains(r#"SKIPPED ["str", "bool"]"#));
+        assert!(plan.contains("PROJECT 3/5 COLUMNS"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_categorical_compare_str() -> Result<()> {
+        let df = df![
+            "c" => [Some("foo"), Some("bar"), None, Some("foo"), Some("ham")],
+            "s" => [Some("foo"), Some("foo"), Some("foo"), Some("spam"), None]
+        ]?;
+        let lf = df.lazy().with_column(col("c").cast(DataType::Categorical));
+
+        let out = lf.clone().filter(col("c").eq(lit("foo"))).collect()?;
+        assert_eq!(out.height(), 2);
+        // not a category
+        let out = lf.clone().filter(col("c").neq(lit("spam"))).collect()?;
+        assert_eq!(out.height(), 4);
+        let out = lf
+            .select(vec![
+                col("c").eq(col("s")).alias("eq"),
+                col("c").gt(lit("egg")).alias("gt"),
+            ])
+            .collect()?;
+        assert_eq!(
+            Vec::from(out.column("eq")?.bool()?),
+            &[Some(true), Some(false), None, Some(false), None]
+        );
+        assert_eq!(
+            Vec::from(out.column("gt")?.bool()?),
+            &[Some(true), Some(false), None, Some(true), Some(true)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_sort_pruning() -> Result<()> {
+        let df = df![
+            "a" => [3, 1, 2, 1, 3],
+            "b" => [1, 2, 3, 4, 5]
+        ]?;
+
+        // a sort by a prefix of the keys of the previous sort is removed
+        let q = df
+            .clone()
+            .lazy()
+            .sort_by_exprs(vec![col("a"), col("b")], vec![false, true])
+            .select(vec![col("a"), col("b")])
+            .filter(col("b").gt(lit(1)))
+            .sort("a", false);
+        assert_eq!(q.describe_optimized_plan()?.matches("SORT").count(), 1);
+        let expected = df
+            .clone()
+            .lazy()
+            .sort_by_exprs(vec![col("a"), col("b")], vec![false, true])
+            .filter(col("b").gt(lit(1)))
+            .collect()?;
+        assert!(q.collect()?.frame_equal(&expected));
+
+        // a different direction, or a projection that changes the keys keeps both sorts
+        let q = df.clone().lazy().sort("a", false).sort("a", true);
+        assert_eq!(q.describe_optimized_plan()?.matches("SORT").count(), 2);
+
+        // a frame of which the key column is flagged as sorted isn't sorted again
+        let sorted = df.sort("a", false)?;
+        let q = sorted
+            .clone()
+            .lazy()
+            .filter(col("b").gt(lit(1)))
+            .sort("a", false);
+        assert_eq!(q.describe_optimized_plan()?.matches("SORT").count(), 0);
+        let expected = sorted
+            .clone()
+            .lazy()
+            .filter(col("b").gt(lit(1)))
+            .collect()?;
+        assert!(q.collect()?.frame_equal(&expected));
+        let q = sorted.lazy().sort("a", true);
+        assert_eq!(q.describe_optimized_plan()?.matches("SORT").count(), 1);
+        let q = df
+            .clone()
+            .lazy()
+            .sort("a", false)
+            .with_column(col("a").reverse())
+            .sort("a", false);
+        assert_eq!(q.describe_optimized_plan()?.matches("SORT").count(), 2);
+
+        // sort before a projection that adds columns computed row by row
+        let q = df
+            .clone()
+            .lazy()
+            .with_column((col("b") * lit(2)).alias("c"))
+            .sort("a", false);
+        let plan = q.describe_optimized_plan()?;
+        assert!(plan.find("STACK").unwrap() < plan.find("SORT").unwrap());
+        let expected = q.clone().without_optimizations().collect()?;
+        assert!(q.collect()?.frame_equal(&expected));
+
+        // not if the projection depends on the order of the rows
+        let q = df
+            .lazy()
+            .with_column(col("b").cum_sum(false).alias("c"))
+            .sort("a", false);
+        let plan = q.describe_optimized_plan()?;
+        assert!(plan.find("SORT").unwrap() < plan.find("STACK").unwrap());
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_partitioned_mean_precision() -> Result<()> {
+        // low cardinality keys take the partitioned groupby, of which the mean is the sum of the
+        // sums of the partitions divided by the sum of the counts
+        let n = 100_000;
+        let g: Vec<_> = (0..n).map(|i| (i % 3) as i64).collect();
+        let int: Vec<_> = (0..n).map(|_| i32::MAX).collect();
+        let flt: Vec<_> = (0..n).map(|_| 0.1f32).collect();
+        let df = df![
+            "g" => g,
+            "int" => int,
+            "flt" => flt
+        ]?;
+
+        let out = df
+            .clone()
+            .lazy()
+            .groupby(vec![col("g")])
+            .agg(vec![col("int").mean(), col("flt").mean()])
+            .sort("g", false)
+            .collect()?;
+        let expected = df
+            .groupby("g")?
+            .select(&["int", "flt"])
+            .mean()?
+            .sort("g", false)?;
+
+        for name in &["int_mean", "flt_mean"] {
+            let partitioned = out.column(name)?.f64()?;
+            let standard = expected.column(name)?.f64()?;
+            for (a, b) in partitioned.into_iter().zip(standard) {
+                let (a, b) = (a.unwrap(), b.unwrap());
+                assert!((a - b).abs() < 1e-9 * b.abs());
+            }
+        }
+        for v in out.column("int_mean")?.f64()? {
+            assert_eq!(v, Some(i32::MAX as f64));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_invalid_multi_output_exprs_error() -> Result<()> {
+        let df = df![
+            "a" => [1, 2, 3],
+            "b" => [1.0, 2.0, 3.0]
+        ]?;
+
+        // an invalid regex
+        let out = df.clone().lazy().select(vec![col("^(a$")]).collect();
+        assert!(out.is_err());
+        // an alias over an expression that expands to multiple columns
+        let out = df
+            .clone()
+            .lazy()
+            .select(vec![col("*").alias("x")])
+            .collect();
+        assert!(out.is_err());
+        let out = df
+            .clone()
+            .lazy()
+            .with_column(cols(&["a", "b"]).alias("x"))
+            .collect();
+        assert!(out.is_err());
+        // multi-output expressions outside of a projection are not expanded
+        let out = df.clone().lazy().filter(cols(&["a"]).gt(lit(1))).collect();
+        assert!(out.is_err());
+        let out = df
+            .clone()
+            .lazy()
+            .groupby(vec![col("a")])
+            .agg(vec![col("^(b$").sum()])
+            .collect();
+        assert!(out.is_err());
+
+        // a valid query still runs
+        let out = df.lazy().select(vec![cols(&["a", "b"]).sum()]).collect()?;
+        assert_eq!(out.shape(), (1, 2));
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_shrink_dtype() -> Result<()> {
+        let df = df![
+            "a" => [1i64, 20, 70_000],
+            "b" => [1i64, 2, 3]
+        ]?;
+        let lf = df
+            .lazy()
+            .with_column(col("a").shrink_dtype())
+            .filter(col("b").gt(lit(1)));
+        // the output type depends on the data
+        assert!(lf.schema().is_err());
+        let out = lf.collect()?;
+        assert_eq!(out.column("a")?.dtype(), &DataType::UInt32);
+        Ok(())
+    }
 }
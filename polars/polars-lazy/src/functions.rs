@@ -2,6 +2,7 @@
 //!
 //! Functions on expressions that might be useful.
 //!
+use crate::logical_plan::det_union_schema;
 use crate::prelude::*;
 use polars_core::prelude::*;
 
@@ -74,22 +75,149 @@ pub fn argsort_by(by: Vec<Expr>, reverse: &[bool]) -> Expr {
     Expr::Function {
         input: by,
         function,
-        output_type: Some(DataType::UInt32),
+        output_type: GetOutput::ToType(IDX_DTYPE),
         collect_groups: true,
     }
 }
 
+/// Apply an [ExprPlugin] to the given inputs.
+pub fn plugin(input: Vec<Expr>, plugin: Arc<dyn ExprPlugin>) -> Expr {
+    Expr::Extension {
+        input,
+        plugin: NoEq::new(plugin),
+    }
+}
+
+/// Concatenates the string representation of its inputs row-wise. See [concat_str].
 #[cfg(feature = "concat_str")]
-/// Concat string columns in linear time
+struct ConcatStr {
+    delimiter: String,
+    null_as_empty: bool,
+}
+
+#[cfg(feature = "concat_str")]
+impl ExprPlugin for ConcatStr {
+    fn name(&self) -> &str {
+        "concat_str"
+    }
+
+    fn params(&self) -> Option<String> {
+        Some(format!("{:?}, {}", self.delimiter, self.null_as_empty))
+    }
+
+    fn evaluate(&self, inputs: &[Series], _state: &ExecutionState) -> Result<Series> {
+        polars_core::functions::concat_str(inputs, &self.delimiter, self.null_as_empty)
+            .map(|ca| ca.into_series())
+    }
+
+    fn to_field(&self, fields: &[Field]) -> Result<Field> {
+        Ok(Field::new(fields[0].name(), DataType::Utf8))
+    }
+
+    fn is_elementwise(&self) -> bool {
+        true
+    }
+
+    fn expand_inputs(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(feature = "concat_str")]
+#[cfg_attr(docsrs, doc(cfg(feature = "concat_str")))]
+/// Concat the string representation of the expressions row-wise in linear time, separated by
+/// `delimiter`. Expressions that produce a single value are broadcast and a null value makes the
+/// whole row null.
+///
+/// A multi-output expression, e.g. `col("*").exclude(vec!["id"])`, adds a column per selected
+/// column to the concatenation.
 pub fn concat_str(s: Vec<Expr>, delimiter: &str) -> Expr {
-    let delimiter = delimiter.to_string();
-    let function = NoEq::new(Arc::new(move |s: &mut [Series]| {
-        polars_core::functions::concat_str(s, &delimiter).map(|ca| ca.into_series())
+    concat_str_null_as_empty(s, delimiter, false)
+}
+
+#[cfg(feature = "concat_str")]
+#[cfg_attr(docsrs, doc(cfg(feature = "concat_str")))]
+/// [concat_str], where null values are concatenated as empty strings if `null_as_empty` is set.
+pub fn concat_str_null_as_empty(s: Vec<Expr>, delimiter: &str, null_as_empty: bool) -> Expr {
+    plugin(
+        s,
+        Arc::new(ConcatStr {
+            delimiter: delimiter.to_string(),
+            null_as_empty,
+        }),
+    )
+}
+
+#[cfg(feature = "dtype-struct")]
+#[cfg_attr(docsrs, doc(cfg(feature = "dtype-struct")))]
+/// Group the columns into a single `Struct` column, with a field per column. The output has the
+/// name of the first column, use [alias](Expr::alias) to name the struct.
+pub fn struct_(exprs: Vec<Expr>) -> Expr {
+    let function = NoEq::new(Arc::new(|s: &mut [Series]| {
+        StructChunked::new(s[0].name(), s).map(|ca| ca.into_series())
     }) as Arc<dyn SeriesUdf>);
+    let output_field = |_: &Schema, _: Context, fields: &[Field]| -> Result<Field> {
+        Ok(Field::new(
+            fields[0].name(),
+            DataType::Struct(fields.to_vec()),
+        ))
+    };
     Expr::Function {
-        input: s,
+        input: exprs,
         function,
-        output_type: Some(DataType::Utf8),
+        output_type: GetOutput::Map(NoEq::new(Arc::new(output_field))),
         collect_groups: false,
     }
 }
+
+/// Create a range of integers from `low` up to (but excluding) `high` with steps of `step`.
+/// The bounds are expressions that should evaluate to a single value, so the length of the
+/// range can be determined by the frame, e.g. `arange(lit(0), col("foo").count(), 1)` creates
+/// a row number column.
+pub fn arange(low: Expr, high: Expr, step: i64) -> Expr {
+    let name = "arange";
+    let function = move |low: Series, high: Series| {
+        let scalar = |s: &Series| -> Result<i64> {
+            if s.len() != 1 {
+                return Err(PolarsError::ShapeMisMatch(
+                    format!(
+                        "the bounds of arange should evaluate to a single value, got length {}",
+                        s.len()
+                    )
+                    .into(),
+                ));
+            }
+            let s = s.cast::<Int64Type>()?;
+            s.i64()?.get(0).ok_or_else(|| {
+                PolarsError::ValueError("the bounds of arange may not be null".into())
+            })
+        };
+        let ca = polars_core::functions::arange(scalar(&low)?, scalar(&high)?, step)?;
+        Ok(ca.into_series())
+    };
+    map_binary(low, high, function, Some(Field::new(name, DataType::Int64))).alias(name)
+}
+
+/// Concatenate multiple `LazyFrame`s.
+///
+/// With [ConcatHow::Vertical](crate::prelude::ConcatHow::Vertical) all inputs should have the same
+/// schema. With [ConcatHow::Diagonal](crate::prelude::ConcatHow::Diagonal) the schemas are merged:
+/// columns are ordered by first occurrence, missing columns are filled with nulls and columns
+/// are cast to their supertype.
+/// With `ConcatHow::MergeSorted(key)` all inputs should have the same schema and be sorted by
+/// `key`; the inputs are merged into a single frame that is sorted by `key`.
+pub fn concat(inputs: &[LazyFrame], how: ConcatHow) -> Result<LazyFrame> {
+    if inputs.is_empty() {
+        return Err(PolarsError::NoData(
+            "cannot concatenate an empty list of LazyFrames".into(),
+        ));
+    }
+    let schema = det_union_schema(inputs.iter().map(|lf| lf.logical_plan.schema()), &how)?;
+    let mut lf = inputs[0].clone();
+    lf.logical_plan = LogicalPlan::Union {
+        inputs: inputs.iter().map(|lf| lf.logical_plan.clone()).collect(),
+        how,
+        schema,
+    };
+    Ok(lf)
+}
@@ -1,9 +1,9 @@
 use crate::logical_plan::Context;
 use crate::prelude::*;
-use crate::utils::rename_field;
+use crate::utils::{rename_field, temporal_arithmetic_dtype};
 use polars_core::frame::groupby::{fmt_groupby_column, GroupByMethod};
 use polars_core::prelude::*;
-use polars_core::utils::{get_supertype, Arena, Node};
+use polars_core::utils::{get_sum_dtype, get_supertype, Arena, Node};
 use std::sync::Arc;
 
 #[derive(Clone, Debug)]
@@ -16,9 +16,11 @@ pub enum AAggExpr {
     Last(Node),
     Mean(Node),
     List(Node),
+    Flatten(Node),
     Quantile { expr: Node, quantile: f64 },
     Sum(Node),
     Count(Node),
+    NullCount(Node),
     Std(Node),
     Var(Node),
     AggGroups(Node),
@@ -31,6 +33,7 @@ pub enum AExpr {
     Duplicated(Node),
     Reverse(Node),
     Explode(Node),
+    DropNulls(Node),
     Alias(Node, Arc<String>),
     Column(Arc<String>),
     Literal(LiteralValue),
@@ -56,8 +59,8 @@ pub enum AExpr {
     },
     SortBy {
         expr: Node,
-        by: Node,
-        reverse: bool,
+        by: Vec<Node>,
+        reverse: Vec<bool>,
     },
     Filter {
         input: Node,
@@ -72,9 +75,13 @@ pub enum AExpr {
     Function {
         input: Vec<Node>,
         function: NoEq<Arc<dyn SeriesUdf>>,
-        output_type: Option<DataType>,
+        output_type: GetOutput,
         collect_groups: bool,
     },
+    Extension {
+        input: Vec<Node>,
+        plugin: NoEq<Arc<dyn ExprPlugin>>,
+    },
     Shift {
         input: Node,
         periods: i64,
@@ -137,6 +144,7 @@ impl AExpr {
             }
             Reverse(expr) => arena.get(*expr).to_field(schema, ctxt, arena),
             Explode(expr) => arena.get(*expr).to_field(schema, ctxt, arena),
+            DropNulls(expr) => arena.get(*expr).to_field(schema, ctxt, arena),
             Alias(expr, name) => Ok(Field::new(
                 name,
                 arena.get(*expr).get_type(schema, ctxt, arena)?,
@@ -159,7 +167,10 @@ impl AExpr {
                     | Operator::LtEq
                     | Operator::GtEq
                     | Operator::Or => DataType::Boolean,
-                    _ => get_supertype(&left_type, &right_type)?,
+                    _ => match temporal_arithmetic_dtype(*op, &left_type, &right_type) {
+                        Some(dtype) => dtype,
+                        None => get_supertype(&left_type, &right_type)?,
+                    },
                 };
 
                 use Operator::*;
@@ -195,34 +206,55 @@ impl AExpr {
                         ctxt,
                         GroupByMethod::Max,
                     ),
-                    Median(expr) => field_by_context(
-                        arena.get(*expr).to_field(schema, ctxt, arena)?,
-                        ctxt,
-                        GroupByMethod::Median,
-                    ),
+                    Median(expr) => {
+                        let mut field = field_by_context(
+                            arena.get(*expr).to_field(schema, ctxt, arena)?,
+                            ctxt,
+                            GroupByMethod::Median,
+                        );
+                        field.coerce(DataType::Float64);
+                        field
+                    }
                     Mean(expr) => {
                         let mut field = field_by_context(
                             arena.get(*expr).to_field(schema, ctxt, arena)?,
                             ctxt,
                             GroupByMethod::Mean,
                         );
-                        field.coerce(DataType::Float64);
+                        // the mean of a duration is a duration
+                        if !matches!(field.data_type(), DataType::Duration(_)) {
+                            field.coerce(DataType::Float64);
+                        }
                         field
                     }
-                    First(expr) => field_by_context(
-                        arena.get(*expr).to_field(schema, ctxt, arena)?,
-                        ctxt,
-                        GroupByMethod::First,
-                    ),
-                    Last(expr) => field_by_context(
-                        arena.get(*expr).to_field(schema, ctxt, arena)?,
-                        ctxt,
-                        GroupByMethod::Last,
-                    ),
-                    List(expr) => field_by_context(
+                    // the first and last value keep the data type, also of a boolean
+                    First(expr) => {
+                        let field = arena.get(*expr).to_field(schema, ctxt, arena)?;
+                        let dtype = field.data_type().clone();
+                        let mut field = field_by_context(field, ctxt, GroupByMethod::First);
+                        field.coerce(dtype);
+                        field
+                    }
+                    Last(expr) => {
+                        let field = arena.get(*expr).to_field(schema, ctxt, arena)?;
+                        let dtype = field.data_type().clone();
+                        let mut field = field_by_context(field, ctxt, GroupByMethod::Last);
+                        field.coerce(dtype);
+                        field
+                    }
+                    // the inner type is that of the input, also of a boolean
+                    List(expr) => {
+                        let field = arena.get(*expr).to_field(schema, ctxt, arena)?;
+                        let inner = field.data_type().to_arrow();
+                        let mut field = field_by_context(field, ctxt, GroupByMethod::List);
+                        field.coerce(DataType::List(inner));
+                        field
+                    }
+                    // the lists are concatenated, so the data type doesn't change
+                    Flatten(expr) => field_by_context(
                         arena.get(*expr).to_field(schema, ctxt, arena)?,
                         ctxt,
-                        GroupByMethod::List,
+                        GroupByMethod::Flatten,
                     ),
                     Std(expr) => {
                         let field = arena.get(*expr).to_field(schema, ctxt, arena)?;
@@ -250,11 +282,11 @@ impl AExpr {
                             }
                         }
                     }
-                    Sum(expr) => field_by_context(
-                        arena.get(*expr).to_field(schema, ctxt, arena)?,
-                        ctxt,
-                        GroupByMethod::Sum,
-                    ),
+                    Sum(expr) => {
+                        let field = arena.get(*expr).to_field(schema, ctxt, arena)?;
+                        let field = Field::new(field.name(), get_sum_dtype(field.data_type()));
+                        field_by_context(field, ctxt, GroupByMethod::Sum)
+                    }
                     Count(expr) => {
                         let field = arena.get(*expr).to_field(schema, ctxt, arena)?;
                         let field = Field::new(field.name(), DataType::UInt32);
@@ -267,10 +299,22 @@ impl AExpr {
                             }
                         }
                     }
+                    NullCount(expr) => {
+                        let field = arena.get(*expr).to_field(schema, ctxt, arena)?;
+                        let field = Field::new(field.name(), DataType::UInt32);
+                        match ctxt {
+                            Context::Default => field,
+                            Context::Aggregation => {
+                                let new_name =
+                                    fmt_groupby_column(field.name(), GroupByMethod::NullCount);
+                                rename_field(&field, &new_name)
+                            }
+                        }
+                    }
                     AggGroups(expr) => {
                         let field = arena.get(*expr).to_field(schema, ctxt, arena)?;
                         let new_name = fmt_groupby_column(field.name(), GroupByMethod::Groups);
-                        Field::new(&new_name, DataType::List(ArrowDataType::UInt32))
+                        Field::new(&new_name, DataType::List(IDX_DTYPE.to_arrow()))
                     }
                     Quantile { expr, quantile } => {
                         let mut field = field_by_context(
@@ -288,16 +332,28 @@ impl AExpr {
                 let field = arena.get(*expr).to_field(schema, ctxt, arena)?;
                 Ok(Field::new(field.name(), data_type.clone()))
             }
-            Ternary { truthy, .. } => arena.get(*truthy).to_field(schema, ctxt, arena),
+            Ternary { truthy, falsy, .. } => {
+                let truthy = arena.get(*truthy).to_field(schema, ctxt, arena)?;
+                // a Null literal adopts the type of the other branch
+                if truthy.data_type() == &DataType::Null {
+                    let falsy = arena.get(*falsy).get_type(schema, ctxt, arena)?;
+                    Ok(Field::new(truthy.name(), falsy))
+                } else {
+                    Ok(truthy)
+                }
+            }
             Function {
                 output_type, input, ..
-            } => match output_type {
-                None => arena.get(input[0]).to_field(schema, ctxt, arena),
-                Some(output_type) => {
-                    let input_field = arena.get(input[0]).to_field(schema, ctxt, arena)?;
-                    Ok(Field::new(input_field.name(), output_type.clone()))
-                }
-            },
+            } => output_type.get_field(schema, ctxt, input.len(), |i| {
+                arena.get(input[i]).to_field(schema, ctxt, arena)
+            }),
+            Extension { input, plugin } => {
+                let fields = input
+                    .iter()
+                    .map(|e| arena.get(*e).to_field(schema, ctxt, arena))
+                    .collect::<Result<Vec<_>>>()?;
+                plugin.to_field(&fields)
+            }
             BinaryFunction {
                 input_a,
                 input_b,
@@ -335,6 +391,12 @@ impl AExpr {
                 (Sort { reverse: l, .. }, Sort { reverse: r, .. }) => l == r,
                 (SortBy { reverse: l, .. }, SortBy { reverse: r, .. }) => l == r,
                 (Shift { periods: l, .. }, Shift { periods: r, .. }) => l == r,
+                (Extension { plugin: l, .. }, Extension { plugin: r, .. }) => {
+                    Arc::as_ptr(&**l) as *const () == Arc::as_ptr(&**r) as *const ()
+                        || (l.name() == r.name()
+                            && l.params().is_some()
+                            && l.params() == r.params())
+                }
                 (
                     Slice {
                         offset: offset_l,
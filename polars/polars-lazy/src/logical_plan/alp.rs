@@ -1,10 +1,12 @@
-use crate::logical_plan::{det_melt_schema, Context, CsvParserOptions};
+use crate::logical_plan::{
+    det_explode_schema, det_melt_schema, det_row_count_schema, ConcatHow, Context, CsvParserOptions,
+};
 use crate::prelude::*;
 use crate::utils::{aexprs_to_schema, PushNode};
 use ahash::RandomState;
-use polars_core::frame::hash_join::JoinType;
 use polars_core::prelude::*;
 use polars_core::utils::{Arena, Node};
+use polars_io::RowCount;
 use std::collections::HashSet;
 #[cfg(any(feature = "csv-file", feature = "parquet"))]
 use std::path::PathBuf;
@@ -19,6 +21,11 @@ pub enum ALogicalPlan {
         value_vars: Arc<Vec<String>>,
         schema: SchemaRef,
     },
+    RowCount {
+        input: Node,
+        row_count: RowCount,
+        schema: SchemaRef,
+    },
     Slice {
         input: Node,
         offset: i64,
@@ -70,6 +77,7 @@ pub enum ALogicalPlan {
     Explode {
         input: Node,
         columns: Vec<String>,
+        schema: SchemaRef,
     },
     Cache {
         input: Node,
@@ -80,16 +88,16 @@ pub enum ALogicalPlan {
         aggs: Vec<Node>,
         schema: SchemaRef,
         apply: Option<Arc<dyn DataFrameUdf>>,
+        drop_null_keys: bool,
+        skipped: Vec<String>,
     },
     Join {
         input_left: Node,
         input_right: Node,
         schema: SchemaRef,
-        how: JoinType,
         left_on: Vec<Node>,
         right_on: Vec<Node>,
-        allow_par: bool,
-        force_par: bool,
+        options: JoinOptions,
     },
     HStack {
         input: Node,
@@ -110,6 +118,11 @@ pub enum ALogicalPlan {
         projection_pd: bool,
         schema: Option<SchemaRef>,
     },
+    Union {
+        inputs: Vec<Node>,
+        how: ConcatHow,
+        schema: SchemaRef,
+    },
 }
 
 impl Default for ALogicalPlan {
@@ -129,7 +142,7 @@ impl ALogicalPlan {
         match self {
             Cache { input } => arena.get(*input).schema(arena),
             Sort { input, .. } => arena.get(*input).schema(arena),
-            Explode { input, .. } => arena.get(*input).schema(arena),
+            Explode { schema, .. } => schema,
             #[cfg(feature = "parquet")]
             ParquetScan { schema, .. } => schema,
             DataFrameScan { schema, .. } => schema,
@@ -144,10 +157,12 @@ impl ALogicalPlan {
             Distinct { input, .. } => arena.get(*input).schema(arena),
             Slice { input, .. } => arena.get(*input).schema(arena),
             Melt { schema, .. } => schema,
+            RowCount { schema, .. } => schema,
             Udf { input, schema, .. } => match schema {
                 Some(schema) => schema,
                 None => arena.get(*input).schema(arena),
             },
+            Union { schema, .. } => schema,
         }
     }
 
@@ -240,6 +255,13 @@ impl ALogicalPlan {
                 value_vars: value_vars.clone(),
                 schema: schema.clone(),
             },
+            RowCount {
+                row_count, schema, ..
+            } => RowCount {
+                input: inputs[0],
+                row_count: row_count.clone(),
+                schema: schema.clone(),
+            },
             Slice { offset, len, .. } => Slice {
                 input: inputs[0],
                 offset: *offset,
@@ -263,6 +285,8 @@ impl ALogicalPlan {
                 keys,
                 schema,
                 apply,
+                drop_null_keys,
+                skipped,
                 ..
             } => Aggregate {
                 input: inputs[0],
@@ -270,23 +294,21 @@ impl ALogicalPlan {
                 aggs: exprs[keys.len()..].to_vec(),
                 schema: schema.clone(),
                 apply: apply.clone(),
+                drop_null_keys: *drop_null_keys,
+                skipped: skipped.clone(),
             },
             Join {
                 schema,
-                how,
                 left_on,
-                allow_par,
-                force_par,
+                options,
                 ..
             } => Join {
                 input_left: inputs[0],
                 input_right: inputs[1],
                 schema: schema.clone(),
-                how: *how,
                 left_on: exprs[..left_on.len()].to_vec(),
                 right_on: exprs[left_on.len()..].to_vec(),
-                allow_par: *allow_par,
-                force_par: *force_par,
+                options: options.clone(),
             },
             Sort {
                 by_column, reverse, ..
@@ -295,9 +317,12 @@ impl ALogicalPlan {
                 by_column: by_column.clone(),
                 reverse: reverse.clone(),
             },
-            Explode { columns, .. } => Explode {
+            Explode {
+                columns, schema, ..
+            } => Explode {
                 input: inputs[0],
                 columns: columns.clone(),
+                schema: schema.clone(),
             },
             Cache { .. } => Cache { input: inputs[0] },
             Distinct {
@@ -394,6 +419,11 @@ impl ALogicalPlan {
                 projection_pd: *projection_pd,
                 schema: schema.clone(),
             },
+            Union { how, schema, .. } => Union {
+                inputs,
+                how: how.clone(),
+                schema: schema.clone(),
+            },
         }
     }
 
@@ -402,12 +432,14 @@ impl ALogicalPlan {
         use ALogicalPlan::*;
         match self {
             Melt { .. }
+            | RowCount { .. }
             | Slice { .. }
             | Sort { .. }
             | Explode { .. }
             | Cache { .. }
             | Distinct { .. }
-            | Udf { .. } => {}
+            | Udf { .. }
+            | Union { .. } => {}
             Selection { predicate, .. } => container.push(*predicate),
             Projection { expr, .. } => container.extend_from_slice(expr),
             LocalProjection { expr, .. } => container.extend_from_slice(expr),
@@ -476,6 +508,7 @@ impl ALogicalPlan {
         use ALogicalPlan::*;
         let input = match self {
             Melt { input, .. } => *input,
+            RowCount { input, .. } => *input,
             Slice { input, .. } => *input,
             Selection { input, .. } => *input,
             Projection { input, .. } => *input,
@@ -493,6 +526,12 @@ impl ALogicalPlan {
                 container.push_node(*input_right);
                 return;
             }
+            Union { inputs, .. } => {
+                for node in inputs {
+                    container.push_node(*node);
+                }
+                return;
+            }
             HStack { input, .. } => *input,
             Distinct { input, .. } => *input,
             Udf { input, .. } => *input,
@@ -531,6 +570,18 @@ impl<'a> ALogicalPlanBuilder<'a> {
         }
     }
 
+    pub fn explode(self, columns: Vec<String>) -> Self {
+        let schema = det_explode_schema(&columns, self.schema());
+
+        let lp = ALogicalPlan::Explode {
+            input: self.root,
+            columns,
+            schema,
+        };
+        let node = self.lp_arena.add(lp);
+        ALogicalPlanBuilder::new(node, self.expr_arena, self.lp_arena)
+    }
+
     pub fn melt(self, id_vars: Arc<Vec<String>>, value_vars: Arc<Vec<String>>) -> Self {
         let schema = det_melt_schema(&value_vars, self.schema());
 
@@ -544,6 +595,18 @@ impl<'a> ALogicalPlanBuilder<'a> {
         ALogicalPlanBuilder::new(node, self.expr_arena, self.lp_arena)
     }
 
+    pub fn with_row_count(self, row_count: RowCount) -> Self {
+        let schema = det_row_count_schema(&row_count.name, self.schema());
+
+        let lp = ALogicalPlan::RowCount {
+            input: self.root,
+            row_count,
+            schema,
+        };
+        let node = self.lp_arena.add(lp);
+        ALogicalPlanBuilder::new(node, self.expr_arena, self.lp_arena)
+    }
+
     pub fn project_local(self, exprs: Vec<Node>) -> Self {
         let input_schema = self.lp_arena.get(self.root).schema(self.lp_arena);
         let schema = aexprs_to_schema(&exprs, input_schema, Context::Default, self.expr_arena);
@@ -626,6 +689,8 @@ impl<'a> ALogicalPlanBuilder<'a> {
         keys: Vec<Node>,
         aggs: Vec<Node>,
         apply: Option<Arc<dyn DataFrameUdf>>,
+        drop_null_keys: bool,
+        skipped: Vec<String>,
     ) -> Self {
         debug_assert!(!keys.is_empty());
         let current_schema = self.schema();
@@ -636,7 +701,7 @@ impl<'a> ALogicalPlanBuilder<'a> {
         let schema2 =
             aexprs_to_schema(&aggs, current_schema, Context::Aggregation, self.expr_arena);
 
-        let schema = Schema::try_merge(&[schema1, schema2]).unwrap();
+        let schema = Schema::try_merge(vec![schema1, schema2]).unwrap();
 
         let lp = ALogicalPlan::Aggregate {
             input: self.root,
@@ -644,6 +709,8 @@ impl<'a> ALogicalPlanBuilder<'a> {
             aggs,
             schema: Arc::new(schema),
             apply,
+            drop_null_keys,
+            skipped,
         };
         let root = self.lp_arena.add(lp);
         Self::new(root, self.expr_arena, self.lp_arena)
@@ -652,42 +719,49 @@ impl<'a> ALogicalPlanBuilder<'a> {
     pub fn join(
         self,
         other: Node,
-        how: JoinType,
         left_on: Vec<Node>,
         right_on: Vec<Node>,
-        allow_par: bool,
-        force_par: bool,
+        options: JoinOptions,
     ) -> Self {
         let schema_left = self.schema();
         let schema_right = self.lp_arena.get(other).schema(self.lp_arena);
 
-        // column names of left table
-        let mut names: HashSet<&String, RandomState> = HashSet::with_capacity_and_hasher(
-            schema_left.len() + schema_right.len(),
-            Default::default(),
-        );
         // fields of new schema
         let mut fields = Vec::with_capacity(schema_left.len() + schema_right.len());
-
-        for f in schema_left.fields() {
-            names.insert(f.name());
-            fields.push(f.clone());
+        fields.extend_from_slice(schema_left.fields());
+
+        // an outer join coalesces the keys into the output columns of the left keys
+        if options.how == JoinType::Outer {
+            for e in &left_on {
+                let field = self
+                    .expr_arena
+                    .get(*e)
+                    .to_field(schema_left, Context::Default, self.expr_arena)
+                    .expect("the join keys are checked when the plan is built");
+                match schema_left.index_of(field.name()) {
+                    Ok(idx) => fields[idx] = field,
+                    Err(_) => fields.push(field),
+                }
+            }
         }
 
+        // column names of left table
+        let names: HashSet<String, RandomState> = fields.iter().map(|f| f.name().clone()).collect();
+
+        // only the right join columns are not in the output, keys computed from them are
         let right_names: HashSet<_, RandomState> = right_on
             .iter()
-            .map(|e| match self.expr_arena.get(*e) {
-                AExpr::Alias(_, name) => name.clone(),
-                AExpr::Column(name) => name.clone(),
-                _ => panic!("could not determine join column names"),
+            .filter_map(|e| match self.expr_arena.get(*e) {
+                AExpr::Column(name) => Some(name.as_str()),
+                _ => None,
             })
             .collect();
 
         for f in schema_right.fields() {
             let name = f.name();
-            if !right_names.contains(name) {
+            if !right_names.contains(name.as_str()) {
                 if names.contains(name) {
-                    let new_name = format!("{}_right", name);
+                    let new_name = format!("{}{}", name, options.suffix);
                     let field = Field::new(&new_name, f.data_type().clone());
                     fields.push(field)
                 } else {
@@ -701,12 +775,10 @@ impl<'a> ALogicalPlanBuilder<'a> {
         let lp = ALogicalPlan::Join {
             input_left: self.root,
             input_right: other,
-            how,
             schema,
             left_on,
             right_on,
-            allow_par,
-            force_par,
+            options,
         };
         let root = self.lp_arena.add(lp);
         Self::new(root, self.expr_arena, self.lp_arena)
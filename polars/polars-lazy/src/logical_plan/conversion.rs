@@ -1,4 +1,6 @@
+use crate::logical_plan::expand_aggregations;
 use crate::prelude::*;
+use crate::utils::has_expr;
 use polars_core::prelude::*;
 
 fn to_aexprs(input: Vec<Expr>, arena: &mut Arena<AExpr>) -> Vec<Node> {
@@ -12,6 +14,7 @@ pub(crate) fn to_aexpr(expr: Expr, arena: &mut Arena<AExpr>) -> Node {
         Expr::Duplicated(expr) => AExpr::Duplicated(to_aexpr(*expr, arena)),
         Expr::Reverse(expr) => AExpr::Reverse(to_aexpr(*expr, arena)),
         Expr::Explode(expr) => AExpr::Explode(to_aexpr(*expr, arena)),
+        Expr::DropNulls(expr) => AExpr::DropNulls(to_aexpr(*expr, arena)),
         Expr::Alias(e, name) => AExpr::Alias(to_aexpr(*e, arena), name),
         Expr::Literal(value) => AExpr::Literal(value),
         Expr::Column(s) => AExpr::Column(s),
@@ -42,7 +45,7 @@ pub(crate) fn to_aexpr(expr: Expr, arena: &mut Arena<AExpr>) -> Node {
         },
         Expr::SortBy { expr, by, reverse } => AExpr::SortBy {
             expr: to_aexpr(*expr, arena),
-            by: to_aexpr(*by, arena),
+            by: by.into_iter().map(|e| to_aexpr(e, arena)).collect(),
             reverse,
         },
         Expr::Filter { input, by } => AExpr::Filter {
@@ -59,7 +62,9 @@ pub(crate) fn to_aexpr(expr: Expr, arena: &mut Arena<AExpr>) -> Node {
                 AggExpr::Last(expr) => AAggExpr::Last(to_aexpr(*expr, arena)),
                 AggExpr::Mean(expr) => AAggExpr::Mean(to_aexpr(*expr, arena)),
                 AggExpr::List(expr) => AAggExpr::List(to_aexpr(*expr, arena)),
+                AggExpr::Flatten(expr) => AAggExpr::Flatten(to_aexpr(*expr, arena)),
                 AggExpr::Count(expr) => AAggExpr::Count(to_aexpr(*expr, arena)),
+                AggExpr::NullCount(expr) => AAggExpr::NullCount(to_aexpr(*expr, arena)),
                 AggExpr::Quantile { expr, quantile } => AAggExpr::Quantile {
                     expr: to_aexpr(*expr, arena),
                     quantile,
@@ -96,6 +101,10 @@ pub(crate) fn to_aexpr(expr: Expr, arena: &mut Arena<AExpr>) -> Node {
             output_type,
             collect_groups,
         },
+        Expr::Extension { input, plugin } => AExpr::Extension {
+            input: to_aexprs(input, arena),
+            plugin,
+        },
         Expr::BinaryFunction {
             input_a,
             input_b,
@@ -131,35 +140,75 @@ pub(crate) fn to_aexpr(expr: Expr, arena: &mut Arena<AExpr>) -> Node {
         },
         Expr::Wildcard => AExpr::Wildcard,
         Expr::Except(input) => AExpr::Except(to_aexpr(*input, arena)),
+        Expr::Columns(_) | Expr::DtypeColumn(_) | Expr::Exclude(_, _) => {
+            // checked by `to_aexpr_checked` before the conversion
+            panic!("multi-output expressions should be expanded at this point")
+        }
     };
     arena.add(v)
 }
 
+/// Converts to AExpr, but returns an error on multi-output expressions that were not expanded
+/// by the builder, e.g. because they are used in a context that only accepts a single column.
+fn to_aexpr_checked(expr: Expr, arena: &mut Arena<AExpr>) -> Result<Node> {
+    if has_expr(&expr, |e| {
+        matches!(
+            e,
+            Expr::Columns(_) | Expr::DtypeColumn(_) | Expr::Exclude(_, _)
+        )
+    }) {
+        return Err(PolarsError::InvalidOperation(
+            format!(
+                "multi-output expression {:?} cannot be used here; \
+                use it in a select, with_columns or agg context",
+                expr
+            )
+            .into(),
+        ));
+    }
+    Ok(to_aexpr(expr, arena))
+}
+
 pub(crate) fn to_alp(
     lp: LogicalPlan,
     expr_arena: &mut Arena<AExpr>,
     lp_arena: &mut Arena<ALogicalPlan>,
-) -> Node {
+) -> Result<Node> {
     let v = match lp {
         LogicalPlan::Selection { input, predicate } => {
-            let i = to_alp(*input, expr_arena, lp_arena);
-            let p = to_aexpr(predicate, expr_arena);
+            let i = to_alp(*input, expr_arena, lp_arena)?;
+            let p = to_aexpr_checked(predicate, expr_arena)?;
             ALogicalPlan::Selection {
                 input: i,
                 predicate: p,
             }
         }
         LogicalPlan::Slice { input, offset, len } => {
-            let input = to_alp(*input, expr_arena, lp_arena);
+            let input = to_alp(*input, expr_arena, lp_arena)?;
             ALogicalPlan::Slice { input, offset, len }
         }
+        LogicalPlan::Union {
+            inputs,
+            how,
+            schema,
+        } => {
+            let inputs = inputs
+                .into_iter()
+                .map(|lp| to_alp(lp, expr_arena, lp_arena))
+                .collect::<Result<_>>()?;
+            ALogicalPlan::Union {
+                inputs,
+                how,
+                schema,
+            }
+        }
         LogicalPlan::Melt {
             input,
             id_vars,
             value_vars,
             schema,
         } => {
-            let input = to_alp(*input, expr_arena, lp_arena);
+            let input = to_alp(*input, expr_arena, lp_arena)?;
             ALogicalPlan::Melt {
                 input,
                 id_vars,
@@ -167,6 +216,18 @@ pub(crate) fn to_alp(
                 schema,
             }
         }
+        LogicalPlan::RowCount {
+            input,
+            row_count,
+            schema,
+        } => {
+            let input = to_alp(*input, expr_arena, lp_arena)?;
+            ALogicalPlan::RowCount {
+                input,
+                row_count,
+                schema,
+            }
+        }
         #[cfg(feature = "csv-file")]
         LogicalPlan::CsvScan {
             path,
@@ -178,11 +239,13 @@ pub(crate) fn to_alp(
             path,
             schema,
             options,
-            predicate: predicate.map(|expr| to_aexpr(expr, expr_arena)),
+            predicate: predicate
+                .map(|expr| to_aexpr_checked(expr, expr_arena))
+                .transpose()?,
             aggregate: aggregate
                 .into_iter()
-                .map(|expr| to_aexpr(expr, expr_arena))
-                .collect(),
+                .map(|expr| to_aexpr_checked(expr, expr_arena))
+                .collect::<Result<_>>()?,
         },
         #[cfg(feature = "parquet")]
         LogicalPlan::ParquetScan {
@@ -197,11 +260,13 @@ pub(crate) fn to_alp(
             path,
             schema,
             with_columns,
-            predicate: predicate.map(|expr| to_aexpr(expr, expr_arena)),
+            predicate: predicate
+                .map(|expr| to_aexpr_checked(expr, expr_arena))
+                .transpose()?,
             aggregate: aggregate
                 .into_iter()
-                .map(|expr| to_aexpr(expr, expr_arena))
-                .collect(),
+                .map(|expr| to_aexpr_checked(expr, expr_arena))
+                .collect::<Result<_>>()?,
             stop_after_n_rows,
             cache,
         },
@@ -214,16 +279,27 @@ pub(crate) fn to_alp(
             df,
             schema,
             projection: projection
-                .map(|exprs| exprs.into_iter().map(|x| to_aexpr(x, expr_arena)).collect()),
-            selection: selection.map(|expr| to_aexpr(expr, expr_arena)),
+                .map(|exprs| {
+                    exprs
+                        .into_iter()
+                        .map(|x| to_aexpr_checked(x, expr_arena))
+                        .collect::<Result<_>>()
+                })
+                .transpose()?,
+            selection: selection
+                .map(|expr| to_aexpr_checked(expr, expr_arena))
+                .transpose()?,
         },
         LogicalPlan::Projection {
             expr,
             input,
             schema,
         } => {
-            let exp = expr.into_iter().map(|x| to_aexpr(x, expr_arena)).collect();
-            let i = to_alp(*input, expr_arena, lp_arena);
+            let exp = expr
+                .into_iter()
+                .map(|x| to_aexpr_checked(x, expr_arena))
+                .collect::<Result<_>>()?;
+            let i = to_alp(*input, expr_arena, lp_arena)?;
             ALogicalPlan::Projection {
                 expr: exp,
                 input: i,
@@ -235,8 +311,11 @@ pub(crate) fn to_alp(
             input,
             schema,
         } => {
-            let exp = expr.into_iter().map(|x| to_aexpr(x, expr_arena)).collect();
-            let i = to_alp(*input, expr_arena, lp_arena);
+            let exp = expr
+                .into_iter()
+                .map(|x| to_aexpr_checked(x, expr_arena))
+                .collect::<Result<_>>()?;
+            let i = to_alp(*input, expr_arena, lp_arena)?;
             ALogicalPlan::LocalProjection {
                 expr: exp,
                 input: i,
@@ -248,23 +327,31 @@ pub(crate) fn to_alp(
             by_column,
             reverse,
         } => {
-            let input = to_alp(*input, expr_arena, lp_arena);
+            let input = to_alp(*input, expr_arena, lp_arena)?;
             let by_column = by_column
                 .into_iter()
-                .map(|x| to_aexpr(x, expr_arena))
-                .collect();
+                .map(|x| to_aexpr_checked(x, expr_arena))
+                .collect::<Result<_>>()?;
             ALogicalPlan::Sort {
                 input,
                 by_column,
                 reverse,
             }
         }
-        LogicalPlan::Explode { input, columns } => {
-            let input = to_alp(*input, expr_arena, lp_arena);
-            ALogicalPlan::Explode { input, columns }
+        LogicalPlan::Explode {
+            input,
+            columns,
+            schema,
+        } => {
+            let input = to_alp(*input, expr_arena, lp_arena)?;
+            ALogicalPlan::Explode {
+                input,
+                columns,
+                schema,
+            }
         }
         LogicalPlan::Cache { input } => {
-            let input = to_alp(*input, expr_arena, lp_arena);
+            let input = to_alp(*input, expr_arena, lp_arena)?;
             ALogicalPlan::Cache { input }
         }
         LogicalPlan::Aggregate {
@@ -273,13 +360,21 @@ pub(crate) fn to_alp(
             aggs,
             schema,
             apply,
+            expand_keys,
+            drop_null_keys,
+            skipped,
         } => {
-            let i = to_alp(*input, expr_arena, lp_arena);
-            let aggs_new = aggs.into_iter().map(|x| to_aexpr(x, expr_arena)).collect();
+            let i = to_alp(*input, expr_arena, lp_arena)?;
+            let aggs =
+                expand_aggregations(&keys, aggs, lp_arena.get(i).schema(lp_arena), expand_keys)?;
+            let aggs_new = aggs
+                .into_iter()
+                .map(|x| to_aexpr_checked(x, expr_arena))
+                .collect::<Result<_>>()?;
             let keys_new = keys
                 .iter()
-                .map(|x| to_aexpr(x.clone(), expr_arena))
-                .collect();
+                .map(|x| to_aexpr_checked(x.clone(), expr_arena))
+                .collect::<Result<_>>()?;
 
             ALogicalPlan::Aggregate {
                 input: i,
@@ -287,39 +382,37 @@ pub(crate) fn to_alp(
                 aggs: aggs_new,
                 schema,
                 apply,
+                drop_null_keys,
+                skipped,
             }
         }
         LogicalPlan::Join {
             input_left,
             input_right,
             schema,
-            how,
             left_on,
             right_on,
-            allow_par,
-            force_par,
+            options,
         } => {
-            let i_l = to_alp(*input_left, expr_arena, lp_arena);
-            let i_r = to_alp(*input_right, expr_arena, lp_arena);
+            let i_l = to_alp(*input_left, expr_arena, lp_arena)?;
+            let i_r = to_alp(*input_right, expr_arena, lp_arena)?;
 
             let l_on = left_on
                 .into_iter()
-                .map(|x| to_aexpr(x, expr_arena))
-                .collect();
+                .map(|x| to_aexpr_checked(x, expr_arena))
+                .collect::<Result<_>>()?;
             let r_on = right_on
                 .into_iter()
-                .map(|x| to_aexpr(x, expr_arena))
-                .collect();
+                .map(|x| to_aexpr_checked(x, expr_arena))
+                .collect::<Result<_>>()?;
 
             ALogicalPlan::Join {
                 input_left: i_l,
                 input_right: i_r,
                 schema,
                 left_on: l_on,
-                how,
                 right_on: r_on,
-                allow_par,
-                force_par,
+                options,
             }
         }
         LogicalPlan::HStack {
@@ -327,8 +420,11 @@ pub(crate) fn to_alp(
             exprs,
             schema,
         } => {
-            let exp = exprs.into_iter().map(|x| to_aexpr(x, expr_arena)).collect();
-            let i = to_alp(*input, expr_arena, lp_arena);
+            let exp = exprs
+                .into_iter()
+                .map(|x| to_aexpr_checked(x, expr_arena))
+                .collect::<Result<_>>()?;
+            let i = to_alp(*input, expr_arena, lp_arena)?;
             ALogicalPlan::HStack {
                 input: i,
                 exprs: exp,
@@ -340,7 +436,7 @@ pub(crate) fn to_alp(
             maintain_order,
             subset,
         } => {
-            let i = to_alp(*input, expr_arena, lp_arena);
+            let i = to_alp(*input, expr_arena, lp_arena)?;
             ALogicalPlan::Distinct {
                 input: i,
                 maintain_order,
@@ -354,7 +450,7 @@ pub(crate) fn to_alp(
             predicate_pd,
             schema,
         } => {
-            let input = to_alp(*input, expr_arena, lp_arena);
+            let input = to_alp(*input, expr_arena, lp_arena)?;
             ALogicalPlan::Udf {
                 input,
                 function,
@@ -363,8 +459,9 @@ pub(crate) fn to_alp(
                 schema,
             }
         }
+        LogicalPlan::Error { err, .. } => return Err(err.take()),
     };
-    lp_arena.add(v)
+    Ok(lp_arena.add(v))
 }
 
 pub(crate) fn node_to_exp(node: Node, expr_arena: &Arena<AExpr>) -> Expr {
@@ -375,6 +472,7 @@ pub(crate) fn node_to_exp(node: Node, expr_arena: &Arena<AExpr>) -> Expr {
         AExpr::IsUnique(node) => Expr::IsUnique(Box::new(node_to_exp(node, expr_arena))),
         AExpr::Reverse(node) => Expr::Reverse(Box::new(node_to_exp(node, expr_arena))),
         AExpr::Explode(node) => Expr::Explode(Box::new(node_to_exp(node, expr_arena))),
+        AExpr::DropNulls(node) => Expr::DropNulls(Box::new(node_to_exp(node, expr_arena))),
         AExpr::Alias(expr, name) => {
             let exp = node_to_exp(expr, expr_arena);
             Expr::Alias(Box::new(exp), name)
@@ -426,10 +524,13 @@ pub(crate) fn node_to_exp(node: Node, expr_arena: &Arena<AExpr>) -> Expr {
         }
         AExpr::SortBy { expr, by, reverse } => {
             let expr = node_to_exp(expr, expr_arena);
-            let by = node_to_exp(by, expr_arena);
+            let by = by
+                .iter()
+                .map(|node| node_to_exp(*node, expr_arena))
+                .collect();
             Expr::SortBy {
                 expr: Box::new(expr),
-                by: Box::new(by),
+                by,
                 reverse,
             }
         }
@@ -475,6 +576,10 @@ pub(crate) fn node_to_exp(node: Node, expr_arena: &Arena<AExpr>) -> Expr {
                 let exp = node_to_exp(expr, expr_arena);
                 AggExpr::List(Box::new(exp)).into()
             }
+            AAggExpr::Flatten(expr) => {
+                let exp = node_to_exp(expr, expr_arena);
+                AggExpr::Flatten(Box::new(exp)).into()
+            }
             AAggExpr::Quantile { expr, quantile } => {
                 let exp = node_to_exp(expr, expr_arena);
                 AggExpr::Quantile {
@@ -503,6 +608,10 @@ pub(crate) fn node_to_exp(node: Node, expr_arena: &Arena<AExpr>) -> Expr {
                 let exp = node_to_exp(expr, expr_arena);
                 AggExpr::Count(Box::new(exp)).into()
             }
+            AAggExpr::NullCount(expr) => {
+                let exp = node_to_exp(expr, expr_arena);
+                AggExpr::NullCount(Box::new(exp)).into()
+            }
         },
         AExpr::Shift { input, periods } => {
             let e = node_to_exp(input, expr_arena);
@@ -537,6 +646,10 @@ pub(crate) fn node_to_exp(node: Node, expr_arena: &Arena<AExpr>) -> Expr {
             output_type,
             collect_groups,
         },
+        AExpr::Extension { input, plugin } => Expr::Extension {
+            input: nodes_to_exprs(&input, expr_arena),
+            plugin,
+        },
         AExpr::BinaryFunction {
             input_a,
             input_b,
@@ -688,9 +801,17 @@ pub(crate) fn node_to_lp(
                 reverse,
             }
         }
-        ALogicalPlan::Explode { input, columns } => {
+        ALogicalPlan::Explode {
+            input,
+            columns,
+            schema,
+        } => {
             let input = Box::new(node_to_lp(input, expr_arena, lp_arena));
-            LogicalPlan::Explode { input, columns }
+            LogicalPlan::Explode {
+                input,
+                columns,
+                schema,
+            }
         }
         ALogicalPlan::Cache { input } => {
             let input = Box::new(node_to_lp(input, expr_arena, lp_arena));
@@ -702,6 +823,8 @@ pub(crate) fn node_to_lp(
             aggs,
             schema,
             apply,
+            drop_null_keys,
+            skipped,
         } => {
             let i = node_to_lp(input, expr_arena, lp_arena);
 
@@ -711,17 +834,19 @@ pub(crate) fn node_to_lp(
                 aggs: nodes_to_exprs(&aggs, expr_arena),
                 schema,
                 apply,
+                // the keys were excluded when the aggregations were expanded
+                expand_keys: true,
+                drop_null_keys,
+                skipped,
             }
         }
         ALogicalPlan::Join {
             input_left,
             input_right,
             schema,
-            how,
             left_on,
             right_on,
-            allow_par,
-            force_par,
+            options,
         } => {
             let i_l = node_to_lp(input_left, expr_arena, lp_arena);
             let i_r = node_to_lp(input_right, expr_arena, lp_arena);
@@ -730,11 +855,9 @@ pub(crate) fn node_to_lp(
                 input_left: Box::new(i_l),
                 input_right: Box::new(i_r),
                 schema,
-                how,
                 left_on: nodes_to_exprs(&left_on, expr_arena),
                 right_on: nodes_to_exprs(&right_on, expr_arena),
-                allow_par,
-                force_par,
+                options,
             }
         }
         ALogicalPlan::HStack {
@@ -762,6 +885,21 @@ pub(crate) fn node_to_lp(
                 subset,
             }
         }
+        ALogicalPlan::Union {
+            inputs,
+            how,
+            schema,
+        } => {
+            let inputs = inputs
+                .into_iter()
+                .map(|node| node_to_lp(node, expr_arena, lp_arena))
+                .collect();
+            LogicalPlan::Union {
+                inputs,
+                how,
+                schema,
+            }
+        }
         ALogicalPlan::Melt {
             input,
             id_vars,
@@ -776,6 +914,18 @@ pub(crate) fn node_to_lp(
                 schema,
             }
         }
+        ALogicalPlan::RowCount {
+            input,
+            row_count,
+            schema,
+        } => {
+            let input = node_to_lp(input, expr_arena, lp_arena);
+            LogicalPlan::RowCount {
+                input: Box::new(input),
+                row_count,
+                schema,
+            }
+        }
         ALogicalPlan::Udf {
             input,
             function,
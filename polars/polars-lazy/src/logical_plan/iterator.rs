@@ -13,7 +13,7 @@ impl<'a> Iterator for ExprIter<'a> {
             let mut push = |e: &'a Expr| self.stack.push(e);
 
             match current_expr {
-                Column(_) | Literal(_) | Wildcard => {}
+                Column(_) | Literal(_) | Wildcard | Columns(_) | DtypeColumn(_) => {}
                 Alias(e, _) => push(e),
                 Not(e) => push(e),
                 BinaryExpr { left, op: _, right } => {
@@ -34,7 +34,9 @@ impl<'a> Iterator for ExprIter<'a> {
                 }
                 SortBy { expr, by, .. } => {
                     push(expr);
-                    push(by)
+                    for e in by {
+                        push(e)
+                    }
                 }
                 Agg(agg_e) => {
                     use AggExpr::*;
@@ -47,7 +49,9 @@ impl<'a> Iterator for ExprIter<'a> {
                         First(e) => push(e),
                         Last(e) => push(e),
                         List(e) => push(e),
+                        Flatten(e) => push(e),
                         Count(e) => push(e),
+                        NullCount(e) => push(e),
                         Quantile { expr, .. } => push(expr),
                         Sum(e) => push(e),
                         AggGroups(e) => push(e),
@@ -65,11 +69,13 @@ impl<'a> Iterator for ExprIter<'a> {
                     push(predicate)
                 }
                 Function { input, .. } => input.iter().for_each(|e| push(e)),
+                Extension { input, .. } => input.iter().for_each(|e| push(e)),
                 Shift { input, .. } => push(input),
                 Reverse(e) => push(e),
                 Duplicated(e) => push(e),
                 IsUnique(e) => push(e),
                 Explode(e) => push(e),
+                DropNulls(e) => push(e),
                 Window {
                     function,
                     partition_by,
@@ -91,6 +97,7 @@ impl<'a> Iterator for ExprIter<'a> {
                     push(input_b)
                 }
                 Except(e) => push(e),
+                Exclude(e, _) => push(e),
             }
             current_expr
         })
@@ -132,7 +139,9 @@ impl AExpr {
             }
             SortBy { expr, by, .. } => {
                 push(expr);
-                push(by);
+                for node in by {
+                    push(node);
+                }
             }
             Filter { input, by } => {
                 push(input);
@@ -149,7 +158,9 @@ impl AExpr {
                     First(e) => push(e),
                     Last(e) => push(e),
                     List(e) => push(e),
+                    Flatten(e) => push(e),
                     Count(e) => push(e),
+                    NullCount(e) => push(e),
                     Quantile { expr, .. } => push(expr),
                     Sum(e) => push(e),
                     AggGroups(e) => push(e),
@@ -167,11 +178,13 @@ impl AExpr {
                 push(predicate)
             }
             Function { input, .. } => input.iter().for_each(|e| push(e)),
+            Extension { input, .. } => input.iter().for_each(|e| push(e)),
             Shift { input, .. } => push(input),
             Reverse(e) => push(e),
             Duplicated(e) => push(e),
             IsUnique(e) => push(e),
             Explode(e) => push(e),
+            DropNulls(e) => push(e),
             Window {
                 function,
                 partition_by,
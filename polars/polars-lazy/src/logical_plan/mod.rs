@@ -1,22 +1,28 @@
 use std::collections::HashSet;
+#[cfg(feature = "csv-file")]
+use std::io::{Cursor, Read as _, Write as _};
+use std::ops::Range;
 #[cfg(any(feature = "csv-file", feature = "parquet"))]
 use std::path::PathBuf;
+#[cfg(feature = "csv-file")]
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::{
-    cell::Cell,
     fmt::{self, Debug, Formatter, Write},
-    sync::Arc,
+    sync::{Arc, Mutex},
 };
 
 use ahash::RandomState;
 use itertools::Itertools;
 
-use polars_core::frame::hash_join::JoinType;
+use polars_core::frame::groupby::GroupByMethod;
 use polars_core::prelude::*;
 #[cfg_attr(docsrs, doc(cfg(feature = "temporal")))]
 #[cfg(feature = "temporal")]
-use polars_core::utils::chrono::NaiveDateTime;
+use polars_core::utils::chrono::{NaiveDate, NaiveDateTime};
 #[cfg(feature = "csv-file")]
 use polars_io::csv_core::utils::infer_file_schema;
+#[cfg(feature = "csv-file")]
+use polars_io::mmap::MmapBytesReader;
 #[cfg(feature = "parquet")]
 use polars_io::{parquet::ParquetReader, SerReader};
 
@@ -26,7 +32,8 @@ use crate::utils::{
     rename_expr_root_name,
 };
 use crate::{prelude::*, utils};
-use polars_io::csv::NullValues;
+use polars_io::csv::{InferenceOptions, NullValues};
+use polars_io::RowCount;
 
 pub(crate) mod aexpr;
 pub(crate) mod alp;
@@ -34,9 +41,6 @@ pub(crate) mod conversion;
 pub(crate) mod iterator;
 pub(crate) mod optimizer;
 
-// Will be set/ unset in the fetch operation to communicate overwriting the number of rows to scan.
-thread_local! {pub(crate) static FETCH_ROWS: Cell<Option<usize>> = Cell::new(None)}
-
 #[derive(Clone, Copy, Debug)]
 pub enum Context {
     /// Any operation that is done on groups
@@ -103,6 +107,8 @@ pub enum LiteralValue {
     },
     #[cfg(all(feature = "temporal", feature = "dtype-date64"))]
     DateTime(NaiveDateTime),
+    #[cfg(all(feature = "temporal", feature = "dtype-date32"))]
+    Date(NaiveDate),
     Series(NoEq<Series>),
 }
 
@@ -130,12 +136,109 @@ impl LiteralValue {
             LiteralValue::Range { data_type, .. } => data_type.clone(),
             #[cfg(all(feature = "temporal", feature = "dtype-date64"))]
             LiteralValue::DateTime(_) => DataType::Date64,
+            #[cfg(all(feature = "temporal", feature = "dtype-date32"))]
+            LiteralValue::Date(_) => DataType::Date32,
             LiteralValue::Series(s) => s.dtype().clone(),
             LiteralValue::Null => DataType::Null,
         }
     }
 }
 
+/// Determines how the inputs of a concatenation are combined.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConcatHow {
+    /// All inputs should have the same schema.
+    Vertical,
+    /// The schemas are merged; columns missing in an input are filled with nulls.
+    Diagonal,
+    /// All inputs should have the same schema and be sorted in ascending order by the given key
+    /// column. The inputs are merged into a single frame that is sorted by the key.
+    #[cfg(feature = "merge_sorted")]
+    MergeSorted(Arc<String>),
+}
+
+/// The data of a stream that is scanned with
+/// [LazyCsvReader::from_reader](crate::frame::LazyCsvReader::from_reader). A stream up to the
+/// memory limit is held in memory, a larger stream is copied to a temporary file that is removed
+/// once the last plan that scans it is dropped.
+#[cfg(feature = "csv-file")]
+pub(crate) struct SpooledFile {
+    /// The path of the temporary file. In-memory data is scanned under this path, but the file is
+    /// not created.
+    path: PathBuf,
+    data: Option<Arc<[u8]>>,
+}
+
+#[cfg(feature = "csv-file")]
+impl SpooledFile {
+    /// Read `reader` into memory if it has at most `memory_limit` bytes, otherwise copy it to a
+    /// new temporary file. The rest of a larger stream is copied in blocks and is never fully
+    /// held in memory.
+    pub(crate) fn from_reader<R: std::io::Read>(
+        mut reader: R,
+        memory_limit: usize,
+    ) -> Result<Self> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "polars-scan-{}-{}.csv",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let mut head = Vec::new();
+        (&mut reader)
+            .take(memory_limit as u64 + 1)
+            .read_to_end(&mut head)?;
+        if head.len() <= memory_limit {
+            return Ok(SpooledFile {
+                path,
+                data: Some(head.into()),
+            });
+        }
+
+        let file = std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        // create the guard first, so that the file is removed if copying fails
+        let spooled = SpooledFile { path, data: None };
+        let mut writer = std::io::BufWriter::new(file);
+        writer.write_all(&head)?;
+        drop(head);
+        std::io::copy(&mut reader, &mut writer)?;
+        writer.flush()?;
+        Ok(spooled)
+    }
+
+    pub(crate) fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    /// The data if the stream is held in memory.
+    pub(crate) fn data(&self) -> Option<&Arc<[u8]>> {
+        self.data.as_ref()
+    }
+}
+
+// the data is not formatted, the options of a scan are formatted to create its cache key
+#[cfg(feature = "csv-file")]
+impl Debug for SpooledFile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SpooledFile")
+            .field("path", &self.path)
+            .field("in_memory", &self.data.is_some())
+            .finish()
+    }
+}
+
+#[cfg(feature = "csv-file")]
+impl Drop for SpooledFile {
+    fn drop(&mut self) {
+        if self.data.is_none() {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct CsvParserOptions {
     pub(crate) delimiter: u8,
@@ -148,6 +251,13 @@ pub struct CsvParserOptions {
     pub(crate) ignore_errors: bool,
     pub(crate) cache: bool,
     pub(crate) null_values: Option<NullValues>,
+    pub(crate) eol_char: u8,
+    /// Keeps the temporary file alive if the scanned data was copied from a stream.
+    pub(crate) spooled: Option<Arc<SpooledFile>>,
+    /// Only read the rows that start in this byte range of the file.
+    pub(crate) byte_range: Option<Range<u64>>,
+    /// Add a row count column as first column. Only set by the physical planner.
+    pub(crate) row_count: Option<RowCount>,
 }
 
 // https://stackoverflow.com/questions/1031076/what-are-projection-and-selection
@@ -208,20 +318,27 @@ pub enum LogicalPlan {
     Aggregate {
         input: Box<LogicalPlan>,
         keys: Arc<Vec<Expr>>,
+        /// The multi-output aggregations, e.g. `col("*").sum()`, are expanded when the plan is
+        /// resolved against the schema of its input, see `to_alp`.
         aggs: Vec<Expr>,
         schema: SchemaRef,
         apply: Option<Arc<dyn DataFrameUdf>>,
+        /// Also aggregate the keys in the multi-output aggregations.
+        expand_keys: bool,
+        /// Exclude the rows of which any of the keys is null.
+        drop_null_keys: bool,
+        /// The columns that were left out of an aggregation over all columns, because their
+        /// data type doesn't support the aggregation.
+        skipped: Vec<String>,
     },
     /// Join operation
     Join {
         input_left: Box<LogicalPlan>,
         input_right: Box<LogicalPlan>,
         schema: SchemaRef,
-        how: JoinType,
         left_on: Vec<Expr>,
         right_on: Vec<Expr>,
-        allow_par: bool,
-        force_par: bool,
+        options: JoinOptions,
     },
     /// Adding columns to the table without a Join
     HStack {
@@ -245,6 +362,7 @@ pub enum LogicalPlan {
     Explode {
         input: Box<LogicalPlan>,
         columns: Vec<String>,
+        schema: SchemaRef,
     },
     /// Slice the table
     Slice {
@@ -259,6 +377,12 @@ pub enum LogicalPlan {
         value_vars: Arc<Vec<String>>,
         schema: SchemaRef,
     },
+    /// Add a `UInt32` column with the row index as first column
+    RowCount {
+        input: Box<LogicalPlan>,
+        row_count: RowCount,
+        schema: SchemaRef,
+    },
     /// A User Defined Function
     Udf {
         input: Box<LogicalPlan>,
@@ -269,6 +393,66 @@ pub enum LogicalPlan {
         projection_pd: bool,
         schema: Option<SchemaRef>,
     },
+    /// Vertical concatenation of multiple inputs
+    Union {
+        inputs: Vec<LogicalPlan>,
+        how: ConcatHow,
+        schema: SchemaRef,
+    },
+    /// A plan that failed to build on top of `input`. The error is returned when the plan is
+    /// optimized or its schema is resolved.
+    Error {
+        input: Box<LogicalPlan>,
+        err: ErrorState,
+    },
+}
+
+/// The error of a [LogicalPlan::Error]. The original error is returned the first time; clones
+/// of the plan get an error with the same message after that.
+#[derive(Clone)]
+pub struct ErrorState {
+    err: Arc<Mutex<Option<PolarsError>>>,
+    msg: Arc<String>,
+}
+
+impl ErrorState {
+    pub(crate) fn take(&self) -> PolarsError {
+        match self.err.lock().unwrap().take() {
+            Some(err) => err,
+            None => PolarsError::ComputeError(self.msg.as_str().to_string().into()),
+        }
+    }
+}
+
+impl From<PolarsError> for ErrorState {
+    fn from(err: PolarsError) -> Self {
+        ErrorState {
+            msg: Arc::new(err.to_string()),
+            err: Arc::new(Mutex::new(Some(err))),
+        }
+    }
+}
+
+impl fmt::Debug for ErrorState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.msg)
+    }
+}
+
+/// Return a [LogicalPlan::Error] on top of `$input` from the builder if `$fallible` is an error.
+macro_rules! try_delayed {
+    ($fallible:expr, $input:expr) => {
+        match $fallible {
+            Ok(success) => success,
+            Err(err) => {
+                return LogicalPlan::Error {
+                    input: Box::new($input),
+                    err: err.into(),
+                }
+                .into()
+            }
+        }
+    };
 }
 
 impl Default for LogicalPlan {
@@ -317,6 +501,11 @@ impl fmt::Debug for LogicalPlan {
             Melt { input, .. } => {
                 write!(f, "MELT\n\t{:?}", input)
             }
+            RowCount {
+                input, row_count, ..
+            } => {
+                write!(f, "WITH ROW COUNT {}\n\t{:?}", row_count.name, input)
+            }
             #[cfg(feature = "csv-file")]
             CsvScan {
                 path,
@@ -383,8 +572,22 @@ impl fmt::Debug for LogicalPlan {
                 write!(f, "EXPLODE COLUMN(S) {:?} OF {:?}", columns, input)
             }
             Aggregate {
-                input, keys, aggs, ..
-            } => write!(f, "Aggregate\n\t{:?} BY {:?} FROM {:?}", aggs, keys, input),
+                input,
+                keys,
+                aggs,
+                skipped,
+                ..
+            } => {
+                if skipped.is_empty() {
+                    write!(f, "Aggregate\n\t{:?} BY {:?} FROM {:?}", aggs, keys, input)
+                } else {
+                    write!(
+                        f,
+                        "Aggregate\n\t{:?} BY {:?} SKIPPED {:?} FROM {:?}",
+                        aggs, keys, skipped, input
+                    )
+                }
+            }
             Join {
                 input_left,
                 input_right,
@@ -403,7 +606,9 @@ impl fmt::Debug for LogicalPlan {
             Slice { input, offset, len } => {
                 write!(f, "SLICE {:?}, offset: {}, len: {}", input, offset, len)
             }
-            Udf { input, .. } => write!(f, "UDF {:?}", input),
+            Udf { input, .. } => write!(f, "MAP UDF {:?}", input),
+            Union { inputs, how, .. } => write!(f, "UNION {:?}\n\t{:?}", how, inputs),
+            Error { input, err } => write!(f, "ERROR {:?} FROM {:?}", err, input),
         }
     }
 }
@@ -559,6 +764,13 @@ impl LogicalPlan {
                 self.write_dot(acc_str, prev_node, &current_node, id)?;
                 input.dot(acc_str, (branch, id + 1), &current_node)
             }
+            RowCount {
+                input, row_count, ..
+            } => {
+                let current_node = format!("ROW COUNT {} [{:?}]", row_count.name, (branch, id));
+                self.write_dot(acc_str, prev_node, &current_node, id)?;
+                input.dot(acc_str, (branch, id + 1), &current_node)
+            }
             Aggregate {
                 input, keys, aggs, ..
             } => {
@@ -654,7 +866,20 @@ impl LogicalPlan {
                 input_right.dot(acc_str, (branch + 20, id + 1), &current_node)
             }
             Udf { input, .. } => {
-                let current_node = format!("UDF [{:?}]", (branch, id));
+                let current_node = format!("MAP UDF [{:?}]", (branch, id));
+                self.write_dot(acc_str, prev_node, &current_node, id)?;
+                input.dot(acc_str, (branch, id + 1), &current_node)
+            }
+            Union { inputs, .. } => {
+                let current_node = format!("UNION [{:?}]", (branch, id));
+                self.write_dot(acc_str, prev_node, &current_node, id)?;
+                for (i, input) in inputs.iter().enumerate() {
+                    input.dot(acc_str, (branch + 10 * (i + 1), id + 1), &current_node)?;
+                }
+                Ok(())
+            }
+            Error { input, err } => {
+                let current_node = format!("ERROR {:?} [{:?}]", err, (branch, id));
                 self.write_dot(acc_str, prev_node, &current_node, id)?;
                 input.dot(acc_str, (branch, id + 1), &current_node)
             }
@@ -665,7 +890,7 @@ impl LogicalPlan {
     pub(crate) fn into_alp(self) -> (Node, Arena<ALogicalPlan>, Arena<AExpr>) {
         let mut lp_arena = Arena::with_capacity(16);
         let mut expr_arena = Arena::with_capacity(16);
-        let root = to_alp(self, &mut expr_arena, &mut lp_arena);
+        let root = to_alp(self, &mut expr_arena, &mut lp_arena).unwrap();
         (root, lp_arena, expr_arena)
     }
 }
@@ -693,6 +918,9 @@ fn replace_wildcard_with_column(expr: Expr, column_name: Arc<String>) -> Expr {
         Expr::Explode(expr) => {
             Expr::Explode(Box::new(replace_wildcard_with_column(*expr, column_name)))
         }
+        Expr::DropNulls(expr) => {
+            Expr::DropNulls(Box::new(replace_wildcard_with_column(*expr, column_name)))
+        }
         Expr::Take { expr, idx } => Expr::Take {
             expr: Box::new(replace_wildcard_with_column(*expr, column_name)),
             idx,
@@ -723,6 +951,13 @@ fn replace_wildcard_with_column(expr: Expr, column_name: Arc<String>) -> Expr {
             output_type,
             collect_groups,
         },
+        Expr::Extension { input, plugin } => Expr::Extension {
+            input: input
+                .into_iter()
+                .map(|e| replace_wildcard_with_column(e, column_name.clone()))
+                .collect(),
+            plugin,
+        },
         Expr::BinaryFunction {
             input_a,
             input_b,
@@ -739,7 +974,9 @@ fn replace_wildcard_with_column(expr: Expr, column_name: Arc<String>) -> Expr {
             op,
             right: Box::new(replace_wildcard_with_column(*right, column_name)),
         },
-        Expr::Wildcard => Expr::Column(column_name),
+        Expr::Wildcard | Expr::Columns(_) | Expr::DtypeColumn(_) => Expr::Column(column_name),
+        Expr::Column(name) if is_regex_projection(&name) => Expr::Column(column_name),
+        Expr::Exclude(expr, _) => replace_wildcard_with_column(*expr, column_name),
         Expr::IsNotNull(e) => {
             Expr::IsNotNull(Box::new(replace_wildcard_with_column(*e, column_name)))
         }
@@ -771,6 +1008,9 @@ fn replace_wildcard_with_column(expr: Expr, column_name: Arc<String>) -> Expr {
             AggExpr::Count(e) => {
                 AggExpr::Count(Box::new(replace_wildcard_with_column(*e, column_name)))
             }
+            AggExpr::NullCount(e) => {
+                AggExpr::NullCount(Box::new(replace_wildcard_with_column(*e, column_name)))
+            }
             AggExpr::Last(e) => {
                 AggExpr::Last(Box::new(replace_wildcard_with_column(*e, column_name)))
             }
@@ -790,6 +1030,9 @@ fn replace_wildcard_with_column(expr: Expr, column_name: Arc<String>) -> Expr {
             AggExpr::List(e) => {
                 AggExpr::List(Box::new(replace_wildcard_with_column(*e, column_name)))
             }
+            AggExpr::Flatten(e) => {
+                AggExpr::Flatten(Box::new(replace_wildcard_with_column(*e, column_name)))
+            }
             AggExpr::Var(e) => {
                 AggExpr::Var(Box::new(replace_wildcard_with_column(*e, column_name)))
             }
@@ -830,11 +1073,139 @@ fn replace_wildcard_with_column(expr: Expr, column_name: Arc<String>) -> Expr {
     }
 }
 
+fn is_regex_projection(name: &str) -> bool {
+    name.starts_with('^') && name.ends_with('$')
+}
+
+/// Get the names of the columns a multi-output expression expands to. These are a wildcard,
+/// a regex column, a dtype selection and a `cols` selection, minus the `exclude`d columns and
+/// dtypes. Returns `None` if the expression doesn't select multiple columns.
+fn expand_multi_output(expr: &Expr, schema: &Schema) -> Result<Option<Vec<Arc<String>>>> {
+    let mut selection = None;
+    let mut exclude = vec![];
+    let mut exclude_dtypes = vec![];
+    for e in expr {
+        let names: Vec<_> = match e {
+            Expr::Wildcard => schema.fields().iter().map(|f| f.name().clone()).collect(),
+            Expr::Columns(names) => names.clone(),
+            Expr::DtypeColumn(dtypes) => schema
+                .fields()
+                .iter()
+                .filter(|f| dtypes.contains(f.data_type()))
+                .map(|f| f.name().clone())
+                .collect(),
+            Expr::Column(name) if is_regex_projection(name) => {
+                let re = regex::Regex::new(name).map_err(|e| {
+                    PolarsError::ValueError(
+                        format!("invalid regex column selection {}: {}", name, e).into(),
+                    )
+                })?;
+                schema
+                    .fields()
+                    .iter()
+                    .filter(|f| re.is_match(f.name()))
+                    .map(|f| f.name().clone())
+                    .collect()
+            }
+            Expr::Exclude(_, excluded) => {
+                for excluded in excluded {
+                    match excluded {
+                        Excluded::Name(name) => exclude.push(name.clone()),
+                        Excluded::Dtype(dtype) => exclude_dtypes.push(dtype.clone()),
+                    }
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        if selection.is_some() {
+            return Err(PolarsError::InvalidOperation(
+                format!(
+                    "{:?} contains more than one multi-output selection, this is not supported",
+                    expr
+                )
+                .into(),
+            ));
+        }
+        selection = Some(names);
+    }
+    Ok(selection.map(|names| {
+        names
+            .into_iter()
+            .filter(|name| match schema.field_with_name(name) {
+                Ok(field) => !exclude_dtypes.contains(field.data_type()),
+                Err(_) => true,
+            })
+            .map(Arc::new)
+            .filter(|name| !exclude.contains(name))
+            .collect()
+    }))
+}
+
+/// Expand the multi-output inputs of the plugins that take them as multiple inputs, see
+/// [ExprPlugin::expand_inputs]. The plugin may be nested in an alias, cast or function.
+fn expand_plugin_inputs(expr: Expr, schema: &Schema) -> Result<Expr> {
+    let expand = |input: Vec<Expr>| -> Result<Vec<Expr>> {
+        input
+            .into_iter()
+            .map(|e| expand_plugin_inputs(e, schema))
+            .collect()
+    };
+    let expr = match expr {
+        Expr::Extension { input, plugin } if plugin.expand_inputs() => {
+            let mut expanded = Vec::with_capacity(input.len());
+            for e in expand(input)? {
+                match expand_multi_output(&e, schema)? {
+                    Some(names) => expanded.extend(
+                        names
+                            .into_iter()
+                            .map(|name| replace_wildcard_with_column(e.clone(), name)),
+                    ),
+                    None => expanded.push(e),
+                }
+            }
+            Expr::Extension {
+                input: expanded,
+                plugin,
+            }
+        }
+        Expr::Extension { input, plugin } => Expr::Extension {
+            input: expand(input)?,
+            plugin,
+        },
+        Expr::Function {
+            input,
+            function,
+            output_type,
+            collect_groups,
+        } => Expr::Function {
+            input: expand(input)?,
+            function,
+            output_type,
+            collect_groups,
+        },
+        Expr::Alias(e, name) => Expr::Alias(Box::new(expand_plugin_inputs(*e, schema)?), name),
+        Expr::Cast { expr, data_type } => Expr::Cast {
+            expr: Box::new(expand_plugin_inputs(*expr, schema)?),
+            data_type,
+        },
+        expr => expr,
+    };
+    Ok(expr)
+}
+
 /// In case of single col(*) -> do nothing, no selection is the same as select all
 /// In other cases replace the wildcard with an expression with all columns
-fn rewrite_projections(exprs: Vec<Expr>, schema: &Schema) -> Vec<Expr> {
+///
+/// The output names of the `keys` are excluded from the expansion of multi-output expressions.
+fn rewrite_projections(exprs: Vec<Expr>, schema: &Schema, keys: &[Expr]) -> Result<Vec<Expr>> {
     let mut result = Vec::with_capacity(exprs.len() + schema.fields().len());
     let mut exclude = vec![];
+    let key_names: Vec<_> = keys
+        .iter()
+        .filter_map(|e| e.to_field(schema, Context::Default).ok())
+        .map(|field| field.name().clone())
+        .collect();
     for expr in exprs {
         // Columns that are excepted are later removed from the projection.
         // This can be ergonomical in combination with a wildcard expression.
@@ -843,17 +1214,22 @@ fn rewrite_projections(exprs: Vec<Expr>, schema: &Schema) -> Vec<Expr> {
                 exclude.push(name.clone());
                 continue;
             } else {
-                panic!("Except expression should have column name")
+                return Err(PolarsError::InvalidOperation(
+                    format!("except expects a column name, got {:?}", column).into(),
+                ));
             }
         }
 
         let has_wildcard = has_expr(&expr, |e| matches!(e, Expr::Wildcard));
+        let has_exclude = has_expr(&expr, |e| matches!(e, Expr::Exclude(_, _)));
 
-        if has_wildcard {
+        if has_wildcard && !has_exclude {
             // if count wildcard. count one column
             if has_expr(&expr, |e| matches!(e, Expr::Agg(AggExpr::Count(_)))) {
-                let new_name = Arc::new(schema.field(0).unwrap().name().clone());
-                let expr = rename_expr_root_name(&expr, new_name).unwrap();
+                let field = schema.field(0).ok_or_else(|| {
+                    PolarsError::NoData("cannot count the rows of a frame without columns".into())
+                })?;
+                let expr = rename_expr_root_name(&expr, Arc::new(field.name().clone()))?;
 
                 let expr = if let Expr::Alias(_, _) = &expr {
                     expr
@@ -864,15 +1240,28 @@ fn rewrite_projections(exprs: Vec<Expr>, schema: &Schema) -> Vec<Expr> {
 
                 continue;
             }
+        }
 
-            for field in schema.fields() {
-                let name = field.name();
-                let new_expr = replace_wildcard_with_column(expr.clone(), Arc::new(name.clone()));
-                result.push(new_expr)
+        let expr = expand_plugin_inputs(expr, schema)?;
+        match expand_multi_output(&expr, schema)? {
+            Some(mut names) => {
+                names.retain(|name| !key_names.contains(name));
+                if names.len() > 1 && has_expr(&expr, |e| matches!(e, Expr::Alias(_, _))) {
+                    return Err(PolarsError::InvalidOperation(
+                        format!(
+                            "cannot alias {:?}, as it expands to multiple columns; \
+                            the expanded columns keep their own names",
+                            expr
+                        )
+                        .into(),
+                    ));
+                }
+                for name in names {
+                    result.push(replace_wildcard_with_column(expr.clone(), name))
+                }
             }
-        } else {
-            result.push(expr)
-        };
+            None => result.push(expr),
+        }
     }
     if !exclude.is_empty() {
         for name in exclude {
@@ -887,7 +1276,75 @@ fn rewrite_projections(exprs: Vec<Expr>, schema: &Schema) -> Vec<Expr> {
             }
         }
     }
-    result
+    Ok(result)
+}
+
+/// Expand the multi-output expressions in the aggregations of a groupby. The output names of the
+/// `keys` are excluded from the expansion, unless `expand_keys` is set.
+pub(crate) fn expand_aggregations(
+    keys: &[Expr],
+    aggs: Vec<Expr>,
+    input_schema: &Schema,
+    expand_keys: bool,
+) -> Result<Vec<Expr>> {
+    let excluded_keys = if expand_keys { &[] } else { keys };
+    rewrite_projections(aggs, input_schema, excluded_keys)
+}
+
+/// The output schema of a groupby, the keys followed by the expanded aggregations.
+fn aggregate_schema(
+    keys: &[Expr],
+    aggs: Vec<Expr>,
+    input_schema: &Schema,
+    expand_keys: bool,
+) -> Result<Schema> {
+    let aggs = expand_aggregations(keys, aggs, input_schema, expand_keys)?;
+    let schema1 = utils::expressions_to_schema(keys, input_schema, Context::Default);
+    let schema2 = utils::expressions_to_schema(&aggs, input_schema, Context::Aggregation);
+    Schema::try_merge(vec![schema1, schema2])
+}
+
+/// Whether the groupby aggregation `method` has an output for a column of type `dtype`. This
+/// follows the aggregations of the eager `GroupBy`, that leave out the columns without output.
+fn groupby_method_supports(method: GroupByMethod, dtype: &DataType) -> bool {
+    use DataType::*;
+    let numeric = matches!(
+        dtype,
+        UInt8 | UInt16 | UInt32 | UInt64 | Int8 | Int16 | Int32 | Int64 | Float32 | Float64
+    );
+    match method {
+        GroupByMethod::Mean => numeric || matches!(dtype, Duration(_)),
+        GroupByMethod::Sum => numeric || matches!(dtype, Boolean | Duration(_)),
+        GroupByMethod::Min | GroupByMethod::Max => {
+            numeric || matches!(dtype, Boolean | Date32 | Date64 | Time64(_) | Duration(_))
+        }
+        GroupByMethod::Median => {
+            numeric || matches!(dtype, Date32 | Date64 | Time64(_) | Duration(_))
+        }
+        GroupByMethod::Quantile(_) | GroupByMethod::Std | GroupByMethod::Var => numeric,
+        _ => true,
+    }
+}
+
+fn groupby_method_expr(expr: Expr, method: GroupByMethod) -> Expr {
+    match method {
+        GroupByMethod::Mean => expr.mean(),
+        GroupByMethod::Sum => expr.sum(),
+        GroupByMethod::Min => expr.min(),
+        GroupByMethod::Max => expr.max(),
+        GroupByMethod::Median => expr.median(),
+        GroupByMethod::Count => expr.count(),
+        GroupByMethod::First => expr.first(),
+        GroupByMethod::Last => expr.last(),
+        GroupByMethod::Groups => expr.agg_groups(),
+        GroupByMethod::NUnique => expr.n_unique(),
+        GroupByMethod::Quantile(quantile) => expr.quantile(quantile),
+        GroupByMethod::NullCount => expr.null_count(),
+        GroupByMethod::List => expr.list(),
+        GroupByMethod::Flatten => expr.flatten(),
+        GroupByMethod::Std => expr.std(),
+        GroupByMethod::Var => expr.var(),
+    }
 }
 
 pub struct LogicalPlanBuilder(LogicalPlan);
@@ -898,7 +1355,7 @@ impl LogicalPlan {
         match self {
             Cache { input } => input.schema(),
             Sort { input, .. } => input.schema(),
-            Explode { input, .. } => input.schema(),
+            Explode { schema, .. } => schema,
             #[cfg(feature = "parquet")]
             ParquetScan { schema, .. } => schema,
             DataFrameScan { schema, .. } => schema,
@@ -913,12 +1370,80 @@ impl LogicalPlan {
             Distinct { input, .. } => input.schema(),
             Slice { input, .. } => input.schema(),
             Melt { schema, .. } => schema,
+            RowCount { schema, .. } => schema,
             Udf { input, schema, .. } => match schema {
                 Some(schema) => schema,
                 None => input.schema(),
             },
+            Union { schema, .. } => schema,
+            Error { input, .. } => input.schema(),
+        }
+    }
+
+    /// Get the output schema and check that it is known before execution. This is not the case
+    /// if the plan contains a groupby apply or a map without a schema, as their output depends on
+    /// the applied function, or an expression of which the data type depends on the data.
+    pub(crate) fn resolved_schema(&self) -> Result<Schema> {
+        self.check_schema_known()?;
+        Ok(self.schema().clone())
+    }
+
+    fn check_schema_known(&self) -> Result<()> {
+        use LogicalPlan::*;
+        match self {
+            Aggregate { apply: Some(_), .. } => Err(PolarsError::ComputeError(
+                "the output schema of a groupby apply is not known before the query is executed"
+                    .into(),
+            )),
+            Udf { schema: None, .. } => Err(PolarsError::ComputeError(
+                "the output schema of a map without a schema is not known before the query is \
+                 executed"
+                    .into(),
+            )),
+            Projection { expr, .. } | LocalProjection { expr, .. } | HStack { exprs: expr, .. }
+            | Aggregate { aggs: expr, .. }
+                if expr.iter().any(|e| {
+                    has_expr(e, |e| {
+                        matches!(e, Expr::Extension { plugin, .. } if plugin.dtype_depends_on_data())
+                    })
+                }) =>
+            {
+                Err(PolarsError::ComputeError(
+                    "the output data type of an expression of the query depends on the data"
+                        .into(),
+                ))
+            }
+            #[cfg(feature = "parquet")]
+            ParquetScan { .. } => Ok(()),
+            #[cfg(feature = "csv-file")]
+            CsvScan { .. } => Ok(()),
+            DataFrameScan { .. } => Ok(()),
+            Join {
+                input_left,
+                input_right,
+                ..
+            } => {
+                input_left.check_schema_known()?;
+                input_right.check_schema_known()
+            }
+            Union { inputs, .. } => inputs.iter().try_for_each(|lp| lp.check_schema_known()),
+            Error { err, .. } => Err(err.take()),
+            Selection { input, .. }
+            | Cache { input }
+            | LocalProjection { input, .. }
+            | Projection { input, .. }
+            | Aggregate { input, .. }
+            | HStack { input, .. }
+            | Distinct { input, .. }
+            | Sort { input, .. }
+            | Explode { input, .. }
+            | Slice { input, .. }
+            | Melt { input, .. }
+            | RowCount { input, .. }
+            | Udf { input, .. } => input.check_schema_known(),
         }
     }
+
     pub fn describe(&self) -> String {
         format!("{:#?}", self)
     }
@@ -930,10 +1455,10 @@ impl From<LogicalPlan> for LogicalPlanBuilder {
     }
 }
 
-pub(crate) fn prepare_projection(exprs: Vec<Expr>, schema: &Schema) -> (Vec<Expr>, Schema) {
-    let exprs = rewrite_projections(exprs, schema);
+pub(crate) fn prepare_projection(exprs: Vec<Expr>, schema: &Schema) -> Result<(Vec<Expr>, Schema)> {
+    let exprs = rewrite_projections(exprs, schema, &[])?;
     let schema = utils::expressions_to_schema(&exprs, schema, Context::Default);
-    (exprs, schema)
+    Ok((exprs, schema))
 }
 
 impl LogicalPlanBuilder {
@@ -979,19 +1504,64 @@ impl LogicalPlanBuilder {
         low_memory: bool,
         comment_char: Option<u8>,
         null_values: Option<NullValues>,
+        eol_char: u8,
+        inference_options: &InferenceOptions,
     ) -> Self {
-        let path = path.into();
-        let mut file = std::fs::File::open(&path).expect("could not open file");
+        Self::scan_csv_spooled(
+            path.into(),
+            delimiter,
+            has_header,
+            ignore_errors,
+            skip_rows,
+            stop_after_n_rows,
+            cache,
+            schema,
+            schema_overwrite,
+            low_memory,
+            comment_char,
+            null_values,
+            eol_char,
+            None,
+            inference_options,
+        )
+    }
 
+    /// Scan CSV data that may be a stream that is held in memory.
+    #[allow(clippy::too_many_arguments)]
+    #[cfg(feature = "csv-file")]
+    pub(crate) fn scan_csv_spooled(
+        path: PathBuf,
+        delimiter: u8,
+        has_header: bool,
+        ignore_errors: bool,
+        skip_rows: usize,
+        stop_after_n_rows: Option<usize>,
+        cache: bool,
+        schema: Option<Arc<Schema>>,
+        schema_overwrite: Option<&Schema>,
+        low_memory: bool,
+        comment_char: Option<u8>,
+        null_values: Option<NullValues>,
+        eol_char: u8,
+        spooled: Option<Arc<SpooledFile>>,
+        inference_options: &InferenceOptions,
+    ) -> Self {
         let schema = schema.unwrap_or_else(|| {
+            let mut reader: Box<dyn MmapBytesReader> =
+                match spooled.as_ref().and_then(|spooled| spooled.data()) {
+                    Some(data) => Box::new(Cursor::new(data.clone())),
+                    None => Box::new(std::fs::File::open(&path).expect("could not open file")),
+                };
             let (schema, _) = infer_file_schema(
-                &mut file,
+                &mut reader,
                 delimiter,
                 Some(100),
                 has_header,
                 schema_overwrite,
                 skip_rows,
                 comment_char,
+                eol_char,
+                inference_options,
             )
             .expect("could not read schema");
             Arc::new(schema)
@@ -1010,6 +1580,10 @@ impl LogicalPlanBuilder {
                 cache,
                 comment_char,
                 null_values,
+                eol_char,
+                spooled,
+                byte_range: None,
+                row_count: None,
             },
             predicate: None,
             aggregate: vec![],
@@ -1025,7 +1599,7 @@ impl LogicalPlanBuilder {
     }
 
     pub fn project(self, exprs: Vec<Expr>) -> Self {
-        let (exprs, schema) = prepare_projection(exprs, self.0.schema());
+        let (exprs, schema) = try_delayed!(prepare_projection(exprs, self.0.schema()), self.0);
 
         // if len == 0, no projection has to be done. This is a select all operation.
         if !exprs.is_empty() {
@@ -1041,7 +1615,7 @@ impl LogicalPlanBuilder {
     }
 
     pub fn project_local(self, exprs: Vec<Expr>) -> Self {
-        let (exprs, schema) = prepare_projection(exprs, self.0.schema());
+        let (exprs, schema) = try_delayed!(prepare_projection(exprs, self.0.schema()), self.0);
         if !exprs.is_empty() {
             LogicalPlan::LocalProjection {
                 expr: exprs,
@@ -1073,11 +1647,12 @@ impl LogicalPlanBuilder {
     pub fn with_columns(self, exprs: Vec<Expr>) -> Self {
         // current schema
         let schema = self.0.schema();
+        let exprs = try_delayed!(rewrite_projections(exprs, schema, &[]), self.0);
 
         let mut new_fields = schema.fields().clone();
 
         for e in &exprs {
-            let field = e.to_field(schema, Context::Default).unwrap();
+            let field = try_delayed!(e.to_field(schema, Context::Default), self.0);
             match schema.index_of(field.name()) {
                 Ok(idx) => {
                     new_fields[idx] = field;
@@ -1113,19 +1688,28 @@ impl LogicalPlanBuilder {
         .into()
     }
 
+    /// The groupby keys are excluded from the expansion of multi-output aggregations, unless
+    /// `expand_keys` is set.
     pub fn groupby(
         self,
         keys: Arc<Vec<Expr>>,
         aggs: Vec<Expr>,
         apply: Option<Arc<dyn DataFrameUdf>>,
+        expand_keys: bool,
+        drop_null_keys: bool,
     ) -> Self {
-        debug_assert!(!keys.is_empty());
+        if keys.is_empty() {
+            return LogicalPlan::Error {
+                input: Box::new(self.0),
+                err: PolarsError::ValueError("a groupby needs at least one key".into()).into(),
+            }
+            .into();
+        }
         let current_schema = self.0.schema();
-        let aggs = rewrite_projections(aggs, current_schema);
-
-        let schema1 = utils::expressions_to_schema(&keys, current_schema, Context::Default);
-        let schema2 = utils::expressions_to_schema(&aggs, current_schema, Context::Aggregation);
-        let schema = Schema::try_merge(&[schema1, schema2]).unwrap();
+        let schema = try_delayed!(
+            aggregate_schema(&keys, aggs.clone(), current_schema, expand_keys),
+            self.0
+        );
 
         LogicalPlan::Aggregate {
             input: Box::new(self.0),
@@ -1133,10 +1717,47 @@ impl LogicalPlanBuilder {
             aggs,
             schema: Arc::new(schema),
             apply,
+            expand_keys,
+            drop_null_keys,
+            skipped: vec![],
         }
         .into()
     }
 
+    /// Aggregate all columns with `method`, the columns of which the data type doesn't support
+    /// the aggregation are skipped.
+    pub fn groupby_all(
+        self,
+        keys: Arc<Vec<Expr>>,
+        method: GroupByMethod,
+        expand_keys: bool,
+        drop_null_keys: bool,
+    ) -> Self {
+        let current_schema = self.0.schema();
+        let key_schema = utils::expressions_to_schema(&keys, current_schema, Context::Default);
+        let mut aggs = vec![];
+        let mut skipped = vec![];
+        for field in current_schema.fields() {
+            let name = field.name();
+            if !expand_keys && key_schema.field_with_name(name).is_ok() {
+                continue;
+            }
+            if groupby_method_supports(method, field.data_type()) {
+                aggs.push(groupby_method_expr(col(name), method));
+            } else {
+                skipped.push(name.clone());
+            }
+        }
+
+        let mut lp = self
+            .groupby(keys, aggs, None, expand_keys, drop_null_keys)
+            .build();
+        if let LogicalPlan::Aggregate { skipped: s, .. } = &mut lp {
+            *s = skipped
+        }
+        lp.into()
+    }
+
     pub fn build(self) -> LogicalPlan {
         self.0
     }
@@ -1161,10 +1782,33 @@ impl LogicalPlanBuilder {
         .into()
     }
 
-    pub fn explode(self, columns: Vec<String>) -> Self {
+    /// Explode the given columns. Expressions that aren't a plain column are first added with
+    /// `with_columns` and their output names are exploded.
+    pub fn explode(self, columns: Vec<Expr>) -> Self {
+        let columns = try_delayed!(rewrite_projections(columns, self.0.schema(), &[]), self.0);
+        let mut names = Vec::with_capacity(columns.len());
+        let mut computed = vec![];
+        for e in columns {
+            match e {
+                Expr::Column(name) => names.push((*name).clone()),
+                e => {
+                    let name = try_delayed!(utils::output_name(&e), self.0);
+                    names.push((*name).clone());
+                    computed.push(e);
+                }
+            }
+        }
+        let builder = if computed.is_empty() {
+            self
+        } else {
+            self.with_columns(computed)
+        };
+
+        let schema = det_explode_schema(&names, builder.0.schema());
         LogicalPlan::Explode {
-            input: Box::new(self.0),
-            columns,
+            input: Box::new(builder.0),
+            columns: names,
+            schema,
         }
         .into()
     }
@@ -1180,6 +1824,16 @@ impl LogicalPlanBuilder {
         .into()
     }
 
+    pub fn with_row_count(self, row_count: RowCount) -> Self {
+        let schema = det_row_count_schema(&row_count.name, self.0.schema());
+        LogicalPlan::RowCount {
+            input: Box::new(self.0),
+            row_count,
+            schema,
+        }
+        .into()
+    }
+
     pub fn drop_duplicates(self, maintain_order: bool, subset: Option<Vec<String>>) -> Self {
         LogicalPlan::Distinct {
             input: Box::new(self.0),
@@ -1201,36 +1855,46 @@ impl LogicalPlanBuilder {
     pub fn join(
         self,
         other: LogicalPlan,
-        how: JoinType,
         left_on: Vec<Expr>,
         right_on: Vec<Expr>,
-        allow_par: bool,
-        force_par: bool,
+        options: JoinOptions,
     ) -> Self {
+        try_delayed!(options.validate(), self.0);
         let schema_left = self.0.schema();
         let schema_right = other.schema();
 
-        // column names of left table
-        let mut names: HashSet<&String, RandomState> = HashSet::default();
         // fields of new schema
-        let mut fields = vec![];
-
-        for f in schema_left.fields() {
-            names.insert(f.name());
-            fields.push(f.clone());
+        let mut fields = schema_left.fields().clone();
+
+        // an outer join coalesces the keys into the output columns of the left keys
+        if options.how == JoinType::Outer {
+            for e in &left_on {
+                let field = try_delayed!(e.to_field(schema_left, Context::Default), self.0);
+                match schema_left.index_of(field.name()) {
+                    Ok(idx) => fields[idx] = field,
+                    Err(_) => fields.push(field),
+                }
+            }
         }
 
+        // column names of left table
+        let names: HashSet<String, RandomState> = fields.iter().map(|f| f.name().clone()).collect();
+
+        // only the right join columns are not in the output, keys computed from them are
         let right_names: HashSet<_, RandomState> = right_on
             .iter()
-            .map(|e| utils::output_name(e).expect("could not find name"))
+            .filter_map(|e| match e {
+                Expr::Column(name) => Some(name.as_str()),
+                _ => None,
+            })
             .collect();
 
         for f in schema_right.fields() {
             let name = f.name();
 
-            if !right_names.contains(name) {
+            if !right_names.contains(name.as_str()) {
                 if names.contains(name) {
-                    let new_name = format!("{}_right", name);
+                    let new_name = format!("{}{}", name, options.suffix);
                     let field = Field::new(&new_name, f.data_type().clone());
                     fields.push(field)
                 } else {
@@ -1244,12 +1908,10 @@ impl LogicalPlanBuilder {
         LogicalPlan::Join {
             input_left: Box::new(self.0),
             input_right: Box::new(other),
-            how,
             schema,
             left_on,
             right_on,
-            allow_par,
-            force_par,
+            options,
         }
         .into()
     }
@@ -1273,6 +1935,58 @@ impl LogicalPlanBuilder {
     }
 }
 
+pub(crate) fn det_union_schema<'a, I>(schemas: I, how: &ConcatHow) -> Result<SchemaRef>
+where
+    I: IntoIterator<Item = &'a Schema>,
+{
+    // all inputs should have the same schema
+    fn vertical_schema<'a>(mut schemas: impl Iterator<Item = &'a Schema>) -> Result<Schema> {
+        let first = schemas.next().ok_or_else(|| {
+            PolarsError::NoData("cannot concatenate an empty list of inputs".into())
+        })?;
+        if let Some(other) = schemas.find(|schema| *schema != first) {
+            return Err(PolarsError::ShapeMisMatch(
+                format!(
+                    "cannot vertically concatenate inputs with different schemas: {:?} and {:?}",
+                    first, other
+                )
+                .into(),
+            ));
+        }
+        Ok(first.clone())
+    }
+
+    let schemas = schemas.into_iter();
+    let schema = match how {
+        ConcatHow::Vertical => vertical_schema(schemas)?,
+        ConcatHow::Diagonal => Schema::try_merge(schemas.cloned())?,
+        #[cfg(feature = "merge_sorted")]
+        ConcatHow::MergeSorted(key) => {
+            let schema = vertical_schema(schemas)?;
+            schema
+                .field_with_name(key)
+                .map_err(|_| PolarsError::ColumnNotFound(key.to_string()))?;
+            schema
+        }
+    };
+    Ok(Arc::new(schema))
+}
+
+/// The exploded list columns get the dtype of their inner values.
+pub(crate) fn det_explode_schema(columns: &[String], input_schema: &Schema) -> SchemaRef {
+    let fields = input_schema
+        .fields()
+        .iter()
+        .map(|field| match field.data_type() {
+            DataType::List(inner) if columns.contains(field.name()) => {
+                Field::new(field.name(), inner.into())
+            }
+            _ => field.clone(),
+        })
+        .collect_vec();
+    Arc::new(Schema::new(fields))
+}
+
 pub(crate) fn det_melt_schema(value_vars: &[String], input_schema: &Schema) -> SchemaRef {
     let mut fields = input_schema
         .fields()
@@ -1294,13 +2008,24 @@ pub(crate) fn det_melt_schema(value_vars: &[String], input_schema: &Schema) -> S
     Arc::new(Schema::new(fields))
 }
 
+pub(crate) fn det_row_count_schema(name: &str, input_schema: &Schema) -> SchemaRef {
+    let mut fields = Vec::with_capacity(input_schema.fields().len() + 1);
+    fields.push(Field::new(name, DataType::UInt32));
+    fields.extend(input_schema.fields().iter().cloned());
+    Arc::new(Schema::new(fields))
+}
+
 #[cfg(test)]
 mod test {
     use polars_core::df;
     use polars_core::prelude::*;
 
+    #[cfg(feature = "csv-file")]
+    use super::SpooledFile;
     use crate::prelude::*;
     use crate::tests::get_df;
+    #[cfg(feature = "csv-file")]
+    use std::io::Cursor;
 
     fn print_plans(lf: &LazyFrame) {
         println!("LOGICAL PLAN\n\n{}\n", lf.describe_plan());
@@ -1459,6 +2184,97 @@ mod test {
         // }
     }
 
+    #[test]
+    #[cfg(feature = "csv-file")]
+    fn test_lazy_join_projection_pushdown() -> Result<()> {
+        use crate::logical_plan::iterator::ArenaLpIter;
+
+        // two tables with 20 columns with the same names
+        let mut paths = vec![];
+        for side in &["left", "right"] {
+            let path = std::env::temp_dir().join(format!("polars_test_join_pushdown_{}.csv", side));
+            let header = std::iter::once("id".to_string())
+                .chain((1..20).map(|j| format!("c{}", j)))
+                .collect::<Vec<_>>()
+                .join(",");
+            let mut csv = header + "\n";
+            for i in 0..3 {
+                let row = std::iter::once(i)
+                    .chain((1..20).map(|j| i * 100 + j))
+                    .map(|v| v.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",");
+                csv.push_str(&row);
+                csv.push('\n');
+            }
+            std::fs::write(&path, csv)?;
+            paths.push(path.to_string_lossy().to_string());
+        }
+
+        let scan_columns = |lf: LazyFrame| -> Result<Vec<Vec<String>>> {
+            let mut expr_arena = Arena::with_capacity(64);
+            let mut lp_arena = Arena::with_capacity(32);
+            let root = lf.optimize(&mut lp_arena, &mut expr_arena)?;
+            let mut columns = (&lp_arena)
+                .iter(root)
+                .filter_map(|(_, lp)| match lp {
+                    ALogicalPlan::CsvScan { options, .. } => {
+                        let mut columns = options.with_columns.clone().unwrap();
+                        columns.sort();
+                        Some(columns)
+                    }
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            columns.sort();
+            Ok(columns)
+        };
+
+        let join = |select: Vec<Expr>| {
+            LazyCsvReader::new(paths[0].clone())
+                .finish()
+                .inner_join(
+                    LazyCsvReader::new(paths[1].clone()).finish(),
+                    col("id"),
+                    col("id"),
+                )
+                .select(&select)
+        };
+
+        // a column that is in both tables refers to the left table, the right table only reads
+        // the join key and the renamed column
+        let lf = join(vec![col("c1"), col("c2"), col("c3_right")]);
+        assert_eq!(
+            scan_columns(lf.clone())?,
+            &[vec!["c1", "c2", "id"], vec!["c3", "id"]]
+        );
+        assert!(lf
+            .describe_optimized_plan()?
+            .contains("PROJECT 2/20 COLUMNS"));
+        let out = lf.clone().sort("c1", false).collect()?;
+        assert_eq!(out.get_column_names(), &["c1", "c2", "c3_right"]);
+        let expected = lf.without_optimizations().sort("c1", false).collect()?;
+        assert!(out.frame_equal(&expected));
+
+        // the same column of both tables
+        let lf = join(vec![col("c1"), col("c1_right").alias("foo")]);
+        assert_eq!(
+            scan_columns(lf.clone())?,
+            &[vec!["c1", "id"], vec!["c1", "id"]]
+        );
+        let out = lf.sort("c1", false).collect()?;
+        assert_eq!(out.get_column_names(), &["c1", "foo"]);
+        assert_eq!(
+            Vec::from(out.column("foo")?.i64()?),
+            &[Some(1), Some(101), Some(201)]
+        );
+
+        for path in paths {
+            std::fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
     #[test]
     fn test_dot() {
         let left = df!("days" => &[0, 1, 2, 3, 4],
@@ -1474,4 +2290,35 @@ mod test {
             .unwrap();
         println!("{}", s);
     }
+
+    #[test]
+    #[cfg(feature = "csv-file")]
+    fn test_scan_csv_from_reader() -> Result<()> {
+        let data = "a,b,c\n1,x,1.0\n2,y,2.0\n3,z,3.0\n";
+        // held in memory and spooled to a file
+        for memory_limit in [data.len(), data.len() - 1] {
+            let lf = LazyCsvReader::from_reader_with_memory_limit(Cursor::new(data), memory_limit)?
+                .finish()
+                .filter(col("a").gt(lit(1)))
+                .select(vec![col("b")]);
+
+            // the plan can be executed multiple times
+            for _ in 0..2 {
+                let out = lf.clone().collect()?;
+                assert_eq!(Vec::from(out.column("b")?.utf8()?), &[Some("y"), Some("z")]);
+            }
+        }
+
+        let spooled = SpooledFile::from_reader(Cursor::new(data), data.len())?;
+        assert_eq!(spooled.data().map(|data| data.len()), Some(data.len()));
+        assert!(!spooled.path().exists());
+
+        let spooled = SpooledFile::from_reader(Cursor::new(data), 4)?;
+        assert!(spooled.data().is_none());
+        let path = spooled.path().clone();
+        assert_eq!(std::fs::read_to_string(&path)?, data);
+        drop(spooled);
+        assert!(!path.exists());
+        Ok(())
+    }
 }
@@ -3,8 +3,32 @@ use polars_core::prelude::*;
 use crate::logical_plan::optimizer::stack_opt::OptimizationRule;
 use crate::logical_plan::Context;
 use crate::prelude::*;
-use crate::utils::{aexpr_to_root_nodes, has_aexpr};
+use crate::utils::aexpr_to_root_names;
 
+/// Check if the expression is an aggregation of a column that a scan can do, see
+/// [ScanAggregation](polars_io::ScanAggregation).
+fn is_scan_aggregation(node: Node, expr_arena: &Arena<AExpr>) -> bool {
+    let node = match expr_arena.get(node) {
+        AExpr::Alias(e, _) => *e,
+        _ => node,
+    };
+    match expr_arena.get(node) {
+        AExpr::Agg(AAggExpr::Min(e))
+        | AExpr::Agg(AAggExpr::Max(e))
+        | AExpr::Agg(AAggExpr::Sum(e))
+        | AExpr::Agg(AAggExpr::First(e))
+        | AExpr::Agg(AAggExpr::Last(e)) => matches!(expr_arena.get(*e), AExpr::Column(_)),
+        _ => false,
+    }
+}
+
+/// Pushes a projection of aggregations down to the scan, such that the scan aggregates while
+/// reading.
+///
+/// The aggregations pass projections that select the aggregated columns unchanged and
+/// `with_column` nodes that don't recompute the aggregated columns. These nodes are removed,
+/// as the aggregations don't read the other columns. At any other node the aggregations are
+/// done on top of that node.
 pub(crate) struct AggregatePushdown {
     accumulated_projections: Vec<Node>,
     processed_state: bool,
@@ -22,42 +46,130 @@ impl AggregatePushdown {
         std::mem::take(&mut self.accumulated_projections)
     }
 
+    /// The names of the columns that are aggregated.
+    fn aggregated_columns(&self, expr_arena: &Arena<AExpr>) -> Vec<Arc<String>> {
+        self.accumulated_projections
+            .iter()
+            .flat_map(|node| aexpr_to_root_names(*node, expr_arena))
+            .collect()
+    }
+
     fn pushdown_projection(
         &mut self,
         node: Node,
-        expr: Vec<Node>,
-        input: Node,
-        schema: SchemaRef,
+        lp: ALogicalPlan,
         lp_arena: &mut Arena<ALogicalPlan>,
         expr_arena: &mut Arena<AExpr>,
     ) -> Option<ALogicalPlan> {
-        // only do aggregation pushdown if all projections are aggregations
-        #[allow(clippy::blocks_in_if_conditions)]
-        if !self.processed_state
-            && expr.iter().all(|node| {
-                has_aexpr(*node, expr_arena, |e| matches!(e, AExpr::Agg(_))) && {
-                    let roots = aexpr_to_root_nodes(*node, expr_arena);
-                    roots.len() == 1
-                }
-            })
+        let (expr, input) = match &lp {
+            ALogicalPlan::Projection { expr, input, .. }
+            | ALogicalPlan::LocalProjection { expr, input, .. } => (expr, *input),
+            _ => unreachable!(),
+        };
+
+        if self.accumulated_projections.is_empty() {
+            // only do aggregation pushdown if all projections are aggregations
+            if !self.processed_state
+                && !expr.is_empty()
+                && expr
+                    .iter()
+                    .all(|node| is_scan_aggregation(*node, expr_arena))
+            {
+                // add to state
+                self.accumulated_projections.extend_from_slice(expr);
+                // swap projection with the input node
+                return Some(lp_arena.take(input));
+            }
+            // restore lp node
+            lp_arena.replace(node, lp);
+            return None;
+        }
+
+        // the projection is removed if it selects the aggregated columns unchanged
+        let selects_unchanged = |name: &Arc<String>| {
+            expr.iter().any(
+                |node| matches!(expr_arena.get(*node), AExpr::Column(column) if column == name),
+            )
+        };
+        if self
+            .aggregated_columns(expr_arena)
+            .iter()
+            .all(selects_unchanged)
         {
-            // add to state
-            self.accumulated_projections.extend_from_slice(&expr);
-            // swap projection with the input node
-            let lp = lp_arena.take(input);
-            Some(lp)
+            Some(lp_arena.take(input))
         } else {
-            // restore lp node
-            lp_arena.replace(
-                node,
-                ALogicalPlan::Projection {
-                    expr,
-                    input,
-                    schema,
-                },
-            );
-            None
+            self.finish_at(node, lp, lp_arena, expr_arena)
+        }
+    }
+
+    fn pushdown_hstack(
+        &mut self,
+        node: Node,
+        lp: ALogicalPlan,
+        lp_arena: &mut Arena<ALogicalPlan>,
+        expr_arena: &mut Arena<AExpr>,
+    ) -> Option<ALogicalPlan> {
+        let (exprs, input) = match &lp {
+            ALogicalPlan::HStack { exprs, input, .. } => (exprs, *input),
+            _ => unreachable!(),
+        };
+        if self.accumulated_projections.is_empty() {
+            lp_arena.replace(node, lp);
+            return None;
+        }
+
+        // the `with_column` is removed if it doesn't (re)compute an aggregated column
+        let input_schema = lp_arena.get(input).schema(lp_arena);
+        let aggregated = self.aggregated_columns(expr_arena);
+        let recomputes_aggregated = exprs.iter().any(|e| {
+            match expr_arena
+                .get(*e)
+                .to_field(input_schema, Context::Default, expr_arena)
+            {
+                Ok(field) => aggregated
+                    .iter()
+                    .any(|name| name.as_str() == field.name().as_str()),
+                Err(_) => true,
+            }
+        });
+        if recomputes_aggregated {
+            self.finish_at(node, lp, lp_arena, expr_arena)
+        } else {
+            Some(lp_arena.take(input))
+        }
+    }
+
+    /// The accumulated aggregations cannot pass this node, so they are done on top of it.
+    fn finish_at(
+        &mut self,
+        node: Node,
+        lp: ALogicalPlan,
+        lp_arena: &mut Arena<ALogicalPlan>,
+        expr_arena: &mut Arena<AExpr>,
+    ) -> Option<ALogicalPlan> {
+        if self.accumulated_projections.is_empty() {
+            lp_arena.replace(node, lp);
+            return None;
         }
+        let new_node = lp_arena.add(lp);
+        let input_schema = lp_arena.get(new_node).schema(lp_arena);
+
+        let nodes: Vec<_> = self.process_nodes();
+        let fields = nodes
+            .iter()
+            .map(|n| {
+                expr_arena
+                    .get(*n)
+                    .to_field(input_schema, Context::Default, expr_arena)
+                    .unwrap()
+            })
+            .collect();
+
+        Some(ALogicalPlan::Projection {
+            expr: nodes,
+            input: new_node,
+            schema: Arc::new(Schema::new(fields)),
+        })
     }
 }
 
@@ -73,45 +185,10 @@ impl OptimizationRule for AggregatePushdown {
         let lp = lp_arena.take(node);
         use ALogicalPlan::*;
         match lp {
-            Projection {
-                expr,
-                input,
-                schema,
-            } => self.pushdown_projection(node, expr, input, schema, lp_arena, expr_arena),
-            LocalProjection {
-                expr,
-                input,
-                schema,
-            } => self.pushdown_projection(node, expr, input, schema, lp_arena, expr_arena),
-            // todo! hstack should pushown not dependent columns
-            Join { .. } | Aggregate { .. } | HStack { .. } | DataFrameScan { .. } => {
-                if self.accumulated_projections.is_empty() {
-                    lp_arena.replace(node, lp);
-                    None
-                } else {
-                    // we cannot pass a join or GroupBy so we do the projection here
-                    let new_node = lp_arena.add(lp.clone());
-                    let input_schema = lp_arena.get(new_node).schema(lp_arena);
-
-                    let nodes: Vec<_> = self.process_nodes();
-                    let fields = self
-                        .accumulated_projections
-                        .iter()
-                        .map(|n| {
-                            expr_arena
-                                .get(*n)
-                                .to_field(input_schema, Context::Default, expr_arena)
-                                .unwrap()
-                        })
-                        .collect();
-
-                    Some(ALogicalPlan::Projection {
-                        expr: nodes,
-                        input: new_node,
-                        schema: Arc::new(Schema::new(fields)),
-                    })
-                }
+            Projection { .. } | LocalProjection { .. } => {
+                self.pushdown_projection(node, lp, lp_arena, expr_arena)
             }
+            HStack { .. } => self.pushdown_hstack(node, lp, lp_arena, expr_arena),
             #[cfg(feature = "csv-file")]
             CsvScan {
                 path,
@@ -182,11 +259,58 @@ impl OptimizationRule for AggregatePushdown {
                     })
                 }
             },
-            _ => {
-                // restore lp
-                lp_arena.replace(node, lp);
-                None
-            }
+            // the other nodes may change the values of the aggregated columns
+            lp => self.finish_at(node, lp, lp_arena, expr_arena),
         }
     }
 }
+
+#[cfg(all(test, feature = "csv-file"))]
+mod test {
+    use super::*;
+    use crate::logical_plan::iterator::ArenaLpIter;
+
+    fn scan_foods() -> LazyFrame {
+        let path = "../../examples/aggregate_multiple_files_in_chunks/datasets/foods1.csv";
+        LazyCsvReader::new(path.to_string())
+            .finish()
+            .with_aggregate_pushdown(true)
+    }
+
+    fn is_pushed_down(lf: LazyFrame) -> Result<bool> {
+        let mut lp_arena = Arena::new();
+        let mut expr_arena = Arena::new();
+        let root = lf.optimize(&mut lp_arena, &mut expr_arena)?;
+        let pushed_down = (&lp_arena).iter(root).any(|(_, lp)| {
+            matches!(lp, ALogicalPlan::CsvScan { aggregate, .. } if !aggregate.is_empty())
+        });
+        Ok(pushed_down)
+    }
+
+    #[test]
+    fn test_aggregate_pushdown_through_projections() -> Result<()> {
+        let q = scan_foods()
+            .select(vec![col("calories"), col("fats_g")])
+            .select(vec![col("calories").min()]);
+        assert!(is_pushed_down(q.clone())?);
+        let out = q.collect()?;
+        assert_eq!(Vec::from(out.column("calories")?.i64()?), &[Some(20)]);
+
+        // the `with_column` doesn't touch the aggregated column
+        let q = scan_foods()
+            .with_column((col("fats_g") * lit(2)).alias("fats_g"))
+            .select(vec![col("calories").max().alias("max")]);
+        assert!(is_pushed_down(q.clone())?);
+        let out = q.collect()?;
+        assert_eq!(Vec::from(out.column("max")?.i64()?), &[Some(200)]);
+
+        // the aggregated column is recomputed
+        let q = scan_foods()
+            .with_column((col("calories") + lit(1)).alias("calories"))
+            .select(vec![col("calories").min()]);
+        assert!(!is_pushed_down(q.clone())?);
+        let out = q.collect()?;
+        assert_eq!(Vec::from(out.column("calories")?.i64()?), &[Some(21)]);
+        Ok(())
+    }
+}
@@ -1,27 +1,30 @@
 use crate::logical_plan::optimizer::stack_opt::OptimizationRule;
 use crate::logical_plan::ALogicalPlanBuilder;
 use crate::prelude::*;
-use polars_core::datatypes::{PlHashMap, PlHashSet};
+use polars_core::datatypes::PlHashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 fn process_with_columns(
     path: &Path,
     with_columns: &Option<Vec<String>>,
-    columns: &mut PlHashMap<PathBuf, PlHashSet<String>>,
+    columns: &mut PlHashMap<PathBuf, Vec<String>>,
 ) {
     if let Some(with_columns) = &with_columns {
-        let cols = columns
-            .entry(path.to_owned())
-            .or_insert_with(PlHashSet::new);
-        cols.extend(with_columns.iter().cloned());
+        let cols = columns.entry(path.to_owned()).or_insert_with(Vec::new);
+        // keep the order in which the columns are first seen, such that the scan is deterministic
+        for name in with_columns {
+            if !cols.contains(name) {
+                cols.push(name.clone())
+            }
+        }
     }
 }
 
 /// Aggregate all the projections in an LP
 pub(crate) fn agg_projection(
     root: Node,
-    columns: &mut PlHashMap<PathBuf, PlHashSet<String>>,
+    columns: &mut PlHashMap<PathBuf, Vec<String>>,
     lp_arena: &Arena<ALogicalPlan>,
 ) {
     use ALogicalPlan::*;
@@ -48,8 +51,12 @@ pub(crate) fn agg_projection(
 /// Aggregate all the columns used in csv scans and make sure that all columns are scanned in one go.
 /// Due to self joins there can be multiple Scans of the same file in a LP. We already cache the scans
 /// in the PhysicalPlan, but we need to make sure that the first scan has all the columns needed.
+///
+/// The aggregated columns of a file are kept in the order in which they are first seen. A scan
+/// that requested other columns, or the same columns in another order, gets a local projection
+/// that selects its columns in the requested order.
 pub struct AggScanProjection {
-    pub columns: PlHashMap<PathBuf, PlHashSet<String>>,
+    pub columns: PlHashMap<PathBuf, Vec<String>>,
 }
 
 impl AggScanProjection {
@@ -61,10 +68,10 @@ impl AggScanProjection {
         path: &Path,
         with_columns: Option<Vec<String>>,
     ) -> ALogicalPlan {
-        // if the original projection is less than the new one. Also project locally
+        // if the original projection differs from the new one. Also project locally
         if let Some(with_columns) = with_columns {
             let agg = self.columns.get(path).unwrap();
-            if with_columns.len() < agg.len() {
+            if &with_columns != agg {
                 let node = lp_arena.add(lp);
 
                 let projections = with_columns
@@ -103,10 +110,7 @@ impl OptimizationRule for AggScanProjection {
                     cache,
                 } = lp
                 {
-                    let new_with_columns = self
-                        .columns
-                        .get(&path)
-                        .map(|agg| agg.iter().cloned().collect());
+                    let new_with_columns = self.columns.get(&path).cloned();
                     // prevent infinite loop
                     if with_columns == new_with_columns {
                         let lp = ALogicalPlan::ParquetScan {
@@ -147,10 +151,7 @@ impl OptimizationRule for AggScanProjection {
                     aggregate,
                 } = lp
                 {
-                    let new_with_columns = self
-                        .columns
-                        .get(&path)
-                        .map(|agg| agg.iter().cloned().collect());
+                    let new_with_columns = self.columns.get(&path).cloned();
                     if options.with_columns == new_with_columns {
                         let lp = ALogicalPlan::CsvScan {
                             path,
@@ -162,15 +163,16 @@ impl OptimizationRule for AggScanProjection {
                         lp_arena.replace(node, lp);
                         return None;
                     }
-                    options.with_columns = new_with_columns;
+                    let with_columns =
+                        std::mem::replace(&mut options.with_columns, new_with_columns);
                     let lp = ALogicalPlan::CsvScan {
                         path: path.clone(),
                         schema,
-                        options: options.clone(),
+                        options,
                         predicate,
                         aggregate,
                     };
-                    Some(self.finish_rewrite(lp, expr_arena, lp_arena, &path, options.with_columns))
+                    Some(self.finish_rewrite(lp, expr_arena, lp_arena, &path, with_columns))
                 } else {
                     unreachable!()
                 }
@@ -59,6 +59,8 @@ fn combine_lp_nodes(
                 keys: keys_l,
                 aggs: aggs_l,
                 apply: apply_l,
+                drop_null_keys: drop_null_keys_l,
+                skipped: skipped_l,
                 ..
             },
             Aggregate {
@@ -66,29 +68,35 @@ fn combine_lp_nodes(
                 keys: keys_r,
                 aggs: aggs_r,
                 apply: apply_r,
+                drop_null_keys: drop_null_keys_r,
+                skipped: skipped_r,
                 ..
             },
             // skip if we have custom functions
         ) if {
             apply_l.is_none()
                 && apply_r.is_none()
+                && drop_null_keys_l == drop_null_keys_r
                 // check if aggregation keys can be combined.
                 && equal_aexprs(keys_l, keys_r, expr_arena)
         }
         =>
             {
                 let keys = keys_l.clone();
+                let drop_null_keys = *drop_null_keys_l;
                 let aggs = aggs_l
                     .iter()
                     .copied()
                     .chain(aggs_r.iter().copied())
                     .collect();
+                let mut skipped = skipped_l.clone();
+                skipped.extend(skipped_r.iter().filter(|name| !skipped_l.contains(name)).cloned());
 
                 combine_lp_nodes(*child_input_l, *child_input_r, lp_arena, expr_arena)
                     .map(|input| {
                         let node = lp_arena.add(input);
                         ALogicalPlanBuilder::new(node, expr_arena, lp_arena)
-                            .groupby(keys, aggs, None)
+                            .groupby(keys, aggs, None, drop_null_keys, skipped)
                             .build()
 
                     })
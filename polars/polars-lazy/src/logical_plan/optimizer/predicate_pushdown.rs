@@ -1,5 +1,5 @@
 use crate::logical_plan::optimizer::ALogicalPlanBuilder;
-use crate::logical_plan::{optimizer, Context};
+use crate::logical_plan::{optimizer, ConcatHow, Context};
 use crate::prelude::*;
 use crate::utils::rename_aexpr_root_name;
 use crate::utils::{
@@ -282,6 +282,7 @@ impl PredicatePushDown {
                             matches!(e, AExpr::Explode(_))
                                 || matches!(e, AExpr::Shift { .. })
                                 || matches!(e, AExpr::Sort { .. })
+                                || matches!(e, AExpr::Extension { plugin, .. } if !plugin.is_elementwise())
                         },
                         &mut local_predicates,
                         &mut acc_predicates,
@@ -336,6 +337,21 @@ impl PredicatePushDown {
                 };
                 Ok(self.apply_predicate(lp, local_predicates, lp_arena, expr_arena))
             }
+            RowCount {
+                input,
+                row_count,
+                schema,
+            } => {
+                // a filter below the row count would change the row indexes, so the row count is
+                // a barrier
+                self.pushdown_and_assign(input, optimizer::init_hashmap(), lp_arena, expr_arena)?;
+                let lp = RowCount {
+                    input,
+                    row_count,
+                    schema,
+                };
+                Ok(self.finish_at_leaf(lp, acc_predicates, lp_arena, expr_arena))
+            }
             LocalProjection { expr, input, .. } => {
                 self.pushdown_and_assign(input, acc_predicates, lp_arena, expr_arena)?;
 
@@ -395,13 +411,21 @@ impl PredicatePushDown {
                 };
                 Ok(lp)
             }
-            Explode { input, columns } => {
+            Explode {
+                input,
+                columns,
+                schema,
+            } => {
                 let condition = |name: Arc<String>| columns.contains(&*name);
                 let local_predicates =
                     transfer_to_local(expr_arena, &mut acc_predicates, condition);
 
                 self.pushdown_and_assign(input, acc_predicates, lp_arena, expr_arena)?;
-                let lp = Explode { input, columns };
+                let lp = Explode {
+                    input,
+                    columns,
+                    schema,
+                };
                 Ok(self.apply_predicate(lp, local_predicates, lp_arena, expr_arena))
             }
             Distinct {
@@ -442,6 +466,8 @@ impl PredicatePushDown {
                 aggs,
                 schema,
                 apply,
+                drop_null_keys,
+                skipped,
             } => {
                 self.pushdown_and_assign(input, optimizer::init_hashmap(), lp_arena, expr_arena)?;
 
@@ -452,6 +478,8 @@ impl PredicatePushDown {
                     aggs,
                     schema,
                     apply,
+                    drop_null_keys,
+                    skipped,
                 };
                 Ok(self.finish_at_leaf(lp, acc_predicates, lp_arena, expr_arena))
             }
@@ -460,9 +488,7 @@ impl PredicatePushDown {
                 input_right,
                 left_on,
                 right_on,
-                how,
-                allow_par,
-                force_par,
+                options,
                 schema,
             } => {
                 let schema_left = lp_arena.get(input_left).schema(lp_arena);
@@ -511,7 +537,7 @@ impl PredicatePushDown {
                     // An outer join or left join may create null values.
                     // we also do it local
                     let matches = |e: &AExpr| matches!(e, AExpr::IsNotNull(_) | AExpr::IsNull(_));
-                    if (how == JoinType::Outer) | (how == JoinType::Left)
+                    if (options.how == JoinType::Outer) | (options.how == JoinType::Left)
                         && has_aexpr(predicate, expr_arena, matches)
                     {
                         local_predicates.push(predicate);
@@ -527,9 +553,7 @@ impl PredicatePushDown {
                     input_right,
                     left_on,
                     right_on,
-                    how,
-                    allow_par,
-                    force_par,
+                    options,
                     schema,
                 };
                 Ok(self.apply_predicate(lp, local_predicates, lp_arena, expr_arena))
@@ -542,7 +566,12 @@ impl PredicatePushDown {
                 let mut added_cols = PlHashSet::with_capacity(exprs.len());
                 for e in &exprs {
                     // shifts | sorts are influenced by a filter so we do all predicates before the shift | sort
-                    let matches = |e: &AExpr| matches!(e, AExpr::Shift { .. } | AExpr::Sort { .. });
+                    // the same holds for plugins that are not elementwise
+                    let matches = |e: &AExpr| match e {
+                        AExpr::Shift { .. } | AExpr::Sort { .. } => true,
+                        AExpr::Extension { plugin, .. } => !plugin.is_elementwise(),
+                        _ => false,
+                    };
                     if has_aexpr(*e, expr_arena, matches) {
                         let lp = ALogicalPlanBuilder::new(input, expr_arena, lp_arena)
                             .with_columns(exprs)
@@ -582,6 +611,42 @@ impl PredicatePushDown {
                 Ok(self.apply_predicate(lp, local_predicates, lp_arena, expr_arena))
             }
 
+            Union {
+                inputs,
+                how,
+                schema,
+            } => {
+                let mut pushdown_predicates = optimizer::init_hashmap();
+                let mut local_predicates = Vec::with_capacity(acc_predicates.len());
+
+                for (name, predicate) in acc_predicates {
+                    // the result of these expressions depends on all inputs
+                    let matches = |e: &AExpr| {
+                        matches!(e, AExpr::IsUnique(_) | AExpr::Duplicated(_) | AExpr::Agg(_))
+                    };
+                    // in a diagonal concatenation columns may be missing in some of the inputs
+                    if how == ConcatHow::Diagonal || has_aexpr(predicate, expr_arena, matches) {
+                        local_predicates.push(predicate);
+                    } else {
+                        pushdown_predicates.insert(name, predicate);
+                    }
+                }
+                for &input in &inputs {
+                    self.pushdown_and_assign(
+                        input,
+                        pushdown_predicates.clone(),
+                        lp_arena,
+                        expr_arena,
+                    )?;
+                }
+
+                let lp = Union {
+                    inputs,
+                    how,
+                    schema,
+                };
+                Ok(self.apply_predicate(lp, local_predicates, lp_arena, expr_arena))
+            }
             Udf {
                 input,
                 function,
@@ -617,13 +682,17 @@ impl PredicatePushDown {
 
                     return Ok(self.apply_predicate(lp, local_predicates, lp_arena, expr_arena));
                 }
-                Ok(Udf {
+                // the udf is a barrier: its input is optimized on its own and the predicates are
+                // applied after the udf
+                self.pushdown_and_assign(input, optimizer::init_hashmap(), lp_arena, expr_arena)?;
+                let lp = Udf {
                     input,
                     function,
                     predicate_pd,
                     projection_pd,
                     schema,
-                })
+                };
+                Ok(self.finish_at_leaf(lp, acc_predicates, lp_arena, expr_arena))
             }
             lp => {
                 let inputs = lp.get_inputs();
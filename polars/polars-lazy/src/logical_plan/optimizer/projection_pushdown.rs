@@ -1,6 +1,8 @@
-use crate::logical_plan::Context;
+use crate::logical_plan::{det_union_schema, ConcatHow, Context};
 use crate::prelude::*;
-use crate::utils::{aexpr_to_root_names, aexpr_to_root_nodes, check_down_node, has_aexpr};
+use crate::utils::{
+    aexpr_to_root_names, aexpr_to_root_nodes, aexprs_to_schema, check_down_node, has_aexpr,
+};
 use polars_core::{datatypes::PlHashSet, prelude::*};
 
 fn init_vec() -> Vec<Node> {
@@ -86,6 +88,18 @@ fn add_str_to_accumulated(
     }
 }
 
+/// Push a column with the given name down, if it isn't already projected.
+fn push_column(
+    name: Arc<String>,
+    acc_projections: &mut Vec<Node>,
+    projected_names: &mut PlHashSet<Arc<String>>,
+    expr_arena: &mut Arena<AExpr>,
+) {
+    if projected_names.insert(name.clone()) {
+        acc_projections.push(expr_arena.add(AExpr::Column(name)))
+    }
+}
+
 pub(crate) struct ProjectionPushDown {}
 
 impl ProjectionPushDown {
@@ -101,40 +115,6 @@ impl ProjectionPushDown {
         }
     }
 
-    #[allow(clippy::too_many_arguments)]
-    fn join_push_down(
-        &self,
-        schema_left: &Schema,
-        schema_right: &Schema,
-        proj: Node,
-        pushdown_left: &mut Vec<Node>,
-        pushdown_right: &mut Vec<Node>,
-        names_left: &mut PlHashSet<Arc<String>>,
-        names_right: &mut PlHashSet<Arc<String>>,
-        expr_arena: &mut Arena<AExpr>,
-    ) -> bool {
-        let mut pushed_at_least_one = false;
-        let names = aexpr_to_root_names(proj, expr_arena);
-        let root_projections = aexpr_to_root_nodes(proj, expr_arena);
-
-        for (name, root_projection) in names.into_iter().zip(root_projections) {
-            if check_down_node(root_projection, schema_left, expr_arena)
-                && names_left.insert(name.clone())
-            {
-                pushdown_left.push(proj);
-                pushed_at_least_one = true;
-            }
-            if check_down_node(root_projection, schema_right, expr_arena)
-                && names_right.insert(name)
-            {
-                pushdown_right.push(proj);
-                pushed_at_least_one = true;
-            }
-        }
-
-        pushed_at_least_one
-    }
-
     /// Helper method. This pushes down current node and assigns the result to this node.
     fn pushdown_and_assign(
         &self,
@@ -370,7 +350,7 @@ impl ProjectionPushDown {
                     reverse,
                 })
             }
-            Explode { input, columns } => {
+            Explode { input, columns, .. } => {
                 columns.iter().for_each(|name| {
                     add_str_to_accumulated(
                         name,
@@ -387,7 +367,8 @@ impl ProjectionPushDown {
                     lp_arena,
                     expr_arena,
                 )?;
-                Ok(Explode { input, columns })
+                let builder = ALogicalPlanBuilder::new(input, expr_arena, lp_arena);
+                Ok(builder.explode(columns).build())
             }
             Distinct {
                 input,
@@ -440,6 +421,46 @@ impl ProjectionPushDown {
                 )?;
                 Ok(Selection { predicate, input })
             }
+            RowCount {
+                input, row_count, ..
+            } => {
+                // the row count column is created here, the input doesn't have it
+                let has_projections = !acc_projections.is_empty();
+                acc_projections.retain(|node| {
+                    !matches!(expr_arena.get(*node), AExpr::Column(name) if **name == row_count.name)
+                });
+                projected_names.remove(&row_count.name);
+                // if only the row count is projected, a single column is needed for the number
+                // of rows. No projections would read all columns.
+                if has_projections && acc_projections.is_empty() {
+                    let first = lp_arena
+                        .get(input)
+                        .schema(lp_arena)
+                        .fields()
+                        .first()
+                        .map(|fld| fld.name().clone());
+                    if let Some(name) = first {
+                        add_str_to_accumulated(
+                            &name,
+                            &mut acc_projections,
+                            &mut projected_names,
+                            expr_arena,
+                        );
+                    }
+                }
+
+                self.pushdown_and_assign(
+                    input,
+                    acc_projections,
+                    projected_names,
+                    projections_seen,
+                    lp_arena,
+                    expr_arena,
+                )?;
+                Ok(ALogicalPlanBuilder::new(input, expr_arena, lp_arena)
+                    .with_row_count(row_count)
+                    .build())
+            }
             Melt {
                 input,
                 id_vars,
@@ -493,6 +514,8 @@ impl ProjectionPushDown {
                 aggs,
                 apply,
                 schema,
+                drop_null_keys,
+                skipped,
             } => {
                 // the custom function may need all columns so we do the projections here.
                 if let Some(f) = apply {
@@ -502,6 +525,8 @@ impl ProjectionPushDown {
                         aggs,
                         schema,
                         apply: Some(f),
+                        drop_null_keys,
+                        skipped,
                     };
                     let input = lp_arena.add(lp);
 
@@ -535,8 +560,13 @@ impl ProjectionPushDown {
                         expr_arena,
                     )?;
 
-                    let builder = ALogicalPlanBuilder::new(input, expr_arena, lp_arena)
-                        .groupby(keys, aggs, apply);
+                    let builder = ALogicalPlanBuilder::new(input, expr_arena, lp_arena).groupby(
+                        keys,
+                        aggs,
+                        apply,
+                        drop_null_keys,
+                        skipped,
+                    );
                     Ok(builder.build())
                 }
             }
@@ -545,9 +575,7 @@ impl ProjectionPushDown {
                 input_right,
                 left_on,
                 right_on,
-                how,
-                allow_par,
-                force_par,
+                options,
                 ..
             } => {
                 let mut pushdown_left = init_vec();
@@ -557,18 +585,14 @@ impl ProjectionPushDown {
                 let mut local_projection = init_vec();
 
                 // if there are no projections we don't have to do anything (all columns are projected)
-                // otherwise we build local projections to sort out proper column names due to the
-                // join operation
-                //
-                // Joins on columns with different names, for example
-                // left_on = "a", right_on = "b
-                // will remove the name "b" (it is "a" now). That columns should therefore not
-                // be added to a local projection.
+                // otherwise we only push down the columns that are needed by every join input and
+                // build a local projection to sort out proper column names due to the join operation.
                 if !acc_projections.is_empty() {
                     let schema_left = lp_arena.get(input_left).schema(lp_arena);
                     let schema_right = lp_arena.get(input_right).schema(lp_arena);
 
                     // We need the join columns so we push the projection downwards
+                    let mut left_on_names = init_set();
                     for e in &left_on {
                         add_expr_to_accumulated(
                             *e,
@@ -576,8 +600,24 @@ impl ProjectionPushDown {
                             &mut names_left,
                             expr_arena,
                         );
-                        if !local_projection.contains(e) {
-                            local_projection.push(*e)
+                        // the join columns, not the keys computed from them, are in the output
+                        for name in aexpr_to_root_names(*e, expr_arena) {
+                            if left_on_names.insert(name.clone()) {
+                                local_projection.push(expr_arena.add(AExpr::Column(name)))
+                            }
+                        }
+                        // an outer join coalesces the keys into the output columns of the left
+                        // keys
+                        if options.how == JoinType::Outer {
+                            let field = expr_arena.get(*e).to_field(
+                                schema_left,
+                                Context::Default,
+                                expr_arena,
+                            )?;
+                            let name = Arc::new(field.name().clone());
+                            if left_on_names.insert(name.clone()) {
+                                local_projection.push(expr_arena.add(AExpr::Column(name)))
+                            }
                         }
                     }
                     for e in &right_on {
@@ -590,61 +630,72 @@ impl ProjectionPushDown {
                         // we don't add right column names to local_projection as they are removed
                     }
 
-                    for proj in acc_projections {
-                        let mut add_local = true;
+                    // Map the names of the join output to the input they originate from.
+                    // The column names of the left table stay as is. A column of the right table
+                    // gets the suffix, e.g. "_right", if the left table has a column with the same
+                    // name. Thus joining two tables with both a foo column leads to
+                    // ["foo", "foo_right"] and only "foo" of the right table must be read for
+                    // "foo_right".
+                    let mut suffixed = init_set();
+                    let mut resolved = Vec::with_capacity(acc_projections.len());
+                    for proj in &acc_projections {
+                        let mut pushed_at_least_one = false;
+                        for name in aexpr_to_root_names(*proj, expr_arena) {
+                            if schema_left.field_with_name(&name).is_ok() {
+                                push_column(name, &mut pushdown_left, &mut names_left, expr_arena);
+                                pushed_at_least_one = true;
+                            } else if schema_right.field_with_name(&name).is_ok() {
+                                push_column(
+                                    name,
+                                    &mut pushdown_right,
+                                    &mut names_right,
+                                    expr_arena,
+                                );
+                                pushed_at_least_one = true;
+                            } else if let Some(downwards_name) =
+                                name.strip_suffix(options.suffix.as_str())
+                            {
+                                if schema_left.field_with_name(downwards_name).is_ok()
+                                    && schema_right.field_with_name(downwards_name).is_ok()
+                                {
+                                    push_column(
+                                        Arc::new(downwards_name.to_string()),
+                                        &mut pushdown_right,
+                                        &mut names_right,
+                                        expr_arena,
+                                    );
+                                    suffixed.insert(name);
+                                    pushed_at_least_one = true;
+                                }
+                            }
+                        }
+                        resolved.push(pushed_at_least_one);
+                    }
+
+                    // The suffix is only added if the left column is also read. Otherwise the
+                    // right column keeps its name in the join output and we alias it locally.
+                    let suffix = options.suffix.as_str();
+                    let output_name = |name: &Arc<String>| {
+                        if suffixed.contains(name) {
+                            let downwards_name = &name[..name.len() - suffix.len()];
+                            if !names_left.contains(&downwards_name.to_string()) {
+                                return Arc::new(downwards_name.to_string());
+                            }
+                        }
+                        name.clone()
+                    };
 
+                    for (proj, resolved) in acc_projections.into_iter().zip(resolved) {
                         // if it is an alias we want to project the root column name downwards
                         // but we don't want to project it a this level, otherwise we project both
-                        // the root and the alias, hence add_local = false.
+                        // the root and the alias.
                         if let AExpr::Alias(expr, name) = expr_arena.get(proj).clone() {
                             for root_name in aexpr_to_root_names(expr, expr_arena) {
-                                let node = expr_arena.add(AExpr::Column(root_name));
+                                let node = expr_arena.add(AExpr::Column(output_name(&root_name)));
                                 let proj = expr_arena.add(AExpr::Alias(node, name.clone()));
                                 local_projection.push(proj)
                             }
-                            // now we don
-                            add_local = false;
-                        }
-
-                        // Path for renamed columns due to the join. The column name of the left table
-                        // stays as is, the column of the right will have the "_right" suffix.
-                        // Thus joining two tables with both a foo column leads to ["foo", "foo_right"]
-                        if !self.join_push_down(
-                            schema_left,
-                            schema_right,
-                            proj,
-                            &mut pushdown_left,
-                            &mut pushdown_right,
-                            &mut names_left,
-                            &mut names_right,
-                            expr_arena,
-                        ) {
-                            // Column name of the projection without any alias.
-                            let root_column_name =
-                                aexpr_to_root_names(proj, expr_arena).pop().unwrap();
-
-                            // If _right suffix exists we need to push a projection down without this
-                            // suffix.
-                            if root_column_name.ends_with("_right") {
-                                // downwards name is the name without the _right i.e. "foo".
-                                let (downwards_name, _) = root_column_name
-                                    .split_at(root_column_name.len() - "_right".len());
-
-                                let downwards_name_column =
-                                    expr_arena.add(AExpr::Column(Arc::new(downwards_name.into())));
-                                // project downwards and locally immediately alias to prevent wrong projections
-                                if names_right.insert(Arc::new(downwards_name.to_string())) {
-                                    pushdown_right.push(downwards_name_column);
-                                }
-
-                                // locally we project and alias
-                                let projection = expr_arena.add(AExpr::Alias(
-                                    downwards_name_column,
-                                    Arc::new(format!("{}_right", downwards_name)),
-                                ));
-                                local_projection.push(projection);
-                            }
-                        } else if add_local {
+                        } else if resolved {
                             // always also do the projection locally, because the join columns may not be
                             // included in the projection.
                             // for instance:
@@ -654,7 +705,19 @@ impl ProjectionPushDown {
                             // JOIN (["days", "temp"]) WITH (["days", "rain"]) ON (left: days right: days)
                             //
                             // should drop the days column after the join.
-                            local_projection.push(proj);
+                            for name in aexpr_to_root_names(proj, expr_arena) {
+                                // the left join columns are already projected
+                                if left_on_names.contains(&name) {
+                                    continue;
+                                }
+                                let output_name = output_name(&name);
+                                let node = expr_arena.add(AExpr::Column(output_name.clone()));
+                                if output_name == name {
+                                    local_projection.push(node)
+                                } else {
+                                    local_projection.push(expr_arena.add(AExpr::Alias(node, name)))
+                                }
+                            }
                         }
                     }
                 }
@@ -678,11 +741,9 @@ impl ProjectionPushDown {
 
                 let builder = ALogicalPlanBuilder::new(input_left, expr_arena, lp_arena).join(
                     input_right,
-                    how,
                     left_on,
                     right_on,
-                    allow_par,
-                    force_par,
+                    options,
                 );
                 Ok(self.finish_node(local_projection, builder))
             }
@@ -719,6 +780,98 @@ impl ProjectionPushDown {
                     .build();
                 Ok(lp)
             }
+            Union { inputs, how, .. } => {
+                // the key of a sorted merge is needed, even if it isn't projected
+                #[cfg(feature = "merge_sorted")]
+                let local_projections = acc_projections.clone();
+                #[cfg(feature = "merge_sorted")]
+                if let ConcatHow::MergeSorted(key) = &how {
+                    add_str_to_accumulated(
+                        key,
+                        &mut acc_projections,
+                        &mut projected_names,
+                        expr_arena,
+                    );
+                }
+
+                let mut new_inputs = Vec::with_capacity(inputs.len());
+                for input in inputs {
+                    // in a diagonal concatenation an input may miss some of the projected columns
+                    let acc_projections = match how {
+                        ConcatHow::Diagonal => {
+                            let input_schema = lp_arena.get(input).schema(lp_arena);
+                            acc_projections
+                                .iter()
+                                .copied()
+                                .filter(|proj| check_down_node(*proj, input_schema, expr_arena))
+                                .collect()
+                        }
+                        _ => acc_projections.clone(),
+                    };
+                    let mut names = init_set();
+                    for proj in &acc_projections {
+                        for name in aexpr_to_root_names(*proj, expr_arena) {
+                            names.insert(name);
+                        }
+                    }
+                    self.pushdown_and_assign(
+                        input,
+                        acc_projections.clone(),
+                        names,
+                        projections_seen,
+                        lp_arena,
+                        expr_arena,
+                    )?;
+
+                    // make sure that all inputs have the same column order
+                    let input = if acc_projections.is_empty() {
+                        input
+                    } else {
+                        let input_schema = lp_arena.get(input).schema(lp_arena);
+                        let schema = aexprs_to_schema(
+                            &acc_projections,
+                            input_schema,
+                            Context::Default,
+                            expr_arena,
+                        );
+                        lp_arena.add(Projection {
+                            expr: acc_projections,
+                            input,
+                            schema: Arc::new(schema),
+                        })
+                    };
+                    new_inputs.push(input);
+                }
+                let schema = det_union_schema(
+                    new_inputs
+                        .iter()
+                        .map(|node| lp_arena.get(*node).schema(lp_arena)),
+                    &how,
+                )?;
+                let lp = Union {
+                    inputs: new_inputs,
+                    how: how.clone(),
+                    schema,
+                };
+
+                // inputs that don't have any of the projected columns aren't projected, so the
+                // diagonal concatenation may have more columns than requested
+                match how {
+                    ConcatHow::Diagonal => {
+                        let node = lp_arena.add(lp);
+                        let builder = ALogicalPlanBuilder::new(node, expr_arena, lp_arena);
+                        Ok(self.finish_node(acc_projections, builder))
+                    }
+                    ConcatHow::Vertical => Ok(lp),
+                    // the key may have been added to the projections
+                    #[cfg(feature = "merge_sorted")]
+                    ConcatHow::MergeSorted(_) => {
+                        let node = lp_arena.add(lp);
+                        let builder = ALogicalPlanBuilder::new(node, expr_arena, lp_arena);
+                        Ok(self.finish_node(local_projections, builder))
+                    }
+                }
+            }
             Udf {
                 input,
                 function,
@@ -726,6 +879,13 @@ impl ProjectionPushDown {
                 projection_pd,
                 schema,
             } => {
+                let lp = Udf {
+                    input,
+                    function,
+                    predicate_pd,
+                    projection_pd,
+                    schema,
+                };
                 if projection_pd {
                     self.pushdown_and_assign(
                         input,
@@ -735,14 +895,22 @@ impl ProjectionPushDown {
                         lp_arena,
                         expr_arena,
                     )?;
+                    Ok(lp)
+                } else {
+                    // the udf is a barrier: its input is optimized on its own and the
+                    // projections are done after the udf
+                    self.pushdown_and_assign(
+                        input,
+                        vec![],
+                        init_set(),
+                        projections_seen,
+                        lp_arena,
+                        expr_arena,
+                    )?;
+                    let node = lp_arena.add(lp);
+                    let builder = ALogicalPlanBuilder::new(node, expr_arena, lp_arena);
+                    Ok(self.finish_node(acc_projections, builder))
                 }
-                Ok(Udf {
-                    input,
-                    function,
-                    predicate_pd,
-                    projection_pd,
-                    schema,
-                })
             }
             lp @ Slice { .. } | lp @ Cache { .. } => {
                 let inputs = lp.get_inputs();
@@ -1,10 +1,15 @@
-use polars_core::utils::Arena;
+use polars_core::prelude::*;
+use polars_core::utils::{get_supertype, Arena};
 
 use crate::logical_plan::optimizer::stack_opt::OptimizationRule;
 use crate::logical_plan::*;
+use crate::utils::is_scan;
+use std::convert::TryFrom;
 
+// Integer operations are checked, an overflow or a division by zero is not folded but left to
+// the runtime. Floating point operations are folded with the same IEEE semantics as at runtime.
 macro_rules! eval_binary_same_type {
-    ($lhs:expr, $operand: tt, $rhs:expr) => {{
+    ($lhs:expr, $operand: tt, $checked: ident, $rhs:expr) => {{
     if let (AExpr::Literal(lit_left), AExpr::Literal(lit_right)) = ($lhs, $rhs) {
         return match (lit_left, lit_right) {
             (LiteralValue::Float32(x), LiteralValue::Float32(y)) => {
@@ -15,32 +20,32 @@ macro_rules! eval_binary_same_type {
             }
             #[cfg(feature = "dtype-i8")]
             (LiteralValue::Int8(x), LiteralValue::Int8(y)) => {
-                Some(AExpr::Literal(LiteralValue::Int8(x $operand y)))
+                x.$checked(*y).map(|v| AExpr::Literal(LiteralValue::Int8(v)))
             }
             #[cfg(feature = "dtype-i16")]
             (LiteralValue::Int16(x), LiteralValue::Int16(y)) => {
-                Some(AExpr::Literal(LiteralValue::Int16(x $operand y)))
+                x.$checked(*y).map(|v| AExpr::Literal(LiteralValue::Int16(v)))
             }
             (LiteralValue::Int32(x), LiteralValue::Int32(y)) => {
-                Some(AExpr::Literal(LiteralValue::Int32(x $operand y)))
+                x.$checked(*y).map(|v| AExpr::Literal(LiteralValue::Int32(v)))
             }
             (LiteralValue::Int64(x), LiteralValue::Int64(y)) => {
-                Some(AExpr::Literal(LiteralValue::Int64(x $operand y)))
+                x.$checked(*y).map(|v| AExpr::Literal(LiteralValue::Int64(v)))
             }
             #[cfg(feature = "dtype-u8")]
             (LiteralValue::UInt8(x), LiteralValue::UInt8(y)) => {
-                Some(AExpr::Literal(LiteralValue::UInt8(x $operand y)))
+                x.$checked(*y).map(|v| AExpr::Literal(LiteralValue::UInt8(v)))
             }
             #[cfg(feature = "dtype-u16")]
             (LiteralValue::UInt16(x), LiteralValue::UInt16(y)) => {
-                Some(AExpr::Literal(LiteralValue::UInt16(x $operand y)))
+                x.$checked(*y).map(|v| AExpr::Literal(LiteralValue::UInt16(v)))
             }
             (LiteralValue::UInt32(x), LiteralValue::UInt32(y)) => {
-                Some(AExpr::Literal(LiteralValue::UInt32(x $operand y)))
+                x.$checked(*y).map(|v| AExpr::Literal(LiteralValue::UInt32(v)))
             }
             #[cfg(feature = "dtype-u64")]
             (LiteralValue::UInt64(x), LiteralValue::UInt64(y)) => {
-                Some(AExpr::Literal(LiteralValue::UInt64(x $operand y)))
+                x.$checked(*y).map(|v| AExpr::Literal(LiteralValue::UInt64(v)))
             }
             _ => None,
         };
@@ -100,6 +105,104 @@ macro_rules! eval_binary_bool_type {
     }}
 }
 
+/// The schema of the input of the expressions of `lp_node`. A scan has no input, its expressions
+/// work on the schema of the scan itself. The keys of a join work on different inputs, so there
+/// is no single input schema.
+fn input_schema(lp_arena: &Arena<ALogicalPlan>, lp_node: Node) -> Option<&Schema> {
+    let plan = lp_arena.get(lp_node);
+    let mut inputs = [None, None];
+    if is_scan(plan) {
+        inputs[0] = Some(lp_node);
+    } else if let ALogicalPlan::Join { .. } = plan {
+        return None;
+    } else {
+        plan.copy_inputs(&mut inputs);
+    }
+    inputs[0].map(|input| lp_arena.get(input).schema(lp_arena))
+}
+
+fn get_type(
+    expr_arena: &Arena<AExpr>,
+    node: Node,
+    lp_arena: &Arena<ALogicalPlan>,
+    lp_node: Node,
+) -> Option<DataType> {
+    get_field(expr_arena, node, lp_arena, lp_node).map(|fld| fld.data_type().clone())
+}
+
+fn get_field(
+    expr_arena: &Arena<AExpr>,
+    node: Node,
+    lp_arena: &Arena<ALogicalPlan>,
+    lp_node: Node,
+) -> Option<Field> {
+    let schema = input_schema(lp_arena, lp_node)?;
+    expr_arena
+        .get(node)
+        .to_field(schema, Context::Default, expr_arena)
+        .ok()
+}
+
+/// Replace `expr_node` by `keep`. If the output names differ, `keep` is aliased to the output name
+/// of `expr_node`, e.g. `lit(1) * col("a")` is named "literal".
+fn replace_keep_name(
+    expr_arena: &Arena<AExpr>,
+    expr_node: Node,
+    keep: Node,
+    lp_arena: &Arena<ALogicalPlan>,
+    lp_node: Node,
+) -> Option<AExpr> {
+    let name = get_field(expr_arena, expr_node, lp_arena, lp_node)?
+        .name()
+        .clone();
+    let keep_name = get_field(expr_arena, keep, lp_arena, lp_node)?
+        .name()
+        .clone();
+    if name == keep_name {
+        Some(expr_arena.get(keep).clone())
+    } else {
+        Some(AExpr::Alias(keep, Arc::new(name)))
+    }
+}
+
+fn is_bool_lit(expr_arena: &Arena<AExpr>, node: Node, value: bool) -> bool {
+    matches!(expr_arena.get(node), AExpr::Literal(LiteralValue::Boolean(b)) if *b == value)
+}
+
+/// Replace `expr_node` by the boolean `value` with the length and the output name of `expr_node`,
+/// of which `x` is the non literal input. A filter broadcasts a literal mask, so in a filter the
+/// literal is enough.
+fn replace_by_bool_lit(
+    expr_arena: &mut Arena<AExpr>,
+    expr_node: Node,
+    x: Node,
+    value: bool,
+    lp_arena: &Arena<ALogicalPlan>,
+    lp_node: Node,
+) -> Option<AExpr> {
+    let lit = AExpr::Literal(LiteralValue::Boolean(value));
+    if matches!(expr_arena.get(x), AExpr::Literal(_))
+        || matches!(lp_arena.get(lp_node), ALogicalPlan::Selection { .. })
+    {
+        return Some(lit);
+    }
+    let name = get_field(expr_arena, expr_node, lp_arena, lp_node)?
+        .name()
+        .clone();
+    // `x` is evaluated for its length only
+    let lit = expr_arena.add(lit);
+    let predicate = expr_arena.add(AExpr::IsNull(x));
+    let broadcast = expr_arena.add(AExpr::Ternary {
+        predicate,
+        truthy: lit,
+        falsy: lit,
+    });
+    Some(AExpr::Alias(broadcast, Arc::new(name)))
+}
+
+/// The boolean rules follow the Kleene logic of the `&` and `|` kernels, where `null & false` is
+/// `false` and `null | true` is `true`. The simplified expression keeps the output name and the
+/// length of the original expression.
 pub(crate) struct SimplifyBooleanRule {}
 
 impl OptimizationRule for SimplifyBooleanRule {
@@ -107,125 +210,78 @@ impl OptimizationRule for SimplifyBooleanRule {
         &self,
         expr_arena: &mut Arena<AExpr>,
         expr_node: Node,
-        _: &Arena<ALogicalPlan>,
-        _: Node,
-    ) -> Option<AExpr> {
-        let expr = expr_arena.get(expr_node);
-        match expr {
+        lp_arena: &Arena<ALogicalPlan>,
+        lp_node: Node,
+    ) -> Result<Option<AExpr>> {
+        let expr = expr_arena.get(expr_node).clone();
+        let out = match expr {
             // true AND x => x
             AExpr::BinaryExpr {
                 left,
                 op: Operator::And,
                 right,
-            } if matches!(
-                expr_arena.get(*left),
-                AExpr::Literal(LiteralValue::Boolean(true))
-            ) =>
-            {
-                Some(expr_arena.get(*right).clone())
+            } if is_bool_lit(expr_arena, left, true) => {
+                replace_keep_name(expr_arena, expr_node, right, lp_arena, lp_node)
             }
             // x AND true => x
             AExpr::BinaryExpr {
                 left,
                 op: Operator::And,
                 right,
-            } if matches!(
-                expr_arena.get(*right),
-                AExpr::Literal(LiteralValue::Boolean(true))
-            ) =>
-            {
-                Some(expr_arena.get(*left).clone())
+            } if is_bool_lit(expr_arena, right, true) => {
+                replace_keep_name(expr_arena, expr_node, left, lp_arena, lp_node)
             }
-            // x AND false -> false
+            // false AND x => false
             AExpr::BinaryExpr {
+                left,
                 op: Operator::And,
                 right,
-                ..
-            } if matches!(
-                expr_arena.get(*right),
-                AExpr::Literal(LiteralValue::Boolean(false))
-            ) =>
-            {
-                Some(AExpr::Literal(LiteralValue::Boolean(false)))
+            } if is_bool_lit(expr_arena, left, false) => {
+                replace_by_bool_lit(expr_arena, expr_node, right, false, lp_arena, lp_node)
             }
-            // false AND x -> false
+            // x AND false => false
             AExpr::BinaryExpr {
                 left,
                 op: Operator::And,
-                ..
-            } if matches!(
-                expr_arena.get(*left),
-                AExpr::Literal(LiteralValue::Boolean(false))
-            ) =>
-            {
-                Some(AExpr::Literal(LiteralValue::Boolean(false)))
-            }
-            // false or x => x
-            AExpr::BinaryExpr {
-                left,
-                op: Operator::Or,
                 right,
-            } if matches!(
-                expr_arena.get(*left),
-                AExpr::Literal(LiteralValue::Boolean(false))
-            ) =>
-            {
-                Some(expr_arena.get(*right).clone())
+            } if is_bool_lit(expr_arena, right, false) => {
+                replace_by_bool_lit(expr_arena, expr_node, left, false, lp_arena, lp_node)
             }
-            // x or false => x
+            // false OR x => x
             AExpr::BinaryExpr {
+                left,
                 op: Operator::Or,
                 right,
-                ..
-            } if matches!(
-                expr_arena.get(*right),
-                AExpr::Literal(LiteralValue::Boolean(false))
-            ) =>
-            {
-                Some(expr_arena.get(*right).clone())
+            } if is_bool_lit(expr_arena, left, false) => {
+                replace_keep_name(expr_arena, expr_node, right, lp_arena, lp_node)
             }
-
-            // false OR x => x
+            // x OR false => x
             AExpr::BinaryExpr {
                 left,
                 op: Operator::Or,
                 right,
-            } if matches!(
-                expr_arena.get(*left),
-                AExpr::Literal(LiteralValue::Boolean(false))
-            ) =>
-            {
-                Some(expr_arena.get(*right).clone())
+            } if is_bool_lit(expr_arena, right, false) => {
+                replace_keep_name(expr_arena, expr_node, left, lp_arena, lp_node)
             }
-
             // true OR x => true
             AExpr::BinaryExpr {
+                left,
                 op: Operator::Or,
                 right,
-                ..
-            } if matches!(
-                expr_arena.get(*right),
-                AExpr::Literal(LiteralValue::Boolean(true))
-            ) =>
-            {
-                Some(AExpr::Literal(LiteralValue::Boolean(false)))
+            } if is_bool_lit(expr_arena, left, true) => {
+                replace_by_bool_lit(expr_arena, expr_node, right, true, lp_arena, lp_node)
             }
-
             // x OR true => true
             AExpr::BinaryExpr {
-                op: Operator::Or,
                 left,
-                ..
-            } if matches!(
-                expr_arena.get(*left),
-                AExpr::Literal(LiteralValue::Boolean(true))
-            ) =>
-            {
-                Some(AExpr::Literal(LiteralValue::Boolean(false)))
+                op: Operator::Or,
+                right,
+            } if is_bool_lit(expr_arena, right, true) => {
+                replace_by_bool_lit(expr_arena, expr_node, left, true, lp_arena, lp_node)
             }
 
             AExpr::Not(x) => {
-                let y = expr_arena.get(*x);
+                let y = expr_arena.get(x);
 
                 match y {
                     // not(not x) => x
@@ -238,7 +294,8 @@ impl OptimizationRule for SimplifyBooleanRule {
                 }
             }
             _ => None,
-        }
+        };
+        Ok(out)
     }
 }
 
@@ -266,47 +323,234 @@ fn eval_or(left: &AExpr, right: &AExpr) -> Option<AExpr> {
     None
 }
 
+/// The value of a numeric literal in a type that can hold all numeric types.
+enum NumericValue {
+    Int(i128),
+    Float(f64),
+}
+
+fn numeric_value(lv: &LiteralValue) -> Option<NumericValue> {
+    use NumericValue::*;
+    let v = match lv {
+        #[cfg(feature = "dtype-u8")]
+        LiteralValue::UInt8(v) => Int(*v as i128),
+        #[cfg(feature = "dtype-u16")]
+        LiteralValue::UInt16(v) => Int(*v as i128),
+        LiteralValue::UInt32(v) => Int(*v as i128),
+        #[cfg(feature = "dtype-u64")]
+        LiteralValue::UInt64(v) => Int(*v as i128),
+        #[cfg(feature = "dtype-i8")]
+        LiteralValue::Int8(v) => Int(*v as i128),
+        #[cfg(feature = "dtype-i16")]
+        LiteralValue::Int16(v) => Int(*v as i128),
+        LiteralValue::Int32(v) => Int(*v as i128),
+        LiteralValue::Int64(v) => Int(*v as i128),
+        LiteralValue::Float32(v) => Float(*v as f64),
+        LiteralValue::Float64(v) => Float(*v),
+        _ => return None,
+    };
+    Some(v)
+}
+
+/// Cast a numeric literal to the numeric `dtype`. Integers are only cast if the value fits in
+/// `dtype` and floats are not cast to integers, those casts are left to the runtime.
+fn cast_literal(lv: &LiteralValue, dtype: &DataType) -> Option<LiteralValue> {
+    use NumericValue::*;
+    let v = numeric_value(lv)?;
+    let out = match (v, dtype) {
+        (Int(v), DataType::Float32) => LiteralValue::Float32(v as f32),
+        (Float(v), DataType::Float32) => LiteralValue::Float32(v as f32),
+        (Int(v), DataType::Float64) => LiteralValue::Float64(v as f64),
+        (Float(v), DataType::Float64) => LiteralValue::Float64(v),
+        #[cfg(feature = "dtype-u8")]
+        (Int(v), DataType::UInt8) => LiteralValue::UInt8(u8::try_from(v).ok()?),
+        #[cfg(feature = "dtype-u16")]
+        (Int(v), DataType::UInt16) => LiteralValue::UInt16(u16::try_from(v).ok()?),
+        (Int(v), DataType::UInt32) => LiteralValue::UInt32(u32::try_from(v).ok()?),
+        #[cfg(feature = "dtype-u64")]
+        (Int(v), DataType::UInt64) => LiteralValue::UInt64(u64::try_from(v).ok()?),
+        #[cfg(feature = "dtype-i8")]
+        (Int(v), DataType::Int8) => LiteralValue::Int8(i8::try_from(v).ok()?),
+        #[cfg(feature = "dtype-i16")]
+        (Int(v), DataType::Int16) => LiteralValue::Int16(i16::try_from(v).ok()?),
+        (Int(v), DataType::Int32) => LiteralValue::Int32(i32::try_from(v).ok()?),
+        (Int(v), DataType::Int64) => LiteralValue::Int64(i64::try_from(v).ok()?),
+        _ => return None,
+    };
+    Some(out)
+}
+
+/// Cast two numeric literals of different types to their supertype, such that they can be
+/// folded like the runtime would compute them.
+fn promote_literals(left: &AExpr, right: &AExpr) -> Option<(AExpr, AExpr)> {
+    if let (AExpr::Literal(lit_left), AExpr::Literal(lit_right)) = (left, right) {
+        let (dt_left, dt_right) = (lit_left.get_datatype(), lit_right.get_datatype());
+        if dt_left == dt_right || dt_left == DataType::Boolean || dt_right == DataType::Boolean {
+            return None;
+        }
+        let st = get_supertype(&dt_left, &dt_right).ok()?;
+        return Some((
+            AExpr::Literal(cast_literal(lit_left, &st)?),
+            AExpr::Literal(cast_literal(lit_right, &st)?),
+        ));
+    }
+    None
+}
+
+/// Check if `node` is a numeric literal equal to `value`.
+#[allow(clippy::float_cmp)]
+fn is_numeric_lit(expr_arena: &Arena<AExpr>, node: Node, value: i128) -> bool {
+    match expr_arena.get(node) {
+        AExpr::Literal(lv) => match numeric_value(lv) {
+            Some(NumericValue::Int(v)) => v == value,
+            Some(NumericValue::Float(v)) => v == value as f64,
+            None => false,
+        },
+        _ => false,
+    }
+}
+
 pub struct SimplifyExprRule {}
 
-impl OptimizationRule for SimplifyExprRule {
+impl SimplifyExprRule {
+    /// Remove the algebraic no-ops `x * 1`, `x / 1`, `x - 0` and `x + 0` where `keep` is the node
+    /// of `x`. This is only done if the output type is the type of `x`, e.g. `int * 1.0` is a
+    /// float.
+    ///
+    /// `x * 0` is not simplified as it is null if `x` is null and NaN if `x` is NaN or infinite.
+    /// `x + 0` is only simplified for integers, for floats `-0.0 + 0.0` is `0.0`.
+    fn remove_identity(
+        &self,
+        expr_arena: &Arena<AExpr>,
+        expr_node: Node,
+        keep: Node,
+        lp_arena: &Arena<ALogicalPlan>,
+        lp_node: Node,
+        float_allowed: bool,
+    ) -> Option<AExpr> {
+        use DataType::*;
+        let dtype = get_type(expr_arena, keep, lp_arena, lp_node)?;
+        let out_type = get_type(expr_arena, expr_node, lp_arena, lp_node)?;
+        let allowed = match dtype {
+            Float32 | Float64 => float_allowed,
+            UInt8 | UInt16 | UInt32 | UInt64 | Int8 | Int16 | Int32 | Int64 => true,
+            _ => false,
+        };
+        if dtype == out_type && allowed {
+            replace_keep_name(expr_arena, expr_node, keep, lp_arena, lp_node)
+        } else {
+            None
+        }
+    }
+
     #[allow(clippy::float_cmp)]
-    fn optimize_expr(
+    fn simplify(
         &self,
         expr_arena: &mut Arena<AExpr>,
         expr_node: Node,
-        _: &Arena<ALogicalPlan>,
-        _: Node,
+        lp_arena: &Arena<ALogicalPlan>,
+        lp_node: Node,
     ) -> Option<AExpr> {
         let expr = expr_arena.get(expr_node);
         match expr {
             // Null propagation
+            // Only if both sides are Null, otherwise the type of the other side would be lost.
             AExpr::BinaryExpr { left, right, .. }
                 if matches!(expr_arena.get(*left), AExpr::Literal(LiteralValue::Null))
-                    || matches!(expr_arena.get(*right), AExpr::Literal(LiteralValue::Null)) =>
+                    && matches!(expr_arena.get(*right), AExpr::Literal(LiteralValue::Null)) =>
             {
                 Some(AExpr::Literal(LiteralValue::Null))
             }
 
-            // lit(left) + lit(right) => lit(left = right)
+            // lit(left) + lit(right) => lit(left + right)
             AExpr::BinaryExpr { left, op, right } => {
-                let left = expr_arena.get(*left);
-                let right = expr_arena.get(*right);
-
-                match op {
-                    Operator::Plus => eval_binary_same_type!(left, +, right),
-                    Operator::Minus => eval_binary_same_type!(left, -, right),
-                    Operator::Multiply => eval_binary_same_type!(left, *, right),
-                    Operator::Divide => eval_binary_same_type!(left, /, right),
-                    Operator::Modulus => eval_binary_same_type!(left, %, right),
+                let (left_node, op, right_node) = (*left, *op, *right);
+                let left = expr_arena.get(left_node);
+                let right = expr_arena.get(right_node);
+
+                if let Some((left, right)) = promote_literals(left, right) {
+                    let left = expr_arena.add(left);
+                    let right = expr_arena.add(right);
+                    return Some(AExpr::BinaryExpr { left, op, right });
+                }
+
+                let folded = match op {
+                    Operator::Plus => eval_binary_same_type!(left, +, checked_add, right),
+                    Operator::Minus => eval_binary_same_type!(left, -, checked_sub, right),
+                    Operator::Multiply => eval_binary_same_type!(left, *, checked_mul, right),
+                    Operator::Divide => eval_binary_same_type!(left, /, checked_div, right),
+                    Operator::Modulus => eval_binary_same_type!(left, %, checked_rem, right),
                     Operator::Lt => eval_binary_bool_type!(left, <, right),
                     Operator::Gt => eval_binary_bool_type!(left, >, right),
                     Operator::Eq => eval_binary_bool_type!(left, ==, right),
                     Operator::NotEq => eval_binary_bool_type!(left, !=, right),
                     Operator::GtEq => eval_binary_bool_type!(left, >=, right),
-                    Operator::LtEq => eval_binary_bool_type!(left, >=, right),
+                    Operator::LtEq => eval_binary_bool_type!(left, <=, right),
                     Operator::And => eval_and(left, right),
                     Operator::Or => eval_or(left, right),
+                };
+                if folded.is_some() {
+                    return folded;
                 }
+
+                let arena: &Arena<AExpr> = expr_arena;
+                let is_zero = |node| is_numeric_lit(arena, node, 0);
+                let is_one = |node| is_numeric_lit(arena, node, 1);
+                let (keep, float_allowed) = match op {
+                    // x * 1 => x
+                    Operator::Multiply if is_one(right_node) => (left_node, true),
+                    // 1 * x => x
+                    Operator::Multiply if is_one(left_node) => (right_node, true),
+                    // x / 1 => x
+                    Operator::Divide if is_one(right_node) => (left_node, true),
+                    // x - 0 => x
+                    Operator::Minus if is_zero(right_node) => (left_node, true),
+                    // x + 0 => x
+                    Operator::Plus if is_zero(right_node) => (left_node, false),
+                    // 0 + x => x
+                    Operator::Plus if is_zero(left_node) => (right_node, false),
+                    _ => return None,
+                };
+                self.remove_identity(
+                    expr_arena,
+                    expr_node,
+                    keep,
+                    lp_arena,
+                    lp_node,
+                    float_allowed,
+                )
+            }
+            // cast(lit) => lit
+            AExpr::Cast { expr, data_type } => match expr_arena.get(*expr) {
+                AExpr::Literal(lv) => cast_literal(lv, data_type).map(AExpr::Literal),
+                _ => None,
+            },
+            // when(true).then(a).otherwise(b) => a
+            // when(false).then(a).otherwise(b) => b
+            AExpr::Ternary {
+                predicate,
+                truthy,
+                falsy,
+            } => {
+                let (predicate, truthy, falsy) = (*predicate, *truthy, *falsy);
+                let (keep, other) = match expr_arena.get(predicate) {
+                    AExpr::Literal(LiteralValue::Boolean(true)) => (truthy, falsy),
+                    AExpr::Literal(LiteralValue::Boolean(false)) => (falsy, truthy),
+                    _ => return None,
+                };
+                // the output type is the supertype of both branches
+                let dtype = get_type(expr_arena, keep, lp_arena, lp_node)?;
+                let other_type = get_type(expr_arena, other, lp_arena, lp_node)?;
+                let keep = if dtype == other_type || other_type == DataType::Null {
+                    keep
+                } else {
+                    let st = get_supertype(&dtype, &other_type).ok()?;
+                    expr_arena.add(AExpr::Cast {
+                        expr: keep,
+                        data_type: st,
+                    })
+                };
+                replace_keep_name(expr_arena, expr_node, keep, lp_arena, lp_node)
             }
             AExpr::Reverse(expr) => {
                 let input = expr_arena.get(*expr);
@@ -317,8 +561,8 @@ impl OptimizationRule for SimplifyExprRule {
                     }),
                     AExpr::SortBy { expr, by, reverse } => Some(AExpr::SortBy {
                         expr: *expr,
-                        by: *by,
-                        reverse: !*reverse,
+                        by: by.clone(),
+                        reverse: reverse.iter().map(|r| !*r).collect(),
                     }),
                     // TODO: add support for cum_sum and other operation that allow reversing.
                     _ => None,
@@ -330,6 +574,18 @@ impl OptimizationRule for SimplifyExprRule {
     }
 }
 
+impl OptimizationRule for SimplifyExprRule {
+    fn optimize_expr(
+        &self,
+        expr_arena: &mut Arena<AExpr>,
+        expr_node: Node,
+        lp_arena: &Arena<ALogicalPlan>,
+        lp_node: Node,
+    ) -> Result<Option<AExpr>> {
+        Ok(self.simplify(expr_arena, expr_node, lp_arena, lp_node))
+    }
+}
+
 #[test]
 #[cfg(feature = "dtype-i8")]
 fn test_expr_to_aexp() {
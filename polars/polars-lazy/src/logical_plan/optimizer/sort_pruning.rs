@@ -0,0 +1,192 @@
+use crate::logical_plan::Context;
+use crate::prelude::stack_opt::OptimizationRule;
+use crate::prelude::*;
+use polars_core::prelude::*;
+
+/// Optimization rule that removes a sort of which the input is already sorted by the same
+/// columns, and that sorts before a projection if the projection widens the frame.
+///
+/// The input is known to be sorted if it is sorted by a previous sort, or if the scanned
+/// `DataFrame` has its key column flagged as sorted.
+///
+/// The rule only applies to sorts by plain columns, and only passes nodes that keep the values
+/// and the order of these columns.
+pub struct SortPrune {}
+
+/// The names of the sort keys, if all of them are columns.
+fn key_names(by_column: &[Node], expr_arena: &Arena<AExpr>) -> Option<Vec<Arc<String>>> {
+    by_column
+        .iter()
+        .map(|node| match expr_arena.get(*node) {
+            AExpr::Column(name) => Some(name.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The sort direction of the key at `idx`. A single direction applies to all keys.
+fn direction(reverse: &[bool], idx: usize) -> bool {
+    reverse
+        .get(idx)
+        .or_else(|| reverse.last())
+        .copied()
+        .unwrap_or(false)
+}
+
+/// Check if the expression maps every row to a single output row, so that it doesn't matter if
+/// the input is sorted before or after the expression.
+fn is_elementwise(node: Node, expr_arena: &Arena<AExpr>) -> bool {
+    use AExpr::*;
+    expr_arena.iter(node).all(|(_, e)| match e {
+        Column(_)
+        | Alias(_, _)
+        | Cast { .. }
+        | BinaryExpr { .. }
+        | Not(_)
+        | IsNull(_)
+        | IsNotNull(_)
+        | Ternary { .. } => true,
+        Literal(lv) => !matches!(lv, LiteralValue::Series(_) | LiteralValue::Range { .. }),
+        _ => false,
+    })
+}
+
+/// If the node outputs the `keys` columns of its input with the same values in the same order,
+/// return the input of the node.
+fn passes_keys(
+    lp: &ALogicalPlan,
+    keys: &[Arc<String>],
+    lp_arena: &Arena<ALogicalPlan>,
+    expr_arena: &Arena<AExpr>,
+) -> Option<Node> {
+    use ALogicalPlan::*;
+    match lp {
+        // a filter keeps the order of the rows
+        Selection { input, .. } => Some(*input),
+        Projection { input, expr, .. } | LocalProjection { input, expr, .. } => {
+            let passes = keys.iter().all(|key| {
+                expr.iter()
+                    .any(|e| matches!(expr_arena.get(*e), AExpr::Column(name) if name == key))
+            });
+            if passes {
+                Some(*input)
+            } else {
+                None
+            }
+        }
+        HStack { input, exprs, .. } => {
+            let input_schema = lp_arena.get(*input).schema(lp_arena);
+            let in_input = keys
+                .iter()
+                .all(|key| input_schema.field_with_name(key).is_ok());
+            let overwrites = exprs.iter().any(|e| {
+                match expr_arena
+                    .get(*e)
+                    .to_field(input_schema, Context::Default, expr_arena)
+                {
+                    Ok(fld) => keys.iter().any(|key| fld.name() == key.as_str()),
+                    Err(_) => true,
+                }
+            });
+            if in_input && !overwrites {
+                Some(*input)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+impl OptimizationRule for SortPrune {
+    fn optimize_plan(
+        &mut self,
+        lp_arena: &mut Arena<ALogicalPlan>,
+        expr_arena: &mut Arena<AExpr>,
+        node: Node,
+    ) -> Option<ALogicalPlan> {
+        use ALogicalPlan::*;
+        let (input, by_column, reverse) = match lp_arena.get(node) {
+            Sort {
+                input,
+                by_column,
+                reverse,
+            } => (*input, by_column, reverse),
+            _ => return None,
+        };
+        let keys = key_names(by_column, expr_arena)?;
+
+        // 1. the input is already sorted if a sort by a prefix of the same keys, or a frame of
+        // which the key is flagged as sorted, is found before any node that changes the order or
+        // the values of the keys
+        let mut current = input;
+        loop {
+            match lp_arena.get(current) {
+                Sort {
+                    by_column: inner_by,
+                    reverse: inner_reverse,
+                    ..
+                } => {
+                    let sorted = key_names(inner_by, expr_arena).map_or(false, |inner_keys| {
+                        inner_keys.len() >= keys.len()
+                            && keys.iter().enumerate().all(|(i, key)| {
+                                inner_keys[i] == *key
+                                    && direction(inner_reverse, i) == direction(reverse, i)
+                            })
+                    });
+                    if sorted {
+                        return Some(lp_arena.get(input).clone());
+                    }
+                    break;
+                }
+                // the flag only says something about a single column
+                DataFrameScan { df, .. } => {
+                    let sorted = keys.len() == 1
+                        && df
+                            .column(&keys[0])
+                            .map_or(false, |s| s.is_sorted_flag(direction(reverse, 0)));
+                    if sorted {
+                        return Some(lp_arena.get(input).clone());
+                    }
+                    break;
+                }
+                lp => match passes_keys(lp, &keys, lp_arena, expr_arena) {
+                    Some(node) => current = node,
+                    None => break,
+                },
+            }
+        }
+
+        // 2. sort the input of a projection that only computes columns row by row and outputs
+        // more columns than its input, so that less columns are sorted
+        let projection = lp_arena.get(input);
+        let (proj_input, exprs) = match projection {
+            Projection { input, expr, .. } | LocalProjection { input, expr, .. } => (*input, expr),
+            HStack { input, exprs, .. } => (*input, exprs),
+            _ => return None,
+        };
+        let narrower =
+            lp_arena.get(proj_input).schema(lp_arena).len() < projection.schema(lp_arena).len();
+        if !narrower
+            || passes_keys(projection, &keys, lp_arena, expr_arena).is_none()
+            || !exprs.iter().all(|e| is_elementwise(*e, expr_arena))
+        {
+            return None;
+        }
+
+        let mut projection = projection.clone();
+        let sort = Sort {
+            input: proj_input,
+            by_column: by_column.clone(),
+            reverse: reverse.clone(),
+        };
+        let sort = lp_arena.add(sort);
+        match &mut projection {
+            Projection { input, .. } | LocalProjection { input, .. } | HStack { input, .. } => {
+                *input = sort
+            }
+            _ => unreachable!(),
+        }
+        Some(projection)
+    }
+}
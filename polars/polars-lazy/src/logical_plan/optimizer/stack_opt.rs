@@ -1,6 +1,7 @@
 use crate::logical_plan::aexpr::AExpr;
 use crate::logical_plan::alp::ALogicalPlan;
 use crate::prelude::{Arena, Node};
+use polars_core::prelude::Result;
 
 /// Optimizer that uses a stack and memory arenas in favor of recursion
 pub struct StackOptimizer {}
@@ -12,7 +13,7 @@ impl StackOptimizer {
         expr_arena: &mut Arena<AExpr>,
         lp_arena: &mut Arena<ALogicalPlan>,
         lp_top: Node,
-    ) -> Node {
+    ) -> Result<Node> {
         let mut changed = true;
 
         let mut plans = Vec::with_capacity(32);
@@ -50,7 +51,7 @@ impl StackOptimizer {
                             current_expr_node,
                             lp_arena,
                             current_node,
-                        ) {
+                        )? {
                             expr_arena.replace(current_expr_node, x);
                             changed = true;
                         }
@@ -62,7 +63,7 @@ impl StackOptimizer {
                 }
             }
         }
-        lp_top
+        Ok(lp_top)
     }
 }
 
@@ -86,7 +87,7 @@ pub trait OptimizationRule {
         _expr_node: Node,
         _lp_arena: &Arena<ALogicalPlan>,
         _lp_node: Node,
-    ) -> Option<AExpr> {
-        None
+    ) -> Result<Option<AExpr>> {
+        Ok(None)
     }
 }
@@ -4,10 +4,81 @@ use polars_core::utils::get_supertype;
 use crate::logical_plan::optimizer::stack_opt::OptimizationRule;
 use crate::logical_plan::Context;
 use crate::prelude::*;
-use crate::utils::is_scan;
+use crate::utils::{is_scan, temporal_arithmetic_dtype};
 
 pub struct TypeCoercionRule {}
 
+/// Cast `node` to the supertype `st`, unless it already is of that type. As the supertype of
+/// `Null` and any other type is that other type, a Null literal adopts the type of the other
+/// operand.
+fn cast_if_needed(
+    expr_arena: &mut Arena<AExpr>,
+    node: Node,
+    dtype: &DataType,
+    st: &DataType,
+) -> Node {
+    if dtype == st {
+        node
+    } else {
+        expr_arena.add(AExpr::Cast {
+            expr: node,
+            data_type: st.clone(),
+        })
+    }
+}
+
+#[cfg(all(
+    feature = "temporal",
+    any(feature = "dtype-date32", feature = "dtype-date64")
+))]
+const DATE_FMT: &str = "%Y-%m-%d";
+#[cfg(all(feature = "temporal", feature = "dtype-date64"))]
+const DATETIME_FMTS: [&str; 4] = [
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M:%S%.f",
+];
+
+/// Parse a string `value` that is compared with a column of type `dtype` into a literal of that
+/// temporal type. Returns `None` if `dtype` is not temporal and an error if `value` cannot be
+/// parsed.
+#[cfg(all(
+    feature = "temporal",
+    any(feature = "dtype-date32", feature = "dtype-date64")
+))]
+fn parse_temporal_literal(value: &str, dtype: &DataType) -> Result<Option<LiteralValue>> {
+    use polars_core::utils::chrono::NaiveDate;
+
+    let parse_err = |expected: String| {
+        PolarsError::ComputeError(
+            format!(
+                "could not parse '{}' to compare it with a column of type {:?}, expected the format {}",
+                value, dtype, expected
+            )
+            .into(),
+        )
+    };
+    let date = NaiveDate::parse_from_str(value, DATE_FMT).ok();
+
+    match dtype {
+        #[cfg(feature = "dtype-date32")]
+        DataType::Date32 => date
+            .map(|nd| Some(LiteralValue::Date(nd)))
+            .ok_or_else(|| parse_err(format!("'{}'", DATE_FMT))),
+        #[cfg(feature = "dtype-date64")]
+        DataType::Date64 => DATETIME_FMTS
+            .iter()
+            .find_map(|fmt| {
+                polars_core::utils::chrono::NaiveDateTime::parse_from_str(value, fmt).ok()
+            })
+            .or_else(|| date.map(|nd| nd.and_hms(0, 0, 0)))
+            .map(|ndt| Some(LiteralValue::DateTime(ndt)))
+            .ok_or_else(|| parse_err(format!("'{}' or '{}'", DATETIME_FMTS[0], DATE_FMT))),
+        _ => Ok(None),
+    }
+}
+
 impl OptimizationRule for TypeCoercionRule {
     fn optimize_expr(
         &self,
@@ -15,9 +86,9 @@ impl OptimizationRule for TypeCoercionRule {
         expr_node: Node,
         lp_arena: &Arena<ALogicalPlan>,
         lp_node: Node,
-    ) -> Option<AExpr> {
+    ) -> Result<Option<AExpr>> {
         let expr = expr_arena.get(expr_node);
-        match *expr {
+        let out = match *expr {
             AExpr::Ternary {
                 truthy: truthy_node,
                 falsy: falsy_node,
@@ -48,14 +119,10 @@ impl OptimizationRule for TypeCoercionRule {
                         None
                     } else {
                         let st = get_supertype(&type_true, &type_false).expect("supertype");
-                        let new_node_truthy = expr_arena.add(AExpr::Cast {
-                            expr: truthy_node,
-                            data_type: st.clone(),
-                        });
-                        let new_node_falsy = expr_arena.add(AExpr::Cast {
-                            expr: falsy_node,
-                            data_type: st,
-                        });
+                        let new_node_truthy =
+                            cast_if_needed(expr_arena, truthy_node, &type_true, &st);
+                        let new_node_falsy =
+                            cast_if_needed(expr_arena, falsy_node, &type_false, &st);
                         Some(AExpr::Ternary {
                             truthy: new_node_truthy,
                             falsy: new_node_falsy,
@@ -93,7 +160,7 @@ impl OptimizationRule for TypeCoercionRule {
                         .get_type(input_schema, Context::Default, expr_arena)
                         .expect("could not get dtype");
 
-                    let compare_cat_to_string = matches!(
+                    let is_comparison = matches!(
                         op,
                         Operator::Eq
                             | Operator::NotEq
@@ -101,23 +168,58 @@ impl OptimizationRule for TypeCoercionRule {
                             | Operator::Lt
                             | Operator::GtEq
                             | Operator::LtEq
-                    ) && ((type_left == DataType::Categorical
-                        && type_right == DataType::Utf8)
-                        || (type_left == DataType::Utf8 && type_right == DataType::Categorical));
+                    );
+
+                    // Parse a string literal that is compared to a temporal column to a
+                    // literal of the column's type, so that the column doesn't need to be cast
+                    // to Utf8 and the comparison is typed.
+                    #[cfg(all(
+                        feature = "temporal",
+                        any(feature = "dtype-date32", feature = "dtype-date64")
+                    ))]
+                    if is_comparison {
+                        let (typed_left, typed_right) = match (left, right) {
+                            (_, AExpr::Literal(LiteralValue::Utf8(v))) => {
+                                (None, parse_temporal_literal(v, &type_left)?)
+                            }
+                            (AExpr::Literal(LiteralValue::Utf8(v)), _) => {
+                                (parse_temporal_literal(v, &type_right)?, None)
+                            }
+                            _ => (None, None),
+                        };
+                        if let Some(lv) = typed_left {
+                            return Ok(Some(AExpr::BinaryExpr {
+                                left: expr_arena.add(AExpr::Literal(lv)),
+                                op,
+                                right: node_right,
+                            }));
+                        }
+                        if let Some(lv) = typed_right {
+                            return Ok(Some(AExpr::BinaryExpr {
+                                left: node_left,
+                                op,
+                                right: expr_arena.add(AExpr::Literal(lv)),
+                            }));
+                        }
+                    }
+
+                    let compare_cat_to_string = is_comparison
+                        && ((type_left == DataType::Categorical && type_right == DataType::Utf8)
+                            || (type_left == DataType::Utf8
+                                && type_right == DataType::Categorical));
+
+                    // temporal arithmetic is done on the operands as is
+                    let temporal_arithmetic =
+                        temporal_arithmetic_dtype(op, &type_left, &type_right).is_some();
 
-                    if type_left == type_right || compare_cat_to_string {
+                    if type_left == type_right || compare_cat_to_string || temporal_arithmetic {
                         None
                     } else {
                         let st = get_supertype(&type_left, &type_right)
                             .expect("could not find supertype of binary expr");
-                        let new_node_left = expr_arena.add(AExpr::Cast {
-                            expr: node_left,
-                            data_type: st.clone(),
-                        });
-                        let new_node_right = expr_arena.add(AExpr::Cast {
-                            expr: node_right,
-                            data_type: st,
-                        });
+                        let new_node_left = cast_if_needed(expr_arena, node_left, &type_left, &st);
+                        let new_node_right =
+                            cast_if_needed(expr_arena, node_right, &type_right, &st);
 
                         Some(AExpr::BinaryExpr {
                             left: new_node_left,
@@ -130,6 +232,7 @@ impl OptimizationRule for TypeCoercionRule {
                 }
             }
             _ => None,
-        }
+        };
+        Ok(out)
     }
 }
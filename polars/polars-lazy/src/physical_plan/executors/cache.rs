@@ -1,7 +1,7 @@
-use crate::physical_plan::executors::POLARS_VERBOSE;
 use crate::physical_plan::state::ExecutionState;
 use crate::prelude::*;
 use polars_core::prelude::*;
+use polars_core::verbose::{log_verbose, VerboseEvent};
 
 pub struct CacheExec {
     pub key: String,
@@ -16,10 +16,16 @@ impl Executor for CacheExec {
 
         // cache miss
         let df = self.input.execute(state)?;
-        state.store_cache(std::mem::take(&mut self.key), df.clone());
-        if std::env::var(POLARS_VERBOSE).is_ok() {
-            println!("cache set {:?}", self.key);
+        if state.verbose {
+            log_verbose(
+                VerboseEvent::new(
+                    "cache",
+                    format_args!("cache set {:?} with {} rows", self.key, df.height()),
+                )
+                .with_rows(df.height()),
+            );
         }
+        state.store_cache(std::mem::take(&mut self.key), df.clone());
         Ok(df)
     }
 }
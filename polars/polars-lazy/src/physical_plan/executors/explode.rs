@@ -10,6 +10,8 @@ pub(crate) struct ExplodeExec {
 impl Executor for ExplodeExec {
     fn execute(&mut self, state: &ExecutionState) -> Result<DataFrame> {
         let df = self.input.execute(state)?;
-        df.explode(&self.columns)
+        let df = df.explode(&self.columns)?;
+        state.check_output("explode", &df)?;
+        Ok(df)
     }
 }
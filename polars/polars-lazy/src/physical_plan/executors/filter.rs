@@ -1,27 +1,392 @@
 use crate::physical_plan::state::ExecutionState;
 use crate::prelude::*;
 use polars_core::prelude::*;
+use polars_core::verbose::{log_verbose, VerboseEvent};
+use polars_io::{predicate_to_mask, ColumnStats};
+
+/// Integers up to this magnitude are exactly represented by a f64.
+const MAX_EXACT_INT: f64 = (1u64 << 53) as f64;
+
+enum ChunkPredicateValue {
+    /// A numeric or temporal literal, compared on its physical value.
+    Numeric { value: f64, is_float: bool },
+    /// A string that is compared with a Categorical column.
+    Categorical(String),
+}
+
+/// A predicate `column <op> literal` of which the outcome is known for a whole chunk if
+/// the literal is outside of the minimum and maximum of that chunk.
+///
+/// Such chunks get an all false mask without evaluating the predicate. If no chunk can be
+/// skipped, the predicate is evaluated as usual.
+pub struct ChunkPredicate {
+    column: Arc<String>,
+    dtype: DataType,
+    op: Operator,
+    value: ChunkPredicateValue,
+}
+
+impl ChunkPredicate {
+    /// Check if the predicate is a comparison for which chunks can be skipped. The min/max
+    /// of a chunk are stored as f64, so the comparison must be exact in f64.
+    pub(crate) fn new(
+        predicate: Node,
+        expr_arena: &Arena<AExpr>,
+        input_schema: &Schema,
+    ) -> Option<Self> {
+        use Operator::*;
+        let (left, op, right) = match expr_arena.get(predicate) {
+            AExpr::BinaryExpr { left, op, right } => (*left, *op, *right),
+            _ => return None,
+        };
+        let (column, op, literal) = match (expr_arena.get(left), expr_arena.get(right)) {
+            (AExpr::Column(name), AExpr::Literal(lv)) => (name, op, lv),
+            (AExpr::Literal(lv), AExpr::Column(name)) => {
+                let op = match op {
+                    Lt => Gt,
+                    LtEq => GtEq,
+                    Gt => Lt,
+                    GtEq => LtEq,
+                    op => op,
+                };
+                (name, op, lv)
+            }
+            _ => return None,
+        };
+        if !matches!(op, Eq | NotEq | Lt | LtEq | Gt | GtEq) {
+            return None;
+        }
+        let dtype = input_schema.field_with_name(column).ok()?.data_type();
+
+        let int = |v: f64| {
+            if v.abs() <= MAX_EXACT_INT {
+                Some(ChunkPredicateValue::Numeric {
+                    value: v,
+                    is_float: false,
+                })
+            } else {
+                None
+            }
+        };
+        let float = |v: f64| ChunkPredicateValue::Numeric {
+            value: v,
+            is_float: true,
+        };
+        let is_float = matches!(dtype, DataType::Float32 | DataType::Float64);
+        let is_int = matches!(
+            dtype,
+            DataType::UInt8
+                | DataType::UInt16
+                | DataType::UInt32
+                | DataType::UInt64
+                | DataType::Int8
+                | DataType::Int16
+                | DataType::Int32
+                | DataType::Int64
+        );
+        let value = match literal {
+            LiteralValue::Float32(v) if is_float => float(*v as f64),
+            LiteralValue::Float64(v) if is_float => float(*v),
+            LiteralValue::Utf8(v) if dtype == &DataType::Categorical && op == Eq => {
+                ChunkPredicateValue::Categorical(v.clone())
+            }
+            #[cfg(all(feature = "temporal", feature = "dtype-date32"))]
+            LiteralValue::Date(v) if dtype == &DataType::Date32 => {
+                int(naive_date_to_date32(*v) as f64)?
+            }
+            #[cfg(all(feature = "temporal", feature = "dtype-date64"))]
+            LiteralValue::DateTime(v) if dtype == &DataType::Date64 => {
+                int(naive_datetime_to_date64(v) as f64)?
+            }
+            #[cfg(feature = "dtype-u8")]
+            LiteralValue::UInt8(v) if is_int => int(*v as f64)?,
+            #[cfg(feature = "dtype-u16")]
+            LiteralValue::UInt16(v) if is_int => int(*v as f64)?,
+            LiteralValue::UInt32(v) if is_int => int(*v as f64)?,
+            #[cfg(feature = "dtype-u64")]
+            LiteralValue::UInt64(v) if is_int => int(*v as f64)?,
+            #[cfg(feature = "dtype-i8")]
+            LiteralValue::Int8(v) if is_int => int(*v as f64)?,
+            #[cfg(feature = "dtype-i16")]
+            LiteralValue::Int16(v) if is_int => int(*v as f64)?,
+            LiteralValue::Int32(v) if is_int => int(*v as f64)?,
+            LiteralValue::Int64(v) if is_int => int(*v as f64)?,
+            _ => return None,
+        };
+        Some(ChunkPredicate {
+            column: column.clone(),
+            dtype: dtype.clone(),
+            op,
+            value,
+        })
+    }
+
+    /// Check if no value in a chunk with the given minimum and maximum can match.
+    fn can_skip(&self, min: f64, max: f64, value: f64, is_float: bool) -> bool {
+        use Operator::*;
+        // large integers are rounded by the conversion to f64
+        if !is_float && (min.abs() > MAX_EXACT_INT || max.abs() > MAX_EXACT_INT) {
+            return false;
+        }
+        // comparisons with NaN are false, so a NaN min/max never leads to a skip
+        match self.op {
+            Eq => value < min || value > max,
+            // a float chunk may contain NaN's, which are not equal to any value
+            NotEq => !is_float && min == value && max == value,
+            Lt => min >= value,
+            LtEq => min > value,
+            Gt => max <= value,
+            GtEq => max < value,
+            _ => false,
+        }
+    }
+
+    /// Check if no row of a batch of a file can match, based on the statistics of the column in
+    /// that batch.
+    pub(crate) fn skip_batch(&self, stats: &dyn Fn(&str) -> Option<ColumnStats>) -> bool {
+        let stats = match stats(&self.column) {
+            Some(stats) if stats.dtype == self.dtype => stats,
+            _ => return false,
+        };
+        match (&self.value, stats.min_max) {
+            // a comparison with a null is null, which is not selected by a filter
+            (_, None) => true,
+            (ChunkPredicateValue::Numeric { value, is_float }, Some((min, max))) => {
+                self.can_skip(min, max, *value, *is_float)
+            }
+            // the categories of the file are not known from the statistics
+            (ChunkPredicateValue::Categorical(_), _) => false,
+        }
+    }
+
+    /// Determine per chunk of the column if it can be skipped. Returns `None` if the column
+    /// has no statistics.
+    fn skip_chunks(&self, s: &Series) -> Option<Vec<bool>> {
+        if s.dtype() != &self.dtype {
+            return None;
+        }
+        let min_max = s.cached_chunk_min_max()?;
+        let (value, is_float) = match &self.value {
+            ChunkPredicateValue::Numeric { value, is_float } => (*value, *is_float),
+            ChunkPredicateValue::Categorical(v) => {
+                match s.categorical().ok()?.get_categorical_map()?.find(v) {
+                    Some(idx) => (idx as f64, false),
+                    // the value is not a category, so no row matches
+                    None => return Some(vec![true; min_max.len()]),
+                }
+            }
+        };
+        Some(
+            min_max
+                .iter()
+                .map(|min_max| match min_max {
+                    Some((min, max)) => self.can_skip(*min, *max, value, is_float),
+                    // a comparison with a null is null, which is not selected by a filter
+                    None => true,
+                })
+                .collect(),
+        )
+    }
+
+    /// Compute the mask of the predicate, with all false chunks for the chunks of the column
+    /// that can be skipped. The predicate is evaluated on the remaining consecutive chunks.
+    ///
+    /// Returns `None` if no chunk can be skipped.
+    fn evaluate(
+        &self,
+        df: &DataFrame,
+        predicate: &dyn PhysicalExpr,
+        state: &ExecutionState,
+    ) -> Result<Option<BooleanChunked>> {
+        let s = df.column(&self.column)?;
+        let skip = match self.skip_chunks(s) {
+            Some(skip) if skip.iter().any(|skip| *skip) => skip,
+            _ => return Ok(None),
+        };
+        if state.verbose {
+            log_verbose(VerboseEvent::new(
+                "filter",
+                format_args!(
+                    "skipped {} of {} chunks of column {}",
+                    skip.iter().filter(|skip| **skip).count(),
+                    skip.len(),
+                    self.column
+                ),
+            ));
+        }
+
+        let mut mask: Option<BooleanChunked> = None;
+        let mut append = |part: &BooleanChunked| match &mut mask {
+            Some(mask) => mask.append(part),
+            None => mask = Some(part.clone()),
+        };
+        let mut offset = 0;
+        // length of the consecutive chunks that need to be evaluated
+        let mut run = 0;
+        for (len, skip) in s.chunk_lengths().zip(skip) {
+            if skip {
+                if run > 0 {
+                    let s = predicate.evaluate(&df.slice(offset as i64, run), state)?;
+                    append(predicate_to_mask(&s)?);
+                    offset += run;
+                    run = 0;
+                }
+                append(&BooleanChunked::full("", false, len));
+                offset += len;
+            } else {
+                run += len;
+            }
+        }
+        if run > 0 {
+            let s = predicate.evaluate(&df.slice(offset as i64, run), state)?;
+            append(predicate_to_mask(&s)?);
+        }
+        Ok(mask)
+    }
+}
 
 pub struct FilterExec {
     pub(crate) predicate: Arc<dyn PhysicalExpr>,
     pub(crate) input: Box<dyn Executor>,
+    pub(crate) chunk_predicate: Option<ChunkPredicate>,
 }
 
 impl FilterExec {
     pub fn new(predicate: Arc<dyn PhysicalExpr>, input: Box<dyn Executor>) -> Self {
-        Self { predicate, input }
+        Self {
+            predicate,
+            input,
+            chunk_predicate: None,
+        }
+    }
+
+    /// Skip the chunks for which the [ChunkPredicate] can't be true.
+    pub fn with_chunk_predicate(mut self, chunk_predicate: Option<ChunkPredicate>) -> Self {
+        self.chunk_predicate = chunk_predicate;
+        self
     }
 }
 
+/// Compute the mask of the predicate. The chunks that can't match the `chunk_predicate`
+/// get an all false mask.
+pub(crate) fn predicate_mask(
+    df: &DataFrame,
+    predicate: &dyn PhysicalExpr,
+    chunk_predicate: Option<&ChunkPredicate>,
+    state: &ExecutionState,
+) -> Result<BooleanChunked> {
+    let mask = match chunk_predicate {
+        Some(chunk_predicate) if state.chunk_pruning => {
+            chunk_predicate.evaluate(df, predicate, state)?
+        }
+        _ => None,
+    };
+    match mask {
+        Some(mask) => Ok(mask),
+        None => {
+            let s = predicate.evaluate(df, state)?;
+            Ok(predicate_to_mask(&s)?.clone())
+        }
+    }
+}
+
+/// Filter the DataFrame by the predicate. The chunks that can't match the `chunk_predicate`
+/// are skipped.
+pub(crate) fn filter_with_chunk_predicate(
+    df: &DataFrame,
+    predicate: &dyn PhysicalExpr,
+    chunk_predicate: Option<&ChunkPredicate>,
+    state: &ExecutionState,
+) -> Result<DataFrame> {
+    let mask = predicate_mask(df, predicate, chunk_predicate, state)?;
+    df.filter(&mask)
+}
+
 impl Executor for FilterExec {
     fn execute(&mut self, state: &ExecutionState) -> Result<DataFrame> {
         let df = self.input.execute(state)?;
-        let s = self.predicate.evaluate(&df, state)?;
-        let mask = s.bool().expect("filter predicate wasn't of type boolean");
-        let df = df.filter(mask)?;
+        let n_rows_in = df.height();
+        let df = filter_with_chunk_predicate(
+            &df,
+            &*self.predicate,
+            self.chunk_predicate.as_ref(),
+            state,
+        )?;
         if state.verbose {
-            eprintln!("dataframe filtered");
+            log_verbose(
+                VerboseEvent::new(
+                    "filter",
+                    format_args!(
+                        "dataframe filtered from {} to {} rows",
+                        n_rows_in,
+                        df.height()
+                    ),
+                )
+                .with_rows(df.height()),
+            );
         }
         Ok(df)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn skip_chunks(predicate: Expr, s: &Series) -> Option<Vec<bool>> {
+        let mut expr_arena = Arena::with_capacity(8);
+        let predicate = to_aexpr(predicate, &mut expr_arena);
+        let schema = Schema::new(vec![s.field().clone()]);
+        ChunkPredicate::new(predicate, &expr_arena, &schema)?.skip_chunks(s)
+    }
+
+    #[test]
+    fn test_skip_chunks() {
+        let mut s = Series::new("a", &[1, 2, 3]);
+        s.append(&Series::new("a", &[5, 5])).unwrap();
+        s.append(&Series::new("a", &[None::<i32>, None])).unwrap();
+        s.append(&Series::new("a", &[6, 7])).unwrap();
+
+        let skip = |predicate| skip_chunks(predicate, &s).unwrap();
+        // min == literal == max
+        assert_eq!(skip(col("a").eq(lit(5))), &[true, false, true, true]);
+        assert_eq!(skip(col("a").neq(lit(5))), &[false, true, true, false]);
+        assert_eq!(skip(col("a").lt(lit(5))), &[false, true, true, true]);
+        assert_eq!(skip(col("a").lt_eq(lit(5))), &[false, false, true, true]);
+        assert_eq!(skip(col("a").gt(lit(5))), &[true, true, true, false]);
+        assert_eq!(skip(col("a").gt_eq(lit(5))), &[true, false, true, false]);
+        // the literal on the left hand side
+        assert_eq!(skip(lit(5).gt(col("a"))), &[false, true, true, true]);
+        // a literal equal to the max of a chunk
+        assert_eq!(skip(col("a").eq(lit(3))), &[false, true, true, true]);
+
+        // a float literal can't be compared exactly with the integer statistics
+        assert!(skip_chunks(col("a").eq(lit(5.0)), &s).is_none());
+        // large integers are not exact in f64
+        assert!(skip_chunks(col("a").eq(lit(i64::MAX)), &s).is_none());
+        assert!(skip_chunks(col("a").eq(col("a")), &s).is_none());
+
+        let mut s = Series::new("b", &[1.0, 5.0]);
+        s.append(&Series::new("b", &[5.0, 5.0])).unwrap();
+        s.append(&Series::new("b", &[f64::NAN, 6.0])).unwrap();
+        let skip = |predicate| skip_chunks(predicate, &s).unwrap();
+        assert_eq!(skip(col("b").gt(lit(5.0))), &[true, true, false]);
+        // a float chunk may contain NaN, which is not equal to 5.0
+        assert_eq!(skip(col("b").neq(lit(5.0))), &[false, false, false]);
+
+        let s = Series::new("c", &["a", "a", "b", "b"])
+            .cast_with_dtype(&DataType::Categorical)
+            .unwrap();
+        let mut c = s.slice(0, 2);
+        c.append(&s.slice(2, 2)).unwrap();
+        assert_eq!(
+            skip_chunks(col("c").eq(lit("b")), &c).unwrap(),
+            &[true, false]
+        );
+        assert_eq!(
+            skip_chunks(col("c").eq(lit("z")), &c).unwrap(),
+            &[true, true]
+        );
+        assert!(skip_chunks(col("c").neq(lit("b")), &c).is_none());
+    }
+}
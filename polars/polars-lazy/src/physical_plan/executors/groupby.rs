@@ -1,9 +1,13 @@
 use super::*;
 use crate::logical_plan::Context;
 use crate::utils::rename_aexpr_root_name;
-use polars_core::utils::{accumulate_dataframes_vertical, split_df};
+use polars_core::chunked_array::builder::CategoricalChunkedBuilder;
+use polars_core::frame::groupby::{GroupBy, GroupbyOptions};
+use polars_core::utils::{accumulate_dataframes_vertical, ensure_unique_names, split_df};
+use polars_core::verbose::{log_verbose, VerboseEvent};
 use polars_core::POOL;
 use rayon::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 /// Take an input Executor and a multiple expressions
 pub struct GroupByExec {
@@ -11,6 +15,8 @@ pub struct GroupByExec {
     keys: Vec<Arc<dyn PhysicalExpr>>,
     aggs: Vec<Arc<dyn PhysicalExpr>>,
     apply: Option<Arc<dyn DataFrameUdf>>,
+    schema: SchemaRef,
+    drop_null_keys: bool,
 }
 
 impl GroupByExec {
@@ -19,29 +25,131 @@ impl GroupByExec {
         keys: Vec<Arc<dyn PhysicalExpr>>,
         aggs: Vec<Arc<dyn PhysicalExpr>>,
         apply: Option<Arc<dyn DataFrameUdf>>,
+        schema: SchemaRef,
+        drop_null_keys: bool,
     ) -> Self {
         Self {
             input,
             keys,
             aggs,
             apply,
+            schema,
+            drop_null_keys,
         }
     }
 }
 
-fn groupby_helper(
+/// Aggregations of zero groups cannot always determine their output type from the data,
+/// e.g. a list aggregation doesn't know its inner type, and some aggregations produce no
+/// column at all. For empty results we use the columns and data types of the output schema.
+fn coerce_empty_to_schema(df: DataFrame, schema: &Schema) -> DataFrame {
+    if df.height() > 0 {
+        return df;
+    }
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|fld| match df.column(fld.name()) {
+            Ok(s) if s.dtype() == fld.data_type() || fld.data_type() == &DataType::Null => {
+                s.clone()
+            }
+            _ => Series::full_null(fld.name(), 0, fld.data_type()),
+        })
+        .collect();
+    DataFrame::new_no_checks(columns)
+}
+
+/// Utf8 keys with more distinct values than this fraction of their length are not interned,
+/// as the codes would hardly be cheaper to group than the strings themselves.
+const MAX_INTERN_DISTINCT_FRAC: f32 = 0.5;
+
+/// Get the interned `u32` codes of a Utf8 `key`. The codes are built once per query and
+/// cached in the [ExecutionState], so that later groupings on the same key column don't
+/// hash the strings again.
+fn interned_codes(key: &Series, state: &ExecutionState) -> Option<Series> {
+    let ca = key.utf8().ok()?;
+    let id = ca
+        .chunks()
+        .iter()
+        .map(|arr| Arc::as_ptr(arr) as *const u8 as usize)
+        .collect::<Vec<_>>();
+
+    if let Some(cached) = state.interned_keys.lock().unwrap().get(&id) {
+        return cached.as_ref().map(|(_, codes)| codes.clone());
+    }
+
+    // the lock is not held while the key is interned, so other keys can be interned in
+    // parallel. If another thread interns the same key meanwhile, its codes are kept.
+    let mut builder = CategoricalChunkedBuilder::new_local(ca.name(), ca.len());
+    builder.from_iter(ca.into_iter());
+    let codes = builder.finish();
+    let n_distinct = codes.get_categorical_map().map_or(0, |map| map.len());
+    let codes = codes.into_series();
+
+    let intern = n_distinct as f32 <= ca.len() as f32 * MAX_INTERN_DISTINCT_FRAC;
+    if state.verbose {
+        log_verbose(VerboseEvent::new(
+            "groupby",
+            format_args!(
+                "interned Utf8 key '{}': {} distinct values in {} rows, cached: {}",
+                ca.name(),
+                n_distinct,
+                ca.len(),
+                intern
+            ),
+        ));
+    }
+    let mut cache = state.interned_keys.lock().unwrap();
+    let cached = cache
+        .entry(id)
+        .or_insert_with(|| intern.then(|| (key.clone(), codes.clone())));
+    Some(cached.as_ref().map_or(codes, |(_, codes)| codes.clone()))
+}
+
+/// Group `df` by `keys`. If interning is turned on, a single Utf8 key is grouped by its interned
+/// codes, see [interned_codes].
+pub(crate) fn groupby_keys<'a>(
+    df: &'a DataFrame,
+    keys: Vec<Series>,
+    options: GroupbyOptions,
+    state: &ExecutionState,
+) -> Result<GroupBy<'a, 'a>> {
+    if state.intern_keys {
+        if let [key] = keys.as_slice() {
+            if let Some(codes) = interned_codes(key, state) {
+                let mut gb = df.groupby_with_series_and_options(vec![codes], options)?;
+                let groups = std::mem::take(gb.get_groups_mut());
+                return Ok(GroupBy::new(df, keys, groups, None));
+            }
+        }
+    }
+    df.groupby_with_series_and_options(keys, options)
+}
+
+pub(crate) fn groupby_helper(
     df: DataFrame,
     keys: Vec<Series>,
     aggs: &[Arc<dyn PhysicalExpr>],
     apply: Option<&Arc<dyn DataFrameUdf>>,
+    drop_null_keys: bool,
     state: &ExecutionState,
 ) -> Result<DataFrame> {
-    let gb = df.groupby_with_series(keys, true)?;
+    let options = GroupbyOptions {
+        drop_null_keys,
+        ..Default::default()
+    };
+    let gb = groupby_keys(&df, keys, options, state)?;
     if let Some(f) = apply {
-        return gb.apply(|df| f.call_udf(df));
+        let out = gb.apply(|df| f.call_udf(df))?;
+        state.check_output("groupby apply", &out)?;
+        return Ok(out);
     }
 
     let groups = gb.get_groups();
+    state.check_output_rows("groupby", groups.len())?;
+    // The sizes of the aggregations are added up as they finish, so that the remaining
+    // aggregations are not computed once the memory limit is exceeded.
+    let agg_bytes = AtomicUsize::new(0);
 
     let (mut columns, agg_columns) = POOL.install(|| {
         let get_columns = || gb.keys();
@@ -59,6 +167,11 @@ fn groupby_helper(
                             groups.len()
                         )
                     }
+                    if state.memory_limit.is_some() {
+                        let size = agg.estimated_size();
+                        let bytes = agg_bytes.fetch_add(size, Ordering::Relaxed) + size;
+                        state.check_output_bytes("groupby aggregation", bytes)?;
+                    }
                 };
                 Ok(opt_agg)
             })
@@ -69,6 +182,7 @@ fn groupby_helper(
     let agg_columns = agg_columns?;
 
     columns.extend(agg_columns.into_iter().flatten());
+    ensure_unique_names(&columns, "groupby aggregation")?;
     let df = DataFrame::new_no_checks(columns);
     Ok(df)
 }
@@ -76,7 +190,10 @@ fn groupby_helper(
 impl Executor for GroupByExec {
     fn execute(&mut self, state: &ExecutionState) -> Result<DataFrame> {
         if state.verbose {
-            eprintln!("aggregates are not partitionable: running default HASH AGGREGATION")
+            log_verbose(VerboseEvent::new(
+                "groupby",
+                format_args!("aggregates are not partitionable: running default HASH AGGREGATION"),
+            ));
         }
         let df = self.input.execute(state)?;
         let keys = self
@@ -84,16 +201,51 @@ impl Executor for GroupByExec {
             .iter()
             .map(|e| e.evaluate(&df, state))
             .collect::<Result<_>>()?;
-        groupby_helper(df, keys, &self.aggs, self.apply.as_ref(), state)
+        #[cfg(feature = "spill")]
+        if spill::exceeds_memory_budget(&df, state) {
+            let out = spill::groupby_spilled(
+                df,
+                keys,
+                &self.aggs,
+                self.apply.as_ref(),
+                self.drop_null_keys,
+                state,
+            )?;
+            state.check_output("groupby", &out)?;
+            return match self.apply {
+                Some(_) => Ok(out),
+                None => Ok(coerce_empty_to_schema(out, &self.schema)),
+            };
+        }
+        let out = groupby_helper(
+            df,
+            keys,
+            &self.aggs,
+            self.apply.as_ref(),
+            self.drop_null_keys,
+            state,
+        )?;
+        state.check_output("groupby", &out)?;
+        match self.apply {
+            Some(_) => Ok(out),
+            None => Ok(coerce_empty_to_schema(out, &self.schema)),
+        }
     }
 }
 
+/// Name of the evaluated key column in the partitioned results. The outer aggregation groups
+/// on this materialized column, so that the key expression is not evaluated a second time and
+/// the key cannot be confused with an aggregation output of the same name.
+const PARTITION_KEY_NAME: &str = "__POLARS_PARTITION_KEY";
+
 /// Take an input Executor and a multiple expressions
 pub struct PartitionGroupByExec {
     input: Box<dyn Executor>,
     key: Arc<dyn PhysicalExpr>,
     phys_aggs: Vec<Arc<dyn PhysicalExpr>>,
     aggs: Vec<Expr>,
+    schema: SchemaRef,
+    drop_null_keys: bool,
 }
 
 impl PartitionGroupByExec {
@@ -102,12 +254,16 @@ impl PartitionGroupByExec {
         key: Arc<dyn PhysicalExpr>,
         phys_aggs: Vec<Arc<dyn PhysicalExpr>>,
         aggs: Vec<Expr>,
+        schema: SchemaRef,
+        drop_null_keys: bool,
     ) -> Self {
         Self {
             input,
             key,
             phys_aggs,
             aggs,
+            schema,
+            drop_null_keys,
         }
     }
 }
@@ -126,9 +282,15 @@ fn run_partitions(
     POOL.install(|| {
         dfs.into_par_iter()
             .map(|df| {
-                let key = exec.key.evaluate(&df, state)?;
+                let mut key = exec.key.evaluate(&df, state)?;
+                key.rename(PARTITION_KEY_NAME);
                 let phys_aggs = &exec.phys_aggs;
-                let gb = df.groupby_with_series(vec![key], false)?;
+                // the null keys are already dropped in the partitions
+                let options = GroupbyOptions {
+                    drop_null_keys: exec.drop_null_keys,
+                    multithreaded: false,
+                };
+                let gb = df.groupby_with_series_and_options(vec![key], options)?;
                 let groups = gb.get_groups();
 
                 let mut columns = gb.keys();
@@ -207,11 +369,47 @@ impl Executor for PartitionGroupByExec {
         // of groups.
         let key = self.key.evaluate(&original_df, state)?;
 
+        if original_df.height() == 0 {
+            let out = groupby_helper(
+                original_df,
+                vec![key],
+                &self.phys_aggs,
+                None,
+                self.drop_null_keys,
+                state,
+            )?;
+            return Ok(coerce_empty_to_schema(out, &self.schema));
+        }
+
+        #[cfg(feature = "spill")]
+        if spill::exceeds_memory_budget(&original_df, state) {
+            let out = spill::groupby_spilled(
+                original_df,
+                vec![key],
+                &self.phys_aggs,
+                None,
+                self.drop_null_keys,
+                state,
+            )?;
+            state.check_output("groupby", &out)?;
+            return Ok(coerce_empty_to_schema(out, &self.schema));
+        }
+
         if std::env::var("POLARS_NO_PARTITION").is_ok() {
             if state.verbose {
-                eprintln!("POLARS_NO_PARTITION set: running default HASH AGGREGATION")
+                log_verbose(VerboseEvent::new(
+                    "groupby",
+                    format_args!("POLARS_NO_PARTITION set: running default HASH AGGREGATION"),
+                ));
             }
-            return groupby_helper(original_df, vec![key], &self.phys_aggs, None, state);
+            return groupby_helper(
+                original_df,
+                vec![key],
+                &self.phys_aggs,
+                None,
+                self.drop_null_keys,
+                state,
+            );
         }
 
         // 0.5% is approximately the tipping point
@@ -239,21 +437,37 @@ impl Executor for PartitionGroupByExec {
             (sample_cardinality(&key, sample_size), "estimated")
         };
         if state.verbose {
-            eprintln!("{} cardinality: {}%", sampled_method, (frac * 100.0) as u32);
+            log_verbose(VerboseEvent::new(
+                "groupby",
+                format_args!("{} cardinality: {}%", sampled_method, (frac * 100.0) as u32),
+            ));
         }
 
         if frac > cardinality_frac {
             if state.verbose {
-                eprintln!(
-                    "(estimated cardinality is > than allowed cardinality: {}\
+                log_verbose(VerboseEvent::new(
+                    "groupby",
+                    format_args!(
+                        "(estimated cardinality is > than allowed cardinality: {}\
                 running default HASH AGGREGATION",
-                    (cardinality_frac * 100.0) as u32
-                );
+                        (cardinality_frac * 100.0) as u32
+                    ),
+                ));
             }
-            return groupby_helper(original_df, vec![key], &self.phys_aggs, None, state);
+            return groupby_helper(
+                original_df,
+                vec![key],
+                &self.phys_aggs,
+                None,
+                self.drop_null_keys,
+                state,
+            );
         }
         if state.verbose {
-            eprintln!("run PARTITIONED HASH AGGREGATION")
+            log_verbose(VerboseEvent::new(
+                "groupby",
+                format_args!("run PARTITIONED HASH AGGREGATION"),
+            ));
         }
 
         // Run the partitioned aggregations
@@ -263,14 +477,21 @@ impl Executor for PartitionGroupByExec {
         // MERGE phase
         // merge and hash aggregate again
         let df = accumulate_dataframes_vertical(dfs)?;
-        let key = self.key.evaluate(&df, state)?;
+        // The key is already evaluated in the partitions, we group by the materialized column
+        // and give it the name of the key expression again.
+        let key_name = key.name().to_string();
+        let outer_key = df.column(PARTITION_KEY_NAME)?.clone();
 
-        let gb = df.groupby_with_series(vec![key], true)?;
+        let gb = df.groupby_with_series(vec![outer_key], true)?;
         let groups = gb.get_groups();
 
         let (aggs_and_names, outer_phys_aggs) = get_outer_agg_exprs(self, &original_df)?;
 
-        let get_columns = || gb.keys();
+        let get_columns = || {
+            let mut keys = gb.keys();
+            keys[0].rename(&key_name);
+            keys
+        };
         let get_agg = || {
             outer_phys_aggs
                 .par_iter()
@@ -294,8 +515,10 @@ impl Executor for PartitionGroupByExec {
             POOL.install(|| rayon::join(get_columns, get_agg));
 
         columns.extend(agg_columns);
+        ensure_unique_names(&columns, "groupby aggregation")?;
 
         let df = DataFrame::new_no_checks(columns);
+        state.check_output("groupby", &df)?;
         Ok(df)
     }
 }
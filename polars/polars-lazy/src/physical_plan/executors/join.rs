@@ -1,7 +1,10 @@
-use crate::logical_plan::FETCH_ROWS;
 use crate::physical_plan::state::ExecutionState;
 use crate::prelude::*;
+use polars_core::frame::groupby::GroupbyOptions;
+use polars_core::frame::hash_join::private_outer_join_on_series;
 use polars_core::prelude::*;
+use polars_core::utils::NoNull;
+use polars_core::verbose::{log_verbose, VerboseEvent};
 use polars_core::POOL;
 
 pub struct JoinExec {
@@ -11,6 +14,7 @@ pub struct JoinExec {
     left_on: Vec<Arc<dyn PhysicalExpr>>,
     right_on: Vec<Arc<dyn PhysicalExpr>>,
     parallel: bool,
+    suffix: String,
 }
 
 impl JoinExec {
@@ -21,6 +25,7 @@ impl JoinExec {
         left_on: Vec<Arc<dyn PhysicalExpr>>,
         right_on: Vec<Arc<dyn PhysicalExpr>>,
         parallel: bool,
+        suffix: String,
     ) -> Self {
         JoinExec {
             input_left: Some(input_left),
@@ -29,30 +34,123 @@ impl JoinExec {
             left_on,
             right_on,
             parallel,
+            suffix,
         }
     }
 }
 
+/// Count the output rows of a join on the given keys without materializing the join tuples.
+/// Every row matches all rows of the other side with the same key, so the keys of both sides
+/// are grouped and the group sizes of the matching keys are multiplied.
+///
+/// The rows with a null key are not grouped, they are counted as the least number of rows they
+/// can output. So this is a lower bound that never aborts a join within the limits, and the
+/// joined frame is checked as well.
+fn join_output_rows(
+    height_left: usize,
+    height_right: usize,
+    left_keys: &[Series],
+    right_keys: &[Series],
+    how: JoinType,
+) -> Result<usize> {
+    match how {
+        JoinType::Inner | JoinType::Left | JoinType::Outer => {}
+        #[cfg(feature = "cross_join")]
+        JoinType::Cross => return Ok(height_left * height_right),
+        // an asof join outputs a row per left row
+        #[allow(unreachable_patterns)]
+        _ => return Ok(height_left),
+    }
+
+    let group_sizes = |keys: &[Series], name: &str| -> Result<(Vec<Series>, DataFrame)> {
+        let df = DataFrame::new_no_checks(keys.to_vec());
+        let options = GroupbyOptions {
+            drop_null_keys: true,
+            ..Default::default()
+        };
+        let gb = df.groupby_with_series_and_options(keys.to_vec(), options)?;
+        let mut sizes: NoNull<UInt32Chunked> = gb
+            .get_groups()
+            .iter()
+            .map(|(_, idx)| idx.len() as u32)
+            .collect();
+        sizes.rename(name);
+        let sizes = DataFrame::new_no_checks(vec![sizes.into_inner().into_series()]);
+        Ok((gb.keys(), sizes))
+    };
+    let (keys_left, sizes_left) = group_sizes(left_keys, "left")?;
+    let (keys_right, sizes_right) = group_sizes(right_keys, "right")?;
+    let null_rows = |sizes: &DataFrame, height: usize| -> Result<usize> {
+        let non_null = sizes.get_columns()[0].u32()?.sum().unwrap_or(0) as usize;
+        Ok(height - non_null)
+    };
+    let null_left = null_rows(&sizes_left, height_left)?;
+    let null_right = null_rows(&sizes_right, height_right)?;
+    // the keys are unique on both sides, so this join is at most as large as its inputs
+    let matches =
+        sizes_left.join_on_series(&sizes_right, &keys_left, &keys_right, JoinType::Inner)?;
+
+    let mut n_rows = 0;
+    let mut matched_left = 0;
+    let mut matched_right = 0;
+    let sizes_left = matches.column("left")?.u32()?;
+    let sizes_right = matches.column("right")?.u32()?;
+    for (l, r) in sizes_left
+        .into_no_null_iter()
+        .zip(sizes_right.into_no_null_iter())
+    {
+        n_rows += l as usize * r as usize;
+        matched_left += l as usize;
+        matched_right += r as usize;
+    }
+    // the rows with a null key output at least one row each in a left join. In an outer join
+    // they either match each other or all output a row, so they output at least as many rows as
+    // the side with the most null keys.
+    Ok(match how {
+        JoinType::Left => n_rows + height_left - matched_left,
+        JoinType::Outer => {
+            n_rows
+                + (height_left - matched_left - null_left)
+                + (height_right - matched_right - null_right)
+                + std::cmp::max(null_left, null_right)
+        }
+        _ => n_rows,
+    })
+}
+
+/// The estimated number of bytes per row of `df`.
+fn row_size(df: &DataFrame) -> usize {
+    match df.height() {
+        0 => 0,
+        height => df.estimated_size() / height,
+    }
+}
+
 impl Executor for JoinExec {
     fn execute<'a>(&'a mut self, state: &'a ExecutionState) -> Result<DataFrame> {
         let mut input_left = self.input_left.take().unwrap();
         let mut input_right = self.input_right.take().unwrap();
 
+        if state.verbose {
+            log_verbose(VerboseEvent::new(
+                "join",
+                format_args!(
+                    "executing the inputs {}",
+                    if self.parallel {
+                        "in parallel"
+                    } else {
+                        "sequentially"
+                    }
+                ),
+            ));
+        }
         let (df_left, df_right) = if self.parallel {
             let state_left = state.clone();
             let state_right = state.clone();
-            // propagate the fetch_rows static value to the spawning threads.
-            let fetch_rows = FETCH_ROWS.with(|fetch_rows| fetch_rows.get());
 
             POOL.join(
-                move || {
-                    FETCH_ROWS.with(|fr| fr.set(fetch_rows));
-                    input_left.execute(&state_left)
-                },
-                move || {
-                    FETCH_ROWS.with(|fr| fr.set(fetch_rows));
-                    input_right.execute(&state_right)
-                },
+                move || input_left.execute(&state_left),
+                move || input_right.execute(&state_right),
             )
         } else {
             (input_left.execute(state), input_right.execute(state))
@@ -61,22 +159,90 @@ impl Executor for JoinExec {
         let df_left = df_left?;
         let df_right = df_right?;
 
-        let left_names = self
+        let left_keys = self
             .left_on
             .iter()
-            .map(|e| e.evaluate(&df_left, state).map(|s| s.name().to_string()))
+            .map(|e| e.evaluate(&df_left, state))
             .collect::<Result<Vec<_>>>()?;
 
-        let right_names = self
+        let right_keys = self
             .right_on
             .iter()
-            .map(|e| e.evaluate(&df_right, state).map(|s| s.name().to_string()))
+            .map(|e| e.evaluate(&df_right, state))
             .collect::<Result<Vec<_>>>()?;
 
-        let df = df_left.join(&df_right, &left_names, &right_names, self.how);
+        // the right join columns are not in the output, the columns that keys are computed
+        // from are
+        let mut right = df_right.clone();
+        for e in &self.right_on {
+            if let Expr::Column(name) = e.as_expression() {
+                if right.find_idx_by_name(name).is_some() {
+                    right = right.drop(name)?;
+                }
+            }
+        }
+
+        // check the limits before the join tuples are materialized, as a join can output many
+        // more rows than its inputs
+        if state.has_output_limits() {
+            let n_rows = join_output_rows(
+                df_left.height(),
+                df_right.height(),
+                &left_keys,
+                &right_keys,
+                self.how,
+            )?;
+            let node = format!(
+                "{:?} join of {} and {} rows",
+                self.how,
+                df_left.height(),
+                df_right.height()
+            );
+            state.check_output_rows(&node, n_rows)?;
+            if state.memory_limit.is_some() {
+                let bytes = n_rows * (row_size(&df_left) + row_size(&right));
+                state.check_output_bytes(&node, bytes)?;
+            }
+        }
+
+        let df = match self.how {
+            JoinType::Outer => {
+                // the output columns of the left keys hold the combined keys of both sides
+                let coalesce = vec![true; left_keys.len()];
+                private_outer_join_on_series(
+                    &df_left,
+                    &right,
+                    &left_keys,
+                    &right_keys,
+                    &coalesce,
+                    Some(&self.suffix),
+                )?
+            }
+            how => df_left.join_on_series_with_suffix(
+                &right,
+                &left_keys,
+                &right_keys,
+                how,
+                Some(&self.suffix),
+            )?,
+        };
         if state.verbose {
-            eprintln!("{:?} join dataframes finished", self.how);
+            log_verbose(
+                VerboseEvent::new(
+                    "join",
+                    format_args!(
+                        "{:?} join of {} and {} rows finished with {} rows",
+                        self.how,
+                        df_left.height(),
+                        df_right.height(),
+                        df.height()
+                    ),
+                )
+                .with_rows(df.height()),
+            );
         };
-        df
+        // the rows with a null key are not counted exactly before the join
+        state.check_output("join", &df)?;
+        Ok(df)
     }
 }
@@ -5,31 +5,44 @@ pub mod filter;
 pub mod groupby;
 pub mod join;
 pub mod melt;
+pub mod row_count;
 pub mod scan;
 pub mod slice;
 pub mod sort;
+#[cfg(feature = "spill")]
+pub(crate) mod spill;
 pub mod stack;
 pub mod udf;
+pub mod union;
 pub mod various;
 
 use super::*;
-use crate::logical_plan::FETCH_ROWS;
 use itertools::Itertools;
+use polars_core::utils::ensure_unique_names;
+use polars_core::verbose::{log_verbose, VerboseEvent};
 use polars_core::POOL;
 use rayon::prelude::*;
 use std::io::Seek;
 use std::path::PathBuf;
 
-const POLARS_VERBOSE: &str = "POLARS_VERBOSE";
-
-fn set_n_rows(stop_after_n_rows: Option<usize>) -> Option<usize> {
-    let fetch_rows = FETCH_ROWS.with(|fetch_rows| fetch_rows.get());
-    match fetch_rows {
+fn set_n_rows(stop_after_n_rows: Option<usize>, state: &ExecutionState) -> Option<usize> {
+    match state.fetch_rows {
         None => stop_after_n_rows,
         Some(n) => Some(n),
     }
 }
 
+#[cfg(any(feature = "parquet", feature = "csv-file"))]
+/// A scan that outputs more rows than `max_output_rows` aborts the query, so a scan without
+/// predicate or aggregation doesn't have to read more than one row over the limit.
+fn bound_scan_rows(stop_after_n_rows: Option<usize>, state: &ExecutionState) -> Option<usize> {
+    match (stop_after_n_rows, state.max_output_rows) {
+        (Some(n), Some(max_rows)) => Some(std::cmp::min(n, max_rows.saturating_add(1))),
+        (None, Some(max_rows)) => Some(max_rows.saturating_add(1)),
+        (n, None) => n,
+    }
+}
+
 pub(crate) fn evaluate_physical_expressions(
     df: &DataFrame,
     exprs: &[Arc<dyn PhysicalExpr>],
@@ -50,15 +63,27 @@ pub(crate) fn evaluate_physical_expressions(
             selected_columns = selected_columns
                 .into_iter()
                 .map(|series| {
-                    if series.len() == 1 && height > 1 {
+                    if series.len() == 1 && height != 1 {
                         series.expand_at_index(0, height)
                     } else {
                         series
                     }
                 })
-                .collect()
+                .collect::<Vec<_>>();
+            if let Some(s) = selected_columns.iter().find(|s| s.len() != height) {
+                return Err(PolarsError::ShapeMisMatch(
+                    format!(
+                        "column '{}' has length {} and cannot be broadcast to the {} rows of the frame",
+                        s.name(),
+                        s.len(),
+                        height
+                    )
+                    .into(),
+                ));
+            }
         }
     }
 
+    ensure_unique_names(&selected_columns, "projection")?;
     Ok(DataFrame::new_no_checks(selected_columns))
 }
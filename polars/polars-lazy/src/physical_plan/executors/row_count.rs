@@ -0,0 +1,16 @@
+use crate::physical_plan::state::ExecutionState;
+use crate::prelude::*;
+use polars_core::prelude::*;
+use polars_io::RowCount;
+
+pub struct RowCountExec {
+    pub input: Box<dyn Executor>,
+    pub row_count: RowCount,
+}
+
+impl Executor for RowCountExec {
+    fn execute(&mut self, state: &ExecutionState) -> Result<DataFrame> {
+        let df = self.input.execute(state)?;
+        df.with_row_count(&self.row_count.name, Some(self.row_count.offset))
+    }
+}
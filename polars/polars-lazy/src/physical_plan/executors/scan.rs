@@ -1,10 +1,240 @@
+use super::filter::{predicate_mask, ChunkPredicate};
 use super::*;
 use crate::logical_plan::CsvParserOptions;
 use crate::utils::try_path_to_str;
 use polars_io::mmap::MmapBytesReader;
 use polars_io::prelude::*;
-use polars_io::{csv::CsvEncoding, ScanAggregation};
+#[cfg(any(feature = "parquet", feature = "csv-file"))]
+use polars_io::resolve_homedir;
+use polars_io::{apply_row_count, csv::CsvEncoding, ScanAggregation};
+use std::collections::hash_map::{DefaultHasher, Entry};
+use std::fmt::Write;
+use std::hash::{Hash, Hasher};
+#[cfg(feature = "csv-file")]
+use std::io::Cursor;
 use std::mem;
+#[cfg(any(feature = "parquet", feature = "csv-file"))]
+use std::path::Path;
+#[cfg(any(feature = "parquet", feature = "csv-file"))]
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Hash a key with the deterministic `DefaultHasher::new`, so that equal keys get equal hashes
+/// in every query.
+fn hash_key<K: Hash + ?Sized>(key: &K) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn hash_ptr<T: ?Sized, H: Hasher>(ptr: &Arc<T>, state: &mut H) {
+    state.write_usize(Arc::as_ptr(ptr) as *const () as usize)
+}
+
+fn hash_literal<H: Hasher>(lv: &LiteralValue, state: &mut H) {
+    use LiteralValue::*;
+    mem::discriminant(lv).hash(state);
+    match lv {
+        Null => {}
+        Boolean(v) => v.hash(state),
+        Utf8(v) => v.hash(state),
+        #[cfg(feature = "dtype-u8")]
+        UInt8(v) => v.hash(state),
+        #[cfg(feature = "dtype-u16")]
+        UInt16(v) => v.hash(state),
+        UInt32(v) => v.hash(state),
+        #[cfg(feature = "dtype-u64")]
+        UInt64(v) => v.hash(state),
+        #[cfg(feature = "dtype-i8")]
+        Int8(v) => v.hash(state),
+        #[cfg(feature = "dtype-i16")]
+        Int16(v) => v.hash(state),
+        Int32(v) => v.hash(state),
+        Int64(v) => v.hash(state),
+        Float32(v) => v.to_bits().hash(state),
+        Float64(v) => v.to_bits().hash(state),
+        Range {
+            low,
+            high,
+            data_type,
+        } => {
+            low.hash(state);
+            high.hash(state);
+            data_type.hash(state);
+        }
+        #[cfg(all(feature = "temporal", feature = "dtype-date64"))]
+        DateTime(v) => v.hash(state),
+        #[cfg(all(feature = "temporal", feature = "dtype-date32"))]
+        Date(v) => v.hash(state),
+        // a series literal is immutable, the data is identified by its address
+        Series(s) => {
+            let s: &polars_core::series::Series = s;
+            s.name().hash(state);
+            s.len().hash(state);
+            hash_ptr(&s.0, state);
+        }
+    }
+}
+
+/// Hash a single node of an expression. The inputs of the node are not hashed, they are
+/// visited by the iterator of the expression. The number of inputs of the nodes that have a
+/// variable number of inputs is hashed, so that differently shaped trees get different hashes.
+///
+/// Closures don't have a structure that can be hashed, so their address is hashed.
+fn hash_expr_node<H: Hasher>(e: &Expr, state: &mut H) {
+    use Expr::*;
+    mem::discriminant(e).hash(state);
+    match e {
+        Alias(_, name) => name.hash(state),
+        Column(name) => name.hash(state),
+        Literal(lv) => hash_literal(lv, state),
+        BinaryExpr { op, .. } => op.hash(state),
+        Cast { data_type, .. } => data_type.hash(state),
+        Sort { reverse, .. } => reverse.hash(state),
+        SortBy { by, reverse, .. } => {
+            by.len().hash(state);
+            reverse.hash(state);
+        }
+        Agg(agg) => {
+            mem::discriminant(agg).hash(state);
+            if let AggExpr::Quantile { quantile, .. } = agg {
+                quantile.to_bits().hash(state)
+            }
+        }
+        Function {
+            input,
+            function,
+            output_type,
+            collect_groups,
+        } => {
+            input.len().hash(state);
+            hash_ptr(&**function, state);
+            mem::discriminant(output_type).hash(state);
+            match output_type {
+                GetOutput::ToType(dtype) => dtype.hash(state),
+                GetOutput::Map(f) => hash_ptr(&**f, state),
+                GetOutput::SameType => {}
+            }
+            collect_groups.hash(state);
+        }
+        Extension { input, plugin } => {
+            input.len().hash(state);
+            hash_ptr(&**plugin, state);
+        }
+        Shift { periods, .. } => periods.hash(state),
+        Window {
+            partition_by,
+            order_by,
+            ..
+        } => {
+            partition_by.len().hash(state);
+            order_by.is_some().hash(state);
+        }
+        Slice { offset, length, .. } => {
+            offset.hash(state);
+            length.hash(state);
+        }
+        BinaryFunction {
+            function,
+            output_field,
+            ..
+        } => {
+            hash_ptr(&**function, state);
+            hash_ptr(&**output_field, state);
+        }
+        Columns(names) => names.hash(state),
+        DtypeColumn(dtypes) => dtypes.hash(state),
+        Exclude(_, excluded) => excluded.hash(state),
+        Not(_)
+        | IsNotNull(_)
+        | IsNull(_)
+        | Take { .. }
+        | Ternary { .. }
+        | Reverse(_)
+        | Duplicated(_)
+        | IsUnique(_)
+        | Explode(_)
+        | Filter { .. }
+        | DropNulls(_)
+        | Wildcard
+        | Except(_) => {}
+    }
+}
+
+/// Hash the structure of the predicate, so that the expression doesn't have to be formatted to
+/// a `String`. Equal predicates get equal fingerprints.
+fn predicate_fingerprint(predicate: &dyn PhysicalExpr) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for e in predicate.as_expression() {
+        hash_expr_node(e, &mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Files that were modified less than this long ago are not shared between queries. The
+/// modification time of some file systems has a granularity of seconds, so a rewrite of the
+/// same size within that window would not change the key.
+#[cfg(any(feature = "parquet", feature = "csv-file"))]
+const MTIME_GRANULARITY: Duration = Duration::from_secs(2);
+
+/// The key under which a file scan is cached.
+#[cfg(any(feature = "parquet", feature = "csv-file"))]
+struct ScanCacheKey {
+    key: String,
+    /// The file is known to be unchanged as long as the key matches, so the scan can be shared
+    /// with the next queries.
+    shared: bool,
+}
+
+/// Create the key under which a scan is cached. The predicate and the options that determine the
+/// output of the scan (projection, number of rows, schema, aggregations) are hashed, so that they
+/// don't have to be formatted to a `String`. `options_hash` is the [hash_key] of these options.
+///
+/// The modification time and the size of the file are part of the key, so that a file that is
+/// rewritten after it was cached is read again. The metadata is read when the scan is executed,
+/// an error (e.g. the file was removed) is returned like the error of opening the file.
+#[cfg(any(feature = "parquet", feature = "csv-file"))]
+fn scan_cache_key(
+    path: &Path,
+    predicate: Option<&Arc<dyn PhysicalExpr>>,
+    row_count: Option<&RowCount>,
+    options_hash: u64,
+) -> Result<ScanCacheKey> {
+    let metadata = std::fs::metadata(resolve_homedir(path))?;
+    let mut key = scan_cache_key_prefix(path, predicate, row_count, options_hash)?;
+
+    // not every platform records the modification time, such a file is only cached per query
+    let mtime = metadata.modified().ok();
+    let shared = mtime
+        .and_then(|mtime| SystemTime::now().duration_since(mtime).ok())
+        .map(|age| age >= MTIME_GRANULARITY)
+        .unwrap_or(false);
+    let mtime = mtime
+        .and_then(|mtime| mtime.duration_since(UNIX_EPOCH).ok())
+        .map(|mtime| mtime.as_nanos())
+        .unwrap_or(0);
+    write!(key, "#mtime:{}:{}", mtime, metadata.len()).unwrap();
+    Ok(ScanCacheKey { key, shared })
+}
+
+/// The part of the key of [scan_cache_key] that doesn't depend on the file metadata.
+#[cfg(any(feature = "parquet", feature = "csv-file"))]
+fn scan_cache_key_prefix(
+    path: &Path,
+    predicate: Option<&Arc<dyn PhysicalExpr>>,
+    row_count: Option<&RowCount>,
+    options_hash: u64,
+) -> Result<String> {
+    let path_str = try_path_to_str(path)?;
+    let mut key = match predicate {
+        Some(predicate) => format!("{}#{:x}", path_str, predicate_fingerprint(&**predicate)),
+        None => path_str.to_string(),
+    };
+    if let Some(rc) = row_count {
+        write!(key, "#rc:{}:{}", rc.name, rc.offset).unwrap();
+    }
+    write!(key, "#opts:{:x}", options_hash).unwrap();
+    Ok(key)
+}
 
 trait FinishScanOps {
     /// Read the file and create the DataFrame. Used from lazy execution
@@ -21,12 +251,18 @@ impl<'a, R: 'static + Seek + Sync + Send + MmapBytesReader> FinishScanOps for Cs
         predicate: Option<Arc<dyn PhysicalExpr>>,
         aggregate: Option<&[ScanAggregation]>,
     ) -> Result<DataFrame> {
-        let predicate =
-            predicate.map(|expr| Arc::new(PhysicalIoHelper { expr }) as Arc<dyn PhysicalIoExpr>);
+        let predicate = predicate.map(|expr| {
+            Arc::new(PhysicalIoHelper {
+                expr,
+                chunk_predicate: None,
+            }) as Arc<dyn PhysicalIoExpr>
+        });
 
         let rechunk = self.rechunk;
+        let row_count = self.row_count.clone();
         let mut csv_reader = self.build_inner_reader()?;
         let df = csv_reader.as_df(predicate, aggregate)?;
+        let df = apply_row_count(df, row_count.as_ref())?;
         match rechunk {
             true => Ok(df.agg_chunks()),
             false => Ok(df),
@@ -43,6 +279,8 @@ pub struct ParquetExec {
     aggregate: Vec<ScanAggregation>,
     stop_after_n_rows: Option<usize>,
     cache: bool,
+    row_count: Option<RowCount>,
+    chunk_predicate: Option<Arc<ChunkPredicate>>,
 }
 
 #[cfg(feature = "parquet")]
@@ -64,23 +302,50 @@ impl ParquetExec {
             aggregate,
             stop_after_n_rows,
             cache,
+            row_count: None,
+            chunk_predicate: None,
         }
     }
+
+    /// Let the reader add the row count column.
+    pub(crate) fn with_row_count(mut self, row_count: Option<RowCount>) -> Self {
+        self.row_count = row_count;
+        self
+    }
+
+    /// Skip the row groups for which the [ChunkPredicate] of the predicate can't be true.
+    pub(crate) fn with_chunk_predicate(mut self, chunk_predicate: Option<ChunkPredicate>) -> Self {
+        self.chunk_predicate = chunk_predicate.map(Arc::new);
+        self
+    }
 }
 
 #[cfg(feature = "parquet")]
 impl Executor for ParquetExec {
     fn execute(&mut self, state: &ExecutionState) -> Result<DataFrame> {
         let path_str = try_path_to_str(&self.path)?;
-        let cache_key = match &self.predicate {
-            Some(predicate) => format!("{}{:?}", path_str, predicate.as_expression()),
-            None => path_str.to_string(),
-        };
-        if let Some(df) = state.cache_hit(&cache_key) {
-            return Ok(df);
+        let mut stop_after_n_rows = set_n_rows(self.stop_after_n_rows, state);
+        if self.predicate.is_none() && self.aggregate.is_empty() {
+            stop_after_n_rows = bound_scan_rows(stop_after_n_rows, state);
+        }
+        let cache_key = scan_cache_key(
+            &self.path,
+            self.predicate.as_ref(),
+            self.row_count.as_ref(),
+            hash_key(&(
+                &self.with_columns,
+                stop_after_n_rows,
+                &*self.schema,
+                &self.aggregate,
+            )),
+        )?;
+        if self.cache {
+            if let Some(df) = state.scan_cache_hit(&cache_key.key, cache_key.shared) {
+                return Ok(df);
+            }
         }
         // cache miss
-        let file = std::fs::File::open(&self.path).unwrap();
+        let file = std::fs::File::open(&self.path)?;
 
         let with_columns = mem::take(&mut self.with_columns);
         let schema = mem::take(&mut self.schema);
@@ -92,30 +357,48 @@ impl Executor for ParquetExec {
                 .collect()
         });
 
-        let stop_after_n_rows = set_n_rows(self.stop_after_n_rows);
         let aggregate = if self.aggregate.is_empty() {
             None
         } else {
             Some(self.aggregate.as_slice())
         };
-        let predicate = self
-            .predicate
-            .clone()
-            .map(|expr| Arc::new(PhysicalIoHelper { expr }) as Arc<dyn PhysicalIoExpr>);
+        let predicate = self.predicate.clone().map(|expr| {
+            Arc::new(PhysicalIoHelper {
+                expr,
+                chunk_predicate: self.chunk_predicate.clone().filter(|_| state.chunk_pruning),
+            }) as Arc<dyn PhysicalIoExpr>
+        });
 
         let df = ParquetReader::new(file)
+            .set_rechunk(false)
             .with_stop_after_n_rows(stop_after_n_rows)
+            .with_row_count(self.row_count.clone())
             .finish_with_scan_ops(
                 predicate,
                 aggregate,
                 projection.as_ref().map(|v| v.as_ref()),
             )?;
+        if state.has_output_limits() {
+            state.check_output(&format!("parquet scan of {:?}", self.path), &df)?;
+        }
 
         if self.cache {
-            state.store_cache(cache_key, df.clone())
+            state.store_scan_cache(cache_key.key, df.clone(), cache_key.shared)
         }
-        if std::env::var(POLARS_VERBOSE).is_ok() {
-            println!("parquet {:?} read", self.path);
+        if state.verbose {
+            log_verbose(
+                VerboseEvent::new(
+                    "parquet scan",
+                    format_args!(
+                        "parquet {:?} read {} rows with predicate: {:?}",
+                        self.path,
+                        df.height(),
+                        self.predicate
+                    ),
+                )
+                .with_path(path_str)
+                .with_rows(df.height()),
+            );
         }
 
         Ok(df)
@@ -135,12 +418,51 @@ pub struct CsvExec {
 impl Executor for CsvExec {
     fn execute(&mut self, state: &ExecutionState) -> Result<DataFrame> {
         let path_str = try_path_to_str(&self.path)?;
-        let state_key = match &self.predicate {
-            Some(predicate) => format!("{}{:?}", path_str, predicate.as_expression()),
-            None => path_str.to_string(),
+        let mut stop_after_n_rows = set_n_rows(self.options.stop_after_n_rows, state);
+        if self.predicate.is_none() && self.aggregate.is_empty() {
+            stop_after_n_rows = bound_scan_rows(stop_after_n_rows, state);
+        }
+        let spooled_data = self
+            .options
+            .spooled
+            .as_ref()
+            .and_then(|spooled| spooled.data().cloned());
+        // the schema holds the overwritten dtypes
+        let options = &self.options;
+        let options_hash = hash_key(&(
+            options.delimiter,
+            options.comment_char,
+            options.has_header,
+            options.skip_rows,
+            &options.with_columns,
+            options.ignore_errors,
+            &options.null_values,
+            options.eol_char,
+            &options.byte_range,
+            stop_after_n_rows,
+            &*self.schema,
+            &self.aggregate,
+        ));
+        let state_key = match &spooled_data {
+            // the path of in-memory data is unique and the data doesn't change
+            Some(_) => ScanCacheKey {
+                key: scan_cache_key_prefix(
+                    &self.path,
+                    self.predicate.as_ref(),
+                    self.options.row_count.as_ref(),
+                    options_hash,
+                )?,
+                shared: true,
+            },
+            None => scan_cache_key(
+                &self.path,
+                self.predicate.as_ref(),
+                self.options.row_count.as_ref(),
+                options_hash,
+            )?,
         };
         if self.options.cache {
-            if let Some(df) = state.cache_hit(&state_key) {
+            if let Some(df) = state.scan_cache_hit(&state_key.key, state_key.shared) {
                 return Ok(df);
             }
         }
@@ -157,10 +479,12 @@ impl Executor for CsvExec {
         if projected_len == 0 {
             with_columns = None;
         }
-        let stop_after_n_rows = set_n_rows(self.options.stop_after_n_rows);
 
-        let reader = CsvReader::from_path(&self.path)
-            .unwrap()
+        let reader: Box<dyn MmapBytesReader> = match spooled_data {
+            Some(data) => Box::new(Cursor::new(data)),
+            None => Box::new(std::fs::File::open(resolve_homedir(&self.path))?),
+        };
+        let mut reader = CsvReader::new(reader)
             .has_header(self.options.has_header)
             .with_schema(self.schema.clone())
             .with_delimiter(self.options.delimiter)
@@ -170,7 +494,12 @@ impl Executor for CsvExec {
             .with_columns(with_columns)
             .low_memory(self.options.low_memory)
             .with_null_values(self.options.null_values.clone())
+            .with_eol_char(self.options.eol_char)
+            .with_row_count(self.options.row_count.clone())
             .with_encoding(CsvEncoding::LossyUtf8);
+        if let Some(range) = self.options.byte_range.clone() {
+            reader = reader.with_byte_range(range);
+        }
 
         let aggregate = if self.aggregate.is_empty() {
             None
@@ -179,12 +508,27 @@ impl Executor for CsvExec {
         };
 
         let df = reader.finish_with_scan_ops(self.predicate.clone(), aggregate)?;
+        if state.has_output_limits() {
+            state.check_output(&format!("csv scan of {:?}", self.path), &df)?;
+        }
 
         if self.options.cache {
-            state.store_cache(state_key, df.clone());
+            state.store_scan_cache(state_key.key, df.clone(), state_key.shared);
         }
-        if std::env::var(POLARS_VERBOSE).is_ok() {
-            println!("csv {:?} read", self.path);
+        if state.verbose {
+            log_verbose(
+                VerboseEvent::new(
+                    "csv scan",
+                    format_args!(
+                        "csv {:?} read {} rows with predicate: {:?}",
+                        self.path,
+                        df.height(),
+                        self.predicate
+                    ),
+                )
+                .with_path(path_str)
+                .with_rows(df.height()),
+            );
         }
 
         Ok(df)
@@ -196,6 +540,7 @@ pub struct DataFrameExec {
     df: Arc<DataFrame>,
     projection: Option<Vec<Arc<dyn PhysicalExpr>>>,
     selection: Option<Arc<dyn PhysicalExpr>>,
+    chunk_predicate: Option<ChunkPredicate>,
 }
 
 impl DataFrameExec {
@@ -208,12 +553,24 @@ impl DataFrameExec {
             df,
             projection,
             selection,
+            chunk_predicate: None,
         }
     }
+
+    /// Skip the chunks for which the [ChunkPredicate] of the selection can't be true.
+    pub(crate) fn with_chunk_predicate(mut self, chunk_predicate: Option<ChunkPredicate>) -> Self {
+        self.chunk_predicate = chunk_predicate;
+        self
+    }
 }
 
 impl Executor for DataFrameExec {
     fn execute(&mut self, state: &ExecutionState) -> Result<DataFrame> {
+        // The address of the `Arc` identifies the frame. A modified copy of a frame lives in
+        // another allocation, so it never hits the mask of the original.
+        let df_ptr = Arc::as_ptr(&self.df) as usize;
+        // only a frame that is shared by multiple scans can be scanned again
+        let shared = Arc::strong_count(&self.df) > 1;
         let df = mem::take(&mut self.df);
         let mut df = Arc::try_unwrap(df).unwrap_or_else(|df| (*df).clone());
 
@@ -223,17 +580,32 @@ impl Executor for DataFrameExec {
         }
 
         if let Some(selection) = &self.selection {
-            let s = selection.evaluate(&df, state)?;
-            let mask = s.bool().map_err(|_| {
-                PolarsError::Other("filter predicate was not of type boolean".into())
-            })?;
-            df = df.filter(mask)?;
+            let key = (df_ptr, predicate_fingerprint(&**selection));
+            let compute =
+                || predicate_mask(&df, &**selection, self.chunk_predicate.as_ref(), state);
+            let mask = if shared {
+                // the lock is held while the mask is computed, so that scans of the same frame
+                // in parallel branches don't all compute it
+                match state.scan_masks.lock().unwrap().entry(key) {
+                    Entry::Occupied(entry) => entry.get().clone(),
+                    Entry::Vacant(entry) => entry.insert(compute()?).clone(),
+                }
+            } else {
+                // the last scan of a frame may find the mask of a previous scan
+                let cached = state.scan_masks.lock().unwrap().get(&key).cloned();
+                match cached {
+                    Some(mask) => mask,
+                    None => compute()?,
+                }
+            };
+            df = df.filter(&mask)?;
         }
 
-        if let Some(limit) = set_n_rows(None) {
-            Ok(df.head(Some(limit)))
-        } else {
-            Ok(df)
-        }
+        let df = match set_n_rows(None, state) {
+            Some(limit) => df.head(Some(limit)),
+            None => df,
+        };
+        state.check_output("dataframe scan", &df)?;
+        Ok(df)
     }
 }
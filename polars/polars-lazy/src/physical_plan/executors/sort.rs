@@ -31,6 +31,15 @@ impl Executor for SortExec {
             }
         }
 
+        #[cfg(feature = "spill")]
+        if super::spill::exceeds_memory_budget(&df, state) {
+            return super::spill::sort_spilled(
+                df,
+                column_names,
+                std::mem::take(&mut self.reverse),
+                state,
+            );
+        }
         df.sort(&column_names, std::mem::take(&mut self.reverse))
     }
 }
@@ -0,0 +1,227 @@
+//! Spill the working set of the sort and groupby executors to disk if the input exceeds the
+//! memory budget of the [ExecutionState].
+use super::groupby::groupby_helper;
+use crate::physical_plan::state::ExecutionState;
+use crate::prelude::*;
+use polars_core::prelude::*;
+use polars_core::utils::accumulate_dataframes_vertical;
+use polars_core::verbose::{log_verbose, VerboseEvent};
+use polars_io::prelude::*;
+use polars_io::temp_dir::TempDir;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Approximate number of bytes of a single value of `dtype`. For variable width types we count
+/// the offset and assume 16 bytes of data.
+fn dtype_width(dtype: &DataType) -> usize {
+    use DataType::*;
+    match dtype {
+        Boolean | UInt8 | Int8 => 1,
+        UInt16 | Int16 => 2,
+        UInt32 | Int32 | Float32 | Date32 | Categorical => 4,
+        UInt64 | Int64 | Float64 | Date64 | Time64(_) | Duration(_) => 8,
+        Null => 0,
+        _ => 24,
+    }
+}
+
+/// Approximate number of bytes of a row of `df`.
+fn row_width(df: &DataFrame) -> usize {
+    let width = df
+        .get_columns()
+        .iter()
+        .map(|s| dtype_width(s.dtype()))
+        .sum::<usize>();
+    std::cmp::max(width, 1)
+}
+
+/// Check if `df` is larger than the memory budget. The size is estimated from the number of rows
+/// and the width of the schema, and doesn't look at the data.
+///
+/// Categorical and object columns cannot be written to disk without losing their type, so
+/// DataFrames with such columns are never spilled.
+pub(crate) fn exceeds_memory_budget(df: &DataFrame, state: &ExecutionState) -> bool {
+    let budget = match state.memory_budget {
+        Some(budget) => budget,
+        None => return false,
+    };
+    let can_spill = df.get_columns().iter().all(|s| match s.dtype() {
+        DataType::Categorical => false,
+        #[cfg(feature = "object")]
+        DataType::Object(_) => false,
+        _ => true,
+    });
+    can_spill && df.height() * row_width(df) > budget
+}
+
+fn write_ipc(df: &DataFrame, path: &Path) -> Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+    IpcWriter::new(&mut file).finish(df)?;
+    file.flush()?;
+    Ok(())
+}
+
+fn read_ipc(path: &Path) -> Result<DataFrame> {
+    let file = BufReader::new(File::open(path)?);
+    IpcReader::new(file).finish()
+}
+
+/// Sort `df` in runs that fit in the memory budget. Every sorted run is written to disk in
+/// blocks, which are read back one at a time per run while the runs are merged.
+pub(crate) fn sort_spilled(
+    df: DataFrame,
+    by_column: Vec<String>,
+    reverse: Vec<bool>,
+    state: &ExecutionState,
+) -> Result<DataFrame> {
+    let budget = state.memory_budget.unwrap_or(0);
+    let run_len = std::cmp::max(budget / row_width(&df), 1);
+    let n_runs = (df.height() + run_len - 1) / run_len;
+    // a block of every run is held in memory during the merge
+    let block_len = std::cmp::max(run_len / std::cmp::max(n_runs, 1), 1);
+    if state.verbose {
+        log_verbose(VerboseEvent::new("spill", format_args!(
+            "sort exceeds the memory budget of {} bytes: spilling {} sorted runs of {} rows to disk",
+            budget, n_runs, run_len
+        )));
+    }
+    let dir = TempDir::new("sort")?;
+    let runs = (0..n_runs)
+        .map(|i| {
+            let run = df
+                .slice((i * run_len) as i64, run_len)
+                .sort(&by_column, reverse.clone())?;
+            let n_blocks = (run.height() + block_len - 1) / block_len;
+            let paths = (0..n_blocks)
+                .map(|j| {
+                    let path = dir.file_path(&format!("run-{}-{}.ipc", i, j));
+                    write_ipc(&run.slice((j * block_len) as i64, block_len), &path)?;
+                    Ok(path)
+                })
+                .collect::<Result<Vec<PathBuf>>>()?;
+            if state.verbose {
+                log_verbose(VerboseEvent::new(
+                    "spill",
+                    format_args!("spilled sorted run {}/{}", i + 1, n_runs),
+                ));
+            }
+            Ok(paths)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let schema = df.schema();
+    drop(df);
+
+    if state.verbose {
+        log_verbose(VerboseEvent::new(
+            "spill",
+            format_args!("merging {} sorted runs", n_runs),
+        ));
+    }
+    let runs = runs
+        .into_iter()
+        .map(|paths| {
+            paths.into_iter().map(|path| {
+                let block = read_ipc(&path)?;
+                // the block is not read again
+                std::fs::remove_file(&path)?;
+                cast_to_schema(block, &schema)
+            })
+        })
+        .collect();
+    let by_column = by_column.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+    DataFrame::merge_sorted_batched_runs(runs, &by_column, &reverse)
+}
+
+/// Data read from IPC can differ in the types arrow doesn't distinguish.
+fn cast_to_schema(df: DataFrame, schema: &Schema) -> Result<DataFrame> {
+    let columns = df
+        .get_columns()
+        .iter()
+        .zip(schema.fields())
+        .map(|(s, fld)| {
+            if s.dtype() == fld.data_type() {
+                Ok(s.clone())
+            } else {
+                s.cast_with_dtype(fld.data_type())
+            }
+        })
+        .collect::<Result<_>>()?;
+    Ok(DataFrame::new_no_checks(columns))
+}
+
+/// Partition the rows of `df` on the hash of the `keys` into files that fit in the memory
+/// budget. The partitions have no groups in common, so they are aggregated one at a time and
+/// the results are concatenated.
+pub(crate) fn groupby_spilled(
+    df: DataFrame,
+    keys: Vec<Series>,
+    aggs: &[Arc<dyn PhysicalExpr>],
+    apply: Option<&Arc<dyn DataFrameUdf>>,
+    drop_null_keys: bool,
+    state: &ExecutionState,
+) -> Result<DataFrame> {
+    let budget = std::cmp::max(state.memory_budget.unwrap_or(0), 1);
+    let keys = DataFrame::new(keys)?;
+    let size = df.height() * (row_width(&df) + row_width(&keys));
+    let n_partitions = std::cmp::max((size + budget - 1) / budget, 1);
+    if state.verbose {
+        log_verbose(VerboseEvent::new(
+            "spill",
+            format_args!(
+                "groupby exceeds the memory budget of {} bytes: spilling {} partitions to disk",
+                budget, n_partitions
+            ),
+        ));
+    }
+
+    let hashes = keys.hash_rows(0)?;
+    let mut partition_idx = vec![Vec::new(); n_partitions];
+    for (idx, hash) in hashes.into_no_null_iter().enumerate() {
+        partition_idx[(hash % n_partitions as u64) as usize].push(idx as IdxSize);
+    }
+
+    let dir = TempDir::new("groupby")?;
+    let df_schema = df.schema();
+    let keys_schema = keys.schema();
+    let partitions = partition_idx
+        .into_iter()
+        .enumerate()
+        .filter(|(_, idx)| !idx.is_empty())
+        .map(|(i, idx)| {
+            let idx = IdxCa::new_from_slice("", &idx);
+            let df_path = dir.file_path(&format!("partition-{}.ipc", i));
+            let keys_path = dir.file_path(&format!("partition-{}-keys.ipc", i));
+            write_ipc(&df.take(&idx), &df_path)?;
+            write_ipc(&keys.take(&idx), &keys_path)?;
+            Ok((df_path, keys_path))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    drop(df);
+    drop(keys);
+
+    let n_written = partitions.len();
+    let dfs = partitions
+        .iter()
+        .enumerate()
+        .map(|(i, (df_path, keys_path))| {
+            if state.verbose {
+                log_verbose(VerboseEvent::new(
+                    "spill",
+                    format_args!("aggregating spilled partition {}/{}", i + 1, n_written),
+                ));
+            }
+            let df = cast_to_schema(read_ipc(df_path)?, &df_schema)?;
+            let keys = cast_to_schema(read_ipc(keys_path)?, &keys_schema)?;
+            groupby_helper(
+                df,
+                keys.get_columns().to_vec(),
+                aggs,
+                apply,
+                drop_null_keys,
+                state,
+            )
+        })
+        .collect::<Result<Vec<_>>>()?;
+    accumulate_dataframes_vertical(dfs)
+}
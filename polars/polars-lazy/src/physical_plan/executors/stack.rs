@@ -25,7 +25,7 @@ impl Executor for StackExec {
                 .map(|expr| {
                     expr.evaluate(&df, state).map(|series| {
                         // literal series. Should be whole column size
-                        if series.len() == 1 && height > 1 {
+                        if series.len() == 1 && height != 1 {
                             series.expand_at_index(0, height)
                         } else {
                             series
@@ -35,10 +35,8 @@ impl Executor for StackExec {
                 .collect::<Result<Vec<_>>>()
         })?;
 
-        for s in res {
-            let name = s.name().to_string();
-            df.replace_or_add(&name, s)?;
-        }
+        df.add_or_replace_columns(res)?;
+        state.check_output("with_columns", &df)?;
 
         state.clear_expr_cache();
         Ok(df)
@@ -0,0 +1,39 @@
+use crate::physical_plan::state::ExecutionState;
+use crate::prelude::*;
+use polars_core::functions::concat_df_diagonal;
+use polars_core::prelude::*;
+use polars_core::utils::concat_df;
+
+pub struct UnionExec {
+    pub inputs: Vec<Box<dyn Executor>>,
+    pub how: ConcatHow,
+}
+
+impl Executor for UnionExec {
+    fn execute(&mut self, state: &ExecutionState) -> Result<DataFrame> {
+        let dfs = self
+            .inputs
+            .iter_mut()
+            .map(|input| input.execute(state))
+            .collect::<Result<Vec<_>>>()?;
+        // the output is as large as the inputs together, so it is checked before concatenating
+        state.check_output_rows("union", dfs.iter().map(|df| df.height()).sum())?;
+        if state.memory_limit.is_some() {
+            state.check_output_bytes("union", dfs.iter().map(|df| df.estimated_size()).sum())?;
+        }
+
+        match &self.how {
+            ConcatHow::Vertical => concat_df(&dfs),
+            ConcatHow::Diagonal => concat_df_diagonal(&dfs),
+            // Fold the inputs with a two-way merge.
+            #[cfg(feature = "merge_sorted")]
+            ConcatHow::MergeSorted(key) => {
+                let mut dfs = dfs.into_iter();
+                let first = dfs.next().ok_or_else(|| {
+                    PolarsError::NoData("cannot concatenate an empty list of inputs".into())
+                })?;
+                dfs.try_fold(first, |acc, df| acc.merge_sorted(&df, key))
+            }
+        }
+    }
+}
@@ -3,7 +3,9 @@ use crate::physical_plan::PhysicalAggregation;
 use crate::prelude::*;
 use polars_arrow::array::ValueSize;
 use polars_core::chunked_array::builder::get_list_builder;
-use polars_core::frame::groupby::{fmt_groupby_column, GroupByMethod, GroupTuples};
+use polars_core::frame::groupby::{
+    agg_null_count, fmt_groupby_column, groups_to_list, GroupByMethod, GroupTuples,
+};
 use polars_core::utils::NoNull;
 use polars_core::{prelude::*, POOL};
 use std::sync::Arc;
@@ -11,15 +13,24 @@ use std::sync::Arc;
 pub(crate) struct AggregationExpr {
     pub(crate) expr: Arc<dyn PhysicalExpr>,
     pub(crate) agg_type: GroupByMethod,
+    expression: Expr,
 }
 
 impl AggregationExpr {
-    pub fn new(expr: Arc<dyn PhysicalExpr>, agg_type: GroupByMethod) -> Self {
-        Self { expr, agg_type }
+    pub fn new(expr: Arc<dyn PhysicalExpr>, agg_type: GroupByMethod, expression: Expr) -> Self {
+        Self {
+            expr,
+            agg_type,
+            expression,
+        }
     }
 }
 
 impl PhysicalExpr for AggregationExpr {
+    fn as_expression(&self) -> &Expr {
+        &self.expression
+    }
+
     fn evaluate(&self, _df: &DataFrame, _state: &ExecutionState) -> Result<Series> {
         unimplemented!()
     }
@@ -79,6 +90,11 @@ impl PhysicalAggregation for AggregationExpr {
                 ca.rename(&new_name);
                 Ok(Some(ca.into_inner().into_series()))
             }
+            GroupByMethod::NullCount => {
+                let mut ca = agg_null_count(&series, &groups);
+                ca.rename(&new_name);
+                Ok(Some(ca.into_series()))
+            }
             GroupByMethod::First => {
                 let mut agg_s = series.agg_first(&groups);
                 agg_s.rename(&new_name);
@@ -101,16 +117,12 @@ impl PhysicalAggregation for AggregationExpr {
                 let opt_agg = series.agg_list(&groups);
                 Ok(rename_option_series(opt_agg, &new_name))
             }
+            GroupByMethod::Flatten => {
+                let opt_agg = series.list()?.agg_flatten(&groups);
+                Ok(rename_option_series(opt_agg, &new_name))
+            }
             GroupByMethod::Groups => {
-                let mut column: ListChunked = groups
-                    .iter()
-                    .map(|(_first, idx)| {
-                        let ca: NoNull<UInt32Chunked> = idx.iter().map(|&v| v as u32).collect();
-                        ca.into_inner().into_series()
-                    })
-                    .collect();
-
-                column.rename(&new_name);
+                let column = groups_to_list(&new_name, groups);
                 Ok(Some(column.into_series()))
             }
             GroupByMethod::Std => {
@@ -138,14 +150,24 @@ impl PhysicalAggregation for AggregationExpr {
             GroupByMethod::Mean => {
                 let series = self.expr.evaluate(df, state)?;
                 let mut new_name = fmt_groupby_column(series.name(), self.agg_type);
-                let agg_s = series.agg_sum(groups);
+                // we expect f64 from mean, so we sum as f64, then the sums of integers don't
+                // overflow and the sums of f32 don't lose precision.
+                // a duration is kept, the final division truncates to its time unit
+                let agg_s = match series.dtype() {
+                    DataType::Duration(_) => series.agg_sum(groups),
+                    _ if series.is_numeric() => {
+                        series.cast_with_dtype(&DataType::Float64)?.agg_sum(groups)
+                    }
+                    _ => series
+                        .agg_sum(groups)
+                        .map(|s| s.cast_with_dtype(&DataType::Float64))
+                        .transpose()?,
+                };
 
                 // If the aggregation is successful,
                 // we also count the valid values (len - null count)
                 // this is needed to compute the final mean.
-                if let Some(agg_s) = agg_s {
-                    // we expect f64 from mean, so we already cast
-                    let mut agg_s = agg_s.cast_with_dtype(&DataType::Float64)?;
+                if let Some(mut agg_s) = agg_s {
                     agg_s.rename(&new_name);
                     new_name.push_str("__POLARS_MEAN_COUNT");
                     let mut count_s = series.agg_valid_count(groups).unwrap();
@@ -187,6 +209,16 @@ impl PhysicalAggregation for AggregationExpr {
                 let agg_s = agg_s.map(|agg_s| &agg_s / &agg_count.unwrap());
                 Ok(rename_option_series(agg_s, &new_name))
             }
+            GroupByMethod::NullCount => {
+                // sum the null counts of the partitions
+                let series = self.expr.evaluate(final_df, state)?;
+                let new_name = fmt_groupby_column(series.name(), self.agg_type);
+                let agg_s = series
+                    .agg_sum(groups)
+                    .map(|s| s.cast::<UInt32Type>())
+                    .transpose()?;
+                Ok(rename_option_series(agg_s, &new_name))
+            }
             GroupByMethod::List => {
                 let series = self.expr.evaluate(final_df, state)?;
                 let ca = series.list().unwrap();
@@ -251,15 +283,24 @@ impl PhysicalAggregation for CastExpr {
 pub struct AggQuantileExpr {
     pub(crate) expr: Arc<dyn PhysicalExpr>,
     pub(crate) quantile: f64,
+    expression: Expr,
 }
 
 impl AggQuantileExpr {
-    pub fn new(expr: Arc<dyn PhysicalExpr>, quantile: f64) -> Self {
-        Self { expr, quantile }
+    pub fn new(expr: Arc<dyn PhysicalExpr>, quantile: f64, expression: Expr) -> Self {
+        Self {
+            expr,
+            quantile,
+            expression,
+        }
     }
 }
 
 impl PhysicalExpr for AggQuantileExpr {
+    fn as_expression(&self) -> &Expr {
+        &self.expression
+    }
+
     fn evaluate(&self, _df: &DataFrame, _state: &ExecutionState) -> Result<Series> {
         unimplemented!()
     }
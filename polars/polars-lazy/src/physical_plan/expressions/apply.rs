@@ -1,3 +1,4 @@
+use crate::logical_plan::Context;
 use crate::physical_plan::state::ExecutionState;
 use crate::physical_plan::PhysicalAggregation;
 use crate::prelude::*;
@@ -9,7 +10,7 @@ use std::sync::Arc;
 pub struct ApplyExpr {
     pub inputs: Vec<Arc<dyn PhysicalExpr>>,
     pub function: NoEq<Arc<dyn SeriesUdf>>,
-    pub output_type: Option<DataType>,
+    pub output_type: GetOutput,
     pub expr: Expr,
     pub collect_groups: bool,
 }
@@ -33,13 +34,10 @@ impl PhysicalExpr for ApplyExpr {
         Ok(out)
     }
     fn to_field(&self, input_schema: &Schema) -> Result<Field> {
-        match &self.output_type {
-            Some(output_type) => {
-                let input_field = self.inputs[0].to_field(input_schema)?;
-                Ok(Field::new(input_field.name(), output_type.clone()))
-            }
-            None => self.inputs[0].to_field(input_schema),
-        }
+        self.output_type
+            .get_field(input_schema, Context::Default, self.inputs.len(), |i| {
+                self.inputs[i].to_field(input_schema)
+            })
     }
     fn as_agg_expr(&self) -> Result<&dyn PhysicalAggregation> {
         Ok(self)
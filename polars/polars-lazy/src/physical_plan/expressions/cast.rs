@@ -6,15 +6,24 @@ use std::sync::Arc;
 pub struct CastExpr {
     pub(crate) input: Arc<dyn PhysicalExpr>,
     pub(crate) data_type: DataType,
+    expr: Expr,
 }
 
 impl CastExpr {
-    pub fn new(input: Arc<dyn PhysicalExpr>, data_type: DataType) -> Self {
-        Self { input, data_type }
+    pub fn new(input: Arc<dyn PhysicalExpr>, data_type: DataType, expr: Expr) -> Self {
+        Self {
+            input,
+            data_type,
+            expr,
+        }
     }
 }
 
 impl PhysicalExpr for CastExpr {
+    fn as_expression(&self) -> &Expr {
+        &self.expr
+    }
+
     fn evaluate(&self, df: &DataFrame, state: &ExecutionState) -> Result<Series> {
         let series = self.input.evaluate(df, state)?;
         // this is quite dirty
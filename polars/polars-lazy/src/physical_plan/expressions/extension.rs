@@ -0,0 +1,158 @@
+use crate::physical_plan::state::ExecutionState;
+use crate::physical_plan::PhysicalAggregation;
+use crate::prelude::*;
+use polars_core::frame::groupby::GroupTuples;
+use polars_core::prelude::*;
+use std::borrow::Cow;
+use std::sync::Arc;
+
+/// Evaluates the inputs and calls an [ExprPlugin] on them.
+pub struct ExtensionExpr {
+    pub inputs: Vec<Arc<dyn PhysicalExpr>>,
+    pub plugin: NoEq<Arc<dyn ExprPlugin>>,
+    pub expr: Expr,
+}
+
+impl ExtensionExpr {
+    fn call_plugin(&self, inputs: &[Series], state: &ExecutionState) -> Result<Series> {
+        let mut out = self.plugin.evaluate(inputs, state)?;
+        if let Some(s) = inputs.first() {
+            if s.name() != out.name() {
+                out.rename(s.name());
+            }
+        }
+        Ok(out)
+    }
+}
+
+impl PhysicalExpr for ExtensionExpr {
+    fn as_expression(&self) -> &Expr {
+        &self.expr
+    }
+
+    fn evaluate(&self, df: &DataFrame, state: &ExecutionState) -> Result<Series> {
+        let inputs = self
+            .inputs
+            .iter()
+            .map(|e| e.evaluate(df, state))
+            .collect::<Result<Vec<_>>>()?;
+        self.call_plugin(&inputs, state)
+    }
+
+    fn to_field(&self, input_schema: &Schema) -> Result<Field> {
+        let fields = self
+            .inputs
+            .iter()
+            .map(|e| e.to_field(input_schema))
+            .collect::<Result<Vec<_>>>()?;
+        self.plugin.to_field(&fields)
+    }
+
+    fn as_agg_expr(&self) -> Result<&dyn PhysicalAggregation> {
+        Ok(self)
+    }
+}
+
+impl PhysicalAggregation for ExtensionExpr {
+    fn aggregate(
+        &self,
+        df: &DataFrame,
+        groups: &GroupTuples,
+        state: &ExecutionState,
+    ) -> Result<Option<Series>> {
+        let inputs = self
+            .inputs
+            .iter()
+            .map(|e| e.evaluate_on_groups(df, groups, state))
+            .collect::<Result<Vec<_>>>()?;
+
+        // an elementwise plugin can be called once if the inputs are not changed per group
+        if self.plugin.is_elementwise()
+            && !self.plugin.is_aggregation()
+            && inputs
+                .iter()
+                .all(|(s, g)| s.len() == df.height() && matches!(g, Cow::Borrowed(_)))
+        {
+            let inputs = inputs.into_iter().map(|(s, _)| s).collect::<Vec<_>>();
+            let out = self.call_plugin(&inputs, state)?;
+            return Ok(out.agg_list(groups));
+        }
+
+        if self.plugin.is_aggregation()
+            && inputs
+                .iter()
+                .all(|(s, g)| s.len() == df.height() && matches!(g, Cow::Borrowed(_)))
+        {
+            let inputs = inputs.iter().map(|(s, _)| s.clone()).collect::<Vec<_>>();
+            if let Some(out) = self.plugin.aggregate_groups(&inputs, groups, state) {
+                let mut out = out?;
+                if let Some(s) = inputs.first() {
+                    out.rename(s.name());
+                }
+                return Ok(Some(out));
+            }
+        }
+
+        let lists = match inputs
+            .iter()
+            .map(|(s, groups)| s.agg_list(groups))
+            .collect::<Option<Vec<_>>>()
+        {
+            Some(lists) => lists,
+            None => return Ok(None),
+        };
+        let name = lists[0].name().to_string();
+        let takers = lists
+            .iter()
+            .map(|s| s.list().map(|ca| ca.take_rand()))
+            .collect::<Result<Vec<_>>>()?;
+
+        // container that will hold the arguments &[Series]
+        let mut args = Vec::with_capacity(takers.len());
+        let out = (0..lists[0].len())
+            .map(|i| {
+                args.clear();
+                takers.iter().for_each(|taker| {
+                    if let Some(s) = taker.get(i) {
+                        args.push(s);
+                    }
+                });
+                if args.len() == takers.len() {
+                    self.call_plugin(&args, state).map(Some)
+                } else {
+                    Ok(None)
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if self.plugin.is_aggregation() {
+            let dtype = match out.iter().flatten().next() {
+                Some(s) => s.dtype().clone(),
+                None => return Ok(None),
+            };
+            let mut agg = Series::full_null(&name, 0, &dtype);
+            for opt_s in out {
+                match opt_s {
+                    Some(s) if s.len() == 1 => agg.append(&s)?,
+                    Some(s) => {
+                        return Err(PolarsError::ShapeMisMatch(
+                            format!(
+                                "aggregation {} returned {} values for a group",
+                                self.plugin.name(),
+                                s.len()
+                            )
+                            .into(),
+                        ))
+                    }
+                    None => agg.append(&Series::full_null(&name, 1, &dtype))?,
+                };
+            }
+            // every group was appended as a chunk
+            Ok(Some(agg.rechunk()))
+        } else {
+            let mut ca: ListChunked = out.into_iter().collect();
+            ca.rename(&name);
+            Ok(Some(ca.into_series()))
+        }
+    }
+}
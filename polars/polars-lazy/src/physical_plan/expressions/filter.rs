@@ -8,12 +8,17 @@ use std::sync::Arc;
 
 pub struct FilterExpr {
     pub(crate) input: Arc<dyn PhysicalExpr>,
-    pub(crate) by: Arc<dyn PhysicalExpr>,
+    /// The predicate, `None` filters the input by its own validity.
+    pub(crate) by: Option<Arc<dyn PhysicalExpr>>,
     expr: Expr,
 }
 
 impl FilterExpr {
-    pub fn new(input: Arc<dyn PhysicalExpr>, by: Arc<dyn PhysicalExpr>, expr: Expr) -> Self {
+    pub fn new(
+        input: Arc<dyn PhysicalExpr>,
+        by: Option<Arc<dyn PhysicalExpr>>,
+        expr: Expr,
+    ) -> Self {
         Self { input, by, expr }
     }
 }
@@ -25,8 +30,13 @@ impl PhysicalExpr for FilterExpr {
 
     fn evaluate(&self, df: &DataFrame, state: &ExecutionState) -> Result<Series> {
         let series = self.input.evaluate(df, state)?;
-        let predicate = self.by.evaluate(df, state)?;
-        series.filter(predicate.bool()?)
+        match &self.by {
+            Some(by) => {
+                let predicate = by.evaluate(df, state)?;
+                series.filter(predicate.bool()?)
+            }
+            None => Ok(series.drop_nulls()),
+        }
     }
 
     fn evaluate_on_groups<'a>(
@@ -36,14 +46,17 @@ impl PhysicalExpr for FilterExpr {
         state: &ExecutionState,
     ) -> Result<(Series, Cow<'a, GroupTuples>)> {
         let s = self.input.evaluate(df, state)?;
-        let predicate_s = self.by.evaluate(df, state)?;
+        let predicate_s = match &self.by {
+            Some(by) => by.evaluate(df, state)?,
+            None => s.is_not_null().into_series(),
+        };
         let predicate = predicate_s.bool()?;
 
         let groups = POOL.install(|| {
             groups
                 .par_iter()
                 .map(|(first, idx)| {
-                    let idx: Vec<u32> = idx
+                    let idx: Vec<IdxSize> = idx
                         .iter()
                         .filter_map(|i| match predicate.get(*i as usize) {
                             Some(true) => Some(*i),
@@ -79,6 +79,11 @@ impl PhysicalExpr for LiteralExpr {
                 let timestamp = naive_datetime_to_date64(ndt);
                 Date64Chunked::full("literal", timestamp, 1).into_series()
             }
+            #[cfg(all(feature = "temporal", feature = "dtype-date32"))]
+            Date(nd) => {
+                use polars_core::chunked_array::temporal::conversion::*;
+                Date32Chunked::full("literal", naive_date_to_date32(*nd), 1).into_series()
+            }
             Series(series) => series.deref().clone(),
         };
         Ok(s)
@@ -109,6 +114,8 @@ impl PhysicalExpr for LiteralExpr {
             Range { data_type, .. } => Field::new(name, data_type.clone()),
             #[cfg(all(feature = "temporal", feature = "dtype-date64"))]
             DateTime(_) => Field::new(name, DataType::Date64),
+            #[cfg(all(feature = "temporal", feature = "dtype-date32"))]
+            Date(_) => Field::new(name, DataType::Date32),
             Series(s) => s.field().clone(),
         };
         Ok(field)
@@ -5,6 +5,7 @@ pub(crate) mod binary;
 pub(crate) mod binary_function;
 pub(crate) mod cast;
 pub(crate) mod column;
+pub(crate) mod extension;
 pub(crate) mod filter;
 pub(crate) mod is_not_null;
 pub(crate) mod is_null;
@@ -17,20 +18,20 @@ pub(crate) mod take;
 pub(crate) mod ternary;
 pub(crate) mod window;
 
+use crate::physical_plan::executors::filter::ChunkPredicate;
 use crate::physical_plan::state::ExecutionState;
 use crate::prelude::*;
 use polars_core::frame::groupby::GroupTuples;
 use polars_core::prelude::*;
-use polars_io::PhysicalIoExpr;
+use polars_io::{ColumnStats, PhysicalIoExpr};
 use std::borrow::Cow;
+use std::fmt::{Debug, Formatter};
 
 /// Take a DataFrame and evaluate the expressions.
 /// Implement this for Column, lt, eq, etc
 pub trait PhysicalExpr: Send + Sync {
-    fn as_expression(&self) -> &Expr {
-        // for instance not needed for aggregations (for now)
-        unimplemented!()
-    }
+    /// Get the expression this physical expression was planned from.
+    fn as_expression(&self) -> &Expr;
 
     /// Take a DataFrame and evaluate the expression.
     fn evaluate(&self, df: &DataFrame, _state: &ExecutionState) -> Result<Series>;
@@ -75,17 +76,32 @@ pub trait PhysicalExpr: Send + Sync {
     }
 }
 
+impl Debug for dyn PhysicalExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.as_expression())
+    }
+}
+
 /// Wrapper struct that allow us to use a PhysicalExpr in polars-io.
 ///
 /// This is used to filter rows during the scan of file.
 pub struct PhysicalIoHelper {
     pub expr: Arc<dyn PhysicalExpr>,
+    /// Used to skip the batches of the file of which the statistics don't match the predicate.
+    pub chunk_predicate: Option<Arc<ChunkPredicate>>,
 }
 
 impl PhysicalIoExpr for PhysicalIoHelper {
     fn evaluate(&self, df: &DataFrame) -> Result<Series> {
         self.expr.evaluate(df, &Default::default())
     }
+
+    fn skip_batch(&self, stats: &dyn Fn(&str) -> Option<ColumnStats>) -> bool {
+        match &self.chunk_predicate {
+            Some(chunk_predicate) => chunk_predicate.skip_batch(stats),
+            None => false,
+        }
+    }
 }
 
 pub trait PhysicalAggregation: Send + Sync {
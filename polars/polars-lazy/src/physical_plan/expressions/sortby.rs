@@ -1,22 +1,23 @@
 use crate::physical_plan::state::ExecutionState;
 use crate::prelude::*;
 use polars_core::frame::groupby::GroupTuples;
+use polars_core::functions::argsort_by;
 use polars_core::prelude::*;
 use std::borrow::Cow;
 use std::sync::Arc;
 
 pub struct SortByExpr {
     pub(crate) input: Arc<dyn PhysicalExpr>,
-    pub(crate) by: Arc<dyn PhysicalExpr>,
-    pub(crate) reverse: bool,
+    pub(crate) by: Vec<Arc<dyn PhysicalExpr>>,
+    pub(crate) reverse: Vec<bool>,
     pub(crate) expr: Expr,
 }
 
 impl SortByExpr {
     pub fn new(
         input: Arc<dyn PhysicalExpr>,
-        by: Arc<dyn PhysicalExpr>,
-        reverse: bool,
+        by: Vec<Arc<dyn PhysicalExpr>>,
+        reverse: Vec<bool>,
         expr: Expr,
     ) -> Self {
         Self {
@@ -26,6 +27,28 @@ impl SortByExpr {
             expr,
         }
     }
+
+    fn argsort(&self, by: &[Series]) -> Result<IdxCa> {
+        if by.len() == 1 {
+            Ok(by[0].argsort(self.reverse[0]))
+        } else {
+            argsort_by(by, &self.reverse)
+        }
+    }
+}
+
+fn check_lengths(series: &Series, by: &Series) -> Result<()> {
+    if series.len() != by.len() {
+        return Err(PolarsError::ShapeMisMatch(
+            format!(
+                "sort_by expects the `by` expressions to have the same length as the input, got {} and {}",
+                by.len(),
+                series.len()
+            )
+            .into(),
+        ));
+    }
+    Ok(())
 }
 
 impl PhysicalExpr for SortByExpr {
@@ -35,8 +58,16 @@ impl PhysicalExpr for SortByExpr {
 
     fn evaluate(&self, df: &DataFrame, state: &ExecutionState) -> Result<Series> {
         let series = self.input.evaluate(df, state)?;
-        let series_sort_by = self.by.evaluate(df, state)?;
-        let sorted_idx = series_sort_by.argsort(self.reverse);
+        let series_sort_by = self
+            .by
+            .iter()
+            .map(|e| {
+                let s = e.evaluate(df, state)?;
+                check_lengths(&series, &s)?;
+                Ok(s)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let sorted_idx = self.argsort(&series_sort_by)?;
 
         // Safety:
         // sorted index are within bounds
@@ -50,19 +81,37 @@ impl PhysicalExpr for SortByExpr {
         groups: &'a GroupTuples,
         state: &ExecutionState,
     ) -> Result<(Series, Cow<'a, GroupTuples>)> {
-        let (series, _) = self.input.evaluate_on_groups(df, groups, state)?;
-        let (series_sort_by, groups) = self.by.evaluate_on_groups(df, groups, state)?;
+        let (series, input_groups) = self.input.evaluate_on_groups(df, groups, state)?;
+        let series_sort_by = self
+            .by
+            .iter()
+            .map(|e| e.evaluate_on_groups(df, groups, state))
+            .collect::<Result<Vec<_>>>()?;
 
-        let groups = groups
+        // Per group we gather the values of the `by` expressions, argsort them and use that
+        // ordering to reorder the group indices of the input.
+        let groups = input_groups
             .iter()
-            .map(|(_first, idx)| {
-                // Safety:
-                // Group tuples are always in bounds
-                let group = unsafe {
-                    series_sort_by.take_iter_unchecked(&mut idx.iter().map(|i| *i as usize))
-                };
+            .enumerate()
+            .map(|(group_idx, (first, idx))| {
+                let by_group = series_sort_by
+                    .iter()
+                    .map(|(s, by_groups)| {
+                        let by_idx = &by_groups[group_idx].1;
+                        if by_idx.len() != idx.len() {
+                            return Err(PolarsError::ShapeMisMatch(
+                                "sort_by expects the `by` expressions to have the same group lengths as the input".into(),
+                            ));
+                        }
+                        // Safety:
+                        // Group tuples are always in bounds
+                        Ok(unsafe {
+                            s.take_iter_unchecked(&mut by_idx.iter().map(|i| *i as usize))
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?;
 
-                let sorted_idx = group.argsort(self.reverse);
+                let sorted_idx = self.argsort(&by_group)?;
 
                 let new_idx: Vec<_> = sorted_idx
                     .cont_slice()
@@ -73,9 +122,9 @@ impl PhysicalExpr for SortByExpr {
                         unsafe { *idx.get_unchecked(i as usize) }
                     })
                     .collect();
-                (new_idx[0], new_idx)
+                Ok((new_idx.first().copied().unwrap_or(*first), new_idx))
             })
-            .collect();
+            .collect::<Result<GroupTuples>>()?;
 
         Ok((series, Cow::Owned(groups)))
     }
@@ -89,40 +138,14 @@ impl PhysicalExpr for SortByExpr {
     }
 }
 impl PhysicalAggregation for SortByExpr {
-    // As a final aggregation a Sort returns a list array.
+    // As a final aggregation a SortBy returns a list array.
     fn aggregate(
         &self,
         df: &DataFrame,
         groups: &GroupTuples,
         state: &ExecutionState,
     ) -> Result<Option<Series>> {
-        let s = self.input.evaluate(df, state)?;
-        let s_sort_by = self.by.evaluate(df, state)?;
-
-        let s_sort_by = s_sort_by.agg_list(groups).ok_or_else(|| {
-            PolarsError::Other(format!("cannot aggregate {:?} as list array", self.expr).into())
-        })?;
-
-        let agg_s = s.agg_list(groups);
-        let out = agg_s.map(|s| {
-            s.list()
-                .unwrap()
-                .into_iter()
-                .zip(s_sort_by.list().unwrap())
-                .map(|(opt_s, opt_sort_by)| {
-                    match (opt_s, opt_sort_by) {
-                        (Some(s), Some(sort_by)) => {
-                            let sorted_idx = sort_by.argsort(self.reverse);
-                            // Safety:
-                            // sorted index are within bounds
-                            unsafe { s.take_unchecked(&sorted_idx) }.ok()
-                        }
-                        _ => None,
-                    }
-                })
-                .collect::<ListChunked>()
-                .into_series()
-        });
-        Ok(out)
+        let (s, groups) = self.evaluate_on_groups(df, groups, state)?;
+        Ok(s.agg_list(&groups))
     }
 }
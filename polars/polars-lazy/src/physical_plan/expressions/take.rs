@@ -6,19 +6,28 @@ use std::sync::Arc;
 pub struct TakeExpr {
     pub(crate) expr: Arc<dyn PhysicalExpr>,
     pub(crate) idx: Arc<dyn PhysicalExpr>,
+    expression: Expr,
 }
 
 impl TakeExpr {
-    pub fn new(expr: Arc<dyn PhysicalExpr>, idx: Arc<dyn PhysicalExpr>) -> Self {
-        Self { expr, idx }
+    pub fn new(expr: Arc<dyn PhysicalExpr>, idx: Arc<dyn PhysicalExpr>, expression: Expr) -> Self {
+        Self {
+            expr,
+            idx,
+            expression,
+        }
     }
 }
 
 impl PhysicalExpr for TakeExpr {
+    fn as_expression(&self) -> &Expr {
+        &self.expression
+    }
+
     fn evaluate(&self, df: &DataFrame, state: &ExecutionState) -> Result<Series> {
         let series = self.expr.evaluate(df, state)?;
         let idx = self.idx.evaluate(df, state)?;
-        let idx_ca = idx.u32()?;
+        let idx_ca = idx.idx()?;
 
         Ok(series.take(idx_ca))
     }
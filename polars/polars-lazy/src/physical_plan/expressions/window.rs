@@ -1,11 +1,22 @@
 use crate::logical_plan::Context;
+use crate::physical_plan::executors::groupby::groupby_keys;
 use crate::physical_plan::state::ExecutionState;
 use crate::prelude::*;
-use polars_core::frame::groupby::GroupBy;
-use polars_core::frame::hash_join::private_left_join_multiple_keys;
+use polars_core::frame::groupby::{GroupBy, GroupTuples, GroupbyOptions};
 use polars_core::prelude::*;
 use std::sync::Arc;
 
+/// Map every row of a frame with `height` rows to the position of its group in `groups`.
+fn map_rows_to_groups(groups: &GroupTuples, height: usize) -> Vec<IdxSize> {
+    let mut group_idx = vec![0; height];
+    for (group, (_first, idx)) in groups.iter().enumerate() {
+        for i in idx {
+            group_idx[*i as usize] = group as IdxSize;
+        }
+    }
+    group_idx
+}
+
 pub struct WindowExpr {
     /// the root column that the Function will be applied on.
     /// This will be used to create a smaller DataFrame to prevent taking unneeded columns by index
@@ -15,18 +26,25 @@ pub struct WindowExpr {
     /// A function Expr. i.e. Mean, Median, Max, etc.
     pub(crate) function: Expr,
     pub(crate) phys_function: Arc<dyn PhysicalExpr>,
+    /// The window expression this was planned from.
+    pub(crate) expr: Expr,
 }
 
 impl PhysicalExpr for WindowExpr {
+    fn as_expression(&self) -> &Expr {
+        &self.expr
+    }
+
     // Note: this was first implemented with expression evaluation but this performed really bad.
-    // Therefore we choose the groupby -> apply -> self join approach
+    // Therefore we choose the groupby -> apply -> scatter approach
     fn evaluate(&self, df: &DataFrame, state: &ExecutionState) -> Result<Series> {
         // This method does the following:
         // 1. determine groupby tuples based on the group_column
         // 2. apply an aggregation function
-        // 3. join the results back to the original dataframe
-        //    this stores all group values on the original df size
-        // 4. select the final column and return
+        // 3. map every row to the position of its group in the aggregated output
+        // 4. scatter the aggregated values to the rows by this mapping. This doesn't depend on
+        //    the order of the rows or on the key values, so duplicate and null keys and frames
+        //    that are filtered or sorted before the window are supported.
 
         // We create a key to store in the state cache
         // assume 32 digits per ptr.
@@ -34,6 +52,8 @@ impl PhysicalExpr for WindowExpr {
         df.get_columns()
             .iter()
             .for_each(|s| key.push_str(&format!("{}", s.get_data_ptr())));
+        // the rows are mapped to their groups by position, so the height is part of the key
+        key.push_str(&format!("#{}", df.height()));
 
         let groupby_columns = self
             .group_by
@@ -70,7 +90,12 @@ impl PhysicalExpr for WindowExpr {
         let groups = match groups_lock.get_mut(&key) {
             Some(groups) => std::mem::take(groups),
             None => {
-                let mut gb = df.groupby_with_series(groupby_columns.clone(), true)?;
+                let mut gb = groupby_keys(
+                    df,
+                    groupby_columns.clone(),
+                    GroupbyOptions::default(),
+                    state,
+                )?;
                 std::mem::take(gb.get_groups_mut())
             }
         };
@@ -84,14 +109,13 @@ impl PhysicalExpr for WindowExpr {
         );
 
         let out = match &self.function {
-            Expr::Function { .. } => {
+            Expr::Function { .. } | Expr::Extension { .. } => {
                 let agg_expr = self.phys_function.as_agg_expr()?;
                 match agg_expr.aggregate(df, gb.get_groups(), state)? {
+                    // only the aggregated column is used, the keys could have the same name
                     Some(mut s) => {
                         s.rename(&self.apply_column);
-                        let mut cols = gb.keys();
-                        cols.push(s);
-                        Ok(DataFrame::new_no_checks(cols))
+                        Ok(DataFrame::new_no_checks(vec![s]))
                     }
                     None => Err(PolarsError::Other(
                         "aggregation did not return a column".into(),
@@ -107,9 +131,11 @@ impl PhysicalExpr for WindowExpr {
                 AggExpr::First(_) => gb.first(),
                 AggExpr::Last(_) => gb.last(),
                 AggExpr::Count(_) => gb.count(),
+                AggExpr::NullCount(_) => gb.null_count(),
                 AggExpr::NUnique(_) => gb.n_unique(),
                 AggExpr::Quantile { quantile, .. } => gb.quantile(*quantile),
                 AggExpr::List(_) => gb.agg_list(),
+                AggExpr::Flatten(_) => gb.flatten(),
                 AggExpr::AggGroups(_) => gb.groups(),
                 AggExpr::Std(_) => gb.std(),
                 AggExpr::Var(_) => gb.var(),
@@ -123,48 +149,48 @@ impl PhysicalExpr for WindowExpr {
                 .into(),
             )),
         }?;
+        // 3. map every row to the position of its group in the aggregated output
+        let n_groups = gb.get_groups().len();
+        let group_idx = {
+            // Same logic as above. We don't wait on the group tuples while holding this lock.
+            let mut group_idx_lock;
+            loop {
+                match state.window_group_idx.try_lock() {
+                    Ok(lock) => {
+                        group_idx_lock = lock;
+                        break;
+                    }
+                    Err(_) => {
+                        std::thread::sleep(std::time::Duration::from_millis(1));
+                    }
+                }
+            }
+            group_idx_lock
+                .entry(key.clone())
+                .or_insert_with(|| Arc::new(map_rows_to_groups(gb.get_groups(), df.height())))
+                .clone()
+        };
+
         // store the group tuples and drop the lock so other threads may use them
-        groups_lock.insert(key.clone(), std::mem::take(gb.get_groups_mut()));
+        groups_lock.insert(key, std::mem::take(gb.get_groups_mut()));
         drop(groups_lock);
 
-        // 3. get the join tuples and use them to take the new Series
+        // 4. scatter the aggregated values to the rows of their groups
         let out_column = out.select_at_idx(out.width() - 1).unwrap();
-
-        // Same logic as above. The join algorithm also spawns new threads.
-        let mut join_tuples_lock;
-        loop {
-            match state.join_tuples.try_lock() {
-                Ok(lock) => {
-                    join_tuples_lock = lock;
-                    break;
-                }
-                Err(_) => {
-                    std::thread::sleep(std::time::Duration::from_millis(1));
-                }
-            }
+        if out_column.len() != n_groups {
+            return Err(PolarsError::ShapeMisMatch(
+                format!(
+                    "the window function {:?} returned {} values for {} groups",
+                    self.function,
+                    out_column.len(),
+                    n_groups
+                )
+                .into(),
+            ));
         }
-
-        let opt_join_tuples = match join_tuples_lock.get_mut(&key) {
-            Some(t) => std::mem::take(t),
-            None => {
-                if groupby_columns.len() == 1 {
-                    // group key from right column
-                    let right = out.select_at_idx(0).unwrap();
-                    groupby_columns[0].hash_join_left(right)
-                } else {
-                    let df_right =
-                        DataFrame::new_no_checks(out.get_columns()[..out.width() - 1].to_vec());
-                    let df_left = DataFrame::new_no_checks(groupby_columns);
-                    private_left_join_multiple_keys(&df_left, &df_right)
-                }
-            }
-        };
-
-        let mut iter = opt_join_tuples
-            .iter()
-            .map(|(_left, right)| right.map(|i| i as usize));
-        let mut out = unsafe { out_column.take_opt_iter_unchecked(&mut iter) };
-        join_tuples_lock.insert(key, opt_join_tuples);
+        // Safety: the group positions are smaller than the number of groups
+        let mut out =
+            unsafe { out_column.take_iter_unchecked(&mut group_idx.iter().map(|i| *i as usize)) };
         if let Some(name) = &self.out_name {
             out.rename(name.as_str());
         }
@@ -3,7 +3,7 @@ pub mod expressions;
 pub mod planner;
 pub(crate) mod state;
 
-use crate::physical_plan::state::ExecutionState;
+pub use crate::physical_plan::state::ExecutionState;
 use crate::prelude::*;
 use polars_core::prelude::*;
 use polars_io::PhysicalIoExpr;
@@ -11,6 +11,26 @@ use polars_io::ScanAggregation;
 use std::collections::HashSet;
 use std::sync::Arc;
 
+/// A Null literal without a data type cannot be materialized as a column, as it would not
+/// combine with other DataFrames.
+fn check_typed_output(schema: &Schema) -> Result<()> {
+    match schema
+        .fields()
+        .iter()
+        .find(|fld| fld.data_type() == &DataType::Null)
+    {
+        Some(fld) => Err(PolarsError::InvalidOperation(
+            format!(
+                "column '{}' is a Null literal without a data type; \
+                use `lit_null(dtype)` or `lit(NULL).cast(dtype)` to create a null column",
+                fld.name()
+            )
+            .into(),
+        )),
+        None => Ok(()),
+    }
+}
+
 #[cfg(any(feature = "parquet", feature = "csv-file"))]
 fn aggregate_expr_to_scan_agg(
     aggregate: Vec<Node>,
@@ -85,6 +105,44 @@ impl DefaultPlanner {
             .map(|e| self.create_physical_expr(*e, context, expr_arena))
             .collect()
     }
+    #[cfg(feature = "parquet")]
+    fn create_parquet_exec(
+        &self,
+        lp: ALogicalPlan,
+        expr_arena: &mut Arena<AExpr>,
+    ) -> Result<ParquetExec> {
+        if let ALogicalPlan::ParquetScan {
+            path,
+            schema,
+            with_columns,
+            predicate,
+            aggregate,
+            stop_after_n_rows,
+            cache,
+        } = lp
+        {
+            let chunk_predicate =
+                predicate.and_then(|pred| ChunkPredicate::new(pred, expr_arena, &schema));
+            let predicate = predicate
+                .map(|pred| self.create_physical_expr(pred, Context::Default, expr_arena))
+                .map_or(Ok(None), |v| v.map(Some))?;
+
+            let aggregate = aggregate_expr_to_scan_agg(aggregate, expr_arena);
+            Ok(ParquetExec::new(
+                path,
+                schema,
+                with_columns,
+                predicate,
+                aggregate,
+                stop_after_n_rows,
+                cache,
+            )
+            .with_chunk_predicate(chunk_predicate))
+        } else {
+            unreachable!()
+        }
+    }
+
     pub fn create_initial_physical_plan(
         &self,
         root: Node,
@@ -111,11 +169,47 @@ impl DefaultPlanner {
                 let input = self.create_initial_physical_plan(input, lp_arena, expr_arena)?;
                 Ok(Box::new(SliceExec { input, offset, len }))
             }
+            RowCount {
+                input, row_count, ..
+            } => match lp_arena.get_mut(input) {
+                // the scans count the rows while reading
+                #[cfg(feature = "csv-file")]
+                CsvScan {
+                    options, aggregate, ..
+                } if aggregate.is_empty() => {
+                    options.row_count = Some(row_count);
+                    self.create_initial_physical_plan(input, lp_arena, expr_arena)
+                }
+                #[cfg(feature = "parquet")]
+                ParquetScan { aggregate, .. } if aggregate.is_empty() => {
+                    let lp = lp_arena.take(input);
+                    let exec = self.create_parquet_exec(lp, expr_arena)?;
+                    Ok(Box::new(exec.with_row_count(Some(row_count))))
+                }
+                _ => {
+                    let input = self.create_initial_physical_plan(input, lp_arena, expr_arena)?;
+                    Ok(Box::new(RowCountExec { input, row_count }))
+                }
+            },
+            Union { inputs, how, .. } => {
+                let inputs = inputs
+                    .into_iter()
+                    .map(|node| self.create_initial_physical_plan(node, lp_arena, expr_arena))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(Box::new(UnionExec { inputs, how }))
+            }
             Selection { input, predicate } => {
+                let chunk_predicate = ChunkPredicate::new(
+                    predicate,
+                    expr_arena,
+                    lp_arena.get(input).schema(lp_arena),
+                );
                 let input = self.create_initial_physical_plan(input, lp_arena, expr_arena)?;
                 let predicate =
                     self.create_physical_expr(predicate, Context::Default, expr_arena)?;
-                Ok(Box::new(FilterExec::new(predicate, input)))
+                Ok(Box::new(
+                    FilterExec::new(predicate, input).with_chunk_predicate(chunk_predicate),
+                ))
             }
             #[cfg(feature = "csv-file")]
             CsvScan {
@@ -138,37 +232,24 @@ impl DefaultPlanner {
                 }))
             }
             #[cfg(feature = "parquet")]
-            ParquetScan {
-                path,
+            lp @ ParquetScan { .. } => Ok(Box::new(self.create_parquet_exec(lp, expr_arena)?)),
+            Projection {
+                expr,
+                input,
                 schema,
-                with_columns,
-                predicate,
-                aggregate,
-                stop_after_n_rows,
-                cache,
             } => {
-                let predicate = predicate
-                    .map(|pred| self.create_physical_expr(pred, Context::Default, expr_arena))
-                    .map_or(Ok(None), |v| v.map(Some))?;
-
-                let aggregate = aggregate_expr_to_scan_agg(aggregate, expr_arena);
-                Ok(Box::new(ParquetExec::new(
-                    path,
-                    schema,
-                    with_columns,
-                    predicate,
-                    aggregate,
-                    stop_after_n_rows,
-                    cache,
-                )))
-            }
-            Projection { expr, input, .. } => {
+                check_typed_output(&schema)?;
                 let input = self.create_initial_physical_plan(input, lp_arena, expr_arena)?;
                 let phys_expr =
                     self.create_physical_expressions(&expr, Context::Default, expr_arena)?;
                 Ok(Box::new(StandardExec::new("projection", input, phys_expr)))
             }
-            LocalProjection { expr, input, .. } => {
+            LocalProjection {
+                expr,
+                input,
+                schema,
+            } => {
+                check_typed_output(&schema)?;
                 let input = self.create_initial_physical_plan(input, lp_arena, expr_arena)?;
                 let phys_expr =
                     self.create_physical_expressions(&expr, Context::Default, expr_arena)?;
@@ -176,10 +257,15 @@ impl DefaultPlanner {
             }
             DataFrameScan {
                 df,
+                schema,
                 projection,
                 selection,
                 ..
             } => {
+                // the dtype of the predicate column is checked again on execution, as the
+                // projection may change it
+                let chunk_predicate =
+                    selection.and_then(|pred| ChunkPredicate::new(pred, expr_arena, &schema));
                 let selection = selection
                     .map(|pred| self.create_physical_expr(pred, Context::Default, expr_arena))
                     .map_or(Ok(None), |v| v.map(Some))?;
@@ -188,7 +274,10 @@ impl DefaultPlanner {
                         self.create_physical_expressions(&proj, Context::Default, expr_arena)
                     })
                     .map_or(Ok(None), |v| v.map(Some))?;
-                Ok(Box::new(DataFrameExec::new(df, projection, selection)))
+                Ok(Box::new(
+                    DataFrameExec::new(df, projection, selection)
+                        .with_chunk_predicate(chunk_predicate),
+                ))
             }
             Sort {
                 input,
@@ -204,7 +293,7 @@ impl DefaultPlanner {
                     reverse,
                 }))
             }
-            Explode { input, columns } => {
+            Explode { input, columns, .. } => {
                 let input = self.create_initial_physical_plan(input, lp_arena, expr_arena)?;
                 Ok(Box::new(ExplodeExec { input, columns }))
             }
@@ -241,8 +330,11 @@ impl DefaultPlanner {
                 keys,
                 aggs,
                 apply,
+                schema,
+                drop_null_keys,
                 ..
             } => {
+                let input_schema = lp_arena.get(input).schema(lp_arena).clone();
                 let input = self.create_initial_physical_plan(input, lp_arena, expr_arena)?;
 
                 // We first check if we can partition the groupby on the latest moment.
@@ -252,8 +344,12 @@ impl DefaultPlanner {
                 if keys.len() == 1 {
                     for agg in &aggs {
                         // make sure that we don't have a binary expr in the expr tree
-                        let matches =
-                            |e: &AExpr| matches!(e, AExpr::SortBy { .. } | AExpr::Filter { .. });
+                        let matches = |e: &AExpr| {
+                            matches!(
+                                e,
+                                AExpr::SortBy { .. } | AExpr::Filter { .. } | AExpr::DropNulls(_)
+                            )
+                        };
                         if aexpr_to_root_nodes(*agg, expr_arena).len() != 1
                             || has_aexpr(*agg, expr_arena, matches)
                         {
@@ -261,10 +357,26 @@ impl DefaultPlanner {
                             break;
                         }
 
+                        // the partitioned list aggregation doesn't support List columns
+                        let nested_list = match expr_arena.get(*agg).to_field(
+                            &input_schema,
+                            Context::Aggregation,
+                            expr_arena,
+                        ) {
+                            Ok(field) => matches!(
+                                field.data_type(),
+                                DataType::List(ArrowDataType::LargeList(_))
+                            ),
+                            Err(_) => false,
+                        };
                         let agg = node_to_exp(*agg, expr_arena);
 
                         // check if the aggregation type is partitionable
                         match agg {
+                            Expr::Agg(AggExpr::List(_)) if nested_list => {
+                                partitionable = false;
+                                break
+                            }
                             Expr::Agg(AggExpr::Min(_))
                             | Expr::Agg(AggExpr::Max(_))
                             | Expr::Agg(AggExpr::Sum(_))
@@ -273,7 +385,14 @@ impl DefaultPlanner {
                             // | Expr::Agg(AggExpr::Count(_))
                             | Expr::Agg(AggExpr::Last(_))
                             | Expr::Agg(AggExpr::List(_))
+                            | Expr::Agg(AggExpr::NullCount(_))
                             | Expr::Agg(AggExpr::First(_)) => {}
+                            // the median or a quantile of the partitions cannot be combined
+                            Expr::Agg(AggExpr::Median(_))
+                            | Expr::Agg(AggExpr::Quantile { .. }) => {
+                                partitionable = false;
+                                break
+                            }
                             _ => {
                                 partitionable = false;
                                 break
@@ -300,26 +419,36 @@ impl DefaultPlanner {
                         aggs.into_iter()
                             .map(|n| node_to_exp(n, expr_arena))
                             .collect(),
+                        schema,
+                        drop_null_keys,
                     )))
                 } else {
                     Ok(Box::new(GroupByExec::new(
-                        input, phys_keys, phys_aggs, apply,
+                        input,
+                        phys_keys,
+                        phys_aggs,
+                        apply,
+                        schema,
+                        drop_null_keys,
                     )))
                 }
             }
             Join {
                 input_left,
                 input_right,
-                how,
                 left_on,
                 right_on,
-                allow_par,
-                force_par,
+                options,
                 ..
             } => {
-                let parallel = if force_par {
-                    force_par
-                } else if allow_par {
+                // running trivial in memory inputs in parallel costs more than it saves
+                let both_in_memory = matches!(
+                    (lp_arena.get(input_left), lp_arena.get(input_right)),
+                    (DataFrameScan { .. }, DataFrameScan { .. })
+                );
+                let parallel = if options.force_parallel {
+                    true
+                } else if options.allow_parallel && !both_in_memory {
                     // check if two DataFrames come from a separate source.
                     // If they don't we can parallelize,
                     // Otherwise it is in cache.
@@ -348,13 +477,19 @@ impl DefaultPlanner {
                 Ok(Box::new(JoinExec::new(
                     input_left,
                     input_right,
-                    how,
+                    options.how,
                     left_on,
                     right_on,
                     parallel,
+                    options.suffix,
                 )))
             }
-            HStack { input, exprs, .. } => {
+            HStack {
+                input,
+                exprs,
+                schema,
+            } => {
+                check_typed_output(&schema)?;
                 let input = self.create_initial_physical_plan(input, lp_arena, expr_arena)?;
                 let phys_expr =
                     self.create_physical_expressions(&exprs, Context::Default, expr_arena)?;
@@ -409,6 +544,7 @@ impl DefaultPlanner {
                     out_name,
                     function,
                     phys_function,
+                    expr: node_to_exp(expression, expr_arena),
                 }))
             }
             Literal(value) => Ok(Arc::new(LiteralExpr::new(
@@ -440,11 +576,25 @@ impl DefaultPlanner {
             Take { expr, idx } => {
                 let phys_expr = self.create_physical_expr(expr, ctxt, expr_arena)?;
                 let phys_idx = self.create_physical_expr(idx, ctxt, expr_arena)?;
-                Ok(Arc::new(TakeExpr::new(phys_expr, phys_idx)))
+                Ok(Arc::new(TakeExpr::new(
+                    phys_expr,
+                    phys_idx,
+                    node_to_exp(expression, expr_arena),
+                )))
             }
             SortBy { expr, by, reverse } => {
+                if by.is_empty() || by.len() != reverse.len() {
+                    return Err(PolarsError::ValueError(
+                        format!(
+                            "sort_by expects a reverse flag for every `by` expression, got {} expressions and {} flags",
+                            by.len(),
+                            reverse.len()
+                        )
+                        .into(),
+                    ));
+                }
                 let phys_expr = self.create_physical_expr(expr, ctxt, expr_arena)?;
-                let phys_by = self.create_physical_expr(by, ctxt, expr_arena)?;
+                let phys_by = self.create_physical_expressions(&by, ctxt, expr_arena)?;
                 Ok(Arc::new(SortByExpr::new(
                     phys_expr,
                     phys_by,
@@ -457,7 +607,15 @@ impl DefaultPlanner {
                 let phys_by = self.create_physical_expr(by, ctxt, expr_arena)?;
                 Ok(Arc::new(FilterExpr::new(
                     phys_input,
-                    phys_by,
+                    Some(phys_by),
+                    node_to_exp(expression, expr_arena),
+                )))
+            }
+            DropNulls(input) => {
+                let phys_input = self.create_physical_expr(input, ctxt, expr_arena)?;
+                Ok(Arc::new(FilterExpr::new(
+                    phys_input,
+                    None,
                     node_to_exp(expression, expr_arena),
                 )))
             }
@@ -496,9 +654,11 @@ impl DefaultPlanner {
                         // todo! Output type is dependent on schema.
                         let input = self.create_physical_expr(expr, ctxt, expr_arena)?;
                         match ctxt {
-                            Context::Aggregation => {
-                                Ok(Arc::new(AggregationExpr::new(input, GroupByMethod::Min)))
-                            }
+                            Context::Aggregation => Ok(Arc::new(AggregationExpr::new(
+                                input,
+                                GroupByMethod::Min,
+                                node_to_exp(expression, expr_arena),
+                            ))),
                             Context::Default => {
                                 let function = NoEq::new(Arc::new(move |s: &mut [Series]| {
                                     let s = std::mem::take(&mut s[0]);
@@ -508,7 +668,7 @@ impl DefaultPlanner {
                                 Ok(Arc::new(ApplyExpr {
                                     inputs: vec![input],
                                     function,
-                                    output_type: None,
+                                    output_type: GetOutput::SameType,
                                     expr: node_to_exp(expression, expr_arena),
                                     collect_groups: false,
                                 }))
@@ -518,9 +678,11 @@ impl DefaultPlanner {
                     AAggExpr::Max(expr) => {
                         let input = self.create_physical_expr(expr, ctxt, expr_arena)?;
                         match ctxt {
-                            Context::Aggregation => {
-                                Ok(Arc::new(AggregationExpr::new(input, GroupByMethod::Max)))
-                            }
+                            Context::Aggregation => Ok(Arc::new(AggregationExpr::new(
+                                input,
+                                GroupByMethod::Max,
+                                node_to_exp(expression, expr_arena),
+                            ))),
                             Context::Default => {
                                 let function = NoEq::new(Arc::new(move |s: &mut [Series]| {
                                     let s = std::mem::take(&mut s[0]);
@@ -530,7 +692,7 @@ impl DefaultPlanner {
                                 Ok(Arc::new(ApplyExpr {
                                     inputs: vec![input],
                                     function,
-                                    output_type: None,
+                                    output_type: GetOutput::SameType,
                                     expr: node_to_exp(expression, expr_arena),
                                     collect_groups: false,
                                 }))
@@ -540,9 +702,11 @@ impl DefaultPlanner {
                     AAggExpr::Sum(expr) => {
                         let input = self.create_physical_expr(expr, ctxt, expr_arena)?;
                         match ctxt {
-                            Context::Aggregation => {
-                                Ok(Arc::new(AggregationExpr::new(input, GroupByMethod::Sum)))
-                            }
+                            Context::Aggregation => Ok(Arc::new(AggregationExpr::new(
+                                input,
+                                GroupByMethod::Sum,
+                                node_to_exp(expression, expr_arena),
+                            ))),
                             Context::Default => {
                                 let function = NoEq::new(Arc::new(move |s: &mut [Series]| {
                                     let s = std::mem::take(&mut s[0]);
@@ -552,7 +716,7 @@ impl DefaultPlanner {
                                 Ok(Arc::new(ApplyExpr {
                                     inputs: vec![input],
                                     function,
-                                    output_type: None,
+                                    output_type: GetOutput::SameType,
                                     expr: node_to_exp(expression, expr_arena),
                                     collect_groups: false,
                                 }))
@@ -562,9 +726,11 @@ impl DefaultPlanner {
                     AAggExpr::Std(expr) => {
                         let input = self.create_physical_expr(expr, ctxt, expr_arena)?;
                         match ctxt {
-                            Context::Aggregation => {
-                                Ok(Arc::new(AggregationExpr::new(input, GroupByMethod::Std)))
-                            }
+                            Context::Aggregation => Ok(Arc::new(AggregationExpr::new(
+                                input,
+                                GroupByMethod::Std,
+                                node_to_exp(expression, expr_arena),
+                            ))),
                             Context::Default => {
                                 let function = NoEq::new(Arc::new(move |s: &mut [Series]| {
                                     let s = std::mem::take(&mut s[0]);
@@ -574,7 +740,7 @@ impl DefaultPlanner {
                                 Ok(Arc::new(ApplyExpr {
                                     inputs: vec![input],
                                     function,
-                                    output_type: None,
+                                    output_type: GetOutput::SameType,
                                     expr: node_to_exp(expression, expr_arena),
                                     collect_groups: false,
                                 }))
@@ -584,9 +750,11 @@ impl DefaultPlanner {
                     AAggExpr::Var(expr) => {
                         let input = self.create_physical_expr(expr, ctxt, expr_arena)?;
                         match ctxt {
-                            Context::Aggregation => {
-                                Ok(Arc::new(AggregationExpr::new(input, GroupByMethod::Var)))
-                            }
+                            Context::Aggregation => Ok(Arc::new(AggregationExpr::new(
+                                input,
+                                GroupByMethod::Var,
+                                node_to_exp(expression, expr_arena),
+                            ))),
                             Context::Default => {
                                 let function = NoEq::new(Arc::new(move |s: &mut [Series]| {
                                     let s = std::mem::take(&mut s[0]);
@@ -596,7 +764,7 @@ impl DefaultPlanner {
                                 Ok(Arc::new(ApplyExpr {
                                     inputs: vec![input],
                                     function,
-                                    output_type: None,
+                                    output_type: GetOutput::SameType,
                                     expr: node_to_exp(expression, expr_arena),
                                     collect_groups: false,
                                 }))
@@ -606,12 +774,17 @@ impl DefaultPlanner {
                     AAggExpr::Mean(expr) => {
                         let input = self.create_physical_expr(expr, ctxt, expr_arena)?;
                         match ctxt {
-                            Context::Aggregation => {
-                                Ok(Arc::new(AggregationExpr::new(input, GroupByMethod::Mean)))
-                            }
+                            Context::Aggregation => Ok(Arc::new(AggregationExpr::new(
+                                input,
+                                GroupByMethod::Mean,
+                                node_to_exp(expression, expr_arena),
+                            ))),
                             Context::Default => {
                                 let function = NoEq::new(Arc::new(move |s: &mut [Series]| {
                                     let s = std::mem::take(&mut s[0]);
+                                    if let DataType::Duration(_) = s.dtype() {
+                                        return Ok(s.mean_as_series());
+                                    }
                                     let len = s.len() as f64;
                                     parallel_op_series(|s| Ok(s.sum_as_series()), s, None)
                                         .map(|s| s.cast::<Float64Type>().unwrap() / len)
@@ -620,7 +793,7 @@ impl DefaultPlanner {
                                 Ok(Arc::new(ApplyExpr {
                                     inputs: vec![input],
                                     function,
-                                    output_type: None,
+                                    output_type: GetOutput::SameType,
                                     expr: node_to_exp(expression, expr_arena),
                                     collect_groups: false,
                                 }))
@@ -630,9 +803,11 @@ impl DefaultPlanner {
                     AAggExpr::Median(expr) => {
                         let input = self.create_physical_expr(expr, ctxt, expr_arena)?;
                         match ctxt {
-                            Context::Aggregation => {
-                                Ok(Arc::new(AggregationExpr::new(input, GroupByMethod::Median)))
-                            }
+                            Context::Aggregation => Ok(Arc::new(AggregationExpr::new(
+                                input,
+                                GroupByMethod::Median,
+                                node_to_exp(expression, expr_arena),
+                            ))),
                             Context::Default => {
                                 let function = NoEq::new(Arc::new(move |s: &mut [Series]| {
                                     let s = std::mem::take(&mut s[0]);
@@ -642,7 +817,7 @@ impl DefaultPlanner {
                                 Ok(Arc::new(ApplyExpr {
                                     inputs: vec![input],
                                     function,
-                                    output_type: None,
+                                    output_type: GetOutput::SameType,
                                     expr: node_to_exp(expression, expr_arena),
                                     collect_groups: false,
                                 }))
@@ -652,9 +827,11 @@ impl DefaultPlanner {
                     AAggExpr::First(expr) => {
                         let input = self.create_physical_expr(expr, ctxt, expr_arena)?;
                         match ctxt {
-                            Context::Aggregation => {
-                                Ok(Arc::new(AggregationExpr::new(input, GroupByMethod::First)))
-                            }
+                            Context::Aggregation => Ok(Arc::new(AggregationExpr::new(
+                                input,
+                                GroupByMethod::First,
+                                node_to_exp(expression, expr_arena),
+                            ))),
                             Context::Default => {
                                 let function = NoEq::new(Arc::new(move |s: &mut [Series]| {
                                     let s = std::mem::take(&mut s[0]);
@@ -664,7 +841,7 @@ impl DefaultPlanner {
                                 Ok(Arc::new(ApplyExpr {
                                     inputs: vec![input],
                                     function,
-                                    output_type: None,
+                                    output_type: GetOutput::SameType,
                                     expr: node_to_exp(expression, expr_arena),
                                     collect_groups: false,
                                 }))
@@ -674,9 +851,11 @@ impl DefaultPlanner {
                     AAggExpr::Last(expr) => {
                         let input = self.create_physical_expr(expr, ctxt, expr_arena)?;
                         match ctxt {
-                            Context::Aggregation => {
-                                Ok(Arc::new(AggregationExpr::new(input, GroupByMethod::Last)))
-                            }
+                            Context::Aggregation => Ok(Arc::new(AggregationExpr::new(
+                                input,
+                                GroupByMethod::Last,
+                                node_to_exp(expression, expr_arena),
+                            ))),
                             Context::Default => {
                                 let function = NoEq::new(Arc::new(move |s: &mut [Series]| {
                                     let s = std::mem::take(&mut s[0]);
@@ -686,7 +865,7 @@ impl DefaultPlanner {
                                 Ok(Arc::new(ApplyExpr {
                                     inputs: vec![input],
                                     function,
-                                    output_type: None,
+                                    output_type: GetOutput::SameType,
                                     expr: node_to_exp(expression, expr_arena),
                                     collect_groups: false,
                                 }))
@@ -696,9 +875,11 @@ impl DefaultPlanner {
                     AAggExpr::List(expr) => {
                         let input = self.create_physical_expr(expr, ctxt, expr_arena)?;
                         match ctxt {
-                            Context::Aggregation => {
-                                Ok(Arc::new(AggregationExpr::new(input, GroupByMethod::List)))
-                            }
+                            Context::Aggregation => Ok(Arc::new(AggregationExpr::new(
+                                input,
+                                GroupByMethod::List,
+                                node_to_exp(expression, expr_arena),
+                            ))),
                             Context::Default => {
                                 panic!(
                                     "list expression is only supported in the aggregation context"
@@ -706,12 +887,27 @@ impl DefaultPlanner {
                             }
                         }
                     }
+                    AAggExpr::Flatten(expr) => {
+                        let input = self.create_physical_expr(expr, ctxt, expr_arena)?;
+                        match ctxt {
+                            Context::Aggregation => Ok(Arc::new(AggregationExpr::new(
+                                input,
+                                GroupByMethod::Flatten,
+                                node_to_exp(expression, expr_arena),
+                            ))),
+                            Context::Default => Err(PolarsError::InvalidOperation(
+                                "flatten expression is only supported in the aggregation context"
+                                    .into(),
+                            )),
+                        }
+                    }
                     AAggExpr::NUnique(expr) => {
                         let input = self.create_physical_expr(expr, ctxt, expr_arena)?;
                         match ctxt {
                             Context::Aggregation => Ok(Arc::new(AggregationExpr::new(
                                 input,
                                 GroupByMethod::NUnique,
+                                node_to_exp(expression, expr_arena),
                             ))),
                             Context::Default => {
                                 let function = NoEq::new(Arc::new(move |s: &mut [Series]| {
@@ -725,7 +921,7 @@ impl DefaultPlanner {
                                 Ok(Arc::new(ApplyExpr {
                                     inputs: vec![input],
                                     function,
-                                    output_type: Some(DataType::UInt32),
+                                    output_type: GetOutput::ToType(DataType::UInt32),
                                     expr: node_to_exp(expression, expr_arena),
                                     collect_groups: false,
                                 }))
@@ -736,9 +932,11 @@ impl DefaultPlanner {
                         // todo! add schema to get correct output type
                         let input = self.create_physical_expr(expr, ctxt, expr_arena)?;
                         match ctxt {
-                            Context::Aggregation => {
-                                Ok(Arc::new(AggQuantileExpr::new(input, quantile)))
-                            }
+                            Context::Aggregation => Ok(Arc::new(AggQuantileExpr::new(
+                                input,
+                                quantile,
+                                node_to_exp(expression, expr_arena),
+                            ))),
                             Context::Default => {
                                 let function = NoEq::new(Arc::new(move |s: &mut [Series]| {
                                     let s = std::mem::take(&mut s[0]);
@@ -748,7 +946,7 @@ impl DefaultPlanner {
                                 Ok(Arc::new(ApplyExpr {
                                     inputs: vec![input],
                                     function,
-                                    output_type: None,
+                                    output_type: GetOutput::SameType,
                                     expr: node_to_exp(expression, expr_arena),
                                     collect_groups: false,
                                 }))
@@ -763,14 +961,17 @@ impl DefaultPlanner {
                         Ok(Arc::new(AggregationExpr::new(
                             phys_expr,
                             GroupByMethod::Groups,
+                            node_to_exp(expression, expr_arena),
                         )))
                     }
                     AAggExpr::Count(expr) => {
                         let input = self.create_physical_expr(expr, ctxt, expr_arena)?;
                         match ctxt {
-                            Context::Aggregation => {
-                                Ok(Arc::new(AggregationExpr::new(input, GroupByMethod::Count)))
-                            }
+                            Context::Aggregation => Ok(Arc::new(AggregationExpr::new(
+                                input,
+                                GroupByMethod::Count,
+                                node_to_exp(expression, expr_arena),
+                            ))),
                             Context::Default => {
                                 let function = NoEq::new(Arc::new(move |s: &mut [Series]| {
                                     let s = std::mem::take(&mut s[0]);
@@ -782,7 +983,34 @@ impl DefaultPlanner {
                                 Ok(Arc::new(ApplyExpr {
                                     inputs: vec![input],
                                     function,
-                                    output_type: Some(DataType::UInt32),
+                                    output_type: GetOutput::ToType(DataType::UInt32),
+                                    expr: node_to_exp(expression, expr_arena),
+                                    collect_groups: false,
+                                }))
+                            }
+                        }
+                    }
+                    AAggExpr::NullCount(expr) => {
+                        let input = self.create_physical_expr(expr, ctxt, expr_arena)?;
+                        match ctxt {
+                            Context::Aggregation => Ok(Arc::new(AggregationExpr::new(
+                                input,
+                                GroupByMethod::NullCount,
+                                node_to_exp(expression, expr_arena),
+                            ))),
+                            Context::Default => {
+                                let function = NoEq::new(Arc::new(move |s: &mut [Series]| {
+                                    let s = std::mem::take(&mut s[0]);
+                                    // the null count is stored in the arrow arrays
+                                    let count = s.null_count();
+                                    Ok(UInt32Chunked::new_from_slice(s.name(), &[count as u32])
+                                        .into_series())
+                                })
+                                    as Arc<dyn SeriesUdf>);
+                                Ok(Arc::new(ApplyExpr {
+                                    inputs: vec![input],
+                                    function,
+                                    output_type: GetOutput::ToType(DataType::UInt32),
                                     expr: node_to_exp(expression, expr_arena),
                                     collect_groups: false,
                                 }))
@@ -792,8 +1020,20 @@ impl DefaultPlanner {
                 }
             }
             Cast { expr, data_type } => {
+                // a typed null literal
+                if let AExpr::Literal(LiteralValue::Null) = expr_arena.get(expr) {
+                    let s = Series::full_null("literal", 1, &data_type);
+                    return Ok(Arc::new(LiteralExpr::new(
+                        LiteralValue::Series(NoEq::new(s)),
+                        node_to_exp(expression, expr_arena),
+                    )));
+                }
                 let phys_expr = self.create_physical_expr(expr, ctxt, expr_arena)?;
-                Ok(Arc::new(CastExpr::new(phys_expr, data_type)))
+                Ok(Arc::new(CastExpr::new(
+                    phys_expr,
+                    data_type,
+                    node_to_exp(expression, expr_arena),
+                )))
             }
             Ternary {
                 predicate,
@@ -825,6 +1065,14 @@ impl DefaultPlanner {
                     collect_groups,
                 }))
             }
+            Extension { input, plugin } => {
+                let inputs = self.create_physical_expressions(&input, ctxt, expr_arena)?;
+                Ok(Arc::new(ExtensionExpr {
+                    inputs,
+                    plugin,
+                    expr: node_to_exp(expression, expr_arena),
+                }))
+            }
             BinaryFunction {
                 input_a,
                 input_b,
@@ -838,6 +1086,7 @@ impl DefaultPlanner {
                     input_b,
                     function,
                     output_field,
+                    expr: node_to_exp(expression, expr_arena),
                 }))
             }
             Shift { input, periods } => {
@@ -849,7 +1098,7 @@ impl DefaultPlanner {
                 Ok(Arc::new(ApplyExpr {
                     inputs: vec![input],
                     function,
-                    output_type: None,
+                    output_type: GetOutput::SameType,
                     expr: node_to_exp(expression, expr_arena),
                     collect_groups: false,
                 }))
@@ -864,6 +1113,7 @@ impl DefaultPlanner {
                     input,
                     offset,
                     len: length,
+                    expr: node_to_exp(expression, expr_arena),
                 }))
             }
             Reverse(expr) => {
@@ -875,7 +1125,7 @@ impl DefaultPlanner {
                 Ok(Arc::new(ApplyExpr {
                     inputs: vec![input],
                     function,
-                    output_type: None,
+                    output_type: GetOutput::SameType,
                     expr: node_to_exp(expression, expr_arena),
                     collect_groups: false,
                 }))
@@ -889,7 +1139,7 @@ impl DefaultPlanner {
                 Ok(Arc::new(ApplyExpr {
                     inputs: vec![input],
                     function,
-                    output_type: None,
+                    output_type: GetOutput::SameType,
                     expr: node_to_exp(expression, expr_arena),
                     collect_groups: false,
                 }))
@@ -903,7 +1153,7 @@ impl DefaultPlanner {
                 Ok(Arc::new(ApplyExpr {
                     inputs: vec![input],
                     function,
-                    output_type: None,
+                    output_type: GetOutput::SameType,
                     expr: node_to_exp(expression, expr_arena),
                     collect_groups: false,
                 }))
@@ -917,7 +1167,7 @@ impl DefaultPlanner {
                 Ok(Arc::new(ApplyExpr {
                     inputs: vec![input],
                     function,
-                    output_type: None,
+                    output_type: GetOutput::SameType,
                     expr: node_to_exp(expression, expr_arena),
                     collect_groups: false,
                 }))
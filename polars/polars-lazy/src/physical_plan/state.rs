@@ -1,33 +1,120 @@
 use ahash::RandomState;
 use polars_core::frame::groupby::GroupTuples;
 use polars_core::prelude::*;
+use polars_core::verbose::verbose;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
-pub type JoinTuplesCache = Arc<Mutex<HashMap<String, Vec<(u32, Option<u32>)>, RandomState>>>;
+pub type GroupIdxCache = Arc<Mutex<HashMap<String, Arc<Vec<IdxSize>>, RandomState>>>;
+/// Keyed by the address of the scanned `Arc<DataFrame>` and the fingerprint of the predicate.
+pub type ScanMaskCache = Arc<Mutex<HashMap<(usize, u64), BooleanChunked, RandomState>>>;
 pub type GroupTuplesCache = Arc<Mutex<HashMap<String, GroupTuples, RandomState>>>;
+/// Keyed by the addresses of the chunks of a Utf8 key. The key is stored next to its codes so
+/// that the chunks are not freed and their addresses not reused during the query. `None` if the
+/// key has too many distinct values to be worth interning.
+pub type InternedKeyCache = Arc<Mutex<HashMap<Vec<usize>, Option<(Series, Series)>, RandomState>>>;
 
 /// State/ cache that is maintained during the Execution of the physical plan.
 #[derive(Clone)]
 pub struct ExecutionState {
+    /// Used by the `cache` nodes and the file scans of a single query
     df_cache: Arc<Mutex<HashMap<String, DataFrame, RandomState>>>,
+    /// Used by the file scans, shared between the queries that are collected with the same state
+    scan_cache: Arc<Mutex<HashMap<String, DataFrame, RandomState>>>,
     /// Used by Window Expression to prevent redundant grouping
     pub(crate) group_tuples: GroupTuplesCache,
-    /// Used by Window Expression to prevent redundant joins
-    pub(crate) join_tuples: JoinTuplesCache,
+    /// Used by Window Expression to map the rows of a frame to their group only once
+    pub(crate) window_group_idx: GroupIdxCache,
+    /// Used by the in memory DataFrame scans to evaluate a predicate only once if the same
+    /// frame is scanned with the same predicate in multiple branches of the plan
+    pub(crate) scan_masks: ScanMaskCache,
+    /// Used by the groupbys and Window Expressions to hash a Utf8 key only once per query
+    pub(crate) interned_keys: InternedKeyCache,
     pub(crate) verbose: bool,
+    /// Overwrites the number of rows read by the scans. Set by `LazyFrame::fetch`.
+    pub(crate) fetch_rows: Option<usize>,
+    /// Skip the chunks of which the statistics show that they don't match a filter predicate.
+    /// Set by `LazyFrame::with_chunk_pruning`.
+    pub(crate) chunk_pruning: bool,
+    /// Group a single Utf8 key by its interned codes, see `interned_keys`.
+    /// Set by `LazyFrame::with_interned_groupby_keys`.
+    pub(crate) intern_keys: bool,
+    /// Sorts and groupbys with an input larger than this number of bytes are spilled to disk.
+    /// Set by `POLARS_MEMORY_BUDGET` or `LazyFrame::with_memory_budget`.
+    #[cfg(feature = "spill")]
+    pub(crate) memory_budget: Option<usize>,
+    /// Abort the query if a node outputs more rows than this.
+    /// Set by `LazyFrame::with_max_output_rows`.
+    pub(crate) max_output_rows: Option<usize>,
+    /// Abort the query if the estimated size of the output of a node exceeds this number of
+    /// bytes. Set by `LazyFrame::with_memory_limit_bytes`.
+    pub(crate) memory_limit: Option<usize>,
 }
 
 impl ExecutionState {
     pub fn new() -> Self {
         Self {
             df_cache: Arc::new(Mutex::new(HashMap::with_hasher(RandomState::default()))),
+            scan_cache: Arc::new(Mutex::new(HashMap::with_hasher(RandomState::default()))),
             group_tuples: Arc::new(Mutex::new(HashMap::with_hasher(RandomState::default()))),
-            join_tuples: Arc::new(Mutex::new(HashMap::with_hasher(RandomState::default()))),
-            verbose: std::env::var("POLARS_VERBOSE").is_ok(),
+            window_group_idx: Arc::new(Mutex::new(HashMap::with_hasher(RandomState::default()))),
+            scan_masks: Arc::new(Mutex::new(HashMap::with_hasher(RandomState::default()))),
+            interned_keys: Arc::new(Mutex::new(HashMap::with_hasher(RandomState::default()))),
+            verbose: verbose(),
+            fetch_rows: None,
+            chunk_pruning: true,
+            intern_keys: false,
+            #[cfg(feature = "spill")]
+            memory_budget: std::env::var("POLARS_MEMORY_BUDGET")
+                .ok()
+                .and_then(|s| s.parse().ok()),
+            max_output_rows: None,
+            memory_limit: None,
         }
     }
 
+    /// Check if the output of the nodes is limited by a maximum number of rows or bytes.
+    pub(crate) fn has_output_limits(&self) -> bool {
+        self.max_output_rows.is_some() || self.memory_limit.is_some()
+    }
+
+    /// Abort if `node` outputs more rows than allowed by `max_output_rows`.
+    pub(crate) fn check_output_rows(&self, node: &str, rows: usize) -> Result<()> {
+        match self.max_output_rows {
+            Some(max_rows) if rows > max_rows => Err(PolarsError::ResourceExhausted(
+                format!(
+                    "{} outputs {} rows, which exceeds the limit of {} rows set by `with_max_output_rows`",
+                    node, rows, max_rows
+                )
+                .into(),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Abort if the output of `node` is estimated to be larger than the `memory_limit`.
+    pub(crate) fn check_output_bytes(&self, node: &str, bytes: usize) -> Result<()> {
+        match self.memory_limit {
+            Some(limit) if bytes > limit => Err(PolarsError::ResourceExhausted(
+                format!(
+                    "{} outputs an estimated {} bytes, which exceeds the limit of {} bytes set by `with_memory_limit_bytes`",
+                    node, bytes, limit
+                )
+                .into(),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Check the output `df` of `node` against both limits.
+    pub(crate) fn check_output(&self, node: &str, df: &DataFrame) -> Result<()> {
+        self.check_output_rows(node, df.height())?;
+        if self.memory_limit.is_some() {
+            self.check_output_bytes(node, df.estimated_size())?;
+        }
+        Ok(())
+    }
+
     /// Check if we have DataFrame in cache
     pub fn cache_hit(&self, key: &str) -> Option<DataFrame> {
         let guard = self.df_cache.lock().unwrap();
@@ -40,13 +127,49 @@ impl ExecutionState {
         guard.insert(key, df);
     }
 
+    /// Check if a file scan is cached by this query, or by a previous query if `shared`.
+    pub(crate) fn scan_cache_hit(&self, key: &str, shared: bool) -> Option<DataFrame> {
+        self.cache_hit(key).or_else(|| {
+            if shared {
+                self.scan_cache.lock().unwrap().get(key).cloned()
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Store the DataFrame of a file scan for this query, and for the next queries if `shared`.
+    pub(crate) fn store_scan_cache(&self, key: String, df: DataFrame, shared: bool) {
+        if shared {
+            self.scan_cache
+                .lock()
+                .unwrap()
+                .insert(key.clone(), df.clone());
+        }
+        self.store_cache(key, df);
+    }
+
+    /// Clear the DataFrames cached by the file scans, such that the next execution with this
+    /// state reads the files again.
+    pub fn clear_scan_cache(&self) {
+        self.scan_cache.lock().unwrap().clear();
+    }
+
+    /// Share the cached file scans of `other`, such that a state that is kept between queries
+    /// doesn't read unchanged files again. The `cache` nodes are not shared, they are only
+    /// valid within a single query.
+    pub(crate) fn with_scan_cache_of(mut self, other: &ExecutionState) -> Self {
+        self.scan_cache = other.scan_cache.clone();
+        self
+    }
+
     /// Clear the cache used by the Window expressions
     pub fn clear_expr_cache(&self) {
         {
             let mut lock = self.group_tuples.lock().unwrap();
             lock.clear();
         }
-        let mut lock = self.join_tuples.lock().unwrap();
+        let mut lock = self.window_group_idx.lock().unwrap();
         lock.clear();
     }
 }
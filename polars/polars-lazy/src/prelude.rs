@@ -1,13 +1,16 @@
 pub use polars_core::utils::{Arena, Node};
+pub use polars_io::csv_core::parse::ParseOptions;
 
 pub use crate::{
     dsl::*,
     frame::*,
     logical_plan::{
         optimizer::{type_coercion::TypeCoercionRule, Optimize, *},
-        DataFrameUdf, LiteralValue, LogicalPlan, LogicalPlanBuilder,
+        ConcatHow, Context, DataFrameUdf, LiteralValue, LogicalPlan, LogicalPlanBuilder,
+    },
+    physical_plan::{
+        expressions::*, planner::DefaultPlanner, ExecutionState, Executor, PhysicalPlanner,
     },
-    physical_plan::{expressions::*, planner::DefaultPlanner, Executor, PhysicalPlanner},
 };
 
 #[cfg(feature = "csv-file")]
@@ -22,15 +25,17 @@ pub(crate) use crate::{
             cache::CacheExec,
             drop_duplicates::DropDuplicatesExec,
             explode::ExplodeExec,
-            filter::FilterExec,
+            filter::{ChunkPredicate, FilterExec},
             groupby::{GroupByExec, PartitionGroupByExec},
             join::JoinExec,
             melt::MeltExec,
+            row_count::RowCountExec,
             scan::DataFrameExec,
             slice::SliceExec,
             sort::SortExec,
             stack::StackExec,
             udf::UdfExec,
+            union::UnionExec,
             various::StandardExec,
         },
         expressions::{
@@ -40,6 +45,7 @@ pub(crate) use crate::{
             binary_function::BinaryFunctionExpr,
             cast::CastExpr,
             column::ColumnExpr,
+            extension::ExtensionExpr,
             filter::FilterExpr,
             is_not_null::IsNotNullExpr,
             is_null::IsNullExpr,
@@ -267,12 +267,86 @@ pub(crate) fn agg_source_paths(
         Melt { input, .. } => {
             agg_source_paths(*input, paths, lp_arena);
         }
+        RowCount { input, .. } => {
+            agg_source_paths(*input, paths, lp_arena);
+        }
         Udf { input, .. } => {
             agg_source_paths(*input, paths, lp_arena);
         }
+        Union { inputs, .. } => {
+            for input in inputs {
+                agg_source_paths(*input, paths, lp_arena);
+            }
+        }
     }
 }
 
+/// Determine if the plan combines categoricals of different inputs, in a join on categorical
+/// keys or a concatenation of categorical columns. Such a plan is executed under the global string
+/// cache, so that the categoricals of all inputs share their categories. Returns the reason.
+pub(crate) fn string_cache_reason(
+    root: Node,
+    lp_arena: &Arena<ALogicalPlan>,
+    expr_arena: &Arena<AExpr>,
+) -> Option<String> {
+    use crate::logical_plan::iterator::ArenaLpIter;
+    let is_categorical = |field: &Field| field.data_type() == &DataType::Categorical;
+
+    lp_arena.iter(root).find_map(|(_, lp)| match lp {
+        ALogicalPlan::Join {
+            input_left,
+            input_right,
+            left_on,
+            right_on,
+            ..
+        } => {
+            let schema_left = lp_arena.get(*input_left).schema(lp_arena);
+            let schema_right = lp_arena.get(*input_right).schema(lp_arena);
+            let keys = left_on
+                .iter()
+                .zip(right_on)
+                .filter_map(|(l, r)| {
+                    let to_field = |node: Node, schema: &Schema| {
+                        expr_arena
+                            .get(node)
+                            .to_field(schema, Context::Default, expr_arena)
+                            .ok()
+                    };
+                    let l = to_field(*l, schema_left)?;
+                    let r = to_field(*r, schema_right)?;
+                    if is_categorical(&l) && is_categorical(&r) {
+                        Some(l.name().clone())
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+            if keys.is_empty() {
+                None
+            } else {
+                Some(format!("join on categorical keys {:?}", keys))
+            }
+        }
+        ALogicalPlan::Union { inputs, schema, .. } if inputs.len() > 1 => {
+            let columns = schema
+                .fields()
+                .iter()
+                .filter(|field| is_categorical(field))
+                .map(|field| field.name().clone())
+                .collect::<Vec<_>>();
+            if columns.is_empty() {
+                None
+            } else {
+                Some(format!(
+                    "concatenation of categorical columns {:?}",
+                    columns
+                ))
+            }
+        }
+        _ => None,
+    })
+}
+
 pub(crate) fn try_path_to_str(path: &Path) -> Result<&str> {
     path.to_str().ok_or_else(|| {
         PolarsError::Other(format!("Non-UTF8 file path: {}", path.to_string_lossy()).into())
@@ -351,3 +425,33 @@ where
     }
     single_pred.expect("an empty iterator was passed")
 }
+
+/// The output type of arithmetic with temporal operands, e.g. `Date64 - Date64 = Duration`. This
+/// mirrors the Series arithmetic, which doesn't cast temporal operands to a supertype. Returns
+/// `None` if the operation isn't temporal arithmetic.
+pub(crate) fn temporal_arithmetic_dtype(
+    op: Operator,
+    left: &DataType,
+    right: &DataType,
+) -> Option<DataType> {
+    use DataType::*;
+    match (op, left, right) {
+        (Operator::Minus, Date64, Date64) => Some(Duration(TimeUnit::Millisecond)),
+        (Operator::Plus, Date64, Duration(_))
+        | (Operator::Minus, Date64, Duration(_))
+        | (Operator::Plus, Duration(_), Date64) => Some(Date64),
+        (Operator::Plus, Duration(tu_l), Duration(tu_r))
+        | (Operator::Minus, Duration(tu_l), Duration(tu_r))
+            if tu_l == tu_r =>
+        {
+            Some(left.clone())
+        }
+        (Operator::Multiply, Duration(_), dt) | (Operator::Divide, Duration(_), dt)
+            if dt.is_integer() =>
+        {
+            Some(left.clone())
+        }
+        (Operator::Multiply, dt, Duration(_)) if dt.is_integer() => Some(right.clone()),
+        _ => None,
+    }
+}
@@ -107,6 +107,7 @@
 //!     - `json` - JSON serialization
 //!     - `ipc` - Arrow's IPC format serialization
 //!     - `simdutf8` - Improve performance of utf8 validation during csv parsing by using [simdutf8 crate](https://github.com/rusticstuff/simdutf8)
+//! * `tracing` - Emit the `POLARS_VERBOSE` logging as debug level [tracing](https://crates.io/crates/tracing) events.
 //! * `DataFrame` operations:
 //!     - `pivot` - [pivot operation](crate::frame::groupby::GroupBy::pivot) on `DataFrame`s
 //!     - `sort_multiple` - Allow sorting a `DataFrame` on multiple columns
@@ -114,6 +115,8 @@
 //!     - `downsample` - [downsample operation](crate::frame::DataFrame::downsample) on `DataFrame`s
 //!     - `asof_join` - Join as of, to join on nearest keys instead of exact equality match.
 //!     - `cross_join` - Create the cartesian product of two DataFrames.
+//!     - `merge_sorted` - Merge two sorted DataFrames into a single sorted DataFrame.
+//!     - `spill` - Spill lazy sorts and groupbys that exceed a memory budget to disk.
 //! * `Series` operations:
 //!     - `is_in` - [Check for membership in `Series`](crate::chunked_array::ops::IsIn)
 //!     - `zip_with` - [Zip two Series/ ChunkedArrays](crate::chunked_array::ops::ChunkZip)
@@ -125,6 +128,7 @@
 //!     - `dot_product` - Dot/inner product on Series and Expressions.
 //!     - `concat_str` - Concat string data in linear time.
 //!     - `reinterpret` - Utility to reinterpret bits to signed/unsigned
+//!     - `ewma` - Exponentially weighted mean, variance and standard deviation.
 //! * `DataFrame` pretty printing (Choose one or none, but not both):
 //!     - `plain_fmt` - no overflowing (less compilation times)
 //!     - `pretty_fmt` - cell overflow (increased compilation times)
@@ -153,11 +157,12 @@
 //! | UInt8Type               | dtype-u8          |
 //! | UInt16Type              | dtype-u16         |
 //! | UInt64Type              | dtype-u64         |
+//! | StructChunked           | dtype-struct      |
 //!
 //!
 //! Or you can choose on of the preconfigured pre-sets.
 //!
-//! * `dtype-full` - all opt-in dtypes.
+//! * `dtype-full` - all opt-in dtypes, except for the nested `dtype-struct`.
 //! * `dtype-slim` - slim preset of opt-in dtypes.
 //!
 //! ## Performance and string data
@@ -187,10 +192,13 @@
 //! * `POLARS_PAR_SORT_BOUND` -> sets the lower bound of rows at which Polars will use a parallel sorting algorithm.
 //!                              Default is 1M rows.
 //! * `POLARS_FMT_MAX_COLS` -> maximum number of columns shown when formatting DataFrames.
-//! * `POLARS_FMT_MAX_ROWS` -> maximum number of rows shown when formatting DataFrames.
+//! * `POLARS_FMT_MAX_ROWS` -> maximum number of rows shown when formatting DataFrames and Series.
+//! * `POLARS_FMT_STR_LEN` -> maximum display width of string and list values when formatting.
+//! * `POLARS_FMT_HIDE_DTYPES` -> don't show the data types below the column names of DataFrames.
+//!   The formatting can also be configured per thread with [FmtConfig](crate::fmt::FmtConfig).
 //! * `POLARS_TABLE_WIDTH` -> width of the tables used during DataFrame formatting.
 //! * `POLARS_MAX_THREADS` -> maximum number of threads used to initialize thread pool (on startup).
-//! * `POLARS_VERBOSE` -> print logging info to stderr
+//! * `POLARS_VERBOSE` -> print logging info to stderr, see [verbose](crate::verbose).
 //!
 //! ## Compile for WASM
 //! To be able to pretty print a `DataFrame` in `wasm32-wasi` you need to patch the `prettytable-rs`
@@ -208,7 +216,9 @@ pub mod docs;
 pub mod prelude;
 
 pub use polars_core::{
-    chunked_array, datatypes, doc, error, frame, functions, series, testing, toggle_string_cache,
+    chunked_array, datatypes, doc, error, fmt, frame, functions, prune_string_cache, series,
+    string_cache_len, string_cache_memory_usage, testing, toggle_string_cache, verbose,
+    StringCacheHolder,
 };
 
 pub use polars_core::apply_method_all_arrow_series;
@@ -1863,11 +1863,19 @@ class DataFrame:
         """
         return wrap_df(self._df.quantile(quantile))
 
-    def to_dummies(self) -> "DataFrame":
+    def to_dummies(self, columns: Optional[tp.List[str]] = None) -> "DataFrame":
         """
         Get one hot encoded dummy variables.
+
+        Parameters
+        ----------
+        columns
+            The columns to encode. By default all Utf8 and Categorical columns are encoded.
+            The other columns are passed through.
         """
-        return wrap_df(self._df.to_dummies())
+        if columns is not None and isinstance(columns, str):
+            columns = [columns]
+        return wrap_df(self._df.to_dummies(columns))
 
     def drop_duplicates(
         self,
@@ -2035,13 +2043,21 @@ class DataFrame:
             df._df.shrink_to_fit()
             return df
 
-    def hash_rows(self) -> "pl.Series":
+    def hash_rows(self, seed: int = 0) -> "pl.Series":
         """
         Hash and combine the rows in this DataFrame.
 
+        The hashes are stable; the same data and seed result in the same hashes
+        on every platform and polars version.
+
         Hash value is UInt64
+
+        Parameters
+        ----------
+        seed
+            Seed of the hash function.
         """
-        return pl.wrap_s(self._df.hash_rows())
+        return pl.wrap_s(self._df.hash_rows(seed))
 
 
 class GroupBy:
@@ -135,7 +135,9 @@ def _prepare_file_arg(
     return managed_file(file)
 
 
-def get_dummies(df: "pl.DataFrame") -> "pl.DataFrame":
+def get_dummies(
+    df: "pl.DataFrame", columns: Optional[List[str]] = None
+) -> "pl.DataFrame":
     """
     Convert categorical variables into dummy/indicator variables.
 
@@ -143,8 +145,10 @@ def get_dummies(df: "pl.DataFrame") -> "pl.DataFrame":
     ----------
     df
         DataFrame to convert.
+    columns
+        The columns to convert. By default all Utf8 and Categorical columns are converted.
     """
-    return df.to_dummies()
+    return df.to_dummies(columns)
 
 
 def read_csv(
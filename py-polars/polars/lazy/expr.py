@@ -143,6 +143,19 @@ class Expr:
         """
         return wrap_expr(self._pyexpr.alias(name))
 
+    def exclude(self, columns: Union[str, tp.List[str]]) -> "Expr":
+        """
+        Exclude certain columns from a wildcard expression.
+
+        Parameters
+        ----------
+        columns
+            Column(s) to exclude from selection.
+        """
+        if isinstance(columns, str):
+            columns = [columns]
+        return wrap_expr(self._pyexpr.exclude(columns))
+
     def is_not(self) -> "Expr":
         """
         Negate a boolean expression.
@@ -314,24 +327,32 @@ class Expr:
         """
         return wrap_expr(self._pyexpr.arg_sort(reverse))
 
-    def sort_by(self, by: Union["Expr", str], reverse: bool = False) -> "Expr":
+    def sort_by(
+        self,
+        by: Union["Expr", str, tp.List[Union["Expr", str]]],
+        reverse: Union[bool, tp.List[bool]] = False,
+    ) -> "Expr":
         """
-        Sort this column by the ordering of another column.
+        Sort this column by the ordering of other columns.
         In projection/ selection context the whole column is sorted.
         If used in a groupby context, the groups are sorted.
 
         Parameters
         ----------
         by
-            The column used for sorting.
+            The column(s) used for sorting.
         reverse
             False -> order from small to large.
             True -> order from large to small.
+            Can be given per column in `by`.
         """
-        if isinstance(by, str):
-            by = col(by)
+        if not isinstance(by, list):
+            by = [by]
+        if not isinstance(reverse, list):
+            reverse = [reverse] * len(by)
+        by = _selection_to_pyexpr_list(by)
 
-        return wrap_expr(self._pyexpr.sort_by(by._pyexpr, reverse))
+        return wrap_expr(self._pyexpr.sort_by(by, reverse))
 
     def take(self, index: "Expr") -> "Expr":
         """
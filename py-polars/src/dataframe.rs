@@ -814,8 +814,11 @@ impl PyDataFrame {
         Ok(df.into())
     }
 
-    pub fn to_dummies(&self) -> PyResult<Self> {
-        let df = self.df.to_dummies().map_err(PyPolarsEr::from)?;
+    pub fn to_dummies(&self, columns: Option<Vec<&str>>) -> PyResult<Self> {
+        let df = self
+            .df
+            .to_dummies(columns.as_deref())
+            .map_err(PyPolarsEr::from)?;
         Ok(df.into())
     }
 
@@ -886,8 +889,8 @@ impl PyDataFrame {
         self.df.shrink_to_fit();
     }
 
-    pub fn hash_rows(&self) -> PyResult<PySeries> {
-        let hash = self.df.hash_rows().map_err(PyPolarsEr::from)?;
+    pub fn hash_rows(&self, seed: u64) -> PyResult<PySeries> {
+        let hash = self.df.hash_rows(seed).map_err(PyPolarsEr::from)?;
         Ok(hash.into_series().into())
     }
 }
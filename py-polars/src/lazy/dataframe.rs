@@ -357,7 +357,6 @@ impl PyLazyFrame {
         let opt = AllowedOptimizations {
             predicate_pushdown: predicate_pd,
             projection_pushdown: projection_pd,
-            ..Default::default()
         };
 
         let function = move |s: DataFrame| {
@@ -396,7 +395,7 @@ impl PyLazyFrame {
             }
             Ok(df)
         };
-        ldf.map(f, None, None).into()
+        ldf.map(f, AllowedOptimizations::all(), None).into()
     }
 
     pub fn clone(&self) -> PyLazyFrame {
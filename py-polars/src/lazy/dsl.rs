@@ -67,6 +67,10 @@ impl PyExpr {
     pub fn alias(&self, name: &str) -> PyExpr {
         self.clone().inner.alias(name).into()
     }
+    pub fn exclude(&self, columns: Vec<String>) -> PyExpr {
+        let columns = columns.iter().map(|s| s.as_ref()).collect::<Vec<_>>();
+        self.clone().inner.exclude(&columns).into()
+    }
     pub fn is_not(&self) -> PyExpr {
         self.clone().inner.not().into()
     }
@@ -153,8 +157,9 @@ impl PyExpr {
         self.clone().inner.take(idx.inner).into()
     }
 
-    pub fn sort_by(&self, by: PyExpr, reverse: bool) -> PyExpr {
-        self.clone().inner.sort_by(by.inner, reverse).into()
+    pub fn sort_by(&self, by: Vec<PyExpr>, reverse: Vec<bool>) -> PyExpr {
+        let by = by.into_iter().map(|e| e.inner).collect();
+        self.clone().inner.sort_by(by, reverse).into()
     }
 
     pub fn backward_fill(&self) -> PyExpr {
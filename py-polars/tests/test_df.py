@@ -386,6 +386,9 @@ def test_to_dummies():
     assert dummies["A_a"].to_list() == [1, 0, 0]
     assert dummies["A_b"].to_list() == [0, 1, 0]
     assert dummies["A_c"].to_list() == [0, 0, 1]
+    assert dummies["B"].to_list() == [1, 3, 5]
+    dummies = df.to_dummies(columns=["B"])
+    assert dummies.columns == ["A", "B_1", "B_3", "B_5"]
 
 
 def test_from_pandas():
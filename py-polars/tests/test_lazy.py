@@ -220,7 +220,7 @@ def test_concat_str():
     df = pl.DataFrame({"a": ["a", "b", "c"], "b": [1, 2, 3]})
 
     out = df[[pl.concat_str(["a", "b"], delimiter="-")]]
-    assert out["a"] == ["a-1", "a-2", "a-3"]
+    assert out["a"] == ["a-1", "b-2", "c-3"]
 
 
 def test_fold_filter():